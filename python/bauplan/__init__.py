@@ -5,8 +5,13 @@ from bauplan._internal import (
     Client,
     InfoState,
     OrganizationInfo,
+    QueryFileResult,
+    QueryResultsEndpoint,
     RunnerNodeInfo,
     UserInfo,
+    WriteTableResult,
+    enable_logging,
+    fetch_query_results,
 )
 
 # Submodules.
@@ -38,9 +43,14 @@ __all__ = [
     "JobKind",
     "JobState",
     "OrganizationInfo",
+    "QueryFileResult",
+    "QueryResultsEndpoint",
     "RefType",
     "RunnerNodeInfo",
     "UserInfo",
+    "WriteTableResult",
+    "enable_logging",
+    "fetch_query_results",
     # Decorators and model definitions.
     "Model",
     "ModelCacheStrategy",
@@ -7,17 +7,24 @@ from bauplan.schema import (
     Commit,
     Job,
     JobContext,
+    JobDAG,
     JobKind,
+    JobLifecycleEvent,
     JobLogEvent,
     JobState,
     Namespace,
     Ref,
+    RefSummary,
     RefType,
     Table,
+    TableDiff,
+    TableSnapshot,
+    TableSync,
     Tag,
 )
 from bauplan.state import (
     ExternalTableCreateState,
+    RunPlan,
     RunState,
     TableCreatePlanApplyState,
     TableCreatePlanState,
@@ -48,9 +55,14 @@ __all__ = [
     "JobKind",
     "JobState",
     "OrganizationInfo",
+    "QueryFileResult",
+    "QueryResultsEndpoint",
     "RefType",
     "RunnerNodeInfo",
     "UserInfo",
+    "WriteTableResult",
+    "enable_logging",
+    "fetch_query_results",
     # Decorators and model definitions.
     "Model",
     "ModelCacheStrategy",
@@ -109,6 +121,23 @@ class Client:
 
     # specify a profile from ~/.bauplan/config.yml - this supersedes BAUPLAN_PROFILE in the environment
     client = bauplan.Client(profile='default')
+
+    # with neither, the profile set by `bauplan config use-profile` is used, or else 'default'
+    ```
+
+    #### The active branch
+
+    Methods that take a ref or branch use, in order of precedence: the
+    argument, if given; then `client.active_branch`, which starts out as the
+    profile's `active_branch`; and finally `"main"`.
+
+    ```python
+    client = bauplan.Client()
+    client.checkout('my_branch_name')
+    # >> queries, runs and table operations now default to 'my_branch_name'
+    client.query('SELECT count(*) FROM titanic')
+    # an explicit ref always wins
+    client.query('SELECT count(*) FROM titanic', ref='main')
     ```
 
     #### Handling Exceptions
@@ -121,7 +150,8 @@ class Client:
         - 404: `bauplan.exceptions.ApiMethodError` e.g. the given API method doesn't exist
         - 405: `bauplan.exceptions.ApiRouteError` e.g. POST on a route with only GET defined
         - 409: `bauplan.exceptions.UpdateConflictError` e.g. creating a record with a name that already exists
-        - 429: `bauplan.exceptions.TooManyRequestsError`
+        - 429: `bauplan.exceptions.TooManyRequestsError`, once retries within `rate_limit_budget` are exhausted
+        - `bauplan.exceptions.RequestTimeoutError`, if a request takes longer than `request_timeout`
 
     Run/Query/Scan/Import operations raise a subclass of `bauplan.exceptions.BauplanError` that represents the error, and also return a `bauplan.state.RunState` object containing details and logs:
         - `bauplan.exceptions.BauplanJobError` e.g. something went wrong in a run/query/import/scan; includes error details
@@ -164,6 +194,41 @@ class Client:
     branches = list(client.get_branches())
     ```
 
+    #### Async code
+
+    Methods that wait for a job to finish (`query`, `run`, `scan`, `import_data`,
+    `create_table`, and friends) block the calling thread. Called from a coroutine,
+    they would freeze the event loop until the job is done, so they raise a
+    `RuntimeError` if an asyncio event loop is running in the current thread.
+    Run them in a worker thread instead:
+
+    ```python
+    import asyncio
+
+    async def main():
+        client = bauplan.Client()
+        table = await asyncio.to_thread(client.query, "SELECT 1", ref="main")
+    ```
+
+    In environments where blocking the loop is expected (like notebooks, which
+    run cells inside an event loop), pass `allow_blocking=True` to get a
+    warning instead.
+
+    #### Threads
+
+    A single client can be shared between threads, for example in a
+    `ThreadPoolExecutor`. Calls from different threads run concurrently,
+    and the GIL is released while they wait on the network. The active
+    branch is shared, so set it before handing the client to other threads.
+
+    #### Forking
+
+    A client can be created before forking, for example at import time in a
+    preforking server like gunicorn or uWSGI. In the child, its connections are
+    transparently rebuilt on the next call. Iterators and log tails that were
+    in progress when the process forked can't be resumed in the child, and
+    raise a `bauplan.exceptions.BauplanError`.
+
     ## Examples
 
     ```python
@@ -174,11 +239,18 @@ class Client:
     ```
 
     Parameters:
-        profile: The Bauplan config profile name to use to determine api_key.
+        profile: The Bauplan config profile name to use. Defaults to `BAUPLAN_PROFILE`, then the config file's `current_profile`, then `default`. Its `api_key`, endpoints and `default_namespace` can be overridden by environment variables, like `BAUPLAN_API_KEY` and `BAUPLAN_NAMESPACE`.
         api_key: Your unique Bauplan API key; mutually exclusive with `profile`. If not provided, fetch precedence is 1) environment `BAUPLAN_API_KEY` 2) .bauplan/config.yml
-        client_timeout: The client timeout in seconds for all the requests.
+        client_timeout: The timeout in seconds for job and query requests.
+        request_timeout: The timeout in seconds for each catalog request, like `get_table` or `create_branch`. Methods that make catalog requests can override it per call.
         config_file_path: The path to the Bauplan config file to use. If not provided, ~/.bauplan/config.yaml will be used. Note that this disables any environment-based configuration.
+        allow_blocking: Whether to allow blocking methods to be called while an asyncio event loop is running in the current thread. If `True`, a `RuntimeWarning` is emitted instead of raising an error.
+        max_user_logs: The maximum number of log events kept on a `bauplan.state.RunState`. Past the cap, the first and last halves are kept, with a marker event in between noting how many were dropped; the full logs remain available from `get_job_logs`. `None` keeps all of them.
+        rate_limit_budget: The total number of seconds to spend waiting and retrying when an API call is rate limited, before raising `bauplan.exceptions.TooManyRequestsError`. Defaults to the profile's `rate_limit_budget`, or 60 seconds. `0` disables retries.
+        flight_max_retries: The number of times to retry, with exponential backoff, when connecting to the server that returns query results fails. Errors returned by the server aren't retried. `0` disables retries.
     """
+    def __enter__(self, /) -> Client: ...
+    def __exit__(self, /, _exc_type: typing.Any, _exc_value: typing.Any, _traceback: typing.Any) -> None: ...
     def __new__(
         cls,
         /,
@@ -186,7 +258,80 @@ class Client:
         api_key: str | None = None,
         client_timeout: int | None = None,
         config_file_path: str | None = None,
+        allow_blocking: bool = False,
+        max_user_logs: int | None = 10000,
+        rate_limit_budget: int | None = None,
+        request_timeout: float = 30.0,
+        flight_max_retries: int = 3,
     ) -> Client: ...
+    @property
+    def active_branch(self, /) -> str:
+        """
+        The branch that methods use when their ref or branch argument is
+        omitted. Initialized from the profile's `active_branch`, or `"main"`.
+
+        Setting it doesn't check that the branch exists; use
+        `bauplan.Client.checkout` for that.
+        """
+    @active_branch.setter
+    def active_branch(self, /, value: "str | Branch") -> None: ...
+    def alter_table(
+        self,
+        /,
+        table: "str | Table",
+        branch: "str | Branch | None" = None,
+        *,
+        namespace: "str | Namespace | None" = None,
+        add_columns: "list[tuple[str, str, bool]] | None" = None,
+        drop_columns: "list[str] | None" = None,
+        rename_columns: "dict[str, str] | None" = None,
+        commit_body: "str | None" = None,
+        commit_properties: "dict[str, str] | None" = None,
+        request_timeout: "float | None" = None,
+    ) -> "Table":
+        """
+        Add, drop or rename columns of a table, in a single commit.
+
+        The changes are checked against the table's current schema before
+        they're sent. If the schema changes in the meantime, the update fails
+        instead of being applied to the new schema.
+
+        ```python
+        import bauplan
+        client = bauplan.Client()
+
+        table = client.alter_table(
+            table='my_table_name',
+            branch='my_branch_name',
+            add_columns=[('notes', 'string', False)],
+            drop_columns=['old_column'],
+            rename_columns={'name': 'full_name'},
+        )
+        ```
+
+        Parameters:
+            table: The table to change.
+            branch: The branch on which the table is stored. Defaults to the active branch.
+            namespace: The namespace of the table.
+            add_columns: Optional, columns to add, as `(name, type, required)` tuples. Types are Iceberg types, like `'string'` or `'decimal(10,2)'`.
+            drop_columns: Optional, names of columns to drop. Partition columns can't be dropped.
+            rename_columns: Optional, a dict from old column names to new ones.
+            commit_body: Optional, the commit body message to attach to the commit.
+            commit_properties: Optional, a list of properties to attach to the commit.
+            request_timeout: Optional, seconds to wait for each request. Defaults to the client's `request_timeout`.
+        Returns:
+            The updated `bauplan.schema.Table`.
+
+        Raises:
+            `bauplan.exceptions.TableNotFoundError`: if the table does not exist.
+            `bauplan.exceptions.BranchNotFoundError`: if the branch does not exist.
+            `bauplan.exceptions.NotAWriteBranchRefError`: if the branch is not a writable ref.
+            `bauplan.exceptions.BranchHeadChangedError`: if the branch head hash has changed.
+            `bauplan.exceptions.UpdateConflictError`: if the table's schema changed concurrently.
+            `bauplan.exceptions.InvalidDataError`: if the catalog rejects the changes.
+            `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+            `ValueError`: if the changes don't apply to the table's schema, or one or more parameters are invalid.
+        """
     def apply_table_creation_plan(
         self,
         /,
@@ -244,6 +389,34 @@ class Client:
         Raises:
             `bauplan.exceptions.TableCreatePlanApplyStatusError`: if the table creation plan apply fails.
         """
+    def attach(
+        self,
+        /,
+        run_state: "RunState",
+        *,
+        client_timeout: "int | None" = None,
+    ) -> "RunState":
+        """
+        Wait for a detached run to finish, and fill in its `RunState` in
+        place, as if it had been run without `detach`. The state is also
+        returned.
+
+        ```python
+        import bauplan
+        client = bauplan.Client()
+        state = client.run(project_dir='./my_pipeline', detach=True)
+
+        # Do something else, then wait for the run.
+        client.attach(state)
+        print(state.job_status, len(state.user_logs))
+        ```
+
+        Parameters:
+            run_state: The `RunState` returned by `run(..., detach=True)`.
+            client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+        Returns:
+            `bauplan.state.RunState`: The same `run_state`, filled in.
+        """
     def cancel_job(self, job_id: str, /) -> "None":
         """
         EXPERIMENTAL: Cancel a job by ID.
@@ -265,13 +438,77 @@ class Client:
         Parameters:
             job_id: A job ID
         """
+    @property
+    def catalog_endpoint(self, /) -> str:
+        """
+        The endpoint of the Iceberg REST catalog, for configuring other
+        Iceberg clients, like Spark, with the same API key. They add the `/v1`
+        prefix themselves.
+        """
+    def checkout(
+        self,
+        /,
+        branch: "str | Branch",
+        *,
+        create: "bool" = False,
+        from_ref: "str | Ref | None" = None,
+        persist: "bool" = False,
+    ) -> "Branch":
+        """
+        Switch the active branch, which methods use when their ref or branch
+        argument is omitted.
+
+        The branch must exist, unless `create` is set. By default only this
+        client is affected; with `persist`, the branch is also saved as the
+        profile's `active_branch` in the config file, like `bauplan checkout`.
+
+        Upon failure, raises `bauplan.exceptions.BauplanError`
+
+        ```python
+        import bauplan
+        client = bauplan.Client()
+
+        client.checkout('my_branch_name', create=True, from_ref='main')
+        table = client.get_table('my_table_name')  # read from my_branch_name
+        ```
+
+        Parameters:
+            branch: The name of the branch to switch to.
+            create: If set to `True`, create the branch first if it doesn't exist.
+            from_ref: The ref to create the branch from. Defaults to the active branch. Only valid with `create`.
+            persist: If set to `True`, save the branch as the profile's active branch.
+        Returns:
+            The `bauplan.schema.Branch` object switched to.
+
+        Raises:
+            `bauplan.exceptions.BranchNotFoundError`: if the branch does not exist.
+            `bauplan.exceptions.NotABranchRefError`: if the object is not a branch.
+            `bauplan.exceptions.RefNotFoundError`: if the source ref does not exist.
+            `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+            `ValueError`: if one or more parameters are invalid.
+        """
+    def close(self, /) -> None:
+        """
+        Closes the client's connections. Afterwards, methods that make
+        requests raise `bauplan.exceptions.BauplanError`. Closing a closed
+        client does nothing.
+
+        Other clients aren't affected. A client can also be used as a context
+        manager, which closes it on exit:
+
+        ```python
+        with bauplan.Client() as client:
+            client.query('SELECT 1', ref='main')
+        ```
+        """
     def create_branch(
         self,
         /,
         branch: "str | Branch",
-        from_ref: "str | Ref",
+        from_ref: "str | Ref | None" = None,
         *,
         if_not_exists: "bool" = False,
+        request_timeout: "float | None" = None,
     ) -> "Branch":
         """
         Create a new branch at a given ref.
@@ -297,8 +534,9 @@ class Client:
 
         Parameters:
             branch: The name of the new branch.
-            from_ref: The name of the base branch; either a branch like "main" or ref like "main@[sha]".
+            from_ref: The name of the base branch; either a branch like "main" or ref like "main@[sha]". Defaults to the active branch.
             if_not_exists: If set to `True`, the branch will not be created if it already exists.
+            request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
         Returns:
             The created `bauplan.schema.Branch` object.
 
@@ -316,7 +554,7 @@ class Client:
         table: "str | Table",
         metadata_json_uri: "str",
         *,
-        namespace: "str | Namespace",
+        namespace: "str | Namespace | None" = None,
         branch: "str | Branch | None" = None,
         overwrite: "bool" = False,
     ) -> "Table":
@@ -343,8 +581,8 @@ class Client:
         Parameters:
             table: The name of the table to create.
             metadata_json_uri: The S3 URI pointing to the Iceberg table's metadata.json file.
-            namespace: The namespace for the table (required).
-            branch: The branch name in which to create the table. Defaults to the active branch, or 'main'.
+            namespace: The namespace for the table. Defaults to the namespace in the table name (e.g. `ns.table`), or the catalog's default namespace.
+            branch: The branch name in which to create the table. Defaults to the active branch.
             overwrite: Whether to overwrite an existing table with the same name (default: False).
 
         Returns:
@@ -396,7 +634,7 @@ class Client:
         Parameters:
             table: The name of the external table to create.
             search_patterns: List of search_patterns for files to create the external table from. Must resolve to parquet files
-            branch: Branch in which to create the table.
+            branch: Branch in which to create the table. Defaults to the active branch.
             namespace: Namespace of the table. If not specified, namespace will be inferred from table name or default settings.
             overwrite: Whether to delete and recreate the table if it already exists.
             args: dict of arbitrary args to pass to the backend.
@@ -411,7 +649,7 @@ class Client:
         self,
         /,
         namespace: "str | Namespace",
-        branch: "str | Branch",
+        branch: "str | Branch | None" = None,
         *,
         commit_body: "str | None" = None,
         commit_properties: "dict[str, str] | None" = None,
@@ -435,7 +673,7 @@ class Client:
 
         Parameters:
             namespace: The name of the namespace.
-            branch: The name of the branch to create the namespace on.
+            branch: The name of the branch to create the namespace on. Defaults to the active branch.
             commit_body: Optional, the commit body to attach to the operation.
             commit_properties: Optional, a list of properties to attach to the commit.
             if_not_exists: If set to `True`, the namespace will not be created if it already exists.
@@ -462,17 +700,20 @@ class Client:
         *,
         branch: "str | Branch | None" = None,
         namespace: "str | Namespace | None" = None,
+        file_format: "Literal['parquet', 'csv', 'jsonl']" = "parquet",
         partitioned_by: "str | None" = None,
         replace: "bool | None" = None,
         args: "dict[str, str] | None" = None,
         priority: "int | None" = None,
         client_timeout: "int | None" = None,
+        events_out: "str | pathlib.Path | None" = None,
     ) -> "Table":
         """
         Create a table from an S3 location.
 
         This operation will attempt to create a table based on schemas of N
-        parquet files found by a given search uri. This is a two step operation using
+        parquet, CSV or JSONL files found by a given search uri. This is a two step
+        operation using
         `Client.plan_table_creation` and `Client.apply_table_creation_plan`.
 
         ```python
@@ -504,14 +745,16 @@ class Client:
 
         Parameters:
             table: The table which will be created.
-            search_uri: The location of the files to scan for schema.
-            branch: The branch name in which to create the table.
+            search_uri: The location of the files to scan for schema, as an `s3://` URI.
+            branch: The branch name in which to create the table. Defaults to the active branch.
             namespace: Optional argument specifying the namespace. If not specified, it will be inferred based on table location or the default.
+            file_format: The format of the files: `'parquet'`, `'csv'` or `'jsonl'`. Only parquet can be read for now. Search URIs with a known extension must match it.
             partitioned_by: Optional argument specifying the table partitioning.
             replace: Replace the table if it already exists.
             args: dict of arbitrary args to pass to the backend.
             priority: Optional job priority (1-10, where 10 is highest priority).
             client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+            events_out: Optional path to record the raw events of the planning and apply jobs to, for debugging. The file can be replayed with `bauplan job replay-events`.
         Returns:
             The created `bauplan.schema.Table`.
 
@@ -523,7 +766,7 @@ class Client:
         self,
         /,
         tag: "str | Tag",
-        from_ref: "str | Ref",
+        from_ref: "str | Ref | None" = None,
         *,
         if_not_exists: "bool" = False,
     ) -> "Tag":
@@ -544,7 +787,7 @@ class Client:
 
         Parameters:
             tag: The name of the new tag.
-            from_ref: The name of the base branch; either a branch like "main" or ref like "main@[sha]".
+            from_ref: The name of the base branch; either a branch like "main" or ref like "main@[sha]". Defaults to the active branch.
             if_not_exists: If set to `True`, the tag will not be created if it already exists.
         Returns:
             The created `bauplan.schema.Tag` object.
@@ -559,6 +802,7 @@ class Client:
         """
     def delete_branch(
         self, /, branch: "str | Branch", *, if_exists: "bool" = False
+        request_timeout: "float | None" = None,
     ) -> "bool":
         """
         Delete a branch.
@@ -576,6 +820,7 @@ class Client:
         Parameters:
             branch: The name of the branch to delete.
             if_exists: If set to `True`, the branch will not raise an error if it does not exist.
+            request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
         Returns:
             A boolean for if the branch was deleted.
 
@@ -593,7 +838,7 @@ class Client:
         self,
         /,
         namespace: "str | Namespace",
-        branch: "str | Branch",
+        branch: "str | Branch | None" = None,
         *,
         if_exists: "bool" = False,
         commit_body: "str | None" = None,
@@ -616,7 +861,7 @@ class Client:
 
         Parameters:
             namespace: The name of the namespace to delete.
-            branch: The name of the branch to delete the namespace from.
+            branch: The name of the branch to delete the namespace from. Defaults to the active branch.
             commit_body: Optional, the commit body to attach to the operation.
             commit_properties: Optional, a list of properties to attach to the commit.
             if_exists: If set to `True`, the namespace will not raise an error if it does not exist.
@@ -638,12 +883,13 @@ class Client:
         self,
         /,
         table: "str | Table",
-        branch: "str | Branch",
+        branch: "str | Branch | None" = None,
         *,
         namespace: "str | Namespace | None" = None,
         if_exists: "bool" = False,
         commit_body: "str | None" = None,
         commit_properties: "dict[str, str] | None" = None,
+        request_timeout: "float | None" = None,
     ) -> "Branch":
         """
         Drop a table.
@@ -663,11 +909,12 @@ class Client:
 
         Parameters:
             table: The table to delete.
-            branch: The branch on which the table is stored.
+            branch: The branch on which the table is stored. Defaults to the active branch.
             namespace: The namespace of the table to delete.
             commit_body: Optional, the commit body message to attach to the commit.
             commit_properties: Optional, a list of properties to attach to the commit.
             if_exists: If set to `True`, the table will not raise an error if it does not exist.
+            request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
         Returns:
             A `bauplan.schema.Branch` object pointing to the new head.
 
@@ -712,42 +959,69 @@ class Client:
             `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
             `ValueError`: if one or more parameters are invalid.
         """
-    def get_branch(self, /, branch: "str | Branch") -> "Branch":
+    def diff_table(
+        self,
+        /,
+        table: "str | Table",
+        from_ref: "str | Ref",
+        to_ref: "str | Ref | None" = None,
+        *,
+        namespace: "str | Namespace | None" = None,
+        request_timeout: "float | None" = None,
+    ) -> "TableDiff":
         """
-        Get the branch.
-
-        Upon failure, raises `bauplan.exceptions.BauplanError`
+        Compare a table between two refs: its record count, current snapshot,
+        columns and partition fields.
 
         ```python
         import bauplan
         client = bauplan.Client()
 
-        branch = client.get_branch('my_branch_name')
+        # what changed in titanic on my branch, compared to main?
+        diff = client.diff_table(
+            table='titanic',
+            from_ref='main',
+            to_ref='my_branch_name',
+            namespace='bauplan',
+        )
+
+        if not diff.is_empty():
+            print(diff.records_delta)
+            for c in diff.columns:
+                print(c.name, c.kind, c.from_type, c.to_type)
         ```
 
         Parameters:
-            branch: The name of the branch to retrieve.
+            table: The table to compare.
+            from_ref: The ref, branch name or tag name to compare from.
+            to_ref: The ref, branch name or tag name to compare to. Defaults to the active branch.
+            namespace: The namespace of the table.
+            request_timeout: Optional, seconds to wait for each request. Defaults to the client's `request_timeout`.
         Returns:
-            A `bauplan.schema.Branch` object.
+            a `bauplan.schema.TableDiff` object
 
         Raises:
-            `bauplan.exceptions.BranchNotFoundError`: if the branch does not exist.
-            `bauplan.exceptions.NotABranchRefError`: if the object is not a branch.
-            `bauplan.exceptions.InvalidRefError`: if the ref format is invalid.
-            `bauplan.exceptions.ForbiddenError`: if the user does not have access to the branch.
+            `bauplan.exceptions.RefNotFoundError`: if either ref does not exist.
+            `bauplan.exceptions.InvalidRefError`: if either ref format is invalid.
+            `bauplan.exceptions.NamespaceNotFoundError`: if the namespace does not exist.
+            `bauplan.exceptions.TableNotFoundError`: if the table does not exist on either ref.
             `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
             `ValueError`: if one or more parameters are invalid.
         """
-    def get_branches(
+    def ensure_branch(
         self,
         /,
+        branch: "str | Branch",
+        from_ref: "str | Ref | None" = None,
         *,
-        name: "str | None" = None,
-        user: "str | None" = None,
-        limit: "int | None" = None,
-    ) -> "typing.Iterator[Branch]":
+        request_timeout: "float | None" = None,
+    ) -> "Branch":
         """
-        Get the available data branches in the Bauplan catalog.
+        Create a branch if it doesn't exist yet, and return it either way.
+
+        Only a `bauplan.exceptions.BranchExistsError` counts as the branch
+        already existing; anything else is raised, as with `create_branch`.
+        Whether the branch was created is logged to the `bauplan` logger.
 
         Upon failure, raises `bauplan.exceptions.BauplanError`
 
@@ -755,36 +1029,39 @@ class Client:
         import bauplan
         client = bauplan.Client()
 
-        for branch in client.get_branches():
-            ...
+        branch = client.ensure_branch('my_branch_name', from_ref='main')
         ```
 
         Parameters:
-            name: Filter the branches by name.
-            user: Filter the branches by user.
-            limit: Optional, max number of branches to get.
+            branch: The name of the branch.
+            from_ref: The ref to create the branch from, if it doesn't exist. Defaults to the active branch.
+            request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
         Returns:
-            An iterator over `bauplan.schema.Branch` objects.
+            The created or existing `bauplan.schema.Branch` object. An existing branch is returned at its current head, which may not be `from_ref`.
+
+        Raises:
+            `bauplan.exceptions.CreateBranchForbiddenError`: if the user does not have access to create the branch.
+            `bauplan.exceptions.RefNotFoundError`: if the source ref does not exist.
+            `bauplan.exceptions.InvalidRefError`: if the ref format is invalid.
+            `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+            `ValueError`: if one or more parameters are invalid.
         """
-    def get_commits(
+    def ensure_namespace(
         self,
         /,
-        ref: "str | Ref",
+        namespace: "str | Namespace",
+        branch: "str | Branch | None" = None,
         *,
-        filter_by_message: "str | None" = None,
-        filter_by_author_username: "str | None" = None,
-        filter_by_author_name: "str | None" = None,
-        filter_by_author_email: "str | None" = None,
-        filter_by_authored_date: "str | datetime | None" = None,
-        filter_by_authored_date_start_at: "str | datetime | None" = None,
-        filter_by_authored_date_end_at: "str | datetime | None" = None,
-        filter_by_parent_hash: "str | None" = None,
-        filter_by_properties: "dict[str, str] | None" = None,
-        filter: "str | None" = None,
-        limit: "int | None" = None,
-    ) -> "typing.Iterator[Commit]":
+        commit_body: "str | None" = None,
+        commit_properties: "dict[str, str] | None" = None,
+    ) -> "Namespace":
         """
-        Get the commits for the target branch or ref.
+        Create a namespace if it doesn't exist yet, and return it either way.
+
+        Only a `bauplan.exceptions.NamespaceExistsError` counts as the
+        namespace already existing; anything else is raised, as with
+        `create_namespace`. Whether the namespace was created is logged to the
+        `bauplan` logger.
 
         Upon failure, raises `bauplan.exceptions.BauplanError`
 
@@ -792,65 +1069,266 @@ class Client:
         import bauplan
         client = bauplan.Client()
 
-        for commit in client.get_commits('my_ref_or_branch_name', limit=50):
-            ...
+        namespace = client.ensure_namespace('my_namespace_name', 'my_branch_name')
         ```
 
         Parameters:
-            ref: The ref or branch to get the commits from.
-            filter_by_message: Optional, filter the commits by message (can be a string or a regex like '^abc.*$')
-            filter_by_author_username: Optional, filter the commits by author username (can be a string or a regex like '^abc.*$')
-            filter_by_author_name: Optional, filter the commits by author name (can be a string or a regex like '^abc.*$')
-            filter_by_author_email: Optional, filter the commits by author email (can be a string or a regex like '^abc.*$')
-            filter_by_authored_date: Optional, filter the commits by the exact authored date.
-            filter_by_authored_date_start_at: Optional, filter the commits by authored date start at.
-            filter_by_authored_date_end_at: Optional, filter the commits by authored date end at.
-            filter_by_parent_hash: Optional, filter the commits by parent hash.
-            filter_by_properties: Optional, filter the commits by commit properties.
-            filter: Optional, a CEL filter expression to filter the commits.
-            limit: Optional, max number of commits to get.
+            namespace: The name of the namespace.
+            branch: The name of the branch to create the namespace on. Defaults to the active branch.
+            commit_body: Optional, the commit body to attach to the operation, if the namespace is created.
+            commit_properties: Optional, a list of properties to attach to the commit, if the namespace is created.
         Returns:
-            An iterator over `bauplan.schema.Commit` objects.
+            The created or existing `bauplan.schema.Namespace` object.
 
         Raises:
-            `bauplan.exceptions.RefNotFoundError`: if the ref does not exist.
-            `bauplan.exceptions.InvalidRefError`: if the ref format is invalid.
+            `bauplan.exceptions.CreateNamespaceForbiddenError`: if the user does not have access to create the namespace.
+            `bauplan.exceptions.BranchNotFoundError`: if the branch does not exist.
+            `bauplan.exceptions.NotABranchRefError`: if the object is not a branch.
+            `bauplan.exceptions.NotAWriteBranchRefError`: if the destination branch is not a writable ref.
             `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
             `ValueError`: if one or more parameters are invalid.
         """
-    def get_job(self, job_id: str, /) -> "Job":
+    def ensure_table_absent(
+        self,
+        /,
+        table: "str | Table",
+        branch: "str | Branch | None" = None,
+        *,
+        namespace: "str | Namespace | None" = None,
+        commit_body: "str | None" = None,
+        commit_properties: "dict[str, str] | None" = None,
+        request_timeout: "float | None" = None,
+    ) -> "Branch":
         """
-        EXPERIMENTAL: Get a job by ID.
+        Delete a table if it exists, and do nothing otherwise.
+
+        Only a `bauplan.exceptions.TableNotFoundError` counts as the table
+        already being absent; anything else is raised, as with `delete_table`.
+        Whether the table was deleted is logged to the `bauplan` logger.
+
+        Upon failure, raises `bauplan.exceptions.BauplanError`
 
         ```python
-        #! my_job: bauplan.schema.Job = ...  # type: ignore[assignment]
         import bauplan
         client = bauplan.Client()
 
-        job = client.get_job(my_job.id)
-        print(f"{job.human_readable_status} ({job.kind})")
+        client.ensure_table_absent(
+            table='my_table_name',
+            branch='my_branch_name',
+            namespace='my_namespace',
+        )
         ```
 
         Parameters:
-            job_id: A job ID
+            table: The table to delete.
+            branch: The branch on which the table is stored. Defaults to the active branch.
+            namespace: The namespace of the table to delete.
+            commit_body: Optional, the commit body message to attach to the commit, if the table is deleted.
+            commit_properties: Optional, a list of properties to attach to the commit, if the table is deleted.
+            request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
         Returns:
-            A `bauplan.schema.Job` object.
+            A `bauplan.schema.Branch` object pointing to the head of the branch, after the delete if there was one.
+
+        Raises:
+            `bauplan.exceptions.DeleteTableForbiddenError`: if the user does not have access to delete the table.
+            `bauplan.exceptions.BranchNotFoundError`: if the branch does not exist.
+            `bauplan.exceptions.BranchHeadChangedError`: if the branch head hash has changed.
+            `bauplan.exceptions.NotAWriteBranchRefError`: if the destination branch is not a writable ref.
+            `bauplan.exceptions.NamespaceNotFoundError`: if the namespace does not exist.
+            `bauplan.exceptions.NamespaceUnresolvedError`: if conflicting namespaces names are specified.
+            `bauplan.exceptions.InvalidRefError`: if the ref format is invalid.
+            `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+            `ValueError`: if one or more parameters are invalid.
         """
-    def get_job_context(
+    def explain_run(
         self,
         /,
-        job: str | Job,
+        project_dir: "str",
         *,
-        include_logs: bool = False,
-        include_snapshot: bool = False,
-    ) -> "JobContext":
+        ref: "str | Ref | None" = None,
+        namespace: "str | Namespace | None" = None,
+        parameters: "dict[str, str | int | float | bool | None] | None" = None,
+        cache: "Literal['on', 'off'] | None" = None,
+        args: "dict[str, str] | None" = None,
+        priority: "int | None" = None,
+        client_timeout: "int | None" = None,
+    ) -> "RunPlan":
         """
-        EXPERIMENTAL: Get context for a job by ID.
+        Plan a Bauplan project without running it, and return the plan: the
+        models that would run and their dependencies. This is the equivalent
+        of running through the CLI the `bauplan run --explain` command.
+
+        The project is submitted as a dry run. Once it has been planned, the
+        job is cancelled and the plan is read from its context.
 
         ```python
-        #! my_job: bauplan.schema.Job = ...  # type: ignore[assignment]
-        import bauplan
-        client = bauplan.Client()
+        #! client = bauplan.Client()
+        plan = client.explain_run(project_dir='./etl_pipelines/daily_sales')
+
+        for model in plan.models:
+            print(model.model_name, model.depends_on)
+        ```
+
+        Parameters:
+            project_dir: The directory of the project (where the `bauplan_project.yml` or `bauplan_project.yaml` file is located).
+            ref: The ref, branch name or tag name to plan the project against. Defaults to the active branch.
+            namespace: The Namespace to plan the job in. If not set, the default namespace is used.
+            parameters: Parameters for templating into SQL or Python models.
+            cache: Whether to enable or disable caching for the run. Defaults to 'on'.
+            args: Additional arguments (optional).
+            priority: Optional job priority (1-10, where 10 is highest priority).
+            client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+        Returns:
+            `bauplan.state.RunPlan`: The plan for the run.
+        """
+    def get_branch(
+        self,
+        /,
+        branch: "str | Branch",
+        *,
+        request_timeout: "float | None" = None,
+    ) -> "Branch":
+        """
+        Get the branch.
+
+        Upon failure, raises `bauplan.exceptions.BauplanError`
+
+        ```python
+        import bauplan
+        client = bauplan.Client()
+
+        branch = client.get_branch('my_branch_name')
+        ```
+
+        Parameters:
+            branch: The name of the branch to retrieve.
+            request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
+        Returns:
+            A `bauplan.schema.Branch` object.
+
+        Raises:
+            `bauplan.exceptions.BranchNotFoundError`: if the branch does not exist.
+            `bauplan.exceptions.NotABranchRefError`: if the object is not a branch.
+            `bauplan.exceptions.InvalidRefError`: if the ref format is invalid.
+            `bauplan.exceptions.ForbiddenError`: if the user does not have access to the branch.
+            `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+            `ValueError`: if one or more parameters are invalid.
+        """
+    def get_branches(
+        self,
+        /,
+        *,
+        name: "str | None" = None,
+        user: "str | None" = None,
+        limit: "int | None" = None,
+        request_timeout: "float | None" = None,
+    ) -> "typing.Iterator[Branch]":
+        """
+        Get the available data branches in the Bauplan catalog.
+
+        Upon failure, raises `bauplan.exceptions.BauplanError`
+
+        ```python
+        import bauplan
+        client = bauplan.Client()
+
+        for branch in client.get_branches():
+            ...
+        ```
+
+        Parameters:
+            name: Filter the branches by name.
+            user: Filter the branches by user.
+            limit: Optional, max number of branches to get.
+            request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
+        Returns:
+            An iterator over `bauplan.schema.Branch` objects.
+        """
+    def get_commits(
+        self,
+        /,
+        ref: "str | Ref | None" = None,
+        *,
+        filter_by_message: "str | None" = None,
+        filter_by_author_username: "str | None" = None,
+        filter_by_author_name: "str | None" = None,
+        filter_by_author_email: "str | None" = None,
+        filter_by_authored_date: "str | datetime | None" = None,
+        filter_by_authored_date_start_at: "str | datetime | None" = None,
+        filter_by_authored_date_end_at: "str | datetime | None" = None,
+        filter_by_parent_hash: "str | None" = None,
+        filter_by_properties: "dict[str, str] | None" = None,
+        filter: "str | None" = None,
+        limit: "int | None" = None,
+    ) -> "typing.Iterator[Commit]":
+        """
+        Get the commits for the target branch or ref.
+
+        Upon failure, raises `bauplan.exceptions.BauplanError`
+
+        ```python
+        import bauplan
+        client = bauplan.Client()
+
+        for commit in client.get_commits('my_ref_or_branch_name', limit=50):
+            ...
+        ```
+
+        Parameters:
+            ref: The ref or branch to get the commits from. Defaults to the active branch.
+            filter_by_message: Optional, filter the commits by message (can be a string or a regex like '^abc.*$')
+            filter_by_author_username: Optional, filter the commits by author username (can be a string or a regex like '^abc.*$')
+            filter_by_author_name: Optional, filter the commits by author name (can be a string or a regex like '^abc.*$')
+            filter_by_author_email: Optional, filter the commits by author email (can be a string or a regex like '^abc.*$')
+            filter_by_authored_date: Optional, filter the commits by the exact authored date.
+            filter_by_authored_date_start_at: Optional, filter the commits by authored date start at.
+            filter_by_authored_date_end_at: Optional, filter the commits by authored date end at.
+            filter_by_parent_hash: Optional, filter the commits by parent hash.
+            filter_by_properties: Optional, filter the commits by commit properties.
+            filter: Optional, a CEL filter expression to filter the commits.
+            limit: Optional, max number of commits to get.
+        Returns:
+            An iterator over `bauplan.schema.Commit` objects.
+
+        Raises:
+            `bauplan.exceptions.RefNotFoundError`: if the ref does not exist.
+            `bauplan.exceptions.InvalidRefError`: if the ref format is invalid.
+            `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+            `ValueError`: if one or more parameters are invalid.
+        """
+    def get_job(self, job_id: str, /) -> "Job":
+        """
+        EXPERIMENTAL: Get a job by ID.
+
+        ```python
+        #! my_job: bauplan.schema.Job = ...  # type: ignore[assignment]
+        import bauplan
+        client = bauplan.Client()
+
+        job = client.get_job(my_job.id)
+        print(f"{job.human_readable_status} ({job.kind})")
+        ```
+
+        Parameters:
+            job_id: A job ID
+        Returns:
+            A `bauplan.schema.Job` object.
+        """
+    def get_job_context(
+        self,
+        /,
+        job: str | Job,
+        *,
+        include_logs: bool = False,
+        include_snapshot: bool = False,
+    ) -> "JobContext":
+        """
+        EXPERIMENTAL: Get context for a job by ID.
+
+        ```python
+        #! my_job: bauplan.schema.Job = ...  # type: ignore[assignment]
+        import bauplan
+        client = bauplan.Client()
 
         ctx = client.get_job_context(my_job.id, include_logs=True)
         print(f"ref: {ctx.ref}, project: {ctx.project_name}")
@@ -893,6 +1371,29 @@ class Client:
         Returns:
             A list of `bauplan.schema.JobContext` objects containing the job details, and optionally logs and snapshot.
         """
+    def get_job_dag(self, /, job: str | Job) -> "JobDAG":
+        """
+        EXPERIMENTAL: Get the DAG of a job, with the state of each model.
+
+        The state and timing of each model come from the job's task events.
+        Jobs that predate DAG tracking return an empty DAG.
+
+        ```python
+        #! my_job: bauplan.schema.Job = ...  # type: ignore[assignment]
+        import bauplan
+        client = bauplan.Client()
+
+        dag = client.get_job_dag(my_job)
+        for node in dag.nodes:
+            print(f"{node.name}: {node.state}")
+        print(dag.to_dot())
+        ```
+
+        Parameters:
+            job: Union[str, Job]: A job ID or a Job instance.
+        Returns:
+            A `bauplan.schema.JobDAG` object.
+        """
     def get_job_logs(self, /, job: str | Job) -> "list[JobLogEvent]":
         """
         EXPERIMENTAL: Get logs for a job.
@@ -918,11 +1419,13 @@ class Client:
         filter_by_current_user: bool = True,
         filter_by_ids: str | list[str] | list[Job] | None = None,
         filter_by_users: str | list[str] | None = None,
-        filter_by_kinds: str | JobKind | list[str] | list[JobKind] | None = None,
-        filter_by_statuses: str | JobState | list[str] | list[JobState] | None = None,
+        filter_by_kinds: str | JobKind | list[str | JobKind] | None = None,
+        filter_by_statuses: str | JobState | list[str | JobState] | None = None,
         filter_by_created_after: datetime | None = None,
         filter_by_created_before: datetime | None = None,
+        filter_by_project: str | None = None,
         limit: int | None = None,
+        order: "Literal['asc', 'desc']" = "desc",
     ) -> "typing.Iterator[Job]":
         """
         Get jobs with optional filtering.
@@ -941,16 +1444,27 @@ class Client:
             filter_by_ids: Optional[Union[str, List[str]]]: Optional, filter by job IDs.
             filter_by_users: Optional[Union[str, List[str]]]: Optional, filter by job users.
             filter_by_kinds: Optional[Union[str, JobKind, List[Union[str, JobKind]]]]: Optional, filter by job kinds.
+                Strings may be enum member names or the CLI's names, in any case, like "RUN", "run" or "table-import".
             filter_by_statuses: Optional[Union[str, JobState, List[Union[str, JobState]]]]: Optional, filter by job statuses.
+                Strings may be enum member names or the CLI's names, in any case, like "NOT_STARTED" or "not-started".
             filter_by_created_after: Optional[datetime]: Optional, filter jobs created after this datetime.
             filter_by_created_before: Optional[datetime]: Optional, filter jobs created before this datetime.
+            filter_by_project: Optional[str]: Optional, filter by project ID or name, or "." for the project in the
+                current directory. This is done client-side, with an extra request per page of jobs; if `limit` is
+                set, only the `limit` most recent jobs are checked, so fewer may be returned.
             limit: Optional[int]: Optional, max number of jobs to return.
+            order: Literal["asc", "desc"]: Whether to return the oldest or the newest jobs first. Defaults to "desc".
+                The server lists jobs newest first, so with "asc" every matching job is fetched before the first
+                one is returned; use `filter_by_created_after` to keep that small.
+
+        Jobs are sorted by creation time. Each job is returned at most once,
+        but jobs created while iterating may be missed.
 
         Returns:
             An iterator over `bauplan.schema.Job` objects.
         """
     def get_namespace(
-        self, /, namespace: "str | Namespace", ref: "str | Ref"
+        self, /, namespace: "str | Namespace", ref: "str | Ref | None" = None
     ) -> "Namespace":
         """
         Get a namespace.
@@ -969,7 +1483,7 @@ class Client:
 
         Parameters:
             namespace: The name of the namespace to get.
-            ref: The ref, branch name or tag name to check the namespace on.
+            ref: The ref, branch name or tag name to check the namespace on. Defaults to the active branch.
         Returns:
             A `bauplan.schema.Namespace` object.
 
@@ -983,7 +1497,7 @@ class Client:
     def get_namespaces(
         self,
         /,
-        ref: "str | Ref",
+        ref: "str | Ref | None" = None,
         *,
         filter_by_name: "str | None" = None,
         limit: "int | None" = None,
@@ -1002,7 +1516,7 @@ class Client:
         ```
 
         Parameters:
-            ref: The ref, branch name or tag name to retrieve the namespaces from.
+            ref: The ref, branch name or tag name to retrieve the namespaces from. Defaults to the active branch.
             filter_by_name: Optional, filter the namespaces by name.
             limit: Optional, max number of namespaces to get.
 
@@ -1015,13 +1529,71 @@ class Client:
         Yields:
             `bauplan.schema.Namespace` objects.
         """
+    def get_query_endpoint(self, job: "str | Job", /) -> "QueryResultsEndpoint":
+        """
+        Get where to fetch the results of a completed query, so that they
+        can be fetched by another process with `bauplan.fetch_query_results`.
+
+        The returned endpoint includes a token that grants access to the
+        results until they expire, without an API key. Only hand it to
+        processes you'd trust with the results, and don't log it. Results
+        can only be fetched once; the server shuts down after serving them.
+
+        ```python
+        #! my_job: bauplan.schema.Job = ...  # type: ignore[assignment]
+        import bauplan
+        client = bauplan.Client()
+
+        endpoint = client.get_query_endpoint(my_job)
+        print(endpoint.endpoint, endpoint.expires_at)
+        ```
+
+        Parameters:
+            job: A job ID or Job instance, for a completed query.
+
+        Returns:
+            A `bauplan.QueryResultsEndpoint`.
+
+        Raises:
+            `ValueError`: if the job isn't a query.
+            `bauplan.exceptions.BauplanError`: if the query hasn't completed, or its results weren't served over Arrow Flight.
+        """
+    def get_ref_summary(self, ref: "str | Ref | None" = None, /) -> "RefSummary":
+        """
+        Get aggregate statistics for the tables in a ref: the number of
+        tables, their total records and size, and when any of them was last
+        updated.
+
+        This lists every table in the ref, a few namespaces at a time, so it
+        can be slow for refs with many tables.
+
+        ```python
+        import bauplan
+        client = bauplan.Client()
+
+        summary = client.get_ref_summary('main')
+        print(f"{summary.tables} tables, {summary.total_size} bytes")
+        ```
+
+        Parameters:
+            ref: The ref or branch to summarize. Defaults to the active branch.
+        Returns:
+            A `bauplan.schema.RefSummary` object.
+
+        Raises:
+            `bauplan.exceptions.RefNotFoundError`: if the ref does not exist.
+            `bauplan.exceptions.InvalidRefError`: if the ref format is invalid.
+            `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+        """
     def get_table(
         self,
         /,
         table: "str | Table",
-        ref: "str | Ref",
+        ref: "str | Ref | None" = None,
         *,
         namespace: "str | Namespace | None" = None,
+        suggest_namespaces: "bool" = False,
+        request_timeout: "float | None" = None,
     ) -> "Table":
         """
         Get the table data and metadata for a table in the target branch.
@@ -1048,9 +1620,11 @@ class Client:
         ```
 
         Parameters:
-            ref: The ref, branch name or tag name to get the table from.
+            ref: The ref, branch name or tag name to get the table from. Defaults to the active branch.
             table: The table to retrieve.
             namespace: The namespace of the table to retrieve.
+            suggest_namespaces: If `True` and a table given without a namespace isn't found, look for tables with that name in other namespaces, and suggest them in the `TableNotFoundError` message.
+            request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
         Returns:
             a `bauplan.schema.Table` object
 
@@ -1063,14 +1637,55 @@ class Client:
             `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
             `ValueError`: if one or more parameters are invalid.
         """
+    def get_table_snapshots(
+        self,
+        /,
+        table: "str | Table",
+        ref: "str | Ref | None" = None,
+        *,
+        namespace: "str | Namespace | None" = None,
+        limit: "int | None" = None,
+        request_timeout: "float | None" = None,
+    ) -> "typing.Iterator[TableSnapshot]":
+        """
+        Get the snapshot history of a table, newest first. Each snapshot is
+        the state of the table after one write. Queries can't read a table as
+        of a snapshot, so these are for inspecting the table's history.
+
+        ```python
+        import bauplan
+        client = bauplan.Client()
+
+        for snapshot in client.get_table_snapshots('titanic', 'main', namespace='bauplan'):
+            print(snapshot.snapshot_id, snapshot.timestamp, snapshot.operation)
+        ```
+
+        Parameters:
+            table: The table to get the snapshots of.
+            ref: The ref, branch name or tag name to read the table at. Defaults to the active branch.
+            namespace: The namespace of the table.
+            limit: Optional, max number of snapshots to get.
+            request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
+        Returns:
+            An iterator over `bauplan.schema.TableSnapshot` objects.
+
+        Raises:
+            `bauplan.exceptions.RefNotFoundError`: if the ref does not exist.
+            `bauplan.exceptions.InvalidRefError`: if the ref format is invalid.
+            `bauplan.exceptions.NamespaceNotFoundError`: if the namespace does not exist.
+            `bauplan.exceptions.TableNotFoundError`: if the table does not exist.
+            `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+            `ValueError`: if one or more parameters are invalid.
+        """
     def get_tables(
         self,
         /,
-        ref: "str | Ref",
+        ref: "str | Ref | None" = None,
         *,
         filter_by_name: "str | None" = None,
         filter_by_namespace: "str | Namespace | None" = None,
         limit: "int | None" = None,
+        request_timeout: "float | None" = None,
     ) -> "typing.Iterator[Table]":
         """
         Get the tables and views in the target branch.
@@ -1086,10 +1701,11 @@ class Client:
         ```
 
         Parameters:
-            ref: The ref or branch to get the tables from.
+            ref: The ref or branch to get the tables from. Defaults to the active branch.
             filter_by_name: Optional, the table name to filter by.
             filter_by_namespace: Optional, the namespace to get filtered tables from.
             limit: Optional, max number of tables to get.
+            request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
         Returns:
             An iterator over `bauplan.schema.Table` objects.
 
@@ -1153,6 +1769,12 @@ class Client:
             `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
             `ValueError`: if one or more parameters are invalid.
         """
+    @property
+    def grpc_endpoint(self, /) -> str:
+        """
+        The endpoint of the gRPC API, which runs jobs and streams their
+        events.
+        """
     def has_branch(self, /, branch: "str | Branch") -> "bool":
         """
         Check if a branch exists.
@@ -1178,7 +1800,7 @@ class Client:
             `ValueError`: if one or more parameters are invalid.
         """
     def has_namespace(
-        self, /, namespace: "str | Namespace", ref: "str | Ref"
+        self, /, namespace: "str | Namespace", ref: "str | Ref | None" = None
     ) -> "bool":
         """
         Check if a namespace exists.
@@ -1197,7 +1819,7 @@ class Client:
 
         Parameters:
             namespace: The name of the namespace to check.
-            ref: The ref, branch name or tag name to check the namespace on.
+            ref: The ref, branch name or tag name to check the namespace on. Defaults to the active branch.
 
         Returns:
             A boolean for if the namespace exists.
@@ -1212,7 +1834,7 @@ class Client:
         self,
         /,
         table: "str | Table",
-        ref: "str | Ref",
+        ref: "str | Ref | None" = None,
         *,
         namespace: "str | Namespace | None" = None,
     ) -> "bool":
@@ -1233,7 +1855,7 @@ class Client:
         ```
 
         Parameters:
-            ref: The ref, branch name or tag name to get the table from.
+            ref: The ref, branch name or tag name to get the table from. Defaults to the active branch.
             table: The table to retrieve.
             namespace: The namespace of the table to check.
         Returns:
@@ -1278,6 +1900,7 @@ class Client:
         *,
         branch: "str | Branch | None" = None,
         namespace: "str | Namespace | None" = None,
+        file_format: "Literal['parquet', 'csv', 'jsonl']" = "parquet",
         continue_on_error: "bool" = False,
         import_duplicate_files: "bool" = False,
         best_effort: "bool" = False,
@@ -1286,6 +1909,8 @@ class Client:
         priority: "int | None" = None,
         client_timeout: "int | None" = None,
         detach: "bool" = False,
+        job_name: "str | None" = None,
+        events_out: "str | pathlib.Path | None" = None,
     ) -> "TableDataImportState":
         """
         Imports data into an already existing table.
@@ -1305,11 +1930,15 @@ class Client:
             print(f"Import succeeded: {state.job_status}")
         ```
 
+        With `continue_on_error`, the job succeeds even if some files fail.
+        The server doesn't report which files failed.
+
         Parameters:
             table: Previously created table into which data will be imported.
-            search_uri: URI to scan for files to import.
-            branch: Branch in which to import the table.
+            search_uri: The `s3://` URI to scan for files to import.
+            branch: Branch in which to import the table. Defaults to the active branch.
             namespace: Namespace of the table. If not specified, namespace will be inferred from table name or default settings.
+            file_format: The format of the files: `'parquet'`, `'csv'` or `'jsonl'`. Only parquet can be read for now. Files and search URIs with a known extension must match it.
             continue_on_error: Do not fail the import even if 1 data import fails.
             import_duplicate_files: Ignore prevention of importing s3 files that were already imported.
             best_effort: Don't fail if schema of table does not match.
@@ -1318,6 +1947,8 @@ class Client:
             priority: Optional job priority (1-10, where 10 is highest priority).
             client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
             detach: Whether to detach the job and return immediately without waiting for the job to finish.
+            job_name: Optional human-friendly name for the job, sent as the `bauplan.job-name` job arg.
+            events_out: Optional path to record the raw events of the job to, for debugging. The file can be replayed with `bauplan job replay-events`. Can't be used with `detach`.
         Returns:
             A `bauplan.state.TableDataImportState` object.
         """
@@ -1346,11 +1977,12 @@ class Client:
         self,
         /,
         source_ref: "str | Ref",
-        into_branch: "str | Branch",
+        into_branch: "str | Branch | None" = None,
         *,
         commit_message: "str | None" = None,
         commit_body: "str | None" = None,
         commit_properties: "dict[str, str] | None" = None,
+        request_timeout: "float | None" = None,
     ) -> "Branch":
         """
         Merge one branch into another.
@@ -1369,10 +2001,11 @@ class Client:
 
         Parameters:
             source_ref: The name of the merge source; either a branch like "main" or ref like "main@[sha]".
-            into_branch: The name of the merge target.
+            into_branch: The name of the merge target. Defaults to the active branch.
             commit_message: Optional, the commit message.
             commit_body: Optional, the commit body.
             commit_properties: Optional, a list of properties to attach to the merge.
+            request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
         Returns:
             The `bauplan.schema.Branch` where the merge was made.
 
@@ -1395,6 +2028,7 @@ class Client:
         *,
         branch: "str | Branch | None" = None,
         namespace: "str | Namespace | None" = None,
+        file_format: "Literal['parquet', 'csv', 'jsonl']" = "parquet",
         partitioned_by: "str | None" = None,
         replace: "bool | None" = None,
         args: "dict[str, str] | None" = None,
@@ -1405,7 +2039,8 @@ class Client:
         Create a table import plan from an S3 location.
 
         This operation will attempt to create a table based on schemas of N
-        parquet files found by a given search uri. A YAML file containing the
+        parquet, CSV or JSONL files found by a given search uri. A YAML file
+        containing the
         schema and plan is returned and if there are no conflicts, it is
         automatically applied.
 
@@ -1453,9 +2088,10 @@ class Client:
 
         Parameters:
             table: The table which will be created.
-            search_uri: The location of the files to scan for schema.
-            branch: The branch name in which to create the table.
+            search_uri: The location of the files to scan for schema, as an `s3://` URI.
+            branch: The branch name in which to create the table. Defaults to the active branch.
             namespace: Optional argument specifying the namespace. If not specified, it will be inferred based on table location or the default.
+            file_format: The format of the files: `'parquet'`, `'csv'` or `'jsonl'`. Only parquet can be read for now. Search URIs with a known extension must match it.
             partitioned_by: Optional argument specifying the table partitioning.
             replace: Replace the table if it already exists.
             args: dict of arbitrary args to pass to the backend.
@@ -1473,6 +2109,7 @@ class Client:
         /,
         query: "str",
         *,
+        params: "dict[str, typing.Any] | list[typing.Any] | None" = None,
         ref: "str | Ref | None" = None,
         max_rows: "int | None" = None,
         cache: "Literal['on', 'off'] | None" = None,
@@ -1480,6 +2117,9 @@ class Client:
         args: "dict[str, str] | None" = None,
         priority: "int | None" = None,
         client_timeout: "int | None" = None,
+        job_name: "str | None" = None,
+        expected_schema: "pyarrow.Schema | None" = None,
+        ignore_nullability: "bool" = True,
     ) -> "pyarrow.Table":
         """
         Execute a SQL query and return the results as a pyarrow.Table.
@@ -1502,17 +2142,33 @@ class Client:
 
         # efficiently cast the table to a pandas DataFrame
         df = my_table.to_pandas()
+
+        # bind values to :name placeholders, instead of formatting them into the query
+        survivors = client.query(
+            query='SELECT Name FROM bauplan.titanic WHERE Age > :age AND Sex IN :sexes',
+            params={'age': 30, 'sexes': ['male', 'female']},
+        )
+
+        # or to ? placeholders, in order
+        survivors = client.query(
+            query='SELECT Name FROM bauplan.titanic WHERE Age > ? AND Sex = ?',
+            params=[30, 'female'],
+        )
         ```
 
         Parameters:
-            query: The Bauplan query to execute. Column and table names are case-sensitive.
-            ref: The ref, branch name or tag name to query from.
+            query: The Bauplan query to execute.
+            params: Values for the placeholders in the query: a dict for `:name` placeholders, or a list for `?` placeholders, in order. Values may be `None`, `bool`, `int`, `float`, `str`, `bytes`, `datetime` or `date`, or a non-empty list of those for use with `IN`. They're escaped and substituted into the query before it's sent. Raises `ValueError` if a value can't be represented, or doesn't match a placeholder.
+            ref: The ref, branch name or tag name to query from. Defaults to the active branch. Queries can't read a table as of a snapshot, so a ref like `main@4527150312935411733` raises `ValueError`.
             max_rows: The maximum number of rows to return; default: `None` (no limit).
             cache: Whether to enable or disable caching for the query.
             namespace: The Namespace to run the query in. If not set, the query will be run in the default namespace for your account.
             args: Additional arguments to pass to the query (default: None).
             priority: Optional job priority (1-10, where 10 is highest priority).
             client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+            job_name: Optional human-friendly name for the job, sent as the `bauplan.job-name` job arg.
+            expected_schema: If set, raise a `SchemaMismatchError` before fetching any rows if the results don't have this schema.
+            ignore_nullability: Whether to ignore differences in nullability when checking `expected_schema` (default: `True`).
         Returns:
             The query results as a `pyarrow.Table`.
         """
@@ -1522,6 +2178,7 @@ class Client:
         path: "str | pathlib.Path",
         query: "str",
         *,
+        params: "dict[str, typing.Any] | list[typing.Any] | None" = None,
         ref: "str | Ref | None" = None,
         max_rows: "int | None" = None,
         cache: "Literal['on', 'off'] | None" = None,
@@ -1529,39 +2186,52 @@ class Client:
         args: "dict[str, str] | None" = None,
         priority: "int | None" = None,
         client_timeout: "int | None" = None,
-    ) -> "pathlib.Path":
+        overwrite: "bool" = False,
+        mkdir_parents: "bool" = True,
+    ) -> "QueryFileResult":
         """
         Export the results of a SQL query to a file in CSV format.
 
+        The path may contain placeholders, as in `query_to_parquet_file`.
+
         ```python
         import bauplan
         client = bauplan.Client()
 
-        # query the table and iterate through the results one row at a time
+        # query the table and write the results to a csv file
         client.query_to_csv_file(
             path='/tmp/out.csv',
             query='SELECT Name, Age FROM bauplan.titanic LIMIT 100',
             ref='my_ref_or_branch_name',
+            overwrite=True,
         )
         ```
 
         Parameters:
             path: The name or path of the file csv to write the results to.
             query: The Bauplan query to execute. Column and table names are case-sensitive.
-            ref: The ref, branch name or tag name to query from.
+            params: Values for the placeholders in the query, as in `query`.
+            ref: The ref, branch name or tag name to query from. Defaults to the active branch.
             max_rows: The maximum number of rows to return; default: `None` (no limit).
             cache: Whether to enable or disable caching for the query.
             namespace: The Namespace to run the query in. If not set, the query will be run in the default namespace for your account.
             args: Additional arguments to pass to the query (default: None).
             client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+            overwrite: Whether to replace the file if it already exists; default: `False`.
+            mkdir_parents: Whether to create any missing parent directories; default: `True`.
         Returns:
-            The path of the file written.
+            A `QueryFileResult` describing the file written. `str(result)` is the path of the file.
+
+        Raises:
+            `FileExistsError`: if the file exists and `overwrite` is not set.
+            `ValueError`: if the path contains an unknown placeholder.
         """
     def query_to_generator(
         self,
         /,
         query: "str",
         *,
+        params: "dict[str, typing.Any] | list[typing.Any] | None" = None,
         ref: "str | Ref | None" = None,
         max_rows: "int | None" = None,
         cache: "Literal['on', 'off'] | None" = None,
@@ -1569,6 +2239,7 @@ class Client:
         args: "dict[str, str] | None" = None,
         priority: "int | None" = None,
         client_timeout: "int | None" = None,
+        unsafe_full_scan: "bool" = False,
     ) -> "typing.Iterator[dict[str, typing.Any]]":
         """
         Execute a SQL query and return the results as a generator, where each row is
@@ -1590,13 +2261,15 @@ class Client:
 
         Parameters:
             query: The Bauplan query to execute. Column and table names are case-sensitive.
-            ref: The ref, branch name or tag name to query from.
-            max_rows: The maximum number of rows to return; default: `None` (no limit).
+            params: Values for the placeholders in the query, as in `query`.
+            ref: The ref, branch name or tag name to query from. Defaults to the active branch.
+            max_rows: The maximum number of rows to return; default: `None` (100,000 rows, unless `unsafe_full_scan` is set). A `RowLimitWarning` is emitted if the default limit truncates the results.
             cache: Whether to enable or disable caching for the query.
             namespace: The Namespace to run the query in. If not set, the query will be run in the default namespace for your account.
             args: Additional arguments to pass to the query (default: `None`).
             priority: Optional job priority (1-10, where 10 is highest priority).
             client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+            unsafe_full_scan: If `True` and `max_rows` is `None`, return all rows instead of applying the default limit.
 
         Yields:
             A dictionary representing a row of query results.
@@ -1607,6 +2280,7 @@ class Client:
         path: "str | pathlib.Path",
         query: "str",
         *,
+        params: "dict[str, typing.Any] | list[typing.Any] | None" = None,
         file_format: "Literal['json', 'jsonl']" = "json",
         ref: "str | Ref | None" = None,
         max_rows: "int | None" = None,
@@ -1615,15 +2289,19 @@ class Client:
         args: "dict[str, str] | None" = None,
         priority: "int | None" = None,
         client_timeout: "int | None" = None,
-    ) -> "pathlib.Path":
+        overwrite: "bool" = False,
+        mkdir_parents: "bool" = True,
+    ) -> "QueryFileResult":
         """
         Export the results of a SQL query to a file in JSON format.
 
+        The path may contain placeholders, as in `query_to_parquet_file`.
+
         ```python
         import bauplan
         client = bauplan.Client()
 
-        # query the table and iterate through the results one row at a time
+        # query the table and write the results to a json file
         client.query_to_json_file(
             path='/tmp/out.json',
             query='SELECT Name, Age FROM bauplan.titanic LIMIT 100',
@@ -1634,15 +2312,22 @@ class Client:
         Parameters:
             path: The name or path of the file json to write the results to.
             query: The Bauplan query to execute. Column and table names are case-sensitive.
+            params: Values for the placeholders in the query, as in `query`.
             file_format: The format to write the results in; default: `json`. Allowed values are 'json' and 'jsonl'.
-            ref: The ref, branch name or tag name to query from.
+            ref: The ref, branch name or tag name to query from. Defaults to the active branch.
             max_rows: The maximum number of rows to return; default: `None` (no limit).
             cache: Whether to enable or disable caching for the query.
             namespace: The Namespace to run the query in. If not set, the query will be run in the default namespace for your account.
             args: Additional arguments to pass to the query (default: None).
             client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+            overwrite: Whether to replace the file if it already exists; default: `False`.
+            mkdir_parents: Whether to create any missing parent directories; default: `True`.
         Returns:
-            The path of the file written.
+            A `QueryFileResult` describing the file written. `str(result)` is the path of the file.
+
+        Raises:
+            `FileExistsError`: if the file exists and `overwrite` is not set.
+            `ValueError`: if the path contains an unknown placeholder.
         """
     def query_to_parquet_file(
         self,
@@ -1650,6 +2335,7 @@ class Client:
         path: "str | pathlib.Path",
         query: "str",
         *,
+        params: "dict[str, typing.Any] | list[typing.Any] | None" = None,
         ref: "str | Ref | None" = None,
         max_rows: "int | None" = None,
         cache: "Literal['on', 'off'] | None" = None,
@@ -1657,33 +2343,105 @@ class Client:
         args: "dict[str, str] | None" = None,
         priority: "int | None" = None,
         client_timeout: "int | None" = None,
-    ) -> "pathlib.Path":
+        overwrite: "bool" = False,
+        mkdir_parents: "bool" = True,
+    ) -> "QueryFileResult":
         """
         Export the results of a SQL query to a file in Parquet format.
 
+        The path may contain `{job_id}`, `{date}` and `{time}` placeholders,
+        as well as `strftime` specifiers like `%Y`, which are expanded once the
+        query has been submitted.
+
         ```python
         import bauplan
         client = bauplan.Client()
 
-        # query the table and iterate through the results one row at a time
-        client.query_to_parquet_file(
-            path='/tmp/out.parquet',
+        # query the table and write the results to a parquet file
+        result = client.query_to_parquet_file(
+            path='/tmp/out/{job_id}_{date}.parquet',
             query='SELECT Name, Age FROM bauplan.titanic LIMIT 100',
             ref='my_ref_or_branch_name',
         )
+
+        print(result.path, result.rows_written)
         ```
 
         Parameters:
             path: The name or path of the file parquet to write the results to.
             query: The Bauplan query to execute. Column and table names are case-sensitive.
-            ref: The ref, branch name or tag name to query from.
+            params: Values for the placeholders in the query, as in `query`.
+            ref: The ref, branch name or tag name to query from. Defaults to the active branch.
             max_rows: The maximum number of rows to return; default: `None` (no limit).
             cache: Whether to enable or disable caching for the query.
             namespace: The Namespace to run the query in. If not set, the query will be run in the default namespace for your account.
             args: Additional arguments to pass to the query (default: None).
             client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+            overwrite: Whether to replace the file if it already exists; default: `False`.
+            mkdir_parents: Whether to create any missing parent directories; default: `True`.
         Returns:
-            The path of the file written.
+            A `QueryFileResult` describing the file written. `str(result)` is the path of the file.
+
+        Raises:
+            `FileExistsError`: if the file exists and `overwrite` is not set.
+            `ValueError`: if the path contains an unknown placeholder.
+        """
+    def query_to_reader(
+        self,
+        /,
+        query: "str",
+        *,
+        params: "dict[str, typing.Any] | list[typing.Any] | None" = None,
+        ref: "str | Ref | None" = None,
+        max_rows: "int | None" = None,
+        cache: "Literal['on', 'off'] | None" = None,
+        namespace: "str | Namespace | None" = None,
+        args: "dict[str, str] | None" = None,
+        priority: "int | None" = None,
+        client_timeout: "int | None" = None,
+        job_name: "str | None" = None,
+        expected_schema: "pyarrow.Schema | None" = None,
+        ignore_nullability: "bool" = True,
+    ) -> "pyarrow.RecordBatchReader":
+        """
+        Execute a SQL query and return the results as a `pyarrow.RecordBatchReader`,
+        which fetches each batch as it's read rather than collecting them all
+        first. The reader can be passed directly to DuckDB or polars, or to
+        anything else that supports the Arrow C stream interface.
+
+        If the reader is closed or garbage collected before all the batches
+        have been read, the query job is cancelled.
+
+        ```python
+        import bauplan
+        import duckdb
+
+        client = bauplan.Client()
+
+        reader = client.query_to_reader(
+            query='SELECT Name, Age FROM bauplan.titanic',
+            ref='my_ref_or_branch_name',
+        )
+
+        duckdb.sql('SELECT avg(Age) FROM reader').show()
+        ```
+
+        Parameters:
+            query: The Bauplan query to execute.
+            params: Values for the placeholders in the query, as in `query`.
+            ref: The ref, branch name or tag name to query from. Defaults to the active branch.
+            max_rows: The maximum number of rows to return; default: `None` (no limit).
+            cache: Whether to enable or disable caching for the query.
+            namespace: The Namespace to run the query in. If not set, the query will be run in the default namespace for your account.
+            args: Additional arguments to pass to the query (default: None).
+            priority: Optional job priority (1-10, where 10 is highest priority).
+            client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+            job_name: Optional human-friendly name for the job, sent as the `bauplan.job-name` job arg.
+            expected_schema: If set, raise a `SchemaMismatchError` before fetching any rows if the results don't have this schema.
+            ignore_nullability: Whether to ignore differences in nullability when checking `expected_schema` (default: `True`).
+        Returns:
+            The query results as a `pyarrow.RecordBatchReader`. Errors while
+            fetching results are raised from `read_next_batch()`.
         """
     def rename_branch(
         self, /, branch: "str | Branch", new_branch: "str | Branch"
@@ -1758,10 +2516,11 @@ class Client:
         *,
         namespace: "str | Namespace | None" = None,
         source_ref: "str | Ref",
-        into_branch: "str | Branch",
+        into_branch: "str | Branch | None" = None,
         replace: "bool | None" = None,
         commit_body: "str | None" = None,
         commit_properties: "dict[str, str] | None" = None,
+        request_timeout: "float | None" = None,
     ) -> "Branch":
         """
         Revert a table to a previous state.
@@ -1784,10 +2543,11 @@ class Client:
             table: The table to revert.
             namespace: The namespace of the table to revert.
             source_ref: The name of the source ref; either a branch like "main" or ref like "main@[sha]".
-            into_branch: The name of the target branch where the table will be reverted.
+            into_branch: The name of the target branch where the table will be reverted. Defaults to the active branch.
             replace: Optional, whether to replace the table if it already exists.
             commit_body: Optional, the commit body message to attach to the operation.
             commit_properties: Optional, a list of properties to attach to the operation.
+            request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
         Returns:
             The `bauplan.schema.Branch` where the revert was made.
 
@@ -1811,8 +2571,9 @@ class Client:
     def run(
         self,
         /,
-        project_dir: "str",
+        project_dir: "str | None" = None,
         *,
+        project: "dict[str, str | bytes] | None" = None,
         ref: "str | Ref | None" = None,
         namespace: "str | Namespace | None" = None,
         parameters: "dict[str, str | int | float | bool | None] | None" = None,
@@ -1825,6 +2586,10 @@ class Client:
         priority: "int | None" = None,
         client_timeout: "int | None" = None,
         detach: "bool" = False,
+        job_name: "str | None" = None,
+        preflight: "bool" = True,
+        fail_on_empty_models: "bool | list[str] | None" = None,
+        events_out: "str | pathlib.Path | None" = None,
     ) -> "RunState":
         """
         Run a Bauplan project and return the state of the run. This is the equivalent of
@@ -1845,9 +2610,83 @@ class Client:
             raise Exception(f"{run_state.job_id} failed: {run_state.job_status} - {run_state.error}")
         ```
 
+        A project can also be passed in memory, as a dict of file names to
+        their contents, instead of a directory:
+
+        ```python
+        #! client = bauplan.Client()
+        run_state = client.run(
+            project={
+                "bauplan_project.yml": open("bauplan_project.yml").read(),
+                "trips_clean.sql": "SELECT * FROM taxi_fhvhv",
+            },
+            ref="username.dev_branch",
+        )
+        ```
+
         Parameters:
-            project_dir: The directory of the project (where the `bauplan_project.yml` or `bauplan_project.yaml` file is located).
-            ref: The ref, branch name or tag name from which to run the project.
+            project_dir: The directory of the project (where the `bauplan_project.yml` or `bauplan_project.yaml` file is located). Exactly one of `project_dir` and `project` must be set.
+            project: The files of the project, keyed by their path relative to the project directory, as text or bytes. It must include a `bauplan_project.yml` or `bauplan_project.yaml` file. Exactly one of `project_dir` and `project` must be set.
+            ref: The ref, branch name or tag name from which to run the project. Defaults to the active branch.
+            namespace: The Namespace to run the job in. If not set, the job will be run in the default namespace.
+            parameters: Parameters for templating into SQL or Python models.
+            cache: Whether to enable or disable caching for the run. Defaults to 'on'.
+            transaction: Whether to enable or disable transaction mode for the run. Defaults to 'on'.
+            dry_run: Whether to enable or disable dry-run mode for the run; models are not materialized.
+            strict: Whether to enable or disable strict schema validation.
+            preview: Whether to enable or disable preview mode for the run.
+            args: Additional arguments (optional).
+            priority: Optional job priority (1-10, where 10 is highest priority).
+            client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+            detach: Whether to detach the run and return immediately instead of blocking on log streaming.
+            job_name: Optional human-friendly name for the job, sent as the `bauplan.job-name` job arg.
+            preflight: Whether to check that `namespace` exists on the ref before submitting the run. Defaults to `True`.
+            fail_on_empty_models: Model names, or `True` for all models, to fail the run if they write no rows. This turns on previews, which are used to count the rows. With transactions on, the job is cancelled as soon as an empty model is found, so that nothing is merged; without them, or if the job finishes first, the output is still written to the ref. The empty models are listed in `RunState.empty_models`.
+            events_out: Optional path to record the raw events of the job to, for debugging. The file can be replayed with `bauplan job replay-events`. Can't be used with `detach`.
+        Returns:
+            `bauplan.state.RunState`: The state of the run.
+        """
+    def run_from_snapshot(
+        self,
+        /,
+        snapshot: "str",
+        *,
+        ref: "str | Ref | None" = None,
+        namespace: "str | Namespace | None" = None,
+        parameters: "dict[str, str | int | float | bool | None] | None" = None,
+        cache: "Literal['on', 'off'] | None" = None,
+        transaction: "Literal['on', 'off'] | None" = None,
+        dry_run: "bool | None" = None,
+        strict: "Literal['on', 'off'] | None" = None,
+        preview: "str | None" = None,
+        args: "dict[str, str] | None" = None,
+        priority: "int | None" = None,
+        client_timeout: "int | None" = None,
+        detach: "bool" = False,
+    ) -> "RunState":
+        """
+        Rerun the code from an earlier run, without a local checkout of the
+        project. The snapshot is fetched from the server, and parameters are
+        resolved against the project file it contains.
+
+        The snapshot is uploaded again as part of the run. A rerun that only
+        references existing code has to name the job that ran it rather than
+        the snapshot, and can't take new parameters.
+
+        ```python
+        #! client = bauplan.Client()
+        state = client.run(project_dir='./etl_pipelines/daily_sales')
+
+        # Later, run the exact same code against another branch.
+        rerun_state = client.run_from_snapshot(
+            state.ctx.snapshot_id,
+            ref='username.dev_branch',
+        )
+        ```
+
+        Parameters:
+            snapshot: The `snapshot_id` or `snapshot_uri` of a previous run, as found on `bauplan.state.RunExecutionContext`.
+            ref: The ref, branch name or tag name from which to run the project. Defaults to the active branch.
             namespace: The Namespace to run the job in. If not set, the job will be run in the default namespace.
             parameters: Parameters for templating into SQL or Python models.
             cache: Whether to enable or disable caching for the run. Defaults to 'on'.
@@ -1869,20 +2708,26 @@ class Client:
         *,
         ref: "str | Ref | None" = None,
         columns: "list[str] | None" = None,
-        filters: "str | None" = None,
+        filters: "str | list[tuple[str, str, typing.Any]] | list[list[tuple[str, str, typing.Any]]] | None" = None,
         limit: "int | None" = None,
         cache: "Literal['on', 'off'] | None" = None,
         namespace: "str | Namespace | None" = None,
         args: "dict[str, str] | None" = None,
         priority: "int | None" = None,
         client_timeout: "int | None" = None,
+        unsafe_full_scan: "bool" = False,
+        expected_schema: "pyarrow.Schema | None" = None,
+        ignore_nullability: "bool" = True,
     ) -> "pyarrow.Table":
         """
         Execute a table scan (with optional filters) and return the results as an arrow Table.
 
-        Note that this function uses SQLGlot to compose a safe SQL query,
-        and then internally defer to the `query` function for the actual
-        scan.
+        Column and table names are always quoted. `filters` is either a SQL
+        expression, which is checked to be a single expression before any job
+        is submitted, or a list of `(column, op, value)` tuples like pyarrow's
+        dataset filters: a flat list is ANDed together, and a list of lists is
+        an OR of ANDs. The supported ops are `=`, `==`, `!=`, `<>`, `<`, `<=`,
+        `>`, `>=`, `in` and `not in`. The query is then run with `query`.
         ```python
         import bauplan
         client = bauplan.Client()
@@ -1896,22 +2741,307 @@ class Client:
             columns=['Name'],
             filters='Age < 30',
         )
+
+        # or as (column, op, value) tuples
+        my_table = client.scan(
+            table='titanic',
+            namespace='bauplan',
+            columns=['Name'],
+            filters=[('Age', '<', 30), ('Sex', '=', 'female')],
+        )
         ```
 
         Parameters:
             table: The table to scan.
-            ref: The ref, branch name or tag name to scan from.
+            ref: The ref, branch name or tag name to scan from. Defaults to the active branch.
             columns: The columns to return (default: `None`).
-            filters: The filters to apply (default: `None`).
-            limit: The maximum number of rows to return (default: `None`).
+            filters: A SQL expression or a list of `(column, op, value)` tuples to filter rows by (default: `None`). Raises `ValueError` if it's invalid.
+            limit: The maximum number of rows to return (default: `None`, which returns at most 1,000,000 rows unless `unsafe_full_scan` is set). A `RowLimitWarning` is emitted if the default limit truncates the results.
             cache: Whether to enable or disable caching for the query.
             namespace: The Namespace to run the scan in. If not set, the scan will be run in the default namespace for your account.
             args: dict of arbitrary args to pass to the backend.
             priority: Optional job priority (1-10, where 10 is highest priority).
             client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+            unsafe_full_scan: If `True` and `limit` is `None`, return the whole table instead of applying the default limit.
+            expected_schema: If set, raise a `SchemaMismatchError` before fetching any rows if the results don't have this schema.
+            ignore_nullability: Whether to ignore differences in nullability when checking `expected_schema` (default: `True`).
         Returns:
             The scan results as a `pyarrow.Table`.
         """
+    def stream_job_logs(
+        self,
+        /,
+        job: str | Job,
+        *,
+        include_system: "bool" = False,
+        since: "datetime | None" = None,
+    ) -> "typing.Iterator[JobLogEvent | JobLifecycleEvent]":
+        """
+        EXPERIMENTAL: Stream the logs for a job as they're emitted, until it
+        completes.
+
+        Alongside `bauplan.schema.JobLogEvent` objects, the iterator yields a
+        `bauplan.schema.JobLifecycleEvent` as each task starts and completes,
+        and a final one when the job completes. If the connection drops, it's
+        reopened automatically. Closing the iterator stops streaming, but
+        doesn't cancel the job.
+
+        ```python
+        #! my_job: bauplan.schema.Job = ...  # type: ignore[assignment]
+        import bauplan
+        client = bauplan.Client()
+
+        for event in client.stream_job_logs(my_job.id):
+            if isinstance(event, bauplan.schema.JobLogEvent):
+                print(f"[{event.level}] {event.message}")
+        ```
+
+        Parameters:
+            job: Union[str, Job]: A job ID or a Job instance.
+            include_system: Whether to include system logs, in addition to logs from user code.
+            since: Optional, skip log events emitted before this datetime.
+        Returns:
+            An iterator over `bauplan.schema.JobLogEvent` and `bauplan.schema.JobLifecycleEvent` objects.
+        """
+    def sync_tables(
+        self,
+        /,
+        ref: "str | Ref | None" = None,
+        *,
+        state_file: "str | pathlib.Path",
+    ) -> "TableSync":
+        """
+        Get the tables added, removed or changed on a ref since the last sync,
+        for mirroring the catalog into another system.
+
+        The tables as of each sync are recorded in `state_file`, a small JSON
+        file; if it doesn't exist yet, every table is reported as added. The
+        file is only updated when `commit()` is called on the result, so if
+        processing the changes fails, the next sync reports them again.
+
+        ```python
+        import bauplan
+        client = bauplan.Client()
+
+        sync = client.sync_tables('main', state_file='catalog_sync.json')
+        for table in sync.added + sync.changed:
+            ...  # upsert into the external catalog
+        for name in sync.removed:
+            ...  # remove from the external catalog
+        sync.commit()
+        ```
+
+        Parameters:
+            ref: The ref or branch to sync. Defaults to the active branch. The state file records the ref as given, and can't be reused for a different one.
+            state_file: The path of the file recording the tables as of the last sync.
+        Returns:
+            A `bauplan.schema.TableSync` object.
+
+        Raises:
+            `bauplan.exceptions.RefNotFoundError`: if the ref does not exist.
+            `bauplan.exceptions.InvalidRefError`: if the ref format is invalid.
+            `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+            `bauplan.exceptions.BauplanError`: if the state file is invalid or belongs to a different ref.
+        """
+    def wait_for_job(
+        self,
+        /,
+        job: "str | Job",
+        *,
+        client_timeout: "int | None" = None,
+    ) -> "RunState":
+        """
+        Wait for a job to finish, and return its state as a `RunState`, as if
+        it had been run without `detach`. If the job has already finished, its
+        state is rebuilt from its stored record and logs.
+
+        The parameters a run was submitted with aren't stored with the job, so
+        the returned state's `ctx` is empty; to keep them, use `attach` on the
+        `RunState` returned by `run(..., detach=True)` instead.
+
+        ```python
+        import bauplan
+        client = bauplan.Client()
+        state = client.run(project_dir='./my_pipeline', detach=True)
+
+        # Later, possibly from another process.
+        result = client.wait_for_job(state.job_id)
+        print(result.job_status, result.duration)
+        ```
+
+        Parameters:
+            job: A job ID or a Job instance.
+            client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+        Returns:
+            `bauplan.state.RunState`: The state of the job.
+        """
+    def wait_for_table(
+        self,
+        /,
+        table: "str | Table",
+        ref: "str | Ref | None" = None,
+        timeout: "float" = 600.0,
+        *,
+        namespace: "str | Namespace | None" = None,
+        min_records: "int | None" = None,
+        after_snapshot_id: "int | None" = None,
+        predicate: "typing.Callable[[Table], bool] | None" = None,
+        poll_interval: "float" = 5.0,
+    ) -> "Table":
+        """
+        Wait for a table to exist, and optionally to meet some conditions.
+
+        If `ref` is a branch, it's polled with conditional requests, and the
+        table is only fetched again when the branch moves, so polling is
+        cheap. Polls that fail with a transient error (a network failure, a
+        rate limit or a server error) are retried with backoff.
+
+        ```python
+        import bauplan
+        client = bauplan.Client()
+
+        # wait up to ten minutes for an upstream import to land some rows
+        table = client.wait_for_table(
+            table='titanic',
+            ref='my_branch_name',
+            timeout=600,
+            min_records=1,
+        )
+        ```
+
+        Parameters:
+            table: The table to wait for.
+            ref: The ref, branch name or tag name to poll the table on. Defaults to the active branch.
+            timeout: Seconds to wait before giving up.
+            namespace: The namespace of the table.
+            min_records: Optional, the fewest records the table must have.
+            after_snapshot_id: Optional, a snapshot ID that the table must have moved on from, like its `current_snapshot_id` before an import.
+            predicate: Optional, a callable that takes the `Table` and returns `True` once it's ready. It's called each time the table is fetched, once the other conditions are met.
+            poll_interval: Seconds between polls. Each delay is jittered by up to 20%.
+        Returns:
+            The `bauplan.schema.Table`, once it's ready.
+
+        Raises:
+            `TimeoutError`: if the table isn't ready before the timeout. Its `table` attribute holds the table as last seen, or `None` if it didn't exist.
+            `bauplan.exceptions.RefNotFoundError`: if the ref does not exist.
+            `bauplan.exceptions.InvalidRefError`: if the ref format is invalid.
+            `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+            `ValueError`: if one or more parameters are invalid.
+        """
+    def watch_ref(
+        self,
+        /,
+        ref: "str | Branch",
+        *,
+        poll_interval: "float" = 15.0,
+        timeout: "float | None" = None,
+    ) -> "typing.Iterator[str]":
+        """
+        Watch a branch, yielding its new head hash each time it moves.
+
+        The branch is polled with conditional requests, so a poll that finds
+        the head unchanged is cheap. Polls that fail with a transient error
+        (a network failure, a rate limit or a server error) are retried with
+        backoff; other errors are raised from the iterator.
+
+        ```python
+        import bauplan
+        client = bauplan.Client()
+
+        for head in client.watch_ref('main', poll_interval=30):
+            print(f"main moved to {head}")
+        ```
+
+        Parameters:
+            ref: The branch to watch.
+            poll_interval: Seconds between polls. Each delay is jittered by up to 20%.
+            timeout: Optional, seconds after which the iterator stops. By default, it never stops.
+        Returns:
+            An iterator over the new head hashes of the branch.
+
+        Raises:
+            `bauplan.exceptions.BranchNotFoundError`: if the branch does not exist, or is deleted while being watched.
+            `bauplan.exceptions.NotABranchRefError`: if the object is not a branch.
+            `bauplan.exceptions.ForbiddenError`: if the user does not have access to the branch.
+            `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+            `ValueError`: if one or more parameters are invalid.
+        """
+    def whoami(self, /, *, client_timeout: "int | None" = None) -> "UserInfo":
+        """
+        Fetch the user and organization that the API key belongs to.
+
+        ```python
+        import bauplan
+        client = bauplan.Client()
+
+        user = client.whoami()
+        print(f"{user.username} ({user.organization_name})")
+        ```
+
+        Parameters:
+            client_timeout: timeout in seconds.
+
+        Returns:
+            A `bauplan.UserInfo` object.
+        """
+    def write_table(
+        self,
+        /,
+        table: "str",
+        data: "pyarrow.Table | pyarrow.RecordBatchReader | typing.Any",
+        branch: "str | Branch | None" = None,
+        *,
+        namespace: "str | Namespace | None" = None,
+        mode: "Literal['append', 'overwrite']" = "append",
+        staging_uri: "str",
+        args: "dict[str, str] | None" = None,
+        priority: "int | None" = None,
+        client_timeout: "int | None" = None,
+    ) -> "WriteTableResult":
+        """
+        Write Arrow data to a table, creating the table if it doesn't exist.
+
+        `data` can be anything that implements the Arrow PyCapsule stream
+        interface, like a `pyarrow.Table`, a `pyarrow.RecordBatchReader` or a
+        `polars.DataFrame`. The rows are staged as a Parquet file under
+        `staging_uri`, using the AWS credentials from the environment, and
+        then imported into the table. The staged file isn't deleted
+        afterwards, so consider a prefix with an expiration policy.
+
+        ```python
+        import bauplan
+        import pyarrow as pa
+
+        client = bauplan.Client()
+
+        result = client.write_table(
+            'my_table_name',
+            pa.table({'id': [1, 2, 3], 'name': ['a', 'b', 'c']}),
+            branch='my_branch_name',
+            staging_uri='s3://my-bucket/staging/',
+        )
+        print(f"Wrote {result.rows_written} rows at {result.ref}")
+        ```
+
+        Parameters:
+            table: The table to write to.
+            data: The rows to write.
+            branch: The branch to write to. Defaults to the active branch.
+            namespace: The namespace of the table.
+            mode: `'append'` to add the rows to the table, or `'overwrite'` to replace the table with them.
+            staging_uri: An `s3://` prefix to stage the rows under, which Bauplan can read from.
+            args: dict of arbitrary args to pass to the backend.
+            priority: Optional job priority (1-10, where 10 is highest priority).
+            client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+        Returns:
+            A `bauplan.WriteTableResult` with the new head of the branch and the number of rows written.
+
+        Raises:
+            `bauplan.exceptions.InvalidDataError`: if the data doesn't match the schema of the table it's appended to.
+            `bauplan.exceptions.TableCreatePlanStatusError`: if the table can't be created from the data.
+            `bauplan.exceptions.BauplanJobError`: if the import fails.
+            `ValueError`: if `mode` or `staging_uri` is invalid.
+        """
 
 @final
 class InfoState:
@@ -1939,6 +3069,76 @@ class OrganizationInfo:
     @property
     def slug(self, /) -> str: ...
 
+@final
+class QueryFileResult:
+    """
+    The file written by one of the `query_to_*_file` methods. Converting it
+    with `str()` or `os.fspath()` gives the path of the file.
+    """
+    def __fspath__(self, /) -> str: ...
+    def __repr__(self, /) -> str: ...
+    def __str__(self, /) -> str: ...
+    @property
+    def bytes_written(self, /) -> int:
+        """
+        The size of the file, in bytes.
+        """
+    @property
+    def job_id(self, /) -> str:
+        """
+        The ID of the query job.
+        """
+    @property
+    def path(self, /) -> pathlib.Path:
+        """
+        The path of the file, with any placeholders expanded.
+        """
+    @property
+    def rows_written(self, /) -> int:
+        """
+        The number of rows written.
+        """
+
+@final
+class QueryResultsEndpoint:
+    """
+    Where to fetch the results of a finished query over Arrow Flight, from
+    `Client.get_query_endpoint`. Pass it to `bauplan.fetch_query_results`,
+    which doesn't need an API key, to fetch the results in another process.
+
+    The token grants access to the results until they expire, to anyone who
+    has it. Treat it like an API key: it's redacted from the `repr`, but
+    `token` returns it as-is.
+    """
+    def __new__(
+        cls,
+        /,
+        endpoint: "str",
+        token: "str",
+        expires_at: "datetime | None" = None,
+    ) -> QueryResultsEndpoint: ...
+    def __repr__(self, /) -> str: ...
+    @property
+    def endpoint(self, /) -> str:
+        """
+        The address of the flight server.
+        """
+    @property
+    def expired(self, /) -> bool:
+        """
+        Whether the results have expired.
+        """
+    @property
+    def expires_at(self, /) -> datetime | None:
+        """
+        When the results stop being available, if known.
+        """
+    @property
+    def token(self, /) -> str:
+        """
+        The token to authenticate with.
+        """
+
 @final
 class RunnerNodeInfo:
     def __repr__(self, /) -> str: ...
@@ -1949,6 +3149,11 @@ class RunnerNodeInfo:
 class UserInfo:
     def __repr__(self, /) -> str: ...
     @property
+    def branch_prefix(self, /) -> str:
+        """
+        The prefix of the user's branches, e.g. `alice.`.
+        """
+    @property
     def first_name(self, /) -> str: ...
     @property
     def full_name(self, /) -> str: ...
@@ -1957,4 +3162,100 @@ class UserInfo:
     @property
     def last_name(self, /) -> str: ...
     @property
+    def organization_id(self, /) -> str | None: ...
+    @property
+    def organization_name(self, /) -> str | None: ...
+    @property
     def username(self, /) -> str: ...
+
+@final
+class WriteTableResult:
+    """
+    The result of `Client.write_table`.
+    """
+    def __repr__(self, /) -> str: ...
+    @property
+    def job_id(self, /) -> str:
+        """
+        The ID of the import job.
+        """
+    @property
+    def ref(self, /) -> Branch:
+        """
+        The branch, at the commit with the new rows.
+        """
+    @property
+    def rows_written(self, /) -> int:
+        """
+        The number of rows written.
+        """
+    @property
+    def staged_uri(self, /) -> str:
+        """
+        The URI of the Parquet file the rows were staged in.
+        """
+    @property
+    def table(self, /) -> Table:
+        """
+        The table, as of that commit.
+        """
+
+def enable_logging(level: "str | int" = "INFO") -> None:
+    """
+    Forward the client's internal log events, such as job progress, to the
+    standard `logging` module, under the "bauplan" logger. Fields like
+    `job_id` are appended to the message, and set as attributes on the
+    records for formatters and filters to use.
+
+    This is off by default. Once enabled, every event at or above `level` is
+    formatted and queued, even if the logger then discards it, and a
+    background thread takes the GIL to log them. Keep `level` at "INFO" or
+    above unless debugging. Call this again to change the level, or pass
+    "OFF" to stop forwarding.
+
+    ```python
+    import logging
+    import bauplan
+
+    logging.basicConfig(format="%(levelname)s %(name)s: %(message)s")
+    bauplan.enable_logging("INFO")
+    ```
+
+    Parameters:
+        level: The lowest level to forward, as a name like "DEBUG" or a `logging` level like `logging.DEBUG`.
+
+    Raises:
+        `ValueError`: if the level is invalid.
+        `RuntimeError`: if another `tracing` subscriber was already installed.
+    """
+
+def fetch_query_results(
+    endpoint: "QueryResultsEndpoint",
+    *,
+    max_rows: "int | None" = None,
+    timeout: "int" = 30,
+) -> "pyarrow.Table":
+    """
+    Fetch the results of a query from an endpoint returned by
+    `Client.get_query_endpoint`. This doesn't need an API key, so it can be
+    called from a process that only has the endpoint.
+
+    ```python
+    #! endpoint: bauplan.QueryResultsEndpoint = ...  # type: ignore[assignment]
+    import bauplan
+
+    table = bauplan.fetch_query_results(endpoint)
+    ```
+
+    Parameters:
+        endpoint: The endpoint to fetch the results from.
+        max_rows: The maximum number of rows to return; default: `None` (no limit).
+        timeout: seconds to wait for the results. Defaults to 30 seconds.
+
+    Returns:
+        The results, as a `pyarrow.Table`.
+
+    Raises:
+        `bauplan.exceptions.BauplanError`: if the results have expired.
+        `bauplan.exceptions.BauplanQueryError`: if the results can't be fetched.
+    """
@@ -90,11 +90,17 @@ class ApiErrorKind:
 
     @final
     class MergeConflict(ApiErrorKind):
-        __match_args__: Final = ("source_ref", "destination_ref")
+        __match_args__: Final = ("source_ref", "destination_ref", "conflicts")
         def __new__(
-            cls, /, source_ref: Ref, destination_ref: Ref
+            cls,
+            /,
+            source_ref: Ref,
+            destination_ref: Ref,
+            conflicts: list[MergeConflictTable],
         ) -> ApiErrorKind.MergeConflict: ...
         @property
+        def conflicts(self, /) -> list[MergeConflictTable]: ...
+        @property
         def destination_ref(self, /) -> Ref: ...
         @property
         def source_ref(self, /) -> Ref: ...
@@ -295,6 +301,29 @@ class BauplanHTTPError(BauplanError):
     @property
     def type(self, /) -> str: ...
 
+@final
+class MergeConflictTable:
+    """
+    A table changed on both sides of a merge, listed in a `MERGE_CONFLICT` error.
+    """
+    def __repr__(self, /) -> str: ...
+    @property
+    def destination_hash(self, /) -> str | None:
+        """
+        The hash of the last commit to change the table on the destination
+        branch.
+        """
+    @property
+    def source_hash(self, /) -> str | None:
+        """
+        The hash of the last commit to change the table on the source ref.
+        """
+    @property
+    def table_name(self, /) -> str:
+        """
+        The fully-qualified table name.
+        """
+
 @final
 class TableCreatePlanApplyStatusError(BauplanError):
     """
@@ -450,6 +479,9 @@ class BranchHeadChangedError(UpdateConflictError):
 class MergeConflictError(UpdateConflictError):
     """Raised when a merge cannot be completed due to conflicting changes."""
 
+    conflicts: list[MergeConflictTable]
+    """The tables changed on both sides of the merge."""
+
 class NamespaceIsNotEmptyError(UpdateConflictError):
     """Raised when attempting to delete a `bauplan.schema.Namespace` that still contains tables."""
 
@@ -460,8 +492,56 @@ class RevertIdenticalTableError(UpdateConflictError):
     """Raised when the source and destination of a revert point to the same table snapshot."""
 
 # 429 Too Many Requests
+@final
 class TooManyRequestsError(BauplanHTTPError):
-    """Raised on an HTTP 429 response from the API."""
+    """
+    Raised on an HTTP 429 response from the API, once the client has spent its
+    `rate_limit_budget` retrying.
+    """
+    def __new__(
+        cls,
+        /,
+        code: int,
+        type: str,
+        message: str,
+        kind: ApiErrorKind | None = None,
+        retries: int = 0,
+        waited: float = 0.0,
+        retry_after: float | None = None,
+        limit: int | None = None,
+        remaining: int | None = None,
+        reset: float | None = None,
+    ) -> TooManyRequestsError: ...
+    @property
+    def limit(self, /) -> int | None:
+        """
+        The number of requests allowed per window, if the API said.
+        """
+    @property
+    def remaining(self, /) -> int | None:
+        """
+        The number of requests left in the window, if the API said.
+        """
+    @property
+    def reset(self, /) -> float | None:
+        """
+        The number of seconds until the window resets, if the API said.
+        """
+    @property
+    def retries(self, /) -> int:
+        """
+        The number of times requests were retried.
+        """
+    @property
+    def retry_after(self, /) -> float | None:
+        """
+        The number of seconds the API asked the client to wait, if it said.
+        """
+    @property
+    def waited(self, /) -> float:
+        """
+        The total number of seconds spent waiting to retry.
+        """
 
 # 5xx Server Errors
 class InternalError(BauplanHTTPError):
@@ -486,5 +566,14 @@ class BauplanQueryError(BauplanJobError):
 class NoResultsFoundError(BauplanError):
     """Raised when a query returns no results."""
 
+class RequestTimeoutError(BauplanError):
+    """Raised when a catalog request doesn't complete within the client's `request_timeout`."""
+
+class RowLimitWarning(UserWarning):
+    """Emitted when results are truncated to a default row limit."""
+
+class SchemaMismatchError(BauplanError):
+    """Raised when query results don't match the expected schema."""
+
 class InvalidPlanError(BauplanError):
     """Raised when a pipeline or table-create plan is invalid."""
@@ -24,6 +24,7 @@ class Branch(Ref):
     """
     A data branch, used to isolate data changes before merging into main.
     """
+    def __new__(cls, /, name: str, hash: str | None = None) -> Branch: ...
 
 @final
 class Commit:
@@ -119,6 +120,17 @@ class DAGNode:
     """
     A node in the job DAG (a model).
     """
+    def __repr__(self, /) -> str: ...
+    @property
+    def ended_at(self, /) -> datetime | None:
+        """
+        When the model's task finished.
+        """
+    @property
+    def error_message(self, /) -> str | None:
+        """
+        Why the model failed or was cancelled, if it was.
+        """
     @property
     def id(self, /) -> str:
         """The unique identifier for this node (model)."""
@@ -127,12 +139,86 @@ class DAGNode:
     def name(self, /) -> str:
         """The model name."""
         ...
+    @property
+    def started_at(self, /) -> datetime | None:
+        """
+        When the model's task started.
+        """
+    @property
+    def state(self, /) -> str | None:
+        """
+        The state of the model's task: "not_started", "running", "success", "failed", "skipped" or "cancelled". `None` if the job's events weren't fetched.
+        """
+
+@final
+class ColumnDiff:
+    """
+    A column that differs between two refs, matched by name. A renamed
+    column shows up as one removed and one added.
+    """
+    def __repr__(self, /) -> str: ...
+    @property
+    def from_required(self, /) -> bool | None:
+        """
+        Whether the column is required in the first ref, if it's there.
+        """
+    @property
+    def from_type(self, /) -> str | None:
+        """
+        The column type in the first ref, if it's there.
+        """
+    @property
+    def kind(self, /) -> DiffKind:
+        """
+        How the column differs.
+        """
+    @property
+    def name(self, /) -> str:
+        """
+        The column name.
+        """
+    @property
+    def to_required(self, /) -> bool | None:
+        """
+        Whether the column is required in the second ref, if it's there.
+        """
+    @property
+    def to_type(self, /) -> str | None:
+        """
+        The column type in the second ref, if it's there.
+        """
+
+@final
+class DiffKind:
+    """
+    Whether a column or partition field was added, removed or changed
+    between two refs.
+    """
+
+    Added: Final[DiffKind]
+    """
+    Only in the second ref.
+    """
+    Changed: Final[DiffKind]
+    """
+    In both refs, but different.
+    """
+    Removed: Final[DiffKind]
+    """
+    Only in the first ref.
+    """
+    def __eq__(self, /, other: object) -> bool: ...
+    def __int__(self, /) -> int: ...
+    def __ne__(self, /, other: object) -> bool: ...
+    def __repr__(self, /) -> str: ...
+    def __str__(self, /) -> str: ...
 
 @final
 class DetachedRef(Ref):
     """
     A ref not attached to a branch or tag, pointing directly to a commit hash.
     """
+    def __new__(cls, /, hash: str) -> DetachedRef: ...
 
 @final
 class Job:
@@ -166,6 +252,18 @@ class Job:
         The type of job (query, run, import, etc.).
         """
     @property
+    def project_id(self, /) -> str | None:
+        """
+        The ID of the project the job ran. Only set when listing jobs
+        filtered by project.
+        """
+    @property
+    def project_name(self, /) -> str | None:
+        """
+        The name of the project the job ran. Only set when listing jobs
+        filtered by project.
+        """
+    @property
     def runner(self, /) -> str:
         """
         The runner instance assigned to execute this job.
@@ -239,6 +337,60 @@ class JobKind:
     def __repr__(self, /) -> str: ...
     def __str__(self, /) -> str: ...
 
+@final
+class JobDAG:
+    """
+    The models of a job and the dataflow between them, with the state of
+    each model. Jobs that predate DAG tracking have no nodes or edges.
+    """
+    def __repr__(self, /) -> str: ...
+    def to_dot(self, /) -> str:
+        """
+        Renders the DAG in Graphviz's DOT language. Nodes are labelled with
+        the model name and state.
+
+        Returns:
+            The DAG as a DOT `digraph`.
+        """
+    @property
+    def edges(self, /) -> list[DAGEdge]:
+        """
+        The dependencies between models.
+        """
+    @property
+    def job_id(self, /) -> str:
+        """
+        The ID of the job.
+        """
+    @property
+    def nodes(self, /) -> list[DAGNode]:
+        """
+        The models in the job.
+        """
+
+@final
+class JobLifecycleEvent:
+    """
+    A change in a job's progress, yielded by `Client.stream_job_logs` alongside its log events.
+    """
+    def __repr__(self, /) -> str: ...
+    @property
+    def kind(self, /) -> str:
+        """
+        The kind of event: "task_start", "task_complete", or "job_complete".
+        """
+    @property
+    def status(self, /) -> str | None:
+        """
+        The final status of the job (e.g. "SUCCESS" or "FAILED"), for
+        "job_complete" events.
+        """
+    @property
+    def task(self, /) -> str | None:
+        """
+        The name of the task, for task events.
+        """
+
 @final
 class JobLogEvent:
     """
@@ -321,6 +473,33 @@ class Namespace:
         The namespace name.
         """
 
+@final
+class PartitionDiff:
+    """
+    A partition field that differs between two refs, matched by name.
+    """
+    def __repr__(self, /) -> str: ...
+    @property
+    def from_transform(self, /) -> str | None:
+        """
+        The partition transform in the first ref, if it's there.
+        """
+    @property
+    def kind(self, /) -> DiffKind:
+        """
+        How the partition field differs.
+        """
+    @property
+    def name(self, /) -> str:
+        """
+        The partition field name.
+        """
+    @property
+    def to_transform(self, /) -> str | None:
+        """
+        The partition transform in the second ref, if it's there.
+        """
+
 @final
 class PartitionField:
     """
@@ -341,12 +520,21 @@ class PartitionField:
 class Ref:
     """
     A reference to a branch, tag, or commit, as returned by API operations.
+
+    Refs can also be constructed directly, to pass to methods that take a ref
+    without looking it up first.
     """
+    def __new__(
+        cls, /, name: str, hash: str | None = None, *, type: RefType
+    ) -> Ref: ...
+    def __eq__(self, /, other: object) -> bool: ...
+    def __hash__(self, /) -> int: ...
+    def __ne__(self, /, other: object) -> bool: ...
     def __repr__(self, /) -> str: ...
     def __str__(self, /) -> str: ...
     @property
-    def hash(self, /) -> str:
-        """The hash of the branch or tag."""
+    def hash(self, /) -> str | None:
+        """The hash of the branch or tag, if known."""
         ...
     @property
     def name(self, /) -> str:
@@ -357,6 +545,44 @@ class Ref:
         """The type of the ref, either 'BRANCH', 'TAG', or 'DETACHED'."""
         ...
 
+@final
+class RefSummary:
+    """
+    Aggregate statistics over the tables in a ref.
+    """
+    def __repr__(self, /) -> str: ...
+    @property
+    def last_updated_at(self, /) -> datetime | None:
+        """
+        The most recent update to any table, if there are any tables.
+        """
+    @property
+    def ref(self, /) -> str:
+        """
+        The ref that was summarized.
+        """
+    @property
+    def tables(self, /) -> int:
+        """
+        The number of tables.
+        """
+    def to_dict(self, /) -> dict:
+        """
+        Returns the summary as a dict.
+        """
+    @property
+    def total_records(self, /) -> int:
+        """
+        The total number of records across all tables. Tables that don't
+        report a record count are skipped.
+        """
+    @property
+    def total_size(self, /) -> int:
+        """
+        The total size of all tables, in bytes. Tables that don't report a
+        size are skipped.
+        """
+
 @final
 class RefType:
     """
@@ -462,6 +688,67 @@ class Table:
         The number of snapshots.
         """
 
+@final
+class TableDiff:
+    """
+    The differences in one table between two refs.
+    """
+    def __repr__(self, /) -> str: ...
+    @property
+    def columns(self, /) -> list[ColumnDiff]:
+        """
+        The columns added, removed or changed in type or nullability.
+        """
+    @property
+    def from_records(self, /) -> int | None:
+        """
+        The number of records in the first ref.
+        """
+    @property
+    def from_ref(self, /) -> str:
+        """
+        The first ref.
+        """
+    @property
+    def from_snapshot_id(self, /) -> int | None:
+        """
+        The current snapshot ID in the first ref.
+        """
+    def is_empty(self, /) -> bool:
+        """
+        Whether the table is the same in both refs.
+        """
+    @property
+    def partitions(self, /) -> list[PartitionDiff]:
+        """
+        The partition fields added, removed or changed in transform.
+        """
+    @property
+    def records_delta(self, /) -> int | None:
+        """
+        The change in the number of records, if both refs report it.
+        """
+    @property
+    def table(self, /) -> str:
+        """
+        The fully qualified table name.
+        """
+    @property
+    def to_records(self, /) -> int | None:
+        """
+        The number of records in the second ref.
+        """
+    @property
+    def to_ref(self, /) -> str:
+        """
+        The second ref.
+        """
+    @property
+    def to_snapshot_id(self, /) -> int | None:
+        """
+        The current snapshot ID in the second ref.
+        """
+
 @final
 class TableField:
     """
@@ -508,8 +795,70 @@ class TableKind:
     def __ne__(self, /, other: object) -> bool: ...
     def __repr__(self, /) -> str: ...
 
+@final
+class TableSnapshot:
+    """
+    An Iceberg snapshot of a table: its state after one write.
+    """
+    def __repr__(self, /) -> str: ...
+    @property
+    def operation(self, /) -> str | None:
+        """
+        The kind of write that produced the snapshot: "append", "overwrite",
+        "delete" or "replace".
+        """
+    @property
+    def parent_id(self, /) -> int | None:
+        """
+        The ID of the snapshot this one was written on top of, if any.
+        """
+    @property
+    def snapshot_id(self, /) -> int:
+        """
+        The snapshot ID.
+        """
+    @property
+    def summary(self, /) -> dict[str, str]:
+        """
+        The snapshot summary properties, like "added-records".
+        """
+    @property
+    def timestamp(self, /) -> datetime:
+        """
+        When the snapshot was written.
+        """
+
+@final
+class TableSync:
+    """
+    The tables added, removed or changed on a ref since the last sync,
+    returned by `bauplan.Client.sync_tables`.
+    """
+    def __repr__(self, /) -> str: ...
+    @property
+    def added(self, /) -> list[Table]:
+        """
+        Tables that weren't on the ref at the last sync.
+        """
+    @property
+    def changed(self, /) -> list[Table]:
+        """
+        Tables with a new snapshot, schema or update time.
+        """
+    def commit(self, /) -> None:
+        """
+        Records the tables as synced, by updating the state file. Call this
+        once the changes have been processed.
+        """
+    @property
+    def removed(self, /) -> list[str]:
+        """
+        The fully qualified names of tables that are no longer on the ref.
+        """
+
 @final
 class Tag(Ref):
     """
     A human-readable name that points to a specific commit in the data lake. Tags are often used to mark important milestones in the data lake history, such as releases or experiments.
     """
+    def __new__(cls, /, name: str, hash: str | None = None) -> Tag: ...
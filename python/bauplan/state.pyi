@@ -7,6 +7,13 @@ class ExternalTableCreateContext:
     """
     The parameters that were passed to an external table creation job.
     """
+    def __eq__(self, /, other: object) -> bool: ...
+    def __ne__(self, /, other: object) -> bool: ...
+    def __repr__(self, /) -> str: ...
+    def to_dict(self, /) -> dict:
+        """
+        The context as a dict, keyed by attribute name.
+        """
     @property
     def branch_name(self, /) -> str:
         """
@@ -18,6 +25,12 @@ class ExternalTableCreateContext:
         Namespace of the external table.
         """
     @property
+    def ref(self, /) -> str:
+        """
+        Alias of `branch_name`, which is the canonical name, for consistency
+        with `bauplan.state.RunExecutionContext.ref`.
+        """
+    @property
     def table_name(self, /) -> str:
         """
         Name of the external table to create.
@@ -50,13 +63,76 @@ class ExternalTableCreateState:
         The final status string (e.g. `"SUCCESS"`, `"FAILED"`).
         """
 
+@final
+class PlannedMaterialization:
+    """
+    A table that a run planned to write a model to. For dry runs, this is a
+    table that would have been written.
+    """
+    def __repr__(self, /) -> str: ...
+    @property
+    def namespace(self, /) -> str:
+        """
+        Namespace of the table.
+        """
+    @property
+    def partitioned_by(self, /) -> list[str]:
+        """
+        Partitioning expressions for the table. The server doesn't report
+        them yet, so this is always empty.
+        """
+    @property
+    def ref(self, /) -> str:
+        """
+        Ref the table would be written on.
+        """
+    @property
+    def table_name(self, /) -> str:
+        """
+        Name of the table.
+        """
+
+@final
+class PlannedModel:
+    """
+    A model in a run's plan, as returned by `Client.explain_run`.
+    """
+    def __repr__(self, /) -> str: ...
+    @property
+    def depends_on(self, /) -> list[str]:
+        """
+        IDs of the models this model reads from.
+        """
+    @property
+    def model_id(self, /) -> str:
+        """
+        ID of the model, as referenced by `depends_on`.
+        """
+    @property
+    def model_name(self, /) -> str:
+        """
+        Name of the model.
+        """
+
 @final
 class RunExecutionContext:
     """
     The execution context for a run, capturing the parameters that were
     used to launch it.
     """
+    def __eq__(self, /, other: object) -> bool: ...
+    def __ne__(self, /, other: object) -> bool: ...
     def __repr__(self, /) -> str: ...
+    def to_dict(self, /) -> dict:
+        """
+        The context as a dict, keyed by attribute name.
+        """
+    @property
+    def branch_name(self, /) -> str:
+        """
+        Alias of `ref`, for consistency with the table job contexts. Runs can
+        also be executed against a tag, so `ref` is the canonical name.
+        """
     @property
     def cache(self, /) -> str:
         """
@@ -90,7 +166,8 @@ class RunExecutionContext:
     @property
     def project_dir(self, /) -> str:
         """
-        Local project directory that was packaged and submitted.
+        Local project directory that was packaged and submitted, or empty
+        for a project passed in memory.
         """
     @property
     def ref(self, /) -> str:
@@ -120,6 +197,24 @@ class RunExecutionContext:
         materialized on a temporary branch and merged atomically on success.
         """
 
+@final
+class RunPlan:
+    """
+    The plan for a run, as returned by `Client.explain_run`: the models it
+    would run.
+    """
+    def __repr__(self, /) -> str: ...
+    @property
+    def job_id(self, /) -> str:
+        """
+        ID of the job that produced the plan.
+        """
+    @property
+    def models(self, /) -> list[PlannedModel]:
+        """
+        The planned models.
+        """
+
 @final
 class RunState:
     """
@@ -127,6 +222,24 @@ class RunState:
     per-task lifecycle events.
     """
     def __repr__(self, /) -> str: ...
+    def assert_writes_within(self, namespace: str, /) -> None:
+        """
+        Raises `AssertionError` if the run planned to write any table outside
+        of the given namespace. Combined with `dry_run=True`, this can be used
+        as a preflight check.
+
+        ```python
+        #! client = bauplan.Client()
+        state = client.run("./my_pipeline", ref="main", dry_run=True)
+        state.assert_writes_within("staging")
+        ```
+
+        Parameters:
+            namespace: The only namespace the run may write to.
+
+        Raises:
+            `AssertionError`: if any planned materialization is in another namespace.
+        """
     @property
     def ctx(self, /) -> RunExecutionContext:
         """
@@ -143,6 +256,12 @@ class RunState:
         Duration in nanoseconds, or None if the run hasn't ended.
         """
     @property
+    def empty_models(self, /) -> list[str]:
+        """
+        Models checked with `fail_on_empty_models` that wrote no rows. If
+        any did, the run is failed, with an error like `"empty output: trips"`.
+        """
+    @property
     def ended_at_ns(self, /) -> int | None:
         """
         Epoch nanoseconds when the run ended, if it has.
@@ -163,24 +282,46 @@ class RunState:
         The final status string (e.g. "SUCCESS", "FAILED").
         """
     @property
+    def planned_materializations(self, /) -> list[PlannedMaterialization]:
+        """
+        Tables that the run planned to write models to. For dry runs, these
+        are the tables that would have been written.
+        """
+    @property
     def started_at_ns(self, /) -> int:
         """
         Epoch nanoseconds when the run started.
         """
     @property
+    def tasks(self, /) -> list[TaskState]:
+        """
+        The tasks of the run, in the order they started.
+        """
+    @property
     def tasks_started(self, /) -> dict[str, datetime]:
         """
         Per-task start times, keyed by task ID.
+
+        Deprecated: use `tasks` instead.
         """
     @property
     def tasks_stopped(self, /) -> dict[str, datetime]:
         """
         Per-task stop times, keyed by task ID.
+
+        Deprecated: use `tasks` instead.
         """
     @property
     def user_logs(self, /) -> list[JobLogEvent]:
         """
-        User log messages emitted during the run.
+        User log messages emitted during the run. Past the client's
+        `max_user_logs`, only the first and last messages are kept, with a
+        marker in between; use `Client.get_job_logs` to get all of them.
+        """
+    @property
+    def user_logs_truncated(self, /) -> bool:
+        """
+        Whether messages were dropped from `user_logs`.
         """
 
 @final
@@ -211,6 +352,13 @@ class TableCreatePlanContext:
     """
     The parameters that were passed to a `Client.plan_table_creation` call.
     """
+    def __eq__(self, /, other: object) -> bool: ...
+    def __ne__(self, /, other: object) -> bool: ...
+    def __repr__(self, /) -> str: ...
+    def to_dict(self, /) -> dict:
+        """
+        The context as a dict, keyed by attribute name.
+        """
     @property
     def branch_name(self, /) -> str:
         """
@@ -222,6 +370,12 @@ class TableCreatePlanContext:
         Namespace the table will be created in.
         """
     @property
+    def ref(self, /) -> str:
+        """
+        Alias of `branch_name`, which is the canonical name, for consistency
+        with `bauplan.state.RunExecutionContext.ref`.
+        """
+    @property
     def search_string(self, /) -> str:
         """
         URI pattern (e.g. `s3://bucket/path/*.parquet`) used to discover the
@@ -314,6 +468,13 @@ class TableDataImportContext:
     """
     The parameters that were passed to a data import job.
     """
+    def __eq__(self, /, other: object) -> bool: ...
+    def __ne__(self, /, other: object) -> bool: ...
+    def __repr__(self, /) -> str: ...
+    def to_dict(self, /) -> dict:
+        """
+        The context as a dict, keyed by attribute name.
+        """
     @property
     def best_effort(self, /) -> bool:
         """
@@ -347,6 +508,12 @@ class TableDataImportContext:
         Preview mode used for the import (`"on"`, `"off"`, `"head"`, `"tail"`).
         """
     @property
+    def ref(self, /) -> str:
+        """
+        Alias of `branch_name`, which is the canonical name, for consistency
+        with `bauplan.state.RunExecutionContext.ref`.
+        """
+    @property
     def search_string(self, /) -> str:
         """
         URI pattern (e.g. `s3://bucket/path/*.parquet`) used to locate the
@@ -389,3 +556,73 @@ class TableDataImportState:
         """
         The final status string (e.g. `"SUCCESS"`, `"FAILED"`).
         """
+
+@final
+class TaskState:
+    """
+    A task of a run, built up from the events reported as it starts and
+    completes.
+    """
+    def __repr__(self, /) -> str: ...
+    @property
+    def cached(self, /) -> bool:
+        """
+        Whether the task succeeded with output served from the cache. Always
+        `False` for servers that don't report it.
+        """
+    @property
+    def description(self, /) -> str:
+        """
+        What kind of task it is, e.g. `"Python model"`.
+        """
+    @property
+    def ended_at(self, /) -> datetime | None:
+        """
+        When the task ended, if it has.
+        """
+    @property
+    def error(self, /) -> str | None:
+        """
+        Why the task failed, was cancelled or timed out.
+        """
+    @property
+    def file_name(self, /) -> str | None:
+        """
+        File the function is defined in.
+        """
+    @property
+    def function_name(self, /) -> str | None:
+        """
+        Name of the function the task runs, if any.
+        """
+    @property
+    def line_number(self, /) -> int | None:
+        """
+        Line the function is defined on.
+        """
+    @property
+    def model_name(self, /) -> str | None:
+        """
+        Name of the model the task runs, if any.
+        """
+    @property
+    def name(self, /) -> str:
+        """
+        Name of the task.
+        """
+    @property
+    def outcome(self, /) -> str | None:
+        """
+        How the task ended (`"SUCCESS"`, `"FAILED"`, `"SKIPPED"`,
+        `"CANCELLED"` or `"TIMEOUT"`), or `None` if it hasn't.
+        """
+    @property
+    def started_at(self, /) -> datetime | None:
+        """
+        When the task started, if it did.
+        """
+    @property
+    def task_id(self, /) -> str:
+        """
+        ID of the task.
+        """
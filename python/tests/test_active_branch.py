@@ -0,0 +1,114 @@
+"""Tests for the client's active branch."""
+
+import pathlib
+import uuid
+
+import pytest
+
+import bauplan
+
+
+@pytest.fixture
+def client() -> bauplan.Client:
+    return bauplan.Client()
+
+
+@pytest.fixture
+def username(client: bauplan.Client):
+    user = client.info().user
+    assert user is not None
+    return user.username
+
+
+@pytest.fixture
+def temp_branch(client: bauplan.Client, username: str):
+    name = f"{username}.pysdk_active_{uuid.uuid4().hex[:8]}"
+    client.create_branch(name, from_ref="main")
+    yield name
+    client.delete_branch(name, if_exists=True)
+
+
+def offline_client(tmp_path: pathlib.Path, extra: str = "") -> bauplan.Client:
+    config = tmp_path / "config.yaml"
+    config.write_text(
+        f"profiles:\n  test:\n    api_endpoint: https://example.com\n{extra}"
+    )
+    return bauplan.Client(
+        profile="test",
+        api_key="bpln_dummy_key",
+        config_file_path=str(config),
+    )
+
+
+def test_active_branch_from_profile(tmp_path: pathlib.Path):
+    client = offline_client(tmp_path, "    active_branch: a.b\n")
+    assert client.active_branch == "a.b"
+
+    client.active_branch = "c.d"
+    assert client.active_branch == "c.d"
+
+
+def test_active_branch_defaults_to_main(tmp_path: pathlib.Path):
+    client = offline_client(tmp_path)
+    assert client.active_branch == "main"
+
+
+def test_checkout_from_ref_requires_create(tmp_path: pathlib.Path):
+    client = offline_client(tmp_path)
+    with pytest.raises(ValueError):
+        client.checkout("a.b", from_ref="main")
+
+
+def test_checkout_missing_branch(client: bauplan.Client, username: str):
+    before = client.active_branch
+    with pytest.raises(bauplan.exceptions.BranchNotFoundError):
+        client.checkout(f"{username}.pysdk_missing_{uuid.uuid4().hex[:8]}")
+
+    assert client.active_branch == before
+
+
+def test_checkout_create(client: bauplan.Client, username: str):
+    name = f"{username}.pysdk_checkout_{uuid.uuid4().hex[:8]}"
+    try:
+        branch = client.checkout(name, create=True, from_ref="main")
+        assert branch.name == name
+        assert client.active_branch == name
+        assert client.has_branch(name)
+    finally:
+        client.delete_branch(name, if_exists=True)
+
+
+def test_get_table_uses_active_branch(client: bauplan.Client, temp_branch: str):
+    client.checkout(temp_branch)
+    client.delete_table("titanic")
+
+    assert not client.has_table("titanic")
+    with pytest.raises(bauplan.exceptions.TableNotFoundError):
+        client.get_table("titanic")
+
+    # An explicit ref still wins.
+    assert client.get_table("titanic", ref="main").name == "titanic"
+
+
+def test_query_uses_active_branch(client: bauplan.Client, temp_branch: str):
+    query = "SELECT COUNT(*) AS n FROM titanic"
+    client.checkout(temp_branch)
+    client.delete_table("titanic")
+
+    with pytest.raises(bauplan.exceptions.BauplanError):
+        client.query(query)
+
+    assert client.query(query, ref="main").column("n")[0].as_py() > 0
+
+
+def test_run_uses_active_branch(client: bauplan.Client, temp_branch: str):
+    client.checkout(temp_branch)
+    state = client.run(
+        project_dir="tests/fixtures/simple_taxi_dag",
+        dry_run=True,
+        cache="off",
+    )
+
+    assert state.job_status == "SUCCESS"
+    assert state.ctx is not None
+    assert temp_branch in state.ctx.ref
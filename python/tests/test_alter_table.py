@@ -0,0 +1,91 @@
+"""Tests for changing table schemas."""
+
+import uuid
+
+import pytest
+import bauplan
+
+
+@pytest.fixture
+def client() -> bauplan.Client:
+    return bauplan.Client()
+
+
+@pytest.fixture
+def username(client: bauplan.Client):
+    user = client.info().user
+    assert user is not None
+    return user.username
+
+
+@pytest.fixture
+def temp_branch(client: bauplan.Client, username: str):
+    name = f"{username}.pysdk_alter_{uuid.uuid4().hex[:8]}"
+    client.create_branch(name, from_ref="main")
+    yield name
+    client.delete_branch(name, if_exists=True)
+
+
+def test_add_and_rename(client: bauplan.Client, temp_branch: str):
+    before = client.get_table("titanic", ref=temp_branch, namespace="bauplan")
+
+    table = client.alter_table(
+        "titanic",
+        temp_branch,
+        namespace="bauplan",
+        add_columns=[("Notes", "string", False)],
+        rename_columns={"Name": "PassengerName"},
+        commit_body="Add notes",
+    )
+
+    names = [f.name for f in table.fields]
+    assert "Notes" in names
+    assert "PassengerName" in names
+    assert "Name" not in names
+    assert table.current_schema_id != before.current_schema_id
+
+    fetched = client.get_table("titanic", ref=temp_branch, namespace="bauplan")
+    assert [f.name for f in fetched.fields] == names
+
+
+
+def test_diff_after_alter(client: bauplan.Client, temp_branch: str):
+    diff = client.diff_table("titanic", "main", temp_branch, namespace="bauplan")
+    assert diff.is_empty()
+
+    client.alter_table(
+        "titanic",
+        temp_branch,
+        namespace="bauplan",
+        add_columns=[("Notes", "string", False)],
+        drop_columns=["Name"],
+    )
+
+    diff = client.diff_table("titanic", "main", temp_branch, namespace="bauplan")
+    assert not diff.is_empty()
+    assert diff.table == "bauplan.titanic"
+    kinds = {c.name: c.kind for c in diff.columns}
+    assert kinds == {
+        "Notes": bauplan.schema.DiffKind.Added,
+        "Name": bauplan.schema.DiffKind.Removed,
+    }
+
+
+@pytest.mark.parametrize(
+    "changes,message",
+    [
+        ({}, "no columns to add, drop or rename"),
+        ({"drop_columns": ["NoSuchColumn"]}, 'no column "NoSuchColumn"'),
+        ({"add_columns": [("Name", "string", False)]}, "more than once"),
+        ({"add_columns": [("Notes", "varchar", False)]}, "invalid type"),
+        (
+            {"drop_columns": ["Name"], "rename_columns": {"Name": "FullName"}},
+            "both dropped and renamed",
+        ),
+    ],
+)
+def test_invalid_changes(
+    client: bauplan.Client, temp_branch: str, changes: dict, message: str
+):
+    with pytest.raises(ValueError, match=message):
+        client.alter_table("titanic", temp_branch, namespace="bauplan", **changes)
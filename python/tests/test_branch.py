@@ -0,0 +1,111 @@
+"""Tests for the branch lifecycle: list, get, create, rename, merge, delete."""
+
+import uuid
+
+import pytest
+import bauplan
+from bauplan import exceptions
+
+
+@pytest.fixture
+def client() -> bauplan.Client:
+    return bauplan.Client()
+
+
+@pytest.fixture
+def username(client: bauplan.Client):
+    user = client.info().user
+    assert user is not None
+    return user.username
+
+
+@pytest.fixture
+def branch_name(client: bauplan.Client, username: str):
+    name = f"{username}.pysdk_branch_{uuid.uuid4().hex[:8]}"
+    yield name
+    client.delete_branch(name, if_exists=True)
+
+
+def test_create_get_delete(client: bauplan.Client, branch_name: str):
+    created = client.create_branch(branch_name, from_ref="main")
+    assert created.name == branch_name
+    assert created.type == bauplan.RefType.BRANCH
+
+    fetched = client.get_branch(bauplan.Branch(branch_name))
+    assert fetched.hash == created.hash
+    assert client.has_branch(branch_name)
+
+    listed = [b.name for b in client.get_branches(name=branch_name, limit=10)]
+    assert branch_name in listed
+
+    assert client.delete_branch(created)
+    assert not client.has_branch(branch_name)
+    assert not client.delete_branch(branch_name, if_exists=True)
+
+    with pytest.raises(exceptions.BranchNotFoundError):
+        client.delete_branch(branch_name)
+
+
+def test_rename(client: bauplan.Client, branch_name: str):
+    client.create_branch(branch_name, from_ref="main")
+    new_name = f"{branch_name}_renamed"
+    try:
+        renamed = client.rename_branch(branch_name, new_name)
+        assert renamed.name == new_name
+        assert not client.has_branch(branch_name)
+
+        with pytest.raises(exceptions.BranchNotFoundError):
+            client.rename_branch(branch_name, new_name)
+    finally:
+        client.delete_branch(new_name, if_exists=True)
+
+
+def test_merge(client: bauplan.Client, branch_name: str):
+    client.create_branch(branch_name, from_ref="main")
+    source = f"{branch_name}_source"
+    try:
+        client.create_branch(source, from_ref=branch_name)
+        client.create_namespace("pysdk_merge_ns", source)
+
+        merged = client.merge_branch(
+            source,
+            into_branch=bauplan.Branch(branch_name),
+            commit_message="merge from pysdk test",
+            commit_properties={"origin": "test_branch.py"},
+        )
+        assert merged.name == branch_name
+        assert client.has_namespace("pysdk_merge_ns", ref=branch_name)
+
+        commit = next(iter(client.get_commits(branch_name, limit=1)))
+        assert commit.message == "merge from pysdk test"
+        assert commit.properties.get("origin") == "test_branch.py"
+    finally:
+        client.delete_branch(source, if_exists=True)
+
+
+def test_merge_conflict(client: bauplan.Client, branch_name: str):
+    client.create_branch(branch_name, from_ref="main")
+    source = f"{branch_name}_source"
+    try:
+        client.create_branch(source, from_ref=branch_name)
+
+        # Change the same table on both sides.
+        client.delete_table("titanic", source, namespace="bauplan")
+        client.delete_table("titanic", branch_name, namespace="bauplan")
+        client.revert_table(
+            "titanic",
+            namespace="bauplan",
+            source_ref="main",
+            into_branch=branch_name,
+        )
+
+        with pytest.raises(exceptions.MergeConflictError) as exc_info:
+            client.merge_branch(source, into_branch=branch_name)
+
+        [conflict] = exc_info.value.conflicts
+        assert conflict.table_name == "bauplan.titanic"
+        assert conflict.source_hash is not None
+        assert conflict.destination_hash is not None
+        assert isinstance(exc_info.value.kind, exceptions.ApiErrorKind.MergeConflict)
+    finally:
+        client.delete_branch(source, if_exists=True)
@@ -1,7 +1,15 @@
 """Tests for Client construction."""
 
 from concurrent.futures import ThreadPoolExecutor, as_completed
+import asyncio
+import importlib.metadata
+import logging
+import multiprocessing
 import pathlib
+import sys
+import time
+
+import pytest
 
 import bauplan
 
@@ -21,6 +29,80 @@ def test_api_key_param(tmp_path: pathlib.Path):
     )
 
 
+PROFILES = """current_profile: staging
+profiles:
+  default:
+    api_key: bpln_default_key
+  staging:
+    api_key: bpln_staging_key
+    api_endpoint: https://api.staging.example.com
+    grpc_endpoint: https://grpc.staging.example.com
+    catalog_endpoint: https://catalog.staging.example.com/iceberg
+"""
+
+
+def test_named_profiles(tmp_path: pathlib.Path, monkeypatch: pytest.MonkeyPatch):
+    (tmp_path / ".bauplan").mkdir()
+    (tmp_path / ".bauplan" / "config.yaml").write_text(PROFILES)
+    monkeypatch.setenv("HOME", str(tmp_path))
+    monkeypatch.setenv("USERPROFILE", str(tmp_path))
+    for var in (
+        "BAUPLAN_PROFILE",
+        "BAUPLAN_API_KEY",
+        "BAUPLAN_API_ENDPOINT",
+        "BAUPLAN_GRPC_ENDPOINT",
+        "BAUPLAN_CATALOG_ENDPOINT",
+    ):
+        monkeypatch.delenv(var, raising=False)
+
+    # The config file's current profile is used by default.
+    client = bauplan.Client()
+    assert client.grpc_endpoint == "https://grpc.staging.example.com/"
+    assert client.catalog_endpoint == "https://catalog.staging.example.com/iceberg"
+
+    # BAUPLAN_PROFILE wins over the config file, and the endpoint variables
+    # over the profile's settings.
+    monkeypatch.setenv("BAUPLAN_PROFILE", "default")
+    monkeypatch.setenv("BAUPLAN_GRPC_ENDPOINT", "https://grpc.env.example.com")
+    client = bauplan.Client()
+    assert client.grpc_endpoint == "https://grpc.env.example.com/"
+    assert client.catalog_endpoint.endswith("bauplanlabs.com/iceberg")
+
+    # An explicit profile wins over both.
+    monkeypatch.delenv("BAUPLAN_GRPC_ENDPOINT")
+    client = bauplan.Client(profile="staging")
+    assert client.grpc_endpoint == "https://grpc.staging.example.com/"
+
+    with pytest.raises(ValueError, match="Profile 'nope' not found"):
+        bauplan.Client(profile="nope")
+
+def test_job_request_module_version(
+    tmp_path: pathlib.Path, caplog: pytest.LogCaptureFixture
+):
+    """Jobs report the installed package version, for the runtime to match."""
+    config = tmp_path / "config.yaml"
+    config.write_text("profiles:\n  offline:\n    api_endpoint: http://127.0.0.1:1\n")
+    client = bauplan.Client(
+        profile="offline",
+        api_key="bpln_dummy_key",
+        config_file_path=str(config),
+        client_timeout=2,
+    )
+
+    bauplan.enable_logging("DEBUG")
+    try:
+        with caplog.at_level(logging.DEBUG, logger="bauplan"):
+            with pytest.raises(Exception):
+                client.run_from_snapshot("abcd", ref="main", detach=True)
+    finally:
+        bauplan.enable_logging("OFF")
+
+    records = [r for r in caplog.records if r.name == "bauplan"]
+    versions = [getattr(r, "module_version", None) for r in records]
+    versions = [v for v in versions if v is not None]
+    assert versions == [importlib.metadata.version("bauplan")]
+
+
 def test_concurrent_queries():
     """A single Client should be usable from multiple threads (#106)."""
     client = bauplan.Client()
@@ -33,3 +115,209 @@ def test_concurrent_queries():
         results = [f.result().column("n")[0].as_py() for f in as_completed(futures)]
 
     assert results == [891, 891, 891]
+
+
+def test_concurrent_calls_offline(tmp_path: pathlib.Path):
+    """Concurrent calls on a shared Client fail on their own, not on each other."""
+    config = tmp_path / "config.yaml"
+    config.write_text("profiles:\n  offline:\n    api_endpoint: http://127.0.0.1:1\n")
+    client = bauplan.Client(
+        profile="offline",
+        api_key="bpln_dummy_key",
+        config_file_path=str(config),
+        client_timeout=2,
+    )
+
+    def call(i: int) -> str:
+        client.active_branch = f"a.branch{i}"
+        try:
+            client.get_branch("main")
+        except Exception as e:
+            return str(e)
+        return ""
+
+    with ThreadPoolExecutor(max_workers=4) as pool:
+        errors = list(pool.map(call, range(8)))
+
+    assert all(errors)
+    assert not any("already borrowed" in e.lower() for e in errors)
+    assert client.active_branch.startswith("a.branch")
+
+
+def unroutable_client(tmp_path: pathlib.Path, **kwargs) -> bauplan.Client:
+    config = tmp_path / "config.yaml"
+    config.write_text(
+        "profiles:\n  unroutable:\n    api_endpoint: http://10.255.255.1\n"
+    )
+    return bauplan.Client(
+        profile="unroutable",
+        api_key="bpln_dummy_key",
+        config_file_path=str(config),
+        **kwargs,
+    )
+
+
+def test_request_timeout(tmp_path: pathlib.Path):
+    """Catalog reads give up after request_timeout, not the job timeout."""
+    client = unroutable_client(tmp_path, request_timeout=1.0, client_timeout=600)
+
+    start = time.monotonic()
+    with pytest.raises(bauplan.exceptions.RequestTimeoutError) as exc_info:
+        client.get_table("titanic", "main")
+
+    assert time.monotonic() - start < 10
+    assert "timed out after" in str(exc_info.value)
+    assert "/tables/titanic" in str(exc_info.value)
+
+
+def test_request_timeout_per_call(tmp_path: pathlib.Path):
+    client = unroutable_client(tmp_path, request_timeout=600.0)
+
+    start = time.monotonic()
+    with pytest.raises(bauplan.exceptions.RequestTimeoutError):
+        client.get_branch("main", request_timeout=1.0)
+
+    assert time.monotonic() - start < 10
+
+    with pytest.raises(ValueError):
+        client.get_branch("main", request_timeout=0)
+
+
+@pytest.mark.parametrize("priority", [-5, 0, 11])
+def test_invalid_priority(tmp_path: pathlib.Path, priority: int):
+    """An out of range priority fails before anything is sent."""
+    client = unroutable_client(tmp_path, request_timeout=600.0)
+    calls = [
+        lambda: client.query("SELECT 1", ref="main", priority=priority),
+        lambda: client.create_table("t", "s3://b/*.parquet", priority=priority),
+        lambda: client.import_data("t", "s3://b/*.parquet", priority=priority),
+        lambda: client.run_from_snapshot("abcd", ref="main", priority=priority),
+    ]
+
+    start = time.monotonic()
+    for call in calls:
+        with pytest.raises(ValueError, match="must be between 1 and 10"):
+            call()
+
+    assert time.monotonic() - start < 5
+
+
+def test_close(tmp_path: pathlib.Path):
+    """A closed client fails fast, without affecting other clients."""
+    client = unroutable_client(tmp_path, request_timeout=600.0)
+    other = unroutable_client(tmp_path, request_timeout=1.0)
+    client.close()
+    client.close()
+
+    start = time.monotonic()
+    with pytest.raises(bauplan.exceptions.BauplanError, match="client is closed"):
+        client.get_branch("main")
+    with pytest.raises(bauplan.exceptions.BauplanError, match="client is closed"):
+        client.get_jobs()
+    assert time.monotonic() - start < 1
+
+    with pytest.raises(bauplan.exceptions.RequestTimeoutError):
+        other.get_branch("main")
+
+
+def test_context_manager(tmp_path: pathlib.Path):
+    with unroutable_client(tmp_path) as client:
+        assert isinstance(client, bauplan.Client)
+
+    with pytest.raises(bauplan.exceptions.BauplanError, match="client is closed"):
+        client.get_table("titanic", "main")
+
+
+def test_blocking_call_in_event_loop():
+    client = bauplan.Client()
+
+    async def main():
+        client.query("SELECT 1", ref="main")
+
+    with pytest.raises(RuntimeError, match="asyncio.to_thread"):
+        asyncio.run(main())
+
+
+def test_blocking_call_in_worker_thread():
+    client = bauplan.Client()
+
+    async def main():
+        return await asyncio.to_thread(
+            client.query, "SELECT COUNT(*) as n from titanic", ref="main"
+        )
+
+    assert asyncio.run(main()).column("n")[0].as_py() == 891
+
+
+def test_allow_blocking_warns():
+    client = bauplan.Client(allow_blocking=True)
+
+    async def main():
+        return client.query("SELECT COUNT(*) as n from titanic", ref="main")
+
+    with pytest.warns(RuntimeWarning, match="event loop"):
+        table = asyncio.run(main())
+
+    assert table.column("n")[0].as_py() == 891
+
+
+def test_catalog_reads_in_event_loop():
+    client = bauplan.Client()
+
+    async def main():
+        return client.get_branch("main")
+
+    assert asyncio.run(main()).name == "main"
+
+
+def _use_after_fork(client: bauplan.Client, jobs, results) -> None:
+    try:
+        branch = client.get_branch("main").name
+        n = client.query("SELECT COUNT(*) as n from titanic", ref="main")
+        results.put(("ok", branch, n.column("n")[0].as_py()))
+    except Exception as e:
+        results.put(("error", type(e).__name__, str(e)))
+
+    # An iterator started in the parent can't be resumed.
+    try:
+        list(jobs)
+        results.put(("ok",))
+    except bauplan.exceptions.BauplanError as e:
+        results.put(("error", str(e)))
+
+
+@pytest.mark.skipif(sys.platform == "win32", reason="fork is not available")
+def test_client_survives_fork():
+    """A Client created before forking (like in a gunicorn worker) works in the child."""
+    client = bauplan.Client()
+    assert client.query("SELECT 1 as n", ref="main").num_rows == 1
+
+    # Only the first page of jobs is fetched before forking.
+    jobs = client.get_jobs(limit=200, filter_by_current_user=False)
+    next(jobs)
+
+    ctx = multiprocessing.get_context("fork")
+    results = ctx.Queue()
+    child = ctx.Process(target=_use_after_fork, args=(client, jobs, results))
+    child.start()
+    first, second = results.get(timeout=120), results.get(timeout=120)
+    child.join(timeout=30)
+
+    assert child.exitcode == 0
+    assert first == ("ok", "main", 891)
+    assert second == ("ok",) or "client used after fork" in second[1]
+
+    # The parent is unaffected.
+    assert client.get_branch("main").name == "main"
+
+
+def test_whoami():
+    client = bauplan.Client()
+    user = client.whoami()
+    info = client.info()
+
+    assert info.user is not None
+    assert user.username == info.user.username
+    assert user.branch_prefix == f"{user.username}."
+    if info.organization:
+        assert user.organization_name == info.organization.name
@@ -0,0 +1,73 @@
+"""Tests for reading the commit history of a ref."""
+
+import uuid
+
+import pytest
+import bauplan
+
+
+@pytest.fixture
+def client() -> bauplan.Client:
+    return bauplan.Client()
+
+
+@pytest.fixture
+def username(client: bauplan.Client):
+    user = client.info().user
+    assert user is not None
+    return user.username
+
+
+@pytest.fixture
+def temp_branch(client: bauplan.Client, username: str):
+    name = f"{username}.pysdk_commits_{uuid.uuid4().hex[:8]}"
+    client.create_branch(name, from_ref="main")
+    yield name
+    client.delete_branch(name, if_exists=True)
+
+
+def test_get_commits(client: bauplan.Client):
+    commits = list(client.get_commits("main", limit=3))
+
+    assert len(commits) == 3
+    assert commits[0].ref.hash == client.get_branch("main").hash
+    for commit in commits:
+        assert isinstance(commit, bauplan.schema.Commit)
+        assert commit.committed_date is not None
+
+
+def test_get_commits_across_pages(client: bauplan.Client):
+    commits = list(client.get_commits("main", limit=250))
+
+    hashes = [c.ref.hash for c in commits]
+    assert 0 < len(hashes) <= 250
+    assert len(set(hashes)) == len(hashes)
+
+    # The first page is the same, however many pages are fetched.
+    first = [c.ref.hash for c in client.get_commits("main", limit=3)]
+    assert hashes[:3] == first
+
+
+def test_get_commits_filter_by_properties(client: bauplan.Client, temp_branch: str):
+    marker = uuid.uuid4().hex
+    client.create_namespace(
+        "commits_ns",
+        branch=temp_branch,
+        commit_properties={"test.marker": marker},
+    )
+
+    commits = list(
+        client.get_commits(temp_branch, filter_by_properties={"test.marker": marker})
+    )
+
+    assert len(commits) == 1
+    assert commits[0].properties["test.marker"] == marker
+    assert commits[0].ref.hash == client.get_branch(temp_branch).hash
+
+
+def test_get_commits_no_match(client: bauplan.Client, temp_branch: str):
+    commits = client.get_commits(
+        temp_branch, filter_by_message="no commit has this message", limit=10
+    )
+
+    assert list(commits) == []
@@ -0,0 +1,100 @@
+"""Tests for the idempotent ensure_* helpers."""
+
+import logging
+import uuid
+
+import pytest
+import bauplan
+from bauplan import exceptions
+
+
+@pytest.fixture
+def client() -> bauplan.Client:
+    return bauplan.Client()
+
+
+@pytest.fixture
+def username(client: bauplan.Client):
+    user = client.info().user
+    assert user is not None
+    return user.username
+
+
+@pytest.fixture
+def temp_branch(client: bauplan.Client, username: str):
+    name = f"{username}.pysdk_ensure_{uuid.uuid4().hex[:8]}"
+    client.create_branch(name, from_ref="main")
+    yield name
+    client.delete_branch(name, if_exists=True)
+
+
+@pytest.fixture
+def info_logs(caplog: pytest.LogCaptureFixture):
+    bauplan.enable_logging("INFO")
+    try:
+        with caplog.at_level(logging.INFO, logger="bauplan"):
+            yield caplog
+    finally:
+        bauplan.enable_logging("OFF")
+
+
+def test_ensure_branch(
+    client: bauplan.Client, username: str, info_logs: pytest.LogCaptureFixture
+):
+    name = f"{username}.pysdk_ensure_{uuid.uuid4().hex[:8]}"
+    try:
+        created = client.ensure_branch(name, from_ref="main")
+        assert created.name == name
+        assert "created branch" in info_logs.text
+
+        info_logs.clear()
+        existing = client.ensure_branch(name, from_ref="main")
+        assert existing.name == name
+        assert existing.hash == created.hash
+        assert "branch already exists" in info_logs.text
+    finally:
+        client.delete_branch(name, if_exists=True)
+
+
+def test_ensure_branch_forbidden(client: bauplan.Client):
+    # Only admins can create branches outside their own prefix.
+    with pytest.raises(exceptions.CreateBranchForbiddenError):
+        client.ensure_branch(f"pysdk_no_prefix_{uuid.uuid4().hex[:8]}", from_ref="main")
+
+
+def test_ensure_branch_other_errors(client: bauplan.Client, username: str):
+    name = f"{username}.pysdk_ensure_{uuid.uuid4().hex[:8]}"
+    with pytest.raises(exceptions.RefNotFoundError):
+        client.ensure_branch(name, from_ref=f"{username}.no_such_branch")
+
+
+def test_ensure_namespace(
+    client: bauplan.Client, temp_branch: str, info_logs: pytest.LogCaptureFixture
+):
+    created = client.ensure_namespace("py_ensure_ns", temp_branch)
+    assert created.name == "py_ensure_ns"
+    assert client.has_namespace("py_ensure_ns", ref=temp_branch)
+    assert "created namespace" in info_logs.text
+
+    info_logs.clear()
+    existing = client.ensure_namespace("py_ensure_ns", temp_branch)
+    assert existing.name == "py_ensure_ns"
+    assert "namespace already exists" in info_logs.text
+
+
+def test_ensure_namespace_other_errors(client: bauplan.Client, username: str):
+    with pytest.raises(exceptions.BranchNotFoundError):
+        client.ensure_namespace("py_ensure_ns", f"{username}.no_such_branch")
+
+
+def test_ensure_table_absent(
+    client: bauplan.Client, temp_branch: str, info_logs: pytest.LogCaptureFixture
+):
+    head = client.ensure_table_absent("no_such_table", temp_branch, namespace="bauplan")
+    assert head.name == temp_branch
+    assert "table already absent" in info_logs.text
+
+
+def test_ensure_table_absent_other_errors(client: bauplan.Client, username: str):
+    with pytest.raises(exceptions.BranchNotFoundError):
+        client.ensure_table_absent("no_such_table", f"{username}.no_such_branch")
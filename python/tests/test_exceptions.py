@@ -74,6 +74,9 @@ class TestExceptionHierarchy:
     def test_plan_error_is_bauplan_error(self):
         assert issubclass(exceptions.TableCreatePlanError, exceptions.BauplanError)
 
+    def test_too_many_requests_is_http_error(self):
+        assert issubclass(exceptions.TooManyRequestsError, exceptions.BauplanHTTPError)
+
     def test_plan_apply_status_error_is_bauplan_error(self):
         assert issubclass(
             exceptions.TableCreatePlanApplyStatusError, exceptions.BauplanError
@@ -91,6 +94,12 @@ class TestTableNotFoundContext:
         assert e.kind.catalog_ref is not None
         assert e.kind.catalog_ref.type == bauplan.RefType.BRANCH
 
+    def test_get_nonexistent_table_suggestions(self, client: bauplan.Client):
+        with pytest.raises(exceptions.TableNotFoundError) as exc_info:
+            client.get_table("nonexistent_table_xyz", "main", suggest_namespaces=True)
+
+        assert "did you mean" not in str(exc_info.value)
+
     def test_delete_nonexistent_table_raises(
         self, client: bauplan.Client, temp_branch: str
     ):
@@ -104,6 +113,8 @@ class TestTableNotFoundContext:
     def test_delete_table_if_exists(self, client: bauplan.Client, temp_branch: str):
         ref = client.delete_table("nonexistent_table_xyz", temp_branch, if_exists=True)
         assert ref.type == bauplan.RefType.BRANCH
+        assert ref.name == temp_branch
+        assert ref.hash == client.get_branch(temp_branch).hash
 
     def test_has_table_false(self, client: bauplan.Client):
         assert client.has_table("nonexistent_table_xyz", "main") is False
@@ -156,6 +167,18 @@ class TestExceptionInstantiation:
         assert isinstance(e, exceptions.BauplanJobError)
         assert isinstance(e, exceptions.BauplanError)
 
+    def test_too_many_requests_instantiation(self):
+        e = exceptions.TooManyRequestsError(
+            429, "", "slow down", retries=3, waited=4.5, retry_after=2.0, limit=100
+        )
+        assert isinstance(e, exceptions.BauplanHTTPError)
+        assert e.code == 429
+        assert e.retries == 3
+        assert e.waited == 4.5
+        assert e.retry_after == 2.0
+        assert e.limit == 100
+        assert e.remaining is None
+
 
 class TestUnauthorized:
     def test_invalid_api_key(self):
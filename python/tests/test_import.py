@@ -7,6 +7,7 @@ import pytest
 import bauplan
 
 SEARCH_URI = "s3://bpln-e2e-test-tables/test_tables/two_columns_two_dates/*"
+CSV_SEARCH_URI = "s3://bpln-e2e-test-tables/test_tables/two_columns_csv/*.csv"
 
 
 @pytest.fixture
@@ -135,7 +136,7 @@ def test_create_external_table_from_metadata(client: bauplan.Client, temp_branch
     assert result.num_rows == 10
 
     # Creating the same table without overwrite should raise.
-    with pytest.raises(bauplan.exceptions.ConflictError):
+    with pytest.raises(bauplan.exceptions.UpdateConflictError):
         client.create_external_table_from_metadata(
             table="ext_metadata_table",
             metadata_json_uri=metadata_uri,
@@ -145,6 +146,34 @@ def test_create_external_table_from_metadata(client: bauplan.Client, temp_branch
         )
 
 
+def test_create_external_table_from_metadata_namespace_in_name(
+    client: bauplan.Client, temp_branch: str
+):
+    metadata_uri = (
+        "s3://bauplan-openlake-db87a23/iceberg/tpch_1/"
+        "customer_e53c682c-36c4-4e3d-9ded-1214d0ee157f/"
+        "metadata/00000-b6f502e1-5140-499e-bf83-22f943067e36.metadata.json"
+    )
+
+    table = client.create_external_table_from_metadata(
+        table="bauplan.ext_metadata_ns_table",
+        metadata_json_uri=metadata_uri,
+        branch=temp_branch,
+    )
+
+    assert table.name == "ext_metadata_ns_table"
+    assert table.namespace == "bauplan"
+
+
+def test_create_external_table_from_metadata_invalid_uri(client: bauplan.Client):
+    with pytest.raises(ValueError):
+        client.create_external_table_from_metadata(
+            table="ext_metadata_table",
+            metadata_json_uri="https://example.com/metadata.json",
+            namespace="bauplan",
+        )
+
+
 def test_plan_and_apply(client: bauplan.Client, temp_branch: str):
     plan_state = client.plan_table_creation(
         table="my_plan_table",
@@ -175,3 +204,39 @@ def test_plan_and_apply(client: bauplan.Client, temp_branch: str):
     )
 
     assert result.num_rows > 0
+
+
+def test_search_uri_must_be_s3(client: bauplan.Client):
+    with pytest.raises(ValueError, match="s3://"):
+        client.plan_table_creation(
+            table="never_created",
+            search_uri="gs://bucket/path/*.parquet",
+            branch="main",
+        )
+
+
+def test_csv_is_not_supported_yet(client: bauplan.Client):
+    with pytest.raises(ValueError, match="only parquet is supported"):
+        client.create_table(
+            table="never_created",
+            search_uri=CSV_SEARCH_URI,
+            branch="main",
+            file_format="csv",
+        )
+
+
+def test_file_format_must_match_search_uri(client: bauplan.Client):
+    with pytest.raises(ValueError, match="matches csv files"):
+        client.plan_table_creation(
+            table="never_created",
+            search_uri=CSV_SEARCH_URI,
+            branch="main",
+        )
+
+    with pytest.raises(ValueError, match="invalid file format"):
+        client.import_data(
+            table="never_imported",
+            search_uri=SEARCH_URI,
+            branch="main",
+            file_format="avro",  # type: ignore[arg-type]
+        )
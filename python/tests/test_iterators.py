@@ -1,4 +1,7 @@
-"""Tests for query_to_generator and PyPaginator functionality."""
+"""Tests for query_to_generator, query_to_reader and PyPaginator functionality."""
+
+import pathlib
+from datetime import datetime, timedelta, timezone
 
 import pytest
 import bauplan
@@ -63,6 +66,33 @@ def test_get_tables_pagination(client: bauplan.Client):
     assert "titanic" in table_names
 
 
+def test_get_ref_summary(client: bauplan.Client):
+    summary = client.get_ref_summary("main")
+
+    assert isinstance(summary, bauplan.schema.RefSummary)
+    assert summary.tables > 0
+    assert summary.total_records > 0
+    assert summary.last_updated_at is not None
+
+    d = summary.to_dict()
+    assert d["ref"] == "main"
+    assert d["tables"] == summary.tables
+
+
+def test_sync_tables(client: bauplan.Client, tmp_path: pathlib.Path):
+    state_file = tmp_path / "state.json"
+    sync = client.sync_tables("main", state_file=state_file)
+
+    assert isinstance(sync, bauplan.schema.TableSync)
+    assert "bauplan.titanic" in [t.fqn for t in sync.added]
+    assert not sync.removed and not sync.changed
+    assert not state_file.exists()
+
+    sync.commit()
+    sync = client.sync_tables("main", state_file=state_file)
+    assert not sync.added and not sync.removed and not sync.changed
+
+
 def test_get_tables_with_limit(client: bauplan.Client):
     all_tables = list(
         client.get_tables(ref="main", filter_by_namespace="bauplan", limit=5)
@@ -104,3 +134,122 @@ def test_get_jobs_filter_by_status_lowercase(client: bauplan.Client):
     jobs = list(client.get_jobs(filter_by_statuses="complete", limit=5))
     for job in jobs:
         assert job.status == bauplan.JobState.COMPLETE
+
+
+@pytest.mark.parametrize(
+    "kinds",
+    [
+        "run",
+        "RUN",
+        "Run",
+        "CodeSnapshotRun",
+        "code_snapshot_run",
+        bauplan.JobKind.RUN,
+        [bauplan.JobKind.RUN, "table-plan-apply"],
+        ("Run", bauplan.schema.JobKind.TABLE_PLAN_CREATE_APPLY),
+    ],
+)
+def test_get_jobs_filter_by_kind_spellings(client: bauplan.Client, kinds):
+    jobs = list(client.get_jobs(filter_by_kinds=kinds, limit=5))
+    for job in jobs:
+        assert job.kind in (
+            bauplan.JobKind.RUN,
+            bauplan.JobKind.TABLE_PLAN_CREATE_APPLY,
+        )
+
+
+@pytest.mark.parametrize(
+    "statuses",
+    [
+        "not-started",
+        "NOT_STARTED",
+        "NotStarted",
+        "Not Started",
+        bauplan.JobState.NOT_STARTED,
+        ["complete", bauplan.JobState.FAIL],
+    ],
+)
+def test_get_jobs_filter_by_status_spellings(client: bauplan.Client, statuses):
+    jobs = list(client.get_jobs(filter_by_statuses=statuses, limit=5))
+    for job in jobs:
+        assert job.status in (
+            bauplan.JobState.NOT_STARTED,
+            bauplan.JobState.COMPLETE,
+            bauplan.JobState.FAIL,
+        )
+
+
+def test_get_jobs_filter_invalid(client: bauplan.Client):
+    with pytest.raises(ValueError, match="invalid job kind"):
+        client.get_jobs(filter_by_kinds="import")
+    with pytest.raises(TypeError):
+        client.get_jobs(filter_by_statuses=3)
+
+
+def test_job_enums_at_root():
+    assert bauplan.JobKind is bauplan.schema.JobKind
+    assert bauplan.JobState is bauplan.schema.JobState
+    assert str(bauplan.JobState.NOT_STARTED) == "Not Started"
+    assert str(bauplan.JobKind.RUN) == "Run"
+
+
+def test_get_jobs_order(client: bauplan.Client):
+    newest = list(client.get_jobs(limit=5))
+
+    # Ascending order fetches every matching job, so keep the window small.
+    since = datetime.now(timezone.utc) - timedelta(days=1)
+    oldest = list(client.get_jobs(limit=5, order="asc", filter_by_created_after=since))
+
+    assert len({job.id for job in newest}) == len(newest)
+
+    newest_created = [job.created_at for job in newest if job.created_at]
+    oldest_created = [job.created_at for job in oldest if job.created_at]
+    assert newest_created == sorted(newest_created, reverse=True)
+    assert oldest_created == sorted(oldest_created)
+
+
+def test_get_jobs_filter_by_project(client: bauplan.Client):
+    state = client.run(
+        project_dir="tests/fixtures/simple_taxi_dag",
+        dry_run=True,
+        cache="off",
+    )
+
+    jobs = list(
+        client.get_jobs(
+            filter_by_project="bauplan_prophet", filter_by_kinds="run", limit=20
+        )
+    )
+    assert state.job_id in {job.id for job in jobs}
+    for job in jobs:
+        assert job.project_name == "bauplan_prophet"
+
+
+def test_get_jobs_invalid_order(client: bauplan.Client):
+    with pytest.raises(ValueError):
+        client.get_jobs(order="sideways")  # type: ignore[arg-type]
+
+
+def test_query_to_reader(client: bauplan.Client):
+    reader = client.query_to_reader(
+        "SELECT PassengerId, Name FROM bauplan.titanic ORDER BY PassengerId"
+    )
+    assert reader.schema.names == ["PassengerId", "Name"]
+
+    table = reader.read_all()
+    assert table.num_rows > 0
+    assert table.column("PassengerId")[0].as_py() == 1
+
+
+def test_query_to_reader_empty(client: bauplan.Client):
+    reader = client.query_to_reader(
+        "SELECT PassengerId FROM bauplan.titanic WHERE PassengerId < 0"
+    )
+    assert reader.schema.names == ["PassengerId"]
+    assert reader.read_all().num_rows == 0
+
+
+def test_query_to_reader_close_early(client: bauplan.Client):
+    reader = client.query_to_reader("SELECT * FROM bauplan.titanic")
+    reader.read_next_batch()
+    reader.close()
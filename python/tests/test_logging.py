@@ -0,0 +1,32 @@
+"""Tests for forwarding the client's log events to the logging module."""
+
+import logging
+
+import pytest
+
+import bauplan
+
+
+def test_enable_logging_invalid_level():
+    with pytest.raises(ValueError):
+        bauplan.enable_logging("LOUD")
+
+
+def test_enable_logging_job_id(caplog: pytest.LogCaptureFixture):
+    bauplan.enable_logging("INFO")
+    client = bauplan.Client()
+
+    try:
+        with caplog.at_level(logging.INFO, logger="bauplan"):
+            state = client.run(
+                project_dir="tests/fixtures/simple_taxi_dag",
+                dry_run=True,
+                cache="off",
+            )
+    finally:
+        bauplan.enable_logging("OFF")
+
+    assert state.job_status == "SUCCESS"
+    records = [r for r in caplog.records if r.name == "bauplan"]
+    assert any(getattr(r, "job_id", None) == state.job_id for r in records)
+    assert any(f"job_id={state.job_id}" in r.getMessage() for r in records)
@@ -0,0 +1,61 @@
+"""Tests for namespace operations."""
+
+import uuid
+
+import pytest
+import bauplan
+from bauplan import exceptions
+
+
+@pytest.fixture
+def client() -> bauplan.Client:
+    return bauplan.Client()
+
+
+@pytest.fixture
+def username(client: bauplan.Client):
+    user = client.info().user
+    assert user is not None
+    return user.username
+
+
+@pytest.fixture
+def temp_branch(client: bauplan.Client, username: str):
+    name = f"{username}.pysdk_namespace_{uuid.uuid4().hex[:8]}"
+    client.create_branch(name, from_ref="main")
+    yield name
+    client.delete_branch(name, if_exists=True)
+
+
+def test_create_list_delete_namespace(client: bauplan.Client, temp_branch: str):
+    namespace = client.create_namespace("py_ns", branch=temp_branch)
+    assert namespace.name == "py_ns"
+    assert client.has_namespace("py_ns", ref=temp_branch)
+
+    names = [ns.name for ns in client.get_namespaces(ref=temp_branch)]
+    assert "py_ns" in names
+    assert "bauplan" in names
+
+    filtered = client.get_namespaces(ref=temp_branch, filter_by_name="py_ns")
+    assert [ns.name for ns in filtered] == ["py_ns"]
+
+    with pytest.raises(exceptions.NamespaceExistsError):
+        client.create_namespace("py_ns", branch=temp_branch)
+
+    existing = client.create_namespace("py_ns", branch=temp_branch, if_not_exists=True)
+    assert existing.name == "py_ns"
+
+    head = client.delete_namespace("py_ns", branch=temp_branch)
+    assert head.name == temp_branch
+    assert not client.has_namespace("py_ns", ref=temp_branch)
+
+
+def test_namespace_not_found(client: bauplan.Client, temp_branch: str):
+    with pytest.raises(exceptions.NamespaceNotFoundError):
+        client.get_namespace("no_such_ns", ref=temp_branch)
+
+    with pytest.raises(exceptions.NamespaceNotFoundError):
+        client.delete_namespace("no_such_ns", branch=temp_branch)
+
+    head = client.delete_namespace("no_such_ns", branch=temp_branch, if_exists=True)
+    assert head.name == temp_branch
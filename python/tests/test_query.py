@@ -1,7 +1,13 @@
 """Tests for query operations."""
 
+import datetime
+import os
+import pathlib
+
+import pyarrow as pa
 import pytest
 import bauplan
+from bauplan import exceptions
 
 
 @pytest.fixture
@@ -79,6 +85,150 @@ def test_query_with_max_rows(client: bauplan.Client):
     assert result.num_rows == 3
 
 
+def test_query_to_csv_file(client: bauplan.Client, tmp_path: pathlib.Path):
+    path = tmp_path / "out.csv"
+    result = client.query_to_csv_file(
+        path=path,
+        query="SELECT PassengerId, Name FROM bauplan.titanic LIMIT 5",
+    )
+
+    assert result.path == path
+    assert str(result) == str(path)
+    assert os.fspath(result) == str(path)
+    assert result.rows_written == 5
+    assert result.bytes_written == path.stat().st_size
+    assert result.job_id
+
+
+def test_query_to_file_collision(client: bauplan.Client, tmp_path: pathlib.Path):
+    path = tmp_path / "out.json"
+    path.write_text("existing")
+
+    with pytest.raises(FileExistsError):
+        client.query_to_json_file(
+            path=path,
+            query="SELECT PassengerId FROM bauplan.titanic LIMIT 5",
+        )
+
+    assert path.read_text() == "existing"
+
+    result = client.query_to_json_file(
+        path=path,
+        query="SELECT PassengerId FROM bauplan.titanic LIMIT 5",
+        overwrite=True,
+    )
+
+    assert result.rows_written == 5
+    assert path.read_text() != "existing"
+
+
+def test_query_to_file_placeholders(client: bauplan.Client, tmp_path: pathlib.Path):
+    result = client.query_to_parquet_file(
+        path=tmp_path / "nested" / "{job_id}_%Y.parquet",
+        query="SELECT PassengerId FROM bauplan.titanic LIMIT 3",
+    )
+
+    assert result.path.parent == tmp_path / "nested"
+    assert result.path.name.startswith(f"{result.job_id}_")
+    assert result.path.exists()
+    assert result.rows_written == 3
+
+
+def test_query_to_file_unknown_placeholder(
+    client: bauplan.Client, tmp_path: pathlib.Path
+):
+    with pytest.raises(ValueError):
+        client.query_to_csv_file(
+            path=tmp_path / "{user}.csv",
+            query="SELECT PassengerId FROM bauplan.titanic LIMIT 3",
+        )
+
+
+SCHEMA_QUERY = "SELECT PassengerId, Name FROM bauplan.titanic LIMIT 5"
+
+
+@pytest.fixture
+def titanic_schema(client: bauplan.Client) -> pa.Schema:
+    return client.query(SCHEMA_QUERY).schema
+
+
+def test_query_expected_schema_matches(
+    client: bauplan.Client, titanic_schema: pa.Schema
+):
+    # Field order doesn't matter.
+    expected = pa.schema(reversed(list(titanic_schema)))
+    result = client.query(SCHEMA_QUERY, expected_schema=expected)
+
+    assert result.num_rows == 5
+
+
+def test_query_expected_schema_extra_column(
+    client: bauplan.Client, titanic_schema: pa.Schema
+):
+    expected = pa.schema([titanic_schema.field("Name")])
+    with pytest.raises(exceptions.SchemaMismatchError) as exc_info:
+        client.query(SCHEMA_QUERY, expected_schema=expected)
+
+    assert 'unexpected column "PassengerId"' in str(exc_info.value)
+
+
+def test_query_expected_schema_missing_column(
+    client: bauplan.Client, titanic_schema: pa.Schema
+):
+    expected = titanic_schema.append(pa.field("Cabin", pa.string()))
+    with pytest.raises(exceptions.SchemaMismatchError) as exc_info:
+        client.query(SCHEMA_QUERY, expected_schema=expected)
+
+    assert 'missing column "Cabin"' in str(exc_info.value)
+
+
+def test_query_expected_schema_type_change(
+    client: bauplan.Client, titanic_schema: pa.Schema
+):
+    idx = titanic_schema.get_field_index("Name")
+    expected = titanic_schema.set(idx, pa.field("Name", pa.float32()))
+    with pytest.raises(exceptions.SchemaMismatchError) as exc_info:
+        client.query(SCHEMA_QUERY, expected_schema=expected)
+
+    assert 'column "Name" has type' in str(exc_info.value)
+
+
+def test_query_expected_schema_nullability(
+    client: bauplan.Client, titanic_schema: pa.Schema
+):
+    idx = titanic_schema.get_field_index("Name")
+    field = titanic_schema.field("Name")
+    expected = titanic_schema.set(idx, field.with_nullable(not field.nullable))
+
+    result = client.query(SCHEMA_QUERY, expected_schema=expected)
+    assert result.num_rows == 5
+
+    with pytest.raises(exceptions.SchemaMismatchError):
+        client.query(
+            SCHEMA_QUERY, expected_schema=expected, ignore_nullability=False
+        )
+
+
+def test_scan_expected_schema(client: bauplan.Client, titanic_schema: pa.Schema):
+    result = client.scan(
+        table="titanic",
+        namespace="bauplan",
+        columns=["PassengerId", "Name"],
+        limit=5,
+        expected_schema=titanic_schema,
+    )
+    assert result.num_rows == 5
+
+    with pytest.raises(exceptions.SchemaMismatchError):
+        client.scan(
+            table="titanic",
+            namespace="bauplan",
+            columns=["Name"],
+            limit=5,
+            expected_schema=titanic_schema,
+        )
+
+
 def test_scan_empty_columns(client: bauplan.Client):
     with pytest.raises(ValueError) as exc_info:
         client.scan(
@@ -93,6 +243,44 @@ def test_scan_empty_columns(client: bauplan.Client):
     assert "Empty column list" in str(exc_info.value)
 
 
+def test_get_table_snapshots(client: bauplan.Client):
+    table = client.get_table("titanic", "main", namespace="bauplan")
+    snapshots = list(client.get_table_snapshots("titanic", "main", namespace="bauplan"))
+
+    assert snapshots
+    assert table.current_snapshot_id in [s.snapshot_id for s in snapshots]
+    assert all(isinstance(s.timestamp, datetime.datetime) for s in snapshots)
+
+
+def test_scan_snapshot_refused(client: bauplan.Client):
+    table = client.get_table("titanic", "main", namespace="bauplan")
+    assert table.current_snapshot_id is not None
+
+    with pytest.raises(ValueError, match="as of a snapshot"):
+        client.scan(
+            table="titanic",
+            namespace="bauplan",
+            ref=f"main@{table.current_snapshot_id}",
+        )
+
+    with pytest.raises(ValueError, match="as of a snapshot"):
+        client.query(
+            "SELECT PassengerId FROM bauplan.titanic LIMIT 5",
+            ref=f"main@{table.current_snapshot_id}",
+        )
+
+
+def test_generator_default_limit_warns(client: bauplan.Client):
+    # The cross join has more rows than the default limit of 100,000.
+    query = "SELECT a.PassengerId FROM bauplan.titanic a CROSS JOIN bauplan.titanic b"
+
+    with pytest.warns(exceptions.RowLimitWarning, match="unsafe_full_scan"):
+        rows = list(client.query_to_generator(query))
+
+    assert len(rows) == 100_000
+    assert issubclass(exceptions.RowLimitWarning, UserWarning)
+
+
 def test_scan_returns_arrow_table(client: bauplan.Client):
     result = client.scan(
         table="titanic",
@@ -131,6 +319,10 @@ INVALID_FILTERS = [
     "\x00",
     "Survived = '",
     'Survived = "',
+    "Survived = 1) OR (1 = 1",
+    "Survived = 1 -- comment",
+    "Survived = 1 /* comment */",
+    "Survived, Name",
 ]
 
 
@@ -145,3 +337,194 @@ def test_scan_invalid_filter(client: bauplan.Client, bad_filter: str):
             filters=bad_filter,
             limit=1,
         )
+
+
+
+def test_scan_rejects_multiple_statements(client: bauplan.Client):
+    with pytest.raises(ValueError) as exc_info:
+        client.scan(
+            table="titanic",
+            namespace="bauplan",
+            ref="main",
+            filters="Age < 30; DROP TABLE titanic",
+        )
+
+    assert "multiple statements" in str(exc_info.value)
+
+
+def test_scan_quotes_reserved_word_columns(client: bauplan.Client):
+    # Unquoted, `select` would be a syntax error. Quoted, it's just a column
+    # that doesn't exist.
+    with pytest.raises(exceptions.BauplanQueryError) as exc_info:
+        client.scan(
+            table="titanic",
+            namespace="bauplan",
+            ref="main",
+            columns=["Name", "select"],
+            limit=1,
+        )
+
+    assert '"select"' in str(exc_info.value)
+    assert "syntax error" not in str(exc_info.value).lower()
+
+
+def test_scan_dotted_table_name(client: bauplan.Client):
+    result = client.scan(table="bauplan.titanic", ref="main", limit=5)
+    assert result.num_rows == 5
+
+
+def test_scan_structured_filters(client: bauplan.Client):
+    def scan(filters: str | list) -> pa.Table:
+        return client.scan(
+            table="titanic",
+            namespace="bauplan",
+            ref="main",
+            columns=["PassengerId"],
+            filters=filters,
+        )
+
+    assert scan([("Survived", "=", 1), ("Age", "<", 30)]).num_rows == (
+        scan("Survived = 1 AND Age < 30").num_rows
+    )
+    assert scan([[("Age", "<", 10)], [("Age", ">=", 60)]]).num_rows == (
+        scan("Age < 10 OR Age >= 60").num_rows
+    )
+    assert scan([("PassengerId", "in", [1, 2, 3])]).num_rows == 3
+    assert scan([("Age", "=", None)]).num_rows == scan("Age IS NULL").num_rows
+    assert scan([("Name", "=", "it's; not SQL")]).num_rows == 0
+
+
+INVALID_STRUCTURED_FILTERS = [
+    [],
+    [[]],
+    [("Age", "~", 30)],
+    [("Age", "<", None)],
+    [("Age", "in", 30)],
+    [("Age", "in", [])],
+    [("Age", "<", [1, 2])],
+    [("", "=", 1)],
+]
+
+
+@pytest.mark.parametrize("bad_filter", INVALID_STRUCTURED_FILTERS)
+def test_scan_invalid_structured_filter(client: bauplan.Client, bad_filter: list):
+    with pytest.raises(ValueError):
+        client.scan(
+            table="titanic",
+            namespace="bauplan",
+            ref="main",
+            columns=["PassengerId"],
+            filters=bad_filter,
+            limit=1,
+        )
+
+def test_query_params(client: bauplan.Client):
+    ts = datetime.datetime(2024, 1, 2, 3, 4, 5, tzinfo=datetime.timezone.utc)
+    result = client.query(
+        "SELECT :s AS s, :i AS i, :f AS f, :b AS b, :n AS n, :d AS d, :ts AS ts,"
+        " :blob AS blob, 2 IN :ids AS found",
+        params={
+            "s": "it's -- not /* a */ comment",
+            "i": -3,
+            "f": 1.5,
+            "b": True,
+            "n": None,
+            "d": datetime.date(2024, 1, 2),
+            "ts": ts,
+            "blob": b"\x00'",
+            "ids": [1, 2, 3],
+        },
+    )
+
+    row = result.to_pylist()[0]
+    assert row["s"] == "it's -- not /* a */ comment"
+    assert row["i"] == -3
+    assert row["f"] == 1.5
+    assert row["b"] is True
+    assert row["n"] is None
+    assert row["d"] == datetime.date(2024, 1, 2)
+    assert row["ts"] == ts
+    assert row["blob"] == b"\x00'"
+    assert row["found"] is True
+
+
+def test_query_params_filter(client: bauplan.Client):
+    result = client.query(
+        "SELECT Name FROM bauplan.titanic WHERE Name = :name",
+        params={"name": "x' OR '1'='1"},
+    )
+
+    assert result.num_rows == 0
+
+
+@pytest.mark.parametrize(
+    "params",
+    [
+        {"b": 1},
+        {"a": 1, "b": 2},
+        {"a": []},
+        {"a": [[1]]},
+        {"a": "\x00"},
+        {"not a name": 1},
+    ],
+)
+def test_query_invalid_params(client: bauplan.Client, params: dict):
+    with pytest.raises(ValueError):
+        client.query("SELECT :a IS NULL", params=params)
+
+
+def test_query_unsupported_param(client: bauplan.Client):
+    with pytest.raises(TypeError):
+        client.query("SELECT :a", params={"a": object()})
+
+
+STRING_PARAMS = [
+    "it's",
+    "'; DROP TABLE bauplan.titanic; --",
+    "two\nlines\r\n",
+    "naïve café ☃ 日本語 🦀",
+    "back\\slash '' \"quoted\"",
+]
+
+
+@pytest.mark.parametrize("value", STRING_PARAMS)
+def test_query_string_params(client: bauplan.Client, value: str):
+    named = client.query("SELECT :v AS v", params={"v": value})
+    assert named.to_pylist() == [{"v": value}]
+
+    positional = client.query("SELECT ? AS v, '?' AS q", params=[value])
+    assert positional.to_pylist() == [{"v": value, "q": "?"}]
+
+
+def test_query_positional_params(client: bauplan.Client):
+    rows = client.query_to_generator(
+        "SELECT ? AS s, ? AS i, ? AS n, ? IN ? AS found",
+        params=("x", 7, None, 2, [1, 2]),
+    )
+
+    assert list(rows) == [{"s": "x", "i": 7, "n": None, "found": True}]
+
+
+@pytest.mark.parametrize(
+    "params",
+    [
+        [1, 2],
+        [[]],
+        ["\x00"],
+        [2**70],
+    ],
+)
+def test_query_invalid_positional_params(client: bauplan.Client, params: list):
+    with pytest.raises(ValueError):
+        client.query("SELECT ? IS NULL", params=params)
+
+
+def test_query_invalid_param_names_parameter(client: bauplan.Client):
+    with pytest.raises(ValueError, match=":big"):
+        client.query("SELECT :big", params={"big": 2**70})
+
+    with pytest.raises(ValueError, match="placeholder 2"):
+        client.query("SELECT ?, ?", params=[1, "\x00"])
+
+    with pytest.raises(TypeError, match=":a"):
+        client.query("SELECT :a", params={"a": object()})
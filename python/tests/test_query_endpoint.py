@@ -0,0 +1,76 @@
+"""Tests for handing off query results to another process."""
+
+import datetime
+import pathlib
+
+import pytest
+
+import bauplan
+from bauplan import exceptions
+
+
+@pytest.fixture
+def client() -> bauplan.Client:
+    return bauplan.Client()
+
+
+def test_repr_redacts_token():
+    endpoint = bauplan.QueryResultsEndpoint("flight.example.com:443", "secret-token")
+    assert endpoint.endpoint == "https://flight.example.com:443/"
+    assert endpoint.token == "secret-token"
+    assert endpoint.expires_at is None
+    assert not endpoint.expired
+    assert "secret-token" not in repr(endpoint)
+    assert "secret-token" not in str(endpoint)
+    assert "<redacted>" in repr(endpoint)
+
+
+def test_invalid_endpoint():
+    with pytest.raises(ValueError):
+        bauplan.QueryResultsEndpoint("not a host", "secret-token")
+
+
+def test_expired():
+    now = datetime.datetime.now(datetime.timezone.utc)
+    endpoint = bauplan.QueryResultsEndpoint(
+        "http://127.0.0.1:1",
+        "secret-token",
+        expires_at=now - datetime.timedelta(minutes=1),
+    )
+    assert endpoint.expired
+
+    # Expired results aren't fetched at all.
+    with pytest.raises(exceptions.BauplanError, match="expired"):
+        bauplan.fetch_query_results(endpoint, timeout=1)
+
+    endpoint = bauplan.QueryResultsEndpoint(
+        "http://127.0.0.1:1",
+        "secret-token",
+        expires_at=now + datetime.timedelta(minutes=10),
+    )
+    assert not endpoint.expired
+
+
+def test_client_endpoints(tmp_path: pathlib.Path):
+    config = tmp_path / "config.yaml"
+    config.write_text("profiles:\n  test:\n    api_endpoint: https://example.com\n")
+    client = bauplan.Client(
+        profile="test",
+        api_key="bpln_dummy_key",
+        config_file_path=str(config),
+    )
+
+    assert client.grpc_endpoint == "https://example.com/"
+    assert client.catalog_endpoint == "https://example.com/iceberg"
+
+
+def test_get_query_endpoint_not_a_query(client: bauplan.Client):
+    state = client.run(
+        project_dir="tests/fixtures/simple_taxi_dag",
+        dry_run=True,
+        cache="off",
+    )
+    assert state.job_id is not None
+
+    with pytest.raises(ValueError, match="not a query"):
+        client.get_query_endpoint(state.job_id)
@@ -7,6 +7,7 @@ These tests verify that passing an object produces identical results to passing
 the equivalent string.
 """
 
+import time
 import uuid
 import pytest
 import bauplan
@@ -118,6 +119,50 @@ class TestRefArg:
         assert [t.name for t in tables_str] == [t.name for t in tables_obj]
 
 
+class TestRefShorthands:
+    def test_as_of_duration(
+        self, client: bauplan.Client, temp_branch: bauplan.schema.Branch
+    ):
+        name = temp_branch.name
+        client.create_namespace("shorthand_ns_1", branch=name)
+        first = client.get_branch(name)
+        time.sleep(5)
+        client.create_namespace("shorthand_ns_2", branch=name)
+
+        commits = list(client.get_commits(f"{name}@{{3s ago}}", limit=1))
+        assert commits[0].ref.hash == first.hash
+
+    def test_invalid_time(self, client: bauplan.Client):
+        with pytest.raises(ValueError, match="invalid ref"):
+            client.get_tables(ref="main@{not a time}")
+
+
+class TestTagArg:
+    def test_tag_lifecycle(self, client: bauplan.Client, temp_branch):
+        """A tag works anywhere a ref does, and survives changes to its branch."""
+        name = f"test_tag_{uuid.uuid4().hex[:8]}"
+        tag = client.create_tag(name, from_ref=temp_branch)
+        try:
+            assert tag.type == bauplan.RefType.TAG
+            assert tag.hash == client.get_branch(temp_branch).hash
+            assert client.has_tag(name)
+            assert client.get_tag(tag).hash == tag.hash
+
+            client.delete_table("titanic", branch=temp_branch)
+
+            query = "SELECT COUNT(*) AS n FROM titanic"
+            at_name = client.query(query, ref=name)
+            at_tag = client.query(query, ref=tag)
+            assert at_name.column("n")[0].as_py() > 0
+            assert at_name.equals(at_tag)
+            assert client.has_table("titanic", ref=tag)
+            assert not client.has_table("titanic", ref=temp_branch)
+        finally:
+            assert client.delete_tag(name, if_exists=True)
+
+        assert not client.has_tag(tag)
+
+
 class TestRefTypes:
     """Tests for ref type properties."""
 
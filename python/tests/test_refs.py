@@ -0,0 +1,77 @@
+"""Tests for constructing ref objects directly."""
+
+import pathlib
+
+import pytest
+
+import bauplan
+from bauplan.schema import Branch, DetachedRef, Ref, RefType, Tag
+
+
+def offline_client(tmp_path: pathlib.Path) -> bauplan.Client:
+    config = tmp_path / "config.yaml"
+    config.write_text("profiles:\n  test:\n    api_endpoint: https://example.com\n")
+    return bauplan.Client(
+        profile="test",
+        api_key="bpln_dummy_key",
+        config_file_path=str(config),
+    )
+
+
+def test_constructors():
+    branch = Branch("a.b")
+    assert isinstance(branch, Ref)
+    assert branch.name == "a.b"
+    assert branch.hash is None
+    assert branch.type == RefType.BRANCH
+    assert str(branch) == "a.b"
+    assert repr(branch) == 'Branch(name="a.b", hash=None)'
+
+    tag = Tag(name="v1", hash="abcd")
+    assert tag.type == RefType.TAG
+    assert str(tag) == "v1@abcd"
+    assert repr(tag) == 'Tag(name="v1", hash="abcd")'
+
+    detached = DetachedRef("abcd")
+    assert detached.type == RefType.DETACHED
+    assert str(detached) == "@abcd"
+
+    ref = Ref("main", type=RefType.BRANCH)
+    assert ref.type == RefType.BRANCH
+    assert str(ref) == "main"
+
+
+def test_equality():
+    assert Branch("a.b", "abcd") == Branch("a.b", "abcd")
+    assert Branch("a.b") != Branch("a.b", "abcd")
+    assert Branch("v1") != Tag("v1")
+    assert Branch("a.b") == Ref("a.b", type=RefType.BRANCH)
+    assert Branch("a.b") != "a.b"
+    assert len({Branch("a.b"), Branch("a.b"), Tag("a.b")}) == 2
+
+
+@pytest.mark.parametrize(
+    "make",
+    [
+        lambda: Branch(""),
+        lambda: Tag("v1", hash=""),
+        lambda: Ref("", type=RefType.TAG),
+        lambda: Ref("x", type=RefType.DETACHED),
+    ],
+)
+def test_invalid(make):
+    with pytest.raises(ValueError):
+        make()
+
+
+def test_branch_arg(tmp_path: pathlib.Path):
+    client = offline_client(tmp_path)
+
+    client.active_branch = Branch("a.b", "abcd")
+    assert client.active_branch == "a.b"
+
+    client.active_branch = Ref("c.d", type=RefType.BRANCH)  # type: ignore[assignment]
+    assert client.active_branch == "c.d"
+
+    with pytest.raises(TypeError):
+        client.active_branch = Tag("v1")  # type: ignore[assignment]
@@ -1,5 +1,7 @@
 """Tests for run operations."""
 
+import json
+import pathlib
 import time
 
 import pytest
@@ -40,6 +42,51 @@ def test_dry_run_duration(client: bauplan.Client):
     assert state.duration_ns > 0
 
 
+def test_dry_run_in_memory_project(client: bauplan.Client):
+    project_dir = pathlib.Path("tests/fixtures/simple_taxi_dag")
+    project = {p.name: p.read_bytes() for p in project_dir.iterdir()}
+    project["models.py"] = project["models.py"].decode()
+
+    state = client.run(project=project, dry_run=True, cache="off")
+
+    assert state.job_status == "SUCCESS"
+    assert state.ctx is not None
+    assert state.ctx.project_dir == ""
+
+
+def test_run_project_args(client: bauplan.Client):
+    with pytest.raises(ValueError, match="exactly one of project_dir and project"):
+        client.run()
+
+    with pytest.raises(ValueError, match="exactly one of project_dir and project"):
+        client.run(
+            project_dir="tests/fixtures/simple_taxi_dag",
+            project={"bauplan_project.yml": ""},
+        )
+
+    with pytest.raises(bauplan.exceptions.BauplanJobError, match="no bauplan_project"):
+        client.run(project={"models.py": ""})
+
+
+def test_user_logs_cap():
+    # The project prints two lines; with a cap of one, only the first is kept.
+    client = bauplan.Client(max_user_logs=1)
+    state = client.run(
+        project_dir="tests/fixtures/simple_taxi_dag",
+        dry_run=True,
+        cache="off",
+    )
+
+    assert state.job_status == "SUCCESS"
+    assert state.user_logs_truncated
+    assert len(state.user_logs) == 2
+    assert "Normalizing model" in state.user_logs[0].message
+    assert "1 log messages omitted" in state.user_logs[1].message
+
+    all_logs = client.get_job_logs(state.job_id)
+    assert any("num_rows=" in log.message for log in all_logs)
+
+
 def test_dry_run_tasks(client: bauplan.Client):
     state = client.run(
         project_dir="tests/fixtures/simple_taxi_dag",
@@ -47,8 +94,37 @@ def test_dry_run_tasks(client: bauplan.Client):
         cache="off",
     )
 
-    assert len(state.tasks_started) > 0
-    assert len(state.tasks_stopped) > 0
+    assert len(state.tasks) > 0
+
+    model = next(t for t in state.tasks if t.model_name == "normalize_data")
+    assert model.outcome == "SUCCESS"
+    assert model.file_name is not None
+    assert model.started_at <= model.ended_at
+    assert model.error is None
+    assert not model.cached
+
+    with pytest.warns(DeprecationWarning):
+        started = state.tasks_started
+    with pytest.warns(DeprecationWarning):
+        stopped = state.tasks_stopped
+    assert started[model.task_id] == model.started_at
+    assert stopped[model.task_id] == model.ended_at
+
+
+def test_dry_run_planned_materializations(client: bauplan.Client):
+    state = client.run(
+        project_dir="tests/fixtures/simple_taxi_dag",
+        dry_run=True,
+        cache="off",
+    )
+
+    planned = {m.table_name: m for m in state.planned_materializations}
+    assert "normalize_data" in planned
+    assert planned["normalize_data"].namespace == state.ctx.namespace
+
+    state.assert_writes_within(state.ctx.namespace)
+    with pytest.raises(AssertionError, match="normalize_data"):
+        state.assert_writes_within("not_the_run_namespace")
 
 
 def test_detach(client: bauplan.Client):
@@ -74,6 +150,69 @@ def test_detach(client: bauplan.Client):
     assert job.status == bauplan.JobState.COMPLETE
 
 
+def test_attach(client: bauplan.Client):
+    state = client.run(
+        project_dir="tests/fixtures/simple_taxi_dag",
+        dry_run=True,
+        cache="off",
+        detach=True,
+    )
+    assert state.job_status is None
+
+    attached = client.attach(state)
+    assert attached is state
+    assert state.job_status == "SUCCESS"
+    assert state.ended_at_ns is not None
+    assert state.ctx.detach is True
+    assert any(t.model_name == "normalize_data" for t in state.tasks)
+    assert any("Normalizing model" in log.message for log in state.user_logs)
+
+
+def test_wait_for_finished_job(client: bauplan.Client):
+    finished = client.run(
+        project_dir="tests/fixtures/simple_taxi_dag",
+        dry_run=True,
+        cache="off",
+    )
+    assert finished.job_id is not None
+
+    # The job has already finished, so this rebuilds its state.
+    state = client.wait_for_job(finished.job_id)
+    assert state.job_id == finished.job_id
+    assert state.job_status == "SUCCESS"
+    assert state.ended_at_ns is not None
+    assert {t.task_id for t in state.tasks} == {t.task_id for t in finished.tasks}
+    assert [log.message for log in state.user_logs] == [
+        log.message for log in finished.user_logs
+    ]
+
+
+def test_events_out(client: bauplan.Client, tmp_path):
+    path = tmp_path / "events.jsonl"
+    state = client.run(
+        project_dir="tests/fixtures/simple_taxi_dag",
+        dry_run=True,
+        cache="off",
+        events_out=path,
+    )
+
+    events = [json.loads(line) for line in path.read_text().splitlines()]
+    assert len(events) > 0
+    assert all(e["job_id"] == state.job_id for e in events)
+    assert any(e["kind"] == "TaskStart" for e in events)
+    assert events[-1]["kind"] == "JobCompletion"
+
+
+def test_events_out_detach(client: bauplan.Client, tmp_path):
+    with pytest.raises(ValueError, match="detach"):
+        client.run(
+            project_dir="tests/fixtures/simple_taxi_dag",
+            dry_run=True,
+            detach=True,
+            events_out=tmp_path / "events.jsonl",
+        )
+
+
 def test_cancel_job(client: bauplan.Client):
     state = client.run(
         project_dir="tests/fixtures/long_running_dag",
@@ -117,3 +256,188 @@ def test_job_context_snapshot(client: bauplan.Client):
 
     assert "normalize_data" in ctx.snapshot_dict["models.py"]
     assert "taxi_fhvhv" in ctx.snapshot_dict["query_model.sql"]
+
+
+
+def test_job_dag(client: bauplan.Client):
+    state = client.run(
+        project_dir="tests/fixtures/simple_taxi_dag",
+        dry_run=True,
+        cache="off",
+    )
+
+    assert state.job_id is not None
+    dag = client.get_job_dag(state.job_id)
+
+    assert dag.job_id == state.job_id
+    nodes = {n.name: n for n in dag.nodes}
+    assert "normalize_data" in nodes
+
+    normalize = nodes["normalize_data"]
+    assert normalize.state == "success"
+    assert normalize.error_message is None
+    assert normalize.started_at is not None
+    assert normalize.ended_at is not None
+    assert normalize.started_at <= normalize.ended_at
+
+    ids = {n.id for n in dag.nodes}
+    assert all(e.destination_model in ids for e in dag.edges)
+
+    dot = dag.to_dot()
+    assert dot.startswith("digraph {")
+    assert "normalize_data\\nsuccess" in dot
+
+def test_explain_run(client: bauplan.Client):
+    plan = client.explain_run(project_dir="tests/fixtures/simple_taxi_dag")
+
+    assert plan.job_id
+    models = {m.model_name: m for m in plan.models}
+    assert "normalize_data" in models
+
+    normalize = models["normalize_data"]
+    ids = {m.model_id for m in plan.models}
+    assert all(dep in ids for dep in normalize.depends_on)
+
+
+def test_stream_job_logs(client: bauplan.Client):
+    state = client.run(
+        project_dir="tests/fixtures/simple_taxi_dag",
+        dry_run=True,
+        cache="off",
+        detach=True,
+    )
+
+    assert state.job_id is not None
+    events = list(client.stream_job_logs(state.job_id))
+
+    assert isinstance(events[-1], bauplan.schema.JobLifecycleEvent)
+    assert events[-1].kind == "job_complete"
+    assert events[-1].status == "SUCCESS"
+    assert any(
+        isinstance(ev, bauplan.schema.JobLifecycleEvent) and ev.kind == "task_start"
+        for ev in events
+    )
+
+    streamed = [
+        ev.message for ev in events if isinstance(ev, bauplan.schema.JobLogEvent)
+    ]
+    stored = [log.message for log in client.get_job_logs(state.job_id)]
+    assert any("Normalizing model" in msg for msg in streamed)
+
+    # The stored logs should appear in the same order in the stream.
+    it = iter(streamed)
+    assert all(msg in it for msg in stored)
+
+
+def test_stream_job_logs_close(client: bauplan.Client):
+    state = client.run(
+        project_dir="tests/fixtures/simple_taxi_dag",
+        dry_run=True,
+        cache="off",
+        detach=True,
+    )
+
+    assert state.job_id is not None
+    events = client.stream_job_logs(state.job_id)
+    next(events)
+    events.close()
+
+    # Closing the stream stops iteration, but doesn't cancel the job.
+    assert list(events) == []
+    for _ in range(120):
+        job = client.get_job(state.job_id)
+        if job.status not in (bauplan.JobState.RUNNING, bauplan.JobState.NOT_STARTED):
+            break
+        time.sleep(1)
+
+    assert job.status == bauplan.JobState.COMPLETE
+
+
+def test_run_from_snapshot(client: bauplan.Client):
+    state = client.run(
+        project_dir="tests/fixtures/simple_taxi_dag",
+        dry_run=True,
+        cache="off",
+    )
+
+    assert state.job_status == "SUCCESS"
+    assert state.ctx.snapshot_id
+
+    rerun = client.run_from_snapshot(
+        state.ctx.snapshot_id,
+        dry_run=True,
+        cache="off",
+    )
+
+    assert rerun.job_id is not None
+    assert rerun.job_id != state.job_id
+    assert rerun.job_status == "SUCCESS"
+
+
+def test_run_from_unknown_snapshot(client: bauplan.Client):
+    with pytest.raises(bauplan.exceptions.BauplanJobError):
+        client.run_from_snapshot("0" * 64, dry_run=True)
+
+
+def test_namespace_preflight_exists(client: bauplan.Client):
+    state = client.run(
+        project_dir="tests/fixtures/simple_taxi_dag",
+        ref="main",
+        namespace="bauplan",
+        dry_run=True,
+        cache="off",
+    )
+
+    assert state.job_status == "SUCCESS"
+
+
+def test_namespace_preflight_missing(client: bauplan.Client):
+    with pytest.raises(bauplan.exceptions.NamespaceNotFoundError) as exc_info:
+        client.run(
+            project_dir="tests/fixtures/simple_taxi_dag",
+            ref="main",
+            namespace="bauplna",
+            dry_run=True,
+        )
+
+    assert "did you mean: bauplan?" in exc_info.value.message
+
+
+def test_namespace_preflight_skipped(client: bauplan.Client):
+    try:
+        client.run(
+            project_dir="tests/fixtures/simple_taxi_dag",
+            ref="main",
+            namespace="bauplna",
+            dry_run=True,
+            preflight=False,
+        )
+    except bauplan.exceptions.NamespaceNotFoundError:
+        pytest.fail("preflight=False should skip the namespace check")
+    except bauplan.exceptions.BauplanError:
+        pass
+
+
+def test_fail_on_empty_models(client: bauplan.Client):
+    state = client.run(
+        project_dir="tests/fixtures/empty_model",
+        ref="main",
+        dry_run=True,
+        cache="off",
+        fail_on_empty_models=True,
+    )
+
+    assert state.job_status == "FAILED"
+    assert state.error == "empty output: empty_trips"
+    assert state.empty_models == ["empty_trips"]
+
+    state = client.run(
+        project_dir="tests/fixtures/empty_model",
+        ref="main",
+        dry_run=True,
+        cache="off",
+        fail_on_empty_models=["some_other_model"],
+    )
+
+    assert state.job_status == "SUCCESS"
+    assert state.empty_models == []
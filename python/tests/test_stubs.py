@@ -77,3 +77,30 @@ class TestRuntimeModule:
     )
     def test_module(self, mod: str, cls: str):
         assert getattr(importlib.import_module(mod), cls).__module__ == mod
+
+
+class TestContextAttributes:
+    """The ctx classes on job states should be fully described in the stubs."""
+
+    @pytest.mark.parametrize(
+        "cls,attrs",
+        [
+            ("RunExecutionContext", ["ref", "branch_name", "namespace", "dry_run"]),
+            (
+                "TableCreatePlanContext",
+                ["branch_name", "ref", "table_name", "search_string"],
+            ),
+            (
+                "TableDataImportContext",
+                ["branch_name", "ref", "table_name", "transformation_query"],
+            ),
+            (
+                "ExternalTableCreateContext",
+                ["branch_name", "ref", "table_name", "namespace"],
+            ),
+        ],
+    )
+    def test_attributes(self, cls: str, attrs: list[str]):
+        members = load_module("bauplan.state").classes[cls].members
+        for name in [*attrs, "to_dict", "__eq__", "__repr__"]:
+            assert name in members, name
@@ -0,0 +1,126 @@
+"""Tests for watching branch heads and waiting for tables."""
+
+import threading
+import time
+import uuid
+
+import pytest
+import bauplan
+
+METADATA_URI = (
+    "s3://bauplan-openlake-db87a23/iceberg/tpch_1/"
+    "customer_e53c682c-36c4-4e3d-9ded-1214d0ee157f/"
+    "metadata/00000-b6f502e1-5140-499e-bf83-22f943067e36.metadata.json"
+)
+
+
+@pytest.fixture
+def client() -> bauplan.Client:
+    return bauplan.Client()
+
+
+@pytest.fixture
+def username(client: bauplan.Client):
+    user = client.info().user
+    assert user is not None
+    return user.username
+
+
+@pytest.fixture
+def temp_branch(client: bauplan.Client, username: str):
+    name = f"{username}.pysdk_watch_{uuid.uuid4().hex[:8]}"
+    client.create_branch(name, from_ref="main")
+    yield name
+    client.delete_branch(name, if_exists=True)
+
+
+def test_watch_ref_yields_new_head(client: bauplan.Client, temp_branch: str):
+    watcher = client.watch_ref(temp_branch, poll_interval=0.5, timeout=30)
+
+    def move_head():
+        time.sleep(1)
+        client.create_namespace("watched_ns", branch=temp_branch)
+
+    mover = threading.Thread(target=move_head)
+    mover.start()
+
+    head = next(watcher)
+    mover.join()
+
+    assert head == client.get_branch(temp_branch).hash
+
+
+def test_watch_ref_timeout(client: bauplan.Client, temp_branch: str):
+    start = time.monotonic()
+    heads = list(client.watch_ref(temp_branch, poll_interval=0.5, timeout=2))
+
+    assert heads == []
+    assert time.monotonic() - start < 10
+
+
+def test_watch_ref_invalid_interval(client: bauplan.Client):
+    with pytest.raises(ValueError, match="poll_interval"):
+        client.watch_ref("main", poll_interval=0)
+
+
+def test_watch_ref_not_found(client: bauplan.Client):
+    with pytest.raises(bauplan.exceptions.BranchNotFoundError):
+        client.watch_ref("nonexistent_branch_12345")
+
+
+def test_wait_for_table_ready(client: bauplan.Client):
+    table = client.wait_for_table("bauplan.titanic", "main", timeout=30, min_records=1)
+    assert table.name == "titanic"
+
+
+def test_wait_for_table_created(client: bauplan.Client, temp_branch: str):
+    def create():
+        time.sleep(1)
+        client.create_external_table_from_metadata(
+            table="bauplan.waited_table",
+            metadata_json_uri=METADATA_URI,
+            branch=temp_branch,
+        )
+
+    creator = threading.Thread(target=create)
+    creator.start()
+
+    table = client.wait_for_table(
+        "bauplan.waited_table", temp_branch, timeout=60, poll_interval=0.5
+    )
+    creator.join()
+
+    assert table.name == "waited_table"
+    assert table.records
+
+
+def test_wait_for_table_timeout(client: bauplan.Client, temp_branch: str):
+    start = time.monotonic()
+    with pytest.raises(TimeoutError, match="doesn't exist") as exc_info:
+        client.wait_for_table(
+            "no_such_table", temp_branch, timeout=2, poll_interval=0.5
+        )
+
+    assert exc_info.value.table is None
+    assert time.monotonic() - start < 10
+
+
+def test_wait_for_table_predicate(client: bauplan.Client):
+    seen = []
+
+    def never(table: bauplan.schema.Table) -> bool:
+        seen.append(table)
+        return False
+
+    with pytest.raises(TimeoutError) as exc_info:
+        client.wait_for_table(
+            "bauplan.titanic", "main", timeout=2, poll_interval=0.5, predicate=never
+        )
+
+    assert exc_info.value.table.name == "titanic"
+    assert seen
+
+
+def test_wait_for_table_invalid_predicate(client: bauplan.Client):
+    with pytest.raises(TypeError, match="predicate"):
+        client.wait_for_table("bauplan.titanic", "main", predicate=1)
@@ -0,0 +1,127 @@
+"""Tests for writing Arrow data to tables."""
+
+import os
+import uuid
+
+import pyarrow as pa
+import pytest
+import bauplan
+
+# Staging needs a bucket that both the test's AWS credentials and Bauplan
+# can access, so the round-trip tests only run if one is configured.
+STAGING_URI = os.environ.get("BAUPLAN_TEST_STAGING_URI")
+needs_staging = pytest.mark.skipif(
+    STAGING_URI is None, reason="BAUPLAN_TEST_STAGING_URI is not set"
+)
+
+
+@pytest.fixture
+def client() -> bauplan.Client:
+    return bauplan.Client()
+
+
+@pytest.fixture
+def username(client: bauplan.Client):
+    user = client.info().user
+    assert user is not None
+    return user.username
+
+
+@pytest.fixture
+def temp_branch(client: bauplan.Client, username: str):
+    name = f"{username}.pysdk_write_{uuid.uuid4().hex[:8]}"
+    client.create_branch(name, from_ref="main")
+    yield name
+    client.delete_branch(name, if_exists=True)
+
+
+def test_invalid_mode(client: bauplan.Client):
+    with pytest.raises(ValueError, match="mode must be"):
+        client.write_table(
+            "my_table",
+            pa.table({"id": [1]}),
+            mode="upsert",
+            staging_uri="s3://bucket/staging",
+        )
+
+
+def test_staging_uri_not_s3(client: bauplan.Client):
+    with pytest.raises(ValueError, match="s3://"):
+        client.write_table(
+            "my_table",
+            pa.table({"id": [1]}),
+            staging_uri="/tmp/staging",
+        )
+
+
+def test_schema_mismatch(client: bauplan.Client, temp_branch: str):
+    data = pa.table({"PassengerId": ["not a number"], "Unknown": [1.5]})
+
+    with pytest.raises(bauplan.exceptions.InvalidDataError) as exc_info:
+        client.write_table(
+            "titanic",
+            data,
+            branch=temp_branch,
+            namespace="bauplan",
+            staging_uri="s3://bucket/staging",
+        )
+
+    message = str(exc_info.value)
+    assert '"PassengerId" has type Utf8' in message
+    assert '"Unknown" (Float64) isn\'t in the table' in message
+
+
+@needs_staging
+def test_write_and_append(client: bauplan.Client, temp_branch: str):
+    assert STAGING_URI is not None
+    data = pa.table({"id": [1, 2, 3], "name": ["a", "b", "c"]})
+
+    created = client.write_table(
+        "my_written_table",
+        data,
+        branch=temp_branch,
+        staging_uri=STAGING_URI,
+    )
+
+    assert created.rows_written == 3
+    assert created.ref.name == temp_branch
+    assert created.staged_uri.startswith(STAGING_URI.rstrip("/"))
+
+    appended = client.write_table(
+        "my_written_table",
+        pa.RecordBatchReader.from_batches(data.schema, data.to_batches()),
+        branch=temp_branch,
+        staging_uri=STAGING_URI,
+    )
+
+    assert appended.rows_written == 3
+    assert appended.ref.hash != created.ref.hash
+
+    result = client.query(
+        'SELECT COUNT(*) AS cnt FROM "my_written_table"',
+        ref=temp_branch,
+        cache="off",
+    )
+    assert result.column("cnt")[0].as_py() == 6
+
+
+@needs_staging
+def test_overwrite(client: bauplan.Client, temp_branch: str):
+    assert STAGING_URI is not None
+    data = pa.table({"id": [1, 2, 3]})
+
+    for _ in range(2):
+        client.write_table(
+            "my_overwritten_table",
+            data,
+            branch=temp_branch,
+            mode="overwrite",
+            staging_uri=STAGING_URI,
+        )
+
+    result = client.query(
+        'SELECT COUNT(*) AS cnt FROM "my_overwritten_table"',
+        ref=temp_branch,
+        cache="off",
+    )
+    assert result.column("cnt")[0].as_py() == 3
@@ -4,7 +4,7 @@
 use std::borrow::Cow;
 use std::io::Read;
 
-use percent_encoding::{AsciiSet, CONTROLS, PercentEncode, utf8_percent_encode};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, PercentEncode, utf8_percent_encode};
 use serde::{Deserialize, Serialize};
 use tracing::warn;
 
@@ -15,27 +15,50 @@ pub mod commit;
 mod error;
 pub mod iceberg;
 pub mod namespace;
+mod operation;
 mod paginate;
+mod ratelimit;
+mod refname;
 pub mod table;
 pub mod tag;
+mod wait;
 
 #[cfg(all(test, feature = "_integration-tests"))]
 pub(crate) mod testutil;
 
 pub use error::*;
+pub use operation::*;
 pub use paginate::*;
+pub use ratelimit::*;
+pub use refname::*;
+pub use wait::*;
 
 /// A percent-encoded URL path for an API request.
 #[derive(Debug)]
 pub struct PathArgs(Cow<'static, str>);
 
 fn encode_segment(s: &str) -> PercentEncode<'_> {
-    // WHATWG path percent-encode set (https://url.spec.whatwg.org/#path-percent-encode-set)
-    // extended with `/` and `%` to treat the input as a single segment.
-    const SEGMENT: &AsciiSet = &CONTROLS
-        .add(b' ').add(b'"').add(b'<').add(b'>').add(b'`')
-        .add(b'#').add(b'?').add(b'{').add(b'}')
-        .add(b'/').add(b'%');
+    // Everything but the characters allowed in a path segment by RFC 3986
+    // (https://www.rfc-editor.org/rfc/rfc3986#section-3.3), so that servers
+    // and proxies can't split or rewrite it; some treat `\` as `/`. `+` is
+    // encoded too, since some servers decode it as a space.
+    const SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+        .remove(b'-')
+        .remove(b'.')
+        .remove(b'_')
+        .remove(b'~')
+        .remove(b'!')
+        .remove(b'$')
+        .remove(b'&')
+        .remove(b'\'')
+        .remove(b'(')
+        .remove(b')')
+        .remove(b'*')
+        .remove(b',')
+        .remove(b';')
+        .remove(b'=')
+        .remove(b':')
+        .remove(b'@');
     utf8_percent_encode(s, SEGMENT)
 }
 
@@ -93,6 +116,17 @@ pub trait ApiRequest: Sized {
         None::<&()>
     }
 
+    /// Additional headers to send with the request.
+    fn headers(&self) -> http::HeaderMap {
+        http::HeaderMap::new()
+    }
+
+    /// Checks the request before it's sent, for mistakes that the API would
+    /// report less clearly, like a malformed [`Ref`].
+    fn validate(&self) -> Result<(), ApiError> {
+        Ok(())
+    }
+
     /// Consume the request and return an [http::Request] suitable for passing
     /// to your favorite HTTP client.
     fn into_request(self, profile: &Profile) -> Result<http::Request<String>, http::Error> {
@@ -123,6 +157,14 @@ pub trait ApiRequest: Sized {
             .uri(uri)
             .header(http::header::USER_AGENT, &profile.user_agent);
 
+        for (name, value) in &self.headers() {
+            req = req.header(name, value);
+        }
+
+        for (name, value) in &profile.extra_headers {
+            req = req.header(name, value);
+        }
+
         if let Some(key) = &profile.api_key {
             req = req.header(http::header::AUTHORIZATION, format!("Bearer {}", key));
         } else {
@@ -177,6 +219,11 @@ impl<T: DataResponse> ApiResponse for T {
         parts: http::response::Parts,
         body: impl Read,
     ) -> Result<Self, ApiError> {
+        // Returned for conditional requests, with an empty body.
+        if parts.status == http::StatusCode::NOT_MODIFIED {
+            return Err(ApiError::NotModified);
+        }
+
         let raw: RawApiResponse<serde_json::Value> =
             serde_json::from_reader(body).map_err(|e| {
                 tracing::error!("Failed to parse API response: {e}");
@@ -221,6 +268,24 @@ impl ApiResponse for CatalogRef {
 mod tests {
     use std::borrow::Cow;
 
+    use super::ApiRequest as _;
+    use crate::{
+        ApiError, ApiErrorKind, CommitOptions, Profile,
+        branch::{GetBranch, MergeBranch},
+        table::{DeleteTable, GetTable, GetTables, RevertTable},
+        tag::GetTag,
+    };
+
+    fn dummy_profile(tmp: &tempfile::TempDir) -> anyhow::Result<Profile> {
+        let path = tmp.path().join("config.yaml");
+        std::fs::write(
+            &path,
+            "profiles:\n  default:\n    api_key: bpln_dummy_key\n",
+        )?;
+
+        Ok(Profile::read(&path, None)?)
+    }
+
     #[test]
     fn urlformat_static_is_borrowed() {
         let p = urlformat!("/catalog/v0/branches").0;
@@ -240,6 +305,11 @@ mod tests {
         assert_eq!(urlformat!("/{}", "a b").0, "/a%20b");
         assert_eq!(urlformat!("/{}", "100%").0, "/100%25");
         assert_eq!(urlformat!("/{}", "café").0, "/caf%C3%A9");
+        assert_eq!(urlformat!("/{}", "a+b").0, "/a%2Bb");
+        assert_eq!(urlformat!("/{}", "a\\b").0, "/a%5Cb");
+        assert_eq!(urlformat!("/{}", "a|b^[0]").0, "/a%7Cb%5E%5B0%5D");
+        assert_eq!(urlformat!("/{}", "a#b?c").0, "/a%23b%3Fc");
+        assert_eq!(urlformat!("/{}", "k=v,w;x:y").0, "/k=v,w;x:y");
         // Iceberg multi-level namespace separator (U+001F) must encode to %1F.
         assert_eq!(urlformat!("/{}", "a\u{1f}b").0, "/a%1Fb");
         // Multi-arg: each segment is encoded independently.
@@ -248,4 +318,188 @@ mod tests {
             "/refs/feature%2Ffoo/namespaces/a%20b",
         );
     }
+
+    #[test]
+    fn special_characters_in_paths() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let profile = dummy_profile(&tmp)?;
+
+        let req = DeleteTable {
+            name: "a/b c+d",
+            branch: "user.feature#1",
+            namespace: Some("x y"),
+            commit: CommitOptions::default(),
+        }
+        .into_request(&profile)?;
+
+        assert_eq!(
+            req.uri().path(),
+            "/catalog/v0/branches/user.feature%231/tables/a%2Fb%20c%2Bd",
+        );
+        assert_eq!(req.uri().query(), Some("namespace=x+y"));
+
+        let req = RevertTable {
+            name: "orders\\..\\x",
+            source_ref: "main@abc?x=1",
+            into_branch: "user.a|b",
+            replace: false,
+            namespace: None,
+            commit: CommitOptions::default(),
+            operation_id: None,
+        }
+        .into_request(&profile)?;
+
+        assert_eq!(
+            req.uri().path(),
+            "/catalog/v0/refs/main@abc%3Fx=1/tables/orders%5C..%5Cx/revert/user.a%7Cb",
+        );
+        assert_eq!(req.uri().query(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn unusual_refs_in_paths() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let profile = dummy_profile(&tmp)?;
+
+        for (at_ref, encoded) in [
+            ("main@abc123", "main@abc123"),
+            ("@abc123", "@abc123"),
+            ("main~3", "main~3"),
+            ("feature/foo", "feature%2Ffoo"),
+            ("feature/foo@abc123~1", "feature%2Ffoo@abc123~1"),
+        ] {
+            let req = GetTable {
+                name: "t",
+                at_ref,
+                namespace: None,
+            };
+            req.validate()?;
+            assert_eq!(
+                req.into_request(&profile)?.uri().path(),
+                format!("/catalog/v0/refs/{encoded}/tables/t"),
+            );
+
+            let req = GetTables {
+                at_ref,
+                filter_by_name: None,
+                filter_by_namespace: None,
+            };
+            req.validate()?;
+            assert_eq!(
+                req.into_request(&profile)?.uri().path(),
+                format!("/catalog/v0/refs/{encoded}/tables"),
+            );
+
+            let req = MergeBranch {
+                source_ref: at_ref,
+                into_branch: "user/dev",
+                commit: Default::default(),
+                operation_id: None,
+            };
+            req.validate()?;
+            assert_eq!(
+                req.into_request(&profile)?.uri().path(),
+                format!("/catalog/v0/refs/{encoded}/merge/user%2Fdev"),
+            );
+        }
+
+        let req = GetBranch {
+            name: "feature/foo",
+            if_none_match: None,
+        };
+        req.validate()?;
+        assert_eq!(
+            req.into_request(&profile)?.uri().path(),
+            "/catalog/v0/branches/feature%2Ffoo",
+        );
+
+        let req = GetTag { name: "v1.0/rc" };
+        req.validate()?;
+        assert_eq!(
+            req.into_request(&profile)?.uri().path(),
+            "/catalog/v0/tags/v1.0%2Frc",
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_rejects_bad_refs() {
+        let invalid_ref = |res: Result<(), ApiError>| {
+            matches!(
+                res,
+                Err(ApiError::ErrorResponse {
+                    kind: ApiErrorKind::InvalidRef { .. },
+                    ..
+                })
+            )
+        };
+
+        for at_ref in ["", "main@", "main~x", "my branch", "main@abc?x=1"] {
+            let req = GetTable {
+                name: "t",
+                at_ref,
+                namespace: None,
+            };
+            assert!(invalid_ref(req.validate()), "{at_ref:?}");
+        }
+
+        let req = GetBranch {
+            name: "main@abc123",
+            if_none_match: None,
+        };
+        assert!(invalid_ref(req.validate()));
+
+        let req = RevertTable {
+            name: "t",
+            source_ref: "main@abc123",
+            into_branch: "main~1",
+            replace: false,
+            namespace: None,
+            commit: CommitOptions::default(),
+            operation_id: None,
+        };
+        assert!(invalid_ref(req.validate()));
+
+        let req = GetTag { name: "" };
+        assert!(invalid_ref(req.validate()));
+    }
+
+    #[test]
+    fn special_characters_in_queries() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let profile = dummy_profile(&tmp)?;
+
+        let req = GetTables {
+            at_ref: "main",
+            filter_by_name: Some("a b+c&d=e"),
+            filter_by_namespace: None,
+        }
+        .into_request(&profile)?;
+
+        assert_eq!(req.uri().query(), Some("filter_by_name=a+b%2Bc%26d%3De"));
+        Ok(())
+    }
+
+    #[test]
+    fn extra_headers() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let profile =
+            dummy_profile(&tmp)?.with_extra_header("X-Org-Request-Context", "ctx", false)?;
+
+        let req = GetBranch {
+            name: "main",
+            if_none_match: None,
+        }
+        .into_request(&profile)?;
+
+        assert_eq!(req.headers()["x-org-request-context"], "ctx");
+        let auth = req.headers().get_all(http::header::AUTHORIZATION);
+        assert_eq!(auth.iter().collect::<Vec<_>>(), ["Bearer bpln_dummy_key"]);
+
+        let clobbered = profile.with_extra_header("Authorization", "Bearer other", false);
+        assert!(clobbered.is_err());
+        Ok(())
+    }
 }
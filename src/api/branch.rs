@@ -4,11 +4,17 @@
 /// calling user.
 pub const CURRENT_USER: &str = "~";
 
+use std::time::Duration;
+
+use rand::Rng as _;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     CatalogRef, PaginatedResponse,
-    api::{ApiRequest, DataResponse, PathArgs, urlformat},
+    api::{
+        ApiError, ApiErrorKind, ApiRequest, DataResponse, OPERATION_ID_PROPERTY, OperationRequest,
+        PathArgs, Ref, operation_headers, urlformat,
+    },
 };
 
 /// A branch in the catalog.
@@ -27,6 +33,10 @@ impl DataResponse for Branch {}
 pub struct GetBranch<'a> {
     /// The name of the branch to fetch.
     pub name: &'a str,
+
+    /// The last seen head hash. If the head still points there, the request
+    /// fails with [`ApiError::NotModified`](crate::ApiError::NotModified).
+    pub if_none_match: Option<&'a str>,
 }
 
 impl ApiRequest for GetBranch<'_> {
@@ -35,6 +45,67 @@ impl ApiRequest for GetBranch<'_> {
     fn path(&self) -> PathArgs {
         urlformat!("/catalog/v0/branches/{}", self.name)
     }
+
+    fn validate(&self) -> Result<(), ApiError> {
+        Ref::name(self.name)?;
+        Ok(())
+    }
+
+    fn headers(&self) -> http::HeaderMap {
+        let mut headers = http::HeaderMap::new();
+        if let Some(hash) = self.if_none_match
+            && let Ok(etag) = format!("\"{hash}\"").parse()
+        {
+            headers.insert(http::header::IF_NONE_MATCH, etag);
+        }
+
+        headers
+    }
+}
+
+/// The delays between polls when watching a branch head with
+/// [`GetBranch::if_none_match`].
+///
+/// Each delay is jittered by up to 20% in either direction, so that many
+/// watchers started at once don't poll in lockstep. After failed polls, the
+/// delay doubles each time, up to [`WatchSchedule::MAX_BACKOFF`].
+#[derive(Debug, Clone)]
+pub struct WatchSchedule {
+    interval: Duration,
+    failures: u32,
+}
+
+impl WatchSchedule {
+    /// The longest delay after repeated failures.
+    pub const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+    /// Creates a schedule that polls every `interval`.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            failures: 0,
+        }
+    }
+
+    /// Returns the delay after a successful poll, resetting any backoff.
+    pub fn succeeded(&mut self) -> Duration {
+        self.failures = 0;
+        jitter(self.interval)
+    }
+
+    /// Returns the delay after a failed poll.
+    pub fn failed(&mut self) -> Duration {
+        self.failures = self.failures.saturating_add(1);
+        let backoff = self
+            .interval
+            .saturating_mul(1 << self.failures.min(16))
+            .min(Self::MAX_BACKOFF.max(self.interval));
+        jitter(backoff)
+    }
+}
+
+fn jitter(d: Duration) -> Duration {
+    d.mul_f64(rand::thread_rng().gen_range(0.8..=1.2))
 }
 
 /// List branches.
@@ -105,6 +176,24 @@ impl ApiRequest for CreateBranch<'_> {
     }
 }
 
+impl CreateBranch<'_> {
+    /// The branch that was already there, if the request failed with
+    /// `BRANCH_EXISTS`. Any other error, including other conflicts, returns
+    /// `None`.
+    pub fn existing(err: &ApiError) -> Option<Branch> {
+        match err.kind()? {
+            ApiErrorKind::BranchExists {
+                catalog_ref: CatalogRef::Branch { name, hash },
+                ..
+            } => Some(Branch {
+                name: name.clone(),
+                hash: hash.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
 /// Delete a branch.
 #[derive(Debug, Clone)]
 pub struct DeleteBranch<'a> {
@@ -122,6 +211,18 @@ impl ApiRequest for DeleteBranch<'_> {
     fn path(&self) -> PathArgs {
         urlformat!("/catalog/v0/branches/{}", self.name)
     }
+
+    fn validate(&self) -> Result<(), ApiError> {
+        Ref::name(self.name)?;
+        Ok(())
+    }
+}
+
+impl DeleteBranch<'_> {
+    /// Whether the request failed with `BRANCH_NOT_FOUND`.
+    pub fn missing(err: &ApiError) -> bool {
+        matches!(err.kind(), Some(ApiErrorKind::BranchNotFound { .. }))
+    }
 }
 
 /// Rename a branch.
@@ -150,6 +251,11 @@ impl ApiRequest for RenameBranch<'_> {
         urlformat!("/catalog/v0/branches/{}", self.name)
     }
 
+    fn validate(&self) -> Result<(), ApiError> {
+        Ref::name(self.name)?;
+        Ok(())
+    }
+
     fn body(&self) -> Option<impl Serialize> {
         Some(RenameBranchBody {
             branch_name: self.new_name,
@@ -168,6 +274,10 @@ pub struct MergeBranch<'a> {
 
     /// Override the commit message or add custom properties.
     pub commit: MergeCommitOptions<'a>,
+
+    /// A client-generated ID, so that the merge can be retried safely. See
+    /// [`send_operation`](crate::send_operation).
+    pub operation_id: Option<&'a str>,
 }
 
 /// Options for a merge commit.
@@ -201,8 +311,60 @@ impl ApiRequest for MergeBranch<'_> {
         )
     }
 
+    fn validate(&self) -> Result<(), ApiError> {
+        Ref::parse(self.source_ref)?;
+        Ref::name(self.into_branch)?;
+        Ok(())
+    }
+
     fn body(&self) -> Option<impl Serialize> {
-        Some(self.commit.clone())
+        let mut commit = self.commit.clone();
+        if let Some(id) = self.operation_id {
+            commit.commit_properties.insert(OPERATION_ID_PROPERTY, id);
+        }
+
+        Some(commit)
+    }
+
+    fn headers(&self) -> http::HeaderMap {
+        operation_headers(self.operation_id)
+    }
+}
+
+impl OperationRequest for MergeBranch<'_> {
+    fn operation_id(&self) -> Option<&str> {
+        self.operation_id
+    }
+
+    fn into_branch(&self) -> &str {
+        self.into_branch
+    }
+}
+
+/// A table changed on both sides of a merge, listed in a `MERGE_CONFLICT` error.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(module = "bauplan.exceptions", from_py_object, get_all)
+)]
+pub struct MergeConflictTable {
+    /// The fully-qualified table name.
+    pub table_name: String,
+    /// The hash of the last commit to change the table on the source ref.
+    pub source_hash: Option<String>,
+    /// The hash of the last commit to change the table on the destination
+    /// branch.
+    pub destination_hash: Option<String>,
+}
+
+#[cfg(feature = "python")]
+#[pyo3::pymethods]
+impl MergeConflictTable {
+    fn __repr__(&self) -> String {
+        format!(
+            "MergeConflictTable(table_name={:?}, source_hash={:?}, destination_hash={:?})",
+            self.table_name, self.source_hash, self.destination_hash,
+        )
     }
 }
 
@@ -217,7 +379,10 @@ mod test {
 
     #[test]
     fn get_branch() -> anyhow::Result<()> {
-        let req = GetBranch { name: "main" };
+        let req = GetBranch {
+            name: "main",
+            if_none_match: None,
+        };
 
         let branch: Branch = roundtrip(req)?;
         assert_eq!(branch.name, "main");
@@ -226,10 +391,33 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn get_branch_not_modified() -> anyhow::Result<()> {
+        let head = roundtrip(GetBranch {
+            name: "main",
+            if_none_match: None,
+        })?;
+
+        let req = GetBranch {
+            name: "main",
+            if_none_match: Some(&head.hash),
+        };
+
+        // main may move between the two requests.
+        match roundtrip(req) {
+            Err(ApiError::NotModified) => (),
+            Ok(branch) => assert_ne!(branch.hash, head.hash),
+            Err(e) => return Err(e.into()),
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn get_branch_not_found() -> anyhow::Result<()> {
         let req = GetBranch {
             name: "nonexistent_branch_12345",
+            if_none_match: None,
         };
 
         let Err(ApiError::ErrorResponse {
@@ -289,7 +477,10 @@ mod test {
         assert_eq!(created.name, branch_name);
 
         // Verify it exists.
-        let req = GetBranch { name: &branch_name };
+        let req = GetBranch {
+            name: &branch_name,
+            if_none_match: None,
+        };
         let fetched = roundtrip(req)?;
         assert_eq!(fetched.name, branch_name);
         assert_eq!(fetched.hash, created.hash);
@@ -300,7 +491,10 @@ mod test {
         assert_eq!(deleted.name, branch_name);
 
         // Verify it's gone.
-        let req = GetBranch { name: &branch_name };
+        let req = GetBranch {
+            name: &branch_name,
+            if_none_match: None,
+        };
 
         let Err(ApiError::ErrorResponse {
             kind: ApiErrorKind::BranchNotFound { branch_name: name },
@@ -377,7 +571,10 @@ mod test {
         assert_eq!(renamed.name, new_name);
 
         // Verify old name is gone.
-        let req = GetBranch { name: &old_name };
+        let req = GetBranch {
+            name: &old_name,
+            if_none_match: None,
+        };
 
         let Err(ApiError::ErrorResponse {
             kind: ApiErrorKind::BranchNotFound { branch_name },
@@ -412,6 +609,7 @@ mod test {
             source_ref: &source.name,
             into_branch: &target.name,
             commit: Default::default(),
+            operation_id: None,
         };
         let result = roundtrip(req)?;
 
@@ -432,12 +630,82 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn merge_conflict() -> anyhow::Result<()> {
+        use crate::table::{DeleteTable, RevertTable};
+
+        let source = TestBranch::new("test_conflict_src")?;
+        let target = TestBranch::new("test_conflict_dst")?;
+
+        // Change the same table on both branches: drop it on the source, and
+        // drop and restore it on the target.
+        for branch in [&source.name, &target.name] {
+            let req = DeleteTable {
+                name: "titanic",
+                branch,
+                namespace: Some("bauplan"),
+                commit: Default::default(),
+            };
+            roundtrip(req)?;
+        }
+
+        let req = RevertTable {
+            name: "titanic",
+            source_ref: "main",
+            into_branch: &target.name,
+            namespace: Some("bauplan"),
+            replace: false,
+            commit: Default::default(),
+            operation_id: None,
+        };
+        let CatalogRef::Branch {
+            hash: target_hash, ..
+        } = roundtrip(req)?
+        else {
+            panic!("expected Branch ref");
+        };
+
+        let req = MergeBranch {
+            source_ref: &source.name,
+            into_branch: &target.name,
+            commit: Default::default(),
+            operation_id: None,
+        };
+
+        let Err(ApiError::ErrorResponse {
+            kind:
+                ApiErrorKind::MergeConflict {
+                    source_ref,
+                    destination_ref,
+                    conflicts,
+                },
+            ..
+        }) = roundtrip(req)
+        else {
+            panic!("expected MERGE_CONFLICT");
+        };
+
+        assert_matches!(source_ref, CatalogRef::Branch { name, .. } if name == source.name);
+        assert_matches!(destination_ref, CatalogRef::Branch { name, .. } if name == target.name);
+
+        let [conflict] = conflicts.as_slice() else {
+            panic!("expected one conflict, got {conflicts:?}");
+        };
+
+        assert_eq!(conflict.table_name, "bauplan.titanic");
+        assert!(conflict.source_hash.is_some());
+        assert_eq!(conflict.destination_hash, Some(target_hash));
+
+        Ok(())
+    }
+
     #[test]
     fn merge_branch_not_found() -> anyhow::Result<()> {
         let req = MergeBranch {
             source_ref: "main",
             into_branch: "nonexistent_branch_12345",
             commit: Default::default(),
+            operation_id: None,
         };
 
         let Err(ApiError::ErrorResponse {
@@ -461,6 +729,7 @@ mod test {
             source_ref: "main",
             into_branch: &tag.name,
             commit: Default::default(),
+            operation_id: None,
         };
 
         let Err(ApiError::ErrorResponse {
@@ -495,3 +764,32 @@ mod test {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::WatchSchedule;
+
+    #[test]
+    fn jittered_interval() {
+        let mut schedule = WatchSchedule::new(Duration::from_secs(10));
+        for _ in 0..100 {
+            let d = schedule.succeeded();
+            assert!(d >= Duration::from_secs(8) && d <= Duration::from_secs(12));
+        }
+    }
+
+    #[test]
+    fn backoff_grows_and_resets() {
+        let mut schedule = WatchSchedule::new(Duration::from_secs(10));
+        let first = schedule.failed();
+        assert!(first >= Duration::from_secs(16));
+
+        for _ in 0..20 {
+            assert!(schedule.failed() <= WatchSchedule::MAX_BACKOFF.mul_f64(1.2));
+        }
+
+        assert!(schedule.succeeded() <= Duration::from_secs(12));
+    }
+}
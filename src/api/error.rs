@@ -1,6 +1,9 @@
+use std::time::Duration;
+
 use serde::Deserialize;
 
-use crate::CatalogRef;
+use super::{RateLimit, ratelimit::retry_summary};
+use crate::{CatalogRef, branch::MergeConflictTable};
 
 /// A typed API error kind, deserialized from the `type` and `context` fields
 /// of an error response.
@@ -84,6 +87,8 @@ pub enum ApiErrorKind {
     MergeConflict {
         source_ref: CatalogRef,
         destination_ref: CatalogRef,
+        #[serde(default)]
+        conflicts: Vec<MergeConflictTable>,
     },
     NamespaceExists {
         namespace_name: String,
@@ -193,6 +198,22 @@ pub enum ApiError {
     },
     /// The API response was invalid.
     InvalidResponse(http::StatusCode),
+    /// The resource is unchanged since the version passed in a conditional
+    /// request.
+    NotModified,
+    /// The API kept rate limiting requests until the [`RetryBudget`] ran out.
+    ///
+    /// [`RetryBudget`]: super::RetryBudget
+    RateLimited {
+        /// The error from the last response.
+        error: Box<ApiError>,
+        /// The rate limit reported on the last response.
+        limit: RateLimit,
+        /// The number of retries made with the budget.
+        retries: u32,
+        /// The total time spent waiting with the budget.
+        waited: Duration,
+    },
 }
 
 impl std::fmt::Display for ApiError {
@@ -224,6 +245,17 @@ impl std::fmt::Display for ApiError {
             ApiError::InvalidResponse(status) => {
                 write!(f, "Invalid response ({status})")?;
             }
+            ApiError::NotModified => {
+                write!(f, "Not modified")?;
+            }
+            ApiError::RateLimited {
+                error,
+                retries,
+                waited,
+                ..
+            } => {
+                write!(f, "{error} ({})", retry_summary(*retries, *waited))?;
+            }
         }
 
         Ok(())
@@ -269,13 +301,32 @@ impl ApiError {
         match self {
             ApiError::ErrorResponse { status, .. } | ApiError::Other { status, .. } => *status,
             ApiError::InvalidResponse(status) => *status,
+            ApiError::NotModified => http::StatusCode::NOT_MODIFIED,
+            ApiError::RateLimited { error, .. } => error.status(),
         }
     }
 
+    /// Whether the request might succeed if retried later: the server was
+    /// overloaded, rate limited the request, or failed internally.
+    pub fn is_transient(&self) -> bool {
+        let status = self.status();
+        status == http::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+    }
+
+    /// Whether the request may have been applied even though it failed,
+    /// because a gateway gave up waiting for the server.
+    pub fn response_lost(&self) -> bool {
+        matches!(
+            self.status(),
+            http::StatusCode::BAD_GATEWAY | http::StatusCode::GATEWAY_TIMEOUT
+        )
+    }
+
     /// Extract server error context, if any is available.
     pub fn kind(&self) -> Option<&ApiErrorKind> {
         match self {
             ApiError::ErrorResponse { kind, .. } => Some(kind),
+            ApiError::RateLimited { error, .. } => error.kind(),
             _ => None,
         }
     }
@@ -332,6 +383,27 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn raw_table_not_found_no_ref() -> anyhow::Result<()> {
+        let raw: RawApiError = serde_json::from_str(
+            r#"{
+                "message": "table not found",
+                "type": "TABLE_NOT_FOUND",
+                "context": { "table_name": "foo", "input_ref": "main" }
+            }"#,
+        )?;
+
+        // Without the ref, the kind can't be parsed, but callers can still
+        // tell what kind of error it was.
+        let err = ApiError::from_raw(http::StatusCode::NOT_FOUND, raw);
+        assert_matches!(
+            &err,
+            ApiError::Other { kind: Some(kind), .. } if kind == "TABLE_NOT_FOUND"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn raw_unknown_error_kind() -> anyhow::Result<()> {
         let raw: RawApiError = serde_json::from_str(
@@ -362,4 +434,145 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn raw_merge_conflict() -> anyhow::Result<()> {
+        let raw: RawApiError = serde_json::from_str(
+            r#"{
+                "message": "merge conflict",
+                "type": "MERGE_CONFLICT",
+                "context": {
+                    "source_ref": { "type": "BRANCH", "name": "u.dev", "hash": "abc" },
+                    "destination_ref": { "type": "BRANCH", "name": "main", "hash": "def" },
+                    "conflicts": [
+                        {
+                            "table_name": "bauplan.titanic",
+                            "source_hash": "abc",
+                            "destination_hash": "def"
+                        }
+                    ]
+                }
+            }"#,
+        )?;
+
+        let err = ApiError::from_raw(http::StatusCode::CONFLICT, raw);
+        let ApiError::ErrorResponse {
+            kind: ApiErrorKind::MergeConflict { conflicts, .. },
+            ..
+        } = &err
+        else {
+            bail!("expected MERGE_CONFLICT, got {err:?}");
+        };
+
+        assert_eq!(
+            conflicts,
+            &[MergeConflictTable {
+                table_name: "bauplan.titanic".to_owned(),
+                source_hash: Some("abc".to_owned()),
+                destination_hash: Some("def".to_owned()),
+            }]
+        );
+
+        // Older servers don't list the conflicts.
+        let raw: RawApiError = serde_json::from_str(
+            r#"{
+                "type": "MERGE_CONFLICT",
+                "context": {
+                    "source_ref": { "type": "BRANCH", "name": "u.dev", "hash": "abc" },
+                    "destination_ref": { "type": "BRANCH", "name": "main", "hash": "def" }
+                }
+            }"#,
+        )?;
+
+        let err = ApiError::from_raw(http::StatusCode::CONFLICT, raw);
+        assert_matches!(
+            err.kind(),
+            Some(ApiErrorKind::MergeConflict { conflicts, .. }) if conflicts.is_empty()
+        );
+
+        Ok(())
+    }
+
+    fn raw(status: http::StatusCode, json: &str) -> ApiError {
+        ApiError::from_raw(status, serde_json::from_str(json).unwrap())
+    }
+
+    #[test]
+    fn existing_only_for_exact_kind() {
+        use crate::{branch::CreateBranch, namespace::CreateNamespace, tag::CreateTag};
+
+        let branch_exists = raw(
+            http::StatusCode::CONFLICT,
+            r#"{
+                "type": "BRANCH_EXISTS",
+                "context": {
+                    "branch_name": "u.dev",
+                    "ref": { "type": "BRANCH", "name": "u.dev", "hash": "abc" }
+                }
+            }"#,
+        );
+        let namespace_exists = raw(
+            http::StatusCode::CONFLICT,
+            r#"{
+                "type": "NAMESPACE_EXISTS",
+                "context": {
+                    "namespace_name": "raw",
+                    "ref": { "type": "BRANCH", "name": "u.dev", "hash": "abc" }
+                }
+            }"#,
+        );
+        let forbidden = raw(
+            http::StatusCode::FORBIDDEN,
+            r#"{ "type": "CREATE_BRANCH_FORBIDDEN" }"#,
+        );
+
+        let branch = CreateBranch::existing(&branch_exists).unwrap();
+        assert_eq!(branch.name, "u.dev");
+        assert_eq!(branch.hash, "abc");
+        let namespace = CreateNamespace::existing(&namespace_exists).unwrap();
+        assert_eq!(namespace.name, "raw");
+
+        // Another 409 isn't the same thing.
+        assert!(CreateBranch::existing(&namespace_exists).is_none());
+        assert!(CreateNamespace::existing(&branch_exists).is_none());
+        assert!(CreateTag::existing(&branch_exists).is_none());
+
+        assert!(CreateBranch::existing(&forbidden).is_none());
+        assert!(CreateNamespace::existing(&forbidden).is_none());
+    }
+
+    #[test]
+    fn missing_table() {
+        use crate::table::DeleteTable;
+
+        let with_ref = raw(
+            http::StatusCode::NOT_FOUND,
+            r#"{
+                "type": "TABLE_NOT_FOUND",
+                "context": {
+                    "table_name": "foo",
+                    "input_ref": "main",
+                    "ref": { "type": "BRANCH", "name": "main", "hash": "abc" }
+                }
+            }"#,
+        );
+        let without_ref = raw(
+            http::StatusCode::NOT_FOUND,
+            r#"{
+                "type": "TABLE_NOT_FOUND",
+                "context": { "table_name": "foo", "input_ref": "main" }
+            }"#,
+        );
+        let forbidden = raw(
+            http::StatusCode::FORBIDDEN,
+            r#"{ "type": "DELETE_TABLE_FORBIDDEN" }"#,
+        );
+
+        assert_matches!(
+            DeleteTable::missing(&with_ref),
+            Some(Some(CatalogRef::Branch { name, .. })) if name == "main"
+        );
+        assert_matches!(DeleteTable::missing(&without_ref), Some(None));
+        assert_matches!(DeleteTable::missing(&forbidden), None);
+    }
 }
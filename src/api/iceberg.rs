@@ -4,7 +4,10 @@
 //! Not all catalog operations are represented here; only the ones which are
 //! presently used in the CLI or PySDK.
 
+use std::collections::HashMap;
+
 use iceberg_catalog_rest::{LoadTableResult as IcebergTable, RegisterTableRequest};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
 use crate::{
     ApiRequest, ApiResponse,
@@ -51,34 +54,128 @@ impl ApiRequest for RegisterTable<'_> {
     }
 }
 
+/// The property the catalog sets to the namespace tables are in when none
+/// is given.
+const DEFAULT_NAMESPACE_PROPERTY: &str = "default-namespace";
+
+/// Fetch the catalog configuration for a branch.
+#[derive(Debug, Clone)]
+pub struct GetCatalogConfig<'a> {
+    /// The branch (or other ref) to fetch the configuration for.
+    pub branch: &'a str,
+}
+
+#[derive(Serialize)]
+struct GetCatalogConfigQuery<'a> {
+    warehouse: &'a str,
+}
+
+impl ApiRequest for GetCatalogConfig<'_> {
+    type Response = CatalogConfig;
+
+    fn path(&self) -> PathArgs {
+        urlformat!("/iceberg/v1/config")
+    }
+
+    fn query(&self) -> Option<impl Serialize> {
+        Some(GetCatalogConfigQuery {
+            warehouse: self.branch,
+        })
+    }
+}
+
+/// The configuration the catalog hands to clients.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CatalogConfig {
+    /// Properties that the client should use unless it sets its own.
+    #[serde(default)]
+    pub defaults: HashMap<String, String>,
+    /// Properties that take precedence over the client's own.
+    #[serde(default)]
+    pub overrides: HashMap<String, String>,
+}
+
+impl CatalogConfig {
+    /// The namespace that tables are in when none is given, if the catalog
+    /// has one.
+    pub fn default_namespace(&self) -> Option<&str> {
+        self.overrides
+            .get(DEFAULT_NAMESPACE_PROPERTY)
+            .or_else(|| self.defaults.get(DEFAULT_NAMESPACE_PROPERTY))
+            .map(String::as_str)
+            .filter(|ns| !ns.is_empty())
+    }
+}
+
 impl ApiResponse for IcebergTable {
     fn from_response_parts(
         parts: http::response::Parts,
         body: impl std::io::Read,
     ) -> Result<Self, super::ApiError> {
-        if parts.status.is_success() {
-            serde_json::from_reader(body).map_err(|e| {
-                tracing::error!("Failed to parse iceberg response: {e:#?}");
-                super::ApiError::InvalidResponse(parts.status)
-            })
-        } else {
-            let raw: super::RawApiResponse<serde_json::Value> = serde_json::from_reader(body)
-                .map_err(|_| super::ApiError::Other {
-                    status: parts.status,
-                    kind: None,
-                    message: None,
-                })?;
-
-            match raw {
-                super::RawApiResponse::Error { error } => {
-                    Err(super::ApiError::from_raw(parts.status, error))
-                }
-                _ => Err(super::ApiError::Other {
-                    status: parts.status,
-                    kind: None,
-                    message: None,
-                }),
+        from_iceberg_response(parts, body)
+    }
+}
+
+impl ApiResponse for CatalogConfig {
+    fn from_response_parts(
+        parts: http::response::Parts,
+        body: impl std::io::Read,
+    ) -> Result<Self, super::ApiError> {
+        from_iceberg_response(parts, body)
+    }
+}
+
+/// Parses a response from the iceberg catalog, which isn't wrapped like the
+/// rest of the API's responses.
+fn from_iceberg_response<T: DeserializeOwned>(
+    parts: http::response::Parts,
+    body: impl std::io::Read,
+) -> Result<T, super::ApiError> {
+    if parts.status.is_success() {
+        serde_json::from_reader(body).map_err(|e| {
+            tracing::error!("Failed to parse iceberg response: {e:#?}");
+            super::ApiError::InvalidResponse(parts.status)
+        })
+    } else {
+        let raw: super::RawApiResponse<serde_json::Value> =
+            serde_json::from_reader(body).map_err(|_| super::ApiError::Other {
+                status: parts.status,
+                kind: None,
+                message: None,
+            })?;
+
+        match raw {
+            super::RawApiResponse::Error { error } => {
+                Err(super::ApiError::from_raw(parts.status, error))
             }
+            _ => Err(super::ApiError::Other {
+                status: parts.status,
+                kind: None,
+                message: None,
+            }),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_namespace() {
+        let config: CatalogConfig = serde_json::from_str(
+            r#"{"defaults": {"default-namespace": "bauplan"}, "overrides": {"prefix": "main"}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.default_namespace(), Some("bauplan"));
+
+        let config: CatalogConfig = serde_json::from_str(
+            r#"{"defaults": {"default-namespace": "bauplan"}, "overrides": {"default-namespace": "raw"}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.default_namespace(), Some("raw"));
+
+        let config: CatalogConfig = serde_json::from_str(r#"{"overrides": {}}"#).unwrap();
+        assert_eq!(config.default_namespace(), None);
+    }
+}
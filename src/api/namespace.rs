@@ -4,7 +4,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     CatalogRef, PaginatedResponse,
-    api::{ApiRequest, DataResponse, PathArgs, commit::CommitOptions, urlformat},
+    api::{
+        ApiError, ApiErrorKind, ApiRequest, DataResponse, PathArgs, commit::CommitOptions,
+        urlformat,
+    },
 };
 
 /// A container for organizing tables.
@@ -80,6 +83,58 @@ impl ApiRequest for GetNamespaces<'_> {
     }
 }
 
+/// The most namespaces to list when checking that a namespace exists.
+pub const NAMESPACE_CHECK_LIMIT: usize = 1000;
+
+/// The result of looking for a namespace in a listing, with
+/// [`check_namespace`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NamespaceCheck {
+    /// The namespace was listed.
+    Found,
+    /// The namespace wasn't listed. The listing was complete, so it doesn't
+    /// exist.
+    Missing {
+        /// The listed namespaces with similar names, closest first.
+        suggestions: Vec<String>,
+    },
+    /// The namespace wasn't listed, but the listing was cut short, so it may
+    /// still exist.
+    Unknown,
+}
+
+/// Looks for `name` in a single page of namespaces, typically the first
+/// [`NAMESPACE_CHECK_LIMIT`] on a ref. Used as a cheap pre-flight check
+/// before running a job in that namespace.
+pub fn check_namespace(name: &str, listing: PaginatedResponse<Namespace>) -> NamespaceCheck {
+    const MAX_SUGGESTIONS: usize = 3;
+    const MIN_SIMILARITY: f64 = 0.7;
+
+    if listing.page.iter().any(|ns| ns.name == name) {
+        return NamespaceCheck::Found;
+    } else if listing.pagination_token.is_some() {
+        return NamespaceCheck::Unknown;
+    }
+
+    let mut scored: Vec<(f64, String)> = listing
+        .page
+        .into_iter()
+        .filter_map(|ns| {
+            let score = strsim::normalized_damerau_levenshtein(name, &ns.name);
+            (score >= MIN_SIMILARITY).then_some((score, ns.name))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+    NamespaceCheck::Missing {
+        suggestions: scored
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, name)| name)
+            .collect(),
+    }
+}
+
 /// Create a namespace on a branch.
 #[derive(Debug, Clone)]
 pub struct CreateNamespace<'a> {
@@ -119,6 +174,20 @@ impl ApiRequest for CreateNamespace<'_> {
     }
 }
 
+impl CreateNamespace<'_> {
+    /// The namespace that was already there, if the request failed with
+    /// `NAMESPACE_EXISTS`. Any other error, including other conflicts,
+    /// returns `None`.
+    pub fn existing(err: &ApiError) -> Option<Namespace> {
+        match err.kind()? {
+            ApiErrorKind::NamespaceExists { namespace_name, .. } => Some(Namespace {
+                name: namespace_name.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
 /// Delete a namespace from a branch.
 #[derive(Debug, Clone)]
 pub struct DeleteNamespace<'a> {
@@ -160,6 +229,76 @@ impl ApiRequest for DeleteNamespace<'_> {
     }
 }
 
+impl DeleteNamespace<'_> {
+    /// The ref the namespace was looked up on, if the request failed with
+    /// `NAMESPACE_NOT_FOUND`.
+    pub fn missing(err: &ApiError) -> Option<&CatalogRef> {
+        match err.kind()? {
+            ApiErrorKind::NamespaceNotFound { catalog_ref, .. } => Some(catalog_ref),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing(names: &[&str], truncated: bool) -> PaginatedResponse<Namespace> {
+        PaginatedResponse {
+            page: names
+                .iter()
+                .map(|name| Namespace {
+                    name: (*name).to_owned(),
+                })
+                .collect(),
+            pagination_token: truncated.then(|| "next".to_owned()),
+        }
+    }
+
+    #[test]
+    fn check_found() {
+        let names = ["bauplan", "analytics"];
+        assert_eq!(
+            check_namespace("analytics", listing(&names, false)),
+            NamespaceCheck::Found
+        );
+        assert_eq!(
+            check_namespace("analytics", listing(&names, true)),
+            NamespaceCheck::Found
+        );
+    }
+
+    #[test]
+    fn check_missing_with_suggestions() {
+        let names = ["analytics", "analytic", "bauplan", "staging"];
+        assert_eq!(
+            check_namespace("anlytics", listing(&names, false)),
+            NamespaceCheck::Missing {
+                suggestions: vec!["analytics".to_owned(), "analytic".to_owned()],
+            }
+        );
+    }
+
+    #[test]
+    fn check_missing_without_suggestions() {
+        assert_eq!(
+            check_namespace("finance", listing(&["bauplan", "staging"], false)),
+            NamespaceCheck::Missing {
+                suggestions: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn check_truncated() {
+        assert_eq!(
+            check_namespace("anlytics", listing(&["analytics"], true)),
+            NamespaceCheck::Unknown
+        );
+    }
+}
+
 #[cfg(all(test, feature = "_integration-tests"))]
 mod test {
     use super::*;
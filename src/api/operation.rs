@@ -0,0 +1,398 @@
+//! Retrying merges and reverts safely.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use tracing::{info, warn};
+
+use crate::{
+    CatalogRef, PaginatedResponse,
+    api::{
+        ApiError, ApiRequest, PaginatedRequest,
+        commit::{Commit, GetCommits},
+    },
+};
+
+/// The commit property that records the operation ID of a merge or revert,
+/// so that a retry can tell whether an earlier attempt was applied.
+pub const OPERATION_ID_PROPERTY: &str = "bauplan.operation_id";
+
+/// The header that carries the operation ID, for the server to deduplicate
+/// retries.
+pub const OPERATION_ID_HEADER: &str = "idempotency-key";
+
+/// The most times an operation is sent, when responses are lost.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// The delay before the first retry, doubled on each retry.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// How many of the latest commits on the branch are searched for an
+/// operation ID.
+const SEARCH_LIMIT: usize = 100;
+
+/// Generates a new operation ID, for [`OperationRequest::operation_id`].
+pub fn new_operation_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// The headers for a request with an operation ID.
+pub(crate) fn operation_headers(operation_id: Option<&str>) -> http::HeaderMap {
+    let mut headers = http::HeaderMap::new();
+    if let Some(id) = operation_id
+        && let Ok(value) = id.parse()
+    {
+        headers.insert(OPERATION_ID_HEADER, value);
+    }
+
+    headers
+}
+
+/// Implemented by requests that commit to a branch, and can be retried
+/// safely if they carry an operation ID.
+pub trait OperationRequest: ApiRequest<Response = CatalogRef> + Clone {
+    /// The client-generated ID for the operation, if any. It's sent as the
+    /// [`OPERATION_ID_HEADER`], and recorded on the commit as the
+    /// [`OPERATION_ID_PROPERTY`].
+    fn operation_id(&self) -> Option<&str>;
+
+    /// The branch that the request commits to.
+    fn into_branch(&self) -> &str;
+}
+
+/// The outcome of [`send_operation`].
+#[derive(Debug, Clone)]
+pub enum OperationResult {
+    /// The operation was applied by this call.
+    Applied(CatalogRef),
+    /// The operation had already been applied by an attempt whose response
+    /// was lost. The ref points to the commit it made.
+    AlreadyApplied(CatalogRef),
+}
+
+impl OperationResult {
+    /// The resulting ref, however the operation was applied.
+    pub fn into_ref(self) -> CatalogRef {
+        match self {
+            OperationResult::Applied(r) | OperationResult::AlreadyApplied(r) => r,
+        }
+    }
+}
+
+/// Sends a merge or revert with `send`, retrying if the response is lost.
+///
+/// A lost response, as decided by `lost`, doesn't mean the operation failed:
+/// it may have been applied before a gateway timed out. So before sending it
+/// again, the commits on the destination branch are fetched with
+/// `fetch_batch` and searched for the operation ID. If an earlier attempt was
+/// applied, that's returned as [`OperationResult::AlreadyApplied`]. The
+/// same check is made if a retry fails outright, since a merge that was
+/// already applied fails as a no-op or a conflict.
+///
+/// Requests without an operation ID are sent once, as-is. `sleep` is called
+/// to wait between attempts.
+pub fn send_operation<R, F, E>(
+    req: R,
+    mut send: impl FnMut(R) -> Result<CatalogRef, E>,
+    fetch_batch: F,
+    lost: impl Fn(&E) -> bool,
+    mut sleep: impl FnMut(Duration),
+) -> Result<OperationResult, E>
+where
+    R: OperationRequest,
+    F: Fn(PaginatedRequest<'_, GetCommits<'_>>) -> Result<PaginatedResponse<Commit>, E>,
+    E: From<ApiError>,
+{
+    let Some(operation_id) = req.operation_id().map(str::to_owned) else {
+        return send(req).map(OperationResult::Applied);
+    };
+
+    let branch = req.into_branch().to_owned();
+    let mut attempt = 1;
+    loop {
+        let err = match send(req.clone()) {
+            Ok(r) => return Ok(OperationResult::Applied(r)),
+            Err(e) => e,
+        };
+
+        let is_lost = lost(&err);
+        if attempt == 1 && !is_lost {
+            return Err(err);
+        }
+
+        if let Some(hash) = find_operation(&branch, &operation_id, &fetch_batch)? {
+            info!(%operation_id, %branch, %hash, "operation was already applied");
+            return Ok(OperationResult::AlreadyApplied(CatalogRef::Branch {
+                name: branch,
+                hash,
+            }));
+        }
+
+        if !is_lost || attempt >= MAX_ATTEMPTS {
+            return Err(err);
+        }
+
+        let delay = RETRY_DELAY * 2u32.pow(attempt - 1);
+        warn!(%operation_id, attempt, ?delay, "response lost, retrying");
+        sleep(delay);
+        attempt += 1;
+    }
+}
+
+/// Searches the latest commits on `branch` for one made by the operation,
+/// and returns its hash.
+fn find_operation<F, E>(
+    branch: &str,
+    operation_id: &str,
+    fetch_batch: F,
+) -> Result<Option<String>, E>
+where
+    F: Fn(PaginatedRequest<'_, GetCommits<'_>>) -> Result<PaginatedResponse<Commit>, E>,
+    E: From<ApiError>,
+{
+    let properties = BTreeMap::from([(OPERATION_ID_PROPERTY.to_owned(), operation_id.to_owned())]);
+
+    let req = GetCommits {
+        at_ref: branch,
+        filter_by_message: None,
+        filter_by_author_username: None,
+        filter_by_author_name: None,
+        filter_by_author_email: None,
+        filter_by_authored_date: None,
+        filter_by_authored_date_start_at: None,
+        filter_by_authored_date_end_at: None,
+        filter_by_parent_hash: None,
+        filter_by_properties: Some(&properties),
+        filter: None,
+    };
+
+    // Check the property as well, in case the server ignores the filter.
+    for commit in crate::paginate(req, Some(SEARCH_LIMIT), fetch_batch)? {
+        let commit = commit?;
+        if commit
+            .properties
+            .get(OPERATION_ID_PROPERTY)
+            .map(String::as_str)
+            == Some(operation_id)
+        {
+            return Ok(Some(commit.hash().to_owned()));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+
+    use chrono::Utc;
+
+    use super::*;
+    use crate::api::{
+        branch::{MergeBranch, MergeCommitOptions},
+        commit::Actor,
+    };
+
+    const GATEWAY_TIMEOUT: ApiError = ApiError::Other {
+        status: http::StatusCode::GATEWAY_TIMEOUT,
+        kind: None,
+        message: None,
+    };
+
+    fn merge(operation_id: Option<&str>) -> MergeBranch<'_> {
+        MergeBranch {
+            source_ref: "a.feature",
+            into_branch: "main",
+            commit: MergeCommitOptions::default(),
+            operation_id,
+        }
+    }
+
+    fn commit(hash: &str, operation_id: Option<&str>) -> Commit {
+        let actor = Actor {
+            name: "a".to_owned(),
+            email: None,
+        };
+
+        Commit {
+            r#ref: CatalogRef::Branch {
+                name: "main".to_owned(),
+                hash: hash.to_owned(),
+            },
+            message: None,
+            authors: vec![actor.clone()],
+            authored_date: Utc::now(),
+            committer: actor,
+            committed_date: Utc::now(),
+            parent_ref: CatalogRef::Detached {
+                hash: "parent".to_owned(),
+            },
+            parent_hashes: Vec::new(),
+            properties: operation_id
+                .map(|id| BTreeMap::from([(OPERATION_ID_PROPERTY.to_owned(), id.to_owned())]))
+                .unwrap_or_default(),
+            signed_off_by: Vec::new(),
+        }
+    }
+
+    /// A fake branch: `send` applies the merge, but can lose the response.
+    struct Server {
+        commits: RefCell<Vec<Commit>>,
+        sent: Cell<u32>,
+        lose_responses: u32,
+    }
+
+    impl Server {
+        fn new(lose_responses: u32) -> Self {
+            Self {
+                commits: RefCell::new(vec![commit("base", None)]),
+                sent: Cell::new(0),
+                lose_responses,
+            }
+        }
+
+        fn send(&self, req: MergeBranch<'_>) -> Result<CatalogRef, ApiError> {
+            self.sent.set(self.sent.get() + 1);
+
+            // Merging twice fails; the source is already merged.
+            let applied = req.operation_id.is_some()
+                && self.commits.borrow().iter().any(|c| {
+                    c.properties.get(OPERATION_ID_PROPERTY).map(String::as_str) == req.operation_id
+                });
+            if applied {
+                return Err(ApiError::Other {
+                    status: http::StatusCode::CONFLICT,
+                    kind: None,
+                    message: None,
+                });
+            }
+
+            let hash = format!("merge{}", self.sent.get());
+            self.commits
+                .borrow_mut()
+                .insert(0, commit(&hash, req.operation_id));
+
+            if self.sent.get() <= self.lose_responses {
+                return Err(GATEWAY_TIMEOUT);
+            }
+
+            Ok(CatalogRef::Branch {
+                name: "main".to_owned(),
+                hash,
+            })
+        }
+
+        fn run(&self, req: MergeBranch<'_>) -> Result<OperationResult, ApiError> {
+            send_operation(
+                req,
+                |req| self.send(req),
+                |_| {
+                    Ok(PaginatedResponse {
+                        page: self.commits.borrow().clone(),
+                        pagination_token: None,
+                    })
+                },
+                ApiError::response_lost,
+                |_| (),
+            )
+        }
+    }
+
+    #[test]
+    fn lost_response_already_applied() {
+        let server = Server::new(1);
+        let res = server.run(merge(Some("op1"))).unwrap();
+
+        // The merge isn't sent again.
+        assert_eq!(server.sent.get(), 1);
+        assert_eq!(server.commits.borrow().len(), 2);
+        assert!(matches!(
+            res,
+            OperationResult::AlreadyApplied(CatalogRef::Branch { hash, .. }) if hash == "merge1"
+        ));
+    }
+
+    #[test]
+    fn lost_response_not_applied() {
+        let server = Server::new(0);
+
+        // The first attempt never reaches the server.
+        let dropped = Cell::new(false);
+        let res = send_operation(
+            merge(Some("op1")),
+            |req| {
+                if dropped.replace(true) {
+                    server.send(req)
+                } else {
+                    Err(GATEWAY_TIMEOUT)
+                }
+            },
+            |_| {
+                Ok(PaginatedResponse {
+                    page: server.commits.borrow().clone(),
+                    pagination_token: None,
+                })
+            },
+            ApiError::response_lost,
+            |_| (),
+        )
+        .unwrap();
+
+        assert!(matches!(res, OperationResult::Applied(_)));
+        assert_eq!(server.sent.get(), 1);
+        assert_eq!(server.commits.borrow().len(), 2);
+    }
+
+    #[test]
+    fn other_errors_not_retried() {
+        let sent = Cell::new(0);
+        let res = send_operation(
+            merge(Some("op1")),
+            |_| {
+                sent.set(sent.get() + 1);
+                Err(ApiError::Other {
+                    status: http::StatusCode::CONFLICT,
+                    kind: None,
+                    message: None,
+                })
+            },
+            |_| panic!("commits shouldn't be fetched"),
+            ApiError::response_lost,
+            |_| (),
+        );
+
+        assert!(res.is_err());
+        assert_eq!(sent.get(), 1);
+    }
+
+    #[test]
+    fn gives_up() {
+        let sent = Cell::new(0);
+        let res = send_operation(
+            merge(Some("op1")),
+            |_| {
+                sent.set(sent.get() + 1);
+                Err(GATEWAY_TIMEOUT)
+            },
+            |_| {
+                Ok(PaginatedResponse {
+                    page: vec![commit("base", None)],
+                    pagination_token: None,
+                })
+            },
+            ApiError::response_lost,
+            |_| (),
+        );
+
+        assert!(matches!(res, Err(e) if e.response_lost()));
+        assert_eq!(sent.get(), MAX_ATTEMPTS);
+    }
+
+    #[test]
+    fn no_operation_id() {
+        let server = Server::new(1);
+        let res = server.run(merge(None));
+        assert!(matches!(res, Err(e) if e.response_lost()));
+        assert_eq!(server.sent.get(), 1);
+    }
+}
@@ -59,6 +59,10 @@ impl<T: ApiRequest> ApiRequest for PaginatedRequest<'_, T> {
             max_records: self.limit,
         })
     }
+
+    fn validate(&self) -> Result<(), ApiError> {
+        self.base.validate()
+    }
 }
 
 /// A possibly partial response, with a pagination token.
@@ -0,0 +1,337 @@
+use std::{io::Read, sync::Mutex, time::Duration};
+
+use serde::de::IgnoredAny;
+use tracing::debug;
+
+use super::{ApiError, RawApiResponse};
+
+/// The first backoff delay, doubled on each retry.
+const BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// The longest backoff delay, not counting `Retry-After`.
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// The rate limit reported in the headers of a 429 response.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimit {
+    /// How long the server asked us to wait, from `Retry-After`.
+    pub retry_after: Option<Duration>,
+    /// The number of requests allowed in the current window.
+    pub limit: Option<u64>,
+    /// The number of requests left in the current window.
+    pub remaining: Option<u64>,
+    /// How long until the current window resets.
+    pub reset: Option<Duration>,
+}
+
+impl RateLimit {
+    /// Reads the rate limit from response headers. Both the `X-RateLimit-*`
+    /// and the unprefixed `RateLimit-*` headers are understood.
+    pub fn from_headers(headers: &http::HeaderMap) -> Self {
+        let get = |name: &str| {
+            headers
+                .get(format!("x-ratelimit-{name}"))
+                .or_else(|| headers.get(format!("ratelimit-{name}")))
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+
+        Self {
+            retry_after: headers
+                .get(http::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_retry_after),
+            limit: get("limit"),
+            remaining: get("remaining"),
+            reset: get("reset").map(Duration::from_secs),
+        }
+    }
+}
+
+/// Parses a `Retry-After` value, which is either a number of seconds or an
+/// HTTP date.
+fn parse_retry_after(v: &str) -> Option<Duration> {
+    let v = v.trim();
+    if let Ok(secs) = v.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let at = chrono::DateTime::parse_from_rfc2822(v).ok()?;
+    let delta = at.signed_duration_since(chrono::Utc::now());
+    Some(delta.to_std().unwrap_or_default())
+}
+
+/// A limit on the total time spent waiting out rate limits, shared by all
+/// the requests made with it.
+///
+/// Share one budget across the requests for a single operation, so that a
+/// long-running loop can't retry forever.
+#[derive(Debug)]
+pub struct RetryBudget {
+    max_wait: Duration,
+    state: Mutex<BudgetState>,
+}
+
+#[derive(Debug, Default)]
+struct BudgetState {
+    retries: u32,
+    waited: Duration,
+}
+
+impl Default for RetryBudget {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_MAX_WAIT)
+    }
+}
+
+impl RetryBudget {
+    /// The default total time to spend waiting.
+    pub const DEFAULT_MAX_WAIT: Duration = Duration::from_secs(60);
+
+    /// Creates a budget allowing up to `max_wait` of waiting in total. A zero
+    /// budget disables retries.
+    pub fn new(max_wait: Duration) -> Self {
+        Self {
+            max_wait,
+            state: Mutex::default(),
+        }
+    }
+
+    /// The number of retries made so far.
+    pub fn retries(&self) -> u32 {
+        self.state.lock().unwrap().retries
+    }
+
+    /// The total time spent waiting so far.
+    pub fn waited(&self) -> Duration {
+        self.state.lock().unwrap().waited
+    }
+
+    /// Summarizes the retries made so far, like "rate limited; retried 12
+    /// times over 43s", or `None` if there weren't any.
+    pub fn summary(&self) -> Option<String> {
+        let state = self.state.lock().unwrap();
+        (state.retries > 0).then(|| retry_summary(state.retries, state.waited))
+    }
+
+    /// Reserves the delay before retry number `attempt` (counting from 0) of
+    /// a request, or returns `None` if that would exceed the budget.
+    ///
+    /// The delay is exponential in `attempt`, scaled by `jitter` (in [0, 1])
+    /// to between half and all of it, and at least the server's
+    /// `Retry-After`.
+    fn reserve(&self, attempt: u32, limit: &RateLimit, jitter: f64) -> Option<Duration> {
+        let backoff = BASE_DELAY
+            .saturating_mul(1u32 << attempt.min(16))
+            .min(MAX_DELAY)
+            .mul_f64(0.5 + jitter / 2.0);
+        let delay = limit.retry_after.map_or(backoff, |d| d.max(backoff));
+
+        let mut state = self.state.lock().unwrap();
+        if state.waited + delay > self.max_wait {
+            return None;
+        }
+
+        state.retries += 1;
+        state.waited += delay;
+        Some(delay)
+    }
+}
+
+pub(crate) fn retry_summary(retries: u32, waited: Duration) -> String {
+    let times = if retries == 1 { "time" } else { "times" };
+    format!(
+        "rate limited; retried {retries} {times} over {}s",
+        waited.as_secs_f64().round()
+    )
+}
+
+/// Sends a request with `send`, waiting and retrying while the API responds
+/// with 429 Too Many Requests, until `budget` runs out.
+///
+/// If it does, the result is an [`ApiError::RateLimited`]. Any other response
+/// is returned as-is. `sleep` is called to wait between attempts.
+pub fn send_with_backoff<B: Read, E: From<ApiError>>(
+    budget: &RetryBudget,
+    req: http::Request<String>,
+    mut send: impl FnMut(http::Request<String>) -> Result<http::Response<B>, E>,
+    mut sleep: impl FnMut(Duration),
+) -> Result<http::Response<B>, E> {
+    let mut attempt = 0;
+    loop {
+        let resp = send(req.clone())?;
+        if resp.status() != http::StatusCode::TOO_MANY_REQUESTS {
+            return Ok(resp);
+        }
+
+        let limit = RateLimit::from_headers(resp.headers());
+        let Some(delay) = budget.reserve(attempt, &limit, rand::random()) else {
+            return Err(ApiError::RateLimited {
+                error: Box::new(error_from_response(resp)),
+                limit,
+                retries: budget.retries(),
+                waited: budget.waited(),
+            }
+            .into());
+        };
+
+        debug!(uri = %req.uri(), ?delay, attempt, "rate limited, retrying");
+        sleep(delay);
+        attempt += 1;
+    }
+}
+
+/// Reads the error from a non-success response.
+fn error_from_response(resp: http::Response<impl Read>) -> ApiError {
+    let (parts, body) = resp.into_parts();
+    match serde_json::from_reader(body) {
+        Ok(RawApiResponse::<IgnoredAny>::Error { error }) => {
+            ApiError::from_raw(parts.status, error)
+        }
+        _ => ApiError::Other {
+            status: parts.status,
+            kind: None,
+            message: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    const RATE_LIMITED: &str =
+        r#"{"error": {"type": "TOO_MANY_REQUESTS", "message": "slow down"}}"#;
+
+    fn response(status: u16, headers: &[(&str, &str)]) -> http::Response<&'static [u8]> {
+        let mut resp = http::Response::builder().status(status);
+        for (name, value) in headers {
+            resp = resp.header(*name, *value);
+        }
+
+        let body = if status == 429 { RATE_LIMITED } else { "{}" };
+        resp.body(body.as_bytes()).unwrap()
+    }
+
+    /// Sends a request to a fake server returning `responses` in order,
+    /// returning the result and the delays slept.
+    fn simulate(
+        budget: &RetryBudget,
+        responses: Vec<http::Response<&'static [u8]>>,
+    ) -> (Result<http::StatusCode, ApiError>, Vec<Duration>) {
+        let mut responses = responses.into_iter();
+        let slept = RefCell::new(Vec::new());
+        let res = send_with_backoff(
+            budget,
+            http::Request::new(String::new()),
+            |_| Ok::<_, ApiError>(responses.next().expect("too many requests sent")),
+            |d| slept.borrow_mut().push(d),
+        );
+
+        (res.map(|r| r.status()), slept.into_inner())
+    }
+
+    #[test]
+    fn retries_until_success() {
+        let budget = RetryBudget::default();
+        let (res, slept) = simulate(
+            &budget,
+            vec![response(429, &[]), response(429, &[]), response(200, &[])],
+        );
+
+        assert_eq!(res.unwrap(), 200);
+        assert_eq!(slept.len(), 2);
+        assert!(slept[0] >= BASE_DELAY / 2 && slept[0] <= BASE_DELAY);
+        assert!(slept[1] >= BASE_DELAY && slept[1] <= BASE_DELAY * 2);
+        assert_eq!(budget.retries(), 2);
+        assert_eq!(budget.waited(), slept.iter().sum());
+        assert!(
+            budget
+                .summary()
+                .unwrap()
+                .starts_with("rate limited; retried 2 times")
+        );
+    }
+
+    #[test]
+    fn honors_retry_after() {
+        let budget = RetryBudget::default();
+        let (res, slept) = simulate(
+            &budget,
+            vec![response(429, &[("retry-after", "7")]), response(200, &[])],
+        );
+
+        assert_eq!(res.unwrap(), 200);
+        assert_eq!(slept, [Duration::from_secs(7)]);
+    }
+
+    #[test]
+    fn budget_exhausted() {
+        let budget = RetryBudget::new(Duration::from_secs(10));
+        let headers = [
+            ("retry-after", "4"),
+            ("x-ratelimit-limit", "100"),
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", "30"),
+        ];
+        let (res, slept) = simulate(&budget, vec![response(429, &headers); 3]);
+
+        assert_eq!(slept, [Duration::from_secs(4); 2]);
+        let Err(ApiError::RateLimited {
+            error,
+            limit,
+            retries,
+            waited,
+        }) = res
+        else {
+            panic!("expected a rate limit error, got {res:?}");
+        };
+
+        assert_eq!(error.status(), http::StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(limit.limit, Some(100));
+        assert_eq!(limit.remaining, Some(0));
+        assert_eq!(limit.reset, Some(Duration::from_secs(30)));
+        assert_eq!(retries, 2);
+        assert_eq!(waited, Duration::from_secs(8));
+    }
+
+    #[test]
+    fn budget_is_shared() {
+        let budget = RetryBudget::new(Duration::from_secs(5));
+        let retry_after = [("retry-after", "3")];
+
+        let (res, _) = simulate(
+            &budget,
+            vec![response(429, &retry_after), response(200, &[])],
+        );
+        assert!(res.is_ok());
+
+        // The first request used most of the budget.
+        let (res, slept) = simulate(&budget, vec![response(429, &retry_after)]);
+        assert!(matches!(res, Err(ApiError::RateLimited { retries: 1, .. })));
+        assert!(slept.is_empty());
+    }
+
+    #[test]
+    fn zero_budget_disables_retries() {
+        let budget = RetryBudget::new(Duration::ZERO);
+        let (res, slept) = simulate(&budget, vec![response(429, &[])]);
+
+        assert!(matches!(res, Err(ApiError::RateLimited { retries: 0, .. })));
+        assert!(slept.is_empty());
+        assert!(budget.summary().is_none());
+    }
+
+    #[test]
+    fn retry_after_date() {
+        let at = chrono::Utc::now() + chrono::Duration::seconds(120);
+        let d = parse_retry_after(&at.to_rfc2822()).unwrap();
+        assert!(d > Duration::from_secs(100) && d <= Duration::from_secs(120));
+
+        let past = "Wed, 21 Oct 2015 07:28:00 GMT";
+        assert_eq!(parse_retry_after(past), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+}
@@ -0,0 +1,194 @@
+use std::fmt;
+
+use crate::api::{ApiError, ApiErrorKind, encode_segment};
+
+/// Characters that can't appear in a branch or tag name, besides whitespace
+/// and control characters. `@` and `~` are allowed in refs, but only as
+/// separators.
+const INVALID_NAME_CHARS: &[char] = &['@', '~', '\\', '^', ':', '?', '*', '[', ']', '{', '}'];
+
+/// A ref in a request path, checked before the request is sent so that a
+/// malformed ref is reported as such, rather than as a missing route.
+///
+/// A ref is a branch or tag name, optionally pinned to a commit as
+/// `name@hash`, or a detached `@hash`. It may end in `~N`, for the Nth
+/// ancestor of that commit. Names may contain `/`, which is percent-encoded
+/// like any other reserved character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ref<'a>(&'a str);
+
+impl<'a> Ref<'a> {
+    /// Parses a ref in any of the forms above.
+    pub fn parse(s: &'a str) -> Result<Self, ApiError> {
+        let (rest, ancestor) = match s.split_once('~') {
+            Some((rest, n)) => (rest, Some(n)),
+            None => (s, None),
+        };
+
+        if let Some(n) = ancestor
+            && (n.is_empty() || !n.bytes().all(|b| b.is_ascii_digit()))
+        {
+            return Err(invalid(s, "expected a number of commits after `~`"));
+        }
+
+        let (name, hash) = match rest.split_once('@') {
+            Some((name, hash)) => (name, Some(hash)),
+            None => (rest, None),
+        };
+
+        if let Some(hash) = hash
+            && (hash.is_empty() || !hash.bytes().all(|b| b.is_ascii_alphanumeric()))
+        {
+            return Err(invalid(s, "expected a commit hash after `@`"));
+        }
+
+        if hash.is_none() || !name.is_empty() {
+            check_name(s, name)?;
+        }
+
+        Ok(Self(s))
+    }
+
+    /// Parses a plain branch or tag name, without a hash or `~N`.
+    pub fn name(s: &'a str) -> Result<Self, ApiError> {
+        check_name(s, s)?;
+        Ok(Self(s))
+    }
+
+    /// The ref as written.
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+
+    /// Whether the ref is a plain branch or tag name, which moves as commits
+    /// are added, rather than a fixed commit. Only a branch name can be
+    /// written to.
+    pub fn is_name(&self) -> bool {
+        !self.0.contains(['@', '~'])
+    }
+}
+
+/// Writes the ref percent-encoded, as a single path segment.
+impl fmt::Display for Ref<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        encode_segment(self.0).fmt(f)
+    }
+}
+
+fn check_name(input: &str, name: &str) -> Result<(), ApiError> {
+    if name.is_empty() {
+        return Err(invalid(input, "expected a branch or tag name"));
+    }
+
+    if let Some(c) = name
+        .chars()
+        .find(|c| c.is_whitespace() || c.is_control() || INVALID_NAME_CHARS.contains(c))
+    {
+        return Err(invalid(
+            input,
+            &format!("{c:?} isn't allowed in a branch or tag name"),
+        ));
+    }
+
+    Ok(())
+}
+
+fn invalid(input: &str, reason: &str) -> ApiError {
+    ApiError::ErrorResponse {
+        status: http::StatusCode::BAD_REQUEST,
+        kind: ApiErrorKind::InvalidRef {
+            input_ref: input.to_owned(),
+        },
+        message: Some(format!("invalid ref {input:?}: {reason}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_refs() {
+        for s in [
+            "main",
+            "user.feature-1",
+            "feature/foo",
+            "main@abc123",
+            "@abc123",
+            "main~3",
+            "main@abc123~1",
+        ] {
+            assert_eq!(Ref::parse(s).unwrap().as_str(), s);
+        }
+    }
+
+    #[test]
+    fn invalid_refs() {
+        for s in [
+            "",
+            "@",
+            "main@",
+            "main@abc@def",
+            "main@abc?x=1",
+            "main~",
+            "main~x",
+            "my branch",
+            "main\n",
+            "a^b",
+            "main@{yesterday}",
+        ] {
+            let err = Ref::parse(s).unwrap_err();
+            assert!(
+                matches!(
+                    &err,
+                    ApiError::ErrorResponse {
+                        kind: ApiErrorKind::InvalidRef { input_ref },
+                        ..
+                    } if input_ref == s
+                ),
+                "{s:?}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn names() {
+        assert!(Ref::name("user.feature").is_ok());
+        assert!(Ref::name("feature/foo").is_ok());
+        assert!(Ref::name("main@abc123").is_err());
+        assert!(Ref::name("main~3").is_err());
+        assert!(Ref::name("").is_err());
+    }
+
+    #[test]
+    fn is_name() {
+        let is_name = |s| Ref::parse(s).unwrap().is_name();
+        assert!(is_name("main"));
+        assert!(is_name("feature/foo"));
+        assert!(!is_name("main@abc123"));
+        assert!(!is_name("@abc123"));
+        assert!(!is_name("main~3"));
+    }
+
+    #[test]
+    fn display_encodes() {
+        assert_eq!(
+            Ref::parse("main@abc123").unwrap().to_string(),
+            "main@abc123"
+        );
+        assert_eq!(Ref::parse("main~3").unwrap().to_string(), "main~3");
+        assert_eq!(
+            Ref::parse("feature/foo").unwrap().to_string(),
+            "feature%2Ffoo"
+        );
+    }
+
+    #[test]
+    fn error_message() {
+        let err = Ref::parse("my branch").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "INVALID_REF: invalid ref \"my branch\": ' ' isn't allowed in a branch or tag name"
+        );
+    }
+}
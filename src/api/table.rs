@@ -4,11 +4,14 @@ use chrono::{DateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::{
     CatalogRef, PaginatedResponse,
-    api::{ApiRequest, DataResponse, PathArgs, commit::CommitOptions, urlformat},
+    api::{
+        ApiError, ApiErrorKind, ApiRequest, DataResponse, OPERATION_ID_PROPERTY, OperationRequest,
+        PathArgs, Ref, commit::CommitOptions, operation_headers, urlformat,
+    },
 };
 
 /// A field in a table schema.
@@ -205,6 +208,11 @@ impl ApiRequest for GetTable<'_> {
         )
     }
 
+    fn validate(&self) -> Result<(), ApiError> {
+        Ref::parse(self.at_ref)?;
+        Ok(())
+    }
+
     fn query(&self) -> Option<impl Serialize> {
         Some(GetTableQuery {
             namespace: self.namespace,
@@ -242,6 +250,11 @@ impl ApiRequest for GetTables<'_> {
         urlformat!("/catalog/v0/refs/{}/tables", self.at_ref)
     }
 
+    fn validate(&self) -> Result<(), ApiError> {
+        Ref::parse(self.at_ref)?;
+        Ok(())
+    }
+
     fn query(&self) -> Option<impl Serialize> {
         Some(GetTablesQuery {
             filter_by_name: self.filter_by_name,
@@ -250,6 +263,473 @@ impl ApiRequest for GetTables<'_> {
     }
 }
 
+/// An Iceberg snapshot of a table: its state after one write.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(
+        name = "TableSnapshot",
+        module = "bauplan.schema",
+        from_py_object,
+        get_all
+    )
+)]
+pub struct TableSnapshot {
+    /// The snapshot ID.
+    pub snapshot_id: i64,
+    /// The ID of the snapshot this one was written on top of, if any.
+    #[serde(alias = "parent_snapshot_id")]
+    pub parent_id: Option<i64>,
+    /// When the snapshot was written.
+    #[serde(alias = "timestamp_ms", deserialize_with = "deserialize_epoch_ms")]
+    pub timestamp: DateTime<Utc>,
+    /// The kind of write that produced the snapshot: "append", "overwrite",
+    /// "delete" or "replace".
+    pub operation: Option<String>,
+    /// The snapshot summary properties, like "added-records".
+    #[serde(default)]
+    pub summary: BTreeMap<String, String>,
+}
+
+#[cfg(feature = "python")]
+#[pyo3::pymethods]
+impl TableSnapshot {
+    fn __repr__(&self) -> String {
+        format!(
+            "TableSnapshot(snapshot_id={}, operation={:?}, timestamp={:?})",
+            self.snapshot_id,
+            self.operation.as_deref().unwrap_or(""),
+            self.timestamp.to_rfc3339(),
+        )
+    }
+}
+
+/// List the snapshots of a table, newest first.
+#[derive(Debug, Clone)]
+pub struct GetTableSnapshots<'a> {
+    /// The name of the table. Can be with or without an explicit namespace
+    /// ('taxi_fhvhv' or 'bauplan.taxi_fhvhv').
+    pub name: &'a str,
+
+    /// The ref (branch, tag, etc) at which to read the table.
+    pub at_ref: &'a str,
+
+    /// The namespace to search for the table. If specified, the table name
+    /// should not include a namespace.
+    pub namespace: Option<&'a str>,
+}
+
+impl ApiRequest for GetTableSnapshots<'_> {
+    type Response = PaginatedResponse<TableSnapshot>;
+
+    fn path(&self) -> PathArgs {
+        urlformat!(
+            "/catalog/v0/refs/{}/tables/{}/snapshots",
+            self.at_ref,
+            self.name,
+        )
+    }
+
+    fn validate(&self) -> Result<(), ApiError> {
+        Ref::parse(self.at_ref)?;
+        Ok(())
+    }
+
+    fn query(&self) -> Option<impl Serialize> {
+        Some(GetTableQuery {
+            namespace: self.namespace,
+        })
+    }
+}
+
+/// How many namespaces [`summarize_namespaces`] lists at once.
+pub const SUMMARY_CONCURRENCY: usize = 4;
+
+/// Aggregate statistics over the tables in a ref.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(
+        name = "RefSummary",
+        module = "bauplan.schema",
+        from_py_object,
+        get_all
+    )
+)]
+pub struct RefSummary {
+    /// The ref that was summarized.
+    #[serde(rename = "ref")]
+    pub r#ref: String,
+    /// The number of tables.
+    pub tables: u64,
+    /// The total number of records across all tables. Tables that don't
+    /// report a record count are skipped.
+    pub total_records: u64,
+    /// The total size of all tables, in bytes. Tables that don't report a
+    /// size are skipped.
+    pub total_size: u64,
+    /// The most recent update to any table, if there are any tables.
+    pub last_updated_at: Option<DateTime<Utc>>,
+}
+
+impl RefSummary {
+    /// Creates an empty summary for a ref.
+    pub fn new(r#ref: impl Into<String>) -> Self {
+        Self {
+            r#ref: r#ref.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds a table to the summary.
+    pub fn add(&mut self, table: &Table) {
+        self.tables += 1;
+        self.total_records += table.records.unwrap_or(0);
+        self.total_size += table.size.unwrap_or(0);
+        self.last_updated_at = self.last_updated_at.max(Some(table.last_updated_at));
+    }
+
+    /// Adds the tables from another summary of the same ref.
+    pub fn merge(&mut self, other: RefSummary) {
+        self.tables += other.tables;
+        self.total_records += other.total_records;
+        self.total_size += other.total_size;
+        self.last_updated_at = self.last_updated_at.max(other.last_updated_at);
+    }
+}
+
+#[cfg(feature = "python")]
+#[pyo3::pymethods]
+impl RefSummary {
+    /// Returns the summary as a dict.
+    fn to_dict<'py>(
+        &self,
+        py: pyo3::Python<'py>,
+    ) -> pyo3::PyResult<pyo3::Bound<'py, pyo3::types::PyDict>> {
+        use pyo3::types::PyDictMethods as _;
+
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("ref", &self.r#ref)?;
+        dict.set_item("tables", self.tables)?;
+        dict.set_item("total_records", self.total_records)?;
+        dict.set_item("total_size", self.total_size)?;
+        dict.set_item("last_updated_at", self.last_updated_at)?;
+        Ok(dict)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RefSummary(ref={:?}, tables={}, total_records={}, total_size={})",
+            self.r#ref, self.tables, self.total_records, self.total_size,
+        )
+    }
+}
+
+/// Summarizes the tables in a ref, one namespace at a time, with at most
+/// [`SUMMARY_CONCURRENCY`] namespaces in flight. `summarize_namespace` should
+/// list the tables in the namespace it's passed and add them to a summary.
+///
+/// The API has no aggregate endpoint, so this lists every table in the ref;
+/// for large catalogs, that can take a while.
+pub fn summarize_namespaces<F, E>(
+    r#ref: &str,
+    namespaces: &[String],
+    summarize_namespace: F,
+) -> Result<RefSummary, E>
+where
+    F: Fn(&str) -> Result<RefSummary, E> + Sync,
+    E: Send,
+{
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let next = AtomicUsize::new(0);
+    let workers = SUMMARY_CONCURRENCY.min(namespaces.len());
+
+    let results = std::thread::scope(|s| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut summary = RefSummary::new(r#ref);
+                    while let Some(ns) = namespaces.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        summary.merge(summarize_namespace(ns)?);
+                    }
+
+                    Ok(summary)
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("summary worker panicked"))
+            .collect::<Vec<_>>()
+    });
+
+    let mut summary = RefSummary::new(r#ref);
+    for result in results {
+        summary.merge(result?);
+    }
+
+    Ok(summary)
+}
+
+/// The most tables to list when looking for a table in other namespaces.
+pub const NAMESPACE_HINT_LIMIT: usize = 50;
+
+/// Given the tables matching a bare table name that couldn't be found,
+/// suggests the fully qualified names it might have meant, as in "did you
+/// mean: bauplan.titanic, analytics.titanic?". Tables whose names don't match
+/// exactly are ignored. Returns `None` if there's nothing to suggest.
+pub fn qualified_name_hint(
+    table_name: &str,
+    tables: impl IntoIterator<Item = Table>,
+) -> Option<String> {
+    let mut names: Vec<String> = tables
+        .into_iter()
+        .filter(|t| t.name == table_name)
+        .map(|t| format!("{}.{}", t.namespace, t.name))
+        .collect();
+    if names.is_empty() {
+        return None;
+    }
+
+    names.sort();
+    names.dedup();
+    Some(format!("did you mean: {}?", names.join(", ")))
+}
+
+/// Whether a column or partition field was added, removed or changed
+/// between two refs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(module = "bauplan.schema", from_py_object, eq, eq_int)
+)]
+pub enum DiffKind {
+    /// Only in the second ref.
+    Added,
+    /// Only in the first ref.
+    Removed,
+    /// In both refs, but different.
+    Changed,
+}
+
+impl std::fmt::Display for DiffKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiffKind::Added => write!(f, "added"),
+            DiffKind::Removed => write!(f, "removed"),
+            DiffKind::Changed => write!(f, "changed"),
+        }
+    }
+}
+
+/// A column that differs between two refs, matched by name. A renamed
+/// column shows up as one removed and one added.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(module = "bauplan.schema", from_py_object, get_all)
+)]
+pub struct ColumnDiff {
+    /// The column name.
+    pub name: String,
+    /// How the column differs.
+    pub kind: DiffKind,
+    /// The column type in the first ref, if it's there.
+    pub from_type: Option<String>,
+    /// The column type in the second ref, if it's there.
+    pub to_type: Option<String>,
+    /// Whether the column is required in the first ref, if it's there.
+    pub from_required: Option<bool>,
+    /// Whether the column is required in the second ref, if it's there.
+    pub to_required: Option<bool>,
+}
+
+/// A partition field that differs between two refs, matched by name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(module = "bauplan.schema", from_py_object, get_all)
+)]
+pub struct PartitionDiff {
+    /// The partition field name.
+    pub name: String,
+    /// How the partition field differs.
+    pub kind: DiffKind,
+    /// The partition transform in the first ref, if it's there.
+    pub from_transform: Option<String>,
+    /// The partition transform in the second ref, if it's there.
+    pub to_transform: Option<String>,
+}
+
+/// The differences in one table between two refs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(module = "bauplan.schema", from_py_object, get_all)
+)]
+pub struct TableDiff {
+    /// The fully qualified table name.
+    pub table: String,
+    /// The first ref.
+    pub from_ref: String,
+    /// The second ref.
+    pub to_ref: String,
+    /// The number of records in the first ref.
+    pub from_records: Option<u64>,
+    /// The number of records in the second ref.
+    pub to_records: Option<u64>,
+    /// The current snapshot ID in the first ref.
+    pub from_snapshot_id: Option<i64>,
+    /// The current snapshot ID in the second ref.
+    pub to_snapshot_id: Option<i64>,
+    /// The columns added, removed or changed in type or nullability.
+    pub columns: Vec<ColumnDiff>,
+    /// The partition fields added, removed or changed in transform.
+    pub partitions: Vec<PartitionDiff>,
+}
+
+impl TableDiff {
+    /// Compares the table `from`, read at `from_ref`, with `to`, read at
+    /// `to_ref`. Columns and partition fields are listed in the order of
+    /// `to`, followed by any only in `from`.
+    pub fn new(from_ref: &str, from: &Table, to_ref: &str, to: &Table) -> Self {
+        let mut columns = Vec::new();
+        for field in &to.fields {
+            let prev = from.fields.iter().find(|f| f.name == field.name);
+            let kind = match prev {
+                None => DiffKind::Added,
+                Some(p) if p.r#type != field.r#type || p.required != field.required => {
+                    DiffKind::Changed
+                }
+                Some(_) => continue,
+            };
+
+            columns.push(ColumnDiff {
+                name: field.name.clone(),
+                kind,
+                from_type: prev.map(|p| p.r#type.clone()),
+                to_type: Some(field.r#type.clone()),
+                from_required: prev.map(|p| p.required),
+                to_required: Some(field.required),
+            });
+        }
+
+        for field in &from.fields {
+            if !to.fields.iter().any(|f| f.name == field.name) {
+                columns.push(ColumnDiff {
+                    name: field.name.clone(),
+                    kind: DiffKind::Removed,
+                    from_type: Some(field.r#type.clone()),
+                    to_type: None,
+                    from_required: Some(field.required),
+                    to_required: None,
+                });
+            }
+        }
+
+        let mut partitions = Vec::new();
+        for field in &to.partitions {
+            let prev = from.partitions.iter().find(|f| f.name == field.name);
+            let kind = match prev {
+                None => DiffKind::Added,
+                Some(p) if p.transform != field.transform => DiffKind::Changed,
+                Some(_) => continue,
+            };
+
+            partitions.push(PartitionDiff {
+                name: field.name.clone(),
+                kind,
+                from_transform: prev.map(|p| p.transform.clone()),
+                to_transform: Some(field.transform.clone()),
+            });
+        }
+
+        for field in &from.partitions {
+            if !to.partitions.iter().any(|f| f.name == field.name) {
+                partitions.push(PartitionDiff {
+                    name: field.name.clone(),
+                    kind: DiffKind::Removed,
+                    from_transform: Some(field.transform.clone()),
+                    to_transform: None,
+                });
+            }
+        }
+
+        Self {
+            table: to.fqn(),
+            from_ref: from_ref.to_owned(),
+            to_ref: to_ref.to_owned(),
+            from_records: from.records,
+            to_records: to.records,
+            from_snapshot_id: from.current_snapshot_id,
+            to_snapshot_id: to.current_snapshot_id,
+            columns,
+            partitions,
+        }
+    }
+
+    /// Whether the table is the same in both refs.
+    pub fn is_empty(&self) -> bool {
+        self.from_records == self.to_records
+            && self.from_snapshot_id == self.to_snapshot_id
+            && self.columns.is_empty()
+            && self.partitions.is_empty()
+    }
+
+    /// The change in the number of records, if both refs report it.
+    pub fn records_delta(&self) -> Option<i64> {
+        let from = i64::try_from(self.from_records?).ok()?;
+        let to = i64::try_from(self.to_records?).ok()?;
+        Some(to - from)
+    }
+}
+
+#[cfg(feature = "python")]
+#[pyo3::pymethods]
+impl TableDiff {
+    /// Whether the table is the same in both refs.
+    #[pyo3(name = "is_empty")]
+    fn py_is_empty(&self) -> bool {
+        self.is_empty()
+    }
+
+    /// The change in the number of records, if both refs report it.
+    #[getter(records_delta)]
+    fn py_records_delta(&self) -> Option<i64> {
+        self.records_delta()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "TableDiff(table={:?}, from_ref={:?}, to_ref={:?}, columns={}, partitions={})",
+            self.table,
+            self.from_ref,
+            self.to_ref,
+            self.columns.len(),
+            self.partitions.len(),
+        )
+    }
+}
+
+#[cfg(feature = "python")]
+#[pyo3::pymethods]
+impl ColumnDiff {
+    fn __repr__(&self) -> String {
+        format!("ColumnDiff(name={:?}, kind={})", self.name, self.kind)
+    }
+}
+
+#[cfg(feature = "python")]
+#[pyo3::pymethods]
+impl PartitionDiff {
+    fn __repr__(&self) -> String {
+        format!("PartitionDiff(name={:?}, kind={})", self.name, self.kind)
+    }
+}
+
 /// Delete a table from a branch.
 #[derive(Debug, Clone)]
 pub struct DeleteTable<'a> {
@@ -308,6 +788,23 @@ impl ApiRequest for DeleteTable<'_> {
     }
 }
 
+impl DeleteTable<'_> {
+    /// Whether the request failed with `TABLE_NOT_FOUND`. If so, the inner
+    /// value is the ref the table was looked up on, which the server doesn't
+    /// always include.
+    pub fn missing(err: &ApiError) -> Option<Option<&CatalogRef>> {
+        match err {
+            ApiError::Other {
+                kind: Some(kind), ..
+            } if kind == "TABLE_NOT_FOUND" => Some(None),
+            _ => match err.kind()? {
+                ApiErrorKind::TableNotFound { catalog_ref, .. } => Some(Some(catalog_ref)),
+                _ => None,
+            },
+        }
+    }
+}
+
 /// Revert a table to a previous ref.
 #[derive(Debug, Clone)]
 pub struct RevertTable<'a> {
@@ -330,6 +827,10 @@ pub struct RevertTable<'a> {
 
     /// Override the commit body or add custom properties.
     pub commit: CommitOptions<'a>,
+
+    /// A client-generated ID, so that the revert can be retried safely. See
+    /// [`send_operation`](crate::send_operation).
+    pub operation_id: Option<&'a str>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -355,22 +856,637 @@ impl ApiRequest for RevertTable<'_> {
         )
     }
 
+    fn validate(&self) -> Result<(), ApiError> {
+        Ref::parse(self.source_ref)?;
+        Ref::name(self.into_branch)?;
+        Ok(())
+    }
+
     fn body(&self) -> Option<impl Serialize> {
+        let mut commit = self.commit.clone();
+        if let Some(id) = self.operation_id {
+            commit.properties.insert(OPERATION_ID_PROPERTY, id);
+        }
+
         Some(RevertTableBody {
             replace: self.replace,
+            commit,
+        })
+    }
+
+    fn headers(&self) -> http::HeaderMap {
+        operation_headers(self.operation_id)
+    }
+}
+
+impl OperationRequest for RevertTable<'_> {
+    fn operation_id(&self) -> Option<&str> {
+        self.operation_id
+    }
+
+    fn into_branch(&self) -> &str {
+        self.into_branch
+    }
+}
+
+/// A column to add with [`UpdateTableSchema`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NewColumn<'a> {
+    /// The column name.
+    pub name: &'a str,
+    /// The Iceberg type of the column, like `string` or `decimal(10,2)`.
+    pub r#type: &'a str,
+    /// Whether the column is required.
+    pub required: bool,
+}
+
+/// Add, drop or rename columns of a table on a branch, in a single commit.
+///
+/// The changes aren't checked by the request itself; call
+/// [`validate`](Self::validate) with the table first for a clearer error.
+#[derive(Debug, Clone)]
+pub struct UpdateTableSchema<'a> {
+    /// The name of the table to change. Can be with or without an explicit
+    /// namespace ('taxi_fhvhv' or 'bauplan.taxi_fhvhv').
+    pub name: &'a str,
+
+    /// The branch to commit to.
+    pub branch: &'a str,
+
+    /// The namespace that the table is in. If specified, the table name
+    /// should not include a namespace.
+    pub namespace: Option<&'a str>,
+
+    /// Columns to add, at the end of the schema.
+    pub add_columns: Vec<NewColumn<'a>>,
+
+    /// Columns to drop.
+    pub drop_columns: Vec<&'a str>,
+
+    /// Columns to rename, from the old name to the new one.
+    pub rename_columns: BTreeMap<&'a str, &'a str>,
+
+    /// The schema the changes were made against, usually the table's
+    /// `current_schema_id`. If the schema has changed since, the update
+    /// fails with a conflict instead of being applied to the new schema.
+    pub expected_schema_id: Option<i32>,
+
+    /// Override the commit body or add custom properties.
+    pub commit: CommitOptions<'a>,
+}
+
+#[derive(Serialize)]
+struct UpdateTableSchemaQuery<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    namespace: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct UpdateTableSchemaBody<'a> {
+    add_columns: &'a [NewColumn<'a>],
+    drop_columns: &'a [&'a str],
+    rename_columns: &'a BTreeMap<&'a str, &'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected_schema_id: Option<i32>,
+    #[serde(flatten)]
+    commit: CommitOptions<'a>,
+}
+
+impl ApiRequest for UpdateTableSchema<'_> {
+    type Response = Table;
+
+    fn method(&self) -> http::Method {
+        http::Method::POST
+    }
+
+    fn path(&self) -> PathArgs {
+        urlformat!(
+            "/catalog/v0/branches/{}/tables/{}/schema",
+            self.branch,
+            self.name,
+        )
+    }
+
+    fn query(&self) -> Option<impl Serialize> {
+        Some(UpdateTableSchemaQuery {
+            namespace: self.namespace,
+        })
+    }
+
+    fn body(&self) -> Option<impl Serialize> {
+        Some(UpdateTableSchemaBody {
+            add_columns: &self.add_columns,
+            drop_columns: &self.drop_columns,
+            rename_columns: &self.rename_columns,
+            expected_schema_id: self.expected_schema_id,
             commit: self.commit.clone(),
         })
     }
 }
 
+/// Why a schema change can't be applied to a table.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum SchemaChangeError {
+    #[error("no columns to add, drop or rename")]
+    Empty,
+    #[error("table has no column {0:?}")]
+    UnknownColumn(String),
+    #[error("column {0:?} would appear more than once")]
+    DuplicateColumn(String),
+    #[error("column {0:?} can't be both dropped and renamed")]
+    DropAndRename(String),
+    #[error("invalid type {type:?} for column {column:?}")]
+    InvalidType { column: String, r#type: String },
+    #[error("can't drop column {column:?}: the table is partitioned by it ({partition})")]
+    PartitionColumn { column: String, partition: String },
+}
+
+impl UpdateTableSchema<'_> {
+    /// Checks the changes against the table's current schema: every dropped
+    /// or renamed column must exist, no two columns may end up with the same
+    /// name, added columns must have a valid type, and partition columns
+    /// can't be dropped.
+    pub fn validate(&self, table: &Table) -> Result<(), SchemaChangeError> {
+        if self.add_columns.is_empty()
+            && self.drop_columns.is_empty()
+            && self.rename_columns.is_empty()
+        {
+            return Err(SchemaChangeError::Empty);
+        }
+
+        let exists = |name: &str| table.fields.iter().any(|f| f.name == name);
+        let unknown = |name: &str| SchemaChangeError::UnknownColumn(name.to_owned());
+        let duplicate = |name: &str| SchemaChangeError::DuplicateColumn(name.to_owned());
+
+        for (i, &column) in self.drop_columns.iter().enumerate() {
+            if !exists(column) {
+                return Err(unknown(column));
+            } else if self.drop_columns[..i].contains(&column) {
+                return Err(duplicate(column));
+            } else if self.rename_columns.contains_key(column) {
+                return Err(SchemaChangeError::DropAndRename(column.to_owned()));
+            }
+
+            if let Some(p) = table.partitions.iter().find(|p| partitioned_by(p, column)) {
+                return Err(SchemaChangeError::PartitionColumn {
+                    column: column.to_owned(),
+                    partition: format!("{} {}", p.transform, p.name),
+                });
+            }
+        }
+
+        if let Some(&from) = self.rename_columns.keys().find(|&&from| !exists(from)) {
+            return Err(unknown(from));
+        }
+
+        // The names of the columns after the change.
+        let mut names: BTreeSet<&str> = table
+            .fields
+            .iter()
+            .map(|f| f.name.as_str())
+            .filter(|&name| {
+                !self.drop_columns.contains(&name) && !self.rename_columns.contains_key(name)
+            })
+            .collect();
+
+        for &to in self.rename_columns.values() {
+            if !names.insert(to) {
+                return Err(duplicate(to));
+            }
+        }
+
+        for column in &self.add_columns {
+            if !is_valid_type(column.r#type) {
+                return Err(SchemaChangeError::InvalidType {
+                    column: column.name.to_owned(),
+                    r#type: column.r#type.to_owned(),
+                });
+            } else if !names.insert(column.name) {
+                return Err(duplicate(column.name));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether a partition field is derived from `column`. Partition fields
+/// don't record their source column by name, so this goes by the names
+/// Iceberg gives them by default: the column name for identity partitions,
+/// and `<column>_<transform>` for the rest.
+fn partitioned_by(partition: &PartitionField, column: &str) -> bool {
+    let transform = partition.transform.split('[').next().unwrap_or_default();
+    let suffix = match transform {
+        "identity" => return partition.name == column,
+        "truncate" => "trunc",
+        t => t,
+    };
+
+    partition
+        .name
+        .strip_prefix(column)
+        .and_then(|s| s.strip_prefix('_'))
+        == Some(suffix)
+}
+
+/// Whether `s` is an Iceberg primitive type, as written in table schemas.
+fn is_valid_type(s: &str) -> bool {
+    const PRIMITIVES: &[&str] = &[
+        "boolean",
+        "int",
+        "long",
+        "float",
+        "double",
+        "date",
+        "time",
+        "timestamp",
+        "timestamptz",
+        "timestamp_ns",
+        "timestamptz_ns",
+        "string",
+        "uuid",
+        "binary",
+    ];
+
+    if PRIMITIVES.contains(&s) {
+        return true;
+    }
+
+    if let Some(args) = s.strip_prefix("decimal(").and_then(|s| s.strip_suffix(')')) {
+        let Some((precision, scale)) = args.split_once(',') else {
+            return false;
+        };
+
+        return match (precision.trim().parse::<u32>(), scale.trim().parse::<u32>()) {
+            (Ok(precision), Ok(scale)) => (1..=38).contains(&precision) && scale <= precision,
+            _ => false,
+        };
+    }
+
+    s.strip_prefix("fixed[")
+        .and_then(|s| s.strip_suffix(']'))
+        .and_then(|len| len.parse::<u32>().ok())
+        .is_some_and(|len| len > 0)
+}
+
+/// Deserializes a timestamp in milliseconds, as the API returns it, or in
+/// RFC 3339, as a `Table` is serialized.
 fn deserialize_epoch_ms<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
-    let millis: i64 = Deserialize::deserialize(deserializer)?;
-    Utc.timestamp_millis_opt(millis)
-        .single()
-        .ok_or_else(|| serde::de::Error::custom("invalid timestamp"))
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Timestamp {
+        Millis(i64),
+        Rfc3339(DateTime<Utc>),
+    }
+
+    match Deserialize::deserialize(deserializer)? {
+        Timestamp::Millis(millis) => Utc
+            .timestamp_millis_opt(millis)
+            .single()
+            .ok_or_else(|| serde::de::Error::custom("invalid timestamp")),
+        Timestamp::Rfc3339(t) => Ok(t),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(namespace: &str, records: u64, updated_ms: i64) -> Table {
+        Table {
+            id: Uuid::new_v4(),
+            name: format!("t{records}"),
+            namespace: namespace.to_owned(),
+            kind: TableKind::Table,
+            records: Some(records),
+            size: Some(records * 10),
+            last_updated_at: Utc.timestamp_millis_opt(updated_ms).unwrap(),
+            fields: Vec::new(),
+            snapshots: None,
+            partitions: Vec::new(),
+            metadata_location: String::new(),
+            current_snapshot_id: None,
+            current_schema_id: None,
+            properties: BTreeMap::new(),
+        }
+    }
+
+    fn named(namespace: &str, name: &str) -> Table {
+        Table {
+            name: name.to_owned(),
+            ..table(namespace, 0, 0)
+        }
+    }
+
+    #[test]
+    fn hint_single_match() {
+        let tables = [named("bauplan", "titanic"), named("bauplan", "titanic_v2")];
+        assert_eq!(
+            qualified_name_hint("titanic", tables).as_deref(),
+            Some("did you mean: bauplan.titanic?")
+        );
+    }
+
+    #[test]
+    fn hint_multiple_matches() {
+        let tables = [
+            named("bauplan", "titanic"),
+            named("analytics", "titanic"),
+            named("raw", "titanic_raw"),
+        ];
+        assert_eq!(
+            qualified_name_hint("titanic", tables).as_deref(),
+            Some("did you mean: analytics.titanic, bauplan.titanic?")
+        );
+    }
+
+    #[test]
+    fn hint_no_match() {
+        let tables = [named("bauplan", "titanic_v2")];
+        assert_eq!(qualified_name_hint("titanic", tables), None);
+        assert_eq!(qualified_name_hint("titanic", []), None);
+    }
+
+    #[test]
+    fn summarize() {
+        let tables = [
+            table("a", 1, 1_000),
+            table("a", 2, 5_000),
+            table("b", 3, 2_000),
+            table("c", 4, 3_000),
+            table("d", 5, 4_000),
+            table("e", 6, 0),
+        ];
+
+        let namespaces: Vec<_> = ["a", "b", "c", "d", "e", "empty"].map(String::from).into();
+
+        let summary = summarize_namespaces("main", &namespaces, |ns| {
+            let mut summary = RefSummary::new("main");
+            for t in tables.iter().filter(|t| t.namespace == ns) {
+                summary.add(t);
+            }
+
+            Ok::<_, ()>(summary)
+        })
+        .unwrap();
+
+        assert_eq!(
+            summary,
+            RefSummary {
+                r#ref: "main".to_owned(),
+                tables: 6,
+                total_records: 21,
+                total_size: 210,
+                last_updated_at: Some(Utc.timestamp_millis_opt(5_000).unwrap()),
+            }
+        );
+    }
+
+    #[test]
+    fn summarize_error() {
+        let namespaces: Vec<_> = ["a", "b", "c"].map(String::from).into();
+        let result = summarize_namespaces("main", &namespaces, |ns| {
+            if ns == "b" {
+                Err(ns.to_owned())
+            } else {
+                Ok(RefSummary::new("main"))
+            }
+        });
+
+        assert_eq!(result, Err("b".to_owned()));
+    }
+
+    fn alter<'a>(
+        add_columns: Vec<NewColumn<'a>>,
+        drop_columns: Vec<&'a str>,
+        rename_columns: &[(&'a str, &'a str)],
+    ) -> UpdateTableSchema<'a> {
+        UpdateTableSchema {
+            name: "orders",
+            branch: "main",
+            namespace: None,
+            add_columns,
+            drop_columns,
+            rename_columns: rename_columns.iter().copied().collect(),
+            expected_schema_id: None,
+            commit: Default::default(),
+        }
+    }
+
+    fn column<'a>(name: &'a str, r#type: &'a str) -> NewColumn<'a> {
+        NewColumn {
+            name,
+            r#type,
+            required: false,
+        }
+    }
+
+    fn orders() -> Table {
+        let field = |id, name: &str, r#type: &str| TableField {
+            id,
+            name: name.to_owned(),
+            required: false,
+            r#type: r#type.to_owned(),
+        };
+
+        Table {
+            fields: vec![
+                field(1, "id", "long"),
+                field(2, "name", "string"),
+                field(3, "created_at", "timestamptz"),
+                field(4, "region", "string"),
+            ],
+            partitions: vec![
+                PartitionField {
+                    name: "created_at_day".to_owned(),
+                    transform: "day".to_owned(),
+                },
+                PartitionField {
+                    name: "region".to_owned(),
+                    transform: "identity".to_owned(),
+                },
+            ],
+            ..named("bauplan", "orders")
+        }
+    }
+
+    #[test]
+    fn validate_schema_change() {
+        let table = orders();
+        let change = alter(
+            vec![column("total", "decimal(10, 2)"), column("name", "string")],
+            vec!["name"],
+            &[("id", "order_id")],
+        );
+        assert_eq!(change.validate(&table), Ok(()));
+
+        // Names can be swapped.
+        let change = alter(vec![], vec![], &[("id", "name"), ("name", "id")]);
+        assert_eq!(change.validate(&table), Ok(()));
+    }
+
+    #[test]
+    fn validate_schema_change_errors() {
+        let table = orders();
+        let err = |change: UpdateTableSchema<'_>| change.validate(&table).unwrap_err().to_string();
+
+        assert_eq!(
+            err(alter(vec![], vec![], &[])),
+            "no columns to add, drop or rename"
+        );
+        assert_eq!(
+            err(alter(vec![], vec!["missing"], &[])),
+            "table has no column \"missing\""
+        );
+        assert_eq!(
+            err(alter(vec![], vec![], &[("missing", "x")])),
+            "table has no column \"missing\""
+        );
+        assert_eq!(
+            err(alter(vec![column("id", "long")], vec![], &[])),
+            "column \"id\" would appear more than once"
+        );
+        assert_eq!(
+            err(alter(vec![], vec![], &[("id", "name")])),
+            "column \"name\" would appear more than once"
+        );
+        assert_eq!(
+            err(alter(
+                vec![column("a", "int"), column("a", "int")],
+                vec![],
+                &[]
+            )),
+            "column \"a\" would appear more than once"
+        );
+        assert_eq!(
+            err(alter(vec![], vec!["id"], &[("id", "order_id")])),
+            "column \"id\" can't be both dropped and renamed"
+        );
+        assert_eq!(
+            err(alter(vec![], vec!["created_at"], &[])),
+            "can't drop column \"created_at\": the table is partitioned by it (day created_at_day)"
+        );
+        assert_eq!(
+            err(alter(vec![], vec!["region"], &[])),
+            "can't drop column \"region\": the table is partitioned by it (identity region)"
+        );
+
+        for r#type in [
+            "varchar",
+            "decimal(40,2)",
+            "decimal(2,3)",
+            "fixed[0]",
+            "list<int>",
+        ] {
+            assert_eq!(
+                err(alter(vec![column("a", r#type)], vec![], &[])),
+                format!("invalid type {type:?} for column \"a\"")
+            );
+        }
+    }
+
+    #[test]
+    fn summarize_empty() {
+        let summary = summarize_namespaces("main", &[], |_| Ok::<_, ()>(RefSummary::new("main")));
+        assert_eq!(summary, Ok(RefSummary::new("main")));
+    }
+
+    #[test]
+    fn deserialize_snapshot() {
+        let snapshot: TableSnapshot = serde_json::from_str(
+            r#"{
+                "snapshot_id": 2,
+                "parent_snapshot_id": 1,
+                "timestamp_ms": 5000,
+                "operation": "append",
+                "summary": { "added-records": "10" }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(snapshot.snapshot_id, 2);
+        assert_eq!(snapshot.parent_id, Some(1));
+        assert_eq!(snapshot.timestamp, Utc.timestamp_millis_opt(5_000).unwrap());
+        assert_eq!(snapshot.operation.as_deref(), Some("append"));
+        assert_eq!(snapshot.summary["added-records"], "10");
+
+        // A serialized snapshot reads back the same.
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let again: TableSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(again.timestamp, snapshot.timestamp);
+        assert_eq!(again.parent_id, Some(1));
+    }
+
+    #[test]
+    fn diff_identical() {
+        let table = orders();
+        let diff = TableDiff::new("main", &table, "u.dev", &table);
+        assert!(diff.is_empty());
+        assert_eq!(diff.table, "bauplan.orders");
+        assert_eq!(diff.records_delta(), Some(0));
+    }
+
+    #[test]
+    fn diff_changes() {
+        let from = orders();
+        let mut to = orders();
+        to.records = Some(7);
+        to.current_snapshot_id = Some(2);
+        to.fields.retain(|f| f.name != "region");
+        to.fields[1].r#type = "binary".to_owned();
+        to.fields.push(TableField {
+            id: 5,
+            name: "total".to_owned(),
+            required: true,
+            r#type: "double".to_owned(),
+        });
+        to.partitions[0].transform = "month".to_owned();
+        to.partitions.pop();
+
+        let diff = TableDiff::new("main", &from, "u.dev", &to);
+        assert!(!diff.is_empty());
+        assert_eq!(diff.records_delta(), Some(7));
+        assert_eq!(diff.from_snapshot_id, None);
+        assert_eq!(diff.to_snapshot_id, Some(2));
+
+        let columns: Vec<_> = diff
+            .columns
+            .iter()
+            .map(|c| {
+                (
+                    c.name.as_str(),
+                    c.kind,
+                    c.from_type.as_deref(),
+                    c.to_type.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            columns,
+            [
+                ("name", DiffKind::Changed, Some("string"), Some("binary")),
+                ("total", DiffKind::Added, None, Some("double")),
+                ("region", DiffKind::Removed, Some("string"), None),
+            ]
+        );
+
+        let partitions: Vec<_> = diff
+            .partitions
+            .iter()
+            .map(|p| (p.name.as_str(), p.kind))
+            .collect();
+        assert_eq!(
+            partitions,
+            [
+                ("created_at_day", DiffKind::Changed),
+                ("region", DiffKind::Removed),
+            ]
+        );
+    }
 }
 
 #[cfg(all(test, feature = "_integration-tests"))]
@@ -474,6 +1590,33 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn get_table_snapshots() -> anyhow::Result<()> {
+        let table: Table = roundtrip(GetTable {
+            name: "titanic",
+            at_ref: "main",
+            namespace: Some("bauplan"),
+        })?;
+
+        let req = GetTableSnapshots {
+            name: "titanic",
+            at_ref: "main",
+            namespace: Some("bauplan"),
+        };
+
+        let snapshots = crate::paginate(req, None, |r| roundtrip(r))?
+            .collect::<Result<Vec<TableSnapshot>, ApiError>>()?;
+
+        assert!(!snapshots.is_empty());
+        assert!(
+            snapshots
+                .iter()
+                .any(|s| Some(s.snapshot_id) == table.current_snapshot_id)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn get_tables_limit() -> anyhow::Result<()> {
         let req = GetTables {
@@ -569,6 +1712,77 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn update_table_schema() -> anyhow::Result<()> {
+        let branch = TestBranch::new("test_table_schema")?;
+
+        // The branch is a copy of main, so it already has the titanic table.
+        let req = GetTable {
+            name: "titanic",
+            at_ref: &branch.name,
+            namespace: Some("bauplan"),
+        };
+        let before = roundtrip(req.clone())?;
+
+        let req = UpdateTableSchema {
+            name: "titanic",
+            branch: &branch.name,
+            namespace: Some("bauplan"),
+            add_columns: vec![NewColumn {
+                name: "Notes",
+                r#type: "string",
+                required: false,
+            }],
+            drop_columns: vec![],
+            rename_columns: [("Name", "PassengerName")].into(),
+            expected_schema_id: before.current_schema_id,
+            commit: Default::default(),
+        };
+        req.validate(&before)?;
+        roundtrip(req)?;
+
+        let req = GetTable {
+            name: "titanic",
+            at_ref: &branch.name,
+            namespace: Some("bauplan"),
+        };
+        let after = roundtrip(req)?;
+
+        let names: Vec<_> = after.fields.iter().map(|f| f.name.as_str()).collect();
+        assert!(names.contains(&"Notes"), "{names:?}");
+        assert!(names.contains(&"PassengerName"), "{names:?}");
+        assert!(!names.contains(&"Name"), "{names:?}");
+        assert_ne!(after.current_schema_id, before.current_schema_id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn delete_table_special_characters() -> anyhow::Result<()> {
+        let branch = TestBranch::new("test_table_delete_special")?;
+
+        // The name should reach the server exactly as given, with the `+`
+        // not decoded as a space and the `/` not splitting the path.
+        let req = DeleteTable {
+            name: "no such+table/x",
+            branch: &branch.name,
+            namespace: Some("bauplan"),
+            commit: Default::default(),
+        };
+
+        let Err(ApiError::ErrorResponse {
+            kind: ApiErrorKind::TableNotFound { table_name, .. },
+            ..
+        }) = roundtrip(req)
+        else {
+            panic!("expected TABLE_NOT_FOUND");
+        };
+
+        assert_eq!(table_name, "bauplan.no such+table/x");
+
+        Ok(())
+    }
+
     #[test]
     fn revert_table() -> anyhow::Result<()> {
         let branch = TestBranch::new("test_table_revert")?;
@@ -607,6 +1821,7 @@ mod test {
             namespace: Some("bauplan"),
             replace: false,
             commit: Default::default(),
+            operation_id: None,
         };
         roundtrip(req)?;
 
@@ -642,6 +1857,7 @@ mod test {
             namespace: Some("bauplan"),
             replace: false,
             commit: Default::default(),
+            operation_id: None,
         };
         roundtrip(req)?;
 
@@ -654,6 +1870,7 @@ mod test {
             namespace: Some("bauplan"),
             replace: false,
             commit: Default::default(),
+            operation_id: None,
         };
         let Err(ApiError::ErrorResponse {
             kind:
@@ -686,6 +1903,7 @@ mod test {
             namespace: Some("bauplan"),
             replace: false,
             commit: Default::default(),
+            operation_id: None,
         };
         let Err(ApiError::ErrorResponse {
             kind: ApiErrorKind::SameRef { .. },
@@ -3,8 +3,8 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    PaginatedResponse,
-    api::{ApiRequest, DataResponse, PathArgs, urlformat},
+    CatalogRef, PaginatedResponse,
+    api::{ApiError, ApiErrorKind, ApiRequest, DataResponse, PathArgs, Ref, urlformat},
 };
 
 /// A tag in the catalog.
@@ -31,6 +31,11 @@ impl ApiRequest for GetTag<'_> {
     fn path(&self) -> PathArgs {
         urlformat!("/catalog/v0/tags/{}", self.name)
     }
+
+    fn validate(&self) -> Result<(), ApiError> {
+        Ref::name(self.name)?;
+        Ok(())
+    }
 }
 
 /// List tags.
@@ -95,6 +100,24 @@ impl ApiRequest for CreateTag<'_> {
     }
 }
 
+impl CreateTag<'_> {
+    /// The tag that was already there, if the request failed with
+    /// `TAG_EXISTS`. Any other error, including other conflicts, returns
+    /// `None`.
+    pub fn existing(err: &ApiError) -> Option<Tag> {
+        match err.kind()? {
+            ApiErrorKind::TagExists {
+                catalog_ref: CatalogRef::Tag { name, hash },
+                ..
+            } => Some(Tag {
+                name: name.clone(),
+                hash: hash.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
 /// Delete a tag.
 #[derive(Debug, Clone)]
 pub struct DeleteTag<'a> {
@@ -112,6 +135,18 @@ impl ApiRequest for DeleteTag<'_> {
     fn path(&self) -> PathArgs {
         urlformat!("/catalog/v0/tags/{}", self.name)
     }
+
+    fn validate(&self) -> Result<(), ApiError> {
+        Ref::name(self.name)?;
+        Ok(())
+    }
+}
+
+impl DeleteTag<'_> {
+    /// Whether the request failed with `TAG_NOT_FOUND`.
+    pub fn missing(err: &ApiError) -> bool {
+        matches!(err.kind(), Some(ApiErrorKind::TagNotFound { .. }))
+    }
 }
 
 /// Rename a tag.
@@ -140,6 +175,11 @@ impl ApiRequest for RenameTag<'_> {
         urlformat!("/catalog/v0/tags/{}", self.name)
     }
 
+    fn validate(&self) -> Result<(), ApiError> {
+        Ref::name(self.name)?;
+        Ok(())
+    }
+
     fn body(&self) -> Option<impl Serialize> {
         Some(RenameTagBody {
             tag_name: self.new_name,
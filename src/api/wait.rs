@@ -0,0 +1,356 @@
+//! Waiting for a table to be ready.
+
+use std::time::Duration;
+
+use tracing::warn;
+
+use crate::api::{
+    ApiError, ApiErrorKind,
+    branch::{Branch, GetBranch, WatchSchedule},
+    table::{GetTable, Table},
+};
+
+/// The conditions for [`wait_for_table`] to consider a table ready, besides
+/// existing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableReadiness {
+    /// The fewest records the table must have. A table that doesn't report
+    /// a record count has none.
+    pub min_records: Option<u64>,
+    /// A snapshot the table must have moved on from, usually the one it was
+    /// at before an import or run.
+    pub after_snapshot_id: Option<i64>,
+}
+
+impl TableReadiness {
+    /// Whether the table meets the conditions.
+    pub fn is_ready(&self, table: &Table) -> bool {
+        self.min_records
+            .is_none_or(|n| table.records.unwrap_or(0) >= n)
+            && self
+                .after_snapshot_id
+                .is_none_or(|id| table.current_snapshot_id != Some(id))
+    }
+}
+
+/// The outcome of [`wait_for_table`].
+#[derive(Debug, Clone)]
+pub enum TableWait {
+    /// The table is ready.
+    Ready(Table),
+    /// The deadline passed first. Holds the table as it was last seen, or
+    /// `None` if it didn't exist yet.
+    TimedOut(Option<Table>),
+}
+
+/// Polls a table with `send_table` until `ready` accepts it.
+///
+/// If the table's ref is a branch, each poll first checks the branch head
+/// with a conditional request, and the table is only fetched again if the
+/// head moved. For other refs, the table is fetched on every poll. A table
+/// that doesn't exist yet, or whose namespace doesn't, is polled until it
+/// does.
+///
+/// `api_error` returns the [`ApiError`] inside an error, if any. Requests
+/// that fail with a transient error, or with no `ApiError` at all (like a
+/// network failure), are retried with backoff from `schedule`; other errors
+/// are returned.
+///
+/// `sleep` is called to wait between polls, and returns false once the
+/// deadline has passed, in which case [`TableWait::TimedOut`] is returned.
+pub fn wait_for_table<E: std::fmt::Display>(
+    req: GetTable<'_>,
+    mut schedule: WatchSchedule,
+    mut send_table: impl FnMut(GetTable<'_>) -> Result<Table, E>,
+    mut send_branch: impl FnMut(GetBranch<'_>) -> Result<Branch, E>,
+    api_error: impl Fn(&E) -> Option<&ApiError>,
+    mut ready: impl FnMut(&Table) -> Result<bool, E>,
+    mut sleep: impl FnMut(Duration) -> Result<bool, E>,
+) -> Result<TableWait, E> {
+    // The branch head when the table was last fetched.
+    let mut head: Option<String> = None;
+    let mut is_branch = true;
+    let mut last = None;
+
+    loop {
+        let delay = 'poll: {
+            let mut moved_to = None;
+            if is_branch {
+                let branch = send_branch(GetBranch {
+                    name: req.at_ref,
+                    if_none_match: head.as_deref(),
+                });
+
+                match branch {
+                    // The server may not support conditional requests.
+                    Ok(branch) if head.as_ref() == Some(&branch.hash) => {
+                        break 'poll schedule.succeeded();
+                    }
+                    Ok(branch) => moved_to = Some(branch.hash),
+                    Err(e) => match api_error(&e) {
+                        Some(ApiError::NotModified) => break 'poll schedule.succeeded(),
+                        // Not a branch; the table request will fail if the
+                        // ref doesn't exist at all.
+                        Some(ae) if !ae.is_transient() => is_branch = false,
+                        _ => break 'poll retry_delay(&mut schedule, req.name, &e),
+                    },
+                }
+            }
+
+            match send_table(req.clone()) {
+                Ok(table) if ready(&table)? => return Ok(TableWait::Ready(table)),
+                Ok(table) => last = Some(table),
+                Err(e) => match api_error(&e) {
+                    Some(ae) if is_missing(ae) => last = None,
+                    Some(ae) if !ae.is_transient() => return Err(e),
+                    _ => break 'poll retry_delay(&mut schedule, req.name, &e),
+                },
+            }
+
+            if moved_to.is_some() {
+                head = moved_to;
+            }
+
+            schedule.succeeded()
+        };
+
+        if !sleep(delay)? {
+            return Ok(TableWait::TimedOut(last));
+        }
+    }
+}
+
+/// Returns the backoff delay after a failed poll, and logs the error.
+fn retry_delay(schedule: &mut WatchSchedule, table: &str, e: &impl std::fmt::Display) -> Duration {
+    let delay = schedule.failed();
+    warn!(table, "failed to poll table, retrying in {delay:?}: {e}");
+    delay
+}
+
+fn is_missing(e: &ApiError) -> bool {
+    matches!(
+        e.kind(),
+        Some(ApiErrorKind::TableNotFound { .. } | ApiErrorKind::NamespaceNotFound { .. })
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        collections::{BTreeMap, VecDeque},
+    };
+
+    use chrono::Utc;
+
+    use super::*;
+    use crate::CatalogRef;
+
+    const INTERVAL: Duration = Duration::from_secs(1);
+
+    fn table(records: u64, snapshot_id: i64) -> Table {
+        Table {
+            id: uuid::Uuid::nil(),
+            name: "orders".to_owned(),
+            namespace: "bauplan".to_owned(),
+            kind: Default::default(),
+            records: Some(records),
+            size: None,
+            last_updated_at: Utc::now(),
+            fields: Vec::new(),
+            snapshots: None,
+            partitions: Vec::new(),
+            metadata_location: String::new(),
+            current_snapshot_id: Some(snapshot_id),
+            current_schema_id: None,
+            properties: BTreeMap::new(),
+        }
+    }
+
+    fn not_found() -> ApiError {
+        ApiError::ErrorResponse {
+            status: http::StatusCode::NOT_FOUND,
+            kind: ApiErrorKind::TableNotFound {
+                table_name: "orders".to_owned(),
+                input_ref: "main".to_owned(),
+                catalog_ref: CatalogRef::Branch {
+                    name: "main".to_owned(),
+                    hash: "a".to_owned(),
+                },
+            },
+            message: None,
+        }
+    }
+
+    fn not_a_branch() -> ApiError {
+        ApiError::ErrorResponse {
+            status: http::StatusCode::BAD_REQUEST,
+            kind: ApiErrorKind::NotABranchRef {
+                input_ref: "v1".to_owned(),
+            },
+            message: None,
+        }
+    }
+
+    /// A fake catalog that replays canned responses, one per poll.
+    struct Catalog {
+        heads: RefCell<VecDeque<Result<&'static str, ApiError>>>,
+        tables: RefCell<VecDeque<Result<Table, ApiError>>>,
+        table_fetches: Cell<u32>,
+        sleeps: Cell<u32>,
+        max_sleeps: u32,
+    }
+
+    impl Catalog {
+        fn new(
+            heads: impl IntoIterator<Item = Result<&'static str, ApiError>>,
+            tables: impl IntoIterator<Item = Result<Table, ApiError>>,
+        ) -> Self {
+            Self {
+                heads: RefCell::new(heads.into_iter().collect()),
+                tables: RefCell::new(tables.into_iter().collect()),
+                table_fetches: Cell::new(0),
+                sleeps: Cell::new(0),
+                max_sleeps: 10,
+            }
+        }
+
+        fn wait(&self, readiness: &TableReadiness) -> Result<TableWait, ApiError> {
+            let req = GetTable {
+                name: "orders",
+                at_ref: "main",
+                namespace: None,
+            };
+
+            wait_for_table(
+                req,
+                WatchSchedule::new(INTERVAL),
+                |_| {
+                    self.table_fetches.set(self.table_fetches.get() + 1);
+                    self.tables
+                        .borrow_mut()
+                        .pop_front()
+                        .expect("too many polls")
+                },
+                |req| {
+                    let hash = self
+                        .heads
+                        .borrow_mut()
+                        .pop_front()
+                        .expect("too many polls")?;
+                    if req.if_none_match == Some(hash) {
+                        return Err(ApiError::NotModified);
+                    }
+
+                    Ok(Branch {
+                        name: "main".to_owned(),
+                        hash: hash.to_owned(),
+                    })
+                },
+                |e| Some(e),
+                |t| Ok(readiness.is_ready(t)),
+                |_| {
+                    self.sleeps.set(self.sleeps.get() + 1);
+                    Ok(self.sleeps.get() < self.max_sleeps)
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn ready_immediately() {
+        let catalog = Catalog::new([Ok("a")], [Ok(table(10, 1))]);
+        let res = catalog.wait(&TableReadiness::default()).unwrap();
+
+        assert!(matches!(res, TableWait::Ready(t) if t.records == Some(10)));
+        assert_eq!(catalog.sleeps.get(), 0);
+    }
+
+    #[test]
+    fn ready_after_created() {
+        let catalog = Catalog::new(
+            [Ok("a"), Ok("a"), Ok("b"), Ok("c")],
+            [Err(not_found()), Ok(table(0, 1)), Ok(table(5, 2))],
+        );
+
+        let readiness = TableReadiness {
+            min_records: Some(1),
+            after_snapshot_id: None,
+        };
+
+        let res = catalog.wait(&readiness).unwrap();
+        assert!(matches!(res, TableWait::Ready(t) if t.current_snapshot_id == Some(2)));
+
+        // The table isn't fetched while the head is unchanged.
+        assert_eq!(catalog.table_fetches.get(), 3);
+        assert_eq!(catalog.sleeps.get(), 3);
+    }
+
+    #[test]
+    fn new_snapshot() {
+        let catalog = Catalog::new([Ok("a"), Ok("b")], [Ok(table(5, 1)), Ok(table(5, 2))]);
+        let readiness = TableReadiness {
+            min_records: None,
+            after_snapshot_id: Some(1),
+        };
+
+        let res = catalog.wait(&readiness).unwrap();
+        assert!(matches!(res, TableWait::Ready(t) if t.current_snapshot_id == Some(2)));
+    }
+
+    #[test]
+    fn not_a_branch_polls_table() {
+        let catalog = Catalog::new(
+            [Err(not_a_branch())],
+            [Err(not_found()), Err(not_found()), Ok(table(1, 1))],
+        );
+
+        let res = catalog.wait(&TableReadiness::default()).unwrap();
+        assert!(matches!(res, TableWait::Ready(_)));
+        assert_eq!(catalog.table_fetches.get(), 3);
+    }
+
+    #[test]
+    fn transient_errors_retried() {
+        let unavailable = ApiError::Other {
+            status: http::StatusCode::SERVICE_UNAVAILABLE,
+            kind: None,
+            message: None,
+        };
+
+        let catalog = Catalog::new([Err(unavailable), Ok("a")], [Ok(table(1, 1))]);
+        let res = catalog.wait(&TableReadiness::default()).unwrap();
+        assert!(matches!(res, TableWait::Ready(_)));
+    }
+
+    #[test]
+    fn other_errors_returned() {
+        let forbidden = ApiError::Other {
+            status: http::StatusCode::FORBIDDEN,
+            kind: None,
+            message: None,
+        };
+
+        let catalog = Catalog::new([Ok("a")], [Err(forbidden)]);
+        let res = catalog.wait(&TableReadiness::default());
+        assert!(matches!(res, Err(e) if e.status() == http::StatusCode::FORBIDDEN));
+    }
+
+    #[test]
+    fn times_out() {
+        let mut catalog = Catalog::new(
+            [Ok("a"), Ok("b"), Ok("b")],
+            [Err(not_found()), Ok(table(0, 1))],
+        );
+        catalog.max_sleeps = 3;
+
+        let readiness = TableReadiness {
+            min_records: Some(1),
+            after_snapshot_id: None,
+        };
+
+        let res = catalog.wait(&readiness).unwrap();
+        assert!(matches!(res, TableWait::TimedOut(Some(t)) if t.records == Some(0)));
+        assert_eq!(catalog.sleeps.get(), 3);
+    }
+}
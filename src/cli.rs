@@ -1,32 +1,43 @@
+pub(crate) mod alias;
+mod auth;
 mod branch;
 mod checkout;
 mod color;
 mod commit;
 mod config;
+mod git;
 mod init;
+mod input;
 mod job;
 mod namespace;
 mod parameter;
+mod plan_file;
 mod query;
+mod render;
 mod run;
 mod spinner;
 mod table;
 mod tag;
-mod yaml;
+
+pub(crate) use bauplan::grpc::job::Priority;
+pub(crate) use spinner::init_progress;
 
 use std::{io::Write as _, str::FromStr, time};
 
 use color::*;
 
-use anyhow::bail;
+use anyhow::{Context as _, bail};
 use bauplan::{
-    ApiError, ApiErrorKind, ApiRequest, ApiResponse, Profile,
+    ApiError, ApiErrorKind, ApiRequest, ApiResponse, OperationRequest, OperationResult, Profile,
+    Ref, RetryBudget,
+    branch::GetBranch,
     grpc::{self, generated as commanderpb},
+    tag::GetTag,
 };
 
 use clap::{Parser, Subcommand};
 use opentelemetry::trace::{SpanId, TraceFlags, TraceId};
-use tracing::debug;
+use tracing::{debug, warn};
 
 #[derive(Debug, Parser)]
 #[command(
@@ -60,46 +71,61 @@ impl std::fmt::Display for Output {
     }
 }
 
-/// A priority for a job, from 1-10, where 10 is the highest.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
-pub(crate) struct Priority(u32);
+/// key=value string pairs. A value of `@path` is read from the file at `path`
+/// when the pair is used; use `@@` for a value that starts with a literal `@`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct KeyValue(String, input::ParamSource);
 
-impl FromStr for Priority {
-    type Err = anyhow::Error;
+impl KeyValue {
+    /// The key and value, reading the value from its file if need be.
+    fn read(&self) -> anyhow::Result<(String, String)> {
+        let KeyValue(key, source) = self;
+        let value = source
+            .read(key)
+            .with_context(|| format!("failed to read the value of {key:?}"))?;
+        Ok((key.clone(), value))
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let priority = s.parse::<u32>()?;
-        if !(1..=10).contains(&priority) {
-            bail!("Invalid priority: {}", s);
-        }
+    /// Reads every pair, failing on the first value that can't be read.
+    fn read_all(pairs: &[KeyValue]) -> anyhow::Result<Vec<(String, String)>> {
+        pairs.iter().map(KeyValue::read).collect()
+    }
 
-        Ok(Priority(priority))
+    /// The pair as it would be passed, for repeating a command.
+    fn to_arg(&self) -> String {
+        self.1.to_arg(&self.0)
     }
 }
 
-/// key=value string pairs.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) struct KeyValue(String, String);
+impl FromStr for KeyValue {
+    type Err = anyhow::Error;
 
-impl KeyValue {
-    fn as_strs(&self) -> (&str, &str) {
-        (&self.0, &self.1)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, source) = input::ParamSource::parse(s, false)?;
+        Ok(KeyValue(key, source))
     }
+}
+
+/// A 'Name: value' HTTP header.
+#[derive(Clone, PartialEq, Eq)]
+pub(crate) struct HeaderArg(String, String);
 
-    fn into_strings(self) -> (String, String) {
-        (self.0, self.1)
+// The value may be a credential.
+impl std::fmt::Debug for HeaderArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HeaderArg({:?}, \"********\")", self.0)
     }
 }
 
-impl FromStr for KeyValue {
+impl FromStr for HeaderArg {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let Some((left, right)) = s.split_once('=') else {
-            bail!("Invalid key=value pair: {}", s);
+        let Some((name, value)) = s.split_once(':') else {
+            bail!("Invalid header, expected 'Name: value': {}", s);
         };
 
-        Ok(KeyValue(left.to_owned(), right.to_owned()))
+        Ok(HeaderArg(name.trim().to_owned(), value.trim().to_owned()))
     }
 }
 
@@ -116,12 +142,24 @@ pub(crate) struct GlobalArgs {
     /// Output format
     #[arg(long, short = 'O', global = true, default_value_t = Output::default())]
     pub output: Output,
+    /// With `-O json`, print errors to stderr as single-line JSON objects, so
+    /// that stdout only ever has the requested data
+    #[arg(long, global = true)]
+    pub json_errors_to_stderr: bool,
     /// Timeout (in seconds) for client operations (-1 = no timeout)
     #[arg(long, global = true)]
     pub client_timeout: Option<i64>,
     /// Print verbose logs
     #[arg(long, short = 'v', global = true)]
     pub verbose: bool,
+    /// Extra header to send with every API request, as 'Name: value' (repeatable)
+    #[arg(
+        long = "header",
+        short = 'H',
+        global = true,
+        value_name = "NAME: VALUE"
+    )]
+    pub headers: Vec<HeaderArg>,
 }
 
 #[derive(Debug, Subcommand)]
@@ -130,6 +168,8 @@ pub(crate) enum Command {
     Version,
     /// Print debug information about the current environment
     Info,
+    /// Show which user and organization the API key belongs to
+    Whoami,
     /// Execute a bauplan run
     Run(run::RunArgs),
     /// Manage branches
@@ -154,6 +194,10 @@ pub(crate) enum Command {
     Checkout(checkout::CheckoutArgs),
     /// Initialize a new bauplan project
     Init(init::InitArgs),
+    /// Manage command aliases
+    Alias(alias::AliasArgs),
+    /// Manage authentication
+    Auth(auth::AuthArgs),
 }
 
 pub(crate) struct Cli {
@@ -163,27 +207,37 @@ pub(crate) struct Cli {
     pub(crate) agent: ureq::Agent,
     pub(crate) multiprogress: indicatif::MultiProgress,
     pub(crate) trace_id: TraceId,
+    /// Shared by every API request the command makes, so that commands which
+    /// make many requests can't retry rate limits forever.
+    pub(crate) rate_limit: RetryBudget,
 }
 
 pub(crate) fn run(args: Args, multiprogress: indicatif::MultiProgress) -> anyhow::Result<()> {
     // Some commands don't require any config.
     match args.command {
         Command::Version => {
-            println!("bauplan {}", env!("BPLN_VERSION"));
+            match args.global.output {
+                Output::Json => render::emit_json(&Version {
+                    version: env!("BPLN_VERSION"),
+                })?,
+                Output::Tty => println!("bauplan {}", env!("BPLN_VERSION")),
+            }
+
             return Ok(());
         }
         Command::Config(config_args) => return config::handle(config_args, args.global),
-        Command::Init(init_args) => return init::handle(init_args),
+        Command::Init(init_args) => return init::handle(init_args, args.global.output),
+        Command::Alias(alias_args) => return alias::handle(alias_args, args.global),
+        Command::Auth(auth_args) => return auth::handle(auth_args, args.global),
         _ => (),
     }
 
-    let profile = if let Some(name) = args.global.profile.as_deref() {
-        Profile::from_env(name)
-    } else {
-        Profile::from_default_env()
-    };
+    let mut profile =
+        Profile::from_env_or_file(args.global.profile.as_deref())?.with_ua_product("bauplan-cli");
+    for HeaderArg(name, value) in &args.global.headers {
+        profile = profile.with_extra_header(name, value, false)?;
+    }
 
-    let profile = profile?.with_ua_product("bauplan-cli");
     profile.validate()?;
 
     // Allows error responses to be parsed.
@@ -206,6 +260,7 @@ pub(crate) fn run(args: Args, multiprogress: indicatif::MultiProgress) -> anyhow
     let trace_id = TraceId::from(rand::random::<u128>());
     debug!(%trace_id, command = ?args.command, "cli invocation");
 
+    let rate_limit = RetryBudget::new(profile.rate_limit_budget);
     let cli = Cli {
         profile,
         global: args.global,
@@ -213,14 +268,18 @@ pub(crate) fn run(args: Args, multiprogress: indicatif::MultiProgress) -> anyhow
         agent,
         multiprogress,
         trace_id,
+        rate_limit,
     };
 
-    match args.command {
+    let res = match args.command {
         Command::Version => unreachable!(),
         Command::Config(_) => unreachable!(),
         Command::Init(_) => unreachable!(),
+        Command::Alias(_) => unreachable!(),
+        Command::Auth(_) => unreachable!(),
         Command::Parameter(args) => parameter::handle(&cli, args),
         Command::Info => with_rt(handle_info(&cli)),
+        Command::Whoami => with_rt(handle_whoami(&cli)),
         Command::Run(args) => run::handle(&cli, args),
         Command::Branch(args) => branch::handle(&cli, args),
         Command::Tag(args) => tag::handle(&cli, args),
@@ -230,7 +289,16 @@ pub(crate) fn run(args: Args, multiprogress: indicatif::MultiProgress) -> anyhow
         Command::Query(args) => with_rt(query::handle(&cli, args)),
         Command::Job(args) => with_rt(job::handle(&cli, args)),
         Command::Checkout(args) => checkout::handle(&cli, args),
+    };
+
+    // Errors already include the summary.
+    if res.is_ok()
+        && let Some(summary) = cli.rate_limit.summary()
+    {
+        warn!("{summary}");
     }
+
+    res
 }
 
 fn with_rt<T, F: Future<Output = T>>(f: F) -> T {
@@ -257,14 +325,107 @@ impl Cli {
     }
 
     pub(crate) fn roundtrip<T: ApiRequest>(&self, req: T) -> anyhow::Result<T::Response> {
+        self.roundtrip_with_timeout(req, None)
+    }
+
+    /// Like [Cli::roundtrip], but with a timeout for this request only.
+    pub(crate) fn roundtrip_with_timeout<T: ApiRequest>(
+        &self,
+        req: T,
+        timeout: Option<time::Duration>,
+    ) -> anyhow::Result<T::Response> {
+        req.validate()?;
         let mut req = req.into_request(&self.profile)?;
         req.headers_mut()
             .insert("traceparent", self.traceparent().parse().unwrap());
-        let resp = self.agent.run(req)?;
-        let resp = <T::Response as ApiResponse>::from_response(resp.map(ureq::Body::into_reader))?;
+        if timeout.is_some() {
+            req = self
+                .agent
+                .configure_request(req)
+                .timeout_global(timeout)
+                .build();
+        }
+
+        let resp = bauplan::send_with_backoff(
+            &self.rate_limit,
+            req,
+            |req| anyhow::Ok(self.agent.run(req)?.map(ureq::Body::into_reader)),
+            std::thread::sleep,
+        )?;
+        let resp = <T::Response as ApiResponse>::from_response(resp)?;
         Ok(resp)
     }
 
+    /// Sends a merge or revert, retrying it if the response is lost. See
+    /// [bauplan::send_operation].
+    pub(crate) fn send_operation<R: OperationRequest>(
+        &self,
+        req: R,
+    ) -> anyhow::Result<OperationResult> {
+        bauplan::send_operation(
+            req,
+            |req| self.roundtrip(req),
+            |req| self.roundtrip(req),
+            response_lost,
+            std::thread::sleep,
+        )
+    }
+
+    /// Resolves the `HEAD` and `<branch>@{<time>}` shorthands in a ref passed
+    /// on the command line.
+    pub(crate) fn resolve_ref(&self, r: &str) -> anyhow::Result<String> {
+        bauplan::resolve_ref(r, self.profile.active_branch.as_deref(), |req| {
+            self.roundtrip(req)
+        })
+    }
+
+    /// Checks that `r`, a ref resolved with [Cli::resolve_ref], is a branch
+    /// before a command writes to it. This way a tag or commit is reported
+    /// up front, rather than by the server after a job has been planned.
+    ///
+    /// Whether a name is a branch or a tag has to be looked up. If the
+    /// lookup fails, the server is left to decide.
+    pub(crate) fn check_write_branch(&self, r: &str) -> anyhow::Result<()> {
+        if !Ref::parse(r)?.is_name() {
+            bail!(
+                "{r:?} is a commit, not a branch, so it can't be written to; \
+                 use a branch name, like \"main\""
+            );
+        }
+
+        let req = GetBranch {
+            name: r,
+            if_none_match: None,
+        };
+
+        match self.roundtrip(req) {
+            Ok(_) => return Ok(()),
+            Err(e)
+                if matches!(
+                    api_err_kind(&e),
+                    Some(
+                        ApiErrorKind::BranchNotFound { .. }
+                            | ApiErrorKind::RefNotFound { .. }
+                            | ApiErrorKind::NotABranchRef { .. }
+                    )
+                ) => {}
+            Err(e) => {
+                debug!(r#ref = r, error = %e, "failed to check the branch to write to");
+                return Ok(());
+            }
+        }
+
+        if self.roundtrip(GetTag { name: r }).is_ok() {
+            bail!(
+                "{r:?} is a tag, not a branch, so it can't be written to; to write \
+                 to a copy of it, run `bauplan branch create <name> --from-ref {r}` \
+                 and use the new branch"
+            );
+        }
+
+        Ok(())
+    }
+
     /// Wraps a gRPC request message with a `traceparent` metadata header.
     pub(crate) fn traced<T>(&self, msg: T) -> tonic::Request<T> {
         let mut req = tonic::Request::new(msg);
@@ -274,17 +435,118 @@ impl Cli {
     }
 }
 
+/// Whether the error that ends the command should be printed as JSON, with
+/// [print_json_error].
+pub(crate) fn json_errors(global: &GlobalArgs) -> bool {
+    global.json_errors_to_stderr && global.output == Output::Json
+}
+
+/// Prints the error that ended the command to stderr, as a single line of
+/// JSON.
+pub(crate) fn print_json_error(err: &anyhow::Error) -> std::io::Result<()> {
+    render::write_json_error(&mut std::io::stderr().lock(), err)
+}
+
+pub(crate) fn api_err(err: &anyhow::Error) -> Option<&ApiError> {
+    err.downcast_ref::<ApiError>()
+}
+
 pub(crate) fn api_err_kind(err: &anyhow::Error) -> Option<&ApiErrorKind> {
-    err.downcast_ref::<ApiError>()?.kind()
+    api_err(err)?.kind()
+}
+
+/// Whether a request may have been applied even though it failed.
+fn response_lost(err: &anyhow::Error) -> bool {
+    if let Some(err) = err.downcast_ref::<ApiError>() {
+        return err.response_lost();
+    }
+
+    matches!(
+        err.downcast_ref::<ureq::Error>(),
+        Some(ureq::Error::Timeout(_) | ureq::Error::Io(_))
+    )
 }
 
 pub(crate) fn format_grpc_status(status: tonic::Status) -> anyhow::Error {
     anyhow::anyhow!("{:?}: {}", status.code(), status.message())
 }
 
-async fn handle_info(cli: &Cli) -> anyhow::Result<()> {
+/// The output of `version`, as printed with `-O json`.
+#[derive(serde::Serialize)]
+struct Version {
+    version: &'static str,
+}
+
+/// The output of `info`, as printed with `-O json`.
+#[derive(serde::Serialize)]
+struct Info<'a> {
+    profile: &'a str,
+    active_branch: &'a str,
+    client_version: &'static str,
+    user: Option<InfoUser>,
+    organization: Option<InfoOrganization>,
+    runners: Vec<String>,
+}
+
+#[derive(serde::Serialize)]
+struct InfoUser {
+    id: Option<String>,
+    username: String,
+    full_name: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+struct InfoOrganization {
+    id: String,
+    name: String,
+    default_secret_key: Option<String>,
+    default_secret_public_key: Option<String>,
+}
+
+async fn handle_whoami(cli: &Cli) -> anyhow::Result<()> {
+    let mut client = grpc::Client::new_lazy(
+        &cli.profile,
+        cli.timeout.unwrap_or(time::Duration::from_secs(5)),
+    )?;
+
+    let user = client
+        .current_user(cli.traced(commanderpb::GetBauplanInfoRequest::default()))
+        .await
+        .map_err(format_grpc_status)?;
+
+    if cli.global.output == Output::Json {
+        return render::emit_json(&user);
+    }
+
     let mut out = anstream::stdout().lock();
+    match &user.full_name {
+        Some(name) => writeln!(&mut out, "{} ({name})", user.username)?,
+        None => writeln!(&mut out, "{}", user.username)?,
+    }
+
+    if let Some(id) = &user.id {
+        writeln!(&mut out, "{BLUE}{:<15}{BLUE:#} {id}", "User ID")?;
+    }
+
+    if let Some(name) = &user.organization_name {
+        let id = user.organization_id.as_deref().unwrap_or_default();
+        writeln!(
+            &mut out,
+            "{BLUE}{:<15}{BLUE:#} {name} ({id})",
+            "Organization"
+        )?;
+    }
+
+    writeln!(
+        &mut out,
+        "{BLUE}{:<15}{BLUE:#} {}",
+        "Branch prefix", user.branch_prefix
+    )?;
+
+    Ok(())
+}
 
+async fn handle_info(cli: &Cli) -> anyhow::Result<()> {
     let mut client = grpc::Client::new_lazy(
         &cli.profile,
         cli.timeout.unwrap_or(time::Duration::from_secs(5)),
@@ -299,6 +561,37 @@ async fn handle_info(cli: &Cli) -> anyhow::Result<()> {
     let profile_name = &cli.profile.name;
     let active_branch = cli.profile.active_branch.as_deref().unwrap_or("main");
 
+    if cli.global.output == Output::Json {
+        let user = match resp.user_info {
+            Some(user) => Some(InfoUser {
+                id: Some(user.id),
+                username: user.username,
+                full_name: Some(format!("{} {}", user.first_name, user.last_name)),
+            }),
+            None if !resp.user.is_empty() => Some(InfoUser {
+                id: None,
+                username: resp.user,
+                full_name: None,
+            }),
+            None => None,
+        };
+
+        return render::emit_json(&Info {
+            profile: profile_name,
+            active_branch,
+            client_version: env!("BPLN_VERSION"),
+            user,
+            organization: resp.organization_info.map(|org| InfoOrganization {
+                id: org.id,
+                name: org.name,
+                default_secret_key: org.default_parameter_secret_key,
+                default_secret_public_key: org.default_parameter_secret_public_key,
+            }),
+            runners: resp.runners.into_iter().map(|r| r.hostname).collect(),
+        });
+    }
+
+    let mut out = anstream::stdout().lock();
     writeln!(&mut out, "{GREEN}{:<35}{GREEN:#} {profile_name}", "Profile")?;
     writeln!(
         &mut out,
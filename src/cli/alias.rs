@@ -0,0 +1,389 @@
+use std::{collections::BTreeMap, ffi::OsString, io::Write as _};
+
+use anyhow::bail;
+use bauplan::{Profile, yaml};
+use clap::CommandFactory as _;
+use serde::Serialize;
+use tabwriter::TabWriter;
+
+use crate::cli::{Args, GlobalArgs, Output, color::*, render::emit_json};
+
+/// Global flags that take a value, and so may come before the command.
+const GLOBAL_VALUE_FLAGS: &[&str] = &[
+    "-P",
+    "--profile",
+    "-O",
+    "--output",
+    "--client-timeout",
+    "-H",
+    "--header",
+];
+
+/// Global flags that an alias can't set. The profile is where the alias came
+/// from, and headers may carry credentials; neither should change without the
+/// user seeing it on the command line.
+const FORBIDDEN_FLAGS: &[&str] = &["-P", "--profile", "-H", "--header"];
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct AliasArgs {
+    #[command(subcommand)]
+    pub command: AliasCommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub(crate) enum AliasCommand {
+    /// List the aliases in the profile
+    #[clap(alias = "list")]
+    Ls,
+    /// Set an alias
+    Set(AliasSetArgs),
+    /// Remove an alias
+    #[clap(alias = "delete")]
+    Rm(AliasRmArgs),
+}
+
+#[derive(Debug, clap::Args)]
+#[command(after_long_help = CliExamples("
+  # Define an alias for a common run invocation, then use it as
+  # \"bauplan ci-run --ref main\"
+  bauplan alias set ci-run \"run --no-cache --strict -O json\"
+"))]
+pub(crate) struct AliasSetArgs {
+    /// Name of the alias
+    pub name: String,
+    /// The command to run, as it would be typed after `bauplan`
+    pub command: String,
+}
+
+#[derive(Debug, clap::Args)]
+#[command(after_long_help = CliExamples("
+  # Remove an alias
+  bauplan alias rm ci-run
+"))]
+pub(crate) struct AliasRmArgs {
+    /// Name of the alias
+    pub name: String,
+}
+
+pub(crate) fn handle(args: AliasArgs, global: GlobalArgs) -> anyhow::Result<()> {
+    let profile = Profile::from_env_or_file(global.profile.as_deref())?;
+
+    match args.command {
+        AliasCommand::Ls => list_aliases(&profile, global.output),
+        AliasCommand::Set(args) => set_alias(&profile, args, global.output),
+        AliasCommand::Rm(args) => remove_alias(&profile, args, global.output),
+    }
+}
+
+fn list_aliases(profile: &Profile, output: Output) -> anyhow::Result<()> {
+    match output {
+        Output::Json => emit_json(&profile.aliases)?,
+        Output::Tty => {
+            let mut tw = TabWriter::new(anstream::stdout().lock());
+            writeln!(&mut tw, "NAME\tCOMMAND")?;
+            for (name, command) in &profile.aliases {
+                writeln!(&mut tw, "{name}\t{command}")?;
+            }
+
+            tw.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The result of `alias set` or `alias rm`, as printed with `-O json`.
+#[derive(Serialize)]
+struct AliasChange<'a> {
+    profile: &'a str,
+    alias: &'a str,
+    /// The new command, or `None` if the alias was removed.
+    command: Option<&'a str>,
+}
+
+fn set_alias(profile: &Profile, args: AliasSetArgs, output: Output) -> anyhow::Result<()> {
+    let AliasSetArgs { name, command } = args;
+
+    if name.is_empty() || name.starts_with('-') || name.contains(char::is_whitespace) {
+        bail!("invalid alias name: {name:?}");
+    } else if is_builtin(&name) {
+        bail!("{name:?} is a built-in command, and can't be an alias");
+    }
+
+    // Check that the alias parses, and doesn't form a loop with the
+    // existing ones.
+    let mut aliases = profile.aliases.clone();
+    aliases.insert(name.clone(), command.clone());
+    expand(&aliases, &name)?;
+
+    yaml::edit_locked(&profile.config_path, |doc| {
+        let mut m = yaml::mapping_at_path(doc, &["profiles", &profile.name, "aliases"])?;
+        yaml::upsert_str(&mut m, &name, &command);
+        Ok(())
+    })?;
+
+    eprintln!("Set alias {name:?} for profile {:?}", profile.name);
+    if output == Output::Json {
+        emit_json(&AliasChange {
+            profile: &profile.name,
+            alias: &name,
+            command: Some(&command),
+        })?;
+    }
+
+    Ok(())
+}
+
+fn remove_alias(profile: &Profile, args: AliasRmArgs, output: Output) -> anyhow::Result<()> {
+    let name = args.name;
+    if !profile.aliases.contains_key(&name) {
+        bail!("alias not found: {name:?}");
+    }
+
+    yaml::edit_locked(&profile.config_path, |doc| {
+        yaml::mapping_at_path(doc, &["profiles", &profile.name, "aliases"])?.remove(&name);
+        Ok(())
+    })?;
+
+    eprintln!("Removed alias {name:?} from profile {:?}", profile.name);
+    if output == Output::Json {
+        emit_json(&AliasChange {
+            profile: &profile.name,
+            alias: &name,
+            command: None,
+        })?;
+    }
+
+    Ok(())
+}
+
+/// If the command in `args` (the first argument after any global flags) is an
+/// alias in the selected profile, replaces it with the alias's expansion.
+/// Returns the new arguments, along with the name of the alias, if one was
+/// expanded.
+///
+/// This runs before the arguments are parsed, so failing to load the profile
+/// isn't an error here; the arguments are returned as is, and any error is
+/// reported later.
+pub(crate) fn expand_args(
+    mut args: Vec<OsString>,
+) -> anyhow::Result<(Vec<OsString>, Option<String>)> {
+    let Some(pos) = command_position(&args) else {
+        return Ok((args, None));
+    };
+
+    let Some(command) = args[pos].to_str().map(str::to_owned) else {
+        return Ok((args, None));
+    };
+
+    if is_builtin(&command) {
+        return Ok((args, None));
+    }
+
+    let profile = Profile::from_env_or_file(profile_arg(&args[..pos]).as_deref());
+
+    let Ok(profile) = profile else {
+        return Ok((args, None));
+    };
+
+    let Some(expanded) = expand(&profile.aliases, &command)? else {
+        return Ok((args, None));
+    };
+
+    args.splice(pos..=pos, expanded.into_iter().map(OsString::from));
+    Ok((args, Some(command)))
+}
+
+/// Expands `name` if it's an alias, following aliases of aliases. Returns
+/// None if it isn't an alias.
+fn expand(aliases: &BTreeMap<String, String>, name: &str) -> anyhow::Result<Option<Vec<String>>> {
+    let mut chain: Vec<String> = Vec::new();
+    let mut words = vec![name.to_owned()];
+
+    while !is_builtin(&words[0])
+        && let Some(value) = aliases.get(&words[0])
+    {
+        let alias = words[0].clone();
+        if chain.contains(&alias) {
+            chain.push(alias);
+            bail!("alias {name:?} is recursive: {}", chain.join(" -> "));
+        }
+
+        words.splice(0..1, split_alias(&alias, value)?);
+        chain.push(alias);
+    }
+
+    Ok((!chain.is_empty()).then_some(words))
+}
+
+/// Splits the value of an alias into arguments, rejecting the global flags
+/// that aliases can't set.
+fn split_alias(name: &str, value: &str) -> anyhow::Result<Vec<String>> {
+    let Some(words) = shlex::split(value) else {
+        bail!("alias {name:?} has invalid quoting: {value}");
+    };
+
+    if words.is_empty() {
+        bail!("alias {name:?} is empty");
+    }
+
+    if let Some(flag) = words.iter().find(|w| is_forbidden_flag(w)) {
+        bail!("alias {name:?} can't set {flag}; pass it on the command line instead");
+    }
+
+    Ok(words)
+}
+
+fn is_forbidden_flag(word: &str) -> bool {
+    FORBIDDEN_FLAGS.iter().any(|flag| {
+        if flag.starts_with("--") {
+            word == *flag || word.starts_with(&format!("{flag}="))
+        } else {
+            // Short flags can have the value attached, like `-Pprod`.
+            word.starts_with(flag)
+        }
+    })
+}
+
+fn is_builtin(name: &str) -> bool {
+    name == "help" || Args::command().find_subcommand(name).is_some()
+}
+
+/// Returns the index of the command in `args`, skipping over the binary name
+/// and any global flags before it.
+fn command_position(args: &[OsString]) -> Option<usize> {
+    let mut i = 1;
+    while let Some(arg) = args.get(i) {
+        let arg = arg.to_str()?;
+        if arg == "--" {
+            return None;
+        } else if !arg.starts_with('-') {
+            return Some(i);
+        }
+
+        i += if GLOBAL_VALUE_FLAGS.contains(&arg) {
+            2
+        } else {
+            1
+        };
+    }
+
+    None
+}
+
+/// Returns the profile selected by the global flags in `args`, if any.
+fn profile_arg(args: &[OsString]) -> Option<String> {
+    let mut profile = None;
+    let mut args = args.iter().filter_map(|a| a.to_str());
+    while let Some(arg) = args.next() {
+        if arg == "-P" || arg == "--profile" {
+            profile = args.next().map(str::to_owned);
+        } else if let Some(name) = arg.strip_prefix("--profile=") {
+            profile = Some(name.to_owned());
+        } else if let Some(name) = arg.strip_prefix("-P") {
+            profile = Some(name.to_owned());
+        }
+    }
+
+    profile
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn aliases(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|&(k, v)| (k.to_owned(), v.to_owned()))
+            .collect()
+    }
+
+    fn os_args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn expansion() -> anyhow::Result<()> {
+        let aliases = aliases(&[
+            ("ci-run", "run --no-cache --strict -O json"),
+            ("nightly", "ci-run --name 'nightly refresh'"),
+        ]);
+
+        assert_eq!(
+            expand(&aliases, "ci-run")?.unwrap(),
+            ["run", "--no-cache", "--strict", "-O", "json"]
+        );
+        assert_eq!(
+            expand(&aliases, "nightly")?.unwrap(),
+            [
+                "run",
+                "--no-cache",
+                "--strict",
+                "-O",
+                "json",
+                "--name",
+                "nightly refresh"
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_passthrough() -> anyhow::Result<()> {
+        let aliases = aliases(&[("ci-run", "run --no-cache"), ("run", "version")]);
+
+        assert_eq!(expand(&aliases, "nope")?, None);
+        // Built-in commands can't be shadowed.
+        assert_eq!(expand(&aliases, "run")?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn recursion() {
+        let aliases = aliases(&[("a", "b --flag"), ("b", "c"), ("c", "a"), ("d", "d")]);
+
+        let err = expand(&aliases, "a").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "alias \"a\" is recursive: a -> b -> c -> a"
+        );
+
+        let err = expand(&aliases, "d").unwrap_err();
+        assert_eq!(err.to_string(), "alias \"d\" is recursive: d -> d");
+    }
+
+    #[test]
+    fn forbidden_flags() {
+        for value in [
+            "run -P prod",
+            "run --profile prod",
+            "run --profile=prod",
+            "run -Pprod",
+            "run -H 'X-Team: data'",
+            "run --header=X-Team:data",
+        ] {
+            let aliases = aliases(&[("sneaky", value)]);
+            assert!(expand(&aliases, "sneaky").is_err(), "{value}");
+        }
+
+        let aliases = aliases(&[("fine", "run -p ./project --param x=1")]);
+        assert!(expand(&aliases, "fine").is_ok());
+    }
+
+    #[test]
+    fn command_after_global_flags() {
+        let args = os_args(&[
+            "bauplan", "-P", "ci", "-O", "json", "-v", "ci-run", "--ref", "x",
+        ]);
+        assert_eq!(command_position(&args), Some(6));
+        assert_eq!(profile_arg(&args[..6]).as_deref(), Some("ci"));
+
+        let args = os_args(&["bauplan", "--profile=ci", "ci-run"]);
+        assert_eq!(command_position(&args), Some(2));
+        assert_eq!(profile_arg(&args[..2]).as_deref(), Some("ci"));
+
+        assert_eq!(command_position(&os_args(&["bauplan", "--help"])), None);
+    }
+}
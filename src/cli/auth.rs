@@ -0,0 +1,295 @@
+use std::{
+    io::{self, IsTerminal as _, Write as _},
+    time,
+};
+
+use anyhow::{Context as _, bail};
+use bauplan::{
+    ApiKeySource, Profile,
+    grpc::{self, generated as commanderpb},
+    keychain, yaml,
+};
+use serde::Serialize;
+use tabwriter::TabWriter;
+
+use crate::cli::{
+    GlobalArgs, HeaderArg, Output, color::*, format_grpc_status, render::emit_json, with_rt,
+};
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct AuthArgs {
+    #[command(subcommand)]
+    pub command: AuthCommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub(crate) enum AuthCommand {
+    /// Log in with an API key
+    Login(AuthLoginArgs),
+    /// Show which credentials are in use
+    Status,
+    /// Remove the stored credentials
+    Logout,
+}
+
+#[derive(Debug, clap::Args)]
+#[command(after_long_help = CliExamples("
+  # Log in, storing the API key in the config file
+  bauplan auth login
+
+  # Log in to another profile, storing the API key in the OS keychain
+  bauplan -P staging auth login --keychain
+"))]
+pub(crate) struct AuthLoginArgs {
+    /// Store the API key in the OS keychain, instead of the config file
+    #[arg(long)]
+    pub keychain: bool,
+}
+
+/// The credentials in effect for a profile, for `auth status`.
+#[derive(Debug, Serialize)]
+struct AuthStatus<'a> {
+    profile: &'a str,
+    source: ApiKeySource,
+    config_path: &'a std::path::Path,
+    /// Stored credentials that aren't in use, because the environment
+    /// overrides them.
+    shadowed: Option<ApiKeySource>,
+}
+
+#[derive(Debug, Serialize)]
+struct LoginInfo<'a> {
+    profile: &'a str,
+    username: String,
+    organization: Option<String>,
+}
+
+/// The result of `auth logout`, as printed with `-O json`.
+#[derive(Debug, Serialize)]
+struct LogoutInfo<'a> {
+    profile: &'a str,
+    /// Where the removed credentials were stored, if there were any.
+    removed: Option<ApiKeySource>,
+}
+
+pub(crate) fn handle(args: AuthArgs, global: GlobalArgs) -> anyhow::Result<()> {
+    let profile = Profile::from_env_or_file(global.profile.as_deref())?;
+
+    match args.command {
+        AuthCommand::Login(args) => login(&profile, args, &global),
+        AuthCommand::Status => status(&profile, global.output),
+        AuthCommand::Logout => logout(&profile, global.output),
+    }
+}
+
+fn login(profile: &Profile, args: AuthLoginArgs, global: &GlobalArgs) -> anyhow::Result<()> {
+    let api_key = read_api_key()?;
+
+    // Check the key before storing it, so that a typo doesn't replace
+    // working credentials.
+    let mut candidate = profile
+        .clone()
+        .with_api_key(api_key.clone())
+        .with_ua_product("bauplan-cli");
+    for HeaderArg(name, value) in &global.headers {
+        candidate = candidate.with_extra_header(name, value, false)?;
+    }
+
+    candidate.validate()?;
+
+    let timeout = match global.client_timeout {
+        Some(v) if v > 0 => time::Duration::from_secs(v as _),
+        _ => time::Duration::from_secs(10),
+    };
+
+    let resp = with_rt(async {
+        grpc::Client::new_lazy(&candidate, timeout)?
+            .get_bauplan_info(commanderpb::GetBauplanInfoRequest::default())
+            .await
+            .map_err(format_grpc_status)
+    })
+    .context("API key was rejected; no credentials were changed")?
+    .into_inner();
+
+    if args.keychain {
+        keychain::set_api_key(&profile.name, &api_key)?;
+    }
+
+    let stored = stored_profile(profile);
+    let key_changed = stored.as_ref().and_then(|p| p.api_key.as_deref()) != Some(api_key.as_str());
+    yaml::edit_locked(&profile.config_path, |doc| {
+        let mut m = yaml::mapping_at_path(doc, &["profiles", &profile.name])?;
+        if args.keychain {
+            m.remove("api_key");
+            yaml::upsert_str(&mut m, "api_key_store", "keychain");
+        } else {
+            m.remove("api_key_store");
+            yaml::upsert_str(&mut m, "api_key", &api_key);
+        }
+
+        // Like `config set api_key`, a new key resets the active branch.
+        if key_changed {
+            yaml::upsert_str(&mut m, "active_branch", "main");
+        }
+
+        Ok(())
+    })?;
+
+    // A key stored in the config file takes precedence over the keychain, so
+    // the old entry is only cleaned up once the file is updated.
+    if !args.keychain && stored_source(stored.as_ref()) == Some(ApiKeySource::Keychain) {
+        let _ = keychain::delete_api_key(&profile.name);
+    }
+
+    let info = LoginInfo {
+        profile: &profile.name,
+        username: match resp.user_info {
+            Some(user) => user.username,
+            None => resp.user,
+        },
+        organization: resp.organization_info.map(|org| org.name),
+    };
+
+    match global.output {
+        Output::Json => emit_json(&info)?,
+        Output::Tty => {
+            let org = info
+                .organization
+                .as_deref()
+                .map(|org| format!(" ({org})"))
+                .unwrap_or_default();
+            anstream::eprintln!(
+                "{GREEN}Logged in as {}{org}{GREEN:#} on profile {:?}",
+                info.username,
+                profile.name
+            );
+
+            if key_changed {
+                eprintln!(
+                    "Active branch reset to \"main\" for profile {:?}",
+                    profile.name
+                );
+            }
+        }
+    }
+
+    if profile.api_key_source == Some(ApiKeySource::Env) {
+        anstream::eprintln!(
+            "{YELLOW}BAUPLAN_API_KEY is set, and overrides the stored API key{YELLOW:#}"
+        );
+    }
+
+    Ok(())
+}
+
+/// Reads an API key without echoing it: from the terminal if there is one,
+/// or else from the first line of stdin.
+fn read_api_key() -> anyhow::Result<String> {
+    let api_key = if io::stdin().is_terminal() {
+        rpassword::prompt_password("API key: ")?
+    } else {
+        let mut line = String::new();
+        io::stdin().read_line(&mut line)?;
+        line
+    };
+
+    let api_key = api_key.trim();
+    if api_key.is_empty() {
+        bail!("no API key provided");
+    }
+
+    Ok(api_key.to_owned())
+}
+
+fn status(profile: &Profile, output: Output) -> anyhow::Result<()> {
+    let Some(source) = profile.api_key_source else {
+        bail!(
+            "not logged in on profile {:?}; run `bauplan auth login`",
+            profile.name
+        );
+    };
+
+    let shadowed = match source {
+        ApiKeySource::Env => stored_source(stored_profile(profile).as_ref()),
+        _ => None,
+    };
+
+    let status = AuthStatus {
+        profile: &profile.name,
+        source,
+        config_path: &profile.config_path,
+        shadowed,
+    };
+
+    match output {
+        Output::Json => emit_json(&status)?,
+        Output::Tty => {
+            let source = match source {
+                ApiKeySource::ConfigFile => {
+                    format!("config file ({})", profile.config_path.display())
+                }
+                source => source.to_string(),
+            };
+
+            let mut tw = TabWriter::new(anstream::stdout().lock()).ansi(true);
+            writeln!(&mut tw, "{GREEN}Profile{GREEN:#}\t{}", profile.name)?;
+            writeln!(&mut tw, "{GREEN}API Key{GREEN:#}\t*********")?;
+            writeln!(&mut tw, "{GREEN}Source{GREEN:#}\t{source}")?;
+            if let Some(shadowed) = shadowed {
+                writeln!(&mut tw, "{GREEN}Overridden{GREEN:#}\t{shadowed}")?;
+            }
+
+            tw.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+fn logout(profile: &Profile, output: Output) -> anyhow::Result<()> {
+    let removed = stored_source(stored_profile(profile).as_ref());
+    match removed {
+        Some(source) => {
+            if source == ApiKeySource::Keychain {
+                keychain::delete_api_key(&profile.name)?;
+            }
+
+            yaml::edit_locked(&profile.config_path, |doc| {
+                let mut m = yaml::mapping_at_path(doc, &["profiles", &profile.name])?;
+                m.remove("api_key");
+                m.remove("api_key_store");
+                Ok(())
+            })?;
+
+            eprintln!("Removed stored credentials for profile {:?}", profile.name);
+        }
+        None => eprintln!("No stored credentials for profile {:?}", profile.name),
+    }
+
+    if profile.api_key_source == Some(ApiKeySource::Env) {
+        anstream::eprintln!(
+            "{YELLOW}BAUPLAN_API_KEY is still set, and will be used until it's unset{YELLOW:#}"
+        );
+    }
+
+    if output == Output::Json {
+        emit_json(&LogoutInfo {
+            profile: &profile.name,
+            removed,
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Reads the profile as stored, without the environment overriding it.
+fn stored_profile(profile: &Profile) -> Option<Profile> {
+    match profile.api_key_source {
+        Some(ApiKeySource::Env) => Profile::read(&profile.config_path, Some(&profile.name)).ok(),
+        _ => Some(profile.clone()),
+    }
+}
+
+fn stored_source(stored: Option<&Profile>) -> Option<ApiKeySource> {
+    stored.and_then(|p| p.api_key_source)
+}
@@ -1,16 +1,25 @@
 use std::{
     collections::BTreeMap,
-    io::{Write as _, stdout},
+    io::{self, Write as _, stderr, stdout},
+    thread, time,
 };
 
-use crate::cli::{Cli, Output, api_err_kind, checkout, color::*};
+use crate::cli::{
+    Cli, Output, api_err, api_err_kind, checkout,
+    color::*,
+    commit,
+    render::{FormatArgs, RefChange, emit_json, render, write_json_array},
+};
 use anyhow::bail;
 use bauplan::{
-    ApiErrorKind,
+    ApiError, ApiErrorKind, OperationResult,
     branch::*,
-    table::{GetTables, Table},
+    namespace::GetNamespaces,
+    table::{GetTables, RefSummary, Table, summarize_namespaces},
+    yaml,
 };
 use tabwriter::TabWriter;
+use tracing::warn;
 
 #[derive(Debug, clap::Args)]
 pub(crate) struct BranchArgs {
@@ -38,6 +47,12 @@ pub(crate) enum BranchCommand {
     Merge(BranchMergeArgs),
     /// Rename a branch
     Rename(BranchRenameArgs),
+    /// Print the head of a branch each time it changes
+    Watch(BranchWatchArgs),
+    /// Show table counts, rows and sizes for a branch
+    Summary(BranchSummaryArgs),
+    /// Show the commit history of a branch (the same as `bauplan commit`)
+    Log(commit::CommitArgs),
 }
 
 #[derive(Debug, clap::Args)]
@@ -119,6 +134,9 @@ pub(crate) struct BranchRmArgs {
 
   # Get with namespace filter
   bauplan branch get username.branch --namespace raw_data
+
+  # Include sizes and record counts
+  bauplan branch get username.dev_branch --format wide
 "))]
 pub(crate) struct BranchGetArgs {
     /// Branch name
@@ -126,6 +144,8 @@ pub(crate) struct BranchGetArgs {
     /// Filter by namespace (exact match or regex)
     #[arg(short, long)]
     pub namespace: Option<String>,
+    #[command(flatten)]
+    pub format: FormatArgs,
 }
 
 #[derive(Debug, clap::Args)]
@@ -186,6 +206,35 @@ pub(crate) struct BranchRenameArgs {
     pub new_branch_name: String,
 }
 
+#[derive(Debug, clap::Args)]
+#[command(after_long_help = CliExamples("
+  # Print the new head of main each time it moves
+  bauplan branch watch main
+
+  # Poll once a minute
+  bauplan branch watch main --poll-interval 60
+"))]
+pub(crate) struct BranchWatchArgs {
+    /// Branch name
+    pub branch_name: String,
+    /// Seconds between polls (jittered)
+    #[arg(long, default_value_t = 15)]
+    pub poll_interval: u64,
+}
+
+#[derive(Debug, clap::Args)]
+#[command(after_long_help = CliExamples("
+  # Count tables, rows and bytes on main
+  bauplan branch summary main
+
+  # Output as JSON
+  bauplan branch summary username.dev_branch -O json
+"))]
+pub(crate) struct BranchSummaryArgs {
+    /// Branch name
+    pub branch_name: String,
+}
+
 #[derive(serde::Serialize)]
 struct JsonDiff<'a> {
     added: Vec<&'a Table>,
@@ -202,6 +251,9 @@ pub(crate) fn handle(cli: &Cli, args: BranchArgs) -> anyhow::Result<()> {
         BranchCommand::Diff(args) => diff_branch(cli, args),
         BranchCommand::Merge(args) => merge_branch(cli, args),
         BranchCommand::Rename(args) => rename_branch(cli, args),
+        BranchCommand::Watch(args) => watch_branch(cli, args),
+        BranchCommand::Summary(args) => summarize_branch(cli, args),
+        BranchCommand::Log(args) => commit::handle(cli, args),
     }
 }
 
@@ -235,11 +287,7 @@ fn list_branches(cli: &Cli, args: BranchLsArgs) -> anyhow::Result<()> {
     let branches = bauplan::paginate(req, limit, |r| cli.roundtrip(r))?;
 
     match cli.global.output {
-        Output::Json => {
-            let all_branches = branches.collect::<anyhow::Result<Vec<_>>>()?;
-            serde_json::to_writer(stdout(), &all_branches)?;
-            println!();
-        }
+        Output::Json => write_json_array(&mut stdout().lock(), branches)?,
         Output::Tty => {
             let mut tw = TabWriter::new(stdout()).ansi(true);
             writeln!(&mut tw, "NAME\tZONE\tHASH")?;
@@ -271,6 +319,7 @@ fn get_branch(cli: &Cli, args: BranchGetArgs) -> anyhow::Result<()> {
     let BranchGetArgs {
         branch_name,
         namespace,
+        format,
     } = args;
 
     let req = GetTables {
@@ -280,25 +329,64 @@ fn get_branch(cli: &Cli, args: BranchGetArgs) -> anyhow::Result<()> {
     };
 
     let tables = bauplan::paginate(req, None, |r| cli.roundtrip(r))?;
+    let tables = tables.collect::<anyhow::Result<Vec<_>>>()?;
+    render(tables.as_slice(), format.resolve(cli.global.output))
+}
 
-    match cli.global.output {
-        Output::Json => {
-            let all_tables = tables.collect::<anyhow::Result<Vec<_>>>()?;
-            serde_json::to_writer(stdout(), &all_tables)?;
-            println!();
+fn summarize_branch(cli: &Cli, args: BranchSummaryArgs) -> anyhow::Result<()> {
+    let BranchSummaryArgs { branch_name } = args;
+
+    let req = GetNamespaces {
+        at_ref: &branch_name,
+        filter_by_name: None,
+    };
+
+    let namespaces = bauplan::paginate(req, None, |r| cli.roundtrip(r))?
+        .map(|ns| ns.map(|ns| ns.name))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    // There's no endpoint for this, so we have to list every table.
+    if namespaces.len() > 50 {
+        warn!(
+            "summarizing {} namespaces; this lists every table and may take a while",
+            namespaces.len()
+        );
+    }
+
+    let summary = summarize_namespaces(&branch_name, &namespaces, |ns| {
+        let req = GetTables {
+            at_ref: &branch_name,
+            filter_by_name: None,
+            filter_by_namespace: Some(ns),
+        };
+
+        let mut summary = RefSummary::new(branch_name.as_str());
+        for table in bauplan::paginate(req, None, |r| cli.roundtrip(r))? {
+            // The namespace filter may also match by regex.
+            let table = table?;
+            if table.namespace == ns {
+                summary.add(&table);
+            }
         }
+
+        anyhow::Ok(summary)
+    })?;
+
+    match cli.global.output {
+        Output::Json => emit_json(&summary)?,
         Output::Tty => {
-            let mut tw = TabWriter::new(stdout());
-            writeln!(&mut tw, "NAMESPACE\tNAME\tKIND")?;
-            for table in tables {
-                let table = table?;
-                writeln!(
-                    &mut tw,
-                    "{}\t{}\t{}",
-                    table.namespace, table.name, table.kind
-                )?;
-            }
+            let last_updated_at = summary
+                .last_updated_at
+                .map(|dt| dt.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+                .unwrap_or_else(|| "-".to_owned());
 
+            let mut tw = TabWriter::new(stdout());
+            writeln!(&mut tw, "TABLES\tROWS\tSIZE (BYTES)\tLAST UPDATED")?;
+            writeln!(
+                &mut tw,
+                "{}\t{}\t{}\t{}",
+                summary.tables, summary.total_records, summary.total_size, last_updated_at
+            )?;
             tw.flush()?;
         }
     }
@@ -313,30 +401,40 @@ fn create_branch(cli: &Cli, args: BranchCreateArgs) -> anyhow::Result<()> {
         if_not_exists,
     } = args;
 
-    let from_ref = from_ref
-        .as_deref()
-        .or(cli.profile.active_branch.as_deref())
-        .unwrap_or("main");
+    let from_ref = cli.resolve_ref(
+        from_ref
+            .as_deref()
+            .or(cli.profile.active_branch.as_deref())
+            .unwrap_or("main"),
+    )?;
 
     let req = CreateBranch {
         name: &branch_name,
-        from_ref,
+        from_ref: &from_ref,
     };
 
-    if let Err(e) = cli.roundtrip(req) {
-        if if_not_exists && matches!(api_err_kind(&e), Some(ApiErrorKind::BranchExists { .. })) {
-            eprintln!("Branch {branch_name:?} already exists");
-            return Ok(());
-        } else {
-            return Err(e);
-        }
-    }
+    let branch = match cli.roundtrip(req) {
+        Ok(branch) => branch,
+        Err(e) => match api_err(&e).and_then(CreateBranch::existing) {
+            Some(existing) if if_not_exists => {
+                eprintln!("Branch {branch_name:?} already exists");
+                if cli.global.output == Output::Json {
+                    emit_json(&RefChange::unchanged(Some(existing)))?;
+                }
+
+                return Ok(());
+            }
+            _ => return Err(e),
+        },
+    };
 
     eprintln!("Created branch \"{branch_name}\"");
-    anstream::eprintln!(
-        "{GREEN}TIP:{GREEN:#} To switch to the new branch, run:",
-    );
+    anstream::eprintln!("{GREEN}TIP:{GREEN:#} To switch to the new branch, run:",);
     eprintln!("\tbauplan checkout {branch_name:?}");
+    if cli.global.output == Output::Json {
+        emit_json(&RefChange::changed(branch))?;
+    }
+
     Ok(())
 }
 
@@ -348,16 +446,23 @@ fn delete_branch(cli: &Cli, args: BranchRmArgs) -> anyhow::Result<()> {
 
     let req = DeleteBranch { name: &branch_name };
 
-    if let Err(e) = cli.roundtrip(req) {
-        if if_exists && matches!(api_err_kind(&e), Some(ApiErrorKind::BranchNotFound { .. })) {
+    let branch = match cli.roundtrip(req) {
+        Ok(branch) => branch,
+        Err(e) if if_exists && api_err(&e).is_some_and(DeleteBranch::missing) => {
             eprintln!("Branch \"{branch_name}\" does not exist");
+            if cli.global.output == Output::Json {
+                emit_json(&RefChange::<Branch>::unchanged(None))?;
+            }
+
             return Ok(());
-        } else {
-            return Err(e);
         }
-    }
+        Err(e) => return Err(e),
+    };
 
     eprintln!("Deleted branch \"{branch_name}\"");
+    if cli.global.output == Output::Json {
+        emit_json(&RefChange::changed(branch))?;
+    }
 
     Ok(())
 }
@@ -369,7 +474,9 @@ fn merge_branch(cli: &Cli, args: BranchMergeArgs) -> anyhow::Result<()> {
     } = args;
 
     let into_branch = cli.profile.active_branch.as_deref().unwrap_or("main");
+    cli.check_write_branch(into_branch)?;
 
+    let operation_id = bauplan::new_operation_id();
     let req = MergeBranch {
         source_ref: &branch_name,
         into_branch,
@@ -377,10 +484,61 @@ fn merge_branch(cli: &Cli, args: BranchMergeArgs) -> anyhow::Result<()> {
             commit_message: commit_message.as_deref(),
             ..Default::default()
         },
+        operation_id: Some(&operation_id),
     };
 
-    cli.roundtrip(req)?;
+    let res = match cli.send_operation(req) {
+        Ok(res) => res,
+        Err(e) => {
+            if let Some(ApiErrorKind::MergeConflict { conflicts, .. }) = api_err_kind(&e)
+                && !conflicts.is_empty()
+            {
+                print_merge_conflicts(conflicts, &branch_name, into_branch)?;
+            }
+
+            return Err(e);
+        }
+    };
+
+    if let OperationResult::AlreadyApplied(_) = res {
+        eprintln!("The merge was applied by an earlier attempt, whose response was lost");
+    }
+
     eprintln!("Merged branch \"{branch_name}\" into \"{into_branch}\"");
+    if cli.global.output == Output::Json {
+        emit_json(&RefChange::changed(res.into_ref()))?;
+    }
+
+    Ok(())
+}
+
+fn print_merge_conflicts(
+    conflicts: &[MergeConflictTable],
+    source_ref: &str,
+    into_branch: &str,
+) -> io::Result<()> {
+    eprintln!("Tables changed on both \"{source_ref}\" and \"{into_branch}\":");
+    let mut tw = TabWriter::new(stderr());
+    writeln!(&mut tw, "TABLE\tSOURCE HASH\tDESTINATION HASH")?;
+    for conflict in conflicts {
+        writeln!(
+            &mut tw,
+            "{}\t{}\t{}",
+            conflict.table_name,
+            conflict.source_hash.as_deref().unwrap_or("-"),
+            conflict.destination_hash.as_deref().unwrap_or("-"),
+        )?;
+    }
+    tw.flush()?;
+
+    anstream::eprintln!(
+        "{GREEN}TIP:{GREEN:#} To resolve a conflict, revert the table on one side, e.g.:"
+    );
+    eprintln!(
+        "\tbauplan table revert {:?} --source-ref {source_ref:?} \
+         --into-branch {into_branch:?} --replace",
+        conflicts[0].table_name,
+    );
 
     Ok(())
 }
@@ -396,12 +554,105 @@ fn rename_branch(cli: &Cli, args: BranchRenameArgs) -> anyhow::Result<()> {
         new_name: &new_branch_name,
     };
 
-    cli.roundtrip(req)?;
+    let branch = cli.roundtrip(req)?;
     eprintln!("Renamed branch \"{branch_name}\" to \"{new_branch_name}\"");
 
+    // Keep the profile pointing at the branch if it was the active one.
+    if cli.profile.active_branch.as_deref() == Some(branch_name.as_str()) {
+        yaml::edit_locked(&cli.profile.config_path, |doc| {
+            let mut profile = yaml::mapping_at_path(doc, &["profiles", &cli.profile.name])?;
+            yaml::upsert_str(&mut profile, "active_branch", &new_branch_name);
+            Ok(())
+        })?;
+
+        eprintln!(
+            "Updated active branch to \"{new_branch_name}\" in profile {:?}",
+            cli.profile.name,
+        );
+    }
+
+    if cli.global.output == Output::Json {
+        emit_json(&RefChange::changed(branch))?;
+    }
+
     Ok(())
 }
 
+#[derive(serde::Serialize)]
+struct JsonHeadChange<'a> {
+    name: &'a str,
+    hash: &'a str,
+    changed_at: &'a str,
+}
+
+fn watch_branch(cli: &Cli, args: BranchWatchArgs) -> anyhow::Result<()> {
+    let BranchWatchArgs {
+        branch_name,
+        poll_interval,
+    } = args;
+
+    let mut schedule = WatchSchedule::new(time::Duration::from_secs(poll_interval.max(1)));
+    let mut head = cli
+        .roundtrip(GetBranch {
+            name: &branch_name,
+            if_none_match: None,
+        })?
+        .hash;
+
+    eprintln!("Watching branch {branch_name:?} at {head}");
+
+    loop {
+        thread::sleep(schedule.succeeded());
+
+        let req = GetBranch {
+            name: &branch_name,
+            if_none_match: Some(&head),
+        };
+
+        let branch = loop {
+            let err = match cli.roundtrip(req.clone()) {
+                Ok(branch) => break Some(branch),
+                Err(e) => e,
+            };
+
+            match err.downcast_ref::<ApiError>() {
+                Some(ApiError::NotModified) => break None,
+                Some(e) if !e.is_transient() => return Err(err),
+                _ => {
+                    let delay = schedule.failed();
+                    warn!("failed to poll branch {branch_name:?}, retrying in {delay:?}: {err:#}");
+                    thread::sleep(delay);
+                }
+            }
+        };
+
+        let Some(branch) = branch else {
+            continue;
+        };
+
+        // The server may not support conditional requests.
+        if branch.hash == head {
+            continue;
+        }
+
+        head = branch.hash;
+        let changed_at = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+
+        match cli.global.output {
+            Output::Json => {
+                let change = JsonHeadChange {
+                    name: &branch_name,
+                    hash: &head,
+                    changed_at: &changed_at,
+                };
+
+                emit_json(&change)?;
+            }
+            Output::Tty => println!("{head}\t{changed_at}"),
+        }
+    }
+}
+
 fn checkout_branch(cli: &Cli, args: BranchCheckoutArgs) -> anyhow::Result<()> {
     let BranchCheckoutArgs { branch_name } = args;
     checkout::switch_branch(cli, &branch_name)
@@ -427,45 +678,22 @@ fn diff_branch(cli: &Cli, args: BranchDiffArgs) -> anyhow::Result<()> {
     let tables_a = collect_tables(cli, branch_a, namespace.as_deref())?;
     let tables_b = collect_tables(cli, branch_b, namespace.as_deref())?;
 
+    let diff = TableDiff::new(&tables_a, &tables_b);
+
     match cli.global.output {
         Output::Json => {
-            let added: Vec<_> = tables_b
-                .iter()
-                .filter_map(|(fqn, table)| {
-                    if !tables_a.contains_key(fqn.as_str()) {
-                        Some(table)
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-            let removed: Vec<_> = tables_a
-                .iter()
-                .filter_map(|(fqn, table)| {
-                    if !tables_b.contains_key(fqn.as_str()) {
-                        Some(table)
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-
-            serde_json::to_writer(stdout(), &JsonDiff { added, removed })?;
-            println!();
+            let TableDiff { added, removed, .. } = diff;
+            emit_json(&JsonDiff { added, removed })?;
         }
         Output::Tty => {
             anstream::eprintln!("{BOLD}diff --bauplan a/{branch_name_a} b/{branch_b}{BOLD:#}");
 
-            for (k, t) in &tables_b {
-                if !tables_a.contains_key(k.as_str()) {
-                    anstream::eprintln!("{GREEN}+{} {}{GREEN:#}", t.kind, t.fqn());
-                }
+            for t in diff.added {
+                anstream::eprintln!("{GREEN}+{} {}{GREEN:#}", t.kind, t.fqn());
             }
 
-            for (k, t) in &tables_a {
-                if !tables_b.contains_key(k.as_str()) {
-                    anstream::eprintln!("{RED}-{} {}{RED:#}", t.kind, t.fqn());
-                }
+            for t in diff.removed {
+                anstream::eprintln!("{RED}-{} {}{RED:#}", t.kind, t.fqn());
             }
         }
     }
@@ -473,7 +701,45 @@ fn diff_branch(cli: &Cli, args: BranchDiffArgs) -> anyhow::Result<()> {
     Ok(())
 }
 
-fn collect_tables(
+/// The tables that differ between two refs, keyed by fully-qualified name.
+pub(crate) struct TableDiff<'a> {
+    /// Tables only in the second ref.
+    pub added: Vec<&'a Table>,
+    /// Tables only in the first ref.
+    pub removed: Vec<&'a Table>,
+    /// Tables in both refs, but pointing at different metadata. These are
+    /// the tables from the second ref.
+    pub changed: Vec<&'a Table>,
+}
+
+impl<'a> TableDiff<'a> {
+    pub(crate) fn new(a: &'a BTreeMap<String, Table>, b: &'a BTreeMap<String, Table>) -> Self {
+        let mut diff = Self {
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        };
+
+        for (fqn, table) in b {
+            match a.get(fqn) {
+                None => diff.added.push(table),
+                Some(prev) if prev.metadata_location != table.metadata_location => {
+                    diff.changed.push(table)
+                }
+                Some(_) => (),
+            }
+        }
+
+        diff.removed = a
+            .iter()
+            .filter(|(fqn, _)| !b.contains_key(*fqn))
+            .map(|(_, t)| t)
+            .collect();
+        diff
+    }
+}
+
+pub(crate) fn collect_tables(
     cli: &Cli,
     at_ref: &str,
     filter_by_namespace: Option<&str>,
@@ -1,6 +1,12 @@
 use anyhow::{Context as _, bail};
-use bauplan::branch::{CreateBranch, GetBranch};
-use crate::cli::{Cli, color::CliExamples, yaml};
+use bauplan::{
+    ApiErrorKind,
+    branch::{Branch, CreateBranch, GetBranch},
+    yaml,
+};
+use serde::Serialize;
+
+use crate::cli::{Cli, Output, api_err, api_err_kind, color::CliExamples, render::emit_json};
 
 #[derive(Debug, clap::Args)]
 #[command(after_long_help = CliExamples("
@@ -15,17 +21,23 @@ use crate::cli::{Cli, color::CliExamples, yaml};
 
   # Create and checkout from active branch
   bauplan checkout -b username.new_feature
+
+  # Create the branch unless it exists, then checkout
+  bauplan checkout -b username.new_feature --if-not-exists
 "))]
 pub(crate) struct CheckoutArgs {
     /// Branch name
     pub branch_name: String,
     /// Create the branch before switching (fails if it already exists)
-    #[arg(short = 'b')]
+    #[arg(short = 'b', long)]
     pub create: bool,
     /// Ref from which to create when using -b. If not specified,
     /// the default is the currently active branch.
     #[arg(long)]
     pub from_ref: Option<String>,
+    /// With -b, switch to the branch even if it already exists
+    #[arg(long, requires = "create")]
+    pub if_not_exists: bool,
 }
 
 pub(crate) fn handle(cli: &Cli, args: CheckoutArgs) -> anyhow::Result<()> {
@@ -33,21 +45,36 @@ pub(crate) fn handle(cli: &Cli, args: CheckoutArgs) -> anyhow::Result<()> {
         branch_name,
         create,
         from_ref,
+        if_not_exists,
     } = args;
 
     if create {
-        let from_ref = from_ref
-            .as_deref()
-            .or(cli.profile.active_branch.as_deref())
-            .unwrap_or("main");
+        let from_ref = cli.resolve_ref(
+            from_ref
+                .as_deref()
+                .or(cli.profile.active_branch.as_deref())
+                .unwrap_or("main"),
+        )?;
 
         let req = CreateBranch {
             name: &branch_name,
-            from_ref,
+            from_ref: &from_ref,
         };
 
-        cli.roundtrip(req).context("Failed to create branch")?;
-        eprintln!("Created branch {branch_name:?}");
+        match cli.roundtrip(req) {
+            Ok(_) => eprintln!("Created branch {branch_name:?}"),
+            Err(e) if api_err(&e).and_then(CreateBranch::existing).is_some() => {
+                if !if_not_exists {
+                    bail!(
+                        "branch {branch_name:?} already exists; to switch to it, run \
+                         `bauplan checkout {branch_name}`, or add --if-not-exists"
+                    );
+                }
+
+                eprintln!("Branch {branch_name:?} already exists");
+            }
+            Err(e) => return Err(e.context("Failed to create branch")),
+        }
     } else if from_ref.is_some() {
         bail!("--from-ref can only be used with -b");
     }
@@ -55,12 +82,39 @@ pub(crate) fn handle(cli: &Cli, args: CheckoutArgs) -> anyhow::Result<()> {
     switch_branch(cli, &branch_name)
 }
 
+/// The result of a checkout, as printed with `-O json`.
+#[derive(Debug, Serialize)]
+struct Checkout<'a> {
+    profile: &'a str,
+    /// The branch that's now active, and its head when it was checked.
+    active_branch: Branch,
+}
+
 pub(crate) fn switch_branch(cli: &Cli, branch_name: &str) -> anyhow::Result<()> {
-    if cli.roundtrip(GetBranch { name: branch_name }).is_err() {
-        bail!("branch {branch_name:?} doesn't exist or is inaccessible");
-    }
+    let req = GetBranch {
+        name: branch_name,
+        if_none_match: None,
+    };
+
+    // Check the branch exists, so that a typo doesn't leave the profile on
+    // a branch that isn't there.
+    let branch = match cli.roundtrip(req) {
+        Ok(branch) => branch,
+        Err(e)
+            if matches!(
+                api_err_kind(&e),
+                Some(ApiErrorKind::BranchNotFound { .. } | ApiErrorKind::RefNotFound { .. })
+            ) =>
+        {
+            bail!(
+                "branch {branch_name:?} doesn't exist; to create it, run \
+                 `bauplan checkout -b {branch_name}`"
+            );
+        }
+        Err(e) => return Err(e.context(format!("Failed to check branch {branch_name:?}"))),
+    };
 
-    yaml::edit(&cli.profile.config_path, |doc| {
+    yaml::edit_locked(&cli.profile.config_path, |doc| {
         let mut profile = yaml::mapping_at_path(doc, &["profiles", &cli.profile.name])?;
         yaml::upsert_str(&mut profile, "active_branch", branch_name);
         Ok(())
@@ -71,5 +125,12 @@ pub(crate) fn switch_branch(cli: &Cli, branch_name: &str) -> anyhow::Result<()>
         cli.profile.name,
     );
 
+    if cli.global.output == Output::Json {
+        emit_json(&Checkout {
+            profile: &cli.profile.name,
+            active_branch: branch,
+        })?;
+    }
+
     Ok(())
 }
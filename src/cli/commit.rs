@@ -3,7 +3,7 @@ use std::io::{Write, stdout};
 
 use bauplan::commit::{Commit, GetCommits};
 
-use crate::cli::{Cli, Output, color::*};
+use crate::cli::{Cli, Output, color::*, render::write_json_array};
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
 pub(crate) enum Format {
@@ -46,6 +46,9 @@ impl std::fmt::Display for Format {
 
   # Show commits in oneline format
   bauplan commit --format oneline
+
+  # Show commits on main up to two days ago
+  bauplan commit \"main@{2 days ago}\"
 "))]
 pub(crate) struct CommitArgs {
     /// Ref or branch name to get commits from [default: active branch]
@@ -75,11 +78,12 @@ pub(crate) struct CommitArgs {
 
 pub(crate) fn handle(cli: &Cli, args: CommitArgs) -> anyhow::Result<()> {
     // Positional ref_name takes precedence over --ref flag.
-    let at_ref = args
-        .ref_name
-        .as_deref()
-        .or(cli.profile.active_branch.as_deref())
-        .unwrap_or("main");
+    let at_ref = cli.resolve_ref(
+        args.ref_name
+            .as_deref()
+            .or(cli.profile.active_branch.as_deref())
+            .unwrap_or("main"),
+    )?;
 
     let properties: BTreeMap<String, String> = args
         .property
@@ -97,7 +101,7 @@ pub(crate) fn handle(cli: &Cli, args: CommitArgs) -> anyhow::Result<()> {
     };
 
     let req = GetCommits {
-        at_ref,
+        at_ref: &at_ref,
         filter_by_message: args.message.as_deref(),
         filter_by_author_username: args.author_username.as_deref(),
         filter_by_author_name: args.author_name.as_deref(),
@@ -113,11 +117,7 @@ pub(crate) fn handle(cli: &Cli, args: CommitArgs) -> anyhow::Result<()> {
     let commits = bauplan::paginate(req, Some(args.max_count), |r| cli.roundtrip(r))?;
 
     match cli.global.output {
-        Output::Json => {
-            let all_commits = commits.collect::<anyhow::Result<Vec<_>>>()?;
-            serde_json::to_writer(stdout(), &all_commits)?;
-            println!();
-        }
+        Output::Json => write_json_array(&mut stdout().lock(), commits)?,
         Output::Tty => {
             let mut out = anstream::stdout().lock();
             for commit in commits {
@@ -1,9 +1,10 @@
 use std::io::Write;
 
-use bauplan::Profile;
+use bauplan::{Profile, yaml};
+use serde::Serialize;
 use tabwriter::TabWriter;
 
-use crate::cli::{GlobalArgs, Output, color::*, yaml};
+use crate::cli::{GlobalArgs, Output, color::*, render::emit_json};
 
 #[derive(Debug, clap::Args)]
 pub(crate) struct ConfigArgs {
@@ -17,6 +18,8 @@ pub(crate) enum ConfigCommand {
     Set(ConfigSetArgs),
     /// Get the current configuration
     Get(ConfigGetArgs),
+    /// Set the profile to use when --profile and BAUPLAN_PROFILE aren't given
+    UseProfile(ConfigUseProfileArgs),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
@@ -25,7 +28,12 @@ pub(crate) enum ConfigSetting {
     ApiKey,
     #[value(hide = true)]
     ApiEndpoint,
+    #[value(hide = true)]
+    GrpcEndpoint,
+    #[value(hide = true)]
+    CatalogEndpoint,
     ActiveBranch,
+    DefaultNamespace,
 }
 
 impl std::fmt::Display for ConfigSetting {
@@ -33,7 +41,10 @@ impl std::fmt::Display for ConfigSetting {
         match self {
             ConfigSetting::ApiKey => f.write_str("api_key"),
             ConfigSetting::ApiEndpoint => f.write_str("api_endpoint"),
+            ConfigSetting::GrpcEndpoint => f.write_str("grpc_endpoint"),
+            ConfigSetting::CatalogEndpoint => f.write_str("catalog_endpoint"),
             ConfigSetting::ActiveBranch => f.write_str("active_branch"),
+            ConfigSetting::DefaultNamespace => f.write_str("default_namespace"),
         }
     }
 }
@@ -64,22 +75,47 @@ pub(crate) struct ConfigGetArgs {
     pub all: bool,
 }
 
+#[derive(Debug, clap::Args)]
+#[command(after_long_help = CliExamples("
+  # Use the staging profile from now on
+  bauplan config use-profile staging
+
+  # Run a single command against another profile
+  bauplan --profile prod branch ls
+"))]
+pub(crate) struct ConfigUseProfileArgs {
+    /// Name of a profile in the config file
+    pub name: String,
+}
+
 pub(crate) fn handle(args: ConfigArgs, global: GlobalArgs) -> anyhow::Result<()> {
     match args.command {
         ConfigCommand::Set(args) => config_set(args, global),
         ConfigCommand::Get(args) => config_get(args, global),
+        ConfigCommand::UseProfile(args) => config_use_profile(args, global.output),
     }
 }
 
+/// The result of `config set`, as printed with `-O json`. The value isn't
+/// echoed, since it may be an API key.
+#[derive(Serialize)]
+struct SettingChange<'a> {
+    profile: &'a str,
+    setting: String,
+}
+
+/// The result of `config use-profile`, as printed with `-O json`.
+#[derive(Serialize)]
+struct ProfileChange<'a> {
+    current_profile: &'a str,
+}
+
 fn config_set(args: ConfigSetArgs, global: GlobalArgs) -> anyhow::Result<()> {
     let key = args.name.to_string();
 
-    let profile = match global.profile {
-        Some(name) => Profile::from_env(&name)?,
-        None => Profile::from_default_env()?,
-    };
+    let profile = Profile::from_env_or_file(global.profile.as_deref())?;
 
-    yaml::edit(&profile.config_path, |doc| {
+    yaml::edit_locked(&profile.config_path, |doc| {
         let mut profile = yaml::mapping_at_path(doc, &["profiles", &profile.name])?;
         yaml::upsert_str(&mut profile, &key, &args.value);
 
@@ -100,24 +136,54 @@ fn config_set(args: ConfigSetArgs, global: GlobalArgs) -> anyhow::Result<()> {
         );
     }
 
+    if global.output == Output::Json {
+        emit_json(&SettingChange {
+            profile: &profile.name,
+            setting: key,
+        })?;
+    }
+
     Ok(())
 }
 
-fn config_get(args: ConfigGetArgs, global: GlobalArgs) -> anyhow::Result<()> {
-    let mut out = anstream::stdout().lock();
+fn config_use_profile(args: ConfigUseProfileArgs, output: Output) -> anyhow::Result<()> {
+    // The profile has to be in the config file, not just made up from the
+    // environment.
+    let profile = Profile::from_env_or_file(Some(&args.name))?;
+    Profile::read(&profile.config_path, Some(&profile.name))?;
+
+    yaml::edit_locked(&profile.config_path, |doc| {
+        let mut root = yaml::mapping_at_path(doc, &[])?;
+        yaml::upsert_str(&mut root, "current_profile", &profile.name);
+        Ok(())
+    })?;
+
+    eprintln!("Using profile {:?}", profile.name);
+    if let Ok(name) = std::env::var("BAUPLAN_PROFILE")
+        && name != profile.name
+    {
+        eprintln!("BAUPLAN_PROFILE is set to {name:?}, which takes precedence");
+    }
+
+    if output == Output::Json {
+        emit_json(&ProfileChange {
+            current_profile: &profile.name,
+        })?;
+    }
+
+    Ok(())
+}
 
+fn config_get(args: ConfigGetArgs, global: GlobalArgs) -> anyhow::Result<()> {
     match (global.output, args.all) {
         (Output::Tty, false) => {
-            let profile = match global.profile {
-                Some(name) => Profile::from_env(&name)?,
-                None => Profile::from_default_env()?,
-            };
+            let profile = Profile::from_env_or_file(global.profile.as_deref())?;
 
-            let mut tw = TabWriter::new(&mut out).ansi(true);
+            let mut tw = TabWriter::new(anstream::stdout().lock()).ansi(true);
             print_profile(&mut tw, &profile)?;
         }
         (Output::Tty, true) => {
-            let mut tw = TabWriter::new(&mut out).ansi(true);
+            let mut tw = TabWriter::new(anstream::stdout().lock()).ansi(true);
             for (i, profile) in Profile::load_all()?.enumerate() {
                 if i > 0 {
                     writeln!(&mut tw)?;
@@ -126,18 +192,12 @@ fn config_get(args: ConfigGetArgs, global: GlobalArgs) -> anyhow::Result<()> {
             }
         }
         (Output::Json, false) => {
-            let profile = match global.profile {
-                Some(name) => Profile::from_env(&name)?,
-                None => Profile::from_default_env()?,
-            };
-
-            serde_json::to_writer(&mut out, &profile)?;
-            writeln!(&mut out)?;
+            let profile = Profile::from_env_or_file(global.profile.as_deref())?;
+            emit_json(&profile)?;
         }
         (Output::Json, true) => {
             let profiles: Vec<_> = Profile::load_all()?.collect();
-            serde_json::to_writer(&mut out, &profiles)?;
-            writeln!(&mut out)?;
+            emit_json(&profiles)?;
         }
     }
 
@@ -150,6 +210,9 @@ fn print_profile(out: &mut impl Write, profile: &bauplan::Profile) -> anyhow::Re
     writeln!(out, "{HEADER}Profile {:?}{HEADER:#}", profile.name)?;
     writeln!(out, "{GREEN}API Key{GREEN:#}\t*********")?;
     writeln!(out, "{GREEN}Active Branch{GREEN:#}\t{active_branch}",)?;
+    if let Some(namespace) = &profile.default_namespace {
+        writeln!(out, "{GREEN}Default Namespace{GREEN:#}\t{namespace}")?;
+    }
 
     if !profile.args.is_empty() {
         writeln!(out, "{GREEN}Args{GREEN:#}")?;
@@ -158,5 +221,24 @@ fn print_profile(out: &mut impl Write, profile: &bauplan::Profile) -> anyhow::Re
         }
     }
 
+    if !profile.aliases.is_empty() {
+        writeln!(out, "{GREEN}Aliases{GREEN:#}")?;
+        for (name, command) in &profile.aliases {
+            writeln!(out, "  {name}\t{command}")?;
+        }
+    }
+
+    if !profile.extra_headers.is_empty() {
+        writeln!(out, "{GREEN}Extra Headers{GREEN:#}")?;
+        for (name, value) in &profile.extra_headers {
+            let value = match value.to_str() {
+                Ok(v) if !value.is_sensitive() => v,
+                _ => "*********",
+            };
+
+            writeln!(out, "  {name}\t{value}")?;
+        }
+    }
+
     Ok(())
 }
@@ -0,0 +1,134 @@
+//! Detection of the git state of a project, which is attached to runs so
+//! that they can be traced back to the code they came from.
+
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use serde::Serialize;
+
+/// The git state of a project directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct GitMetadata {
+    /// The HEAD commit hash.
+    pub commit: String,
+    /// The checked-out branch, or None if HEAD is detached.
+    pub branch: Option<String>,
+    /// Whether there are uncommitted changes within the project directory.
+    pub dirty: bool,
+}
+
+impl GitMetadata {
+    /// Detects the git state of `dir`. Returns None if git isn't installed or
+    /// `dir` isn't inside a work tree.
+    pub(crate) fn detect(dir: &Path) -> Option<Self> {
+        let commit = git(dir, &["rev-parse", "--verify", "-q", "HEAD"])?;
+        let branch = git(dir, &["symbolic-ref", "--short", "-q", "HEAD"]);
+        let status = git(dir, &["status", "--porcelain", "--", "."])?;
+
+        Some(Self {
+            commit,
+            branch,
+            dirty: !status.is_empty(),
+        })
+    }
+
+    /// The job args used to record this metadata on a run.
+    pub(crate) fn to_args(&self) -> Vec<(String, String)> {
+        let mut args = vec![
+            ("bauplan.git-commit".to_owned(), self.commit.clone()),
+            ("bauplan.git-dirty".to_owned(), self.dirty.to_string()),
+        ];
+
+        if let Some(branch) = &self.branch {
+            args.push(("bauplan.git-branch".to_owned(), branch.clone()));
+        }
+
+        args
+    }
+}
+
+/// Runs a git command in `dir`, returning its trimmed stdout if it succeeded.
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    Some(stdout.trim().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args([
+                "-c",
+                "user.name=Test",
+                "-c",
+                "user.email=test@example.com",
+                "-c",
+                "commit.gpgsign=false",
+            ])
+            .args(args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .expect("failed to run git");
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[test]
+    fn not_a_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(GitMetadata::detect(dir.path()), None);
+    }
+
+    #[test]
+    fn clean_and_dirty() {
+        let dir = tempfile::tempdir().unwrap();
+        run_git(dir.path(), &["init", "-q", "-b", "pipelines"]);
+        std::fs::write(dir.path().join("models.py"), "# v1\n").unwrap();
+        run_git(dir.path(), &["add", "."]);
+        run_git(dir.path(), &["commit", "-q", "-m", "initial"]);
+
+        let clean = GitMetadata::detect(dir.path()).unwrap();
+        assert_eq!(clean.commit.len(), 40);
+        assert_eq!(clean.branch.as_deref(), Some("pipelines"));
+        assert!(!clean.dirty);
+        assert!(
+            clean
+                .to_args()
+                .contains(&("bauplan.git-dirty".to_owned(), "false".to_owned()))
+        );
+
+        std::fs::write(dir.path().join("models.py"), "# v2\n").unwrap();
+        let dirty = GitMetadata::detect(dir.path()).unwrap();
+        assert_eq!(dirty.commit, clean.commit);
+        assert!(dirty.dirty);
+
+        run_git(dir.path(), &["checkout", "-q", "--detach"]);
+        let detached = GitMetadata::detect(dir.path()).unwrap();
+        assert_eq!(detached.branch, None);
+        assert!(
+            !detached
+                .to_args()
+                .iter()
+                .any(|(k, _)| k == "bauplan.git-branch")
+        );
+    }
+}
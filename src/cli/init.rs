@@ -1,9 +1,13 @@
-use std::{io::Write as _, path::PathBuf};
+use std::{
+    io::Write as _,
+    path::{Path, PathBuf},
+};
 
 use anyhow::Context as _;
 use bauplan::project::{ProjectFile, ProjectInfo};
+use serde::Serialize;
 
-use crate::cli::color::CliExamples;
+use crate::cli::{Output, color::CliExamples, render::emit_json};
 
 #[derive(Debug, clap::Args)]
 #[command(after_long_help = CliExamples("
@@ -24,7 +28,15 @@ pub(crate) struct InitArgs {
     pub name: Option<String>,
 }
 
-pub(crate) fn handle(args: InitArgs) -> anyhow::Result<()> {
+/// The project created by `init`, as printed with `-O json`.
+#[derive(Serialize)]
+struct InitializedProject<'a> {
+    project_id: uuid::Uuid,
+    name: &'a str,
+    path: &'a Path,
+}
+
+pub(crate) fn handle(args: InitArgs, output: Output) -> anyhow::Result<()> {
     let dir = match args.path {
         Some(p) => p,
         None => std::env::current_dir()?,
@@ -55,6 +67,7 @@ pub(crate) fn handle(args: InitArgs) -> anyhow::Result<()> {
         parameters: Default::default(),
         include_paths: Vec::new(),
         path: Default::default(), // unused
+        files: None,
     };
 
     let project_yaml =
@@ -126,10 +139,18 @@ pub(crate) fn handle(args: InitArgs) -> anyhow::Result<()> {
         dir.display()
     );
 
+    if output == Output::Json {
+        emit_json(&InitializedProject {
+            project_id,
+            name: &project_name,
+            path: &dir,
+        })?;
+    }
+
     Ok(())
 }
 
-fn no_clobber(path: &std::path::Path, content: &str) -> anyhow::Result<()> {
+fn no_clobber(path: &Path, content: &str) -> anyhow::Result<()> {
     match std::fs::File::create_new(path) {
         Ok(mut f) => f.write_all(content.as_bytes()),
         Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(()),
@@ -0,0 +1,230 @@
+use std::{
+    io::{self, IsTerminal as _, Read},
+    path::{Path, PathBuf},
+};
+
+use anyhow::bail;
+
+/// The default cap on the size of a text input, overridden with
+/// `BAUPLAN_MAX_INPUT_SIZE` (in bytes).
+const DEFAULT_MAX_INPUT_SIZE: u64 = 10 * 1024 * 1024;
+
+const UTF8_BOM: &[u8] = b"\xef\xbb\xbf";
+const UTF16_LE_BOM: &[u8] = b"\xff\xfe";
+const UTF16_BE_BOM: &[u8] = b"\xfe\xff";
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum InputError {
+    #[error("{0}: {1}")]
+    Io(String, io::Error),
+    #[error(
+        "{name}: input is larger than {limit} bytes (set BAUPLAN_MAX_INPUT_SIZE to raise the limit)"
+    )]
+    TooLarge { name: String, limit: u64 },
+    #[error("{0}: input is UTF-16 encoded; re-save it as UTF-8")]
+    Utf16(String),
+    #[error("{name}: invalid UTF-8 at byte {offset}")]
+    InvalidUtf8 { name: String, offset: usize },
+}
+
+/// Reads a text file, such as a plan, SQL query, or parameter value. See
+/// [read_text].
+pub(crate) fn read_text_file(path: &Path) -> Result<String, InputError> {
+    let name = path.display().to_string();
+    match std::fs::File::open(path) {
+        Ok(f) => read_text(&name, f, max_input_size()),
+        Err(e) => Err(InputError::Io(name, e)),
+    }
+}
+
+/// Reads text from stdin. See [read_text].
+pub(crate) fn read_text_stdin() -> Result<String, InputError> {
+    read_text("<stdin>", io::stdin().lock(), max_input_size())
+}
+
+/// Reads UTF-8 text, stripping a leading byte order mark and normalizing
+/// CRLF line endings to LF. Inputs larger than `limit` are rejected without
+/// reading them in full.
+fn read_text(name: &str, r: impl Read, limit: u64) -> Result<String, InputError> {
+    let mut buf = Vec::new();
+    r.take(limit.saturating_add(1))
+        .read_to_end(&mut buf)
+        .map_err(|e| InputError::Io(name.to_owned(), e))?;
+
+    if buf.len() as u64 > limit {
+        return Err(InputError::TooLarge {
+            name: name.to_owned(),
+            limit,
+        });
+    }
+
+    if buf.starts_with(UTF16_LE_BOM) || buf.starts_with(UTF16_BE_BOM) {
+        return Err(InputError::Utf16(name.to_owned()));
+    }
+
+    let bom_len = if buf.starts_with(UTF8_BOM) {
+        UTF8_BOM.len()
+    } else {
+        0
+    };
+
+    let text = match std::str::from_utf8(&buf[bom_len..]) {
+        Ok(text) => text,
+        Err(e) => {
+            return Err(InputError::InvalidUtf8 {
+                name: name.to_owned(),
+                offset: bom_len + e.valid_up_to(),
+            });
+        }
+    };
+
+    Ok(text.replace("\r\n", "\n"))
+}
+
+/// Where the value of a `key=value` argument comes from. A value of `@path`
+/// is read from the file at `path`, and for arguments that allow it, `-` is
+/// read from stdin. Use `@@` for a value that starts with a literal `@`.
+/// Nothing is read until the value is needed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ParamSource {
+    Value(String),
+    File(PathBuf),
+    Stdin,
+}
+
+impl ParamSource {
+    /// Splits a `key=value` argument into the key and where the value comes
+    /// from. A value of `-` is only read from stdin if `stdin` is set.
+    pub(crate) fn parse(s: &str, stdin: bool) -> anyhow::Result<(String, Self)> {
+        let Some((key, value)) = s.split_once('=') else {
+            bail!("Invalid key=value pair: {}", s);
+        };
+
+        let source = if let Some(literal) = value.strip_prefix("@@") {
+            Self::Value(format!("@{literal}"))
+        } else if let Some(path) = value.strip_prefix('@') {
+            Self::File(path.into())
+        } else if stdin && value == "-" {
+            Self::Stdin
+        } else {
+            Self::Value(value.to_owned())
+        };
+
+        Ok((key.to_owned(), source))
+    }
+
+    /// Reads the value of `key`. On a terminal, the value is prompted for
+    /// without echo rather than read from stdin.
+    pub(crate) fn read(&self, key: &str) -> anyhow::Result<String> {
+        let text = match self {
+            Self::Value(v) => v.clone(),
+            Self::File(path) => read_text_file(path)?,
+            Self::Stdin if io::stdin().is_terminal() => {
+                rpassword::prompt_password(format!("Value for {key}: "))?
+            }
+            Self::Stdin => read_text_stdin()?,
+        };
+
+        Ok(text)
+    }
+
+    /// The argument for `key` as it would be passed, for repeating a
+    /// command. Values read from files or stdin are read again.
+    pub(crate) fn to_arg(&self, key: &str) -> String {
+        match self {
+            Self::Value(v) if v.starts_with('@') => format!("{key}=@{v}"),
+            Self::Value(v) => format!("{key}={v}"),
+            Self::File(path) => format!("{key}=@{}", path.display()),
+            Self::Stdin => format!("{key}=-"),
+        }
+    }
+}
+
+fn max_input_size() -> u64 {
+    std::env::var("BAUPLAN_MAX_INPUT_SIZE")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_MAX_INPUT_SIZE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_fixture(name: &str, limit: u64) -> Result<String, InputError> {
+        let path = Path::new("tests/fixtures/text_input").join(name);
+        let f = std::fs::File::open(&path).unwrap();
+        read_text(name, f, limit)
+    }
+
+    #[test]
+    fn param_sources() {
+        let parse = |s: &str, stdin| ParamSource::parse(s, stdin).unwrap();
+
+        assert_eq!(
+            parse("a=b=c", true),
+            ("a".to_owned(), ParamSource::Value("b=c".into()))
+        );
+        assert_eq!(parse("a=@@x", true).1, ParamSource::Value("@x".into()));
+        assert_eq!(
+            parse("a=@/tmp/x", false).1,
+            ParamSource::File("/tmp/x".into())
+        );
+        assert_eq!(parse("a=-", true).1, ParamSource::Stdin);
+        assert_eq!(parse("a=-", false).1, ParamSource::Value("-".into()));
+        assert_eq!(parse("a=--", true).1, ParamSource::Value("--".into()));
+        assert!(ParamSource::parse("a", true).is_err());
+
+        for s in ["a=b", "a=@@x", "a=@/tmp/x", "a=-"] {
+            let (key, source) = parse(s, true);
+            assert_eq!(source.to_arg(&key), s);
+        }
+    }
+
+    #[test]
+    fn bom_and_crlf() {
+        let text = read_fixture("bom_crlf.yml", DEFAULT_MAX_INPUT_SIZE).unwrap();
+        assert!(!text.starts_with('\u{feff}'));
+        assert!(!text.contains('\r'));
+        assert!(text.starts_with("plan:\n"));
+    }
+
+    #[test]
+    fn crlf() {
+        let text = read_fixture("crlf.sql", DEFAULT_MAX_INPUT_SIZE).unwrap();
+        assert_eq!(text, "SELECT 1\nAS one\n");
+    }
+
+    #[test]
+    fn utf16() {
+        let err = read_fixture("utf16.sql", DEFAULT_MAX_INPUT_SIZE).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "utf16.sql: input is UTF-16 encoded; re-save it as UTF-8"
+        );
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let err = read_fixture("invalid_utf8.sql", DEFAULT_MAX_INPUT_SIZE).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid_utf8.sql: invalid UTF-8 at byte 12"
+        );
+    }
+
+    #[test]
+    fn too_large() {
+        let err = read_fixture("crlf.sql", 4).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "crlf.sql: input is larger than 4 bytes (set BAUPLAN_MAX_INPUT_SIZE to raise the limit)"
+        );
+
+        // Exactly at the limit is fine.
+        let len = std::fs::metadata("tests/fixtures/text_input/crlf.sql")
+            .unwrap()
+            .len();
+        read_fixture("crlf.sql", len).unwrap();
+    }
+}
@@ -1,22 +1,32 @@
-use std::io::{Write as _, stdout};
+use std::io::{self, Write as _};
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time;
 
 use anyhow::bail;
+use bauplan::events::{self, JobEvent, LogMessage, capture};
 use bauplan::grpc::CancelJobError;
 use bauplan::grpc::{
     self, generated as commanderpb,
-    job::{Job, JobState},
+    job::{Job, JobState, ProjectFilter},
 };
 use chrono::{DateTime, Local, Utc};
 use clap::ValueEnum;
 
 use commanderpb::runtime_log_event::{LogLevel, LogType};
-use futures::{Stream, StreamExt as _, TryStreamExt, stream};
+use futures::{StreamExt as _, TryStreamExt, stream};
+use serde::Serialize;
 use tabwriter::TabWriter;
 
 use tracing::info;
 
-use crate::cli::{Cli, Output, color::*, format_grpc_status};
+use crate::cli::{
+    Cli, Output,
+    color::*,
+    format_grpc_status,
+    render::{FormatArgs, Render, emit_json, render},
+    run::{JobResult, replay_job},
+};
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum JobKindArg {
@@ -81,6 +91,8 @@ pub(crate) enum JobCommand {
     Logs(JobLogsArgs),
     /// Stop a job
     Stop(JobStopArgs),
+    /// Replay the events recorded with --events-out, for debugging
+    ReplayEvents(JobReplayEventsArgs),
 }
 
 #[derive(Debug, clap::Args)]
@@ -109,8 +121,17 @@ pub(crate) enum JobCommand {
   # Filter by job ID
   bauplan job ls --id abc123 --id def456
 
+  # Filter by project, by ID or name
+  bauplan job ls --project my_project
+
+  # Filter by the project in the current directory
+  bauplan job ls --project .
+
   # Filter failed jobs
   bauplan job ls --status fail --max-count 10
+
+  # Show every column
+  bauplan job ls --format wide
 "))]
 pub(crate) struct JobLsArgs {
     /// Show jobs from all users, not just your own
@@ -128,6 +149,12 @@ pub(crate) struct JobLsArgs {
     /// Filter by status
     #[arg(short, long, ignore_case = true, action = clap::ArgAction::Append)]
     pub status: Vec<JobStatusArg>,
+    /// Filter by project ID or name, or "." for the project in the current
+    /// directory. This is done client-side, with an extra request per page of
+    /// jobs: only the --max-count most recent jobs are checked, so fewer may
+    /// be shown
+    #[arg(long)]
+    pub project: Option<String>,
     /// Filter jobs created after this date (e.g., 2024-01-15 or 2024-01-15T10:30:00Z)
     #[arg(long)]
     pub created_after: Option<String>,
@@ -140,16 +167,23 @@ pub(crate) struct JobLsArgs {
     /// Use UTC for date parsing and display
     #[arg(short = 'z', long)]
     pub utc: bool,
+    #[command(flatten)]
+    pub format: FormatArgs,
 }
 
 #[derive(Debug, clap::Args)]
 #[command(after_long_help = CliExamples("
   # Get job details
   bauplan job get abc123def456
+
+  # Get job details as YAML
+  bauplan job get abc123def456 --format yaml
 "))]
 pub(crate) struct JobGetArgs {
     /// Job id
     pub job_id: String,
+    #[command(flatten)]
+    pub format: FormatArgs,
 }
 
 #[derive(Debug, clap::Args)]
@@ -159,6 +193,9 @@ pub(crate) struct JobGetArgs {
 
   # Get all logs including system logs
   bauplan job logs abc123def456 --all --system
+
+  # Follow the logs of a running job until it completes
+  bauplan job logs abc123def456 --follow
 "))]
 pub(crate) struct JobLogsArgs {
     /// Job id
@@ -169,6 +206,20 @@ pub(crate) struct JobLogsArgs {
     /// Include all logs
     #[arg(long)]
     pub all: bool,
+    /// Print logs as they're emitted until the job completes, then exit with
+    /// its status. Press Ctrl-C to stop following without cancelling the job.
+    /// With --output json, each entry is printed as a separate line of JSON
+    #[arg(short, long)]
+    pub follow: bool,
+}
+
+impl JobLogsArgs {
+    /// Whether the logs of the given type are selected.
+    fn includes(&self, log_type: LogType) -> bool {
+        self.all
+            || (self.system && log_type == LogType::System)
+            || (!self.system && log_type == LogType::User)
+    }
 }
 
 #[derive(Debug, clap::Args)]
@@ -181,12 +232,24 @@ pub(crate) struct JobStopArgs {
     pub job_id: String,
 }
 
+#[derive(Debug, clap::Args)]
+#[command(after_long_help = CliExamples("
+  # Record the events of a run, then replay them
+  bauplan run --events-out events.jsonl
+  bauplan job replay-events events.jsonl
+"))]
+pub(crate) struct JobReplayEventsArgs {
+    /// The file the events were recorded to
+    pub path: PathBuf,
+}
+
 pub(crate) async fn handle(cli: &Cli, args: JobArgs) -> anyhow::Result<()> {
     match args.command {
         JobCommand::Ls(args) => handle_ls(cli, args).await,
         JobCommand::Get(args) => handle_get(cli, args).await,
         JobCommand::Logs(args) => handle_logs(cli, args).await,
         JobCommand::Stop(args) => handle_stop(cli, args).await,
+        JobCommand::ReplayEvents(args) => handle_replay_events(cli, args),
     }
 }
 
@@ -251,6 +314,13 @@ async fn handle_ls(cli: &Cli, args: JobLsArgs) -> anyhow::Result<()> {
         .transpose()?
         .map(to_proto_timestamp);
 
+    let project_filter = args
+        .project
+        .as_deref()
+        .map(ProjectFilter::new)
+        .transpose()?;
+    let show_project = project_filter.is_some();
+
     let all_users = args.all_users || !args.user.is_empty();
     let base_request = commanderpb::GetJobsRequest {
         job_ids: args.id,
@@ -270,6 +340,7 @@ async fn handle_ls(cli: &Cli, args: JobLsArgs) -> anyhow::Result<()> {
 
     let stream = stream::try_unfold(seed, move |(token, remaining)| {
         let base_request = base_request.clone();
+        let project_filter = project_filter.clone();
         let mut client = client.clone();
         async move {
             // The pagination token starts as None. Some("") means we're done.
@@ -288,87 +359,133 @@ async fn handle_ls(cli: &Cli, args: JobLsArgs) -> anyhow::Result<()> {
 
             let remaining = remaining.saturating_sub(page.jobs.len());
             let token = Some(page.pagination_token);
-            let jobs = stream::iter(page.jobs).map(|j| Ok(Job::from(j)));
+            let mut jobs: Vec<Job> = page.jobs.into_iter().map(Job::from).collect();
+
+            if let Some(filter) = &project_filter
+                && !jobs.is_empty()
+            {
+                let mut req = cli.traced(ProjectFilter::context_request(&jobs));
+                req.set_timeout(timeout);
+                let contexts = client.get_job_context(req).await?.into_inner();
+                filter.apply(&mut jobs, contexts.job_contexts);
+            }
+
+            let jobs = stream::iter(jobs).map(Ok);
             Ok(Some((jobs, (token, remaining))))
         }
     })
-    .try_flatten()
-    .map_ok(Job::from);
+    .try_flatten();
 
-    match cli.global.output {
-        Output::Json => {
-            let jobs: Vec<Job> = stream.try_collect().await?;
-            serde_json::to_writer(stdout(), &jobs)?;
-            println!();
-        }
-        Output::Tty => print_jobs_stream(stream, args.utc).await?,
-    }
+    let jobs: Vec<Job> = stream.try_collect().await?;
 
-    Ok(())
+    let list = JobList {
+        jobs,
+        utc: args.utc,
+        show_project,
+    };
+
+    render(&list, args.format.resolve(cli.global.output))
 }
 
-async fn print_jobs_stream<S>(stream: S, utc: bool) -> anyhow::Result<()>
-where
-    S: Stream<Item = Result<Job, tonic::Status>>,
-{
-    let mut tw = TabWriter::new(anstream::stdout()).ansi(true);
-    let mut headers_printed = false;
+/// The jobs listed by `job ls`.
+#[derive(Serialize)]
+#[serde(transparent)]
+struct JobList {
+    jobs: Vec<Job>,
+    #[serde(skip)]
+    utc: bool,
+    #[serde(skip)]
+    show_project: bool,
+}
 
-    futures::pin_mut!(stream);
-    while let Some(job) = stream.try_next().await? {
-        if !headers_printed {
-            headers_printed = true;
-            writeln!(
-                &mut tw,
-                "ID\tKIND\tUSER\tSTATUS\tCREATED\tFINISHED\tDURATION"
-            )?;
+impl Render for JobList {
+    fn render_table(&self, w: &mut dyn io::Write, wide: bool) -> io::Result<()> {
+        if self.jobs.is_empty() {
+            eprintln!("No jobs found!");
+            return Ok(());
         }
 
-        let status_style = match job.status {
-            JobState::Complete => GREEN,
-            JobState::Fail | JobState::Abort => RED,
-            JobState::Running => YELLOW,
-            _ => anstyle::Style::new(),
-        };
-
-        let duration = if let Some(start) = job.started_at
-            && let Some(end) = job.finished_at
-            && let Ok(elapsed_ms) = u64::try_from((end - start).num_milliseconds())
-        {
-            humantime::format_duration(time::Duration::from_millis(elapsed_ms)).to_string()
-        } else {
-            "-".to_string()
-        };
-
+        let project = if self.show_project { "PROJECT\t" } else { "" };
+        let extra = if wide { "\tSTARTED\tRUNNER\tERROR" } else { "" };
         writeln!(
-            &mut tw,
-            "{}\t{}\t{}\t{status_style}{}{status_style:#}\t{}\t{}\t{}",
-            job.id,
-            job.kind,
-            job.user,
-            job.human_readable_status,
-            format_datetime(job.created_at, utc, false),
-            format_datetime(job.finished_at, utc, true),
-            duration
+            w,
+            "ID\t{project}KIND\tUSER\tSTATUS\tCREATED\tFINISHED\tDURATION{extra}"
         )?;
-    }
 
-    tw.flush()?;
+        for job in &self.jobs {
+            let status_style = match job.status {
+                JobState::Complete => GREEN,
+                JobState::Fail | JobState::Abort => RED,
+                JobState::Running => YELLOW,
+                _ => anstyle::Style::new(),
+            };
+
+            let duration = if let Some(start) = job.started_at
+                && let Some(end) = job.finished_at
+                && let Ok(elapsed_ms) = u64::try_from((end - start).num_milliseconds())
+            {
+                humantime::format_duration(time::Duration::from_millis(elapsed_ms)).to_string()
+            } else {
+                "-".to_string()
+            };
+
+            let project = if self.show_project {
+                let project = job.project_name.as_ref().or(job.project_id.as_ref());
+                format!("{}\t", project.map_or("-", String::as_str))
+            } else {
+                String::new()
+            };
+
+            write!(
+                w,
+                "{}\t{project}{}\t{}\t{status_style}{}{status_style:#}\t{}\t{}\t{}",
+                job.id,
+                job.kind,
+                job.user,
+                job.human_readable_status,
+                format_datetime(job.created_at, self.utc, false),
+                format_datetime(job.finished_at, self.utc, true),
+                duration
+            )?;
 
-    if !headers_printed {
-        eprintln!("No jobs found!")
-    }
+            if wide {
+                write!(
+                    w,
+                    "\t{}\t{}\t{}",
+                    format_datetime(job.started_at, self.utc, false),
+                    job.runner,
+                    // Only the first line, to keep the table intact.
+                    job.error_message
+                        .as_deref()
+                        .and_then(|e| e.lines().next())
+                        .unwrap_or("-"),
+                )?;
+            }
 
-    Ok(())
+            writeln!(w)?;
+        }
+
+        Ok(())
+    }
 }
 
 async fn handle_get(cli: &Cli, args: JobGetArgs) -> anyhow::Result<()> {
     let timeout = cli.timeout.unwrap_or(time::Duration::from_secs(30));
 
     let mut client = grpc::Client::new_lazy(&cli.profile, timeout)?;
+    let job = fetch_job(cli, &mut client, &args.job_id, timeout).await?;
+
+    render(&JobDetails(job), args.format.resolve(cli.global.output))
+}
 
+async fn fetch_job(
+    cli: &Cli,
+    client: &mut grpc::Client,
+    job_id: &str,
+    timeout: time::Duration,
+) -> anyhow::Result<Job> {
     let mut request = cli.traced(commanderpb::GetJobsRequest {
-        job_ids: vec![args.job_id.clone()],
+        job_ids: vec![job_id.to_owned()],
         all_users: true,
         ..Default::default()
     });
@@ -380,43 +497,67 @@ async fn handle_get(cli: &Cli, args: JobGetArgs) -> anyhow::Result<()> {
         .map_err(format_grpc_status)?
         .into_inner();
     let Some(job) = response.jobs.into_iter().next().map(Job::from) else {
-        bail!("job not found: {}", args.job_id);
+        bail!("job not found: {job_id}");
     };
 
-    match cli.global.output {
-        Output::Json => {
-            serde_json::to_writer(stdout(), &[job])?;
-            println!();
+    Ok(job)
+}
+
+/// The job shown by `job get`. It's serialized as a list of one job, as it
+/// always has been.
+struct JobDetails(Job);
+
+impl Serialize for JobDetails {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        [&self.0].serialize(serializer)
+    }
+}
+
+impl Render for JobDetails {
+    fn render_table(&self, w: &mut dyn io::Write, wide: bool) -> io::Result<()> {
+        let job = &self.0;
+        writeln!(w, "Job ID:\t{}", job.id)?;
+        writeln!(w, "Status:\t{}", job.status)?;
+        writeln!(w, "Kind:\t{}", job.kind)?;
+        writeln!(w, "User:\t{}", job.user)?;
+        writeln!(w, "Runner:\t{}", job.runner)?;
+        if let Some(error_message) = &job.error_message {
+            writeln!(w, "Error:\t{}", error_message)?;
         }
-        Output::Tty => {
-            let mut tw = TabWriter::new(anstream::stdout()).ansi(true);
-            writeln!(&mut tw, "Job ID:\t{}", job.id)?;
-            writeln!(&mut tw, "Status:\t{}", job.status)?;
-            writeln!(&mut tw, "Kind:\t{}", job.kind)?;
-            writeln!(&mut tw, "User:\t{}", job.user)?;
-            writeln!(&mut tw, "Runner:\t{}", job.runner)?;
-            if let Some(error_message) = &job.error_message {
-                writeln!(&mut tw, "Error:\t{}", error_message)?;
-            }
-            writeln!(
-                &mut tw,
-                "Created:\t{}",
-                format_datetime(job.created_at, false, false)
-            )?;
+        writeln!(
+            w,
+            "Created:\t{}",
+            format_datetime(job.created_at, false, false)
+        )?;
+        if wide {
             writeln!(
-                &mut tw,
-                "Finished:\t{}",
-                format_datetime(job.finished_at, false, true)
+                w,
+                "Started:\t{}",
+                format_datetime(job.started_at, false, false)
             )?;
-            tw.flush()?;
         }
-    }
+        writeln!(
+            w,
+            "Finished:\t{}",
+            format_datetime(job.finished_at, false, true)
+        )?;
 
-    Ok(())
+        if wide {
+            writeln!(w, "Status detail:\t{}", job.human_readable_status)?;
+            if let Some(project_id) = &job.project_id {
+                writeln!(w, "Project ID:\t{project_id}")?;
+            }
+            if let Some(project_name) = &job.project_name {
+                writeln!(w, "Project:\t{project_name}")?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, serde::Serialize)]
-struct LogEntry {
+pub(crate) struct LogEntry {
     timestamp: DateTime<Utc>,
     #[serde(serialize_with = "serialize_log_level")]
     level: LogLevel,
@@ -439,10 +580,93 @@ where
     serializer.serialize_str(level.as_str_name())
 }
 
+impl From<LogMessage> for LogEntry {
+    fn from(log: LogMessage) -> Self {
+        let level = match log.level {
+            events::LogLevel::Error => LogLevel::Error,
+            events::LogLevel::Warning => LogLevel::Warning,
+            events::LogLevel::Info => LogLevel::Info,
+            events::LogLevel::Debug => LogLevel::Debug,
+            events::LogLevel::Trace => LogLevel::Trace,
+            events::LogLevel::Unspecified => LogLevel::Unspecified,
+        };
+
+        Self {
+            // Output lines streamed directly from a task have no timestamp.
+            timestamp: match log.timestamp_ns {
+                0 => Utc::now(),
+                ns => DateTime::from_timestamp_nanos(ns),
+            },
+            level,
+            log_type: if log.user {
+                LogType::User
+            } else {
+                LogType::System
+            },
+            message: log.message,
+        }
+    }
+}
+
+impl LogEntry {
+    /// The entry as a row of the logs table.
+    fn to_row(&self) -> String {
+        let level = match self.level {
+            LogLevel::Error => format!("{RED}ERROR{RED:#}"),
+            LogLevel::Warning => format!("{YELLOW}WARNING{YELLOW:#}"),
+            LogLevel::Debug => format!("{BLUE}DEBUG{BLUE:#}"),
+            LogLevel::Info => format!("{GREEN}INFO{GREEN:#}"),
+            LogLevel::Trace => format!("{CYAN}TRACE{CYAN:#}"),
+            LogLevel::Unspecified => format!("{DIM}UNKNOWN{DIM:#}"),
+        };
+
+        let log_type = match self.log_type {
+            LogType::System => format!("{DIM}SYSTEM{DIM:#}"),
+            LogType::User => format!("{GREEN}USER{GREEN:#}"),
+            LogType::Unspecified => format!("{DIM}UNKNOWN{DIM:#}"),
+        };
+
+        format!(
+            "{DIM}{}{DIM:#}\t{level}\t{log_type}\t{}",
+            self.timestamp.to_rfc3339(),
+            self.message.replace('\n', "\\n")
+        )
+    }
+}
+
 async fn handle_logs(cli: &Cli, args: JobLogsArgs) -> anyhow::Result<()> {
     let timeout = cli.timeout.unwrap_or(time::Duration::from_secs(30));
     let mut client = grpc::Client::new_lazy(&cli.profile, timeout)?;
 
+    if !args.follow {
+        return print_logs(cli, &mut client, &args, timeout).await;
+    }
+
+    // Once a job has finished, its logs are all stored, so there's nothing
+    // to follow.
+    let job = fetch_job(cli, &mut client, &args.job_id, timeout).await?;
+    if matches!(job.status, JobState::NotStarted | JobState::Running) {
+        return follow_logs(cli, &mut client, &args).await;
+    }
+
+    print_logs(cli, &mut client, &args, timeout).await?;
+    match job.status {
+        JobState::Fail => bail!(
+            "job failed: {}",
+            job.error_message.as_deref().unwrap_or("unknown error")
+        ),
+        JobState::Abort => Err(grpc::JobError::Cancelled.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Prints the logs stored for a job.
+async fn print_logs(
+    cli: &Cli,
+    client: &mut grpc::Client,
+    args: &JobLogsArgs,
+    timeout: time::Duration,
+) -> anyhow::Result<()> {
     let mut request = cli.traced(commanderpb::GetLogsRequest {
         job_id: args.job_id.clone(),
         ..Default::default()
@@ -460,10 +684,7 @@ async fn handle_logs(cli: &Cli, args: JobLogsArgs) -> anyhow::Result<()> {
         };
 
         let log_type = LogType::try_from(log.r#type).unwrap_or(LogType::Unspecified);
-        if args.all
-            || (args.system && log_type == LogType::System)
-            || (!args.system && log_type == LogType::User)
-        {
+        if args.includes(log_type) {
             let timestamp = DateTime::from_timestamp_nanos(log.emit_timestamp_ns);
             let level = LogLevel::try_from(log.level).unwrap_or(LogLevel::Unspecified);
 
@@ -479,10 +700,13 @@ async fn handle_logs(cli: &Cli, args: JobLogsArgs) -> anyhow::Result<()> {
     });
 
     match cli.global.output {
-        Output::Json => {
-            serde_json::to_writer(stdout(), &entries.collect::<Vec<_>>())?;
-            println!();
+        // With --follow, print the same JSON lines as while following.
+        Output::Json if args.follow => {
+            for entry in entries {
+                emit_json(&entry)?;
+            }
         }
+        Output::Json => emit_json(&entries.collect::<Vec<_>>())?,
         Output::Tty => {
             let mut entries = entries.peekable();
             if entries.peek().is_none() {
@@ -493,27 +717,7 @@ async fn handle_logs(cli: &Cli, args: JobLogsArgs) -> anyhow::Result<()> {
             writeln!(&mut tw, "TIMESTAMP\tLEVEL\tTYPE\tMESSAGE")?;
 
             for entry in entries {
-                let level = match entry.level {
-                    LogLevel::Error => format!("{RED}ERROR{RED:#}"),
-                    LogLevel::Warning => format!("{YELLOW}WARNING{YELLOW:#}"),
-                    LogLevel::Debug => format!("{BLUE}DEBUG{BLUE:#}"),
-                    LogLevel::Info => format!("{GREEN}INFO{GREEN:#}"),
-                    LogLevel::Trace => format!("{CYAN}TRACE{CYAN:#}"),
-                    LogLevel::Unspecified => format!("{DIM}UNKNOWN{DIM:#}"),
-                };
-
-                let log_type = match entry.log_type {
-                    LogType::System => format!("{DIM}SYSTEM{DIM:#}"),
-                    LogType::User => format!("{GREEN}USER{GREEN:#}"),
-                    LogType::Unspecified => format!("{DIM}UNKNOWN{DIM:#}"),
-                };
-
-                writeln!(
-                    &mut tw,
-                    "{DIM}{}{DIM:#}\t{level}\t{log_type}\t{}",
-                    entry.timestamp.to_rfc3339(),
-                    entry.message.replace('\n', "\\n")
-                )?;
+                writeln!(&mut tw, "{}", entry.to_row())?;
             }
 
             tw.flush()?;
@@ -523,6 +727,79 @@ async fn handle_logs(cli: &Cli, args: JobLogsArgs) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Prints the logs of a running job as they're emitted, until it completes.
+/// The log stream replays the job's events from the beginning, so logs
+/// emitted before following started are printed too.
+async fn follow_logs(
+    cli: &Cli,
+    client: &mut grpc::Client,
+    args: &JobLogsArgs,
+) -> anyhow::Result<()> {
+    let mut request = cli.traced(commanderpb::SubscribeLogsRequest {
+        job_id: args.job_id.clone(),
+    });
+
+    if let Some(timeout) = cli.timeout {
+        request.set_timeout(timeout);
+    }
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    futures::pin_mut!(ctrl_c);
+
+    let endpoint = Arc::new(tokio::sync::OnceCell::new());
+    let events = client.monitor_job(request, Arc::clone(&endpoint));
+    futures::pin_mut!(events);
+
+    let res = loop {
+        let event = tokio::select! {
+            event = events.next() => event,
+            _ = &mut ctrl_c => {
+                eprintln!("\nStopped following job {}; it's still running.", args.job_id);
+                break Ok(());
+            }
+        };
+
+        let log = match event {
+            Some(Ok(JobEvent::UserLog(log))) => log,
+            Some(Ok(JobEvent::Completion(completion))) => {
+                break completion.result.map(drop).map_err(Into::into);
+            }
+            Some(Ok(_)) => continue,
+            Some(Err(status)) => break Err(format_grpc_status(status)),
+            None => {
+                break Err(anyhow::anyhow!(
+                    "the log stream for job {} ended before the job completed",
+                    args.job_id
+                ));
+            }
+        };
+
+        let log_type = if log.user {
+            LogType::User
+        } else {
+            LogType::System
+        };
+        if !args.includes(log_type) {
+            continue;
+        }
+
+        let entry = LogEntry::from(log);
+        match cli.global.output {
+            Output::Json => emit_json(&entry)?,
+            Output::Tty => {
+                let mut out = anstream::stdout().lock();
+                writeln!(&mut out, "{}", entry.to_row().replace('\t', "  "))?;
+            }
+        }
+    };
+
+    if let Some(ep) = endpoint.get() {
+        ep.close().await;
+    }
+
+    res
+}
+
 async fn handle_stop(cli: &Cli, args: JobStopArgs) -> anyhow::Result<()> {
     let timeout = cli.timeout.unwrap_or(time::Duration::from_secs(30));
     let mut client = grpc::Client::new_lazy(&cli.profile, timeout)?;
@@ -541,6 +818,37 @@ async fn handle_stop(cli: &Cli, args: JobStopArgs) -> anyhow::Result<()> {
     }
 
     info!(job_id = args.job_id, "job cancelled");
+    if cli.global.output == Output::Json {
+        emit_json(&JobResult {
+            job_id: args.job_id,
+            status: JobState::Abort,
+            details: (),
+        })?;
+    }
+
+    Ok(())
+}
+
+fn handle_replay_events(cli: &Cli, args: JobReplayEventsArgs) -> anyhow::Result<()> {
+    let events = capture::read_events(&args.path)?;
+    if events.is_empty() {
+        bail!("no events recorded in {}", args.path.display());
+    }
+
+    // A capture can have several jobs, like the plan and apply jobs of a
+    // table creation. Replay them one at a time, in the order they started.
+    let mut jobs: Vec<(&str, Vec<JobEvent>)> = Vec::new();
+    for (job_id, event) in capture::replay(&events) {
+        match jobs.iter_mut().find(|(id, _)| *id == job_id) {
+            Some((_, job_events)) => job_events.push(event),
+            None => jobs.push((job_id, vec![event])),
+        }
+    }
+
+    for (job_id, job_events) in jobs {
+        replay_job(cli, job_id, job_events)?;
+    }
+
     Ok(())
 }
 
@@ -550,3 +858,62 @@ fn to_proto_timestamp(dt: DateTime<Utc>) -> prost_types::Timestamp {
         nanos: dt.timestamp_subsec_nanos() as i32,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bauplan::grpc::job::JobKind;
+
+    use super::*;
+    use crate::cli::render::assert_round_trips;
+
+    fn job(id: &str) -> Job {
+        Job {
+            id: id.to_owned(),
+            status: JobState::Fail,
+            human_readable_status: "fail".to_owned(),
+            kind: JobKind::Run,
+            user: "someone".to_owned(),
+            created_at: DateTime::from_timestamp(1_700_000_000, 123_000_000),
+            started_at: DateTime::from_timestamp(1_700_000_005, 0),
+            finished_at: None,
+            runner: "runner-1".to_owned(),
+            error_message: Some("model 'trips' failed:\n  - no rows".to_owned()),
+            project_id: None,
+            project_name: Some("taxi".to_owned()),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        assert_round_trips::<Vec<Job>>(&JobDetails(job("a")));
+        assert_round_trips::<Vec<Job>>(&JobList {
+            jobs: vec![job("a"), job("b")],
+            utc: true,
+            show_project: true,
+        });
+    }
+
+    /// The SDK parses filters with the same names as the CLI.
+    #[test]
+    fn filter_names_match_sdk() {
+        for arg in JobKindArg::value_variants() {
+            let name = arg.to_possible_value().unwrap().get_name().to_owned();
+            let kind: JobKind = name.parse().unwrap();
+            assert_eq!(
+                commanderpb::JobKind::from(kind),
+                commanderpb::JobKind::from(*arg),
+                "{name}"
+            );
+        }
+
+        for arg in JobStatusArg::value_variants() {
+            let name = arg.to_possible_value().unwrap().get_name().to_owned();
+            let state: JobState = name.parse().unwrap();
+            assert_eq!(
+                commanderpb::JobStateType::from(state),
+                commanderpb::JobStateType::from(*arg),
+                "{name}"
+            );
+        }
+    }
+}
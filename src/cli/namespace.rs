@@ -1,9 +1,14 @@
 use std::io::{Write as _, stdout};
 
-use bauplan::{ApiErrorKind, commit::CommitOptions, namespace::*};
+use bauplan::{commit::CommitOptions, namespace::*};
+use serde::Serialize;
 use tabwriter::TabWriter;
 
-use crate::cli::{Cli, Output, api_err_kind, color::CliExamples};
+use crate::cli::{
+    Cli, Output, api_err,
+    color::CliExamples,
+    render::{RefChange, emit_json, write_json_array},
+};
 
 #[derive(Debug, clap::Args)]
 pub(crate) struct NamespaceArgs {
@@ -111,24 +116,22 @@ fn list_namespaces(
         limit,
     }: NamespaceLsArgs,
 ) -> anyhow::Result<()> {
-    let at_ref = r#ref
-        .as_deref()
-        .or(cli.profile.active_branch.as_deref())
-        .unwrap_or("main");
+    let at_ref = cli.resolve_ref(
+        r#ref
+            .as_deref()
+            .or(cli.profile.active_branch.as_deref())
+            .unwrap_or("main"),
+    )?;
 
     let req = GetNamespaces {
-        at_ref,
+        at_ref: &at_ref,
         filter_by_name: namespace.as_deref(),
     };
 
     let namespaces = bauplan::paginate(req, limit, |r| cli.roundtrip(r))?;
 
     match cli.global.output {
-        Output::Json => {
-            let all_namespaces = namespaces.collect::<anyhow::Result<Vec<_>>>()?;
-            serde_json::to_writer(stdout(), &all_namespaces)?;
-            println!();
-        }
+        Output::Json => write_json_array(&mut stdout().lock(), namespaces)?,
         Output::Tty => {
             let mut tw = TabWriter::new(stdout());
             writeln!(&mut tw, "NAME\tKIND")?;
@@ -144,6 +147,14 @@ fn list_namespaces(
     Ok(())
 }
 
+/// The result of `namespace create`, as printed with `-O json`.
+#[derive(Debug, Serialize)]
+struct CreatedNamespace {
+    namespace: Namespace,
+    /// False if the namespace already existed, with `--if-not-exists`.
+    changed: bool,
+}
+
 fn create_namespace(
     cli: &Cli,
     NamespaceCreateArgs {
@@ -167,16 +178,30 @@ fn create_namespace(
         },
     };
 
-    if let Err(e) = cli.roundtrip(req) {
-        if if_not_exists && matches!(api_err_kind(&e), Some(ApiErrorKind::NamespaceExists { .. })) {
-            eprintln!("Namespace {namespace:?} already exists");
-            return Ok(());
-        } else {
-            return Err(e);
-        }
+    let created = match cli.roundtrip(req) {
+        Ok(ns) => CreatedNamespace {
+            namespace: ns,
+            changed: true,
+        },
+        Err(e) => match api_err(&e).and_then(CreateNamespace::existing) {
+            Some(existing) if if_not_exists => CreatedNamespace {
+                namespace: existing,
+                changed: false,
+            },
+            _ => return Err(e),
+        },
+    };
+
+    if created.changed {
+        eprintln!("Created namespace {namespace:?}");
+    } else {
+        eprintln!("Namespace {namespace:?} already exists");
+    }
+
+    if cli.global.output == Output::Json {
+        emit_json(&created)?;
     }
 
-    eprintln!("Created namespace {namespace:?}");
     Ok(())
 }
 
@@ -203,20 +228,23 @@ fn delete_namespace(
         },
     };
 
-    if let Err(e) = cli.roundtrip(req) {
-        if if_exists
-            && matches!(
-                api_err_kind(&e),
-                Some(ApiErrorKind::NamespaceNotFound { .. })
-            )
-        {
-            eprintln!("Namespace {namespace:?} does not exist");
-            return Ok(());
-        } else {
-            return Err(e);
+    let res = match cli.roundtrip(req) {
+        Ok(head) => {
+            eprintln!("Deleted namespace {namespace:?}");
+            RefChange::changed(head)
         }
+        Err(e) => match api_err(&e).and_then(DeleteNamespace::missing) {
+            Some(head) if if_exists => {
+                eprintln!("Namespace {namespace:?} does not exist");
+                RefChange::unchanged(Some(head.clone()))
+            }
+            _ => return Err(e),
+        },
+    };
+
+    if cli.global.output == Output::Json {
+        emit_json(&res)?;
     }
 
-    eprintln!("Deleted namespace {namespace:?}");
     Ok(())
 }
@@ -1,6 +1,7 @@
 use std::{
-    io::Write,
+    io::{self, IsTerminal as _, Write},
     path::{Path, PathBuf},
+    str::FromStr,
     time,
 };
 
@@ -8,10 +9,13 @@ use anyhow::{Context as _, anyhow, bail};
 use bauplan::{
     grpc::{self, generated as commanderpb},
     project::{ParameterDefault, ParameterType, ParameterValue, ProjectFile},
+    yaml,
 };
 use resolve_path::PathResolveExt as _;
 use tabwriter::TabWriter;
-use crate::cli::{Cli, color::*, format_grpc_status, with_rt, yaml};
+use tracing::info;
+
+use crate::cli::{Cli, Output, color::*, format_grpc_status, input, render::emit_json, with_rt};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 pub(crate) enum ParameterTypeArg {
@@ -61,8 +65,10 @@ pub(crate) enum ParameterCommand {
   bauplan parameter ls --project-dir /path/to/project
 "))]
 pub(crate) struct ParameterLsArgs {
-    /// Path to the root Bauplan project directory.
-    #[arg(short, long, default_value = ".")]
+    /// Path to the root Bauplan project directory. With `-`, the nearest
+    /// directory containing bauplan_project.yml, walking up from the current
+    /// directory.
+    #[arg(short, long, default_value = "-")]
     pub project_dir: Option<PathBuf>,
 }
 
@@ -77,8 +83,10 @@ pub(crate) struct ParameterLsArgs {
 pub(crate) struct ParameterRmArgs {
     /// Name of the parameter to remove
     pub name: String,
-    /// Path to the root Bauplan project directory.
-    #[arg(short, long, default_value = ".")]
+    /// Path to the root Bauplan project directory. With `-`, the nearest
+    /// directory containing bauplan_project.yml, walking up from the current
+    /// directory.
+    #[arg(short, long, default_value = "-")]
     pub project_dir: Option<PathBuf>,
 }
 
@@ -122,20 +130,22 @@ pub(crate) struct ParameterSetArgs {
     /// Read value from file
     #[arg(short, long)]
     pub file: Option<PathBuf>,
-    /// Path to the root Bauplan project directory.
-    #[arg(short, long, default_value = ".")]
+    /// Path to the root Bauplan project directory. With `-`, the nearest
+    /// directory containing bauplan_project.yml, walking up from the current
+    /// directory.
+    #[arg(short, long, default_value = "-")]
     pub project_dir: Option<PathBuf>,
 }
 
 pub(crate) fn handle(cli: &Cli, args: ParameterArgs) -> anyhow::Result<()> {
     match args.command {
-        ParameterCommand::Ls(args) => list_parameters(args),
-        ParameterCommand::Rm(args) => remove_parameter(args),
+        ParameterCommand::Ls(args) => list_parameters(cli, args),
+        ParameterCommand::Rm(args) => remove_parameter(cli, args),
         ParameterCommand::Set(args) => set_parameter(cli, args),
     }
 }
 
-fn list_parameters(args: ParameterLsArgs) -> anyhow::Result<()> {
+fn list_parameters(cli: &Cli, args: ParameterLsArgs) -> anyhow::Result<()> {
     let project_dir = resolve_project_dir(args.project_dir.as_deref())?;
     let project = ProjectFile::from_dir(&project_dir)?;
 
@@ -148,10 +158,10 @@ fn list_parameters(args: ParameterLsArgs) -> anyhow::Result<()> {
         }
     }
 
-    print_parameters(&project)
+    print_parameters(cli, &project)
 }
 
-fn remove_parameter(args: ParameterRmArgs) -> anyhow::Result<()> {
+fn remove_parameter(cli: &Cli, args: ParameterRmArgs) -> anyhow::Result<()> {
     validate_parameter_name(&args.name)?;
 
     let project_dir = resolve_project_dir(args.project_dir.as_deref())?;
@@ -165,7 +175,7 @@ fn remove_parameter(args: ParameterRmArgs) -> anyhow::Result<()> {
         .context("unable to update parameter in project file")?;
 
     project.parameters.remove(&args.name);
-    print_parameters(&project)
+    print_parameters(cli, &project)
 }
 
 fn set_parameter(cli: &Cli, args: ParameterSetArgs) -> anyhow::Result<()> {
@@ -176,7 +186,7 @@ fn set_parameter(cli: &Cli, args: ParameterSetArgs) -> anyhow::Result<()> {
     }
 
     let default_value = if let Some(p) = &args.file {
-        Some(std::fs::read_to_string(p)?)
+        Some(input::read_text_file(p)?)
     } else {
         args.default_value
     };
@@ -249,7 +259,7 @@ fn set_parameter(cli: &Cli, args: ParameterSetArgs) -> anyhow::Result<()> {
     })
     .context("unable to update parameter in project file")?;
 
-    print_parameters(&project)
+    print_parameters(cli, &project)
 }
 
 fn write_parameter(
@@ -308,11 +318,21 @@ fn write_parameter(
     Ok(())
 }
 
-pub(crate) fn resolve_project_dir(arg: Option<&Path>) -> std::io::Result<PathBuf> {
-    if let Some(p) = arg {
-        Ok(p.try_resolve()?.into_owned())
-    } else {
-        std::env::current_dir()
+/// Resolves the `--project-dir` argument. If it's missing or `-`, this is the
+/// nearest directory containing a project file, starting from the current
+/// directory and walking up.
+pub(crate) fn resolve_project_dir(arg: Option<&Path>) -> anyhow::Result<PathBuf> {
+    match arg {
+        Some(p) if p != Path::new("-") => Ok(p.try_resolve()?.into_owned()),
+        _ => {
+            let cwd = std::env::current_dir()?;
+            let dir = ProjectFile::find_dir(&cwd)?;
+            if dir != cwd {
+                info!("using project in {}", dir.display());
+            }
+
+            Ok(dir)
+        }
     }
 }
 
@@ -328,6 +348,50 @@ pub(crate) fn validate_parameter_name(name: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// A `--param` value for a run, as `name=value`. With `name=@path` the
+/// value is read from a file, and with `name=-` from stdin, prompting
+/// without echo on a terminal, so that secrets stay out of shell history.
+/// See [ParamSource].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ParamArg {
+    pub name: String,
+    pub source: ParamSource,
+}
+
+impl FromStr for ParamArg {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, source) = ParamSource::parse(s, true)?;
+        Ok(ParamArg { name, source })
+    }
+}
+
+impl ParamArg {
+    /// The value, read from a file or stdin if need be. A single trailing
+    /// newline is trimmed from values that are read.
+    pub(crate) fn read_value(&self) -> anyhow::Result<String> {
+        let name = &self.name;
+        let text = match &self.source {
+            ParamSource::Value(v) => return Ok(v.clone()),
+            source => source
+                .read(name)
+                .with_context(|| format!("failed to read the value of parameter {name:?}"))?,
+        };
+
+        Ok(match text.strip_suffix('\n') {
+            Some(trimmed) => trimmed.to_owned(),
+            None => text,
+        })
+    }
+
+    /// The argument as it would be passed, for repeating a command. Values
+    /// read from files or stdin are read again.
+    pub(crate) fn to_arg(&self) -> String {
+        self.source.to_arg(&self.name)
+    }
+}
+
 /// Parse a raw parameter string as a value. Should only be called for
 /// non-secret parameters.
 pub(crate) fn parse_parameter(
@@ -359,7 +423,11 @@ fn parse_bool(s: &str) -> anyhow::Result<bool> {
     }
 }
 
-fn print_parameters(project: &ProjectFile) -> anyhow::Result<()> {
+fn print_parameters(cli: &Cli, project: &ProjectFile) -> anyhow::Result<()> {
+    if cli.global.output == Output::Json {
+        return emit_json(&project.parameters);
+    }
+
     let mut tw = TabWriter::new(anstream::stdout().lock()).ansi(true);
     writeln!(&mut tw, "NAME\tTYPE\tREQUIRED\tDEFAULT\tDESCRIPTION")?;
 
@@ -382,3 +450,26 @@ fn print_parameters(project: &ProjectFile) -> anyhow::Result<()> {
     tw.flush()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_from_file() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("token");
+        std::fs::write(&path, "hunter2\n\n")?;
+
+        let arg: ParamArg = format!("token=@{}", path.display()).parse()?;
+        assert_eq!(arg.read_value()?, "hunter2\n");
+
+        let missing: ParamArg = "token=@/no/such/file".parse()?;
+        let err = missing.read_value().unwrap_err();
+        assert!(
+            format!("{err:#}").contains(r#"parameter "token""#),
+            "{err:#}"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+//! Checksums for saved table plans. A plan saved with `--save-plan` gets a
+//! `<file>.sha256` sidecar in the format `sha256sum` writes, so a plan
+//! mangled on its way to another machine (by git's autocrlf, or a bad
+//! copy-paste) is caught before it's applied.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail};
+use rsa::sha2::{Digest as _, Sha256};
+use serde::Serialize;
+
+/// A plan file's checksum, and whether it matched a sidecar.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Checksum {
+    pub sha256: String,
+    pub size: u64,
+    /// Whether a sidecar was found, and matched. Verification fails on a
+    /// mismatch, so this is only `false` if there was no sidecar.
+    pub verified: bool,
+}
+
+/// The path of the checksum sidecar for a plan file.
+pub(crate) fn sidecar_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".sha256");
+    PathBuf::from(name)
+}
+
+/// Writes a plan, along with its checksum sidecar.
+pub(crate) fn write(path: &Path, contents: &str) -> anyhow::Result<()> {
+    std::fs::write(path, contents)
+        .map_err(|e| anyhow!("failed to write plan {}: {e}", path.display()))?;
+    write_sidecar(path, contents.as_bytes())
+}
+
+/// Writes the checksum sidecar for a plan file, replacing any existing one.
+/// Returns the new checksum.
+pub(crate) fn update(path: &Path) -> anyhow::Result<Checksum> {
+    let contents =
+        std::fs::read(path).map_err(|e| anyhow!("failed to read plan {}: {e}", path.display()))?;
+    write_sidecar(path, &contents)?;
+
+    Ok(Checksum {
+        sha256: sha256(&contents),
+        size: contents.len() as u64,
+        verified: true,
+    })
+}
+
+fn write_sidecar(path: &Path, contents: &[u8]) -> anyhow::Result<()> {
+    let name = path
+        .file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy();
+    let sidecar = sidecar_path(path);
+    std::fs::write(&sidecar, format!("{}  {name}\n", sha256(contents)))
+        .map_err(|e| anyhow!("failed to write checksum {}: {e}", sidecar.display()))
+}
+
+/// Checks a plan file against its sidecar, if it has one. The checksum is of
+/// the file as it is on disk, before line endings are normalized.
+pub(crate) fn verify(path: &Path) -> anyhow::Result<Checksum> {
+    let contents =
+        std::fs::read(path).map_err(|e| anyhow!("failed to read plan {}: {e}", path.display()))?;
+    let actual = sha256(&contents);
+
+    let sidecar = sidecar_path(path);
+    let expected = match std::fs::read_to_string(&sidecar) {
+        Ok(s) => parse_sidecar(&s)
+            .ok_or_else(|| anyhow!("{}: not a valid SHA-256 checksum", sidecar.display()))?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Ok(Checksum {
+                sha256: actual,
+                size: contents.len() as u64,
+                verified: false,
+            });
+        }
+        Err(e) => bail!("failed to read checksum {}: {e}", sidecar.display()),
+    };
+
+    if expected != actual {
+        bail!(
+            "plan {path} doesn't match its checksum: expected sha256 {expected}, got {actual} \
+             ({} bytes). If you edited the plan on purpose, run \
+             `bauplan table plan verify --update {path}`",
+            contents.len(),
+            path = path.display(),
+        );
+    }
+
+    Ok(Checksum {
+        sha256: actual,
+        size: contents.len() as u64,
+        verified: true,
+    })
+}
+
+/// Reads the digest from a `sha256sum` line, like `<hex>  <name>`.
+fn parse_sidecar(s: &str) -> Option<String> {
+    let digest = s.split_whitespace().next()?;
+    if digest.len() == 64 && digest.bytes().all(|b| b.is_ascii_hexdigit()) {
+        Some(digest.to_ascii_lowercase())
+    } else {
+        None
+    }
+}
+
+fn sha256(contents: &[u8]) -> String {
+    Sha256::digest(contents)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("plan.yaml");
+        write(&path, "table:\n  name: orders\n")?;
+
+        let sidecar = std::fs::read_to_string(sidecar_path(&path))?;
+        assert!(sidecar.ends_with("  plan.yaml\n"), "{sidecar}");
+
+        let checksum = verify(&path)?;
+        assert!(checksum.verified);
+        assert_eq!(checksum.size, 22);
+        Ok(())
+    }
+
+    #[test]
+    fn tampered() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("plan.yaml");
+        write(&path, "table:\n  name: orders\n")?;
+        std::fs::write(&path, "table:\r\n  name: orders\r\n")?;
+
+        let err = verify(&path).unwrap_err().to_string();
+        assert!(err.contains(&format!(
+            "expected sha256 {}",
+            sha256(b"table:\n  name: orders\n")
+        )));
+        assert!(err.contains(&format!("got {}", sha256(b"table:\r\n  name: orders\r\n"))));
+        Ok(())
+    }
+
+    #[test]
+    fn updated() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("plan.yaml");
+        write(&path, "table:\n  name: orders\n")?;
+        std::fs::write(&path, "table:\n  name: returns\n")?;
+        assert!(verify(&path).is_err());
+
+        update(&path)?;
+        assert!(verify(&path)?.verified);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_sidecar() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("plan.yaml");
+        std::fs::write(&path, "table: {}\n")?;
+
+        assert!(!verify(&path)?.verified);
+        Ok(())
+    }
+}
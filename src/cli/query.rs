@@ -1,24 +1,34 @@
-use std::{fmt::Write as _, io::Write, path::PathBuf, time};
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    time,
+};
 
 use crate::cli::{
-    Cli, KeyValue, Output, Priority, format_grpc_status, on_off,
+    Cli, KeyValue, Output, Priority, format_grpc_status, input, on_off,
+    render::emit_json,
     run::{job_request_common, monitor_job_progress},
     spinner::ProgressExt,
 };
 use anyhow::{Context as _, bail};
 use arrow::{
-    array::RecordBatch,
+    array::{RecordBatch, RecordBatchWriter},
     datatypes::Schema,
     error::ArrowError,
     util::display::{ArrayFormatter, FormatOptions},
 };
 use arrow_flight::error::{FlightError, Result as FlightResult};
 use bauplan::{
-    flight::{fetch_flight_results, limit_rows},
+    events::{FlightServer, JobEvent},
+    export,
+    flight::{self, fetch_flight_results_with_retries, limit_rows},
     grpc::{self, generated as commanderpb},
+    query::{SqlValue, bind_params},
 };
 use bauplan_longbow::{BauplanPreset, iroh};
-use commanderpb::runner_event::Event as RunnerEvent;
 use futures::{Stream, StreamExt, TryStreamExt, future::Either};
 use tabwriter::TabWriter;
 
@@ -36,11 +46,20 @@ use tabwriter::TabWriter;
   # Run query on specific branch
   bauplan query --ref main \"SELECT * FROM my_table\"
 
+  # Bind values to :name placeholders
+  bauplan query --param-sql min_age=30 --param-sql name=\"O'Brien\" \"SELECT * FROM titanic WHERE Age > :min_age AND Name != :name\"
+
   # Run query in specific namespace
   bauplan query --namespace raw_data \"SELECT * FROM customers LIMIT 5\"
 
   # Run query with full output (no truncation)
   bauplan query --no-trunc \"SELECT * FROM wide_table\"
+
+  # Write all results to a CSV file named after the job
+  bauplan query --all-rows --output-file \"out/{job_id}_{date}.csv\" \"SELECT * FROM my_table\"
+
+  # Write all results to a Parquet file
+  bauplan query --all-rows --output-file out.parquet \"SELECT * FROM my_table\"
 "))]
 pub(crate) struct QueryArgs {
     /// SQL query. Column and table names are case-sensitive
@@ -66,12 +85,84 @@ pub(crate) struct QueryArgs {
     /// Do not truncate output
     #[arg(long)]
     pub no_trunc: bool,
-    /// Extra arguments as key=value pairs (repeatable)
+    /// Extra arguments as key=value pairs, where key=@path reads the value from a file (repeatable)
     #[arg(short, long, action = clap::ArgAction::Append)]
     pub arg: Vec<KeyValue>,
+    /// Bind a value to a :key placeholder in the query, as a key=value pair
+    /// (repeatable). Integers, decimals, true, false and null are bound as
+    /// such; anything else, or a value in single quotes, is bound as a string
+    #[arg(long, value_name = "KEY=VALUE", action = clap::ArgAction::Append)]
+    pub param_sql: Vec<KeyValue>,
     /// Set the job priority (1-10, where 10 is highest priority)
     #[arg(long)]
     pub priority: Option<Priority>,
+    /// A human-friendly name for the job
+    #[arg(long)]
+    pub name: Option<String>,
+    /// Write results to a file instead of printing them. The format is
+    /// picked from the extension (.csv, .json, .jsonl or .parquet), and the
+    /// path may contain {job_id}, {date}, {time} or strftime placeholders
+    #[arg(long)]
+    pub output_file: Option<PathBuf>,
+    /// Replace the output file if it already exists
+    #[arg(long, requires = "output_file")]
+    pub overwrite: bool,
+}
+
+/// The format of a file written with `--output-file`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFileFormat {
+    Csv,
+    Json,
+    JsonLines,
+    Parquet,
+}
+
+impl OutputFileFormat {
+    fn from_path(path: &Path) -> anyhow::Result<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("csv") => Ok(Self::Csv),
+            Some("json") => Ok(Self::Json),
+            Some("jsonl" | "ndjson") => Ok(Self::JsonLines),
+            Some("parquet") => Ok(Self::Parquet),
+            _ => bail!(
+                "can't determine the format of {} (expected a .csv, .json, .jsonl or .parquet extension)",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// Infers the type of a `--param-sql` value.
+fn parse_sql_param(value: String) -> SqlValue {
+    if let Some(s) = value.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+        return SqlValue::Str(s.to_owned());
+    }
+
+    match value.as_str() {
+        "null" => SqlValue::Null,
+        "true" => SqlValue::Bool(true),
+        "false" => SqlValue::Bool(false),
+        // Leaves out 'inf' and 'nan', which are more likely to be strings.
+        s if s.bytes().any(|b| b.is_ascii_digit()) => {
+            if let Ok(i) = s.parse() {
+                SqlValue::Int(i)
+            } else if let Ok(f) = s.parse() {
+                SqlValue::Float(f)
+            } else {
+                SqlValue::Str(value)
+            }
+        }
+        _ => SqlValue::Str(value),
+    }
+}
+
+#[derive(serde::Serialize)]
+struct JsonOutputFile<'a> {
+    job_id: &'a str,
+    path: &'a Path,
+    rows_written: u64,
+    bytes_written: u64,
 }
 
 pub(crate) async fn handle(cli: &Cli, args: QueryArgs) -> anyhow::Result<()> {
@@ -85,7 +176,11 @@ pub(crate) async fn handle(cli: &Cli, args: QueryArgs) -> anyhow::Result<()> {
         all_rows,
         no_trunc,
         arg,
+        param_sql,
         priority,
+        name,
+        output_file,
+        overwrite,
     } = args;
 
     let timeout = cli.timeout.unwrap_or(time::Duration::from_secs(1800));
@@ -93,23 +188,60 @@ pub(crate) async fn handle(cli: &Cli, args: QueryArgs) -> anyhow::Result<()> {
     let mut client = grpc::Client::new_lazy(&cli.profile, timeout)?;
 
     let sql_query = match (sql, file) {
-        (None, Some(path)) => std::fs::read_to_string(&path)?,
+        (None, Some(path)) => input::read_text_file(&path)?,
         (Some(s), None) => s,
         _ => bail!("exactly one of either '--file' or inline SQL must be specified"),
     };
 
+    let params: HashMap<String, SqlValue> = KeyValue::read_all(&param_sql)?
+        .into_iter()
+        .map(|(k, v)| (k, parse_sql_param(v)))
+        .collect();
+    let sql_query = bind_params(&sql_query, &params)?;
+
+    // Check the output path up front, so we don't run the query only to fail
+    // at the end.
+    let output_file = output_file
+        .map(|path| {
+            let format = OutputFileFormat::from_path(&path)?;
+            let template = path
+                .to_str()
+                .filter(|s| export::is_output_path_template(s))
+                .map(str::to_owned);
+
+            if template.is_none() && !overwrite && path.exists() {
+                bail!(
+                    "{} already exists (use --overwrite to replace it)",
+                    path.display()
+                );
+            }
+
+            Ok((path, template, format))
+        })
+        .transpose()?;
+
     let row_limit = if max_rows > 0 && !all_rows {
         Some(max_rows)
     } else {
         None
     };
 
-    let job_request_common = job_request_common(cli, arg, priority);
+    let job_request_common = job_request_common(cli, arg, priority, name)?;
 
     let progress = cli.new_spinner().with_message("Planning query...");
     progress.enable_steady_tick(time::Duration::from_millis(100));
 
-    let r#ref = r#ref.or_else(|| cli.profile.active_branch.clone());
+    let r#ref = r#ref
+        .map(|r| cli.resolve_ref(&r))
+        .transpose()?
+        .or_else(|| cli.profile.active_branch.clone());
+    if let Some((_, id)) = r#ref.as_deref().and_then(bauplan::split_snapshot_ref) {
+        bail!(
+            "can't read snapshot {id}: queries can only read a table at a ref, not as of a snapshot"
+        );
+    }
+
+    let namespace = namespace.or_else(|| cli.profile.default_namespace.clone());
 
     let req = commanderpb::QueryRunRequest {
         job_request_common: Some(job_request_common),
@@ -136,17 +268,19 @@ pub(crate) async fn handle(cli: &Cli, args: QueryArgs) -> anyhow::Result<()> {
     let ctrl_c = tokio::signal::ctrl_c();
     futures::pin_mut!(ctrl_c);
 
-    let mut flight_event = None;
+    let mut flight_server = None;
     monitor_job_progress(
         cli,
         &mut client,
         job_id.clone(),
         "query",
         progress.clone(),
+        None,
+        None,
         &mut ctrl_c,
         |event| {
-            if let RunnerEvent::FlightServerStart(flight) = event {
-                flight_event = Some(flight);
+            if let JobEvent::FlightReady(flight) = event {
+                flight_server = Some(flight);
             }
         },
     )
@@ -160,7 +294,7 @@ pub(crate) async fn handle(cli: &Cli, args: QueryArgs) -> anyhow::Result<()> {
         (Some(endpoint), schema, Either::Left(batches))
     } else {
         let tp = cli.traceparent();
-        let (schema, batches) = fetch_results(flight_event, timeout, row_limit, tp).await?;
+        let (schema, batches) = fetch_results(flight_server, timeout, row_limit, tp).await?;
 
         (None, schema, Either::Right(batches))
     };
@@ -168,10 +302,46 @@ pub(crate) async fn handle(cli: &Cli, args: QueryArgs) -> anyhow::Result<()> {
     let batches = limit_rows(batches, row_limit);
     futures::pin_mut!(batches);
 
-    progress.finish_with_done();
-    match cli.global.output {
-        Output::Tty => print_tty(schema, batches, !no_trunc).await?,
-        Output::Json => print_json(batches, &job_id).await?,
+    if let Some((path, template, format)) = output_file {
+        let path = match template {
+            Some(t) => export::expand_output_path(&t, &job_id, &chrono::Local::now())?,
+            None => path,
+        };
+
+        let file = export::create_output_file(&path, overwrite, true).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::AlreadyExists {
+                anyhow::anyhow!(
+                    "{} already exists (use --overwrite to replace it)",
+                    path.display()
+                )
+            } else {
+                anyhow::Error::new(e).context(format!("failed to create {}", path.display()))
+            }
+        })?;
+
+        let rows_written = write_file(file, format, schema, batches).await?;
+        let bytes_written = std::fs::metadata(&path)?.len();
+        progress.finish_with_done();
+
+        match cli.global.output {
+            Output::Tty => eprintln!("Wrote {rows_written} rows to {}", path.display()),
+            Output::Json => {
+                let written = JsonOutputFile {
+                    job_id: &job_id,
+                    path: &path,
+                    rows_written,
+                    bytes_written,
+                };
+
+                emit_json(&written)?;
+            }
+        }
+    } else {
+        progress.finish_with_done();
+        match cli.global.output {
+            Output::Tty => print_tty(schema, batches, !no_trunc, row_limit).await?,
+            Output::Json => print_json(batches, &job_id).await?,
+        }
     }
 
     if let Some(endpoint) = longbow_endpoint {
@@ -182,16 +352,16 @@ pub(crate) async fn handle(cli: &Cli, args: QueryArgs) -> anyhow::Result<()> {
 }
 
 async fn fetch_results(
-    flight_event: Option<commanderpb::FlightServerStartEvent>,
+    flight_server: Option<FlightServer>,
     timeout: time::Duration,
     row_limit: Option<u64>,
     traceparent: String,
 ) -> anyhow::Result<(Schema, impl Stream<Item = FlightResult<RecordBatch>>)> {
-    let Some(commanderpb::FlightServerStartEvent {
+    let Some(FlightServer {
         endpoint,
         magic_token,
         ..
-    }) = flight_event
+    }) = flight_server
     else {
         bail!("Query completed, but no results available");
     };
@@ -206,12 +376,13 @@ async fn fetch_results(
         bail!("Invalid endpoint: {}", endpoint);
     };
 
-    let (schema, batches) = fetch_flight_results(
+    let (schema, batches) = fetch_flight_results_with_retries(
         endpoint,
         magic_token,
         timeout,
         row_limit,
         Some(&traceparent),
+        flight::DEFAULT_MAX_RETRIES,
     )
     .await
     .context("Failed to fetch query results")?;
@@ -260,6 +431,7 @@ async fn print_tty(
     schema: Schema,
     mut batches: impl Stream<Item = FlightResult<RecordBatch>> + Unpin,
     truncate: bool,
+    row_limit: Option<u64>,
 ) -> anyhow::Result<()> {
     const TRUNCATE_TO_COLUMN_WIDTH: usize = 32;
 
@@ -287,6 +459,7 @@ async fn print_tty(
     // Track if we truncated any values, so we can print a helpful note at the end.
     let mut truncation_occurred = false;
     let mut header_printed = false;
+    let mut rows = 0;
     let mut tw = TabWriter::new(&mut stdout);
     let mut buf = String::new();
 
@@ -317,6 +490,7 @@ async fn print_tty(
             .map(|col| ArrayFormatter::try_new(col.as_ref(), &options))
             .collect::<Result<_, _>>()?;
 
+        rows += batch.num_rows() as u64;
         for row in 0..batch.num_rows() {
             for (i, formatter) in formatters.iter().enumerate() {
                 if i > 0 {
@@ -347,6 +521,11 @@ async fn print_tty(
 
     if !header_printed {
         eprintln!("No results!");
+    } else if row_limit == Some(rows) {
+        eprintln!("\n({rows} rows; there may be more, use --all-rows to see them)");
+    } else {
+        let s = if rows == 1 { "" } else { "s" };
+        eprintln!("\n({rows} row{s})");
     }
 
     if truncation_occurred {
@@ -356,6 +535,45 @@ async fn print_tty(
     Ok(())
 }
 
+async fn write_file(
+    file: File,
+    format: OutputFileFormat,
+    schema: Schema,
+    batches: impl Stream<Item = FlightResult<RecordBatch>> + Unpin,
+) -> anyhow::Result<u64> {
+    use arrow::json::{ArrayWriter, LineDelimitedWriter};
+    use parquet::arrow::ArrowWriter;
+
+    match format {
+        OutputFileFormat::Csv => {
+            let writer = arrow_csv::WriterBuilder::new()
+                .with_header(true)
+                .build(file);
+            write_batches(writer, batches).await
+        }
+        OutputFileFormat::Json => write_batches(ArrayWriter::new(file), batches).await,
+        OutputFileFormat::JsonLines => write_batches(LineDelimitedWriter::new(file), batches).await,
+        OutputFileFormat::Parquet => {
+            let writer = ArrowWriter::try_new(file, schema.into(), None)?;
+            write_batches(writer, batches).await
+        }
+    }
+}
+
+async fn write_batches(
+    mut writer: impl RecordBatchWriter,
+    mut batches: impl Stream<Item = FlightResult<RecordBatch>> + Unpin,
+) -> anyhow::Result<u64> {
+    let mut rows_written = 0;
+    while let Some(batch) = batches.try_next().await? {
+        rows_written += batch.num_rows() as u64;
+        writer.write(&batch)?;
+    }
+
+    writer.close()?;
+    Ok(rows_written)
+}
+
 async fn print_json(
     mut batches: impl Stream<Item = FlightResult<RecordBatch>> + Unpin,
     job_id: &str,
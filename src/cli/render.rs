@@ -0,0 +1,331 @@
+//! Printing results in the format picked with `--format`.
+
+use std::io::{self, Write};
+
+use bauplan::ApiError;
+use serde::Serialize;
+use tabwriter::TabWriter;
+
+use crate::cli::Output;
+
+/// How to print a result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum Format {
+    /// The default layout
+    Table,
+    /// The default layout, with every field
+    Wide,
+    /// JSON, as printed with `-O json`
+    Json,
+    /// YAML, with the same fields as JSON
+    Yaml,
+}
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct FormatArgs {
+    /// Output format for this command [default: table, or json with -O json]
+    #[arg(long, value_enum)]
+    pub format: Option<Format>,
+}
+
+impl FormatArgs {
+    /// The format to print in, falling back to the global `-O` option.
+    pub(crate) fn resolve(&self, output: Output) -> Format {
+        self.format.unwrap_or(match output {
+            Output::Json => Format::Json,
+            Output::Tty => Format::Table,
+        })
+    }
+}
+
+/// A result that can be printed in any [`Format`]. JSON and YAML come from
+/// the `Serialize` impl, so implementors only have to lay out the table.
+pub(crate) trait Render: Serialize {
+    /// Writes the result as a table, with every field if `wide` is set.
+    fn render_table(&self, w: &mut dyn Write, wide: bool) -> io::Result<()>;
+}
+
+/// Prints a result to stdout.
+pub(crate) fn render<T: Render + ?Sized>(value: &T, format: Format) -> anyhow::Result<()> {
+    write(&mut anstream::stdout(), value, format)
+}
+
+fn write<T: Render + ?Sized>(w: &mut impl Write, value: &T, format: Format) -> anyhow::Result<()> {
+    match format {
+        Format::Json => {
+            serde_json::to_writer(&mut *w, value)?;
+            writeln!(w)?;
+        }
+        Format::Yaml => serde_yaml::to_writer(w, value)?,
+        Format::Table | Format::Wide => {
+            let mut tw = TabWriter::new(w).ansi(true);
+            value.render_table(&mut tw, format == Format::Wide)?;
+            tw.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints a result to stdout as a single line of JSON, for commands that
+/// only have JSON output besides their messages on stderr.
+pub(crate) fn emit_json<T: Serialize + ?Sized>(value: &T) -> anyhow::Result<()> {
+    let mut w = io::stdout().lock();
+    serde_json::to_writer(&mut w, value)?;
+    writeln!(w)?;
+    Ok(())
+}
+
+/// The result of a command that creates, moves or deletes a ref, as printed
+/// with `-O json`.
+#[derive(Debug, Serialize)]
+pub(crate) struct RefChange<T> {
+    /// The ref afterwards, or the one that was deleted. `None` if there was
+    /// nothing to delete.
+    #[serde(rename = "ref")]
+    pub(crate) target: Option<T>,
+    /// Whether anything changed, as opposed to the ref already being in the
+    /// requested state (with `--if-exists` or `--if-not-exists`).
+    pub(crate) changed: bool,
+}
+
+impl<T: Serialize> RefChange<T> {
+    pub(crate) fn changed(target: T) -> Self {
+        Self {
+            target: Some(target),
+            changed: true,
+        }
+    }
+
+    pub(crate) fn unchanged(target: Option<T>) -> Self {
+        Self {
+            target,
+            changed: false,
+        }
+    }
+}
+
+/// Writes a list as a single-line JSON array, printing each item as soon as
+/// it's fetched rather than collecting them first.
+///
+/// If fetching fails partway, the array is closed, so that the output is
+/// still valid JSON with the items fetched so far, and the error is returned
+/// wrapped in a [`PartialOutput`]. If the first item fails, nothing is
+/// written.
+pub(crate) fn write_json_array<T: Serialize>(
+    w: &mut impl Write,
+    items: impl IntoIterator<Item = anyhow::Result<T>>,
+) -> anyhow::Result<()> {
+    let mut items = items.into_iter();
+    let first = match items.next().transpose()? {
+        Some(item) => item,
+        None => {
+            writeln!(w, "[]")?;
+            return Ok(());
+        }
+    };
+
+    w.write_all(b"[")?;
+    serde_json::to_writer(&mut *w, &first)?;
+
+    let mut emitted = 1;
+    for item in items {
+        match item {
+            Ok(item) => {
+                w.write_all(b",")?;
+                serde_json::to_writer(&mut *w, &item)?;
+                emitted += 1;
+            }
+            Err(source) => {
+                writeln!(w, "]")?;
+                w.flush()?;
+                return Err(PartialOutput { emitted, source }.into());
+            }
+        }
+    }
+
+    writeln!(w, "]")?;
+    Ok(())
+}
+
+/// An error after some of a list was already printed.
+#[derive(Debug, thiserror::Error)]
+#[error("failed after printing {emitted} items")]
+pub(crate) struct PartialOutput {
+    pub(crate) emitted: usize,
+    #[source]
+    pub(crate) source: anyhow::Error,
+}
+
+/// An error, as printed with `--json-errors-to-stderr`.
+#[derive(Debug, Serialize)]
+struct JsonError {
+    error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<String>,
+    causes: Vec<String>,
+    /// For a [`PartialOutput`], the number of items printed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    emitted: Option<usize>,
+}
+
+/// Writes an error and its causes as a single line of JSON.
+pub(crate) fn write_json_error(w: &mut impl Write, err: &anyhow::Error) -> io::Result<()> {
+    let emitted = err.downcast_ref::<PartialOutput>().map(|p| p.emitted);
+    let kind = err
+        .chain()
+        .find_map(|e| e.downcast_ref::<ApiError>())
+        .and_then(ApiError::kind);
+
+    // `emitted` already says what a `PartialOutput` would.
+    let mut messages = err
+        .chain()
+        .filter(|e| !e.is::<PartialOutput>())
+        .map(ToString::to_string);
+
+    let json = JsonError {
+        error: messages.next().unwrap_or_default(),
+        kind: kind.map(ToString::to_string),
+        causes: messages.collect(),
+        emitted,
+    };
+
+    serde_json::to_writer(&mut *w, &json)?;
+    writeln!(w)
+}
+
+/// Formats an optional value for a table, with a dash for `None`.
+pub(crate) fn or_dash(value: Option<impl std::fmt::Display>) -> String {
+    value.map_or_else(|| "-".to_owned(), |v| v.to_string())
+}
+
+/// Checks that the JSON and YAML output of `value` parse back into a `T`
+/// that serializes the same way.
+#[cfg(test)]
+pub(crate) fn assert_round_trips<T>(value: &(impl Render + ?Sized))
+where
+    T: serde::de::DeserializeOwned + Serialize,
+{
+    let expected = serde_json::to_value(value).unwrap();
+    for format in [Format::Json, Format::Yaml] {
+        let mut buf = Vec::new();
+        write(&mut buf, value, format).unwrap();
+
+        let parsed: T = match format {
+            Format::Json => serde_json::from_slice(&buf).unwrap(),
+            _ => serde_yaml::from_slice(&buf).unwrap(),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&parsed).unwrap(),
+            expected,
+            "{format:?}"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bauplan::{ApiErrorKind, PaginatedResponse, tag::GetTags, tag::Tag};
+
+    use super::*;
+
+    fn tag(name: &str) -> Tag {
+        Tag {
+            name: name.to_owned(),
+            hash: "abc123".to_owned(),
+        }
+    }
+
+    /// Lists tags two per page, failing on the page after `fail_after`.
+    fn fake_tags(fail_after: usize) -> anyhow::Result<impl Iterator<Item = anyhow::Result<Tag>>> {
+        let req = GetTags {
+            filter_by_name: None,
+        };
+
+        bauplan::paginate(req, None, move |r| {
+            let page: usize = r.pagination_token.map_or(0, |t| t.parse().unwrap());
+            if page >= fail_after {
+                return Err(ApiError::ErrorResponse {
+                    status: http::StatusCode::BAD_REQUEST,
+                    kind: ApiErrorKind::BadRequest {},
+                    message: Some("invalid pagination token".to_owned()),
+                }
+                .into());
+            }
+
+            Ok(PaginatedResponse {
+                page: vec![tag(&format!("t{page}a")), tag(&format!("t{page}b"))],
+                pagination_token: (page < 2).then(|| (page + 1).to_string()),
+            })
+        })
+    }
+
+    #[test]
+    fn json_array_streams() -> anyhow::Result<()> {
+        let mut buf = Vec::new();
+        write_json_array(&mut buf, fake_tags(usize::MAX)?)?;
+
+        let tags: Vec<Tag> = serde_json::from_slice(&buf)?;
+        assert_eq!(tags.len(), 6);
+        assert!(buf.ends_with(b"]\n"));
+        assert_eq!(buf.iter().filter(|&&b| b == b'\n').count(), 1);
+
+        let mut buf = Vec::new();
+        write_json_array(&mut buf, std::iter::empty::<anyhow::Result<Tag>>())?;
+        assert_eq!(buf, b"[]\n");
+        Ok(())
+    }
+
+    #[test]
+    fn json_array_fails_midway() -> anyhow::Result<()> {
+        let mut buf = Vec::new();
+        let err = write_json_array(&mut buf, fake_tags(2)?).unwrap_err();
+
+        // What was fetched is still valid JSON.
+        let tags: Vec<Tag> = serde_json::from_slice(&buf)?;
+        let names: Vec<_> = tags.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["t0a", "t0b", "t1a", "t1b"]);
+
+        let mut stderr = Vec::new();
+        write_json_error(&mut stderr, &err)?;
+        assert_eq!(stderr.iter().filter(|&&b| b == b'\n').count(), 1);
+
+        let json: serde_json::Value = serde_json::from_slice(&stderr)?;
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "error": "BAD_REQUEST: invalid pagination token",
+                "kind": "BAD_REQUEST",
+                "causes": [],
+                "emitted": 4,
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn json_array_fails_first() {
+        let mut buf = Vec::new();
+        let err = write_json_array(&mut buf, [Err::<Tag, _>(anyhow::anyhow!("nope"))]).unwrap_err();
+        assert!(buf.is_empty());
+        assert!(err.downcast_ref::<PartialOutput>().is_none());
+    }
+
+    #[test]
+    fn json_error_causes() -> anyhow::Result<()> {
+        let err = anyhow::anyhow!("connection refused").context("failed to list tables");
+
+        let mut buf = Vec::new();
+        write_json_error(&mut buf, &err)?;
+        let json: serde_json::Value = serde_json::from_slice(&buf)?;
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "error": "failed to list tables",
+                "causes": ["connection refused"],
+            })
+        );
+        Ok(())
+    }
+}
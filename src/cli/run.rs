@@ -1,15 +1,32 @@
 use std::{
-    cell::RefCell, collections::BTreeMap, fmt::Display, io::Write as _, path::PathBuf, sync::Arc,
+    cell::RefCell,
+    collections::BTreeMap,
+    fmt::Display,
+    fs::File,
+    io::{self, IsTerminal as _, Write as _},
+    path::{Path, PathBuf},
+    sync::Arc,
     time,
 };
 
-use anyhow::{Context as _, bail};
+use anyhow::{Context as _, anyhow, bail};
 use bauplan::{
+    ApiRequest as _,
+    branch::GetBranch,
+    events::{
+        EmptyOutputCheck, JobEvent, JobMetrics, LogMessage, OutputStream, TablePreview, Task,
+        TaskMetadata, TaskOutcome, capture::EventRecorder,
+    },
     grpc::{
         self,
+        explain::{self, RunPlan},
         generated::{self as commanderpb, JobResponseCommon},
+        job::{JOB_NAME_ARG, JobState},
+        materialization::{PlannedMaterialization, record_materialization},
     },
+    namespace::{GetNamespaces, NAMESPACE_CHECK_LIMIT, NamespaceCheck, check_namespace},
     project::{ParameterType, ParameterValue, ProjectFile},
+    table::Table,
 };
 use chrono::Utc;
 use futures::TryStreamExt as _;
@@ -17,16 +34,21 @@ use indicatif::{ProgressBar, ProgressDrawTarget};
 use rsa::RsaPublicKey;
 use serde::Serialize;
 use tabwriter::TabWriter;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::cli::{
-    Cli, KeyValue, Priority, on_off,
+    Cli, KeyValue, Output, Priority,
+    branch::{TableDiff, collect_tables},
     color::*,
     format_grpc_status,
-    parameter::{parse_parameter, resolve_project_dir},
+    git::GitMetadata,
+    input::ParamSource,
+    job::LogEntry,
+    on_off,
+    parameter::{ParamArg, parse_parameter, resolve_project_dir},
+    render::emit_json,
     spinner::{self, ProgressExt},
 };
-use commanderpb::runner_event::Event as RunnerEvent;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 pub(crate) enum Preview {
@@ -48,7 +70,7 @@ impl Display for Preview {
     }
 }
 
-#[derive(Debug, clap::Args)]
+#[derive(Debug, Clone, clap::Args)]
 #[command(after_long_help = crate::cli::CliExamples("
   # Run pipeline in current directory
   bauplan run
@@ -64,10 +86,37 @@ impl Display for Preview {
 
   # Run in background
   bauplan run --detach
+
+  # Give the job a name to find it later
+  bauplan run --name nightly-refresh
+
+  # Don't record the git state of the project
+  bauplan run --no-git-metadata
+
+  # Fail if the branch has diverged from main outside the project's models
+  bauplan run --ref username.feature --require-clean-diff
+
+  # Show the planned models, without running them
+  bauplan run --explain
+
+  # Fail, rather than ask, if the namespace doesn't exist on the ref
+  bauplan run --namespace analytics --strict-namespace
+
+  # Fail if any model, or just the trips model, writes no rows
+  bauplan run --fail-on-empty-model
+  bauplan run --fail-on-empty-model trips
+
+  # Cancel the job if it takes longer than 10 minutes
+  bauplan run --job-timeout 10m
+
+  # Keep a log of every task and log message, as JSON lines
+  bauplan run --log-file run.jsonl
 "))]
 pub(crate) struct RunArgs {
-    /// Path to the root Bauplan project directory.
-    #[arg(short, long, default_value = ".")]
+    /// Path to the root Bauplan project directory. With `-`, the nearest
+    /// directory containing bauplan_project.yml, walking up from the current
+    /// directory.
+    #[arg(short, long, default_value = "-")]
     pub project_dir: Option<PathBuf>,
     /// Ref or branch name from which to run the job [default: active branch]
     #[arg(short, long)]
@@ -90,18 +139,62 @@ pub(crate) struct RunArgs {
     /// Dry run the job without materializing any models.
     #[arg(long)]
     pub dry_run: bool,
-    /// Set a parameter for the job. Format: key=value. Can be used multiple times.
+    /// Print the job's plan (its models and their dependencies) without
+    /// running any models. The job is submitted as a dry run, and cancelled
+    /// once it has been planned.
+    #[arg(long, conflicts_with = "detach")]
+    pub explain: bool,
+    /// Set a parameter for the job. Format: key=value, key=@path to read the value from a file, or key=- to read it from stdin. Can be used multiple times.
     #[arg(long, action = clap::ArgAction::Append)]
-    pub param: Vec<KeyValue>,
+    pub param: Vec<ParamArg>,
     /// Run the job in the background instead of streaming logs
     #[arg(short, long)]
     pub detach: bool,
-    /// Extra arguments as key=value pairs (repeatable)
+    /// Extra arguments as key=value pairs, where key=@path reads the value from a file (repeatable)
     #[arg(short, long, action = clap::ArgAction::Append)]
     pub arg: Vec<KeyValue>,
     /// Set the job priority (1-10, where 10 is highest priority)
     #[arg(long)]
     pub priority: Option<Priority>,
+    /// A human-friendly name for the job [default: project name and timestamp]
+    #[arg(long)]
+    pub name: Option<String>,
+    /// Don't record the git commit, branch, and dirty state of the project
+    #[arg(long)]
+    pub no_git_metadata: bool,
+    /// Before running, fail if the ref differs from --against in any table
+    /// other than the project's own models
+    #[arg(long)]
+    pub require_clean_diff: bool,
+    /// The ref to compare against for --require-clean-diff
+    #[arg(long, default_value = "main", requires = "require_clean_diff")]
+    pub against: String,
+    /// Fail, instead of asking for confirmation, if --namespace doesn't
+    /// exist on the ref
+    #[arg(long, requires = "namespace", conflicts_with = "no_preflight")]
+    pub strict_namespace: bool,
+    /// Skip checking that --namespace exists on the ref before running
+    #[arg(long)]
+    pub no_preflight: bool,
+    /// Fail the run if any of the given models, or any model if none are
+    /// given, writes no rows. This turns on previews, which are used to count
+    /// the rows. With transactions on, the job is cancelled as soon as an
+    /// empty model is found, so that nothing is merged; without them, or if
+    /// the job finishes first, the output is still written to the ref.
+    #[arg(long, num_args = 0.., value_name = "MODEL")]
+    pub fail_on_empty_model: Option<Vec<String>>,
+    /// Record the raw events of the job to a file, for debugging. The file
+    /// can be replayed with "bauplan job replay-events"
+    #[arg(long, value_name = "PATH", conflicts_with = "detach")]
+    pub events_out: Option<PathBuf>,
+    /// Write each task and log message of the job to a file as JSON lines,
+    /// ending with the run's summary. With `-`, the lines are written to
+    /// stdout instead of any other output, which requires -O json
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["detach", "explain"])]
+    pub log_file: Option<PathBuf>,
+    /// Cancel the job if it runs for longer than this, like 30s or 10m
+    #[arg(long, value_parser = humantime::parse_duration, conflicts_with = "detach")]
+    pub job_timeout: Option<time::Duration>,
 }
 
 #[derive(Debug, Serialize)]
@@ -114,6 +207,18 @@ enum SummaryOutcome {
     Skipped,
 }
 
+impl From<&TaskOutcome> for SummaryOutcome {
+    fn from(outcome: &TaskOutcome) -> Self {
+        match outcome {
+            TaskOutcome::Success { .. } => Self::Success,
+            TaskOutcome::Failure { .. } => Self::Failed,
+            TaskOutcome::Skipped => Self::Skipped,
+            TaskOutcome::Cancelled { .. } => Self::Cancelled,
+            TaskOutcome::Timeout { .. } => Self::Timeout,
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct TaskSummary {
     task_id: String,
@@ -124,15 +229,183 @@ struct TaskSummary {
     started: chrono::DateTime<Utc>,
     ended: chrono::DateTime<Utc>,
     outcome: SummaryOutcome,
+    /// Set if the task succeeded with output served from the cache.
+    cached: bool,
+    #[serde(skip)]
+    expectation: bool,
 }
 
 #[derive(Debug, Serialize)]
 struct Summary {
     job_id: String,
     outcome: SummaryOutcome,
+    /// Why the run failed, if it failed locally although the job succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
     started: chrono::DateTime<Utc>,
     ended: chrono::DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    git: Option<GitMetadata>,
     tasks: Vec<TaskSummary>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    planned_materializations: Vec<PlannedMaterialization>,
+    /// A `bauplan run` command that runs the same project against the same
+    /// data, quoted for a POSIX shell.
+    repro_command: String,
+    job_url: String,
+}
+
+/// The file written with `--log-file`: a JSON line for each task and log
+/// message of a run, ending with its summary. Each line is flushed as it's
+/// written, so the file can be followed while the job runs.
+///
+/// Failing to write a line is logged, and stops the log; it doesn't fail
+/// the run.
+struct RunLog {
+    path: PathBuf,
+    // `None` once a write fails.
+    out: Option<Box<dyn io::Write>>,
+}
+
+impl RunLog {
+    /// Creates the log at `path`, or writes it to stdout if `path` is `-`.
+    fn create(path: &Path) -> anyhow::Result<Self> {
+        let out: Box<dyn io::Write> = if path == Path::new("-") {
+            Box::new(io::stdout())
+        } else {
+            let file = File::create(path)
+                .with_context(|| format!("failed to create {}", path.display()))?;
+            Box::new(io::BufWriter::new(file))
+        };
+
+        Ok(Self {
+            path: path.to_owned(),
+            out: Some(out),
+        })
+    }
+
+    fn is_stdout(&self) -> bool {
+        self.path == Path::new("-")
+    }
+
+    /// Writes a line for `event`, if it's a task or log message.
+    fn record(&mut self, event: &JobEvent) {
+        let timestamp = |task: &Task| task.timestamp.unwrap_or_else(Utc::now);
+        let record = match event {
+            JobEvent::TaskStarted(task) => LogRecord::TaskStarted {
+                timestamp: timestamp(task),
+                task: LogTask::new(Some(task.id.as_str()), &task.name, &task.metadata),
+            },
+            JobEvent::TaskCompleted(task, outcome) => {
+                let (message, stack_trace) = match outcome {
+                    TaskOutcome::Success { message, .. }
+                    | TaskOutcome::Timeout { message }
+                    | TaskOutcome::Cancelled { reason: message } => (Some(message), None),
+                    TaskOutcome::Failure {
+                        message,
+                        stack_trace,
+                        ..
+                    } => (Some(message), stack_trace.as_ref()),
+                    TaskOutcome::Skipped => (None, None),
+                };
+
+                LogRecord::TaskCompleted {
+                    timestamp: timestamp(task),
+                    task: LogTask::new(Some(task.id.as_str()), &task.name, &task.metadata),
+                    outcome: SummaryOutcome::from(outcome),
+                    cached: matches!(outcome, TaskOutcome::Success { cached: true, .. }),
+                    message: message.filter(|m| !m.is_empty()).map(String::as_str),
+                    stack_trace: stack_trace.map(String::as_str),
+                }
+            }
+            JobEvent::UserLog(log) => {
+                let metadata = &log.task;
+                let name = metadata
+                    .model_name
+                    .as_deref()
+                    .or(metadata.function_name.as_deref())
+                    .unwrap_or(&metadata.description);
+
+                LogRecord::Log {
+                    entry: LogEntry::from(log.clone()),
+                    task: LogTask::new(None, name, metadata),
+                }
+            }
+            _ => return,
+        };
+
+        self.write(&record);
+    }
+
+    fn write(&mut self, record: &LogRecord<'_>) {
+        let Some(out) = &mut self.out else {
+            return;
+        };
+
+        let res = serde_json::to_writer(&mut *out, record)
+            .map_err(io::Error::from)
+            .and_then(|()| writeln!(out))
+            .and_then(|()| out.flush());
+        if let Err(e) = res {
+            warn!(path = %self.path.display(), "failed to write to the log file: {e}");
+            self.out = None;
+        }
+    }
+}
+
+/// A line of the `--log-file` output.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum LogRecord<'a> {
+    TaskStarted {
+        timestamp: chrono::DateTime<Utc>,
+        #[serde(flatten)]
+        task: LogTask<'a>,
+    },
+    TaskCompleted {
+        timestamp: chrono::DateTime<Utc>,
+        #[serde(flatten)]
+        task: LogTask<'a>,
+        outcome: SummaryOutcome,
+        cached: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message: Option<&'a str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        stack_trace: Option<&'a str>,
+    },
+    Log {
+        #[serde(flatten)]
+        entry: LogEntry,
+        #[serde(flatten)]
+        task: LogTask<'a>,
+    },
+    Summary(&'a Summary),
+}
+
+/// The task a line of the `--log-file` output is about.
+#[derive(Debug, Serialize)]
+struct LogTask<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task_id: Option<&'a str>,
+    name: &'a str,
+    /// False for tasks internal to the system.
+    dag: bool,
+    model_name: Option<&'a str>,
+    file_name: Option<&'a str>,
+    line_number: Option<u32>,
+}
+
+impl<'a> LogTask<'a> {
+    fn new(task_id: Option<&'a str>, name: &'a str, metadata: &'a TaskMetadata) -> Self {
+        Self {
+            task_id,
+            name,
+            dag: metadata.is_dag(),
+            model_name: metadata.model_name.as_deref(),
+            file_name: metadata.file_name.as_deref(),
+            line_number: metadata.line_number,
+        }
+    }
 }
 
 pub(crate) fn handle(cli: &Cli, args: RunArgs) -> anyhow::Result<()> {
@@ -143,42 +416,220 @@ pub(crate) fn job_request_common(
     cli: &Cli,
     args: Vec<KeyValue>,
     priority: Option<Priority>,
-) -> commanderpb::JobRequestCommon {
+    job_name: Option<String>,
+) -> anyhow::Result<commanderpb::JobRequestCommon> {
     let hostname = gethostname::gethostname().to_string_lossy().into_owned();
 
     let mut merged_args = cli.profile.args.clone();
-    merged_args.extend(args.into_iter().map(|kv| kv.into_strings()));
+    merged_args.extend(KeyValue::read_all(&args)?);
+    if let Some(name) = job_name {
+        merged_args.insert(JOB_NAME_ARG.to_owned(), name);
+    }
 
-    commanderpb::JobRequestCommon {
+    Ok(commanderpb::JobRequestCommon {
         module_version: env!("CARGO_PKG_VERSION").to_owned(),
         hostname,
         args: merged_args,
         debug: 0,
-        priority: priority.map(|p| p.0 as _),
+        priority: priority.map(|p| p.get() as _),
+    })
+}
+
+/// The name given to a run when none is passed explicitly: the project name
+/// plus a timestamp. Returns None if the profile has auto-naming disabled or
+/// the project has no name.
+fn default_job_name(cli: &Cli, project: &ProjectFile) -> Option<String> {
+    if !cli.profile.auto_job_name() {
+        return None;
+    }
+
+    let name = project.project.name.as_deref()?;
+    Some(format!("{name}-{}", Utc::now().format("%Y%m%d-%H%M%S")))
+}
+
+fn job_url(job_id: &str) -> String {
+    format!("https://app.bauplanlabs.com/jobs/{job_id}")
+}
+
+/// Pins a branch to its current head, like `main@abc123`. Other refs, and
+/// branches whose head can't be fetched, are returned as they are.
+fn pin_ref(cli: &Cli, r: &str) -> String {
+    if r.contains('@') {
+        return r.to_owned();
+    }
+
+    let req = GetBranch {
+        name: r,
+        if_none_match: None,
+    };
+
+    match cli.roundtrip(req) {
+        Ok(branch) => format!("{}@{}", branch.name, branch.hash),
+        Err(e) => {
+            debug!(branch = r, error = %e, "failed to pin ref to its head");
+            r.to_owned()
+        }
+    }
+}
+
+/// The arguments for a `bauplan run` that repeats a run with `args`, with the
+/// project dir and ref replaced by their resolved values. Flags that don't
+/// change what runs, like `--detach`, are left out, and the values of secret
+/// parameters are redacted.
+fn repro_args(
+    args: &RunArgs,
+    project: &ProjectFile,
+    project_dir: &Path,
+    r#ref: &str,
+) -> Vec<String> {
+    let mut out: Vec<String> = vec!["bauplan".into(), "run".into()];
+    let mut push = |flag: &str, value: Option<String>| {
+        out.push(flag.to_owned());
+        out.extend(value);
+    };
+
+    push(
+        "--project-dir",
+        Some(project_dir.to_string_lossy().into_owned()),
+    );
+    push("--ref", Some(r#ref.to_owned()));
+    if let Some(namespace) = &args.namespace {
+        push("--namespace", Some(namespace.clone()));
+    }
+
+    for param in &args.param {
+        let secret = project
+            .parameters
+            .get(&param.name)
+            .is_some_and(|p| p.param_type == ParameterType::Secret);
+        if secret && matches!(param.source, ParamSource::Value(_)) {
+            push("--param", Some(format!("{}=***********", param.name)));
+        } else {
+            push("--param", Some(param.to_arg()));
+        }
+    }
+
+    for arg in &args.arg {
+        push("--arg", Some(arg.to_arg()));
+    }
+
+    if args.no_cache {
+        push("--no-cache", None);
+    }
+    if args.preview != Preview::default() {
+        push("--preview", Some(args.preview.to_string()));
+    }
+    if args.strict {
+        push("--strict", None);
+    }
+    if args.no_transaction {
+        push("--no-transaction", None);
+    }
+    if args.dry_run {
+        push("--dry-run", None);
+    }
+    if let Some(priority) = args.priority {
+        push("--priority", Some(priority.to_string()));
+    }
+    if let Some(name) = &args.name {
+        push("--name", Some(name.clone()));
+    }
+    if args.no_git_metadata {
+        push("--no-git-metadata", None);
+    }
+    if args.require_clean_diff {
+        push("--require-clean-diff", None);
+        push("--against", Some(args.against.clone()));
+    }
+    if args.strict_namespace {
+        push("--strict-namespace", None);
+    }
+    if args.no_preflight {
+        push("--no-preflight", None);
+    }
+    if let Some(models) = &args.fail_on_empty_model {
+        push("--fail-on-empty-model", None);
+        out.extend(models.iter().cloned());
+    }
+
+    out
+}
+
+/// Quotes a command for a POSIX shell. Windows shells quote differently, so
+/// the command may need adjusting there. Nul bytes, which can't be passed in
+/// an argument anyway, are dropped.
+fn shell_command(args: &[String]) -> String {
+    let args: Vec<String> = args.iter().map(|a| a.replace('\0', "")).collect();
+    shlex::try_join(args.iter().map(String::as_str)).expect("nul bytes were removed")
+}
+
+/// Creates the file for `--events-out`, if it was given.
+pub(crate) fn create_event_recorder(path: Option<&Path>) -> anyhow::Result<Option<EventRecorder>> {
+    path.map(|path| {
+        EventRecorder::create(path).with_context(|| format!("failed to create {}", path.display()))
+    })
+    .transpose()
+}
+
+/// A job's ID and status, as printed with `-O json` by the commands that
+/// start or stop a job, other than `run`. The status is `Running` if the job
+/// was detached, `Complete` once it has finished, and `Abort` once it was
+/// cancelled; a failed job is reported as an error instead.
+#[derive(Debug, Serialize)]
+pub(crate) struct JobResult<T = ()> {
+    pub(crate) job_id: String,
+    pub(crate) status: JobState,
+    /// Anything else the command reports about the job.
+    #[serde(flatten)]
+    pub(crate) details: T,
+}
+
+impl JobResult {
+    pub(crate) fn detached(job_id: String) -> Self {
+        Self {
+            job_id,
+            status: JobState::Running,
+            details: (),
+        }
+    }
+}
+
+impl<T> JobResult<T> {
+    pub(crate) fn complete(job_id: String, details: T) -> Self {
+        Self {
+            job_id,
+            status: JobState::Complete,
+            details,
+        }
     }
 }
 
 /// Runs a job and manages spinners for it. This handles the following common
 /// behavior:
-///  - Cancelling a job on a cancel signal or a request timeout
+///  - Cancelling a job on a cancel signal, a request timeout, or once
+///    `job_timeout` has passed
 ///  - Monitoring job logs until a JobCompletion event is received.
 ///
 /// `thing` influences the format of the spinner message ("Running {thing}...").
 ///
-/// The provided closure is called on every event except the final JobCompletion.
+/// The provided closure is called on every event except the final Completion.
+/// The raw events are also recorded to `events`, if given.
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn monitor_job_progress(
     cli: &Cli,
     client: &mut grpc::Client,
     job_id: String,
     thing: &'static str,
     progress: ProgressBar,
+    events: Option<&EventRecorder>,
+    job_timeout: Option<time::Duration>,
     mut cancel_signal: impl Future + Unpin,
-    mut handler: impl FnMut(RunnerEvent),
-) -> anyhow::Result<commanderpb::JobSuccess> {
+    mut handler: impl FnMut(JobEvent),
+) -> anyhow::Result<JobMetrics> {
     info!(job_id, "started {thing}");
 
     let mut client_clone = client.clone();
-    let mut kill_job = async |reason: &str| -> anyhow::Result<commanderpb::JobSuccess> {
+    let mut kill_job = async |reason: &str, err: anyhow::Error| -> anyhow::Result<JobMetrics> {
         error!(job_id, "{reason}, cancelling {thing}");
 
         progress.set_message(format!("Cancelling {thing}..."));
@@ -199,7 +650,13 @@ pub(crate) async fn monitor_job_progress(
             progress.finish_with_done();
         }
 
-        Err(grpc::JobError::Cancelled.into())
+        Err(err)
+    };
+
+    let timed_out = || {
+        let timeout = humantime::format_duration(job_timeout.unwrap_or_default());
+        anyhow::Error::from(grpc::JobError::Timeout)
+            .context(format!("{thing} exceeded --job-timeout of {timeout}"))
     };
 
     // We have to manually tick the progress bar here, or we get ghosting.
@@ -214,15 +671,25 @@ pub(crate) async fn monitor_job_progress(
     // we need to set the timeout again here, because the channel timeout
     // only affects the stream establishment (and not the duration of the
     // stream).
-    if let Some(timeout) = cli.timeout {
+    if let Some(timeout) = job_timeout.or(cli.timeout) {
         monitor_req.set_timeout(timeout);
     }
 
+    // The deadline is enforced by the server, so this is a backstop in case
+    // the stream outlives it.
+    let deadline = async {
+        match job_timeout {
+            Some(timeout) => tokio::time::sleep(timeout).await,
+            None => futures::future::pending().await,
+        }
+    };
+    futures::pin_mut!(deadline);
+
     let endpoint = std::sync::Arc::new(tokio::sync::OnceCell::new());
-    let stream = client.monitor_job(monitor_req, Arc::clone(&endpoint));
+    let stream = client.monitor_job_recorded(monitor_req, Arc::clone(&endpoint), events.cloned());
     futures::pin_mut!(stream);
 
-    let mut outcome = None;
+    let mut completion = None;
     loop {
         let res = tokio::select! {
             v = stream.try_next() => v,
@@ -230,7 +697,10 @@ pub(crate) async fn monitor_job_progress(
                 progress.tick();
                 continue;
             }
-            _ = &mut cancel_signal => return kill_job("interrupt received").await,
+            _ = &mut cancel_signal => {
+                return kill_job("interrupt received", grpc::JobError::Cancelled.into()).await;
+            }
+            _ = &mut deadline => return kill_job("job timeout exceeded", timed_out()).await,
         };
 
         let event = match res {
@@ -244,26 +714,30 @@ pub(crate) async fn monitor_job_progress(
                     ep.close().await;
                 }
 
-                return kill_job("execution timed out").await;
+                if job_timeout.is_some() {
+                    return kill_job("job timeout exceeded", timed_out()).await;
+                }
+
+                return kill_job("execution timed out", grpc::JobError::Cancelled.into()).await;
             }
             Err(e) => return Err(e.into()),
         };
 
         match event {
-            RunnerEvent::RuntimeUserLog(ref ev) => {
+            JobEvent::UserLog(ref log) => {
                 debug!(
-                    job_id = ev.job_id,
-                    level = ?ev.level(),
-                    output_stream = ?ev.output_stream(),
-                    r#type = ?ev.r#type(),
-                    msg = ev.msg,
+                    job_id = log.job_id,
+                    level = ?log.level,
+                    output_stream = ?log.stream,
+                    user = log.user,
+                    msg = log.message,
                     "runtime log event"
                 );
 
                 handler(event);
             }
-            RunnerEvent::JobCompletion(ev) => {
-                outcome = ev.outcome;
+            JobEvent::Completion(ev) => {
+                completion = Some(ev);
             }
             _ => handler(event),
         }
@@ -273,7 +747,12 @@ pub(crate) async fn monitor_job_progress(
         ep.close().await;
     }
 
-    Ok(grpc::interpret_outcome(outcome)?)
+    let Some(completion) = completion else {
+        return Err(grpc::JobError::Unknown.into());
+    };
+
+    completion.result?;
+    Ok(completion.metrics)
 }
 
 async fn handle_run(cli: &Cli, args: RunArgs) -> anyhow::Result<()> {
@@ -286,15 +765,39 @@ async fn handle_run(cli: &Cli, args: RunArgs) -> anyhow::Result<()> {
         strict,
         no_transaction,
         dry_run,
+        explain,
         param,
         detach,
         arg,
         priority,
-    } = args;
+        name,
+        no_git_metadata,
+        require_clean_diff,
+        against,
+        strict_namespace,
+        no_preflight,
+        fail_on_empty_model,
+        events_out,
+        log_file,
+        job_timeout,
+    } = args.clone();
+
+    let mut run_log = log_file.as_deref().map(RunLog::create).transpose()?;
+    let log_to_stdout = run_log.as_ref().is_some_and(RunLog::is_stdout);
+    if log_to_stdout {
+        if cli.global.output != Output::Json {
+            bail!("--log-file - writes to stdout, so it requires -O json");
+        }
+
+        // The log replaces the human output.
+        cli.multiprogress
+            .set_draw_target(ProgressDrawTarget::hidden());
+    }
 
     let start = Utc::now();
     let timeout = cli.timeout.unwrap_or(time::Duration::from_secs(1800));
     let mut client = grpc::Client::new_lazy(&cli.profile, timeout)?;
+    let events = create_event_recorder(events_out.as_deref())?;
 
     let project_dir = resolve_project_dir(project_dir.as_deref())?;
     let project = ProjectFile::from_dir(&project_dir)?;
@@ -304,15 +807,80 @@ async fn handle_run(cli: &Cli, args: RunArgs) -> anyhow::Result<()> {
         .context("failed to resolve parameters")?;
     let zip_file = project.create_code_snapshot()?;
 
-    let job_request_common = job_request_common(cli, arg, priority);
+    let job_name = name.or_else(|| default_job_name(cli, &project));
+    let mut job_request_common = job_request_common(cli, arg, priority, job_name)?;
+
+    let git = if no_git_metadata {
+        None
+    } else {
+        GitMetadata::detect(&project_dir)
+    };
+
+    if let Some(git) = &git {
+        debug!(
+            commit = git.commit.as_str(),
+            branch = git.branch.as_deref(),
+            dirty = git.dirty,
+            "detected git metadata"
+        );
+
+        // Explicit --arg values take precedence.
+        for (k, v) in git.to_args() {
+            job_request_common.args.entry(k).or_insert(v);
+        }
+    }
 
-    let dry_run = if dry_run {
+    let dry_run = if dry_run || explain {
         commanderpb::JobRequestOptionalBool::True as _
     } else {
         commanderpb::JobRequestOptionalBool::False as _
     };
 
-    let r#ref = r#ref.or_else(|| cli.profile.active_branch.clone());
+    let r#ref = r#ref
+        .map(|r| cli.resolve_ref(&r))
+        .transpose()?
+        .or_else(|| cli.profile.active_branch.clone());
+    let namespace = namespace.or_else(|| cli.profile.default_namespace.clone());
+
+    // Only a run that materializes its models writes to the ref.
+    if !args.dry_run
+        && !explain
+        && let Some(r#ref) = &r#ref
+    {
+        cli.check_write_branch(r#ref)?;
+    }
+
+    if require_clean_diff {
+        let against = cli.resolve_ref(&against)?;
+        check_clean_diff(cli, &project, r#ref.as_deref().unwrap_or("main"), &against)?;
+    }
+
+    if let Some(namespace) = &namespace
+        && !no_preflight
+    {
+        preflight_namespace(
+            cli,
+            r#ref.as_deref().unwrap_or("main"),
+            namespace,
+            strict_namespace,
+        )?;
+    }
+
+    // Pin the ref before the job moves it, so that the run can be reproduced
+    // against the same data.
+    let repro_command = if explain || detach {
+        String::new()
+    } else {
+        let pinned = pin_ref(cli, r#ref.as_deref().unwrap_or("main"));
+        shell_command(&repro_args(&args, &project, &project_dir, &pinned))
+    };
+
+    // The empty model check counts the rows in the previews.
+    let requested_preview = if fail_on_empty_model.is_some() && preview == Preview::Off {
+        Preview::Head
+    } else {
+        preview
+    };
 
     let req = commanderpb::CodeSnapshotRunRequest {
         job_request_common: Some(job_request_common),
@@ -323,7 +891,7 @@ async fn handle_run(cli: &Cli, args: RunArgs) -> anyhow::Result<()> {
         transaction: on_off(!no_transaction),
         strict: on_off(strict),
         cache: on_off(!no_cache),
-        preview: preview.to_string(),
+        preview: requested_preview.to_string(),
         project_id: project.project.id.as_hyphenated().to_string(),
         project_name: project.project.name.clone().unwrap_or_default(),
         parameters,
@@ -343,15 +911,23 @@ async fn handle_run(cli: &Cli, args: RunArgs) -> anyhow::Result<()> {
         bail!("response missing job ID");
     };
 
-    if !resp.dag_ascii.is_empty() {
+    if !resp.dag_ascii.is_empty() && !log_to_stdout {
         cli.multiprogress
             .suspend(|| print_dag(&job_id, resp.dag_ascii))?
     }
 
+    if explain {
+        return explain_job(cli, &mut client, job_id, progress).await;
+    }
+
     if detach {
         progress.finish_with_done();
         eprintln!("\nJob {job_id} is now running in detached mode.\n");
         eprintln!("Tip: use \"bauplan job <command>\" to list and inspect running jobs.");
+        if cli.global.output == Output::Json {
+            emit_json(&JobResult::detached(job_id))?;
+        }
+
         return Ok(());
     }
 
@@ -363,15 +939,28 @@ async fn handle_run(cli: &Cli, args: RunArgs) -> anyhow::Result<()> {
     // One spinner for each task.
     let spinners: RefCell<BTreeMap<String, ProgressBar>> = RefCell::new(BTreeMap::new());
 
-    let show_previews = resp.preview != "off";
+    let show_previews = preview != Preview::Off && resp.preview != "off" && !log_to_stdout;
+
+    // If a model is empty, the job is cancelled before its transaction can be
+    // merged. Without a transaction, the model's output is already written.
+    let mut empty_check = fail_on_empty_model.map(EmptyOutputCheck::new);
+    let cancel_on_empty = !no_transaction && !resp.dry_run;
+    let found_empty = tokio::sync::Notify::new();
+    let found_empty_signal = found_empty.notified();
+    futures::pin_mut!(found_empty_signal);
 
     // All events, collated for json output.
     let mut summary = Summary {
         job_id: job_id.clone(),
         outcome: SummaryOutcome::Success,
+        reason: None,
         started: start,
         ended: start,
+        git,
         tasks: Vec::new(),
+        planned_materializations: Vec::new(),
+        repro_command,
+        job_url: job_url(&job_id),
     };
 
     let outcome = monitor_job_progress(
@@ -380,124 +969,96 @@ async fn handle_run(cli: &Cli, args: RunArgs) -> anyhow::Result<()> {
         job_id,
         "job",
         progress.clone(),
-        &mut ctrl_c,
-        |event| match event {
-            RunnerEvent::TaskStart(ev) => {
-                let Some(metadata) = ev.task_metadata else {
-                    return;
-                };
-
-                add_task(
-                    cli,
-                    &spinners,
-                    &mut summary,
-                    ev.task_id,
-                    ev.task_name,
-                    metadata,
-                );
+        events.as_ref(),
+        job_timeout,
+        futures::future::select(&mut ctrl_c, found_empty_signal),
+        |event| {
+            if let Some(check) = &mut empty_check
+                && let Some(model) = check.observe(&event)
+            {
+                info!(model, "model output is empty");
+                if cancel_on_empty {
+                    found_empty.notify_one();
+                }
             }
-            RunnerEvent::TaskCompletion(ev) => {
-                use commanderpb::task_complete_event::Outcome;
-                let Some(outcome) = ev.outcome else {
-                    return;
-                };
 
-                let Some(metadata) = ev.task_metadata else {
-                    return;
-                };
+            if let Some(log) = &mut run_log {
+                log.record(&event);
+            }
 
-                // Register the task, just in case we didn't get a TaskStarted
-                // event for it (this happens for skipped tasks, for example).
-                add_task(
-                    cli,
-                    &spinners,
-                    &mut summary,
-                    ev.task_id.clone(),
-                    ev.task_name,
-                    metadata,
+            if let JobEvent::TaskStarted(task) | JobEvent::TaskCompleted(task, _) = &event {
+                record_materialization(
+                    &mut summary.planned_materializations,
+                    &task.metadata,
+                    &resp.namespace,
+                    &resp.r#ref,
                 );
-
-                // Finish the task spinner.
-                if let Some(task_spinner) = spinners.borrow().get(ev.task_id.as_str()) {
-                    let status = match &outcome {
-                        Outcome::Success(_) => spinner::DONE,
-                        Outcome::Failure(f) if !f.is_fatal => spinner::FAILED_WARN,
-                        Outcome::Failure(_) => spinner::FAILED,
-                        Outcome::Cancel(_) => spinner::CANCELLED,
-                        Outcome::Timeout(_) => spinner::TIMEOUT,
-                        Outcome::Skipped(_) => spinner::SKIPPED,
-                    };
-
-                    task_spinner.finish_with_status(status);
-                }
-
-                // Print a preview(s), if relevant.
-                if show_previews
-                    && let Outcome::Success(success) = &outcome
-                    && !success.runtime_table_preview.is_empty()
-                {
-                    for preview in &success.runtime_table_preview {
-                        cli.multiprogress
-                            .suspend(|| print_preview(preview).unwrap());
-                    }
-                }
-
-                // Update the JSON summary.
-                if let Some(task_summary) =
-                    summary.tasks.iter_mut().find(|ts| ts.task_id == ev.task_id)
-                {
-                    task_summary.outcome = match outcome {
-                        Outcome::Success(_) => SummaryOutcome::Success,
-                        Outcome::Failure(_) => SummaryOutcome::Failed,
-                        Outcome::Skipped(_) => SummaryOutcome::Skipped,
-                        Outcome::Cancel(_) => SummaryOutcome::Cancelled,
-                        Outcome::Timeout(_) => SummaryOutcome::Timeout,
-                    };
-                    task_summary.ended = Utc::now();
-                }
             }
-            RunnerEvent::RuntimeUserLog(ev)
-                if ev.r#type() == commanderpb::runtime_log_event::LogType::User =>
-            {
-                let stream = ev.output_stream();
-                let Some(metadata) = ev.task_metadata else {
-                    return;
-                };
-
-                if metadata.level() != commanderpb::task_metadata::TaskLevel::Dag {
-                    return;
-                }
 
-                cli.multiprogress
-                    .suspend(|| print_user_log(&ev.msg, stream, metadata));
-            }
-            _ => (),
+            render_event(
+                cli,
+                &spinners,
+                &mut summary,
+                show_previews,
+                !log_to_stdout,
+                event,
+            );
         },
     )
     .await;
 
+    // Empty models fail the run if the job succeeded, or if it was cancelled
+    // because of them.
+    let empty_reason = empty_check
+        .as_ref()
+        .and_then(EmptyOutputCheck::failure_reason)
+        .filter(|_| match &outcome {
+            Ok(_) => true,
+            Err(e) => matches!(
+                e.downcast_ref::<grpc::JobError>(),
+                Some(grpc::JobError::Cancelled)
+            ),
+        });
+
     summary.ended = Utc::now();
-    let res = match outcome {
-        Ok(_) => {
-            summary.outcome = SummaryOutcome::Success;
-            progress.finish_with_done();
-            Ok(())
+    let res = if let Some(reason) = empty_reason {
+        summary.outcome = SummaryOutcome::Failed;
+        summary.reason = Some(reason.clone());
+        progress.finish_with_failed();
+
+        if outcome.is_ok() && !resp.dry_run {
+            Err(anyhow!(
+                "{reason}; the job succeeded, so its output was still written to {:?}",
+                resp.r#ref
+            ))
+        } else {
+            Err(anyhow!(reason))
         }
-        Err(e) => {
-            if let Some(job_err) = e.downcast_ref::<grpc::JobError>() {
-                let (outcome, status) = match job_err {
-                    grpc::JobError::Cancelled => (SummaryOutcome::Cancelled, spinner::CANCELLED),
-                    grpc::JobError::Rejected(_) => (SummaryOutcome::Skipped, spinner::SKIPPED),
-                    grpc::JobError::Timeout => (SummaryOutcome::Timeout, spinner::TIMEOUT),
-                    _ => (SummaryOutcome::Failed, spinner::FAILED),
-                };
+    } else {
+        match outcome {
+            Ok(_) => {
+                summary.outcome = SummaryOutcome::Success;
+                progress.finish_with_done();
+                Ok(())
+            }
+            Err(e) => {
+                if let Some(job_err) = e.downcast_ref::<grpc::JobError>() {
+                    let (outcome, status) = match job_err {
+                        grpc::JobError::Cancelled => {
+                            (SummaryOutcome::Cancelled, spinner::CANCELLED)
+                        }
+                        grpc::JobError::Rejected(_) => (SummaryOutcome::Skipped, spinner::SKIPPED),
+                        grpc::JobError::Timeout => (SummaryOutcome::Timeout, spinner::TIMEOUT),
+                        _ => (SummaryOutcome::Failed, spinner::FAILED),
+                    };
 
-                summary.outcome = outcome;
-                progress.finish_with_status(status);
-                Err(e)
-            } else {
-                // Exit now.
-                return Err(e);
+                    summary.outcome = outcome;
+                    progress.finish_with_status(status);
+                    Err(e)
+                } else {
+                    // Exit now.
+                    return Err(e);
+                }
             }
         }
     };
@@ -508,29 +1069,394 @@ async fn handle_run(cli: &Cli, args: RunArgs) -> anyhow::Result<()> {
         }
     }
 
-    if cli.global.output == crate::cli::Output::Json {
+    if cli.global.output == Output::Tty {
+        print_task_counts(&summary.tasks);
+    }
+
+    if resp.dry_run && cli.global.output == Output::Tty {
+        print_planned_materializations(&summary.planned_materializations)?;
+    }
+
+    if cli.global.output == Output::Tty {
+        print_repro(&summary)?;
+    }
+
+    // The summary is the last line of the log, so that it's self-contained.
+    if let Some(log) = &mut run_log {
+        log.write(&LogRecord::Summary(&summary));
+    }
+
+    if cli.global.output == Output::Json && !log_to_stdout {
         // Redirect any further writes to stderr, so that they don't get
         // interleaved with the json to stdout.
         cli.multiprogress
             .set_draw_target(ProgressDrawTarget::hidden());
 
-        let mut out = std::io::stdout().lock();
-        serde_json::to_writer(&mut out, &summary)?;
-        writeln!(&mut out)?;
+        emit_json(&summary)?;
     }
 
     res
 }
 
+/// Renders an event of a run: a spinner for each task, and any previews and
+/// logs from user code. Tasks are also added to the summary.
+fn render_event(
+    cli: &Cli,
+    spinners: &RefCell<BTreeMap<String, ProgressBar>>,
+    summary: &mut Summary,
+    show_previews: bool,
+    show_logs: bool,
+    event: JobEvent,
+) {
+    match event {
+        JobEvent::TaskStarted(task) => {
+            add_task(cli, &spinners, &mut summary, task);
+        }
+        JobEvent::TaskCompleted(task, outcome) => {
+            // Register the task, just in case we didn't get a TaskStarted
+            // event for it (this happens for skipped tasks, for example).
+            let task_id = task.id.clone();
+            add_task(cli, &spinners, &mut summary, task);
+
+            // Finish the task spinner.
+            if let Some(task_spinner) = spinners.borrow().get(task_id.as_str()) {
+                let status = match &outcome {
+                    TaskOutcome::Success { cached: true, .. } => spinner::CACHED,
+                    TaskOutcome::Success { .. } => spinner::DONE,
+                    TaskOutcome::Failure { fatal: false, .. } => spinner::FAILED_WARN,
+                    TaskOutcome::Failure { .. } => spinner::FAILED,
+                    TaskOutcome::Cancelled { .. } => spinner::CANCELLED,
+                    TaskOutcome::Timeout { .. } => spinner::TIMEOUT,
+                    TaskOutcome::Skipped => spinner::SKIPPED,
+                };
+
+                task_spinner.finish_with_status(status);
+            }
+
+            // Update the JSON summary.
+            if let Some(task_summary) = summary.tasks.iter_mut().find(|ts| ts.task_id == task_id) {
+                task_summary.cached = matches!(outcome, TaskOutcome::Success { cached: true, .. });
+                task_summary.outcome = SummaryOutcome::from(&outcome);
+                task_summary.ended = Utc::now();
+            }
+        }
+        // Print previews, if relevant. These follow the completion of the
+        // task that produced them.
+        JobEvent::Preview(preview) if show_previews => {
+            cli.multiprogress
+                .suspend(|| print_preview(&preview).unwrap());
+        }
+        JobEvent::UserLog(log) if show_logs && log.user && log.task.is_dag() => {
+            cli.multiprogress.suspend(|| print_user_log(&log));
+        }
+        _ => (),
+    }
+}
+
+/// Replays a job from its recorded events, rendering its progress (and with
+/// `-O json`, its summary) the way `bauplan run` would have.
+pub(crate) fn replay_job(cli: &Cli, job_id: &str, events: Vec<JobEvent>) -> anyhow::Result<()> {
+    let progress = cli
+        .new_spinner()
+        .with_message(format!("Replaying job {job_id}..."));
+    let spinners = RefCell::new(BTreeMap::new());
+
+    let now = Utc::now();
+    let mut summary = Summary {
+        job_id: job_id.to_owned(),
+        outcome: SummaryOutcome::Success,
+        reason: None,
+        started: now,
+        ended: now,
+        git: None,
+        tasks: Vec::new(),
+        planned_materializations: Vec::new(),
+        repro_command: String::new(),
+        job_url: job_url(job_id),
+    };
+
+    let mut completion = None;
+    for event in events {
+        match event {
+            JobEvent::Completion(c) => completion = Some(c),
+            event => render_event(cli, &spinners, &mut summary, true, true, event),
+        }
+    }
+
+    for sp in spinners.borrow().values() {
+        if !sp.is_finished() {
+            sp.finish_with_status(spinner::CANCELLED);
+        }
+    }
+
+    let failure = match completion.map(|c| c.result) {
+        Some(Ok(_)) => {
+            progress.finish_with_done();
+            None
+        }
+        Some(Err(e)) => {
+            progress.finish_with_failed();
+            summary.outcome = SummaryOutcome::Failed;
+            Some(format!("Job {job_id} failed: {e}"))
+        }
+        None => {
+            progress.finish_with_status(spinner::CANCELLED);
+            summary.outcome = SummaryOutcome::Cancelled;
+            Some(format!("The capture ended before job {job_id} completed."))
+        }
+    };
+
+    match cli.global.output {
+        Output::Json => {
+            summary.reason = failure.clone();
+            emit_json(&summary)?;
+        }
+        Output::Tty => print_task_counts(&summary.tasks),
+    }
+
+    if let Some(failure) = failure {
+        eprintln!("{failure}");
+    }
+
+    Ok(())
+}
+
+/// Waits for a dry run to be planned, cancels it, and prints its plan: the
+/// models it would run, read from the job's context.
+async fn explain_job(
+    cli: &Cli,
+    client: &mut grpc::Client,
+    job_id: String,
+    progress: ProgressBar,
+) -> anyhow::Result<()> {
+    let mut ticker = tokio::time::interval(time::Duration::from_millis(100));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    let mut monitor_req = cli.traced(commanderpb::SubscribeLogsRequest {
+        job_id: job_id.clone(),
+    });
+
+    if let Some(timeout) = cli.timeout {
+        monitor_req.set_timeout(timeout);
+    }
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    futures::pin_mut!(ctrl_c);
+
+    let endpoint = Arc::new(tokio::sync::OnceCell::new());
+    let res = {
+        let wait =
+            explain::wait_for_planning(client.monitor_job(monitor_req, Arc::clone(&endpoint)));
+        futures::pin_mut!(wait);
+
+        loop {
+            tokio::select! {
+                res = &mut wait => break Some(res),
+                _ = ticker.tick() => progress.tick(),
+                _ = &mut ctrl_c => break None,
+            }
+        }
+    };
+
+    if let Some(ep) = endpoint.get() {
+        ep.close().await;
+    }
+
+    let cancel_req = commanderpb::CancelJobRequest {
+        job_id: Some(commanderpb::JobId {
+            id: job_id.clone(),
+            ..Default::default()
+        }),
+    };
+
+    // This fails if the job already finished, which is fine.
+    if let Err(e) = client.cancel(cli.traced(cancel_req)).await {
+        debug!(job_id, error = %e, "failed to cancel job after planning");
+    }
+
+    let plan = match res {
+        Some(Ok(())) => fetch_run_plan(cli, client, job_id).await,
+        Some(Err(e)) => Err(e),
+        None => {
+            progress.finish_with_status(spinner::CANCELLED);
+            return Err(grpc::JobError::Cancelled.into());
+        }
+    };
+
+    let plan = match plan {
+        Ok(plan) => plan,
+        Err(e) => {
+            progress.finish_with_failed();
+            return Err(match e {
+                explain::ExplainError::Status(status) => format_grpc_status(status),
+                e => e.into(),
+            });
+        }
+    };
+
+    progress.finish_with_done();
+    cli.multiprogress
+        .set_draw_target(ProgressDrawTarget::hidden());
+
+    match cli.global.output {
+        Output::Json => emit_json(&plan)?,
+        Output::Tty => print_run_plan(&plan)?,
+    }
+
+    Ok(())
+}
+
+async fn fetch_run_plan(
+    cli: &Cli,
+    client: &mut grpc::Client,
+    job_id: String,
+) -> Result<RunPlan, explain::ExplainError> {
+    let req = cli.traced(RunPlan::context_request(&job_id));
+    let contexts = client.get_job_context(req).await?.into_inner();
+    RunPlan::from_contexts(job_id, contexts.job_contexts)
+}
+
+fn print_run_plan(plan: &RunPlan) -> anyhow::Result<()> {
+    let mut tw = TabWriter::new(anstream::stdout()).ansi(true);
+    writeln!(tw, "MODEL\tDEPENDS ON")?;
+
+    for model in &plan.models {
+        let depends_on = plan.dependency_names(model);
+        let depends_on = if depends_on.is_empty() {
+            "-".to_owned()
+        } else {
+            depends_on.join(", ")
+        };
+
+        writeln!(tw, "{}\t{depends_on}", model.model_name)?;
+    }
+
+    tw.flush()?;
+    Ok(())
+}
+
+/// Checks that `namespace` exists on `run_ref`, so that a typo fails before
+/// the job is submitted rather than partway through it. If it doesn't exist,
+/// asks whether to run anyway, or fails if `strict` is set or there's no
+/// terminal to ask on. The check is skipped if the namespaces can't be listed.
+fn preflight_namespace(
+    cli: &Cli,
+    run_ref: &str,
+    namespace: &str,
+    strict: bool,
+) -> anyhow::Result<()> {
+    const CHECK_TIMEOUT: time::Duration = time::Duration::from_secs(5);
+
+    let req = GetNamespaces {
+        at_ref: run_ref,
+        filter_by_name: None,
+    }
+    .paginate(None, Some(NAMESPACE_CHECK_LIMIT));
+
+    let listing = match cli.roundtrip_with_timeout(req, Some(CHECK_TIMEOUT)) {
+        Ok(listing) => listing,
+        Err(e) => {
+            debug!(run_ref, namespace, error = %e, "skipping namespace check");
+            return Ok(());
+        }
+    };
+
+    let suggestions = match check_namespace(namespace, listing) {
+        NamespaceCheck::Found | NamespaceCheck::Unknown => return Ok(()),
+        NamespaceCheck::Missing { suggestions } => suggestions,
+    };
+
+    let mut msg = format!("namespace {namespace:?} doesn't exist on {run_ref:?}");
+    if !suggestions.is_empty() {
+        msg.push_str(&format!("; did you mean: {}?", suggestions.join(", ")));
+    }
+
+    if strict || !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+        bail!(msg);
+    }
+
+    if !confirm(cli, &format!("{msg}\nRun anyway?"))? {
+        bail!("run cancelled");
+    }
+
+    Ok(())
+}
+
+/// Asks a yes/no question on the terminal, defaulting to no.
+fn confirm(cli: &Cli, prompt: &str) -> anyhow::Result<bool> {
+    let mut answer = String::new();
+    cli.multiprogress.suspend(|| {
+        anstream::eprint!("{YELLOW}{prompt}{YELLOW:#} [y/N] ");
+        std::io::stdin().read_line(&mut answer)
+    })?;
+
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+/// Fails if `run_ref` differs from `against` in any table that isn't one of
+/// the project's own models.
+fn check_clean_diff(
+    cli: &Cli,
+    project: &ProjectFile,
+    run_ref: &str,
+    against: &str,
+) -> anyhow::Result<()> {
+    if run_ref == against {
+        return Ok(());
+    }
+
+    let models = project.model_names()?;
+    let tables_against = collect_tables(cli, against, None)?;
+    let tables_run = collect_tables(cli, run_ref, None)?;
+    let diff = TableDiff::new(&tables_against, &tables_run);
+
+    let is_unexpected = |t: &&Table| !models.contains(&t.name);
+    let added: Vec<_> = diff.added.into_iter().filter(is_unexpected).collect();
+    let removed: Vec<_> = diff.removed.into_iter().filter(is_unexpected).collect();
+    let changed: Vec<_> = diff.changed.into_iter().filter(is_unexpected).collect();
+
+    if added.is_empty() && removed.is_empty() && changed.is_empty() {
+        debug!(run_ref, against, "no unexpected differences");
+        return Ok(());
+    }
+
+    cli.multiprogress.suspend(|| {
+        anstream::eprintln!(
+            "{BOLD}Unexpected differences between {against:?} and {run_ref:?}:{BOLD:#}"
+        );
+        for t in added {
+            anstream::eprintln!("{GREEN}+{} {}{GREEN:#}", t.kind, t.fqn());
+        }
+
+        for t in removed {
+            anstream::eprintln!("{RED}-{} {}{RED:#}", t.kind, t.fqn());
+        }
+
+        for t in changed {
+            anstream::eprintln!("{YELLOW}~{} {}{YELLOW:#}", t.kind, t.fqn());
+        }
+    });
+
+    bail!("{run_ref:?} has diverged from {against:?}; rebase it and try again")
+}
+
 fn add_task(
     cli: &Cli,
     spinners: &RefCell<BTreeMap<String, ProgressBar>>,
     summary: &mut Summary,
-    task_id: String,
-    task_name: String,
-    metadata: commanderpb::TaskMetadata,
+    task: Task,
 ) {
-    if metadata.level() != commanderpb::task_metadata::TaskLevel::Dag {
+    let Task {
+        id: task_id,
+        name: task_name,
+        metadata,
+        ..
+    } = task;
+
+    if !metadata.is_dag() {
         return;
     }
 
@@ -545,7 +1471,8 @@ fn add_task(
     task_spinner.enable_steady_tick(time::Duration::from_millis(100));
 
     // Indent the task name to present a hierarchy.
-    let name = if metadata.task_type == "USER_CODE_EXPECTATION" {
+    let expectation = metadata.task_type == "USER_CODE_EXPECTATION";
+    let name = if expectation {
         let name = metadata.function_name.unwrap_or(task_name);
         task_spinner.set_message(format!("{CYAN}  {name} [expectation]{CYAN:#}"));
         name
@@ -557,16 +1484,44 @@ fn add_task(
 
     summary.tasks.push(TaskSummary {
         task_id,
-        description: metadata.human_readable_task_type,
+        description: metadata.description,
         name,
         file_name: metadata.file_name,
-        line_number: metadata.line_number.map(|x| x as _),
+        line_number: metadata.line_number,
         started: Utc::now(),
         ended: Utc::now(),
         outcome: SummaryOutcome::Success,
+        cached: false,
+        expectation,
     });
 }
 
+/// Prints a line like "7 models: 4 executed, 3 cached, 0 failed", so that
+/// it's clear whether models actually ran with the cache on. Servers that
+/// don't report cache hits have every model count as executed.
+fn print_task_counts(tasks: &[TaskSummary]) {
+    let models: Vec<_> = tasks.iter().filter(|t| !t.expectation).collect();
+    if models.is_empty() {
+        return;
+    }
+
+    let count = |f: fn(&TaskSummary) -> bool| models.iter().filter(|t| f(t)).count();
+    let cached = count(|t| matches!(t.outcome, SummaryOutcome::Success) && t.cached);
+    let executed = count(|t| matches!(t.outcome, SummaryOutcome::Success) && !t.cached);
+    let failed = count(|t| matches!(t.outcome, SummaryOutcome::Failed));
+    let skipped = count(|t| matches!(t.outcome, SummaryOutcome::Skipped));
+
+    let mut line = format!(
+        "{} models: {executed} executed, {cached} cached, {failed} failed",
+        models.len()
+    );
+    if skipped > 0 {
+        line.push_str(&format!(", {skipped} skipped"));
+    }
+
+    eprintln!("{line}");
+}
+
 fn print_dag(job_id: &str, dag_ascii: String) -> anyhow::Result<()> {
     let mut stderr = anstream::stderr().lock();
 
@@ -577,29 +1532,50 @@ fn print_dag(job_id: &str, dag_ascii: String) -> anyhow::Result<()> {
 
     writeln!(
         &mut stderr,
-        "{DIM}=>{DIM:#} View this job in the app: https://app.bauplanlabs.com/jobs/{job_id}"
+        "{DIM}=>{DIM:#} View this job in the app: {}",
+        job_url(job_id)
     )?;
 
     Ok(())
 }
 
-fn print_user_log(
-    msg: &str,
-    stream: commanderpb::runtime_log_event::OutputStream,
-    metadata: commanderpb::TaskMetadata,
-) {
-    let model_name = metadata
+fn print_repro(summary: &Summary) -> anyhow::Result<()> {
+    let mut stderr = anstream::stderr().lock();
+
+    writeln!(&mut stderr, "\n{DIM}=> Reproduce{DIM:#}")?;
+    writeln!(&mut stderr, "{DIM}=>{DIM:#} {}", summary.repro_command)?;
+    if cfg!(windows) {
+        writeln!(
+            &mut stderr,
+            "{DIM}=>{DIM:#} (quoted for a POSIX shell, like Git Bash or WSL)"
+        )?;
+    }
+
+    writeln!(
+        &mut stderr,
+        "{DIM}=>{DIM:#} View this job in the app: {}",
+        summary.job_url
+    )?;
+
+    Ok(())
+}
+
+fn print_user_log(log: &LogMessage) {
+    let task = &log.task;
+    let model_name = task
         .model_name
-        .or(metadata.function_name)
-        .unwrap_or(metadata.human_readable_task_type);
+        .as_deref()
+        .or(task.function_name.as_deref())
+        .unwrap_or(&task.description);
 
-    let style = match stream {
-        commanderpb::runtime_log_event::OutputStream::Stderr => YELLOW,
+    let style = match log.stream {
+        OutputStream::Stderr => YELLOW,
         _ => BLUE,
     };
 
-    if let Some(file_name) = metadata.file_name
-        && let Some(line_number) = metadata.line_number
+    let msg = &log.message;
+    if let Some(file_name) = &task.file_name
+        && let Some(line_number) = task.line_number
     {
         anstream::eprintln!("{style}{model_name}: @ {file_name}:{line_number}{style:#} | {msg}");
     } else {
@@ -607,7 +1583,38 @@ fn print_user_log(
     }
 }
 
-fn print_preview(preview: &commanderpb::RuntimeTablePreview) -> anyhow::Result<()> {
+fn print_planned_materializations(planned: &[PlannedMaterialization]) -> anyhow::Result<()> {
+    let mut tw = TabWriter::new(anstream::stderr()).ansi(true);
+    writeln!(
+        tw,
+        "{DIM}=>{DIM:#} {YELLOW}{BOLD}WOULD MATERIALIZE{BOLD:#}{YELLOW:#}"
+    )?;
+    if planned.is_empty() {
+        writeln!(tw, "{DIM}=>{DIM:#} (no tables)")?;
+    } else {
+        writeln!(tw, "{DIM}=>{DIM:#} {DIM}TABLE\tREF\tPARTITIONED BY{DIM:#}")?;
+    }
+
+    for m in planned {
+        let partitioned_by = if m.partitioned_by.is_empty() {
+            "-".to_owned()
+        } else {
+            m.partitioned_by.join(", ")
+        };
+
+        writeln!(
+            tw,
+            "{DIM}=>{DIM:#} {}\t{}\t{partitioned_by}",
+            m.qualified_name(),
+            m.r#ref
+        )?;
+    }
+
+    tw.flush()?;
+    Ok(())
+}
+
+fn print_preview(preview: &TablePreview) -> anyhow::Result<()> {
     if preview.columns.is_empty() {
         return Ok(());
     }
@@ -620,12 +1627,11 @@ fn print_preview(preview: &commanderpb::RuntimeTablePreview) -> anyhow::Result<(
     let mut tw = TabWriter::new(anstream::stderr()).ansi(true);
     write!(tw, "{DIM}=>{DIM:#} ")?;
     for col in &preview.columns {
-        write!(tw, "{DIM}{}{DIM:#}\t", col.column_name.to_uppercase())?;
+        write!(tw, "{DIM}{}{DIM:#}\t", col.name.to_uppercase())?;
     }
     writeln!(tw)?;
 
-    let num_rows = preview.columns[0].values.len();
-    for i in 0..num_rows {
+    for i in 0..preview.num_rows() {
         write!(tw, "{DIM}=>{DIM:#} ")?;
         for col in &preview.columns {
             let val = col.values.get(i).map(String::as_str).unwrap_or_default();
@@ -642,15 +1648,21 @@ fn print_preview(preview: &commanderpb::RuntimeTablePreview) -> anyhow::Result<(
 async fn resolve_parameters(
     cli: &Cli,
     project: &ProjectFile,
-    cli_params: Vec<KeyValue>,
+    cli_params: Vec<ParamArg>,
 ) -> anyhow::Result<Vec<commanderpb::Parameter>> {
     // Are all the parameters correct?
-    for kv in &cli_params {
-        if !project.parameters.contains_key(&kv.0) {
-            bail!("unknown parameter: {:?}", kv.0);
+    for p in &cli_params {
+        if !project.parameters.contains_key(&p.name) {
+            bail!("unknown parameter: {:?}", p.name);
         }
     }
 
+    // Stdin can only be read once.
+    let from_stdin = cli_params.iter().filter(|p| p.source == ParamSource::Stdin);
+    if from_stdin.count() > 1 {
+        bail!("only one parameter can be read from stdin");
+    }
+
     // If any of the params are a secret, we need to fetch the org-wide public
     // key from commander. This is used to cache the result, in case multiple
     // parameters are secrets.
@@ -661,8 +1673,9 @@ async fn resolve_parameters(
 
     let mut resolved = Vec::with_capacity(project.parameters.len());
     for (name, param) in &project.parameters {
-        let kv = cli_params.iter().find(|kv| &kv.0 == name);
-        if let Some(KeyValue(_, value)) = kv {
+        let arg = cli_params.iter().find(|p| &p.name == name);
+        if let Some(arg) = arg {
+            let value = &arg.read_value()?;
             let parsed = if param.param_type == ParameterType::Secret {
                 let (key_name, key) = if let Some((key_name, key)) = &key_cache {
                     (key_name.clone(), key)
@@ -699,3 +1712,97 @@ async fn resolve_parameters(
 
     Ok(resolved)
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser as _;
+
+    use super::*;
+    use crate::cli::{Args, Command};
+
+    fn parse_run(args: &[&str]) -> RunArgs {
+        match Args::try_parse_from(args).unwrap().command {
+            Command::Run(args) => args,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn repro_command_round_trips() {
+        let project: ProjectFile = serde_yaml::from_str(
+            "project:\n  id: 6d5f4a3c-2b1a-4c9d-8e7f-0a1b2c3d4e5f\n\
+             parameters:\n  token:\n    type: secret\n  label:\n    type: str\n",
+        )
+        .unwrap();
+
+        let args = parse_run(&[
+            "bauplan",
+            "run",
+            "--ref",
+            "HEAD",
+            "--namespace",
+            "it's",
+            "--param",
+            "label=@@ $HOME \"quoted\" `x`",
+            "--param",
+            "token=hunter2",
+            "--param",
+            "token=@/run/secrets/token",
+            "--arg",
+            "k=v\nw",
+            "--preview",
+            "head",
+            "--priority",
+            "7",
+            "--require-clean-diff",
+            "--detach",
+            "--fail-on-empty-model",
+            "trips",
+        ]);
+
+        let dir = Path::new("/tmp/my project");
+        let command = shell_command(&repro_args(&args, &project, dir, "main@abc123"));
+        assert!(!command.contains("hunter2"), "{command}");
+        assert!(command.contains("token=@/run/secrets/token"), "{command}");
+        assert!(!command.contains("--detach"), "{command}");
+
+        let words = shlex::split(&command).unwrap();
+        let repro = parse_run(&words.iter().map(String::as_str).collect::<Vec<_>>());
+
+        assert_eq!(repro.project_dir.as_deref(), Some(dir));
+        assert_eq!(repro.r#ref.as_deref(), Some("main@abc123"));
+        assert_eq!(repro.namespace, args.namespace);
+        assert_eq!(repro.param[0], args.param[0]);
+        assert_eq!(
+            repro.param[1],
+            "token=***********".parse::<ParamArg>().unwrap()
+        );
+        assert_eq!(repro.param[2], args.param[2]);
+        assert_eq!(repro.arg, args.arg);
+        assert_eq!(repro.preview, Preview::Head);
+        assert_eq!(repro.priority, args.priority);
+        assert!(repro.require_clean_diff);
+        assert_eq!(repro.against, "main");
+        assert!(!repro.detach);
+        assert_eq!(repro.fail_on_empty_model, Some(vec!["trips".to_owned()]));
+    }
+
+    #[test]
+    fn repro_command_defaults() {
+        let project: ProjectFile =
+            serde_yaml::from_str("project:\n  id: 6d5f4a3c-2b1a-4c9d-8e7f-0a1b2c3d4e5f\n").unwrap();
+        let args = parse_run(&["bauplan", "run"]);
+
+        let command = shell_command(&repro_args(&args, &project, Path::new("/p"), "main@abc"));
+        assert_eq!(command, "bauplan run --project-dir /p --ref main@abc");
+    }
+
+    #[test]
+    fn job_timeout() {
+        let args = parse_run(&["bauplan", "run", "--job-timeout", "10m"]);
+        assert_eq!(args.job_timeout, Some(time::Duration::from_secs(600)));
+
+        let res = Args::try_parse_from(["bauplan", "run", "--job-timeout", "10m", "--detach"]);
+        assert!(res.is_err());
+    }
+}
@@ -1,7 +1,115 @@
-use indicatif::{ProgressBar, ProgressState, ProgressStyle};
+use std::{
+    io::IsTerminal as _,
+    sync::{Mutex, OnceLock},
+    thread, time,
+};
+
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressState, ProgressStyle, WeakProgressBar};
 
 use super::color::*;
 
+/// How often plain-text progress repeats a status line that hasn't changed.
+const PLAIN_PROGRESS_INTERVAL: time::Duration = time::Duration::from_secs(10);
+
+/// Set if progress is printed as plain text lines, rather than drawn with
+/// spinners.
+static PLAIN_PROGRESS: OnceLock<PlainProgress> = OnceLock::new();
+
+/// Returns true if spinners shouldn't be drawn: on dumb or non-interactive
+/// terminals, or if `BAUPLAN_NO_PROGRESS` is set.
+fn wants_plain_progress() -> bool {
+    if std::env::var_os("BAUPLAN_NO_PROGRESS").is_some_and(|v| !v.is_empty() && v != "0") {
+        return true;
+    }
+
+    if std::env::var_os("TERM").is_some_and(|t| t == "dumb") {
+        return true;
+    }
+
+    !std::io::stderr().is_terminal()
+}
+
+/// Switches to plain-text progress if the terminal can't draw spinners, and
+/// returns true if it did. In that mode, spinners are hidden, and a
+/// background thread periodically prints the status of the oldest unfinished
+/// one instead.
+pub(crate) fn init_progress(multiprogress: &indicatif::MultiProgress) -> bool {
+    if !wants_plain_progress() {
+        return false;
+    }
+
+    multiprogress.set_draw_target(ProgressDrawTarget::hidden());
+    if PLAIN_PROGRESS.set(PlainProgress::default()).is_ok() {
+        thread::spawn(|| PLAIN_PROGRESS.get().unwrap().report());
+    }
+
+    true
+}
+
+/// Tracks spinners for plain-text progress.
+#[derive(Default)]
+struct PlainProgress {
+    bars: Mutex<Vec<WeakProgressBar>>,
+}
+
+impl PlainProgress {
+    fn register(&self, bar: &ProgressBar) {
+        self.bars.lock().unwrap().push(bar.downgrade());
+    }
+
+    fn report(&self) {
+        let mut last: Option<(String, time::Instant)> = None;
+        loop {
+            thread::sleep(time::Duration::from_secs(1));
+
+            let line = {
+                let mut bars = self.bars.lock().unwrap();
+                bars.retain(|b| b.upgrade().is_some());
+
+                let bars: Vec<_> = bars.iter().filter_map(WeakProgressBar::upgrade).collect();
+                let Some(main) = bars.iter().position(|b| !b.is_finished()) else {
+                    continue;
+                };
+
+                // Spinners started after the main one are its tasks.
+                let tasks = &bars[main + 1..];
+                let mut line = strip(&bars[main].message());
+                if !tasks.is_empty() {
+                    let complete = tasks.iter().filter(|b| b.is_finished()).count();
+                    line = format!("{line} {complete}/{} tasks complete", tasks.len());
+                }
+
+                (bars[main].elapsed(), line)
+            };
+
+            let (elapsed, line) = line;
+            let repeat = match &last {
+                Some((prev, at)) => *prev != line || at.elapsed() >= PLAIN_PROGRESS_INTERVAL,
+                None => true,
+            };
+
+            if repeat {
+                eprintln!("[{}] {line}", format_elapsed(elapsed));
+                last = Some((line, time::Instant::now()));
+            }
+        }
+    }
+}
+
+fn strip(msg: &str) -> String {
+    anstream::adapter::strip_str(msg).to_string()
+}
+
+/// Formats a duration like `2m10s`.
+fn format_elapsed(elapsed: time::Duration) -> String {
+    let secs = elapsed.as_secs();
+    if secs >= 60 {
+        format!("{}m{}s", secs / 60, secs % 60)
+    } else {
+        format!("{secs}s")
+    }
+}
+
 impl super::Cli {
     /// Creates a progress spinner that plays nicely with logging.
     pub(crate) fn new_spinner(&self) -> ProgressBar {
@@ -24,11 +132,17 @@ impl super::Cli {
             .tick_strings(&["⠋", "⠙", "⠚", "⠞", "⠖", "⠦", "⠴", "⠲", "⠳", "⠓", ""]),
         );
 
-        self.multiprogress.add(progress)
+        let progress = self.multiprogress.add(progress);
+        if let Some(plain) = PLAIN_PROGRESS.get() {
+            plain.register(&progress);
+        }
+
+        progress
     }
 }
 
 pub(crate) const DONE: Styled = Styled(GREEN, "done");
+pub(crate) const CACHED: Styled = Styled(CYAN, "cached");
 pub(crate) const FAILED: Styled = Styled(RED, "failed");
 pub(crate) const FAILED_WARN: Styled = Styled(YELLOW, "failed");
 pub(crate) const CANCELLED: Styled = Styled(RED, "cancelled");
@@ -53,5 +167,22 @@ impl ProgressExt for ProgressBar {
 
     fn finish_with_status(&self, st: impl std::fmt::Display) {
         self.finish_with_message(format!("{} {st}", self.message()));
+
+        // Hidden spinners don't draw their final message, so print it.
+        if PLAIN_PROGRESS.get().is_some() {
+            let elapsed = format_elapsed(self.elapsed());
+            eprintln!("[{elapsed}] {}", strip(&self.message()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elapsed() {
+        assert_eq!(format_elapsed(time::Duration::from_millis(4500)), "4s");
+        assert_eq!(format_elapsed(time::Duration::from_secs(130)), "2m10s");
     }
 }
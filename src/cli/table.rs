@@ -1,28 +1,33 @@
 use std::{
-    io::{IsTerminal as _, Read as _, Write as _, stdout},
-    path::PathBuf,
-    time,
+    io::{self, IsTerminal as _, Write as _, stdout},
+    path::{Path, PathBuf},
+    thread, time,
 };
 
 use crate::cli::{
-    Cli, KeyValue, Output, Priority, api_err_kind,
+    Cli, KeyValue, Output, Priority, api_err, api_err_kind,
     color::*,
-    format_grpc_status,
-    run::{job_request_common, monitor_job_progress},
+    format_grpc_status, input, plan_file,
+    render::{FormatArgs, RefChange, Render, emit_json, or_dash, render, write_json_array},
+    run::{JobResult, create_event_recorder, job_request_common, monitor_job_progress},
     spinner::{self, ProgressExt as _},
     with_rt,
 };
 use anyhow::{anyhow, bail};
 use bauplan::{
-    ApiErrorKind,
+    ApiError, ApiErrorKind, OperationResult, TableReadiness, TableWait,
+    branch::WatchSchedule,
     commit::CommitOptions,
-    grpc::{self, generated as commanderpb},
+    events::{JobEvent, capture::EventRecorder},
+    grpc::{self, generated as commanderpb, import::ImportHistory, job::Job, search_uri},
+    sync::TableSync,
     table::*,
 };
-use commanderpb::runner_event::Event as RunnerEvent;
+use chrono::{DateTime, Utc};
 use indicatif::ProgressBar;
+use serde::Serialize;
 use tabwriter::TabWriter;
-use tracing::info;
+use tracing::{info, warn};
 
 #[derive(Debug, clap::Args)]
 pub(crate) struct TableArgs {
@@ -37,6 +42,10 @@ pub(crate) enum TableCommand {
     Ls(TableLsArgs),
     /// Get information about a table
     Get(TableGetArgs),
+    /// List the snapshots of a table, newest first
+    Snapshots(TableSnapshotsArgs),
+    /// Show how a table differs between two refs
+    Diff(TableDiffArgs),
     /// Drop a table from the data catalog (does not free up storage)
     #[clap(alias = "delete", alias = "drop")]
     Rm(TableRmArgs),
@@ -46,12 +55,20 @@ pub(crate) enum TableCommand {
     CreatePlan(TableCreatePlanArgs),
     /// Apply a table create plan manually
     CreatePlanApply(TableCreatePlanApplyArgs),
+    /// Work with saved table create plans
+    Plan(TablePlanArgs),
     /// Create an external read-only Iceberg table from existing data without any copies
     CreateExternal(TableCreateExternalArgs),
     /// Import data to an existing table. Use `bauplan table create` to create the table first
     Import(TableImportArgs),
     /// Revert a table to a previous state from a source ref
     Revert(TableRevertArgs),
+    /// Add, drop or rename columns of a table
+    Alter(TableAlterArgs),
+    /// Show the tables added, removed or changed since the last sync
+    SyncState(TableSyncStateArgs),
+    /// Wait for a table to exist, or to have some records
+    Wait(TableWaitArgs),
 }
 
 #[derive(Debug, clap::Args)]
@@ -65,6 +82,9 @@ pub(crate) enum TableCommand {
   # List tables from specific branch
   bauplan table ls --ref main
 
+  # List tables on main as of yesterday
+  bauplan table ls --ref main@{yesterday}
+
   # Limit results
   bauplan table ls --limit 20
 "))]
@@ -93,6 +113,16 @@ pub(crate) struct TableLsArgs {
 
   # Get table info with namespace prefix
   bauplan table get raw_data.customers
+
+  # Get table info from an explicit namespace
+  bauplan table get customers --namespace raw_data
+
+  # Show every field of the table, or print it as YAML
+  bauplan table get customers --format wide
+  bauplan table get customers --format yaml
+
+  # Print only the schema, without the table details
+  bauplan table get customers --schema-only
 "))]
 pub(crate) struct TableGetArgs {
     /// Table name
@@ -100,6 +130,64 @@ pub(crate) struct TableGetArgs {
     /// Ref or branch name to get the table from [default: active branch]
     #[arg(short, long)]
     pub r#ref: Option<String>,
+    /// Namespace of the table (the table name must not include one)
+    #[arg(short, long)]
+    pub namespace: Option<String>,
+    /// Print only the schema in the table layout (JSON and YAML are unaffected)
+    #[arg(long)]
+    pub schema_only: bool,
+    #[command(flatten)]
+    pub format: FormatArgs,
+}
+
+#[derive(Debug, clap::Args)]
+#[command(after_long_help = CliExamples("
+  # List the snapshots of a table on the active branch
+  bauplan table snapshots customers
+
+  # List the last 5 snapshots of a table on main
+  bauplan table snapshots raw_data.customers --ref main --limit 5
+
+  # Query the table as of one of them
+  bauplan query --ref main@4527150312935411733 \"SELECT * FROM raw_data.customers\"
+"))]
+pub(crate) struct TableSnapshotsArgs {
+    /// Table name
+    pub table_name: String,
+    /// Ref or branch name to read the table at [default: active branch]
+    #[arg(short, long)]
+    pub r#ref: Option<String>,
+    /// Namespace of the table (the table name must not include one)
+    #[arg(short, long)]
+    pub namespace: Option<String>,
+    /// Limit the number of snapshots to show
+    #[arg(long)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, clap::Args)]
+#[command(after_long_help = CliExamples("
+  # Show what changed in a table on the active branch, compared to main
+  bauplan table diff raw_data.customers --from main
+
+  # Compare a table between two branches
+  bauplan table diff customers --namespace raw_data --from main --to my_branch
+
+  # Check a table is unchanged since a tag, as JSON
+  bauplan table diff raw_data.customers --from v1.0 --to main --output json
+"))]
+pub(crate) struct TableDiffArgs {
+    /// Table name
+    pub table_name: String,
+    /// Ref or branch name to compare from
+    #[arg(long = "from")]
+    pub from_ref: String,
+    /// Ref or branch name to compare to [default: active branch]
+    #[arg(long = "to")]
+    pub to_ref: Option<String>,
+    /// Namespace of the table (the table name must not include one)
+    #[arg(short, long)]
+    pub namespace: Option<String>,
 }
 
 #[derive(Debug, clap::Args)]
@@ -112,6 +200,9 @@ pub(crate) struct TableGetArgs {
 
   # Conditionally delete
   bauplan table rm maybe_table --if-exists
+
+  # Delete from an explicit namespace
+  bauplan table rm old_table --namespace raw_data
 "))]
 pub(crate) struct TableRmArgs {
     /// Table name
@@ -119,6 +210,9 @@ pub(crate) struct TableRmArgs {
     /// Branch to delete the table from [default: active branch]
     #[arg(short, long)]
     pub branch: Option<String>,
+    /// Namespace of the table (the table name must not include one)
+    #[arg(short, long)]
+    pub namespace: Option<String>,
     /// Do not fail if the table does not exist
     #[arg(long)]
     pub if_exists: bool,
@@ -150,26 +244,35 @@ pub(crate) struct TableCreateArgs {
     /// Namespace for the table
     #[arg(short, long)]
     pub namespace: Option<String>,
-    /// S3 URI pattern for parquet files to import (e.g. s3://bucket/path/*)
+    /// S3 URI pattern for the files to import (e.g. s3://bucket/path/*)
     #[arg(long)]
     pub search_uri: url::Url,
+    /// Format of the files: parquet, csv or jsonl. Only parquet can be read for now
+    #[arg(long, value_name = "FORMAT", default_value_t)]
+    pub file_format: search_uri::FileFormat,
     /// Partition the table by the given columns
     #[arg(long)]
     pub partitioned_by: Option<String>,
     /// Replace the existing table, if it exists
     #[arg(short, long)]
     pub replace: bool,
-    /// Extra arguments as key=value pairs (repeatable)
+    /// Extra arguments as key=value pairs, where key=@path reads the value from a file (repeatable)
     #[arg(short, long, action = clap::ArgAction::Append)]
     pub arg: Vec<KeyValue>,
     /// Set the job priority (1-10, where 10 is highest priority)
     #[arg(long)]
     pub priority: Option<Priority>,
+    /// Record the raw events of the planning and import jobs to a file, for debugging. The file can be replayed with "bauplan job replay-events"
+    #[arg(long, value_name = "PATH")]
+    pub events_out: Option<PathBuf>,
+    /// Cancel the planning or import job if it runs for longer than this, like 30s or 10m
+    #[arg(long, value_parser = humantime::parse_duration)]
+    pub job_timeout: Option<time::Duration>,
 }
 
 #[derive(Debug, clap::Args)]
 #[command(after_long_help = CliExamples("
-  # Create plan and save to file
+  # Create plan and save to file, along with a plan.json.sha256 checksum
   bauplan table create-plan customers --search-uri s3://mybucket/customers/*.parquet --save-plan plan.json
 
   # Create plan without saving
@@ -184,19 +287,22 @@ pub(crate) struct TableCreatePlanArgs {
     /// Namespace for the table
     #[arg(short, long)]
     pub namespace: Option<String>,
-    /// S3 URI pattern for parquet files to import (e.g. s3://bucket/path/*)
+    /// S3 URI pattern for the files to import (e.g. s3://bucket/path/*)
     #[arg(long)]
     pub search_uri: url::Url,
+    /// Format of the files: parquet, csv or jsonl. Only parquet can be read for now
+    #[arg(long, value_name = "FORMAT", default_value_t)]
+    pub file_format: search_uri::FileFormat,
     /// Partition the table by the given columns
     #[arg(long)]
     pub partitioned_by: Option<String>,
     /// Replace the existing table, if it exists
     #[arg(short, long)]
     pub replace: bool,
-    /// A filename to write the plan to
+    /// A filename to write the plan to, along with a `<file>.sha256` checksum
     #[arg(short = 'p', long)]
     pub save_plan: Option<PathBuf>,
-    /// Extra arguments as key=value pairs (repeatable)
+    /// Extra arguments as key=value pairs, where key=@path reads the value from a file (repeatable)
     #[arg(short, long, action = clap::ArgAction::Append)]
     pub arg: Vec<KeyValue>,
 }
@@ -207,10 +313,10 @@ pub(crate) struct TableCreatePlanArgs {
   bauplan table create-plan-apply --plan plan.json
 "))]
 pub(crate) struct TableCreatePlanApplyArgs {
-    /// Path to a plan YAML file; reads from stdin if not provided
+    /// Path to a plan YAML file, checked against its `<file>.sha256` checksum if there is one; reads from stdin if not provided
     #[arg(long)]
-    pub plan: Option<String>,
-    /// Extra arguments as key=value pairs (repeatable)
+    pub plan: Option<PathBuf>,
+    /// Extra arguments as key=value pairs, where key=@path reads the value from a file (repeatable)
     #[arg(short, long, action = clap::ArgAction::Append)]
     pub arg: Vec<KeyValue>,
     /// Set the job priority (1-10, where 10 is highest priority)
@@ -218,11 +324,42 @@ pub(crate) struct TableCreatePlanApplyArgs {
     pub priority: Option<Priority>,
 }
 
+#[derive(Debug, clap::Args)]
+pub(crate) struct TablePlanArgs {
+    #[command(subcommand)]
+    pub command: TablePlanCommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub(crate) enum TablePlanCommand {
+    /// Check a saved plan against its checksum
+    Verify(TablePlanVerifyArgs),
+}
+
+#[derive(Debug, clap::Args)]
+#[command(after_long_help = CliExamples("
+  # Check a plan copied from another machine
+  bauplan table plan verify plan.json
+
+  # Accept the plan's current contents after editing it, e.g. to resolve schema conflicts
+  bauplan table plan verify --update plan.json
+"))]
+pub(crate) struct TablePlanVerifyArgs {
+    /// Path to the plan file; its checksum is read from `<file>.sha256`
+    pub plan: PathBuf,
+    /// Write a new checksum for the plan as it is now, after editing it on purpose
+    #[arg(long)]
+    pub update: bool,
+}
+
 #[derive(Debug, clap::Args)]
 #[command(after_long_help = CliExamples("
   # Create external table from Iceberg metadata
   bauplan table create-external events --metadata-json-uri s3://bucket/metadata.json --namespace raw_data
 
+  # Create external table from Iceberg metadata, with the namespace in the name
+  bauplan table create-external raw_data.events --metadata-json-uri s3://bucket/metadata.json
+
   # Create external table from parquet files
   bauplan table create-external events --search-pattern \"s3://bucket/data/*.parquet\" --namespace raw_data
 
@@ -238,7 +375,7 @@ pub(crate) struct TableCreateExternalArgs {
     /// Branch to create the table in [default: active branch]
     #[arg(short, long)]
     pub branch: Option<String>,
-    /// Namespace for the table
+    /// Namespace for the table [default: from the table name, or the catalog's default namespace]
     #[arg(short, long)]
     pub namespace: Option<String>,
     /// URI to Iceberg metadata.json file (e.g., s3://bucket/metadata.json)
@@ -274,6 +411,9 @@ pub(crate) struct TableCreateExternalArgs {
 
   # Import in background
   bauplan table import logs --search-uri s3://bucket/logs/*.parquet --detach
+
+  # Name the import job
+  bauplan table import logs --search-uri s3://bucket/logs/*.parquet --name logs-backfill
 "))]
 pub(crate) struct TableImportArgs {
     /// Name of table where data will be imported into
@@ -287,6 +427,9 @@ pub(crate) struct TableImportArgs {
     /// Uri search string e.g s3://bucket/path/a/*
     #[arg(long)]
     pub search_uri: url::Url,
+    /// Format of the files: parquet, csv or jsonl. Only parquet can be read for now
+    #[arg(long, value_name = "FORMAT", default_value_t)]
+    pub file_format: search_uri::FileFormat,
     /// Don't fail the command even if 1/N files fails to import
     #[arg(long)]
     pub continue_on_error: bool,
@@ -299,12 +442,21 @@ pub(crate) struct TableImportArgs {
     /// Run the job in the background
     #[arg(short, long)]
     pub detach: bool,
-    /// Extra arguments as key=value pairs (repeatable)
+    /// Extra arguments as key=value pairs, where key=@path reads the value from a file (repeatable)
     #[arg(short, long, action = clap::ArgAction::Append)]
     pub arg: Vec<KeyValue>,
     /// Set the job priority (1-10, where 10 is highest priority)
     #[arg(long)]
     pub priority: Option<Priority>,
+    /// A human-friendly name for the job
+    #[arg(long)]
+    pub name: Option<String>,
+    /// Record the raw events of the job to a file, for debugging. The file can be replayed with "bauplan job replay-events"
+    #[arg(long, value_name = "PATH", conflicts_with = "detach")]
+    pub events_out: Option<PathBuf>,
+    /// Cancel the job if it runs for longer than this, like 30s or 10m
+    #[arg(long, value_parser = humantime::parse_duration, conflicts_with = "detach")]
+    pub job_timeout: Option<time::Duration>,
 }
 
 #[derive(Debug, clap::Args)]
@@ -341,14 +493,153 @@ pub(crate) struct TableRevertArgs {
     pub commit_property: Vec<KeyValue>,
 }
 
+#[derive(Debug, clap::Args)]
+#[command(after_long_help = CliExamples("
+  # Add a column
+  bauplan table alter customers --add notes:string
+
+  # Add a required column
+  bauplan table alter customers --add signup_date:date:required
+
+  # Drop a column, and rename another
+  bauplan table alter customers --drop legacy_id --rename name=full_name
+
+  # Change a table on a specific branch, with a commit message
+  bauplan table alter customers --add score:double --branch username.dev_branch --commit-body \"Add score\"
+"))]
+pub(crate) struct TableAlterArgs {
+    /// Table name
+    pub table_name: String,
+    /// Branch to change the table on [default: active branch]
+    #[arg(short, long)]
+    pub branch: Option<String>,
+    /// Namespace of the table (the table name must not include one)
+    #[arg(short, long)]
+    pub namespace: Option<String>,
+    /// Column to add, as name:type or name:type:required, with an Iceberg type like string or decimal(10,2) (repeatable)
+    #[arg(long, value_name = "NAME:TYPE")]
+    pub add: Vec<NewColumnArg>,
+    /// Column to drop (repeatable)
+    #[arg(long, value_name = "NAME")]
+    pub drop: Vec<String>,
+    /// Column to rename, as old=new (repeatable)
+    #[arg(long, value_name = "OLD=NEW")]
+    pub rename: Vec<KeyValue>,
+    /// Optional commit body to append to the commit message
+    #[arg(long)]
+    pub commit_body: Option<String>,
+    /// Commit properties as key=value pairs (can be used multiple times)
+    #[arg(long, action = clap::ArgAction::Append)]
+    pub commit_property: Vec<KeyValue>,
+    #[command(flatten)]
+    pub format: FormatArgs,
+}
+
+/// A column to add, as `name:type` or `name:type:required`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NewColumnArg {
+    name: String,
+    r#type: String,
+    required: bool,
+}
+
+impl std::str::FromStr for NewColumnArg {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((name, rest)) = s.split_once(':') else {
+            bail!("invalid column {s:?}: expected name:type");
+        };
+
+        let (r#type, required) = match rest.rsplit_once(':') {
+            Some((r#type, "required")) => (r#type, true),
+            Some(_) => bail!("invalid column {s:?}: expected name:type or name:type:required"),
+            None => (rest, false),
+        };
+
+        if name.is_empty() || r#type.is_empty() {
+            bail!("invalid column {s:?}: expected name:type");
+        }
+
+        Ok(NewColumnArg {
+            name: name.to_owned(),
+            r#type: r#type.to_owned(),
+            required,
+        })
+    }
+}
+
+#[derive(Debug, clap::Args)]
+#[command(after_long_help = CliExamples("
+  # Show what changed on the active branch since the last sync
+  bauplan table sync-state --state-file state.json
+
+  # Print the changes as JSON, for another tool to process
+  bauplan -O json table sync-state --state-file state.json --ref main
+
+  # Show the changes, without recording them as synced
+  bauplan table sync-state --state-file state.json --no-commit
+"))]
+pub(crate) struct TableSyncStateArgs {
+    /// File recording the tables as of the last sync. It's created if it
+    /// doesn't exist, and updated after the changes are printed
+    #[arg(long)]
+    pub state_file: PathBuf,
+    /// Ref or branch name to sync tables from [default: active branch]
+    #[arg(short, long)]
+    pub r#ref: Option<String>,
+    /// Don't update the state file
+    #[arg(long)]
+    pub no_commit: bool,
+}
+
+#[derive(Debug, clap::Args)]
+#[command(after_long_help = CliExamples("
+  # Wait for a table to exist on the active branch
+  bauplan table wait customers
+
+  # Wait up to 10 minutes for a table to have some records
+  bauplan table wait customers --ref my_branch --timeout 10m --min-records 1
+
+  # Wait for a new snapshot of a table
+  bauplan table wait customers --after-snapshot-id 4527150312935411733
+"))]
+pub(crate) struct TableWaitArgs {
+    /// Table name
+    pub table_name: String,
+    /// Ref or branch name to poll the table on [default: active branch]
+    #[arg(short, long)]
+    pub r#ref: Option<String>,
+    /// Namespace of the table (the table name must not include one)
+    #[arg(short, long)]
+    pub namespace: Option<String>,
+    /// How long to wait before giving up, like 30s or 10m
+    #[arg(long, default_value = "10m", value_parser = humantime::parse_duration)]
+    pub timeout: time::Duration,
+    /// Wait until the table has at least this many records
+    #[arg(long)]
+    pub min_records: Option<u64>,
+    /// Wait until the table has moved on from this snapshot
+    #[arg(long)]
+    pub after_snapshot_id: Option<i64>,
+    /// Seconds between polls (jittered)
+    #[arg(long, default_value_t = 5)]
+    pub poll_interval: u64,
+}
+
 pub(crate) fn handle(cli: &Cli, args: TableArgs) -> anyhow::Result<()> {
     match args.command {
         TableCommand::Ls(args) => handle_list_tables(cli, args),
         TableCommand::Get(args) => handle_get_table(cli, args),
+        TableCommand::Snapshots(args) => handle_table_snapshots(cli, args),
+        TableCommand::Diff(args) => handle_table_diff(cli, args),
         TableCommand::Rm(args) => handle_delete_table(cli, args),
         TableCommand::Create(args) => with_rt(handle_create_table(cli, args)),
         TableCommand::CreatePlan(args) => with_rt(handle_create_plan(cli, args)),
         TableCommand::CreatePlanApply(args) => with_rt(handle_apply_plan(cli, args)),
+        TableCommand::Plan(args) => match args.command {
+            TablePlanCommand::Verify(args) => handle_verify_plan(cli, args),
+        },
         TableCommand::CreateExternal(args) => {
             if args.metadata_json_uri.is_some() {
                 handle_create_external_from_metadata(cli, args)
@@ -358,6 +649,9 @@ pub(crate) fn handle(cli: &Cli, args: TableArgs) -> anyhow::Result<()> {
         }
         TableCommand::Import(args) => with_rt(handle_import_data(cli, args)),
         TableCommand::Revert(args) => handle_revert_table(cli, args),
+        TableCommand::Alter(args) => handle_alter_table(cli, args),
+        TableCommand::SyncState(args) => handle_sync_state(cli, args),
+        TableCommand::Wait(args) => handle_wait_table(cli, args),
     }
 }
 
@@ -370,13 +664,15 @@ fn handle_list_tables(
         limit,
     }: TableLsArgs,
 ) -> anyhow::Result<()> {
-    let at_ref = r#ref
-        .as_deref()
-        .or(cli.profile.active_branch.as_deref())
-        .unwrap_or("main");
+    let at_ref = cli.resolve_ref(
+        r#ref
+            .as_deref()
+            .or(cli.profile.active_branch.as_deref())
+            .unwrap_or("main"),
+    )?;
 
     let req = GetTables {
-        at_ref,
+        at_ref: &at_ref,
         filter_by_name: name.as_deref(),
         filter_by_namespace: namespace.as_deref(),
     };
@@ -384,11 +680,7 @@ fn handle_list_tables(
     let tables = bauplan::paginate(req, limit, |r| cli.roundtrip(r))?;
 
     match cli.global.output {
-        Output::Json => {
-            let all_tables = tables.collect::<anyhow::Result<Vec<_>>>()?;
-            serde_json::to_writer(stdout(), &all_tables)?;
-            println!();
-        }
+        Output::Json => write_json_array(&mut stdout().lock(), tables)?,
         Output::Tty => {
             let mut tw = TabWriter::new(stdout());
             writeln!(&mut tw, "NAMESPACE\tNAME\tKIND")?;
@@ -410,42 +702,435 @@ fn handle_list_tables(
 
 fn handle_get_table(
     cli: &Cli,
-    TableGetArgs { table_name, r#ref }: TableGetArgs,
+    TableGetArgs {
+        table_name,
+        r#ref,
+        namespace,
+        schema_only,
+        format,
+    }: TableGetArgs,
 ) -> anyhow::Result<()> {
+    let at_ref = cli.resolve_ref(
+        r#ref
+            .as_deref()
+            .or(cli.profile.active_branch.as_deref())
+            .unwrap_or("main"),
+    )?;
+
     let req = GetTable {
         name: &table_name,
-        at_ref: r#ref
+        at_ref: &at_ref,
+        namespace: namespace.as_deref(),
+    };
+
+    let resp = cli.roundtrip(req).map_err(|e| {
+        with_namespace_candidates(cli, e, &table_name, namespace.as_deref(), &at_ref)
+    })?;
+
+    let format = format.resolve(cli.global.output);
+    if schema_only {
+        render(&TableSchema(&resp), format)
+    } else {
+        render(&resp, format)
+    }
+}
+
+fn handle_table_snapshots(
+    cli: &Cli,
+    TableSnapshotsArgs {
+        table_name,
+        r#ref,
+        namespace,
+        limit,
+    }: TableSnapshotsArgs,
+) -> anyhow::Result<()> {
+    let at_ref = cli.resolve_ref(
+        r#ref
             .as_deref()
             .or(cli.profile.active_branch.as_deref())
             .unwrap_or("main"),
-        namespace: None,
+    )?;
+
+    let req = GetTableSnapshots {
+        name: &table_name,
+        at_ref: &at_ref,
+        namespace: namespace.as_deref(),
     };
 
-    let resp = cli.roundtrip(req)?;
+    let snapshots = bauplan::paginate(req, limit, |r| cli.roundtrip(r)).map_err(|e| {
+        with_namespace_candidates(cli, e, &table_name, namespace.as_deref(), &at_ref)
+    })?;
+
     match cli.global.output {
-        Output::Json => {
-            serde_json::to_writer(stdout(), &resp)?;
-            println!();
-        }
+        Output::Json => write_json_array(&mut stdout().lock(), snapshots)?,
         Output::Tty => {
+            let now = Utc::now();
             let mut tw = TabWriter::new(stdout());
-            writeln!(&mut tw, "NAME\tREQUIRED\tTYPE")?;
+            writeln!(&mut tw, "SNAPSHOT ID\tOPERATION\tCREATED\tPARENT ID")?;
+            for snapshot in snapshots {
+                let snapshot = snapshot?;
+                writeln!(
+                    &mut tw,
+                    "{}\t{}\t{}\t{}",
+                    snapshot.snapshot_id,
+                    or_dash(snapshot.operation),
+                    format_age(snapshot.timestamp, now),
+                    or_dash(snapshot.parent_id),
+                )?;
+            }
+
+            tw.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Formats how long before `now` something happened, to the most
+/// significant unit, like "3h ago" or "2months ago".
+fn format_age(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = (now - at).to_std().unwrap_or_default();
+    if elapsed.as_secs() == 0 {
+        return "just now".to_owned();
+    }
+
+    let elapsed = humantime::format_duration(time::Duration::from_secs(elapsed.as_secs()));
+    let elapsed = elapsed.to_string();
+    format!("{} ago", elapsed.split_ascii_whitespace().next().unwrap())
+}
+
+/// The exit code for `table diff` when the table differs between the refs.
+const DIFF_EXIT_CODE: i32 = 3;
+
+fn handle_table_diff(
+    cli: &Cli,
+    TableDiffArgs {
+        table_name,
+        from_ref,
+        to_ref,
+        namespace,
+    }: TableDiffArgs,
+) -> anyhow::Result<()> {
+    let from_ref = cli.resolve_ref(&from_ref)?;
+    let to_ref = cli.resolve_ref(
+        to_ref
+            .as_deref()
+            .or(cli.profile.active_branch.as_deref())
+            .unwrap_or("main"),
+    )?;
+
+    let get = |at_ref: &str| {
+        let req = GetTable {
+            name: &table_name,
+            at_ref,
+            namespace: namespace.as_deref(),
+        };
+
+        cli.roundtrip(req).map_err(|e| {
+            with_namespace_candidates(cli, e, &table_name, namespace.as_deref(), at_ref)
+        })
+    };
+
+    let from = get(&from_ref)?;
+    let to = get(&to_ref)?;
+    let diff = TableDiff::new(&from_ref, &from, &to_ref, &to);
+
+    match cli.global.output {
+        Output::Json => emit_json(&diff)?,
+        Output::Tty if diff.is_empty() => println!("no changes"),
+        Output::Tty => print_table_diff(&diff)?,
+    }
+
+    if !diff.is_empty() {
+        stdout().flush()?;
+        std::process::exit(DIFF_EXIT_CODE);
+    }
+
+    Ok(())
+}
+
+fn print_table_diff(diff: &TableDiff) -> anyhow::Result<()> {
+    let mut out = anstream::stdout().lock();
+    writeln!(
+        &mut out,
+        "{BOLD}{}{BOLD:#} {DIM}{} -> {}{DIM:#}",
+        diff.table, diff.from_ref, diff.to_ref
+    )?;
+
+    if diff.from_records != diff.to_records {
+        let delta = diff
+            .records_delta()
+            .map(|d| format!(" ({d:+})"))
+            .unwrap_or_default();
+        writeln!(
+            &mut out,
+            "records: {} -> {}{delta}",
+            or_dash(diff.from_records),
+            or_dash(diff.to_records),
+        )?;
+    }
+
+    if diff.from_snapshot_id != diff.to_snapshot_id {
+        writeln!(
+            &mut out,
+            "snapshot: {} -> {}",
+            or_dash(diff.from_snapshot_id),
+            or_dash(diff.to_snapshot_id),
+        )?;
+    }
+
+    if !diff.columns.is_empty() {
+        writeln!(&mut out, "columns:")?;
+        for c in &diff.columns {
+            let (marker, style) = diff_marker(c.kind);
+            let from = c
+                .from_type
+                .as_deref()
+                .map(|t| column_type(t, c.from_required));
+            let to = c.to_type.as_deref().map(|t| column_type(t, c.to_required));
+            let types = match (from, to) {
+                (Some(from), Some(to)) => format!("{from} -> {to}"),
+                (from, to) => from.or(to).unwrap_or_default(),
+            };
+
+            writeln!(&mut out, "  {style}{marker} {}{style:#} {types}", c.name)?;
+        }
+    }
+
+    if !diff.partitions.is_empty() {
+        writeln!(&mut out, "partitions:")?;
+        for p in &diff.partitions {
+            let (marker, style) = diff_marker(p.kind);
+            let transforms = match (&p.from_transform, &p.to_transform) {
+                (Some(from), Some(to)) => format!("{from} -> {to}"),
+                (from, to) => from.as_ref().or(to.as_ref()).cloned().unwrap_or_default(),
+            };
+
+            writeln!(
+                &mut out,
+                "  {style}{marker} {}{style:#} {transforms}",
+                p.name
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn diff_marker(kind: DiffKind) -> (char, anstyle::Style) {
+    match kind {
+        DiffKind::Added => ('+', GREEN),
+        DiffKind::Removed => ('-', RED),
+        DiffKind::Changed => ('~', YELLOW),
+    }
+}
+
+fn column_type(ty: &str, required: Option<bool>) -> String {
+    if required == Some(true) {
+        format!("{ty} not null")
+    } else {
+        ty.to_owned()
+    }
+}
+
+fn handle_wait_table(
+    cli: &Cli,
+    TableWaitArgs {
+        table_name,
+        r#ref,
+        namespace,
+        timeout,
+        min_records,
+        after_snapshot_id,
+        poll_interval,
+    }: TableWaitArgs,
+) -> anyhow::Result<()> {
+    let at_ref = cli.resolve_ref(
+        r#ref
+            .as_deref()
+            .or(cli.profile.active_branch.as_deref())
+            .unwrap_or("main"),
+    )?;
 
+    let req = GetTable {
+        name: &table_name,
+        at_ref: &at_ref,
+        namespace: namespace.as_deref(),
+    };
+
+    let readiness = TableReadiness {
+        min_records,
+        after_snapshot_id,
+    };
+
+    let deadline = time::Instant::now() + timeout;
+    eprintln!("Waiting for table {table_name:?} on {at_ref:?}");
+
+    let res = bauplan::wait_for_table(
+        req,
+        WatchSchedule::new(time::Duration::from_secs(poll_interval.max(1))),
+        |req| cli.roundtrip(req),
+        |req| cli.roundtrip(req),
+        |e| e.downcast_ref::<ApiError>(),
+        |t| Ok(readiness.is_ready(t)),
+        |delay| {
+            let now = time::Instant::now();
+            thread::sleep(delay.min(deadline.saturating_duration_since(now)));
+            Ok(now + delay < deadline)
+        },
+    )?;
+
+    let table = match res {
+        TableWait::Ready(table) => table,
+        TableWait::TimedOut(last) => {
+            let seen = match last {
+                Some(t) => format!(
+                    "last seen with {} records at snapshot {}",
+                    t.records.unwrap_or(0),
+                    t.current_snapshot_id
+                        .map_or_else(|| "none".to_owned(), |id| id.to_string()),
+                ),
+                None => "it doesn't exist".to_owned(),
+            };
+
+            bail!(
+                "table {table_name:?} on {at_ref:?} wasn't ready after {}; {seen}",
+                humantime::format_duration(timeout)
+            );
+        }
+    };
+
+    match cli.global.output {
+        Output::Json => emit_json(&table)?,
+        Output::Tty => eprintln!(
+            "Table {} is ready ({} records)",
+            table.fqn(),
+            table.records.unwrap_or(0)
+        ),
+    }
+
+    Ok(())
+}
+
+impl Render for Table {
+    fn render_table(&self, w: &mut dyn io::Write, wide: bool) -> io::Result<()> {
+        if !wide {
+            writeln!(w, "Namespace:\t{}", self.namespace)?;
+            writeln!(w, "Kind:\t{}", self.kind)?;
+            writeln!(w, "Records:\t{}", or_dash(self.records))?;
+            writeln!(w, "Size:\t{}", or_dash(self.size.map(format_bytes)))?;
+            writeln!(w, "Last updated:\t{}", self.last_updated_at.to_rfc3339())?;
+            writeln!(w, "Snapshots:\t{}", or_dash(self.snapshots))?;
+            writeln!(w, "Snapshot ID:\t{}", or_dash(self.current_snapshot_id))?;
+            writeln!(w, "Partitions:\t{}", format_partitions(&self.partitions))?;
+            writeln!(w)?;
+            return TableSchema(self).render_table(w, false);
+        }
+
+        writeln!(w, "ID:\t{}", self.id)?;
+        writeln!(w, "Name:\t{}", self.fqn())?;
+        writeln!(w, "Kind:\t{}", self.kind)?;
+        writeln!(w, "Records:\t{}", or_dash(self.records))?;
+        writeln!(w, "Size:\t{}", or_dash(self.size.map(format_bytes)))?;
+        writeln!(w, "Last updated:\t{}", self.last_updated_at.to_rfc3339())?;
+        writeln!(w, "Snapshots:\t{}", or_dash(self.snapshots))?;
+        writeln!(w, "Snapshot ID:\t{}", or_dash(self.current_snapshot_id))?;
+        writeln!(w, "Schema ID:\t{}", or_dash(self.current_schema_id))?;
+        writeln!(w, "Metadata:\t{}", self.metadata_location)?;
+        for PartitionField { name, transform } in &self.partitions {
+            writeln!(w, "Partition:\t{transform}({name})")?;
+        }
+        for (key, value) in &self.properties {
+            writeln!(w, "Property:\t{key}={value}")?;
+        }
+
+        writeln!(w)?;
+        TableSchema(self).render_table(w, true)
+    }
+}
+
+/// Just the schema of a table, as printed by `table get --schema-only`. It
+/// serializes as the whole table, so JSON and YAML output is unchanged.
+#[derive(Serialize)]
+#[serde(transparent)]
+struct TableSchema<'a>(&'a Table);
+
+impl Render for TableSchema<'_> {
+    fn render_table(&self, w: &mut dyn io::Write, wide: bool) -> io::Result<()> {
+        if !wide {
+            writeln!(w, "NAME\tREQUIRED\tTYPE")?;
             for TableField {
                 name,
                 required,
                 r#type,
                 ..
-            } in resp.fields
+            } in &self.0.fields
             {
-                writeln!(&mut tw, "{name}\t{required}\t{type}")?;
+                writeln!(w, "{name}\t{required}\t{type}")?;
             }
 
-            tw.flush()?;
+            return Ok(());
+        }
+
+        writeln!(w, "ID\tNAME\tREQUIRED\tTYPE")?;
+        for TableField {
+            id,
+            name,
+            required,
+            r#type,
+        } in &self.0.fields
+        {
+            writeln!(w, "{id}\t{name}\t{required}\t{type}")?;
         }
+
+        Ok(())
     }
+}
 
-    Ok(())
+/// Formats a partition spec on one line, like
+/// `day(created_at), identity(region)`, or a dash if the table isn't
+/// partitioned.
+fn format_partitions(partitions: &[PartitionField]) -> String {
+    if partitions.is_empty() {
+        return "-".to_owned();
+    }
+
+    partitions
+        .iter()
+        .map(|PartitionField { name, transform }| format!("{transform}({name})"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A list of tables, as printed by `branch get`.
+impl Render for [Table] {
+    fn render_table(&self, w: &mut dyn io::Write, wide: bool) -> io::Result<()> {
+        if !wide {
+            writeln!(w, "NAMESPACE\tNAME\tKIND")?;
+            for table in self {
+                writeln!(w, "{}\t{}\t{}", table.namespace, table.name, table.kind)?;
+            }
+
+            return Ok(());
+        }
+
+        writeln!(w, "NAMESPACE\tNAME\tKIND\tRECORDS\tSIZE\tLAST UPDATED\tID")?;
+        for table in self {
+            writeln!(
+                w,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                table.namespace,
+                table.name,
+                table.kind,
+                or_dash(table.records),
+                or_dash(table.size.map(format_bytes)),
+                table.last_updated_at.to_rfc3339(),
+                table.id,
+            )?;
+        }
+
+        Ok(())
+    }
 }
 
 fn handle_delete_table(
@@ -453,6 +1138,7 @@ fn handle_delete_table(
     TableRmArgs {
         table_name,
         branch,
+        namespace,
         if_exists,
         commit_body,
     }: TableRmArgs,
@@ -461,28 +1147,129 @@ fn handle_delete_table(
         .as_deref()
         .or(cli.profile.active_branch.as_deref())
         .unwrap_or("main");
+    cli.check_write_branch(branch)?;
+
+    let req = DeleteTable {
+        name: &table_name,
+        branch,
+        namespace: namespace.as_deref(),
+        commit: CommitOptions {
+            body: commit_body.as_deref(),
+            properties: Default::default(),
+        },
+    };
+
+    let res = match cli.roundtrip(req) {
+        Ok(head) => {
+            eprintln!("Deleted table {table_name:?}");
+            RefChange::changed(head)
+        }
+        Err(e) => match api_err(&e).and_then(DeleteTable::missing) {
+            Some(head) if if_exists => {
+                eprintln!("Table {table_name:?} does not exist");
+                RefChange::unchanged(head.cloned())
+            }
+            _ => {
+                return Err(with_namespace_candidates(
+                    cli,
+                    e,
+                    &table_name,
+                    namespace.as_deref(),
+                    branch,
+                ));
+            }
+        },
+    };
+
+    if cli.global.output == Output::Json {
+        emit_json(&res)?;
+    }
+
+    Ok(())
+}
+
+/// If the server couldn't pick a namespace for a bare table name, or didn't
+/// find the table in the default namespace, looks up the namespaces that
+/// contain a table with that name and adds them to the error, so that the
+/// user can choose one with --namespace.
+fn with_namespace_candidates(
+    cli: &Cli,
+    err: anyhow::Error,
+    table_name: &str,
+    namespace: Option<&str>,
+    at_ref: &str,
+) -> anyhow::Error {
+    // Keep the lookup cheap, since it's only for a hint.
+    const HINT_TIMEOUT: time::Duration = time::Duration::from_secs(3);
+
+    let unresolved = match api_err_kind(&err) {
+        Some(ApiErrorKind::NamespaceUnresolved { .. }) => true,
+        Some(ApiErrorKind::TableNotFound { .. })
+            if namespace.is_none()
+                && !table_name.contains('.')
+                && cli.global.output == Output::Tty =>
+        {
+            false
+        }
+        _ => return err,
+    };
+
+    let req = GetTables {
+        at_ref,
+        filter_by_name: Some(table_name),
+        filter_by_namespace: None,
+    };
 
-    let req = DeleteTable {
-        name: &table_name,
-        branch,
-        namespace: None,
-        commit: CommitOptions {
-            body: commit_body.as_deref(),
-            properties: Default::default(),
-        },
+    let tables = bauplan::paginate(req, Some(NAMESPACE_HINT_LIMIT), |r| {
+        cli.roundtrip_with_timeout(r, Some(HINT_TIMEOUT))
+    });
+    let Ok(tables) = tables else {
+        return err;
     };
 
-    if let Err(e) = cli.roundtrip(req) {
-        if if_exists && matches!(api_err_kind(&e), Some(ApiErrorKind::TableNotFound { .. })) {
-            eprintln!("Table {table_name:?} does not exist");
-            return Ok(());
-        } else {
-            return Err(e);
-        }
+    let tables = tables.map_while(Result::ok);
+    if !unresolved {
+        return match qualified_name_hint(table_name, tables) {
+            Some(hint) => err.context(format!("table {table_name:?} not found; {hint}")),
+            None => err,
+        };
     }
 
-    eprintln!("Deleted table {table_name:?}");
-    Ok(())
+    let namespaces: Vec<String> = tables
+        .filter(|t| t.name == table_name)
+        .map(|t| t.namespace)
+        .collect();
+    if namespaces.is_empty() {
+        return err;
+    }
+
+    err.context(format!(
+        "table {table_name:?} exists in multiple namespaces ({}); use --namespace to pick one",
+        namespaces.join(", ")
+    ))
+}
+
+/// Validates the --search-uri and --file-format values, returning the value
+/// for the `search_string` field of the request.
+fn encode_search_uri(url: &url::Url, format: search_uri::FileFormat) -> anyhow::Result<String> {
+    let search_string = search_uri::encode(url.as_str())?;
+    format.check(url.as_str())?;
+    Ok(search_string)
+}
+
+/// A table creation plan, along with what the planning job found.
+struct CreatedPlan {
+    job_id: String,
+    yaml: String,
+    can_auto_apply: bool,
+    files_matched: usize,
+}
+
+impl CreatedPlan {
+    /// Prints a one-line summary of the files the plan matched.
+    fn print_summary(&self) {
+        eprintln!("Matched {} files", self.files_matched);
+    }
 }
 
 async fn create_plan(
@@ -490,7 +1277,9 @@ async fn create_plan(
     client: &mut grpc::Client,
     req: commanderpb::TableCreatePlanRequest,
     progress: ProgressBar,
-) -> anyhow::Result<(String, bool)> {
+    events: Option<&EventRecorder>,
+    job_timeout: Option<time::Duration>,
+) -> anyhow::Result<CreatedPlan> {
     let resp = client
         .table_create_plan(cli.traced(req))
         .await?
@@ -507,20 +1296,27 @@ async fn create_plan(
     monitor_job_progress(
         cli,
         client,
-        job_id,
+        job_id.clone(),
         "import planning job",
         progress.clone(),
+        events,
+        job_timeout,
         ctrl_c,
         |event| {
-            if let RunnerEvent::TableCreatePlanDoneEvent(ev) = event {
-                if !ev.error_message.is_empty() {
-                    res = Err(anyhow!("plan creation failed: {}", ev.error_message));
+            if let JobEvent::TableCreatePlanned(plan) = event {
+                if let Some(error) = plan.error {
+                    res = Err(anyhow!("plan creation failed: {error}"));
                 } else {
-                    res = Ok((ev.plan_as_yaml, ev.can_auto_apply));
+                    res = Ok(CreatedPlan {
+                        job_id: job_id.clone(),
+                        files_matched: plan.files_to_be_imported.len(),
+                        yaml: plan.plan_yaml,
+                        can_auto_apply: plan.can_auto_apply,
+                    });
 
                     info!(
-                        can_auto_apply = ev.can_auto_apply,
-                        files = ev.files_to_be_imported.len(),
+                        can_auto_apply = plan.can_auto_apply,
+                        files = plan.files_to_be_imported.len(),
                         "plan created"
                     );
                 }
@@ -532,12 +1328,32 @@ async fn create_plan(
     res
 }
 
+/// Formats a byte count with a binary unit, e.g. "1.5 GiB".
+fn format_bytes(n: u64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB", "PiB"];
+
+    if n < 1024 {
+        return format!("{n} B");
+    }
+
+    let mut value = n as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{value:.1} {}", UNITS[unit])
+}
+
 async fn apply_plan(
     cli: &Cli,
     client: &mut grpc::Client,
     req: commanderpb::TableCreatePlanApplyRequest,
     progress: &indicatif::ProgressBar,
-) -> anyhow::Result<()> {
+    events: Option<&EventRecorder>,
+    job_timeout: Option<time::Duration>,
+) -> anyhow::Result<String> {
     let resp = client
         .table_create_plan_apply(cli.traced(req))
         .await
@@ -554,15 +1370,30 @@ async fn apply_plan(
     monitor_job_progress(
         cli,
         client,
-        job_id,
+        job_id.clone(),
         "import job",
         progress.clone(),
+        events,
+        job_timeout,
         ctrl_c,
         |_| {},
     )
     .await?;
 
-    Ok(())
+    Ok(job_id)
+}
+
+/// What `table create-plan` reports about the planning job with `-O json`.
+#[derive(Serialize)]
+struct PlanDetails<'a> {
+    files_matched: usize,
+    can_auto_apply: bool,
+    /// Where the plan was saved, with `--save-plan`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    plan_file: Option<&'a Path>,
+    /// The plan itself, if it wasn't saved to a file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    plan_yaml: Option<&'a str>,
 }
 
 async fn handle_create_plan(cli: &Cli, args: TableCreatePlanArgs) -> anyhow::Result<()> {
@@ -571,6 +1402,7 @@ async fn handle_create_plan(cli: &Cli, args: TableCreatePlanArgs) -> anyhow::Res
         branch,
         namespace,
         search_uri,
+        file_format,
         partitioned_by,
         replace,
         save_plan,
@@ -581,21 +1413,24 @@ async fn handle_create_plan(cli: &Cli, args: TableCreatePlanArgs) -> anyhow::Res
     let mut client = grpc::Client::new_lazy(&cli.profile, timeout)?;
 
     let branch = branch.or_else(|| cli.profile.active_branch.clone());
+    let namespace = namespace.or_else(|| cli.profile.default_namespace.clone());
+    let common = job_request_common(cli, arg, None, None)?;
+    let search_string = encode_search_uri(&search_uri, file_format)?;
 
     let req = commanderpb::TableCreatePlanRequest {
-        job_request_common: Some(job_request_common(cli, arg, None)),
+        job_request_common: Some(common),
         branch_name: branch,
         table_name: name,
         namespace,
-        search_string: search_uri.to_string(),
+        search_string,
         table_replace: replace,
         table_partitioned_by: partitioned_by,
     };
 
     let progress = cli.new_spinner().with_message("Creating plan...");
 
-    let yaml = match create_plan(cli, &mut client, req, progress.clone()).await {
-        Ok((yaml, _)) => yaml,
+    let plan = match create_plan(cli, &mut client, req, progress.clone(), None, None).await {
+        Ok(plan) => plan,
         Err(e) => {
             progress.finish_with_failed();
             return Err(e);
@@ -603,11 +1438,25 @@ async fn handle_create_plan(cli: &Cli, args: TableCreatePlanArgs) -> anyhow::Res
     };
 
     progress.finish_with_done();
-    if let Some(path) = save_plan {
-        std::fs::write(&path, &yaml)?;
+    plan.print_summary();
+
+    if let Some(path) = &save_plan {
+        plan_file::write(path, &plan.yaml)?;
         info!(path = %path.display(), "plan saved");
-    } else {
-        print!("{}", yaml);
+    }
+
+    match cli.global.output {
+        Output::Json => emit_json(&JobResult::complete(
+            plan.job_id,
+            PlanDetails {
+                files_matched: plan.files_matched,
+                can_auto_apply: plan.can_auto_apply,
+                plan_file: save_plan.as_deref(),
+                plan_yaml: save_plan.is_none().then_some(plan.yaml.as_str()),
+            },
+        ))?,
+        Output::Tty if save_plan.is_none() => print!("{}", plan.yaml),
+        Output::Tty => (),
     }
 
     Ok(())
@@ -621,15 +1470,22 @@ async fn handle_apply_plan(cli: &Cli, args: TableCreatePlanApplyArgs) -> anyhow:
     } = args;
 
     let plan_yaml = match plan {
-        Some(path) => std::fs::read_to_string(&path)?,
+        Some(path) => {
+            if !plan_file::verify(&path)?.verified {
+                warn!(
+                    "{} has no checksum file, so it can't be checked for changes since it was saved",
+                    path.display()
+                );
+            }
+
+            input::read_text_file(&path)?
+        }
         None => {
             if std::io::stdin().is_terminal() {
                 bail!("no plan provided; use --plan <file> or pipe YAML to stdin");
             }
 
-            let mut buf = String::new();
-            std::io::stdin().read_to_string(&mut buf)?;
-            buf
+            input::read_text_stdin()?
         }
     };
 
@@ -637,38 +1493,115 @@ async fn handle_apply_plan(cli: &Cli, args: TableCreatePlanApplyArgs) -> anyhow:
     let mut client = grpc::Client::new_lazy(&cli.profile, timeout)?;
 
     let req = commanderpb::TableCreatePlanApplyRequest {
-        job_request_common: Some(job_request_common(cli, arg, priority)),
+        job_request_common: Some(job_request_common(cli, arg, priority, None)?),
         plan_yaml,
     };
 
     let progress = cli.new_spinner().with_message("Applying plan...");
 
-    if let Err(e) = apply_plan(cli, &mut client, req, &progress).await {
-        progress.finish_with_failed();
-        return Err(e);
-    }
+    let job_id = match apply_plan(cli, &mut client, req, &progress, None, None).await {
+        Ok(job_id) => job_id,
+        Err(e) => {
+            progress.finish_with_failed();
+            return Err(e);
+        }
+    };
 
     progress.finish_with_done();
+    if cli.global.output == Output::Json {
+        emit_json(&JobResult::complete(job_id, ()))?;
+    }
+
+    Ok(())
+}
+
+/// The result of `table plan verify`, as printed with `-O json`.
+#[derive(Serialize)]
+struct VerifiedPlan<'a> {
+    file: &'a Path,
+    #[serde(flatten)]
+    checksum: &'a plan_file::Checksum,
+}
+
+fn handle_verify_plan(cli: &Cli, args: TablePlanVerifyArgs) -> anyhow::Result<()> {
+    let TablePlanVerifyArgs { plan, update } = args;
+
+    let yaml = input::read_text_file(&plan)?;
+    serde_yaml::from_str::<serde_yaml::Value>(&yaml)
+        .map_err(|e| anyhow!("{} is not valid YAML: {e}", plan.display()))?;
+
+    let checksum = if update {
+        plan_file::update(&plan)?
+    } else {
+        plan_file::verify(&plan)?
+    };
+
+    match cli.global.output {
+        Output::Json => emit_json(&VerifiedPlan {
+            file: &plan,
+            checksum: &checksum,
+        })?,
+        Output::Tty => {
+            let status = if checksum.verified {
+                format!("{GREEN}verified{GREEN:#}")
+            } else {
+                format!("{YELLOW}missing{YELLOW:#}")
+            };
+
+            let mut tw = TabWriter::new(anstream::stdout()).ansi(true);
+            writeln!(tw, "File\t{}", plan.display())?;
+            writeln!(tw, "Size\t{} bytes", checksum.size)?;
+            writeln!(tw, "SHA-256\t{}", checksum.sha256)?;
+            writeln!(tw, "Checksum\t{status}")?;
+            writeln!(tw, "YAML\tvalid")?;
+            tw.flush()?;
+        }
+    }
+
+    if !checksum.verified {
+        warn!(
+            "no checksum file found at {}",
+            plan_file::sidecar_path(&plan).display()
+        );
+    }
+
     Ok(())
 }
 
+/// What `table create` reports with `-O json`, besides the import job.
+#[derive(Serialize)]
+struct CreatedTable<'a> {
+    plan_job_id: &'a str,
+    files_matched: usize,
+}
+
 async fn handle_create_table(cli: &Cli, args: TableCreateArgs) -> anyhow::Result<()> {
     let TableCreateArgs {
         table_name: name,
         branch,
         namespace,
         search_uri,
+        file_format,
         partitioned_by,
         replace,
         arg,
         priority,
+        events_out,
+        job_timeout,
     } = args;
 
     let timeout = cli.timeout.unwrap_or(time::Duration::from_secs(1800));
     let mut client = grpc::Client::new_lazy(&cli.profile, timeout)?;
+    let events = create_event_recorder(events_out.as_deref())?;
 
     let branch = branch.or_else(|| cli.profile.active_branch.clone());
-    let common = job_request_common(cli, arg, priority);
+    if let Some(branch) = &branch {
+        cli.check_write_branch(branch)?;
+    }
+
+    let namespace = namespace.or_else(|| cli.profile.default_namespace.clone());
+    let common = job_request_common(cli, arg, priority, None)?;
+    let search_string = encode_search_uri(&search_uri, file_format)?;
 
     // Step 1: create the plan.
     let plan_req = commanderpb::TableCreatePlanRequest {
@@ -676,25 +1609,34 @@ async fn handle_create_table(cli: &Cli, args: TableCreateArgs) -> anyhow::Result
         branch_name: branch,
         table_name: name,
         namespace,
-        search_string: search_uri.to_string(),
+        search_string,
         table_replace: replace,
         table_partitioned_by: partitioned_by,
     };
 
     let progress = cli.new_spinner().with_message("Creating plan...");
 
-    let (yaml, can_auto_apply) =
-        match create_plan(cli, &mut client, plan_req, progress.clone()).await {
-            Ok(v) => v,
-            Err(e) => {
-                progress.finish_with_failed();
-                return Err(e);
-            }
-        };
+    let plan = match create_plan(
+        cli,
+        &mut client,
+        plan_req,
+        progress.clone(),
+        events.as_ref(),
+        job_timeout,
+    )
+    .await
+    {
+        Ok(plan) => plan,
+        Err(e) => {
+            progress.finish_with_failed();
+            return Err(e);
+        }
+    };
 
     progress.finish_with_done();
+    plan.print_summary();
 
-    if !can_auto_apply {
+    if !plan.can_auto_apply {
         bail!(
             "plan has schema conflicts and cannot be auto-applied; \
              use `table create-plan` and `table create-plan-apply` instead"
@@ -707,15 +1649,37 @@ async fn handle_create_table(cli: &Cli, args: TableCreateArgs) -> anyhow::Result
 
     let apply_req = commanderpb::TableCreatePlanApplyRequest {
         job_request_common: Some(common),
-        plan_yaml: yaml,
+        plan_yaml: plan.yaml,
     };
 
-    if let Err(e) = apply_plan(cli, &mut client, apply_req, &progress).await {
-        progress.finish_with_failed();
-        return Err(e);
-    }
+    let job_id = match apply_plan(
+        cli,
+        &mut client,
+        apply_req,
+        &progress,
+        events.as_ref(),
+        job_timeout,
+    )
+    .await
+    {
+        Ok(job_id) => job_id,
+        Err(e) => {
+            progress.finish_with_failed();
+            return Err(e);
+        }
+    };
 
     progress.finish_with_done();
+    if cli.global.output == Output::Json {
+        emit_json(&JobResult::complete(
+            job_id,
+            CreatedTable {
+                plan_job_id: &plan.job_id,
+                files_matched: plan.files_matched,
+            },
+        ))?;
+    }
+
     Ok(())
 }
 
@@ -725,25 +1689,38 @@ async fn handle_import_data(cli: &Cli, args: TableImportArgs) -> anyhow::Result<
         branch,
         namespace,
         search_uri,
+        file_format,
         continue_on_error,
         import_duplicate_files,
         best_effort,
         detach,
         arg,
         priority,
+        name: job_name,
+        events_out,
+        job_timeout,
     } = args;
 
     let timeout = cli.timeout.unwrap_or(time::Duration::from_secs(1800));
     let mut client = grpc::Client::new_lazy(&cli.profile, timeout)?;
+    let events = create_event_recorder(events_out.as_deref())?;
 
     let branch = branch.or_else(|| cli.profile.active_branch.clone());
+    if let Some(branch) = &branch {
+        cli.check_write_branch(branch)?;
+    }
+
+    let namespace = namespace.or_else(|| cli.profile.default_namespace.clone());
+    let history = ImportHistory::new(branch.clone());
+    let common = job_request_common(cli, arg, priority, job_name)?;
+    let search_string = encode_search_uri(&search_uri, file_format)?;
 
     let req = commanderpb::TableDataImportRequest {
-        job_request_common: Some(job_request_common(cli, arg, priority)),
+        job_request_common: Some(common),
         branch_name: branch,
         table_name: name,
         namespace,
-        search_string: search_uri.to_string(),
+        search_string,
         import_duplicate_files,
         best_effort,
         continue_on_error,
@@ -769,32 +1746,135 @@ async fn handle_import_data(cli: &Cli, args: TableImportArgs) -> anyhow::Result<
         progress.finish_with_status(spinner::STARTED);
         eprintln!("\nJob {job_id} is now running in detached mode.\n");
         eprintln!("Tip: use \"bauplan job <command>\" to list and inspect running jobs.");
+        if cli.global.output == Output::Json {
+            emit_json(&JobResult::detached(job_id))?;
+        }
+
         return Ok(());
     }
 
     let ctrl_c = tokio::signal::ctrl_c();
     futures::pin_mut!(ctrl_c);
 
-    if let Err(e) = monitor_job_progress(
+    let res = monitor_job_progress(
         cli,
         &mut client,
-        job_id,
+        job_id.clone(),
         "job",
         progress.clone(),
+        events.as_ref(),
+        job_timeout,
         ctrl_c,
-        |_| {},
+        |_| (),
     )
-    .await
-    {
+    .await;
+
+    if let Err(e) = res {
         progress.finish_with_failed();
+        // There's no failure code for a rejected duplicate import. It fails
+        // as a user error, which is when the earlier imports are worth showing.
+        let user_error = matches!(
+            e.downcast_ref::<grpc::JobError>(),
+            Some(grpc::JobError::Failed(
+                commanderpb::job_failure::ErrorCode::RuntimeTaskUserError,
+                _
+            ))
+        );
+
+        if user_error && !import_duplicate_files {
+            print_import_history(cli, &mut client, &history).await;
+            return Err(e.context(
+                "if the files were already imported, check the earlier import jobs listed above before passing --import-duplicate-files",
+            ));
+        }
+
         return Err(e);
     }
 
     progress.finish_with_done();
+    print_import_summary(progress.elapsed());
+
     info!("data imported successfully");
+    if cli.global.output == Output::Json {
+        emit_json(&JobResult::complete(job_id, ()))?;
+    }
+
     Ok(())
 }
 
+/// Prints how long the import took. The runner doesn't report progress
+/// through the files, so there are no counts to summarize.
+fn print_import_summary(elapsed: time::Duration) {
+    let elapsed = humantime::format_duration(time::Duration::from_secs(elapsed.as_secs()));
+    eprintln!("Imported data in {elapsed}");
+}
+
+/// The most earlier import jobs to list when an import fails.
+const MAX_HISTORY_SHOWN: usize = 10;
+
+/// Fetches the recent import jobs into the branch, newest first.
+async fn import_history(
+    cli: &Cli,
+    client: &mut grpc::Client,
+    history: &ImportHistory,
+) -> anyhow::Result<Vec<Job>> {
+    let mut jobs: Vec<Job> = client
+        .get_jobs(cli.traced(history.jobs_request()))
+        .await
+        .map_err(format_grpc_status)?
+        .into_inner()
+        .jobs
+        .into_iter()
+        .map(Job::from)
+        .collect();
+
+    if let Some(req) = history.context_request(&jobs) {
+        let contexts = client
+            .get_job_context(cli.traced(req))
+            .await
+            .map_err(format_grpc_status)?
+            .into_inner()
+            .job_contexts;
+        history.apply(&mut jobs, contexts);
+    }
+
+    Ok(jobs)
+}
+
+/// Lists the earlier import jobs into the branch, after an import was
+/// rejected for duplicating them. This is best-effort, since the import
+/// already failed.
+async fn print_import_history(cli: &Cli, client: &mut grpc::Client, history: &ImportHistory) {
+    let jobs = match import_history(cli, client, history).await {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            warn!(error = %e, "failed to list earlier import jobs");
+            return;
+        }
+    };
+
+    if jobs.is_empty() {
+        return;
+    }
+
+    eprintln!("Earlier import jobs into the branch:");
+    for job in jobs.iter().take(MAX_HISTORY_SHOWN) {
+        let when = job
+            .created_at
+            .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+            .unwrap_or_else(|| "unknown time".to_owned());
+        eprintln!(
+            "  {} (by {}, {when}, {})",
+            job.id, job.user, job.human_readable_status
+        );
+    }
+
+    let more = jobs.len().saturating_sub(MAX_HISTORY_SHOWN);
+    if more > 0 {
+        eprintln!("  ... and {more} more");
+    }
+}
+
 async fn handle_create_external(cli: &Cli, args: TableCreateExternalArgs) -> anyhow::Result<()> {
     let TableCreateExternalArgs {
         table_name,
@@ -817,9 +1897,10 @@ async fn handle_create_external(cli: &Cli, args: TableCreateExternalArgs) -> any
     let mut client = grpc::Client::new_lazy(&cli.profile, timeout)?;
 
     let branch = branch.or_else(|| cli.profile.active_branch.clone());
+    let namespace = namespace.or_else(|| cli.profile.default_namespace.clone());
 
     let req = commanderpb::ExternalTableCreateRequest {
-        job_request_common: Some(job_request_common(cli, arg, priority)),
+        job_request_common: Some(job_request_common(cli, arg, priority, None)?),
         branch_name: branch,
         table_name,
         namespace,
@@ -853,6 +1934,10 @@ async fn handle_create_external(cli: &Cli, args: TableCreateExternalArgs) -> any
         progress.finish_and_clear();
         eprintln!("\nJob {job_id} is now running in detached mode.\n");
         eprintln!("Tip: use \"bauplan job <command>\" to list and inspect running jobs.");
+        if cli.global.output == Output::Json {
+            emit_json(&JobResult::detached(job_id))?;
+        }
+
         return Ok(());
     }
 
@@ -862,17 +1947,33 @@ async fn handle_create_external(cli: &Cli, args: TableCreateExternalArgs) -> any
     monitor_job_progress(
         cli,
         &mut client,
-        job_id,
+        job_id.clone(),
         "job",
         progress.clone(),
+        None,
+        None,
         ctrl_c,
         |_| {},
     )
     .await?;
 
+    if cli.global.output == Output::Json {
+        emit_json(&JobResult::complete(job_id, ()))?;
+    }
+
     Ok(())
 }
 
+/// The result of `table create-external --metadata-json-uri`, as printed
+/// with `-O json`. No job is run, so there's no job ID.
+#[derive(Serialize)]
+struct RegisteredTable<'a> {
+    table_id: String,
+    namespace: &'a str,
+    name: &'a str,
+    branch: &'a str,
+}
+
 fn handle_create_external_from_metadata(
     cli: &Cli,
     args: TableCreateExternalArgs,
@@ -896,19 +1997,31 @@ fn handle_create_external_from_metadata(
         bail!("metadata JSON URI must use s3:// scheme");
     }
 
-    // Namespace is required for metadata mode, because it forms part of the
-    // iceberg endpoint.
-    let namespace = namespace.ok_or_else(|| {
-        anyhow!(
-            "namespace must be specified when creating from metadata-json-uri. \
-             This restriction will be lifted in future versions"
-        )
-    })?;
-
     let branch = branch
         .or_else(|| cli.profile.active_branch.clone())
         .unwrap_or_else(|| "main".to_string());
 
+    // The namespace forms part of the iceberg endpoint, so it has to be
+    // resolved here rather than by the server.
+    let (namespace, table_name) = match namespace {
+        Some(namespace) => (namespace, table_name),
+        None => match table_name.rsplit_once('.') {
+            Some((namespace, name)) => (namespace.to_owned(), name.to_owned()),
+            None => {
+                let config =
+                    cli.roundtrip(bauplan::iceberg::GetCatalogConfig { branch: &branch })?;
+                let Some(namespace) = config.default_namespace() else {
+                    bail!(
+                        "no namespace given for {table_name:?}; \
+                         pass --namespace or use namespace.table"
+                    );
+                };
+
+                (namespace.to_owned(), table_name)
+            }
+        },
+    };
+
     let req = bauplan::iceberg::RegisterTable {
         name: &table_name,
         metadata_location: &metadata_uri,
@@ -926,6 +2039,15 @@ fn handle_create_external_from_metadata(
         "registered external table"
     );
 
+    if cli.global.output == Output::Json {
+        emit_json(&RegisteredTable {
+            table_id: table_id.as_hyphenated().to_string(),
+            namespace: &namespace,
+            name: &table_name,
+            branch: &branch,
+        })?;
+    }
+
     Ok(())
 }
 
@@ -944,6 +2066,8 @@ fn handle_revert_table(cli: &Cli, args: TableRevertArgs) -> anyhow::Result<()> {
         .or(cli.profile.active_branch.as_deref())
         .unwrap_or("main");
 
+    let operation_id = bauplan::new_operation_id();
+    let commit_property = KeyValue::read_all(&commit_property)?;
     let req = RevertTable {
         name: &table_name,
         source_ref: &source_ref,
@@ -952,13 +2076,328 @@ fn handle_revert_table(cli: &Cli, args: TableRevertArgs) -> anyhow::Result<()> {
         replace,
         commit: CommitOptions {
             body: commit_body.as_deref(),
-            properties: commit_property.iter().map(KeyValue::as_strs).collect(),
+            properties: commit_property
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect(),
         },
+        operation_id: Some(&operation_id),
     };
 
-    let r#ref = cli.roundtrip(req)?;
+    let res = cli.send_operation(req)?;
+    if let OperationResult::AlreadyApplied(_) = res {
+        eprintln!("The revert was applied by an earlier attempt, whose response was lost");
+    }
+
+    let r#ref = res.into_ref();
     tracing::debug!(?r#ref, "Created ref");
     eprintln!("Reverted table {table_name:?} to {source_ref:?} in {into_branch:?}");
+    if cli.global.output == Output::Json {
+        emit_json(&RefChange::changed(r#ref))?;
+    }
+
+    Ok(())
+}
+
+fn handle_alter_table(cli: &Cli, args: TableAlterArgs) -> anyhow::Result<()> {
+    let TableAlterArgs {
+        table_name,
+        branch,
+        namespace,
+        add,
+        drop,
+        rename,
+        commit_body,
+        commit_property,
+        format,
+    } = args;
+
+    let branch = branch
+        .as_deref()
+        .or(cli.profile.active_branch.as_deref())
+        .unwrap_or("main");
+
+    let req = GetTable {
+        name: &table_name,
+        at_ref: branch,
+        namespace: namespace.as_deref(),
+    };
+
+    let table = cli.roundtrip(req).map_err(|e| {
+        with_namespace_candidates(cli, e, &table_name, namespace.as_deref(), branch)
+    })?;
+
+    let rename = KeyValue::read_all(&rename)?;
+    let commit_property = KeyValue::read_all(&commit_property)?;
+    let req = UpdateTableSchema {
+        name: &table_name,
+        branch,
+        namespace: namespace.as_deref(),
+        add_columns: add
+            .iter()
+            .map(|c| NewColumn {
+                name: &c.name,
+                r#type: &c.r#type,
+                required: c.required,
+            })
+            .collect(),
+        drop_columns: drop.iter().map(String::as_str).collect(),
+        rename_columns: rename
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect(),
+        expected_schema_id: table.current_schema_id,
+        commit: CommitOptions {
+            body: commit_body.as_deref(),
+            properties: commit_property
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect(),
+        },
+    };
+
+    req.validate(&table)
+        .map_err(|e| anyhow!("can't alter {}: {e}", table.fqn()))?;
+
+    // A conflict without a more specific kind means the schema changed
+    // since it was read.
+    let table = cli.roundtrip(req).map_err(|e| {
+        let conflict = matches!(
+            e.downcast_ref::<ApiError>(),
+            Some(ae) if ae.kind().is_none() && ae.status().as_u16() == 409
+        );
+
+        if conflict {
+            e.context(format!(
+                "the schema of {table_name:?} changed while it was being altered, run the command again"
+            ))
+        } else {
+            e
+        }
+    })?;
+
+    eprintln!("Altered table {:?} in {branch:?}", table.fqn());
+    render(&table, format.resolve(cli.global.output))
+}
+
+fn handle_sync_state(
+    cli: &Cli,
+    TableSyncStateArgs {
+        state_file,
+        r#ref,
+        no_commit,
+    }: TableSyncStateArgs,
+) -> anyhow::Result<()> {
+    // The state is recorded against the ref as given, so that a branch can
+    // be synced as it moves.
+    let sync_ref = r#ref
+        .or_else(|| cli.profile.active_branch.clone())
+        .unwrap_or_else(|| "main".to_owned());
+    let at_ref = cli.resolve_ref(&sync_ref)?;
+
+    let req = GetTables {
+        at_ref: &at_ref,
+        filter_by_name: None,
+        filter_by_namespace: None,
+    };
+
+    let tables = bauplan::paginate(req, None, |r| cli.roundtrip(r))?;
+    let sync = TableSync::new(&state_file, &sync_ref, tables)?;
+
+    match cli.global.output {
+        Output::Json => emit_json(&sync.delta)?,
+        Output::Tty => {
+            let delta = &sync.delta;
+            for t in &delta.added {
+                anstream::println!("{GREEN}+ {}{GREEN:#}", t.fqn());
+            }
+
+            for fqn in &delta.removed {
+                anstream::println!("{RED}- {fqn}{RED:#}");
+            }
+
+            for t in &delta.changed {
+                anstream::println!("{YELLOW}~ {}{YELLOW:#}", t.fqn());
+            }
+
+            if delta.is_empty() {
+                eprintln!("No changes since the last sync");
+            }
+        }
+    }
+
+    if !no_commit {
+        sync.commit()?;
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use chrono::DateTime;
+
+    use super::*;
+    use crate::cli::render::assert_round_trips;
+
+    fn table(name: &str) -> Table {
+        Table {
+            id: uuid::Uuid::new_v4(),
+            name: name.to_owned(),
+            namespace: "bauplan".to_owned(),
+            kind: TableKind::ExternalTable,
+            records: Some(891),
+            size: None,
+            last_updated_at: DateTime::from_timestamp_millis(1_700_000_000_123).unwrap(),
+            fields: vec![TableField {
+                id: 1,
+                name: "PassengerId".to_owned(),
+                required: false,
+                r#type: "long".to_owned(),
+            }],
+            snapshots: Some(3),
+            partitions: vec![PartitionField {
+                name: "created_at".to_owned(),
+                transform: "day".to_owned(),
+            }],
+            metadata_location: "s3://bucket/metadata/00001.metadata.json".to_owned(),
+            current_snapshot_id: Some(-42),
+            current_schema_id: None,
+            properties: BTreeMap::from([("owner".to_owned(), "yes: no".to_owned())]),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        assert_round_trips::<Table>(&table("titanic"));
+        assert_round_trips::<Vec<Table>>([table("a"), table("b")].as_slice());
+    }
+
+    /// A partitioned table, as returned by the catalog.
+    const PARTITIONED_TABLE: &str = r#"{
+        "id": "5b1f0c3e-8a4d-4c2b-9f6e-2d7a1e0b3c4f",
+        "name": "orders",
+        "namespace": "sales",
+        "kind": "TABLE",
+        "records": 1200,
+        "size": 3145728,
+        "last_updated_ms": 1700000000123,
+        "fields": [
+            {"id": 1, "name": "order_id", "required": true, "type": "long"},
+            {"id": 2, "name": "created_at", "required": false, "type": "timestamptz"}
+        ],
+        "snapshots": 7,
+        "partitions": [
+            {"name": "created_at", "transform": "day"},
+            {"name": "region", "transform": "identity"}
+        ],
+        "metadata_location": "s3://bucket/sales/orders/metadata/00007.metadata.json",
+        "current_snapshot_id": 5836491021,
+        "current_schema_id": 0,
+        "properties": {}
+    }"#;
+
+    fn render_lines(value: &(impl Render + ?Sized), wide: bool) -> Vec<String> {
+        let mut buf = Vec::new();
+        value.render_table(&mut buf, wide).unwrap();
+        String::from_utf8(buf)
+            .unwrap()
+            .lines()
+            .map(str::to_owned)
+            .collect()
+    }
+
+    #[test]
+    fn render_table_details() {
+        let table: Table = serde_json::from_str(PARTITIONED_TABLE).unwrap();
+
+        assert_eq!(
+            render_lines(&table, false),
+            [
+                "Namespace:\tsales",
+                "Kind:\tTABLE",
+                "Records:\t1200",
+                "Size:\t3.0 MiB",
+                "Last updated:\t2023-11-14T22:13:20.123+00:00",
+                "Snapshots:\t7",
+                "Snapshot ID:\t5836491021",
+                "Partitions:\tday(created_at), identity(region)",
+                "",
+                "NAME\tREQUIRED\tTYPE",
+                "order_id\ttrue\tlong",
+                "created_at\tfalse\ttimestamptz",
+            ]
+        );
+    }
+
+    #[test]
+    fn render_table_unpartitioned() {
+        let mut table: Table = serde_json::from_str(PARTITIONED_TABLE).unwrap();
+        table.partitions.clear();
+        table.snapshots = None;
+
+        let lines = render_lines(&table, false);
+        assert!(lines.contains(&"Snapshots:\t-".to_owned()), "{lines:?}");
+        assert!(lines.contains(&"Partitions:\t-".to_owned()), "{lines:?}");
+    }
+
+    #[test]
+    fn snapshot_age() {
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let ago = |secs| format_age(now - chrono::Duration::seconds(secs), now);
+
+        assert_eq!(ago(0), "just now");
+        assert_eq!(ago(-30), "just now");
+        assert_eq!(ago(45), "45s ago");
+        assert_eq!(ago(3 * 3600 + 125), "3h ago");
+        assert_eq!(ago(2 * 86400 + 3600), "2days ago");
+    }
+
+    #[test]
+    fn render_schema_only() {
+        let table: Table = serde_json::from_str(PARTITIONED_TABLE).unwrap();
+
+        assert_eq!(
+            render_lines(&TableSchema(&table), false),
+            [
+                "NAME\tREQUIRED\tTYPE",
+                "order_id\ttrue\tlong",
+                "created_at\tfalse\ttimestamptz",
+            ]
+        );
+        assert_eq!(
+            render_lines(&TableSchema(&table), true)[0],
+            "ID\tNAME\tREQUIRED\tTYPE"
+        );
+
+        // Other formats still include the whole table.
+        assert_eq!(
+            serde_json::to_value(TableSchema(&table)).unwrap(),
+            serde_json::to_value(&table).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_new_column() {
+        let column = |name: &str, r#type: &str, required| NewColumnArg {
+            name: name.to_owned(),
+            r#type: r#type.to_owned(),
+            required,
+        };
+
+        assert_eq!(
+            "total:decimal(10,2)".parse::<NewColumnArg>().unwrap(),
+            column("total", "decimal(10,2)", false)
+        );
+        assert_eq!(
+            "signup_date:date:required".parse::<NewColumnArg>().unwrap(),
+            column("signup_date", "date", true)
+        );
+
+        for s in ["notes", ":string", "notes:", "notes:string:optional"] {
+            assert!(s.parse::<NewColumnArg>().is_err(), "{s}");
+        }
+    }
+}
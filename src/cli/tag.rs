@@ -1,9 +1,13 @@
 use std::io::{Write as _, stdout};
 
-use bauplan::{ApiErrorKind, tag::*};
+use bauplan::tag::*;
 use tabwriter::TabWriter;
 
-use crate::cli::{Cli, Output, api_err_kind, color::CliExamples};
+use crate::cli::{
+    Cli, Output, api_err,
+    color::CliExamples,
+    render::{RefChange, emit_json, write_json_array},
+};
 
 #[derive(Debug, clap::Args)]
 pub(crate) struct TagArgs {
@@ -111,11 +115,7 @@ fn list_tags(cli: &Cli, TagLsArgs { name, limit }: TagLsArgs) -> anyhow::Result<
     let tags = bauplan::paginate(req, limit, |r| cli.roundtrip(r))?;
 
     match cli.global.output {
-        Output::Json => {
-            let all_tags = tags.collect::<anyhow::Result<Vec<_>>>()?;
-            serde_json::to_writer(stdout(), &all_tags)?;
-            println!();
-        }
+        Output::Json => write_json_array(&mut stdout().lock(), tags)?,
         Output::Tty => {
             let mut tw = TabWriter::new(stdout());
             writeln!(&mut tw, "NAME\tHASH")?;
@@ -139,26 +139,38 @@ fn create_tag(
         if_not_exists,
     }: TagCreateArgs,
 ) -> anyhow::Result<()> {
-    let from_ref = from_ref
-        .as_deref()
-        .or(cli.profile.active_branch.as_deref())
-        .unwrap_or("main");
+    let from_ref = cli.resolve_ref(
+        from_ref
+            .as_deref()
+            .or(cli.profile.active_branch.as_deref())
+            .unwrap_or("main"),
+    )?;
 
     let req = CreateTag {
         name: &tag_name,
-        from_ref,
+        from_ref: &from_ref,
     };
 
-    if let Err(e) = cli.roundtrip(req) {
-        if if_not_exists && matches!(api_err_kind(&e), Some(ApiErrorKind::TagExists { .. })) {
-            eprintln!("Tag {tag_name:?} already exists");
-            return Ok(());
-        } else {
-            return Err(e);
-        }
-    }
+    let tag = match cli.roundtrip(req) {
+        Ok(tag) => tag,
+        Err(e) => match api_err(&e).and_then(CreateTag::existing) {
+            Some(existing) if if_not_exists => {
+                eprintln!("Tag {tag_name:?} already exists");
+                if cli.global.output == Output::Json {
+                    emit_json(&RefChange::unchanged(Some(existing)))?;
+                }
+
+                return Ok(());
+            }
+            _ => return Err(e),
+        },
+    };
 
     eprintln!("Created tag {tag_name:?}");
+    if cli.global.output == Output::Json {
+        emit_json(&RefChange::changed(tag))?;
+    }
+
     Ok(())
 }
 
@@ -171,16 +183,24 @@ fn delete_tag(
 ) -> anyhow::Result<()> {
     let req = DeleteTag { name: &tag_name };
 
-    if let Err(e) = cli.roundtrip(req) {
-        if if_exists && matches!(api_err_kind(&e), Some(ApiErrorKind::TagNotFound { .. })) {
+    let tag = match cli.roundtrip(req) {
+        Ok(tag) => tag,
+        Err(e) if if_exists && api_err(&e).is_some_and(DeleteTag::missing) => {
             eprintln!("Tag {tag_name:?} does not exist");
+            if cli.global.output == Output::Json {
+                emit_json(&RefChange::<Tag>::unchanged(None))?;
+            }
+
             return Ok(());
-        } else {
-            return Err(e);
         }
-    }
+        Err(e) => return Err(e),
+    };
 
     eprintln!("Deleted tag {tag_name:?}");
+    if cli.global.output == Output::Json {
+        emit_json(&RefChange::changed(tag))?;
+    }
+
     Ok(())
 }
 
@@ -196,8 +216,11 @@ fn rename_tag(
         new_name: &new_tag_name,
     };
 
-    cli.roundtrip(req)?;
+    let tag = cli.roundtrip(req)?;
     eprintln!("Renamed tag {tag_name:?} to {new_tag_name:?}");
+    if cli.global.output == Output::Json {
+        emit_json(&RefChange::changed(tag))?;
+    }
 
     Ok(())
 }
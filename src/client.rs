@@ -0,0 +1,280 @@
+// ClientError wraps ApiError, which is just over the threshold.
+#![allow(clippy::result_large_err)]
+
+use std::time;
+
+use arrow::{array::RecordBatch, datatypes::Schema};
+use futures::TryStreamExt as _;
+
+use crate::{
+    ApiError, ApiErrorKind, ApiRequest, ApiResponse, CatalogRef, Profile, RetryBudget,
+    branch::{Branch, CreateBranch, MergeBranch, MergeCommitOptions},
+    commit::CommitOptions,
+    new_operation_id,
+    query::{QueryError, QueryRun},
+    table::{DeleteTable, GetTable, GetTables, Table},
+};
+
+#[cfg(feature = "async-client")]
+mod async_client;
+
+#[cfg(feature = "async-client")]
+pub use async_client::AsyncClient;
+
+/// How long a request may take when no timeout is set.
+const DEFAULT_TIMEOUT: time::Duration = time::Duration::from_secs(30);
+
+/// An error from a [`Client`] or `AsyncClient` call.
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    /// The profile isn't usable, for example because it has no API key.
+    #[error(transparent)]
+    Profile(#[from] crate::config::Error),
+    /// The request couldn't be built.
+    #[error("error building request: {0}")]
+    Request(#[from] http::Error),
+    /// The request couldn't be sent, or the response couldn't be read.
+    #[error("transport error: {0}")]
+    Transport(#[from] ureq::Error),
+    /// The request couldn't be sent by an `AsyncClient`, or the response
+    /// couldn't be read.
+    #[cfg(feature = "async-client")]
+    #[error("transport error: {0}")]
+    AsyncTransport(#[from] reqwest::Error),
+    /// The API returned an error.
+    #[error(transparent)]
+    Api(#[from] ApiError),
+    /// A query failed.
+    #[error(transparent)]
+    Query(#[from] QueryError),
+}
+
+impl ClientError {
+    /// The kind of API error, if the API returned one.
+    pub fn kind(&self) -> Option<&ApiErrorKind> {
+        match self {
+            ClientError::Api(e) => e.kind(),
+            _ => None,
+        }
+    }
+
+    /// Whether the request may have been applied even though it failed.
+    fn response_lost(&self) -> bool {
+        match self {
+            ClientError::Transport(ureq::Error::Timeout(_) | ureq::Error::Io(_)) => true,
+            ClientError::Api(e) => e.response_lost(),
+            _ => false,
+        }
+    }
+}
+
+/// A blocking client for the Bauplan API.
+///
+/// This covers common operations; any other request can be sent with
+/// [`Client::roundtrip`]. Rate-limited requests are retried within the
+/// profile's `rate_limit_budget`, and merges are retried if their response
+/// is lost.
+///
+/// ```no_run
+/// # fn main() -> anyhow::Result<()> {
+/// let client = bauplan::Client::new(bauplan::Profile::from_default_env()?)?;
+/// let table = client.get_table("bauplan.taxi_fhvhv", "main")?;
+/// println!("Table: {} ({} records)", table.name, table.records.unwrap_or(0));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Client {
+    profile: Profile,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    /// Creates a client for the given profile, with a 30 second timeout for
+    /// each request.
+    pub fn new(profile: Profile) -> Result<Self, ClientError> {
+        Self::with_timeout(profile, DEFAULT_TIMEOUT)
+    }
+
+    /// Creates a client with a different timeout for each request. Queries
+    /// have their own timeout; see [`Client::run_query`].
+    pub fn with_timeout(profile: Profile, timeout: time::Duration) -> Result<Self, ClientError> {
+        profile.validate()?;
+
+        // Allows error responses to be parsed.
+        let cfg = ureq::config::Config::builder()
+            .http_status_as_error(false)
+            .tls_config(
+                ureq::tls::TlsConfig::builder()
+                    .root_certs(ureq::tls::RootCerts::PlatformVerifier)
+                    .build(),
+            )
+            .timeout_global(Some(timeout));
+
+        Ok(Self {
+            profile,
+            agent: ureq::Agent::new_with_config(cfg.build()),
+        })
+    }
+
+    /// The profile the client was created with.
+    pub fn profile(&self) -> &Profile {
+        &self.profile
+    }
+
+    /// Sends any API request, and parses the response.
+    pub fn roundtrip<T: ApiRequest>(&self, req: T) -> Result<T::Response, ClientError> {
+        req.validate()?;
+        let req = req.into_request(&self.profile)?;
+        let budget = RetryBudget::new(self.profile.rate_limit_budget);
+        let resp = crate::send_with_backoff(
+            &budget,
+            req,
+            |req| Ok::<_, ClientError>(self.agent.run(req)?.map(ureq::Body::into_reader)),
+            std::thread::sleep,
+        )?;
+
+        if let Some(summary) = budget.summary() {
+            tracing::warn!("{summary}");
+        }
+
+        Ok(<T::Response as ApiResponse>::from_response(resp)?)
+    }
+
+    /// Fetches a table. The name may include a namespace, like
+    /// `bauplan.taxi_fhvhv`.
+    pub fn get_table(&self, name: &str, at_ref: &str) -> Result<Table, ClientError> {
+        self.roundtrip(GetTable {
+            name,
+            at_ref,
+            namespace: None,
+        })
+    }
+
+    /// Lists the tables at a ref, fetching more pages as the iterator is
+    /// consumed. Returns at most `limit` tables, if set.
+    pub fn get_tables<'a>(
+        &'a self,
+        at_ref: &'a str,
+        limit: Option<usize>,
+    ) -> Result<impl Iterator<Item = Result<Table, ClientError>> + 'a, ClientError> {
+        let req = GetTables {
+            at_ref,
+            filter_by_name: None,
+            filter_by_namespace: None,
+        };
+
+        crate::paginate(req, limit, |req| self.roundtrip(req))
+    }
+
+    /// Creates a branch from a ref.
+    pub fn create_branch(&self, name: &str, from_ref: &str) -> Result<Branch, ClientError> {
+        self.roundtrip(CreateBranch { name, from_ref })
+    }
+
+    /// Merges a ref into a branch, and returns the new head of the branch.
+    /// If the response is lost, the merge is retried only if it wasn't
+    /// already applied.
+    pub fn merge_branch(
+        &self,
+        source_ref: &str,
+        into_branch: &str,
+    ) -> Result<CatalogRef, ClientError> {
+        let operation_id = new_operation_id();
+        let req = MergeBranch {
+            source_ref,
+            into_branch,
+            commit: MergeCommitOptions::default(),
+            operation_id: Some(&operation_id),
+        };
+
+        let res = crate::send_operation(
+            req,
+            |req| self.roundtrip(req),
+            |req| self.roundtrip(req),
+            ClientError::response_lost,
+            std::thread::sleep,
+        )?;
+
+        Ok(res.into_ref())
+    }
+
+    /// Deletes a table from a branch, and returns the new head of the
+    /// branch. The name may include a namespace.
+    pub fn delete_table(&self, name: &str, branch: &str) -> Result<CatalogRef, ClientError> {
+        self.roundtrip(DeleteTable {
+            name,
+            branch,
+            namespace: None,
+            commit: CommitOptions::default(),
+        })
+    }
+
+    /// Runs a query against a ref and waits for all of its results, for up
+    /// to [`DEFAULT_QUERY_TIMEOUT`](crate::query::DEFAULT_QUERY_TIMEOUT).
+    /// Use [`QueryRun`] directly to stream the results, or to set other
+    /// options.
+    ///
+    /// Like the other methods, this blocks, so it mustn't be called from
+    /// async code.
+    pub fn run_query(
+        &self,
+        sql: &str,
+        at_ref: &str,
+    ) -> Result<(Schema, Vec<RecordBatch>), ClientError> {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to create a tokio runtime");
+
+        let run = QueryRun::new(sql).at_ref(at_ref);
+        let res = rt.block_on(async {
+            let (_, schema, batches) = run.run(&self.profile).await?;
+            let batches = batches.try_collect().await?;
+            Ok::<_, QueryError>((schema, batches))
+        })?;
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requires_api_key() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let path = tmp.path().join("config.yaml");
+        std::fs::write(&path, "profiles:\n  default: {}\n")?;
+
+        let profile = Profile::read(&path, None)?;
+        assert!(matches!(Client::new(profile), Err(ClientError::Profile(_))));
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "_integration-tests"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn get_tables() -> anyhow::Result<()> {
+        let client = Client::new(Profile::from_default_env()?)?;
+
+        let table = client.get_table("bauplan.titanic", "main")?;
+        assert_eq!(table.name, "titanic");
+
+        let tables = client
+            .get_tables("main", Some(5))?
+            .collect::<Result<Vec<_>, _>>()?;
+        assert!(!tables.is_empty() && tables.len() <= 5);
+
+        let err = client.get_table("does_not_exist", "main").unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            Some(ApiErrorKind::TableNotFound { .. })
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+use std::time;
+
+use futures::{Stream, StreamExt as _, TryStreamExt as _, stream};
+
+use super::{ClientError, DEFAULT_TIMEOUT};
+use crate::{ApiRequest, ApiResponse, PaginatedResponse, Profile};
+
+/// An async client for the Bauplan API, using [reqwest].
+///
+/// Any request can be sent with [`AsyncClient::send`], and paginated
+/// requests can be streamed with [`AsyncClient::paginate`]. Unlike
+/// [`Client`](super::Client), rate-limited requests aren't retried.
+///
+/// ```no_run
+/// use bauplan::{AsyncClient, Profile, table::GetTables};
+/// use futures::TryStreamExt as _;
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let client = AsyncClient::new(Profile::from_default_env()?)?;
+///
+/// let req = GetTables {
+///     at_ref: "main",
+///     filter_by_name: None,
+///     filter_by_namespace: Some("bauplan"),
+/// };
+///
+/// let tables: Vec<_> = client.paginate(req, Some(10)).try_collect().await?;
+/// for table in tables {
+///     println!("Table: {} ({} records)", table.name, table.records.unwrap_or(0));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AsyncClient {
+    profile: Profile,
+    client: reqwest::Client,
+}
+
+impl AsyncClient {
+    /// Creates a client for the given profile, with a 30 second timeout for
+    /// each request.
+    pub fn new(profile: Profile) -> Result<Self, ClientError> {
+        Self::with_timeout(profile, DEFAULT_TIMEOUT)
+    }
+
+    /// Creates a client with a different timeout for each request.
+    pub fn with_timeout(profile: Profile, timeout: time::Duration) -> Result<Self, ClientError> {
+        let client = reqwest::Client::builder().timeout(timeout).build()?;
+        Self::with_client(profile, client)
+    }
+
+    /// Creates a client that sends requests with an existing reqwest client.
+    pub fn with_client(profile: Profile, client: reqwest::Client) -> Result<Self, ClientError> {
+        profile.validate()?;
+        Ok(Self { profile, client })
+    }
+
+    /// The profile the client was created with.
+    pub fn profile(&self) -> &Profile {
+        &self.profile
+    }
+
+    /// Sends any API request, and parses the response.
+    pub async fn send<R: ApiRequest>(&self, req: R) -> Result<R::Response, ClientError> {
+        req.validate()?;
+        let req: reqwest::Request = req.into_request(&self.profile)?.try_into()?;
+        let resp = self.client.execute(req).await?;
+
+        let mut builder = http::Response::builder()
+            .status(resp.status())
+            .version(resp.version());
+        if let Some(headers) = builder.headers_mut() {
+            headers.extend(resp.headers().clone());
+        }
+
+        let body = resp.bytes().await?;
+        let (parts, ()) = builder.body(())?.into_parts();
+        Ok(R::Response::from_response_parts(parts, body.as_ref())?)
+    }
+
+    /// Sends a paginated request, fetching more pages as the stream is
+    /// consumed. Like [`paginate`](crate::paginate), this yields at most
+    /// `limit` results, if set.
+    pub fn paginate<'a, R, T>(
+        &'a self,
+        req: R,
+        limit: Option<usize>,
+    ) -> impl Stream<Item = Result<T, ClientError>> + 'a
+    where
+        R: ApiRequest<Response = PaginatedResponse<T>> + Clone + 'a,
+        T: 'a,
+    {
+        // The state is the token for the next page, if there is one, and the
+        // number of results fetched so far.
+        let pages = stream::try_unfold((Some(None::<String>), 0), move |(token, fetched)| {
+            let req = req.clone();
+            async move {
+                let Some(token) = token else {
+                    return Ok(None);
+                };
+
+                let remaining = limit.map(|l| l.saturating_sub(fetched));
+                if remaining == Some(0) {
+                    return Ok(None);
+                }
+
+                let PaginatedResponse {
+                    mut page,
+                    pagination_token,
+                } = self.send(req.paginate(token.as_deref(), remaining)).await?;
+
+                if let Some(remaining) = remaining {
+                    page.truncate(remaining);
+                }
+
+                // An empty page ends the results, even with a token.
+                let next = pagination_token.filter(|_| !page.is_empty()).map(Some);
+                let fetched = fetched + page.len();
+                Ok(Some((page, (next, fetched))))
+            }
+        });
+
+        pages
+            .map_ok(|page| stream::iter(page).map(Ok))
+            .try_flatten()
+    }
+}
+
+#[cfg(all(test, feature = "_integration-tests"))]
+mod test {
+    use futures::TryStreamExt as _;
+
+    use super::*;
+    use crate::{
+        ApiErrorKind,
+        table::{GetTable, GetTables},
+    };
+
+    #[tokio::test]
+    async fn get_tables() -> anyhow::Result<()> {
+        let client = AsyncClient::new(Profile::from_default_env()?)?;
+
+        let table = client
+            .send(GetTable {
+                name: "bauplan.titanic",
+                at_ref: "main",
+                namespace: None,
+            })
+            .await?;
+        assert_eq!(table.name, "titanic");
+
+        let req = GetTables {
+            at_ref: "main",
+            filter_by_name: None,
+            filter_by_namespace: None,
+        };
+
+        let tables: Vec<_> = client.paginate(req, Some(5)).try_collect().await?;
+        assert!(!tables.is_empty() && tables.len() <= 5);
+
+        let err = client
+            .send(GetTable {
+                name: "does_not_exist",
+                at_ref: "main",
+                namespace: None,
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            Some(ApiErrorKind::TableNotFound { .. })
+        ));
+        Ok(())
+    }
+}
@@ -4,9 +4,12 @@ use std::{
     fs::File,
     io,
     path::{Path, PathBuf},
+    sync::LazyLock,
+    time::Duration,
 };
 
-use serde::{Deserialize, Serialize};
+use http::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize, Serializer, ser::SerializeMap};
 use tracing::debug;
 
 const DEFAULT_API_ENDPOINT: &str = "https://api.use1.aprod.bauplanlabs.com";
@@ -26,6 +29,37 @@ pub enum Error {
     NoApiKey,
     #[error("Invalid URI")]
     InvalidUri(#[from] http::uri::InvalidUri),
+    #[error("Invalid header {name:?}: {reason}")]
+    InvalidHeader { name: String, reason: String },
+    #[error("Header {name:?} references ${{{var}}}, which is not set")]
+    MissingHeaderVar { name: String, var: String },
+    #[error(transparent)]
+    Keychain(#[from] crate::keychain::KeychainError),
+}
+
+/// Where a profile's API key came from, in order of precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeySource {
+    /// Set explicitly, with [`Profile::with_api_key`].
+    Explicit,
+    /// The `BAUPLAN_API_KEY` environment variable.
+    Env,
+    /// The `api_key` setting in the config file.
+    ConfigFile,
+    /// The OS keychain, for a profile with `api_key_store: keychain`.
+    Keychain,
+}
+
+impl std::fmt::Display for ApiKeySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiKeySource::Explicit => f.write_str("explicit"),
+            ApiKeySource::Env => f.write_str("BAUPLAN_API_KEY environment variable"),
+            ApiKeySource::ConfigFile => f.write_str("config file"),
+            ApiKeySource::Keychain => f.write_str("OS keychain"),
+        }
+    }
 }
 
 /// A fully resolved configuration profile for interacting with Bauplan.
@@ -36,17 +70,55 @@ pub struct Profile {
     /// The API endpoint to use. Intended for internal use.
     #[serde(skip)]
     pub api_endpoint: http::Uri,
+    /// Overrides the gRPC endpoint, which is otherwise the API endpoint.
+    /// Intended for internal use.
+    #[serde(skip)]
+    pub grpc_endpoint_override: Option<http::Uri>,
+    /// Overrides the Iceberg REST catalog endpoint, which is otherwise
+    /// derived from the API endpoint. Intended for internal use.
+    #[serde(skip)]
+    pub catalog_endpoint_override: Option<http::Uri>,
     /// The API key to use for authentication.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub api_key: Option<String>,
+    /// Where the API key came from.
+    #[serde(skip)]
+    pub api_key_source: Option<ApiKeySource>,
     /// The default branch for CLI operations. Set by `bauplan checkout`.
     /// Intended for internal use.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub active_branch: Option<String>,
+    /// The namespace for CLI and SDK operations that take one, when it isn't
+    /// given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_namespace: Option<String>,
     /// Default args to include in every job request. CLI/SDK args override
     /// these on a per-key basis.
     #[serde(skip_serializing_if = "HashMap::is_empty")]
     pub args: HashMap<String, String>,
+    /// Whether the CLI should name runs after the project when no name is
+    /// given. Defaults to true; set `auto_job_name: false` to disable.
+    #[serde(skip_serializing_if = "is_true")]
+    pub(crate) auto_job_name: bool,
+    /// Named CLI invocations, like `ci-run: "run --cache off -O json"`,
+    /// which the CLI expands when the alias is given as the command.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+    /// Additional headers to send with every request, for example to satisfy
+    /// a proxy. Values not marked `safe` in the config file are redacted when
+    /// printed.
+    #[serde(serialize_with = "serialize_headers")]
+    pub extra_headers: HeaderMap,
+    /// The total time to spend waiting out rate limits before failing, set
+    /// in seconds as `rate_limit_budget`. Defaults to
+    /// [`RetryBudget::DEFAULT_MAX_WAIT`](crate::RetryBudget::DEFAULT_MAX_WAIT).
+    #[serde(serialize_with = "serialize_secs")]
+    pub rate_limit_budget: Duration,
+    /// How many times to retry a read-only gRPC call, like fetching job
+    /// logs, if the connection drops. Defaults to
+    /// [`DEFAULT_GRPC_RETRIES`](crate::grpc::DEFAULT_GRPC_RETRIES); `0`
+    /// disables retries. Calls which submit jobs are never retried.
+    pub grpc_retries: u32,
     /// The user-agent used on requests. Intended for internal use.
     #[serde(skip)]
     pub user_agent: String,
@@ -61,9 +133,18 @@ impl std::fmt::Debug for Profile {
         f.debug_struct("Profile")
             .field("name", &self.name)
             .field("api_endpoint", &self.api_endpoint)
+            .field("grpc_endpoint_override", &self.grpc_endpoint_override)
+            .field("catalog_endpoint_override", &self.catalog_endpoint_override)
             .field("api_key", &"********")
+            .field("api_key_source", &self.api_key_source)
             .field("active_branch", &self.active_branch)
+            .field("default_namespace", &self.default_namespace)
             .field("args", &self.args)
+            .field("auto_job_name", &self.auto_job_name)
+            .field("aliases", &self.aliases)
+            .field("extra_headers", &self.extra_headers)
+            .field("rate_limit_budget", &self.rate_limit_budget)
+            .field("grpc_retries", &self.grpc_retries)
             .field("user_agent", &self.user_agent)
             .finish()
     }
@@ -73,17 +154,64 @@ impl std::fmt::Debug for Profile {
 #[derive(Debug, Default, Clone, Deserialize)]
 struct ConfigProfile {
     pub(crate) active_branch: Option<String>,
+    pub(crate) default_namespace: Option<String>,
     pub(crate) api_endpoint: Option<String>,
+    pub(crate) grpc_endpoint: Option<String>,
+    pub(crate) catalog_endpoint: Option<String>,
     pub(crate) api_key: Option<String>,
+    pub(crate) api_key_store: Option<ApiKeyStore>,
     #[serde(default)]
     pub(crate) args: HashMap<String, String>,
+    pub(crate) auto_job_name: Option<bool>,
+    #[serde(default)]
+    pub(crate) aliases: BTreeMap<String, String>,
+    #[serde(default)]
+    pub(crate) extra_headers: BTreeMap<String, ConfigHeader>,
+    pub(crate) rate_limit_budget: Option<u64>,
+    pub(crate) grpc_retries: Option<u32>,
 }
 
+/// Where the config file says a profile's API key is stored, other than in
+/// the file itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ApiKeyStore {
+    Keychain,
+}
+
+/// A header value in the config file, either as a plain string or with
+/// additional options.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum ConfigHeader {
+    Value(String),
+    Detailed {
+        value: String,
+        /// Whether the value may be printed in logs and `bauplan config get`.
+        #[serde(default)]
+        safe: bool,
+    },
+}
+
+// Matches "${VAR}" in header values.
+static ENV_VAR_REGEX: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap());
+
 #[derive(Debug, Default, Clone, Deserialize)]
 struct Config {
+    /// The profile to use when none is given, set by `bauplan config
+    /// use-profile`.
+    current_profile: Option<String>,
     profiles: BTreeMap<String, ConfigProfile>,
 }
 
+impl Config {
+    /// The name of the profile to use, if none is given explicitly.
+    fn profile_name(&self) -> &str {
+        self.current_profile.as_deref().unwrap_or("default")
+    }
+}
+
 impl Profile {
     /// Validate the profile.
     pub fn validate(&self) -> Result<(), Error> {
@@ -98,78 +226,193 @@ impl Profile {
         Ok(())
     }
 
-    /// Load the given profile from the Bauplan configuration file (usually
-    /// ~/.bauplan/config.yaml). If no configuration file is present, then the
-    /// configuration will be loaded solely from the environment.
+    /// Load a profile from the Bauplan configuration file (usually
+    /// ~/.bauplan/config.yaml), with overrides from the environment. If no
+    /// configuration file is present, then the configuration will be loaded
+    /// solely from the environment.
     ///
-    /// If `BAUPLAN_PROFILE` is set, that will be used to select the profile.
-    /// Otherwise the profile `default` will be used.
+    /// The profile is, in order of precedence: `name`, if given; then
+    /// `BAUPLAN_PROFILE`; then the config file's `current_profile`, set by
+    /// `bauplan config use-profile`; and finally `default`.
     ///
-    /// The following environment variables can override the corresponding
+    /// The following environment variables override the corresponding
     /// values in the config file:
     ///
-    /// | Environment Variable    | Config Value   |
-    /// |-------------------------|----------------|
-    /// | `BAUPLAN_API_KEY`       | `api_key`      |
-    /// | `BAUPLAN_API_ENDPOINT`  | `api_endpoint` |
+    /// | Environment Variable       | Config Value        |
+    /// |----------------------------|---------------------|
+    /// | `BAUPLAN_API_KEY`          | `api_key`           |
+    /// | `BAUPLAN_API_ENDPOINT`     | `api_endpoint`      |
+    /// | `BAUPLAN_GRPC_ENDPOINT`    | `grpc_endpoint`     |
+    /// | `BAUPLAN_CATALOG_ENDPOINT` | `catalog_endpoint`  |
+    /// | `BAUPLAN_NAMESPACE`        | `default_namespace` |
+    pub fn from_env_or_file(name: Option<&str>) -> Result<Self, Error> {
+        Self::resolve(name, find_config()?, |var| env::var(var).ok())
+    }
+
+    /// Load the default profile, as [`Profile::from_env_or_file`] does when
+    /// no name is given.
     pub fn from_default_env() -> Result<Self, Error> {
-        if let Ok(s) = env::var("BAUPLAN_PROFILE") {
-            Self::from_env(&s)
-        } else {
-            Self::from_env("default")
-        }
+        Self::from_env_or_file(None)
     }
 
-    /// Load the given profile from the Bauplan configuration file (usually
-    /// ~/.bauplan/config.yaml). If no configuration file is present, then the
-    /// configuration will be loaded solely from the environment.
-    ///
-    /// The following environment variables can override the corresponding
-    /// values in the config file:
-    ///
-    /// | Environment Variable    | Config Value   |
-    /// |-------------------------|----------------|
-    /// | `BAUPLAN_API_KEY`       | `api_key`      |
-    /// | `BAUPLAN_API_ENDPOINT`  | `api_endpoint` |
+    /// Load the given profile, with overrides from the environment, like
+    /// [`Profile::from_env_or_file`].
     pub fn from_env(name: &str) -> Result<Self, Error> {
-        let api_key = env::var("BAUPLAN_API_KEY").ok();
-        let api_endpoint = env::var("BAUPLAN_API_ENDPOINT").ok();
+        Self::from_env_or_file(Some(name))
+    }
 
-        let config_path = find_config()?;
-        let profile = match read_profile(&config_path, name) {
-            Ok(p) => p,
+    /// A profile for tests, which doesn't read the config file or the
+    /// environment. It has a dummy API key and an API endpoint that doesn't
+    /// resolve, so it's meant for use with
+    /// [`MockCatalog`](crate::testing::MockCatalog).
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn for_tests() -> Self {
+        let raw = ConfigProfile {
+            api_key: Some("bpln_test_key".to_owned()),
+            api_endpoint: Some("http://bauplan.invalid".to_owned()),
+            ..Default::default()
+        };
+
+        Self::from_raw(raw, "test".to_owned(), PathBuf::new())
+            .expect("the test profile should be valid")
+    }
+
+    /// Implements [`Profile::from_env_or_file`], looking up environment
+    /// variables with `var`.
+    fn resolve(
+        name: Option<&str>,
+        config_path: PathBuf,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, Error> {
+        let config = match read_config_file(&config_path) {
+            Ok(config) => Some(config),
             Err(Error::Io(e)) if e.kind() == io::ErrorKind::NotFound => {
                 debug!("no config file found");
-                Default::default()
+                None
             }
             Err(e) => return Err(e),
         };
 
-        let api_endpoint = api_endpoint
-            .as_deref()
-            .or(profile.api_endpoint.as_deref())
-            .unwrap_or(DEFAULT_API_ENDPOINT)
-            .parse()?;
+        let name = match (name, var("BAUPLAN_PROFILE"), &config) {
+            (Some(name), _, _) => name.to_owned(),
+            (None, Some(name), _) => name,
+            (None, None, Some(config)) => config.profile_name().to_owned(),
+            (None, None, None) => "default".to_owned(),
+        };
 
-        let api_key = api_key.or(profile.api_key);
+        let mut raw = match config {
+            Some(mut config) => config
+                .profiles
+                .remove(&name)
+                .ok_or_else(|| Error::ProfileNotFound(name.clone()))?,
+            None => Default::default(),
+        };
 
-        Ok(Self {
-            name: name.to_owned(),
-            active_branch: profile.active_branch,
-            args: profile.args,
-            api_endpoint,
-            api_key,
-            user_agent: make_ua(None),
-            config_path,
-        })
+        let overrides = [
+            ("BAUPLAN_API_ENDPOINT", &mut raw.api_endpoint),
+            ("BAUPLAN_GRPC_ENDPOINT", &mut raw.grpc_endpoint),
+            ("BAUPLAN_CATALOG_ENDPOINT", &mut raw.catalog_endpoint),
+            ("BAUPLAN_NAMESPACE", &mut raw.default_namespace),
+        ];
+        for (key, value) in overrides {
+            if let Some(v) = var(key) {
+                *value = Some(v);
+            }
+        }
+
+        // An API key from the environment wins over the config file, and
+        // means the keychain isn't consulted.
+        let api_key = var("BAUPLAN_API_KEY");
+        if api_key.is_some() {
+            raw.api_key = None;
+            raw.api_key_store = None;
+        }
+
+        let mut profile = Self::from_raw(raw, name, config_path)?;
+        if let Some(api_key) = api_key {
+            profile.api_key = Some(api_key);
+            profile.api_key_source = Some(ApiKeySource::Env);
+        }
+
+        Ok(profile)
     }
 
     /// Sets the API key on the profile.
     pub fn with_api_key(mut self, api_key: String) -> Self {
         self.api_key = Some(api_key);
+        self.api_key_source = Some(ApiKeySource::Explicit);
+        self
+    }
+
+    /// Sets the total time to spend waiting out rate limits before failing.
+    pub fn with_rate_limit_budget(mut self, budget: Duration) -> Self {
+        self.rate_limit_budget = budget;
+        self
+    }
+
+    /// Sets how many times to retry a read-only gRPC call if the connection
+    /// drops.
+    pub fn with_grpc_retries(mut self, retries: u32) -> Self {
+        self.grpc_retries = retries;
+        self
+    }
+
+    /// Sets whether the CLI names runs after the project when no name is
+    /// given.
+    pub fn with_auto_job_name(mut self, auto_job_name: bool) -> Self {
+        self.auto_job_name = auto_job_name;
         self
     }
 
+    /// Whether the CLI names runs after the project when no name is given.
+    pub fn auto_job_name(&self) -> bool {
+        self.auto_job_name
+    }
+
+    /// Adds a header to send with every request. Values are redacted when
+    /// printed, unless `safe` is true.
+    ///
+    /// Headers which the client sets itself, like `Authorization`, can't be
+    /// overridden.
+    pub fn with_extra_header(mut self, name: &str, value: &str, safe: bool) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::InvalidHeader {
+            name: name.to_owned(),
+            reason: reason.to_owned(),
+        };
+
+        let header_name: HeaderName = name.parse().map_err(|_| invalid("invalid header name"))?;
+        if RESERVED_HEADERS.contains(&header_name) {
+            return Err(invalid("this header is set by the client"));
+        } else if header_name.as_str().ends_with("-bin") {
+            return Err(invalid("binary headers are not supported"));
+        }
+
+        let mut header_value: HeaderValue = value
+            .parse()
+            .map_err(|_| invalid("header values must be visible ASCII"))?;
+        header_value.set_sensitive(!safe);
+
+        self.extra_headers.append(header_name, header_value);
+        Ok(self)
+    }
+
+    fn with_config_headers(
+        mut self,
+        headers: BTreeMap<String, ConfigHeader>,
+    ) -> Result<Self, Error> {
+        for (name, header) in headers {
+            let (value, safe) = match header {
+                ConfigHeader::Value(value) => (value, false),
+                ConfigHeader::Detailed { value, safe } => (value, safe),
+            };
+
+            let value = interpolate_env(&name, &value)?;
+            self = self.with_extra_header(&name, &value, safe)?;
+        }
+
+        Ok(self)
+    }
+
     /// Modifies the user-agent to have a different prefix. Intended for
     /// internal use.
     #[doc(hidden)]
@@ -180,11 +423,31 @@ impl Profile {
         }
     }
 
-    /// Load the given profile (or 'default') from the Bauplan configuration
-    /// file (usually ~/.bauplan/config.yaml). Does not read any environment
-    /// variables.
+    /// The endpoint of the gRPC API, which runs jobs and streams their
+    /// events.
+    pub fn grpc_endpoint(&self) -> http::Uri {
+        self.grpc_endpoint_override
+            .clone()
+            .unwrap_or_else(|| self.api_endpoint.clone())
+    }
+
+    /// The endpoint of the Iceberg REST catalog, for configuring other
+    /// Iceberg clients. They add the `/v1` prefix themselves.
+    pub fn catalog_endpoint(&self) -> http::Uri {
+        if let Some(endpoint) = &self.catalog_endpoint_override {
+            return endpoint.clone();
+        }
+
+        let mut parts = self.api_endpoint.clone().into_parts();
+        parts.path_and_query = Some(http::uri::PathAndQuery::from_static("/iceberg"));
+        http::Uri::from_parts(parts).unwrap()
+    }
+
+    /// Load the given profile (or the current one) from the Bauplan
+    /// configuration file (usually ~/.bauplan/config.yaml). Does not read any
+    /// environment variables.
     ///
-    /// Usually, you will want to use [Profile::from_env] instead.
+    /// Usually, you will want to use [Profile::from_env_or_file] instead.
     pub fn load(name: Option<&str>) -> Result<Self, Error> {
         let file = find_config()?;
         Self::read(&file, name)
@@ -193,27 +456,33 @@ impl Profile {
     /// Iterate through all profiles in the Bauplan configuration file (usually
     /// ~/.bauplan/config.yaml). Does not read any environment variables.
     pub fn load_all() -> Result<impl Iterator<Item = Self>, Error> {
-        let path = find_config()?;
-        let file = File::open(&path)?;
-        let config: Config = serde_yaml::from_reader(file)?;
-        let profiles: Result<Vec<_>, Error> = config
-            .profiles
-            .into_iter()
-            .map(|(name, raw)| Profile::from_raw(raw, name, path.clone()))
-            .collect();
-
-        Ok(profiles?.into_iter())
+        Self::read_all(find_config()?)
     }
 
-    /// Load the given profile (or 'default') from the given file, which must
-    /// be a valid Bauplan configuration file. Does not read any environment
+    /// The name of the profile used when none is given: the config file's
+    /// `current_profile`, or `default`. Does not read any environment
     /// variables.
+    pub fn current_name(path: impl AsRef<Path>) -> Result<String, Error> {
+        match read_config_file(path.as_ref()) {
+            Ok(config) => Ok(config.profile_name().to_owned()),
+            Err(Error::Io(e)) if e.kind() == io::ErrorKind::NotFound => Ok("default".to_owned()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Load the given profile (or the file's `current_profile`, or
+    /// `default`) from the given file, which must be a valid Bauplan
+    /// configuration file. Does not read any environment variables.
     ///
-    /// Usually, you will want to use [Profile::from_env] instead.
+    /// Usually, you will want to use [Profile::from_env_or_file] instead.
     pub fn read(path: impl AsRef<Path>, name: Option<&str>) -> Result<Self, Error> {
         let path = path.as_ref();
-        let name = name.unwrap_or("default").to_owned();
-        let profile = read_profile(path, &name)?;
+        let mut config = read_config_file(path)?;
+        let name = name.unwrap_or(config.profile_name()).to_owned();
+        let Some(profile) = config.profiles.remove(&name) else {
+            return Err(Error::ProfileNotFound(name));
+        };
+
         Self::from_raw(profile, name, path.to_owned())
     }
 
@@ -221,8 +490,7 @@ impl Profile {
     /// configuration file. Does not read any environment variables.
     pub fn read_all(path: impl AsRef<Path>) -> Result<impl Iterator<Item = Self>, Error> {
         let path = path.as_ref();
-        let file = File::open(path)?;
-        let config: Config = serde_yaml::from_reader(file)?;
+        let config = read_config_file(path)?;
 
         let profiles: Result<Vec<_>, Error> = config
             .profiles
@@ -236,24 +504,47 @@ impl Profile {
     fn from_raw(raw: ConfigProfile, name: String, path: PathBuf) -> Result<Self, Error> {
         let ConfigProfile {
             active_branch,
+            default_namespace,
             api_endpoint,
+            grpc_endpoint,
+            catalog_endpoint,
             api_key,
+            api_key_store,
             args,
+            auto_job_name,
+            aliases,
+            extra_headers,
+            rate_limit_budget: budget,
+            grpc_retries,
         } = raw;
 
         let api_endpoint = api_endpoint
             .unwrap_or(DEFAULT_API_ENDPOINT.to_string())
             .parse()?;
+        let grpc_endpoint_override = grpc_endpoint.map(|s| s.parse()).transpose()?;
+        let catalog_endpoint_override = catalog_endpoint.map(|s| s.parse()).transpose()?;
+        let (api_key, api_key_source) = stored_api_key(&name, api_key, api_key_store)?;
 
-        Ok(Self {
+        let profile = Self {
             name,
             active_branch,
+            default_namespace,
             args,
+            auto_job_name: auto_job_name.unwrap_or(true),
+            aliases,
             api_endpoint,
+            grpc_endpoint_override,
+            catalog_endpoint_override,
             api_key,
+            api_key_source,
+            extra_headers: HeaderMap::new(),
+            rate_limit_budget: rate_limit_budget(budget),
+            grpc_retries: grpc_retries.unwrap_or(crate::grpc::DEFAULT_GRPC_RETRIES),
             user_agent: make_ua(None),
             config_path: path.to_owned(),
-        })
+        };
+
+        profile.with_config_headers(extra_headers)
     }
 }
 
@@ -280,18 +571,332 @@ fn find_config() -> Result<PathBuf, Error> {
     Ok(canonical)
 }
 
-fn read_profile(p: &Path, name: &str) -> Result<ConfigProfile, Error> {
+fn read_config_file(p: &Path) -> Result<Config, Error> {
     let file = File::open(p)?;
-    let mut config: Config = serde_yaml::from_reader(file).map_err(Error::Invalid)?;
-    let Some(config_profile) = config.profiles.remove(name) else {
-        return Err(Error::ProfileNotFound(name.to_string()));
-    };
-
+    let config: Config = serde_yaml::from_reader(file).map_err(Error::Invalid)?;
     debug!(path = %p.display(), "loaded config file");
 
-    Ok(config_profile)
+    Ok(config)
+}
+
+/// Resolves the API key stored for a profile: in the config file, or else
+/// wherever the config file says it's stored.
+fn stored_api_key(
+    name: &str,
+    api_key: Option<String>,
+    store: Option<ApiKeyStore>,
+) -> Result<(Option<String>, Option<ApiKeySource>), Error> {
+    if let Some(api_key) = api_key {
+        return Ok((Some(api_key), Some(ApiKeySource::ConfigFile)));
+    }
+
+    match store {
+        Some(ApiKeyStore::Keychain) => {
+            let api_key = crate::keychain::get_api_key(name)?;
+            let source = api_key.as_ref().map(|_| ApiKeySource::Keychain);
+            Ok((api_key, source))
+        }
+        None => Ok((None, None)),
+    }
+}
+
+/// Headers that the client sets itself.
+const RESERVED_HEADERS: &[HeaderName] = &[
+    http::header::AUTHORIZATION,
+    http::header::USER_AGENT,
+    http::header::CONTENT_TYPE,
+];
+
+/// Replaces `${VAR}` in a header value with the value of the environment
+/// variable.
+fn interpolate_env(name: &str, value: &str) -> Result<String, Error> {
+    let mut missing = None;
+    let value = ENV_VAR_REGEX.replace_all(value, |caps: &regex::Captures<'_>| {
+        env::var(&caps[1]).unwrap_or_else(|_| {
+            missing.get_or_insert_with(|| caps[1].to_owned());
+            String::new()
+        })
+    });
+
+    match missing {
+        Some(var) => Err(Error::MissingHeaderVar {
+            name: name.to_owned(),
+            var,
+        }),
+        None => Ok(value.into_owned()),
+    }
+}
+
+fn serialize_headers<S: Serializer>(headers: &HeaderMap, s: S) -> Result<S::Ok, S::Error> {
+    let mut map = s.serialize_map(Some(headers.len()))?;
+    for (name, value) in headers {
+        let value = if value.is_sensitive() {
+            "********"
+        } else {
+            value.to_str().unwrap_or_default()
+        };
+
+        map.serialize_entry(name.as_str(), value)?;
+    }
+
+    map.end()
+}
+
+fn is_true(b: &bool) -> bool {
+    *b
+}
+
+fn serialize_secs<S: Serializer>(d: &Duration, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_u64(d.as_secs())
+}
+
+fn rate_limit_budget(secs: Option<u64>) -> Duration {
+    secs.map(Duration::from_secs)
+        .unwrap_or(crate::RetryBudget::DEFAULT_MAX_WAIT)
 }
 
 fn make_ua(product: Option<&str>) -> String {
     format!("{}/{}", product.unwrap_or("default"), env!("BPLN_VERSION"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_config(yaml: &str) -> Result<Profile, Error> {
+        let tmp = tempfile::tempdir()?;
+        let path = tmp.path().join("config.yaml");
+        std::fs::write(&path, yaml)?;
+        Profile::read(&path, None)
+    }
+
+    #[test]
+    fn extra_headers() -> anyhow::Result<()> {
+        let profile = read_config(
+            "profiles:
+  default:
+    api_key: bpln_dummy_key
+    extra_headers:
+      X-Org-Request-Context: ctx-${CARGO_PKG_NAME}
+      X-Team:
+        value: data
+        safe: true
+",
+        )?;
+
+        let headers = &profile.extra_headers;
+        assert_eq!(headers["x-org-request-context"], "ctx-bauplan");
+        assert!(headers["x-org-request-context"].is_sensitive());
+        assert_eq!(headers["x-team"], "data");
+        assert!(!headers["x-team"].is_sensitive());
+
+        let json = serde_json::to_value(&profile)?;
+        assert_eq!(json["extra_headers"]["x-org-request-context"], "********");
+        assert_eq!(json["extra_headers"]["x-team"], "data");
+        Ok(())
+    }
+
+    #[test]
+    fn extra_headers_missing_var() {
+        let err = read_config(
+            "profiles:
+  default:
+    extra_headers:
+      X-Org-Request-Context: ${BAUPLAN_TEST_UNSET_VAR}
+",
+        )
+        .unwrap_err();
+
+        assert!(
+            matches!(err, Error::MissingHeaderVar { var, .. } if var == "BAUPLAN_TEST_UNSET_VAR")
+        );
+    }
+
+    #[test]
+    fn extra_headers_reserved() {
+        for name in ["Authorization", "authorization", "User-Agent"] {
+            let err = read_config(&format!(
+                "profiles:\n  default:\n    extra_headers:\n      {name}: Bearer other\n"
+            ))
+            .unwrap_err();
+
+            assert!(matches!(err, Error::InvalidHeader { .. }), "{name}: {err}");
+        }
+    }
+
+    #[test]
+    fn extra_headers_invalid() -> anyhow::Result<()> {
+        let profile = read_config("profiles:\n  default: {}\n")?;
+        let add = |name, value| profile.clone().with_extra_header(name, value, false);
+
+        assert!(add("bad name", "v").is_err());
+        assert!(add("x-ok", "bad\nvalue").is_err());
+        assert!(add("x-data-bin", "v").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn rate_limit_budget() -> anyhow::Result<()> {
+        let profile = read_config("profiles:\n  default: {}\n")?;
+        assert_eq!(
+            profile.rate_limit_budget,
+            crate::RetryBudget::DEFAULT_MAX_WAIT
+        );
+
+        let profile = read_config("profiles:\n  default:\n    rate_limit_budget: 300\n")?;
+        assert_eq!(profile.rate_limit_budget, Duration::from_secs(300));
+        assert_eq!(serde_json::to_value(&profile)?["rate_limit_budget"], 300);
+        Ok(())
+    }
+
+    #[test]
+    fn grpc_retries() -> anyhow::Result<()> {
+        let profile = read_config("profiles:\n  default: {}\n")?;
+        assert_eq!(profile.grpc_retries, crate::grpc::DEFAULT_GRPC_RETRIES);
+
+        let profile = read_config("profiles:\n  default:\n    grpc_retries: 0\n")?;
+        assert_eq!(profile.grpc_retries, 0);
+        assert_eq!(serde_json::to_value(&profile)?["grpc_retries"], 0);
+        Ok(())
+    }
+
+    #[test]
+    fn auto_job_name() -> anyhow::Result<()> {
+        let profile = read_config("profiles:\n  default: {}\n")?;
+        let json = serde_json::to_value(&profile)?;
+        assert!(profile.auto_job_name());
+        assert!(json.get("auto_job_name").is_none());
+
+        let profile = read_config("profiles:\n  default:\n    auto_job_name: false\n")?;
+        assert!(!profile.auto_job_name());
+        assert_eq!(serde_json::to_value(&profile)?["auto_job_name"], false);
+
+        assert!(profile.with_auto_job_name(true).auto_job_name());
+        Ok(())
+    }
+
+    const PROFILES: &str = "current_profile: staging
+profiles:
+  default:
+    api_key: bpln_prod_key
+  staging:
+    api_key: bpln_staging_key
+    api_endpoint: https://api.staging.example.com
+    grpc_endpoint: https://grpc.staging.example.com:8443
+    catalog_endpoint: https://catalog.staging.example.com/iceberg
+    active_branch: me.staging
+    default_namespace: raw
+";
+
+    fn resolve(yaml: &str, name: Option<&str>, env: &[(&str, &str)]) -> Result<Profile, Error> {
+        let tmp = tempfile::tempdir()?;
+        let path = tmp.path().join("config.yaml");
+        std::fs::write(&path, yaml)?;
+
+        let env: HashMap<String, String> = env
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Profile::resolve(name, path, |var| env.get(var).cloned())
+    }
+
+    #[test]
+    fn profile_from_file() -> anyhow::Result<()> {
+        // Without a name, the file's current_profile is used.
+        let profile = resolve(PROFILES, None, &[])?;
+        assert_eq!(profile.name, "staging");
+        assert_eq!(profile.api_key.as_deref(), Some("bpln_staging_key"));
+        assert_eq!(profile.api_key_source, Some(ApiKeySource::ConfigFile));
+        assert_eq!(profile.api_endpoint, "https://api.staging.example.com/");
+        assert_eq!(
+            profile.grpc_endpoint(),
+            "https://grpc.staging.example.com:8443/"
+        );
+        assert_eq!(
+            profile.catalog_endpoint(),
+            "https://catalog.staging.example.com/iceberg"
+        );
+        assert_eq!(profile.active_branch.as_deref(), Some("me.staging"));
+        assert_eq!(profile.default_namespace.as_deref(), Some("raw"));
+
+        let profile = resolve("profiles:\n  default: {}\n", None, &[])?;
+        assert_eq!(profile.name, "default");
+        assert_eq!(profile.default_namespace, None);
+        Ok(())
+    }
+
+    #[test]
+    fn profile_from_env() -> anyhow::Result<()> {
+        let env = [
+            ("BAUPLAN_PROFILE", "default"),
+            ("BAUPLAN_API_KEY", "bpln_env_key"),
+            ("BAUPLAN_GRPC_ENDPOINT", "https://grpc.env.example.com"),
+            ("BAUPLAN_NAMESPACE", "env_ns"),
+        ];
+
+        // BAUPLAN_PROFILE wins over current_profile, and the other variables
+        // over the profile's settings.
+        let profile = resolve(PROFILES, None, &env)?;
+        assert_eq!(profile.name, "default");
+        assert_eq!(profile.api_key.as_deref(), Some("bpln_env_key"));
+        assert_eq!(profile.api_key_source, Some(ApiKeySource::Env));
+        assert_eq!(profile.grpc_endpoint(), "https://grpc.env.example.com/");
+        assert_eq!(profile.default_namespace.as_deref(), Some("env_ns"));
+
+        let err = resolve(PROFILES, None, &[("BAUPLAN_PROFILE", "nope")]).unwrap_err();
+        assert!(matches!(err, Error::ProfileNotFound(name) if name == "nope"));
+        Ok(())
+    }
+
+    #[test]
+    fn profile_explicit() -> anyhow::Result<()> {
+        // An explicit name wins over BAUPLAN_PROFILE and current_profile, but
+        // the environment still overrides its settings.
+        let env = [
+            ("BAUPLAN_PROFILE", "default"),
+            (
+                "BAUPLAN_CATALOG_ENDPOINT",
+                "https://catalog.env.example.com",
+            ),
+        ];
+        let profile = resolve(PROFILES, Some("staging"), &env)?;
+        assert_eq!(profile.name, "staging");
+        assert_eq!(profile.api_key.as_deref(), Some("bpln_staging_key"));
+        assert_eq!(
+            profile.catalog_endpoint(),
+            "https://catalog.env.example.com/"
+        );
+
+        let profile = profile.with_api_key("bpln_explicit_key".to_owned());
+        assert_eq!(profile.api_key_source, Some(ApiKeySource::Explicit));
+        Ok(())
+    }
+
+    #[test]
+    fn profile_without_file() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let path = tmp.path().join("config.yaml");
+        let profile = Profile::resolve(Some("staging"), path.clone(), |_| None)?;
+        assert_eq!(profile.name, "staging");
+        assert_eq!(profile.api_key, None);
+        assert_eq!(
+            profile.api_endpoint,
+            DEFAULT_API_ENDPOINT.parse::<http::Uri>()?
+        );
+        assert_eq!(Profile::current_name(&path)?, "default");
+        Ok(())
+    }
+
+    #[test]
+    fn endpoints() -> anyhow::Result<()> {
+        let profile =
+            read_config("profiles:\n  default:\n    api_endpoint: https://api.example.com:8443\n")?;
+
+        assert_eq!(profile.grpc_endpoint(), "https://api.example.com:8443/");
+        assert_eq!(
+            profile.catalog_endpoint(),
+            "https://api.example.com:8443/iceberg"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,850 @@
+//! Typed events reported while a job runs.
+//!
+//! [`grpc::Client::monitor_job`] produces a stream of [`JobEvent`]s, converted
+//! from the raw protocol events in one place. New kinds of protocol events
+//! show up as [`JobEvent::Other`] until they get a variant of their own.
+//! The raw events can also be recorded to a file, and replayed later; see
+//! [`capture`].
+
+pub mod capture;
+
+use std::{collections::HashMap, time};
+
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt as _, TryStreamExt as _, stream};
+
+use crate::grpc::{
+    self, JobError,
+    generated::{self as commanderpb, runner_event::Event as RunnerEvent},
+    job::pb_to_chrono,
+};
+
+/// An event reported by a running job.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum JobEvent {
+    /// A task started.
+    TaskStarted(Task),
+    /// A task finished. Any previews of its output follow as
+    /// [`JobEvent::Preview`] events.
+    TaskCompleted(Task, TaskOutcome),
+    /// A log message, from user code or from the system.
+    UserLog(LogMessage),
+    /// A preview of a table written by a task.
+    Preview(TablePreview),
+    /// A Flight server is ready to serve the results of a query.
+    FlightReady(FlightServer),
+    /// The plan for a table creation.
+    TableCreatePlanned(TableCreatePlan),
+    /// The job finished. This is always the last event.
+    Completion(Completion),
+    /// An event without a variant of its own, by name.
+    Other(String),
+}
+
+/// A task in a job.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Task {
+    /// The ID of the task.
+    pub id: String,
+    /// The name of the task.
+    pub name: String,
+    /// When the event was reported.
+    pub timestamp: Option<DateTime<Utc>>,
+    /// What the task does.
+    pub metadata: TaskMetadata,
+}
+
+/// Describes what a task does, and the user code it runs, if any.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskMetadata {
+    /// Whether the task is part of the user's DAG, or internal to the system.
+    pub level: TaskLevel,
+    /// The kind of task, e.g. `USER_CODE_EXPECTATION`.
+    pub task_type: String,
+    /// A description of the kind of task.
+    pub description: String,
+    /// The name of the model the task runs, if any.
+    pub model_name: Option<String>,
+    /// The name of the function the task runs, if any.
+    pub function_name: Option<String>,
+    /// The file the function is defined in.
+    pub file_name: Option<String>,
+    /// The line the function is defined on.
+    pub line_number: Option<u32>,
+}
+
+impl TaskMetadata {
+    /// Whether the task is part of the user's DAG.
+    pub fn is_dag(&self) -> bool {
+        self.level == TaskLevel::Dag
+    }
+}
+
+impl From<commanderpb::TaskMetadata> for TaskMetadata {
+    fn from(metadata: commanderpb::TaskMetadata) -> Self {
+        Self {
+            level: metadata.level().into(),
+            task_type: metadata.task_type,
+            description: metadata.human_readable_task_type,
+            model_name: metadata.model_name,
+            function_name: metadata.function_name,
+            file_name: metadata.file_name,
+            line_number: metadata.line_number.map(|n| n as _),
+        }
+    }
+}
+
+/// Where a task sits in a job.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum TaskLevel {
+    #[default]
+    Unspecified,
+    Dag,
+    System,
+}
+
+impl From<commanderpb::task_metadata::TaskLevel> for TaskLevel {
+    fn from(level: commanderpb::task_metadata::TaskLevel) -> Self {
+        use commanderpb::task_metadata::TaskLevel as Pb;
+
+        match level {
+            Pb::Unspecified => Self::Unspecified,
+            Pb::Dag => Self::Dag,
+            Pb::System => Self::System,
+        }
+    }
+}
+
+/// How a task finished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum TaskOutcome {
+    Success {
+        message: String,
+        /// Whether the output was served from the cache, rather than
+        /// computed. Always false for servers that don't report it.
+        cached: bool,
+    },
+    Failure {
+        message: String,
+        stack_trace: Option<String>,
+        /// Whether the failure stops the job. Non-fatal failures, like a
+        /// failed expectation, are reported but the job carries on.
+        fatal: bool,
+    },
+    Cancelled {
+        reason: String,
+    },
+    Timeout {
+        message: String,
+    },
+    Skipped,
+}
+
+/// A log message from a job.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogMessage {
+    /// The ID of the job.
+    pub job_id: String,
+    /// The severity of the message.
+    pub level: LogLevel,
+    /// The stream the message was written to.
+    pub stream: OutputStream,
+    /// Whether the message came from user code, rather than the system.
+    pub user: bool,
+    /// When the message was emitted, in nanoseconds since the epoch. Zero for
+    /// output lines streamed directly from a task.
+    pub timestamp_ns: i64,
+    /// The message.
+    pub message: String,
+    /// The task that logged the message.
+    pub task: TaskMetadata,
+}
+
+impl From<commanderpb::RuntimeLogEvent> for LogMessage {
+    fn from(ev: commanderpb::RuntimeLogEvent) -> Self {
+        use commanderpb::runtime_log_event::LogType;
+
+        Self {
+            level: ev.level().into(),
+            stream: ev.output_stream().into(),
+            user: ev.r#type() == LogType::User,
+            job_id: ev.job_id,
+            timestamp_ns: ev.emit_timestamp_ns,
+            message: ev.msg,
+            task: ev.task_metadata.unwrap_or_default().into(),
+        }
+    }
+}
+
+/// The severity of a log message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum LogLevel {
+    Unspecified,
+    Error,
+    Warning,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<commanderpb::runtime_log_event::LogLevel> for LogLevel {
+    fn from(level: commanderpb::runtime_log_event::LogLevel) -> Self {
+        use commanderpb::runtime_log_event::LogLevel as Pb;
+
+        match level {
+            Pb::Unspecified => Self::Unspecified,
+            Pb::Error => Self::Error,
+            Pb::Warning => Self::Warning,
+            Pb::Info => Self::Info,
+            Pb::Debug => Self::Debug,
+            Pb::Trace => Self::Trace,
+        }
+    }
+}
+
+/// The stream a log message was written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum OutputStream {
+    Unspecified,
+    Stdout,
+    Stderr,
+}
+
+impl From<commanderpb::runtime_log_event::OutputStream> for OutputStream {
+    fn from(stream: commanderpb::runtime_log_event::OutputStream) -> Self {
+        use commanderpb::runtime_log_event::OutputStream as Pb;
+
+        match stream {
+            Pb::Unspecified => Self::Unspecified,
+            Pb::Stdout => Self::Stdout,
+            Pb::Stderr => Self::Stderr,
+        }
+    }
+}
+
+/// A preview of a table written by a task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TablePreview {
+    /// The ID of the task that wrote the table.
+    pub task_id: String,
+    /// The name of the table.
+    pub table_name: String,
+    /// The columns of the preview.
+    pub columns: Vec<PreviewColumn>,
+}
+
+/// A column in a [`TablePreview`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviewColumn {
+    /// The name of the column.
+    pub name: String,
+    /// The type of the column.
+    pub column_type: String,
+    /// The values in the column, formatted as strings.
+    pub values: Vec<String>,
+}
+
+impl TablePreview {
+    fn new(task_id: String, preview: commanderpb::RuntimeTablePreview) -> Self {
+        Self {
+            task_id,
+            table_name: preview.table_name,
+            columns: preview
+                .columns
+                .into_iter()
+                .map(|c| PreviewColumn {
+                    name: c.column_name,
+                    column_type: c.column_type,
+                    values: c.values,
+                })
+                .collect(),
+        }
+    }
+
+    /// The number of rows in the preview.
+    pub fn num_rows(&self) -> usize {
+        self.columns.first().map_or(0, |c| c.values.len())
+    }
+}
+
+/// Watches the events of a run for models that wrote no rows, judging by the
+/// previews of their output. Previews are only reported for runs with preview
+/// mode on, and a model without a preview is never counted as empty.
+#[derive(Debug, Clone, Default)]
+pub struct EmptyOutputCheck {
+    /// The models to check; empty to check all of them.
+    models: Vec<String>,
+    /// The model run by each task seen so far, by task ID.
+    task_models: HashMap<String, String>,
+    empty: Vec<String>,
+}
+
+impl EmptyOutputCheck {
+    /// Checks the given models, or all models if there are none.
+    pub fn new(models: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            models: models.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Updates the check with an event. Returns the name of the model, if
+    /// the event shows a checked model to be empty for the first time.
+    pub fn observe(&mut self, event: &JobEvent) -> Option<&str> {
+        match event {
+            JobEvent::TaskStarted(task) | JobEvent::TaskCompleted(task, _) => {
+                if task.metadata.is_dag()
+                    && let Some(name) = &task.metadata.model_name
+                {
+                    self.task_models.insert(task.id.clone(), name.clone());
+                }
+
+                None
+            }
+            JobEvent::Preview(preview)
+                if !preview.columns.is_empty() && preview.num_rows() == 0 =>
+            {
+                let name = self.task_models.get(&preview.task_id)?;
+                if (!self.models.is_empty() && !self.models.contains(name))
+                    || self.empty.contains(name)
+                {
+                    return None;
+                }
+
+                self.empty.push(name.clone());
+                self.empty.last().map(String::as_str)
+            }
+            _ => None,
+        }
+    }
+
+    /// The checked models found to be empty, in the order they were found.
+    pub fn empty_models(&self) -> &[String] {
+        &self.empty
+    }
+
+    /// Why the run should be failed, like `empty output: trips`, if any
+    /// checked model was empty.
+    pub fn failure_reason(&self) -> Option<String> {
+        if self.empty.is_empty() {
+            None
+        } else {
+            Some(format!("empty output: {}", self.empty.join(", ")))
+        }
+    }
+}
+
+/// A Flight server serving the results of a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlightServer {
+    /// The address of the server. This may not include a scheme.
+    pub endpoint: String,
+    /// The token to authenticate with.
+    pub magic_token: String,
+    /// The number of rows in the results.
+    pub num_rows: u64,
+    /// Whether the server uses TLS.
+    pub use_tls: bool,
+}
+
+impl From<commanderpb::FlightServerStartEvent> for FlightServer {
+    fn from(ev: commanderpb::FlightServerStartEvent) -> Self {
+        Self {
+            endpoint: ev.endpoint,
+            magic_token: ev.magic_token,
+            num_rows: ev.num_rows.max(0) as _,
+            use_tls: ev.use_tls,
+        }
+    }
+}
+
+/// The plan for a table creation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableCreatePlan {
+    /// The plan, as YAML.
+    pub plan_yaml: String,
+    /// Whether the plan can be applied without changes.
+    pub can_auto_apply: bool,
+    /// The files the plan would import.
+    pub files_to_be_imported: Vec<String>,
+    /// Set if planning failed.
+    pub error: Option<String>,
+}
+
+impl From<commanderpb::TableCreatePlanDoneEvent> for TableCreatePlan {
+    fn from(ev: commanderpb::TableCreatePlanDoneEvent) -> Self {
+        Self {
+            plan_yaml: ev.plan_as_yaml,
+            can_auto_apply: ev.can_auto_apply,
+            files_to_be_imported: ev.files_to_be_imported,
+            error: Some(ev.error_message).filter(|e| !e.is_empty()),
+        }
+    }
+}
+
+/// The end of a job.
+#[derive(Debug, Clone)]
+pub struct Completion {
+    /// The success message, or the reason the job didn't succeed.
+    pub result: Result<String, JobError>,
+    /// Counts of what happened while the job was monitored.
+    pub metrics: JobMetrics,
+}
+
+/// Counts of the events seen for a job, reported with its [`Completion`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JobMetrics {
+    /// The number of tasks that started.
+    pub tasks_started: usize,
+    /// The number of tasks that succeeded.
+    pub tasks_succeeded: usize,
+    /// The number of tasks that failed, fatally or not.
+    pub tasks_failed: usize,
+    /// The number of tasks that were skipped.
+    pub tasks_skipped: usize,
+    /// The number of tasks that were cancelled or timed out.
+    pub tasks_cancelled: usize,
+    /// The number of log messages from user code.
+    pub user_logs: usize,
+    /// How long the job was monitored for.
+    pub elapsed: time::Duration,
+}
+
+/// Converts raw runner events to [`JobEvent`]s, keeping the counts reported
+/// in the [`Completion`].
+#[derive(Debug)]
+pub(crate) struct Converter {
+    metrics: JobMetrics,
+    started: time::Instant,
+}
+
+impl Converter {
+    pub(crate) fn new() -> Self {
+        Self {
+            metrics: JobMetrics::default(),
+            started: time::Instant::now(),
+        }
+    }
+
+    /// Converts one runner event. Most produce a single [`JobEvent`], but task
+    /// completions are followed by a [`JobEvent::Preview`] per preview.
+    pub(crate) fn convert(&mut self, event: RunnerEvent) -> Vec<JobEvent> {
+        use commanderpb::task_complete_event::Outcome;
+
+        let ev = match event {
+            RunnerEvent::TaskStart(ev) => {
+                self.metrics.tasks_started += 1;
+                JobEvent::TaskStarted(Task {
+                    id: ev.task_id,
+                    name: ev.task_name,
+                    timestamp: ev.timestamp.and_then(pb_to_chrono),
+                    metadata: ev.task_metadata.unwrap_or_default().into(),
+                })
+            }
+            RunnerEvent::TaskCompletion(ev) => {
+                let task = Task {
+                    id: ev.task_id,
+                    name: ev.task_name,
+                    timestamp: ev.timestamp.and_then(pb_to_chrono),
+                    metadata: ev.task_metadata.unwrap_or_default().into(),
+                };
+
+                let mut previews = Vec::new();
+                let outcome = match ev.outcome {
+                    Some(Outcome::Success(s)) => {
+                        self.metrics.tasks_succeeded += 1;
+                        previews = s.runtime_table_preview;
+                        TaskOutcome::Success {
+                            message: s.message,
+                            cached: s.cache_hit.unwrap_or(false),
+                        }
+                    }
+                    Some(Outcome::Failure(f)) => {
+                        self.metrics.tasks_failed += 1;
+                        TaskOutcome::Failure {
+                            message: f.error_message,
+                            stack_trace: f.stack_trace.filter(|s| !s.is_empty()),
+                            fatal: f.is_fatal,
+                        }
+                    }
+                    Some(Outcome::Cancel(c)) => {
+                        self.metrics.tasks_cancelled += 1;
+                        TaskOutcome::Cancelled { reason: c.reason }
+                    }
+                    Some(Outcome::Timeout(t)) => {
+                        self.metrics.tasks_cancelled += 1;
+                        TaskOutcome::Timeout { message: t.message }
+                    }
+                    Some(Outcome::Skipped(_)) => {
+                        self.metrics.tasks_skipped += 1;
+                        TaskOutcome::Skipped
+                    }
+                    None => return vec![JobEvent::Other("TaskCompletion".to_owned())],
+                };
+
+                let task_id = task.id.clone();
+                let mut events = vec![JobEvent::TaskCompleted(task, outcome)];
+                events.extend(
+                    previews
+                        .into_iter()
+                        .map(|p| JobEvent::Preview(TablePreview::new(task_id.clone(), p))),
+                );
+
+                return events;
+            }
+            RunnerEvent::RuntimeUserLog(ev) => {
+                let log = LogMessage::from(ev);
+                if log.user {
+                    self.metrics.user_logs += 1;
+                }
+
+                JobEvent::UserLog(log)
+            }
+            RunnerEvent::FlightServerStart(ev) => JobEvent::FlightReady(ev.into()),
+            RunnerEvent::TableCreatePlanDoneEvent(ev) => JobEvent::TableCreatePlanned(ev.into()),
+            RunnerEvent::JobCompletion(ev) => {
+                self.metrics.elapsed = self.started.elapsed();
+                JobEvent::Completion(Completion {
+                    result: grpc::interpret_outcome(ev.outcome).map(|s| s.msg),
+                    metrics: self.metrics,
+                })
+            }
+            RunnerEvent::ImportPlanCreated(_) => JobEvent::Other("ImportPlanCreated".to_owned()),
+            RunnerEvent::ApplyPlanDone(_) => JobEvent::Other("ApplyPlanDone".to_owned()),
+            RunnerEvent::TableCreatePlanApplyDoneEvent(_) => {
+                JobEvent::Other("TableCreatePlanApplyDone".to_owned())
+            }
+            RunnerEvent::GlobalLivelinessHeartbeat(_) => {
+                JobEvent::Other("GlobalLivelinessHeartbeat".to_owned())
+            }
+        };
+
+        vec![ev]
+    }
+}
+
+/// Converts a stream of raw runner events to [`JobEvent`]s.
+pub(crate) fn convert_stream<E>(
+    events: impl Stream<Item = Result<RunnerEvent, E>>,
+) -> impl Stream<Item = Result<JobEvent, E>> {
+    let mut converter = Converter::new();
+    events
+        .map_ok(move |ev| stream::iter(converter.convert(ev)).map(Ok))
+        .try_flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use commanderpb::{job_complete_event, runtime_log_event, task_complete_event};
+
+    fn metadata(level: commanderpb::task_metadata::TaskLevel) -> commanderpb::TaskMetadata {
+        commanderpb::TaskMetadata {
+            level: level as _,
+            human_readable_task_type: "Python model".to_owned(),
+            task_type: "USER_CODE_MODEL".to_owned(),
+            model_name: Some("trips".to_owned()),
+            file_name: Some("models.py".to_owned()),
+            line_number: Some(12),
+            ..Default::default()
+        }
+    }
+
+    /// The events of a small run, as reported by the server: a model that
+    /// logs a line and writes a previewed table, an expectation that fails
+    /// without stopping the job, and a skipped task.
+    fn run_fixture() -> Vec<RunnerEvent> {
+        use commanderpb::task_metadata::TaskLevel;
+
+        let ts = prost_types::Timestamp {
+            seconds: 1_700_000_000,
+            nanos: 0,
+        };
+
+        vec![
+            RunnerEvent::GlobalLivelinessHeartbeat(Default::default()),
+            RunnerEvent::TaskStart(commanderpb::TaskStartEvent {
+                task_metadata: Some(metadata(TaskLevel::Dag)),
+                timestamp: Some(ts),
+                task_id: "t1".to_owned(),
+                task_name: "run trips".to_owned(),
+                ..Default::default()
+            }),
+            RunnerEvent::RuntimeUserLog(commanderpb::RuntimeLogEvent {
+                level: runtime_log_event::LogLevel::Info as _,
+                output_stream: runtime_log_event::OutputStream::Stderr as _,
+                r#type: runtime_log_event::LogType::User as _,
+                emit_timestamp_ns: 1_700_000_000_000_000_000,
+                msg: "loaded 10 rows".to_owned(),
+                task_metadata: Some(metadata(TaskLevel::Dag)),
+                job_id: "job".to_owned(),
+            }),
+            RunnerEvent::RuntimeUserLog(commanderpb::RuntimeLogEvent {
+                level: runtime_log_event::LogLevel::Debug as _,
+                r#type: runtime_log_event::LogType::System as _,
+                msg: "pulling image".to_owned(),
+                ..Default::default()
+            }),
+            RunnerEvent::TaskCompletion(commanderpb::TaskCompleteEvent {
+                outcome: Some(task_complete_event::Outcome::Success(
+                    commanderpb::TaskSuccess {
+                        message: "ok".to_owned(),
+                        cache_hit: Some(true),
+                        runtime_table_preview: vec![commanderpb::RuntimeTablePreview {
+                            table_name: "trips".to_owned(),
+                            columns: vec![commanderpb::RuntimeTableColumnInfo {
+                                column_name: "id".to_owned(),
+                                column_type: "int64".to_owned(),
+                                values: vec!["1".to_owned(), "2".to_owned()],
+                            }],
+                        }],
+                    },
+                )),
+                task_metadata: Some(metadata(TaskLevel::Dag)),
+                timestamp: Some(ts),
+                task_id: "t1".to_owned(),
+                task_name: "run trips".to_owned(),
+            }),
+            RunnerEvent::TaskCompletion(commanderpb::TaskCompleteEvent {
+                outcome: Some(task_complete_event::Outcome::Failure(
+                    commanderpb::TaskFailure {
+                        error_message: "expectation failed".to_owned(),
+                        stack_trace: Some(String::new()),
+                        is_fatal: false,
+                        ..Default::default()
+                    },
+                )),
+                task_id: "t2".to_owned(),
+                ..Default::default()
+            }),
+            RunnerEvent::TaskCompletion(commanderpb::TaskCompleteEvent {
+                outcome: Some(task_complete_event::Outcome::Skipped(Default::default())),
+                task_id: "t3".to_owned(),
+                ..Default::default()
+            }),
+            RunnerEvent::JobCompletion(commanderpb::JobCompleteEvent {
+                outcome: Some(job_complete_event::Outcome::Success(
+                    commanderpb::JobSuccess {
+                        msg: "done".to_owned(),
+                    },
+                )),
+                job_id: "job".to_owned(),
+            }),
+        ]
+    }
+
+    fn convert_all(events: Vec<RunnerEvent>) -> Vec<JobEvent> {
+        let mut converter = Converter::new();
+        events
+            .into_iter()
+            .flat_map(|ev| converter.convert(ev))
+            .collect()
+    }
+
+    #[test]
+    fn run() {
+        let events = convert_all(run_fixture());
+        assert_eq!(events.len(), 9);
+
+        assert!(matches!(&events[0], JobEvent::Other(name) if name == "GlobalLivelinessHeartbeat"));
+
+        let JobEvent::TaskStarted(task) = &events[1] else {
+            panic!("expected TaskStarted, got {:?}", events[1]);
+        };
+        assert_eq!(task.id, "t1");
+        assert!(task.metadata.is_dag());
+        assert_eq!(task.metadata.line_number, Some(12));
+        assert_eq!(task.timestamp.map(|ts| ts.timestamp()), Some(1_700_000_000));
+
+        let JobEvent::UserLog(log) = &events[2] else {
+            panic!("expected UserLog, got {:?}", events[2]);
+        };
+        assert_eq!(log.message, "loaded 10 rows");
+        assert_eq!(log.level, LogLevel::Info);
+        assert_eq!(log.stream, OutputStream::Stderr);
+        assert!(log.user);
+
+        let JobEvent::UserLog(log) = &events[3] else {
+            panic!("expected UserLog, got {:?}", events[3]);
+        };
+        assert!(!log.user);
+        assert_eq!(log.task, TaskMetadata::default());
+
+        let JobEvent::TaskCompleted(task, outcome) = &events[4] else {
+            panic!("expected TaskCompleted, got {:?}", events[4]);
+        };
+        assert_eq!(task.id, "t1");
+        assert_eq!(
+            outcome,
+            &TaskOutcome::Success {
+                message: "ok".to_owned(),
+                cached: true,
+            }
+        );
+
+        let JobEvent::Preview(preview) = &events[5] else {
+            panic!("expected Preview, got {:?}", events[5]);
+        };
+        assert_eq!(preview.task_id, "t1");
+        assert_eq!(preview.table_name, "trips");
+        assert_eq!(preview.columns[0].name, "id");
+        assert_eq!(preview.num_rows(), 2);
+
+        assert!(matches!(
+            &events[6],
+            JobEvent::TaskCompleted(
+                _,
+                TaskOutcome::Failure {
+                    fatal: false,
+                    stack_trace: None,
+                    ..
+                }
+            )
+        ));
+        assert!(matches!(
+            &events[7],
+            JobEvent::TaskCompleted(_, TaskOutcome::Skipped)
+        ));
+
+        let JobEvent::Completion(completion) = &events[8] else {
+            panic!("expected Completion, got {:?}", events[8]);
+        };
+        assert_eq!(completion.result.as_deref().ok(), Some("done"));
+        assert_eq!(
+            completion.metrics,
+            JobMetrics {
+                tasks_started: 1,
+                tasks_succeeded: 1,
+                tasks_failed: 1,
+                tasks_skipped: 1,
+                tasks_cancelled: 0,
+                user_logs: 1,
+                elapsed: completion.metrics.elapsed,
+            }
+        );
+    }
+
+    #[test]
+    fn empty_output() {
+        let mut fixture = run_fixture();
+        let RunnerEvent::TaskCompletion(commanderpb::TaskCompleteEvent {
+            outcome: Some(task_complete_event::Outcome::Success(success)),
+            ..
+        }) = &mut fixture[4]
+        else {
+            panic!("expected a successful TaskCompletion");
+        };
+        success.runtime_table_preview[0].columns[0].values.clear();
+        let events = convert_all(fixture);
+
+        let mut check = EmptyOutputCheck::new(Vec::new());
+        let found: Vec<_> = events
+            .iter()
+            .filter_map(|ev| check.observe(ev).map(str::to_owned))
+            .collect();
+        assert_eq!(found, vec!["trips".to_owned()]);
+        assert_eq!(check.empty_models(), ["trips".to_owned()]);
+        assert_eq!(
+            check.failure_reason().as_deref(),
+            Some("empty output: trips")
+        );
+
+        // Models that aren't listed aren't checked.
+        let mut check = EmptyOutputCheck::new(vec!["zones".to_owned()]);
+        for ev in &events {
+            assert_eq!(check.observe(ev), None);
+        }
+        assert_eq!(check.failure_reason(), None);
+
+        // Nor is a model whose preview has rows.
+        let mut check = EmptyOutputCheck::new(Vec::new());
+        for ev in &convert_all(run_fixture()) {
+            assert_eq!(check.observe(ev), None);
+        }
+        assert!(check.empty_models().is_empty());
+    }
+
+    #[test]
+    fn plans() {
+        let events = convert_all(vec![
+            RunnerEvent::TableCreatePlanDoneEvent(commanderpb::TableCreatePlanDoneEvent {
+                plan_as_yaml: "schema_info: {}".to_owned(),
+                can_auto_apply: true,
+                files_to_be_imported: vec!["s3://bucket/a.parquet".to_owned()],
+                ..Default::default()
+            }),
+            RunnerEvent::ImportPlanCreated(commanderpb::ImportPlanCreatedEvent {
+                plan_as_yaml: "schema_info: {}".to_owned(),
+                success: true,
+                ..Default::default()
+            }),
+            RunnerEvent::ApplyPlanDone(Default::default()),
+        ]);
+
+        let JobEvent::TableCreatePlanned(plan) = &events[0] else {
+            panic!("expected TableCreatePlanned, got {:?}", events[0]);
+        };
+        assert!(plan.can_auto_apply);
+        assert_eq!(plan.error, None);
+
+        assert!(matches!(&events[1], JobEvent::Other(name) if name == "ImportPlanCreated"));
+
+        assert!(matches!(&events[2], JobEvent::Other(name) if name == "ApplyPlanDone"));
+    }
+
+    #[test]
+    fn failed_completion() {
+        let events = convert_all(vec![
+            RunnerEvent::FlightServerStart(commanderpb::FlightServerStartEvent {
+                endpoint: "flight.example.com:443".to_owned(),
+                magic_token: "token".to_owned(),
+                num_rows: 3,
+                ..Default::default()
+            }),
+            RunnerEvent::JobCompletion(commanderpb::JobCompleteEvent {
+                outcome: Some(job_complete_event::Outcome::Rejected(
+                    commanderpb::JobRejected {
+                        reason: "too many jobs".to_owned(),
+                    },
+                )),
+                ..Default::default()
+            }),
+            RunnerEvent::JobCompletion(Default::default()),
+        ]);
+
+        let JobEvent::FlightReady(server) = &events[0] else {
+            panic!("expected FlightReady, got {:?}", events[0]);
+        };
+        assert_eq!(server.endpoint, "flight.example.com:443");
+        assert_eq!(server.num_rows, 3);
+
+        assert!(matches!(
+            &events[1],
+            JobEvent::Completion(Completion {
+                result: Err(JobError::Rejected(reason)),
+                ..
+            }) if reason == "too many jobs"
+        ));
+        assert!(matches!(
+            &events[2],
+            JobEvent::Completion(Completion {
+                result: Err(JobError::Unknown),
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn converted_stream() {
+        let events = stream::iter(run_fixture().into_iter().map(Ok::<_, tonic::Status>));
+        let events: Vec<_> = convert_stream(events).try_collect().await.unwrap();
+        assert_eq!(events.len(), 9);
+        assert!(matches!(events.last(), Some(JobEvent::Completion(_))));
+    }
+}
@@ -0,0 +1,483 @@
+//! Recording the raw runner events of a job to a file, and reading them
+//! back.
+//!
+//! Each line of a capture is a JSON object with the time the event was
+//! received, the job it belongs to, the kind of event, and the event itself
+//! as a base64-encoded protobuf message. The protobuf encoding keeps fields
+//! the client doesn't know about yet, so a capture from a newer server still
+//! has everything the server sent.
+//!
+//! Once a file grows past its size limit, the capture continues in a new
+//! file with a numeric suffix: `events.jsonl`, then `events.jsonl.1`,
+//! `events.jsonl.2`, and so on. [`read_events`] reads all of them, in order.
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader, BufWriter, Write as _},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time,
+};
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use chrono::{DateTime, Utc};
+use prost::Message as _;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::{Converter, JobEvent};
+use crate::grpc::generated::{self as commanderpb, runner_event::Event as RunnerEvent};
+
+/// The size past which a capture continues in a new file, by default.
+pub const DEFAULT_MAX_FILE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// How often buffered events are written out, at most.
+const FLUSH_INTERVAL: time::Duration = time::Duration::from_secs(1);
+
+/// A runner event, as it was received.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedEvent {
+    /// When the client received the event.
+    pub received_at: DateTime<Utc>,
+    /// The job the event belongs to.
+    pub job_id: String,
+    /// The event.
+    pub event: RunnerEvent,
+}
+
+/// A line of a capture.
+#[derive(Debug, Serialize, Deserialize)]
+struct Line<'a> {
+    received_at: DateTime<Utc>,
+    #[serde(borrow)]
+    job_id: Cow<'a, str>,
+    #[serde(borrow)]
+    kind: Cow<'a, str>,
+    event: String,
+}
+
+/// Records runner events to a file. Clones share the same file, so one
+/// recorder can capture several jobs, like the two jobs of a table creation.
+///
+/// Failing to write an event is logged, and stops the recording; it doesn't
+/// fail the job being recorded.
+#[derive(Debug, Clone)]
+pub struct EventRecorder(Arc<Mutex<Recorder>>);
+
+#[derive(Debug)]
+struct Recorder {
+    path: PathBuf,
+    // `None` once a write fails.
+    file: Option<BufWriter<File>>,
+    written: u64,
+    max_file_bytes: u64,
+    part: u32,
+    last_flush: time::Instant,
+}
+
+impl EventRecorder {
+    /// Creates the capture file at `path`, replacing any earlier capture
+    /// there, including the files it continued in.
+    pub fn create(path: impl Into<PathBuf>) -> io::Result<Self> {
+        Self::with_max_file_bytes(path, DEFAULT_MAX_FILE_BYTES)
+    }
+
+    /// Like [`EventRecorder::create`], continuing in a new file each time
+    /// one grows past `max_file_bytes`.
+    pub fn with_max_file_bytes(path: impl Into<PathBuf>, max_file_bytes: u64) -> io::Result<Self> {
+        let path = path.into();
+        for part in 1.. {
+            match std::fs::remove_file(part_path(&path, part)) {
+                Ok(()) => (),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        let file = BufWriter::new(File::create(&path)?);
+        Ok(Self(Arc::new(Mutex::new(Recorder {
+            path,
+            file: Some(file),
+            written: 0,
+            max_file_bytes,
+            part: 0,
+            last_flush: time::Instant::now(),
+        }))))
+    }
+
+    /// Records an event received for `job_id`. Events are flushed to the
+    /// file periodically, and after the event that completes a job.
+    pub fn record(&self, job_id: &str, event: &RunnerEvent) {
+        let mut recorder = self.lock();
+        if let Err(e) = recorder.record(job_id, event) {
+            warn!(path = %recorder.path.display(), "failed to record job events: {e}");
+            recorder.file = None;
+        }
+    }
+
+    /// Writes out any buffered events.
+    pub fn flush(&self) -> io::Result<()> {
+        match &mut self.lock().file {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Recorder> {
+        // A panic while recording leaves nothing to repair.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Recorder {
+    fn record(&mut self, job_id: &str, event: &RunnerEvent) -> io::Result<()> {
+        if self.file.is_none() {
+            return Ok(());
+        }
+
+        let message = commanderpb::RunnerEvent {
+            event: Some(event.clone()),
+        };
+
+        let mut line = serde_json::to_vec(&Line {
+            received_at: Utc::now(),
+            job_id: job_id.into(),
+            kind: kind(event).into(),
+            event: STANDARD.encode(message.encode_to_vec()),
+        })?;
+        line.push(b'\n');
+
+        if self.written > 0 && self.written + line.len() as u64 > self.max_file_bytes {
+            self.rotate()?;
+        }
+
+        let Some(file) = &mut self.file else {
+            return Ok(());
+        };
+
+        file.write_all(&line)?;
+        self.written += line.len() as u64;
+
+        if matches!(event, RunnerEvent::JobCompletion(_))
+            || self.last_flush.elapsed() >= FLUSH_INTERVAL
+        {
+            file.flush()?;
+            self.last_flush = time::Instant::now();
+        }
+
+        Ok(())
+    }
+
+    /// Finishes the current file, and continues in the next one.
+    fn rotate(&mut self) -> io::Result<()> {
+        if let Some(mut file) = self.file.take() {
+            file.flush()?;
+        }
+
+        self.part += 1;
+        self.file = Some(BufWriter::new(File::create(part_path(
+            &self.path, self.part,
+        ))?));
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if let Some(file) = &mut self.file
+            && let Err(e) = file.flush()
+        {
+            warn!(path = %self.path.display(), "failed to record job events: {e}");
+        }
+    }
+}
+
+/// The path of a file a capture continued in, or of the first file for
+/// part 0.
+fn part_path(path: &Path, part: u32) -> PathBuf {
+    if part == 0 {
+        return path.to_owned();
+    }
+
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{part}"));
+    PathBuf::from(name)
+}
+
+/// An error reading a capture.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum ReadEventsError {
+    #[error("failed to read {}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error("{}:{line}: {message}", path.display())]
+    Invalid {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+}
+
+/// Reads the events of a capture, starting at `path` and continuing through
+/// the files with numeric suffixes after it. A partial last line, from a
+/// capture that was cut short, is ignored.
+pub fn read_events(path: &Path) -> Result<Vec<RecordedEvent>, ReadEventsError> {
+    let mut events = Vec::new();
+    for part in 0.. {
+        let part_path = part_path(path, part);
+        let file = match File::open(&part_path) {
+            Ok(file) => file,
+            Err(e) if part > 0 && e.kind() == io::ErrorKind::NotFound => break,
+            Err(source) => {
+                return Err(ReadEventsError::Io {
+                    path: part_path,
+                    source,
+                });
+            }
+        };
+
+        read_file(&part_path, BufReader::new(file), &mut events)?;
+    }
+
+    Ok(events)
+}
+
+fn read_file(
+    path: &Path,
+    reader: impl io::BufRead,
+    events: &mut Vec<RecordedEvent>,
+) -> Result<(), ReadEventsError> {
+    let invalid = |line: usize, message: String| ReadEventsError::Invalid {
+        path: path.to_owned(),
+        line,
+        message,
+    };
+
+    let mut lines = reader.lines().enumerate().peekable();
+    while let Some((i, line)) = lines.next() {
+        let line = line.map_err(|source| ReadEventsError::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let parsed: Line<'_> = match serde_json::from_str(&line) {
+            Ok(parsed) => parsed,
+            Err(e) if e.is_eof() && lines.peek().is_none() => break,
+            Err(e) => return Err(invalid(i + 1, e.to_string())),
+        };
+
+        let bytes = STANDARD
+            .decode(&parsed.event)
+            .map_err(|e| invalid(i + 1, format!("invalid event: {e}")))?;
+        let message = commanderpb::RunnerEvent::decode(bytes.as_slice())
+            .map_err(|e| invalid(i + 1, format!("invalid event: {e}")))?;
+
+        // An event kind this client doesn't know about.
+        let Some(event) = message.event else {
+            continue;
+        };
+
+        events.push(RecordedEvent {
+            received_at: parsed.received_at,
+            job_id: parsed.job_id.into_owned(),
+            event,
+        });
+    }
+
+    Ok(())
+}
+
+/// Converts recorded events to [`JobEvent`]s, the way
+/// [`grpc::Client::monitor_job`] converted them when they were received.
+/// Each event is paired with the ID of its job.
+///
+/// The elapsed time reported with a job's completion is the time between the
+/// first and last events recorded for the job.
+///
+/// [`grpc::Client::monitor_job`]: crate::grpc::Client::monitor_job
+pub fn replay(events: &[RecordedEvent]) -> Vec<(&str, JobEvent)> {
+    let mut converters: HashMap<&str, (Converter, DateTime<Utc>)> = HashMap::new();
+    let mut replayed = Vec::new();
+    for recorded in events {
+        let (converter, first) = converters
+            .entry(recorded.job_id.as_str())
+            .or_insert_with(|| (Converter::new(), recorded.received_at));
+
+        for mut event in converter.convert(recorded.event.clone()) {
+            if let JobEvent::Completion(completion) = &mut event {
+                completion.metrics.elapsed =
+                    (recorded.received_at - *first).to_std().unwrap_or_default();
+            }
+
+            replayed.push((recorded.job_id.as_str(), event));
+        }
+    }
+
+    replayed
+}
+
+/// The name of an event's kind, as written to a capture.
+fn kind(event: &RunnerEvent) -> &'static str {
+    match event {
+        RunnerEvent::TaskStart(_) => "TaskStart",
+        RunnerEvent::TaskCompletion(_) => "TaskCompletion",
+        RunnerEvent::JobCompletion(_) => "JobCompletion",
+        RunnerEvent::RuntimeUserLog(_) => "RuntimeUserLog",
+        RunnerEvent::FlightServerStart(_) => "FlightServerStart",
+        RunnerEvent::ImportPlanCreated(_) => "ImportPlanCreated",
+        RunnerEvent::ApplyPlanDone(_) => "ApplyPlanDone",
+        RunnerEvent::TableCreatePlanDoneEvent(_) => "TableCreatePlanDone",
+        RunnerEvent::TableCreatePlanApplyDoneEvent(_) => "TableCreatePlanApplyDone",
+        RunnerEvent::GlobalLivelinessHeartbeat(_) => "GlobalLivelinessHeartbeat",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(msg: &str) -> RunnerEvent {
+        RunnerEvent::RuntimeUserLog(commanderpb::RuntimeLogEvent {
+            r#type: commanderpb::runtime_log_event::LogType::User as _,
+            msg: msg.to_owned(),
+            job_id: "job".to_owned(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn round_trip() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("events.jsonl");
+
+        let recorder = EventRecorder::create(&path)?;
+        recorder.record("job", &log("first"));
+        recorder.record("job", &RunnerEvent::JobCompletion(Default::default()));
+        drop(recorder);
+
+        let events = read_events(&path)?;
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].job_id, "job");
+        assert_eq!(events[0].event, log("first"));
+        assert!(matches!(events[1].event, RunnerEvent::JobCompletion(_)));
+
+        let contents = std::fs::read_to_string(&path)?;
+        assert!(
+            contents.contains(r#""kind":"RuntimeUserLog""#),
+            "{contents}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rotates() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("events.jsonl");
+
+        let recorder = EventRecorder::with_max_file_bytes(&path, 300)?;
+        let messages: Vec<_> = (0..10).map(|i| format!("line {i}")).collect();
+        for msg in &messages {
+            recorder.record("job", &log(msg));
+        }
+        drop(recorder);
+
+        assert!(part_path(&path, 1).exists());
+        for part in 0.. {
+            let part_path = part_path(&path, part);
+            if !part_path.exists() {
+                break;
+            }
+
+            assert!(std::fs::metadata(&part_path)?.len() <= 300);
+        }
+
+        let events = read_events(&path)?;
+        let read: Vec<_> = events
+            .iter()
+            .map(|e| match &e.event {
+                RunnerEvent::RuntimeUserLog(log) => log.msg.clone(),
+                e => panic!("unexpected event {e:?}"),
+            })
+            .collect();
+        assert_eq!(read, messages);
+
+        // A new capture removes the parts of the old one.
+        let recorder = EventRecorder::create(&path)?;
+        recorder.record("job", &log("new"));
+        drop(recorder);
+
+        assert!(!part_path(&path, 1).exists());
+        assert_eq!(read_events(&path)?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn truncated() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("events.jsonl");
+
+        let recorder = EventRecorder::create(&path)?;
+        recorder.record("job", &log("first"));
+        recorder.record("job", &log("second"));
+        drop(recorder);
+
+        // Cut the last line short, as a crash might.
+        let contents = std::fs::read_to_string(&path)?;
+        std::fs::write(&path, &contents[..contents.len() - 10])?;
+
+        let events = read_events(&path)?;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, log("first"));
+
+        // Garbage elsewhere is an error.
+        std::fs::write(&path, format!("not json\n{contents}"))?;
+        let err = read_events(&path).unwrap_err().to_string();
+        assert!(
+            err.ends_with(":1: expected ident at line 1 column 2"),
+            "{err}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn replay_by_job() {
+        let start = Utc::now();
+        let at = |secs| start + chrono::TimeDelta::seconds(secs);
+        let recorded = |job_id: &str, secs, event| RecordedEvent {
+            received_at: at(secs),
+            job_id: job_id.to_owned(),
+            event,
+        };
+
+        let events = vec![
+            recorded("a", 0, log("from a")),
+            recorded("b", 1, log("from b")),
+            recorded("b", 3, RunnerEvent::JobCompletion(Default::default())),
+            recorded("a", 5, RunnerEvent::JobCompletion(Default::default())),
+        ];
+
+        let replayed = replay(&events);
+        assert_eq!(replayed.len(), 4);
+
+        let completions: Vec<_> = replayed
+            .iter()
+            .filter_map(|(job_id, event)| match event {
+                JobEvent::Completion(c) => Some((*job_id, c.metrics)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(completions[0].0, "b");
+        assert_eq!(completions[0].1.user_logs, 1);
+        assert_eq!(completions[0].1.elapsed, std::time::Duration::from_secs(2));
+        assert_eq!(completions[1].0, "a");
+        assert_eq!(completions[1].1.elapsed, std::time::Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,194 @@
+//! Helpers for writing query results to local files.
+//!
+//! Output paths may contain placeholders, which are expanded once the job
+//! has been submitted:
+//!
+//! - `{job_id}`: the ID of the query job
+//! - `{date}`: the current local date, as `YYYY-MM-DD`
+//! - `{time}`: the current local time, as `HHMMSS`
+//! - any `strftime` specifier, like `%Y` or `%H`
+//!
+//! Literal braces are written as `{{` and `}}`, and a literal percent sign as
+//! `%%`.
+
+use std::{
+    fmt::Display,
+    fs::{self, File, OpenOptions},
+    io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{
+    DateTime, TimeZone,
+    format::{Item, StrftimeItems},
+};
+
+/// An error expanding an output path template.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum OutputPathError {
+    /// The template used a `{...}` placeholder that isn't recognized.
+    #[error(
+        "unknown placeholder {{{0}}} in output path (expected {{job_id}}, {{date}} or {{time}})"
+    )]
+    UnknownPlaceholder(String),
+    /// The template had a `{` or `}` without a match.
+    #[error("unmatched brace in output path (use {{{{ or }}}} for a literal brace)")]
+    UnmatchedBrace,
+    /// The template had an invalid `strftime` specifier.
+    #[error("invalid strftime specifier in output path {0:?}")]
+    InvalidStrftime(String),
+}
+
+/// Returns true if the path contains anything [`expand_output_path`] would
+/// replace.
+pub fn is_output_path_template(template: &str) -> bool {
+    template.contains(['{', '}', '%'])
+}
+
+/// Expands the placeholders in an output path. See the [module
+/// documentation](self) for the supported syntax.
+pub fn expand_output_path<Tz>(
+    template: &str,
+    job_id: &str,
+    now: &DateTime<Tz>,
+) -> Result<PathBuf, OutputPathError>
+where
+    Tz: TimeZone,
+    Tz::Offset: Display,
+{
+    let formatted = if template.contains('%') {
+        let items: Vec<_> = StrftimeItems::new(template).collect();
+        if items.iter().any(|item| matches!(item, Item::Error)) {
+            return Err(OutputPathError::InvalidStrftime(template.to_owned()));
+        }
+
+        now.format_with_items(items.iter()).to_string()
+    } else {
+        template.to_owned()
+    };
+
+    let mut out = String::with_capacity(formatted.len());
+    let mut chars = formatted.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some('{') | None => return Err(OutputPathError::UnmatchedBrace),
+                        Some(c) => name.push(c),
+                    }
+                }
+
+                match name.as_str() {
+                    "job_id" => out.push_str(job_id),
+                    "date" => out.push_str(&now.format("%Y-%m-%d").to_string()),
+                    "time" => out.push_str(&now.format("%H%M%S").to_string()),
+                    _ => return Err(OutputPathError::UnknownPlaceholder(name)),
+                }
+            }
+            '}' => return Err(OutputPathError::UnmatchedBrace),
+            c => out.push(c),
+        }
+    }
+
+    Ok(PathBuf::from(out))
+}
+
+/// Opens a file to write query results to. Unless `overwrite` is set, this
+/// fails with [`io::ErrorKind::AlreadyExists`] if the file exists. If
+/// `mkdir_parents` is set, any missing parent directories are created first.
+pub fn create_output_file(path: &Path, overwrite: bool, mkdir_parents: bool) -> io::Result<File> {
+    if mkdir_parents && let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut opts = OpenOptions::new();
+    opts.write(true);
+    if overwrite {
+        opts.create(true).truncate(true);
+    } else {
+        opts.create_new(true);
+    }
+
+    opts.open(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn now() -> DateTime<Utc> {
+        "2025-03-07T14:05:09Z".parse().unwrap()
+    }
+
+    #[test]
+    fn expand_placeholders() {
+        let cases = [
+            ("out.parquet", "out.parquet"),
+            (
+                "out/{job_id}_{date}.parquet",
+                "out/abc123_2025-03-07.parquet",
+            ),
+            ("{date}T{time}.csv", "2025-03-07T140509.csv"),
+            ("%Y/%m/{job_id}.json", "2025/03/abc123.json"),
+            ("{{literal}}_100%%.csv", "{literal}_100%.csv"),
+        ];
+
+        for (template, expected) in cases {
+            assert_eq!(
+                expand_output_path(template, "abc123", &now()).unwrap(),
+                PathBuf::from(expected),
+                "{template}"
+            );
+        }
+    }
+
+    #[test]
+    fn expand_errors() {
+        let err = |template| expand_output_path(template, "abc123", &now()).unwrap_err();
+
+        assert!(matches!(
+            err("{user}.csv"),
+            OutputPathError::UnknownPlaceholder(name) if name == "user"
+        ));
+        assert!(matches!(
+            err("{job_id.csv"),
+            OutputPathError::UnmatchedBrace
+        ));
+        assert!(matches!(
+            err("job_id}.csv"),
+            OutputPathError::UnmatchedBrace
+        ));
+        assert!(matches!(err("%Q.csv"), OutputPathError::InvalidStrftime(_)));
+    }
+
+    #[test]
+    fn create_collision() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested/out.csv");
+
+        assert_eq!(
+            create_output_file(&path, false, false).unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+
+        create_output_file(&path, false, true).unwrap();
+        assert_eq!(
+            create_output_file(&path, false, true).unwrap_err().kind(),
+            io::ErrorKind::AlreadyExists
+        );
+
+        create_output_file(&path, true, true).unwrap();
+    }
+}
@@ -1,16 +1,116 @@
 //! Support for fetching query results via Arrow Flight.
 
-use std::time;
+use std::{fmt, time};
 
 use arrow::{array::RecordBatch, datatypes::Schema};
 use arrow_flight::{
     FlightClient, FlightEndpoint,
     error::{FlightError, Result as FlightResult},
 };
+use chrono::{DateTime, Utc};
 use futures::{Stream, StreamExt as _, TryStreamExt as _, stream};
 use http::Uri;
 use serde_json::json;
 use tonic::transport::{Channel, ClientTlsConfig};
+use tracing::warn;
+
+use crate::events::FlightServer;
+
+/// How long a flight server keeps a query's results after the query
+/// finishes. The server may shut down sooner, once the results have been
+/// fetched.
+pub const RESULTS_TTL: time::Duration = time::Duration::from_secs(600);
+
+/// How many times to retry connecting to a flight server, by default.
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// How long to wait before the first retry. The wait doubles after each one.
+const INITIAL_BACKOFF: time::Duration = time::Duration::from_millis(200);
+
+/// Where to fetch a finished query's results over Arrow Flight, so that the
+/// fetch can be handed to another process.
+///
+/// The token grants access to the results, without any other credentials,
+/// until they expire. Treat it like an API key: it's redacted when the
+/// endpoint is printed, and shouldn't be logged.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ResultsEndpoint {
+    /// The address of the flight server.
+    pub endpoint: Uri,
+    /// The token to authenticate with.
+    pub auth_token: String,
+    /// When the results stop being available, if known.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl fmt::Debug for ResultsEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResultsEndpoint")
+            .field("endpoint", &self.endpoint)
+            .field("auth_token", &"<redacted>")
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
+impl ResultsEndpoint {
+    /// An endpoint from its parts, as handed over from another process. The
+    /// address may omit the scheme, in which case TLS is used. Fails with
+    /// the address if it's invalid.
+    pub fn new(
+        endpoint: &str,
+        auth_token: String,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, String> {
+        let endpoint = if endpoint.starts_with("http://") || endpoint.starts_with("https://") {
+            endpoint.to_owned()
+        } else {
+            format!("https://{endpoint}")
+        };
+
+        Ok(Self {
+            endpoint: endpoint.parse().map_err(|_| endpoint)?,
+            auth_token,
+            expires_at,
+        })
+    }
+
+    /// The endpoint for the results served by `server`, for a query that
+    /// finished at `finished_at`.
+    pub fn from_server(
+        server: FlightServer,
+        finished_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, String> {
+        let expires_at = finished_at.map(|t| t + RESULTS_TTL);
+        Self::new(&server.endpoint, server.magic_token, expires_at)
+    }
+
+    /// Whether the results have expired as of `now`.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|t| now >= t)
+    }
+
+    /// Fetches the results, like [`fetch_flight_results_with_retries`].
+    pub async fn fetch(
+        self,
+        client_timeout: time::Duration,
+        row_limit: Option<u64>,
+        max_retries: u32,
+    ) -> FlightResult<(
+        Schema,
+        impl Stream<Item = FlightResult<RecordBatch>> + use<>,
+    )> {
+        fetch_flight_results_with_retries(
+            self.endpoint,
+            self.auth_token,
+            client_timeout,
+            row_limit,
+            None,
+            max_retries,
+        )
+        .await
+    }
+}
 
 /// Connects to a given flight server and streams all the batches from all the
 /// endpoints. This is bauplan-specific and not generically useful.
@@ -50,6 +150,60 @@ pub async fn fetch_flight_results(
     Ok((schema, batches.chain(shutdown)))
 }
 
+/// Like [`fetch_flight_results`], but retries up to `max_retries` times,
+/// with exponential backoff, if the flight server can't be reached. Errors
+/// returned by the server, and errors while streaming the batches, aren't
+/// retried. If every attempt fails, the last error is returned.
+pub async fn fetch_flight_results_with_retries(
+    endpoint: Uri,
+    auth_token: String,
+    client_timeout: time::Duration,
+    row_limit: Option<u64>,
+    traceparent: Option<&str>,
+    max_retries: u32,
+) -> FlightResult<(
+    Schema,
+    impl Stream<Item = FlightResult<RecordBatch>> + use<>,
+)> {
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 0;
+    loop {
+        let res = fetch_flight_results(
+            endpoint.clone(),
+            auth_token.clone(),
+            client_timeout,
+            row_limit,
+            traceparent,
+        )
+        .await;
+
+        match res {
+            Err(err) if attempt < max_retries && is_transient(&err) => {
+                attempt += 1;
+                warn!(%err, attempt, ?backoff, "failed to reach flight server, retrying");
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+            res => return res,
+        }
+    }
+}
+
+/// Whether a flight error is a connection or transport failure, which may
+/// succeed on retry, rather than an error returned by the server.
+fn is_transient(err: &FlightError) -> bool {
+    let FlightError::Tonic(status) = err else {
+        return false;
+    };
+
+    // Connection failures, including TLS handshakes, are reported as
+    // Unavailable. A connection that drops before the server responds is
+    // Unknown, so we also check for a transport error underneath.
+    status.code() == tonic::Code::Unavailable
+        || std::error::Error::source(status.as_ref())
+            .is_some_and(|e| e.is::<tonic::transport::Error>())
+}
+
 async fn fetch(
     channel: Channel,
     auth_token: String,
@@ -171,4 +325,269 @@ mod tests {
         assert_eq!(row_counts, vec![3, 1]);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_row_limit_stops_polling() -> anyhow::Result<()> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let make_batch = |values: &[i32]| {
+            let array = arrow::array::Int32Array::from(values.to_vec());
+            let schema = Arc::new(Schema::new(vec![Field::new("x", DataType::Int32, false)]));
+            RecordBatch::try_new(schema, vec![Arc::new(array)]).unwrap()
+        };
+
+        // An endless stream of batches; the limit must be what ends it.
+        let polled = AtomicUsize::new(0);
+        let input = stream::repeat_with(|| {
+            polled.fetch_add(1, Ordering::SeqCst);
+            FlightResult::Ok(make_batch(&[1, 2, 3]))
+        });
+
+        let batches: Vec<RecordBatch> = limit_rows(input, Some(7)).try_collect().await?;
+
+        let row_counts: Vec<_> = batches.iter().map(|b| b.num_rows()).collect();
+        assert_eq!(row_counts, vec![3, 3, 1]);
+        assert_eq!(polled.load(Ordering::SeqCst), 3);
+        Ok(())
+    }
+
+    fn server(endpoint: &str) -> FlightServer {
+        FlightServer {
+            endpoint: endpoint.to_owned(),
+            magic_token: "secret-token".to_owned(),
+            num_rows: 10,
+            use_tls: true,
+        }
+    }
+
+    #[test]
+    fn results_endpoint() {
+        let finished_at = Utc::now();
+        let ep = ResultsEndpoint::from_server(server("flight.example.com:443"), Some(finished_at))
+            .unwrap();
+        assert_eq!(ep.endpoint, "https://flight.example.com:443/");
+        assert_eq!(ep.expires_at, Some(finished_at + RESULTS_TTL));
+
+        let ep = ResultsEndpoint::from_server(server("http://10.0.0.1:8815"), None).unwrap();
+        assert_eq!(ep.endpoint, "http://10.0.0.1:8815/");
+
+        assert_eq!(
+            ResultsEndpoint::from_server(server("not a host"), None),
+            Err("https://not a host".to_owned())
+        );
+    }
+
+    #[test]
+    fn expiry() {
+        let finished_at = Utc::now();
+        let ep =
+            ResultsEndpoint::from_server(server("flight.example.com"), Some(finished_at)).unwrap();
+        assert!(!ep.is_expired(finished_at));
+        assert!(ep.is_expired(finished_at + RESULTS_TTL));
+
+        // Without a finish time, there's no known expiry.
+        let ep = ResultsEndpoint::from_server(server("flight.example.com"), None).unwrap();
+        assert!(!ep.is_expired(finished_at + RESULTS_TTL * 10));
+    }
+
+    #[test]
+    fn token_redacted() {
+        let ep = ResultsEndpoint::from_server(server("flight.example.com"), None).unwrap();
+        let debug = format!("{ep:?}");
+        assert!(!debug.contains("secret-token"), "{debug}");
+        assert!(debug.contains("<redacted>"));
+    }
+
+    mod mock {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use arrow_flight::{
+            Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo,
+            HandshakeRequest, HandshakeResponse, PollInfo, PutResult, SchemaResult, Ticket,
+            flight_service_server::{FlightService, FlightServiceServer},
+        };
+        use futures::stream::BoxStream;
+        use tonic::{Request, Response, Status, Streaming, transport::Server};
+
+        use super::*;
+
+        /// A flight server with a single, empty result set, which rejects
+        /// any token but "secret-token".
+        #[derive(Default)]
+        pub struct MockFlightService {
+            pub list_calls: Arc<AtomicUsize>,
+        }
+
+        impl MockFlightService {
+            /// Serves on a local port, dropping the first `drop_connections`
+            /// connections without a response.
+            pub async fn serve(self, drop_connections: usize) -> anyhow::Result<Uri> {
+                let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+                let uri = format!("http://{}", listener.local_addr()?).parse()?;
+
+                let incoming = stream::unfold(
+                    (listener, drop_connections),
+                    |(listener, mut remaining)| async move {
+                        loop {
+                            let conn = listener.accept().await.map(|(stream, _)| stream);
+                            if remaining > 0 && conn.is_ok() {
+                                remaining -= 1;
+                                continue;
+                            }
+
+                            return Some((conn, (listener, remaining)));
+                        }
+                    },
+                );
+
+                tokio::spawn(
+                    Server::builder()
+                        .add_service(FlightServiceServer::new(self))
+                        .serve_with_incoming(incoming),
+                );
+                Ok(uri)
+            }
+        }
+
+        #[tonic::async_trait]
+        impl FlightService for MockFlightService {
+            type HandshakeStream = BoxStream<'static, Result<HandshakeResponse, Status>>;
+            type ListFlightsStream = BoxStream<'static, Result<FlightInfo, Status>>;
+            type DoGetStream = BoxStream<'static, Result<FlightData, Status>>;
+            type DoPutStream = BoxStream<'static, Result<PutResult, Status>>;
+            type DoExchangeStream = BoxStream<'static, Result<FlightData, Status>>;
+            type DoActionStream = BoxStream<'static, Result<arrow_flight::Result, Status>>;
+            type ListActionsStream = BoxStream<'static, Result<ActionType, Status>>;
+
+            async fn list_flights(
+                &self,
+                request: Request<Criteria>,
+            ) -> Result<Response<Self::ListFlightsStream>, Status> {
+                self.list_calls.fetch_add(1, Ordering::SeqCst);
+                let auth = request.metadata().get("authorization");
+                if auth.is_none_or(|v| v != "Bearer secret-token") {
+                    return Err(Status::unauthenticated("invalid token"));
+                }
+
+                let schema = Schema::new(vec![Field::new("x", DataType::Int32, false)]);
+                let info = FlightInfo::new()
+                    .try_with_schema(&schema)
+                    .map_err(|e| Status::internal(e.to_string()))?;
+                Ok(Response::new(stream::iter([Ok(info)]).boxed()))
+            }
+
+            async fn do_action(
+                &self,
+                _request: Request<Action>,
+            ) -> Result<Response<Self::DoActionStream>, Status> {
+                Ok(Response::new(stream::empty().boxed()))
+            }
+
+            async fn handshake(
+                &self,
+                _request: Request<Streaming<HandshakeRequest>>,
+            ) -> Result<Response<Self::HandshakeStream>, Status> {
+                Err(Status::unimplemented("handshake"))
+            }
+
+            async fn get_flight_info(
+                &self,
+                _request: Request<FlightDescriptor>,
+            ) -> Result<Response<FlightInfo>, Status> {
+                Err(Status::unimplemented("get_flight_info"))
+            }
+
+            async fn poll_flight_info(
+                &self,
+                _request: Request<FlightDescriptor>,
+            ) -> Result<Response<PollInfo>, Status> {
+                Err(Status::unimplemented("poll_flight_info"))
+            }
+
+            async fn get_schema(
+                &self,
+                _request: Request<FlightDescriptor>,
+            ) -> Result<Response<SchemaResult>, Status> {
+                Err(Status::unimplemented("get_schema"))
+            }
+
+            async fn do_get(
+                &self,
+                _request: Request<Ticket>,
+            ) -> Result<Response<Self::DoGetStream>, Status> {
+                Err(Status::unimplemented("do_get"))
+            }
+
+            async fn do_put(
+                &self,
+                _request: Request<Streaming<FlightData>>,
+            ) -> Result<Response<Self::DoPutStream>, Status> {
+                Err(Status::unimplemented("do_put"))
+            }
+
+            async fn do_exchange(
+                &self,
+                _request: Request<Streaming<FlightData>>,
+            ) -> Result<Response<Self::DoExchangeStream>, Status> {
+                Err(Status::unimplemented("do_exchange"))
+            }
+
+            async fn list_actions(
+                &self,
+                _request: Request<Empty>,
+            ) -> Result<Response<Self::ListActionsStream>, Status> {
+                Err(Status::unimplemented("list_actions"))
+            }
+        }
+    }
+
+    async fn fetch_mock(
+        endpoint: Uri,
+        auth_token: &str,
+        max_retries: u32,
+    ) -> FlightResult<(Schema, Vec<RecordBatch>)> {
+        let (schema, batches) = fetch_flight_results_with_retries(
+            endpoint,
+            auth_token.to_owned(),
+            time::Duration::from_secs(5),
+            None,
+            None,
+            max_retries,
+        )
+        .await?;
+        Ok((schema, batches.try_collect().await?))
+    }
+
+    #[tokio::test]
+    async fn retries_dropped_connection() -> anyhow::Result<()> {
+        let endpoint = mock::MockFlightService::default().serve(1).await?;
+
+        let (schema, batches) = fetch_mock(endpoint, "secret-token", 1).await?;
+        assert_eq!(schema.field(0).name(), "x");
+        assert!(batches.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() -> anyhow::Result<()> {
+        let endpoint = mock::MockFlightService::default().serve(2).await?;
+
+        let err = fetch_mock(endpoint, "secret-token", 1).await.unwrap_err();
+        assert!(is_transient(&err), "{err}");
+        assert!(err.to_string().contains("transport error"), "{err}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn server_errors_not_retried() -> anyhow::Result<()> {
+        let service = mock::MockFlightService::default();
+        let list_calls = service.list_calls.clone();
+        let endpoint = service.serve(0).await?;
+
+        let err = fetch_mock(endpoint, "wrong-token", 3).await.unwrap_err();
+        assert!(!is_transient(&err), "{err}");
+        assert!(err.to_string().contains("invalid token"), "{err}");
+        assert_eq!(list_calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        Ok(())
+    }
 }
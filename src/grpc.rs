@@ -1,6 +1,14 @@
 //! Helpers for the deprecated gRPC API.
 
+pub mod explain;
+pub mod import;
 pub mod job;
+pub mod materialization;
+mod retry;
+pub mod search_uri;
+pub mod user;
+
+pub use retry::{DEFAULT_GRPC_RETRIES, RetryChannel};
 
 use std::{sync::Arc, time};
 
@@ -9,7 +17,7 @@ use futures::{Stream, TryStreamExt, stream};
 use rsa::{RsaPublicKey, pkcs8::DecodePublicKey as _};
 use tonic::{
     IntoRequest,
-    metadata::{Ascii, MetadataValue},
+    metadata::{Ascii, MetadataKey, MetadataValue},
     service::{Interceptor, interceptor::InterceptedService},
     transport::{Channel, ClientTlsConfig},
 };
@@ -26,6 +34,7 @@ pub mod generated {
 
 use crate::{
     Profile,
+    events::{self, JobEvent, capture::EventRecorder},
     grpc::generated::{
         CancelJobRequest, GetBauplanInfoRequest, JobFailure, JobSuccess, OrganizationInfo,
         SubscribeLogsRequest, cancel_job_response::CancelStatus, job_complete_event::Outcome,
@@ -36,33 +45,25 @@ use crate::{
 use generated::v2_commander_service_client::V2CommanderServiceClient;
 
 /// A client for the deprecated gRPC API.
-pub type Client = V2CommanderServiceClient<InterceptedService<Channel, AuthInterceptor>>;
+pub type Client = V2CommanderServiceClient<InterceptedService<RetryChannel, AuthInterceptor>>;
 
 impl Client {
-    /// Make a client for the deprecated gRPC API.
+    /// Make a client for the deprecated gRPC API. Calls which only read
+    /// state are retried up to the profile's `grpc_retries` times if the
+    /// connection drops.
     pub fn new_lazy(
         profile: &Profile,
         timeout: time::Duration,
     ) -> Result<Self, tonic::transport::Error> {
-        let api_endpoint = profile.api_endpoint.clone();
-        let channel = Channel::builder(api_endpoint)
+        let channel = Channel::builder(profile.grpc_endpoint())
             .tls_config(ClientTlsConfig::new().with_enabled_roots())?
             .timeout(timeout)
             .user_agent(&profile.user_agent)?
             .connect_lazy();
+        let channel = RetryChannel::new(channel, profile.grpc_retries);
 
-        let auth_header = profile
-            .api_key
-            .as_ref()
-            .and_then(|key| format!("Bearer {}", key).parse().ok());
-        if auth_header.is_none() {
-            warn!("API key missing or not ASCII");
-        }
-
-        let inner = V2CommanderServiceClient::with_interceptor(
-            channel,
-            AuthInterceptor { value: auth_header },
-        );
+        let inner =
+            V2CommanderServiceClient::with_interceptor(channel, AuthInterceptor::new(profile));
 
         Ok(inner)
     }
@@ -81,6 +82,16 @@ impl Client {
         }
     }
 
+    /// Fetches the user and organization the API key belongs to.
+    pub async fn current_user(
+        &mut self,
+        req: impl IntoRequest<GetBauplanInfoRequest>,
+    ) -> Result<user::CurrentUser, tonic::Status> {
+        let resp = self.get_bauplan_info(req).await?.into_inner();
+        user::CurrentUser::from_info(resp)
+            .ok_or_else(|| tonic::Status::not_found("the server didn't report a user"))
+    }
+
     /// Fetches the organization-wide default public key, along with the key name
     /// (usually the ARN).
     pub async fn org_default_public_key(
@@ -110,11 +121,39 @@ impl Client {
     /// Runs a job to completion. Produces a stream of job events from commander. If
     /// an error is encountered in the initial SubscribeLogs call, then it is the
     /// first item returned from the stream.
-    pub fn monitor_job(
+    pub fn monitor_job<R: IntoRequest<SubscribeLogsRequest>>(
+        &mut self,
+        req: R,
+        endpoint: Arc<tokio::sync::OnceCell<iroh::Endpoint>>,
+    ) -> impl Stream<Item = Result<JobEvent, tonic::Status>> + use<R> {
+        events::convert_stream(self.runner_events(req, endpoint))
+    }
+
+    /// Like [`Client::monitor_job`], and also records the raw protocol events
+    /// to `recorder` as they're received.
+    pub fn monitor_job_recorded<R: IntoRequest<SubscribeLogsRequest>>(
+        &mut self,
+        req: R,
+        endpoint: Arc<tokio::sync::OnceCell<iroh::Endpoint>>,
+        recorder: Option<EventRecorder>,
+    ) -> impl Stream<Item = Result<JobEvent, tonic::Status>> + use<R> {
+        let req = req.into_request();
+        let job_id = req.get_ref().job_id.clone();
+        let events = self.runner_events(req, endpoint).inspect_ok(move |ev| {
+            if let Some(recorder) = &recorder {
+                recorder.record(&job_id, ev);
+            }
+        });
+
+        events::convert_stream(events)
+    }
+
+    /// Like [`Client::monitor_job`], but produces the raw protocol events.
+    fn runner_events<R: IntoRequest<SubscribeLogsRequest>>(
         &mut self,
-        req: impl IntoRequest<SubscribeLogsRequest>,
+        req: R,
         endpoint: Arc<tokio::sync::OnceCell<iroh::Endpoint>>,
-    ) -> impl Stream<Item = Result<RunnerEvent, tonic::Status>> {
+    ) -> impl Stream<Item = Result<RunnerEvent, tonic::Status>> + use<R> {
         let req = req.into_request();
         let mut client = self.clone();
 
@@ -127,11 +166,45 @@ impl Client {
     }
 }
 
-/// Adds "authorization: Bearer <token>" to requests.
+/// Adds "authorization: Bearer <token>" to requests, along with any extra
+/// headers configured on the profile.
 #[doc(hidden)]
 #[derive(Debug, Clone)]
 pub struct AuthInterceptor {
     value: Option<MetadataValue<Ascii>>,
+    extra_headers: Arc<Vec<(MetadataKey<Ascii>, MetadataValue<Ascii>)>>,
+}
+
+impl AuthInterceptor {
+    fn new(profile: &Profile) -> Self {
+        let value = profile
+            .api_key
+            .as_ref()
+            .and_then(|key| format!("Bearer {}", key).parse().ok());
+        if value.is_none() {
+            warn!("API key missing or not ASCII");
+        }
+
+        let mut extra_headers = Vec::new();
+        for (name, value) in &profile.extra_headers {
+            let key = MetadataKey::from_bytes(name.as_str().as_bytes());
+            let Ok(mut meta_value) = MetadataValue::try_from(value.as_bytes()) else {
+                warn!(header = %name, "skipping header with a non-ASCII value");
+                continue;
+            };
+
+            meta_value.set_sensitive(value.is_sensitive());
+            match key {
+                Ok(key) => extra_headers.push((key, meta_value)),
+                Err(_) => warn!(header = %name, "skipping header not valid as gRPC metadata"),
+            }
+        }
+
+        Self {
+            value,
+            extra_headers: Arc::new(extra_headers),
+        }
+    }
 }
 
 impl Interceptor for AuthInterceptor {
@@ -139,6 +212,10 @@ impl Interceptor for AuthInterceptor {
         &mut self,
         mut request: tonic::Request<()>,
     ) -> Result<tonic::Request<()>, tonic::Status> {
+        for (key, value) in self.extra_headers.iter() {
+            request.metadata_mut().append(key.clone(), value.clone());
+        }
+
         if let Some(value) = &self.value {
             request
                 .metadata_mut()
@@ -171,7 +248,7 @@ pub enum JobError {
     Cancelled,
     #[error("job rejected: {0}")]
     Rejected(String),
-    #[error("job hit server timeout")]
+    #[error("job timed out")]
     Timeout,
     #[error("internal server error")]
     Internal,
@@ -195,10 +272,10 @@ impl JobError {
 }
 
 /// The outcome of a job, as returned by [`Client::subscribe_logs`].
-pub type JobResult = Result<JobSuccess, JobError>;
+pub(crate) type JobResult = Result<JobSuccess, JobError>;
 
 /// Parse a job outcome event as a possible [`JobError`].
-pub fn interpret_outcome(outcome: Option<Outcome>) -> JobResult {
+pub(crate) fn interpret_outcome(outcome: Option<Outcome>) -> JobResult {
     match outcome {
         Some(outcome) => match outcome {
             Outcome::Success(job_success) => Ok(job_success),
@@ -218,3 +295,33 @@ pub fn interpret_outcome(outcome: Option<Outcome>) -> JobResult {
         None => Err(JobError::Unknown),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interceptor_headers() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let path = tmp.path().join("config.yaml");
+        std::fs::write(
+            &path,
+            "profiles:\n  default:\n    api_key: bpln_dummy_key\n",
+        )?;
+
+        let profile =
+            Profile::read(&path, None)?.with_extra_header("X-Org-Request-Context", "ctx", false)?;
+
+        let req = AuthInterceptor::new(&profile).call(tonic::Request::new(()))?;
+        let metadata = req.metadata();
+        assert_eq!(
+            metadata.get("x-org-request-context").unwrap().to_str()?,
+            "ctx"
+        );
+
+        let auth: Vec<_> = metadata.get_all("authorization").iter().collect();
+        assert_eq!(auth.len(), 1);
+        assert_eq!(auth[0].to_str()?, "Bearer bpln_dummy_key");
+        Ok(())
+    }
+}
@@ -0,0 +1,219 @@
+//! Types describing a run's plan. The server can't plan a run without
+//! starting it, so the plan is read from the context of a dry run once it has
+//! been planned: the models it would run, and the edges between them.
+
+use futures::{Stream, TryStreamExt as _};
+use serde::Serialize;
+
+use crate::{
+    events::JobEvent,
+    grpc::{JobError, generated as commanderpb},
+};
+
+/// A model in a run's plan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlannedModel {
+    /// The ID of the model, as referenced by `depends_on`.
+    pub model_id: String,
+    /// The name of the model.
+    pub model_name: String,
+    /// The IDs of the models this model reads from.
+    pub depends_on: Vec<String>,
+}
+
+/// The plan for a run: the models it would run, as listed by the server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RunPlan {
+    /// The ID of the job that produced the plan.
+    pub job_id: String,
+    /// The planned models.
+    pub models: Vec<PlannedModel>,
+}
+
+impl RunPlan {
+    /// The `GetJobContext` request that looks up the plan of `job_id`.
+    pub fn context_request(job_id: &str) -> commanderpb::GetJobContextRequest {
+        commanderpb::GetJobContextRequest {
+            job_ids: vec![job_id.to_owned()],
+            ..Default::default()
+        }
+    }
+
+    /// Builds the plan for `job_id` from the models and edges in its context.
+    pub fn from_contexts(
+        job_id: String,
+        contexts: Vec<commanderpb::JobContext>,
+    ) -> Result<Self, ExplainError> {
+        let ctx = contexts
+            .into_iter()
+            .find(|ctx| ctx.job_id == job_id)
+            .filter(|ctx| !ctx.models.is_empty())
+            .ok_or(ExplainError::NoPlan)?;
+
+        let models = ctx
+            .models
+            .into_iter()
+            .map(|node| PlannedModel {
+                // Edges from leaf models, which read from the catalog, have
+                // no source.
+                depends_on: ctx
+                    .model_deps
+                    .iter()
+                    .filter(|edge| edge.destination_id == node.model_id)
+                    .filter_map(|edge| edge.source_id.clone())
+                    .collect(),
+                model_id: node.model_id,
+                model_name: node.model_name,
+            })
+            .collect();
+
+        Ok(Self { job_id, models })
+    }
+
+    /// Returns the names of the models `model` depends on, falling back to
+    /// the ID for models that aren't in the plan.
+    pub fn dependency_names<'a>(&'a self, model: &'a PlannedModel) -> Vec<&'a str> {
+        model
+            .depends_on
+            .iter()
+            .map(|id| {
+                self.models
+                    .iter()
+                    .find(|m| &m.model_id == id)
+                    .map_or(id.as_str(), |m| m.model_name.as_str())
+            })
+            .collect()
+    }
+}
+
+/// An error getting a run's plan.
+#[derive(Debug, thiserror::Error)]
+pub enum ExplainError {
+    /// The job event stream, or the context lookup, failed.
+    #[error(transparent)]
+    Status(#[from] tonic::Status),
+    /// The job failed before it was planned.
+    #[error(transparent)]
+    Job(#[from] JobError),
+    /// The job's context didn't list any models.
+    #[error("the job didn't report a plan")]
+    NoPlan,
+}
+
+/// Reads a dry run's events until it has been planned: until it starts
+/// running its first model, or finishes.
+///
+/// The job isn't cancelled, and may still be running when this returns, so
+/// callers should cancel it once they've looked up the plan.
+pub async fn wait_for_planning(
+    events: impl Stream<Item = Result<JobEvent, tonic::Status>>,
+) -> Result<(), ExplainError> {
+    futures::pin_mut!(events);
+
+    while let Some(event) = events.try_next().await? {
+        match event {
+            JobEvent::TaskStarted(task) if task.metadata.is_dag() => return Ok(()),
+            JobEvent::Completion(completion) => {
+                completion.result?;
+                return Ok(());
+            }
+            _ => (),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::events::convert_stream;
+    use commanderpb::runner_event::Event as RunnerEvent;
+
+    fn node(id: &str, name: &str) -> commanderpb::ModelNode {
+        commanderpb::ModelNode {
+            model_id: id.to_owned(),
+            model_name: name.to_owned(),
+        }
+    }
+
+    fn edge(source_id: Option<&str>, destination_id: &str) -> commanderpb::ModelEdge {
+        commanderpb::ModelEdge {
+            source_id: source_id.map(str::to_owned),
+            destination_id: destination_id.to_owned(),
+        }
+    }
+
+    #[test]
+    fn plan() {
+        let ctx = commanderpb::JobContext {
+            job_id: "job".to_owned(),
+            models: vec![
+                node("m1", "trips"),
+                node("m2", "zones"),
+                node("m3", "trips_by_zone"),
+            ],
+            model_deps: vec![
+                edge(None, "m1"),
+                edge(None, "m2"),
+                edge(Some("m1"), "m3"),
+                edge(Some("m2"), "m3"),
+                edge(Some("m9"), "m3"),
+            ],
+            ..Default::default()
+        };
+
+        let plan = RunPlan::from_contexts("job".to_owned(), vec![ctx]).unwrap();
+
+        let names: Vec<_> = plan.models.iter().map(|m| m.model_name.as_str()).collect();
+        assert_eq!(names, ["trips", "zones", "trips_by_zone"]);
+        assert!(plan.models[0].depends_on.is_empty());
+        assert_eq!(
+            plan.dependency_names(&plan.models[2]),
+            ["trips", "zones", "m9"]
+        );
+    }
+
+    #[test]
+    fn no_plan() {
+        let ctx = commanderpb::JobContext {
+            job_id: "job".to_owned(),
+            ..Default::default()
+        };
+
+        let err = RunPlan::from_contexts("job".to_owned(), vec![ctx]).unwrap_err();
+        assert!(matches!(err, ExplainError::NoPlan));
+
+        let err = RunPlan::from_contexts("job".to_owned(), vec![]).unwrap_err();
+        assert!(matches!(err, ExplainError::NoPlan));
+    }
+
+    async fn wait(events: Vec<RunnerEvent>) -> Result<(), ExplainError> {
+        let events = futures::stream::iter(events.into_iter().map(Ok));
+        wait_for_planning(convert_stream(events)).await
+    }
+
+    #[tokio::test]
+    async fn planning() {
+        let dag_task_start = RunnerEvent::TaskStart(commanderpb::TaskStartEvent {
+            task_metadata: Some(commanderpb::TaskMetadata {
+                level: commanderpb::task_metadata::TaskLevel::Dag as _,
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        wait(vec![dag_task_start]).await.unwrap();
+
+        let completion = RunnerEvent::JobCompletion(commanderpb::JobCompleteEvent {
+            outcome: Some(commanderpb::job_complete_event::Outcome::Cancellation(
+                Default::default(),
+            )),
+            ..Default::default()
+        });
+        let err = wait(vec![completion]).await.unwrap_err();
+        assert!(matches!(err, ExplainError::Job(JobError::Cancelled)));
+
+        wait(vec![]).await.unwrap();
+    }
+}
@@ -0,0 +1,106 @@
+//! The earlier import jobs into a branch.
+
+use crate::grpc::{generated as commanderpb, job::Job};
+
+/// How many of the most recent import jobs [`ImportHistory`] looks through.
+pub const IMPORT_HISTORY_SCANNED: i32 = 100;
+
+/// Finds the earlier import jobs into a branch, newest first. The server
+/// doesn't record which table or files an import job read, so this is as
+/// close as the job listing gets to an import ledger.
+#[derive(Debug, Clone)]
+pub struct ImportHistory {
+    branch: Option<String>,
+}
+
+impl ImportHistory {
+    /// Makes a history of the import jobs into `branch`, or into any branch
+    /// if it's `None`.
+    pub fn new(branch: Option<String>) -> Self {
+        Self { branch }
+    }
+
+    /// The `GetJobs` request for the most recent import jobs, by any user.
+    pub fn jobs_request(&self) -> commanderpb::GetJobsRequest {
+        commanderpb::GetJobsRequest {
+            all_users: true,
+            filter_kinds: vec![commanderpb::JobKind::TableDataImport as _],
+            max_records: IMPORT_HISTORY_SCANNED,
+            ..Default::default()
+        }
+    }
+
+    /// The `GetJobContext` request that looks up the branches of `jobs`, or
+    /// `None` if there's no branch to filter by.
+    pub fn context_request(&self, jobs: &[Job]) -> Option<commanderpb::GetJobContextRequest> {
+        if self.branch.is_none() || jobs.is_empty() {
+            return None;
+        }
+
+        Some(commanderpb::GetJobContextRequest {
+            job_ids: jobs.iter().map(|j| j.id.clone()).collect(),
+            ..Default::default()
+        })
+    }
+
+    /// Removes the jobs that didn't import into the branch, given their
+    /// contexts.
+    pub fn apply(&self, jobs: &mut Vec<Job>, contexts: Vec<commanderpb::JobContext>) {
+        let Some(branch) = self.branch.as_deref() else {
+            return;
+        };
+
+        let on_branch: Vec<String> = contexts
+            .into_iter()
+            .filter(|ctx| {
+                ctx.branch.as_deref() == Some(branch) || ctx.r#ref.as_deref() == Some(branch)
+            })
+            .map(|ctx| ctx.job_id)
+            .collect();
+
+        jobs.retain(|job| on_branch.contains(&job.id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_on_branch() {
+        let job = |id: &str| {
+            Job::from(commanderpb::JobInfo {
+                id: id.to_owned(),
+                ..Default::default()
+            })
+        };
+        let ctx = |job_id: &str, branch: Option<&str>, r: Option<&str>| commanderpb::JobContext {
+            job_id: job_id.to_owned(),
+            branch: branch.map(str::to_owned),
+            r#ref: r.map(str::to_owned),
+            ..Default::default()
+        };
+
+        let history = ImportHistory::new(Some("alice.dev".to_owned()));
+        let mut jobs = vec![job("a"), job("b"), job("c"), job("d")];
+        assert!(history.context_request(&jobs).is_some());
+
+        history.apply(
+            &mut jobs,
+            vec![
+                ctx("a", Some("alice.dev"), None),
+                ctx("b", Some("main"), Some("main")),
+                ctx("c", None, Some("alice.dev")),
+            ],
+        );
+        let ids: Vec<_> = jobs.iter().map(|j| j.id.as_str()).collect();
+        assert_eq!(ids, ["a", "c"]);
+
+        // Without a branch, every import job is kept.
+        let history = ImportHistory::new(None);
+        let mut jobs = vec![job("a"), job("b")];
+        assert!(history.context_request(&jobs).is_none());
+        history.apply(&mut jobs, Vec::new());
+        assert_eq!(jobs.len(), 2);
+    }
+}
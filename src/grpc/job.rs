@@ -1,7 +1,7 @@
 //! Job types returned by the gRPC API.
 
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
@@ -10,11 +10,12 @@ use std::{
 use bauplan_longbow::{BauplanPreset, iroh};
 use chrono::{DateTime, TimeZone, Utc};
 use futures::StreamExt;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tokio_util::codec::{FramedRead, LinesCodec};
 use tracing::{debug, error};
 
 use crate::{
+    events::{Converter, JobEvent, TaskOutcome},
     grpc::generated::{
         self as commanderpb, RuntimeLogEvent, SubscribeLogsResponse, TaskMetadata, TaskStartEvent,
         runner_event::Event as RunnerEvent,
@@ -23,7 +24,7 @@ use crate::{
 };
 
 /// The execution state of a job.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 #[allow(missing_docs)]
 #[cfg_attr(
     feature = "python",
@@ -60,15 +61,15 @@ impl std::fmt::Display for JobState {
     }
 }
 
-#[cfg(feature = "python")]
+/// Parses a state as displayed, as a Python enum member name, or as a CLI
+/// value (like `not-started`), ignoring case.
 impl std::str::FromStr for JobState {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Accept PascalCase, lowercase, kebab-case, and SCREAMING_SNAKE_CASE.
-        match s.to_ascii_lowercase().replace('_', "-").as_str() {
+        match normalize_enum_str(s).as_str() {
             "unspecified" => Ok(Self::Unspecified),
-            "not-started" | "not started" => Ok(Self::NotStarted),
+            "notstarted" => Ok(Self::NotStarted),
             "running" => Ok(Self::Running),
             "complete" => Ok(Self::Complete),
             "abort" => Ok(Self::Abort),
@@ -108,7 +109,7 @@ impl From<JobState> for commanderpb::JobStateType {
 }
 
 /// The kind/type of a job.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 #[allow(missing_docs)]
 #[cfg_attr(
     feature = "python",
@@ -177,28 +178,100 @@ impl From<JobKind> for commanderpb::JobKind {
     }
 }
 
-#[cfg(feature = "python")]
+/// Parses a kind as displayed, as a Python enum member name, as a CLI value
+/// (like `table-plan-apply`), or by its API name (like `CODE_SNAPSHOT_RUN`),
+/// ignoring case.
 impl std::str::FromStr for JobKind {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Accept PascalCase, lowercase, kebab-case, and SCREAMING_SNAKE_CASE.
-        match s.to_ascii_lowercase().replace('_', "-").as_str() {
+        match normalize_enum_str(s).as_str() {
             "unknown" | "unspecified" => Ok(Self::Unspecified),
-            "run" | "codesnapshotrun" | "code-snapshot-run" => Ok(Self::Run),
-            "query" => Ok(Self::Query),
-            "import-plan-create" | "importplancreate" => Ok(Self::ImportPlanCreate),
-            "import-plan-apply" | "importplanapply" => Ok(Self::ImportPlanApply),
-            "table-plan-create" | "tableplancreate" => Ok(Self::TablePlanCreate),
-            "table-plan-create-apply" | "tableplancreateapply" => Ok(Self::TablePlanCreateApply),
-            "table-import" | "tableimport" | "table-data-import" => Ok(Self::TableImport),
+            "run" | "codesnapshotrun" => Ok(Self::Run),
+            "query" | "queryrun" => Ok(Self::Query),
+            "importplancreate" => Ok(Self::ImportPlanCreate),
+            "importplanapply" => Ok(Self::ImportPlanApply),
+            "tableplancreate" => Ok(Self::TablePlanCreate),
+            "tableplanapply" | "tableplancreateapply" => Ok(Self::TablePlanCreateApply),
+            "tableimport" | "tabledataimport" => Ok(Self::TableImport),
             _ => Err(format!("invalid job kind: {s}")),
         }
     }
 }
 
+/// Lowercases `s` and drops word separators, so that `NotStarted`,
+/// `not-started`, `NOT_STARTED` and `Not Started` all compare equal.
+fn normalize_enum_str(s: &str) -> String {
+    s.chars()
+        .filter(|c| !matches!(c, '_' | '-' | ' '))
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// A priority for a job, from 1 to 10, where 10 is the highest.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Priority(u32);
+
+/// A priority outside of 1 to 10, or one that isn't a number.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("invalid priority {0}: must be between 1 and 10")]
+pub struct InvalidPriority(String);
+
+impl Priority {
+    /// The lowest priority.
+    pub const MIN: Self = Self(1);
+    /// The highest priority.
+    pub const MAX: Self = Self(10);
+
+    /// The priority as a number.
+    pub fn get(self) -> u32 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl TryFrom<i64> for Priority {
+    type Error = InvalidPriority;
+
+    fn try_from(n: i64) -> Result<Self, Self::Error> {
+        if (i64::from(Self::MIN.0)..=i64::from(Self::MAX.0)).contains(&n) {
+            Ok(Self(n as u32))
+        } else {
+            Err(InvalidPriority(n.to_string()))
+        }
+    }
+}
+
+impl TryFrom<u32> for Priority {
+    type Error = InvalidPriority;
+
+    fn try_from(n: u32) -> Result<Self, Self::Error> {
+        Self::try_from(i64::from(n))
+    }
+}
+
+impl std::str::FromStr for Priority {
+    type Err = InvalidPriority;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<i64>() {
+            Ok(n) => Self::try_from(n),
+            Err(_) => Err(InvalidPriority(format!("{s:?}"))),
+        }
+    }
+}
+
+/// The job arg that carries a job's human-friendly name. The server stores
+/// it with the rest of the job's args.
+pub const JOB_NAME_ARG: &str = "bauplan.job-name";
+
 /// The record of running a pipeline, query, or an import (see `bauplan.schema.JobKind` for all job kinds).
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(module = "bauplan.schema", from_py_object, get_all)
@@ -224,6 +297,14 @@ pub struct Job {
     pub runner: String,
     /// Error message for failed jobs, when available.
     pub error_message: Option<String>,
+    /// The ID of the project the job ran. Only set when listing jobs
+    /// filtered by project.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    /// The name of the project the job ran. Only set when listing jobs
+    /// filtered by project.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_name: Option<String>,
 }
 
 #[cfg(feature = "python")]
@@ -254,11 +335,270 @@ impl From<commanderpb::JobInfo> for Job {
             finished_at: info.finished_at.and_then(pb_to_chrono),
             runner: info.runner,
             error_message: info.error_message,
+            project_id: None,
+            project_name: None,
+        }
+    }
+}
+
+/// Filters jobs by the project they ran, given as a project ID or name.
+/// `GetJobs` can't filter by project, so the project of each job is looked up
+/// with a `GetJobContext` request for every page of jobs.
+#[derive(Debug, Clone)]
+pub struct ProjectFilter {
+    id_or_name: String,
+}
+
+impl ProjectFilter {
+    /// Makes a filter for a project ID or name. `.` means the project in the
+    /// current directory.
+    pub fn new(id_or_name: &str) -> Result<Self, project::ProjectError> {
+        let id_or_name = if id_or_name == "." {
+            project::ProjectFile::from_dir(".")?.project.id.to_string()
+        } else {
+            id_or_name.to_owned()
+        };
+
+        Ok(Self { id_or_name })
+    }
+
+    /// The `GetJobContext` request that looks up the projects of `jobs`.
+    pub fn context_request(jobs: &[Job]) -> commanderpb::GetJobContextRequest {
+        commanderpb::GetJobContextRequest {
+            job_ids: jobs.iter().map(|j| j.id.clone()).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Fills in the project of each job from `contexts`, and removes the jobs
+    /// that didn't run the project.
+    pub fn apply(&self, jobs: &mut Vec<Job>, contexts: Vec<commanderpb::JobContext>) {
+        let mut projects: HashMap<_, _> = contexts
+            .into_iter()
+            .map(|ctx| (ctx.job_id, (ctx.project_id, ctx.project_name)))
+            .collect();
+
+        jobs.retain_mut(|job| {
+            if let Some((id, name)) = projects.remove(&job.id) {
+                job.project_id = id;
+                job.project_name = name;
+            }
+
+            self.matches(job)
+        });
+    }
+
+    fn matches(&self, job: &Job) -> bool {
+        let needle = self.id_or_name.as_str();
+        job.project_id
+            .as_deref()
+            .is_some_and(|id| id.eq_ignore_ascii_case(needle))
+            || job.project_name.as_deref() == Some(needle)
+    }
+}
+
+/// Tracks the jobs returned across the pages of a `GetJobs` listing, so that
+/// each job is only yielded once. If jobs are created while paging, the
+/// server's offsets shift and a job from the end of one page can reappear at
+/// the start of the next. Jobs created during iteration may be missed, but
+/// never duplicated.
+#[derive(Debug, Default)]
+pub struct JobPageDedup {
+    seen: HashSet<String>,
+}
+
+impl JobPageDedup {
+    /// Removes the jobs from `page` that an earlier page already returned.
+    pub fn filter(&mut self, page: &mut Vec<commanderpb::JobInfo>) {
+        page.retain(|job| self.seen.insert(job.id.clone()));
+    }
+}
+
+/// Sorts jobs oldest first, by creation time and then by ID. The server only
+/// lists jobs newest first.
+pub fn sort_oldest_first(jobs: &mut [Job]) {
+    jobs.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+}
+
+/// Keeps the first and last items pushed to it, dropping the ones in the
+/// middle once it holds `cap` items. This bounds the memory used by the logs
+/// of a chatty job, while keeping both how it started and how it ended.
+#[derive(Debug, Clone)]
+pub struct HeadTailBuffer<T> {
+    head: Vec<T>,
+    tail: VecDeque<T>,
+    head_cap: usize,
+    tail_cap: usize,
+    dropped: usize,
+}
+
+impl<T> HeadTailBuffer<T> {
+    /// Makes a buffer that keeps at most `cap` items: the first half and the
+    /// last half.
+    pub fn new(cap: usize) -> Self {
+        let tail_cap = cap / 2;
+        Self {
+            head: Vec::new(),
+            tail: VecDeque::new(),
+            head_cap: cap - tail_cap,
+            tail_cap,
+            dropped: 0,
+        }
+    }
+
+    /// Adds an item, dropping the oldest item after the head if the buffer
+    /// is full.
+    pub fn push(&mut self, item: T) {
+        if self.head.len() < self.head_cap {
+            self.head.push(item);
+            return;
+        }
+
+        self.tail.push_back(item);
+        if self.tail.len() > self.tail_cap {
+            self.tail.pop_front();
+            self.dropped += 1;
+        }
+    }
+
+    /// The number of items dropped so far.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Returns the retained items in order. If any were dropped, the item
+    /// returned by `marker` (given the number dropped) is placed where they
+    /// were.
+    pub fn into_vec(self, marker: impl FnOnce(usize) -> T) -> Vec<T> {
+        let mut items = self.head;
+        if self.dropped > 0 {
+            items.push(marker(self.dropped));
+        }
+
+        items.extend(self.tail);
+        items
+    }
+}
+
+/// The state of a model in a job's DAG. When a model has several tasks,
+/// the later variants take precedence.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[allow(missing_docs)]
+pub enum ModelState {
+    #[default]
+    NotStarted,
+    Skipped,
+    Success,
+    Cancelled,
+    Running,
+    Failed,
+}
+
+impl ModelState {
+    /// The state in snake case, e.g. `not_started`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModelState::NotStarted => "not_started",
+            ModelState::Skipped => "skipped",
+            ModelState::Success => "success",
+            ModelState::Cancelled => "cancelled",
+            ModelState::Running => "running",
+            ModelState::Failed => "failed",
+        }
+    }
+}
+
+/// What happened to a model in a job, according to its task events.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ModelStatus {
+    /// The state of the model.
+    pub state: ModelState,
+    /// When the model's first task started.
+    pub started_at: Option<DateTime<Utc>>,
+    /// When the model's last task finished.
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Why the model failed or was cancelled, if it was.
+    pub error_message: Option<String>,
+}
+
+impl ModelStatus {
+    fn merge(&mut self, other: ModelStatus) {
+        self.started_at = match (self.started_at, other.started_at) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.ended_at = self.ended_at.max(other.ended_at);
+        if other.state > self.state {
+            self.state = other.state;
+            self.error_message = other.error_message;
+        }
+    }
+}
+
+/// Correlates the task start and completion events stored with a job, by
+/// model name, the way `bauplan run` tracks its tasks. Only tasks in the
+/// user's DAG are counted; models without any events are left out.
+pub fn model_statuses(
+    events: impl IntoIterator<Item = commanderpb::RunnerEvent>,
+) -> HashMap<String, ModelStatus> {
+    let mut converter = Converter::new();
+    let mut tasks: BTreeMap<String, (String, ModelStatus)> = BTreeMap::new();
+    for event in events
+        .into_iter()
+        .filter_map(|ev| ev.event)
+        .flat_map(|ev| converter.convert(ev))
+    {
+        let (task, outcome) = match event {
+            JobEvent::TaskStarted(task) => (task, None),
+            JobEvent::TaskCompleted(task, outcome) => (task, Some(outcome)),
+            _ => continue,
+        };
+
+        if !task.metadata.is_dag() {
+            continue;
         }
+
+        let Some(model) = task.metadata.model_name else {
+            continue;
+        };
+
+        let (_, status) = tasks
+            .entry(task.id)
+            .or_insert_with(|| (model, ModelStatus::default()));
+        let Some(outcome) = outcome else {
+            status.started_at = status.started_at.or(task.timestamp);
+            if status.state == ModelState::NotStarted {
+                status.state = ModelState::Running;
+            }
+            continue;
+        };
+
+        status.ended_at = task.timestamp;
+        (status.state, status.error_message) = match outcome {
+            TaskOutcome::Success { .. } => (ModelState::Success, None),
+            TaskOutcome::Failure { message, .. } => (ModelState::Failed, Some(message)),
+            TaskOutcome::Cancelled { reason: message } | TaskOutcome::Timeout { message } => {
+                (ModelState::Cancelled, Some(message))
+            }
+            TaskOutcome::Skipped => (ModelState::Skipped, None),
+        };
+        status.error_message = status.error_message.take().filter(|m| !m.is_empty());
     }
+
+    let mut models: HashMap<String, ModelStatus> = HashMap::new();
+    for (model, status) in tasks.into_values() {
+        match models.get_mut(&model) {
+            Some(existing) => existing.merge(status),
+            None => {
+                models.insert(model, status);
+            }
+        }
+    }
+
+    models
 }
 
-fn pb_to_chrono(ts: prost_types::Timestamp) -> Option<DateTime<Utc>> {
+pub(crate) fn pb_to_chrono(ts: prost_types::Timestamp) -> Option<DateTime<Utc>> {
     Utc.timestamp_opt(ts.seconds, ts.nanos as u32).single()
 }
 
@@ -491,3 +831,336 @@ fn synthetic_line_event(metadata: Option<&TaskMetadata>, stdio: Stdio, msg: Stri
         job_id: String::new(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn priority_bounds() {
+        for n in [1, 5, 10] {
+            assert_eq!(Priority::try_from(n).unwrap().get(), n as u32);
+        }
+
+        for n in [i64::MIN, -5, -1, 0, 11, i64::from(u32::MAX) + 1] {
+            let err = Priority::try_from(n).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("invalid priority {n}: must be between 1 and 10")
+            );
+        }
+
+        assert_eq!("10".parse::<Priority>().unwrap(), Priority::MAX);
+        assert!("0".parse::<Priority>().is_err());
+        assert!("11".parse::<Priority>().is_err());
+        assert!("-1".parse::<Priority>().is_err());
+        assert_eq!(
+            "high".parse::<Priority>().unwrap_err().to_string(),
+            r#"invalid priority "high": must be between 1 and 10"#
+        );
+    }
+
+    #[test]
+    fn parse_job_state() {
+        for (s, want) in [
+            ("Not Started", JobState::NotStarted),
+            ("NotStarted", JobState::NotStarted),
+            ("NOT_STARTED", JobState::NotStarted),
+            ("not-started", JobState::NotStarted),
+            ("running", JobState::Running),
+            ("COMPLETE", JobState::Complete),
+            ("Abort", JobState::Abort),
+            ("fail", JobState::Fail),
+            ("other", JobState::Other),
+            ("unspecified", JobState::Unspecified),
+        ] {
+            assert_eq!(s.parse::<JobState>(), Ok(want), "{s:?}");
+        }
+
+        assert!("done".parse::<JobState>().is_err());
+    }
+
+    #[test]
+    fn parse_job_kind() {
+        for (s, want) in [
+            ("run", JobKind::Run),
+            ("RUN", JobKind::Run),
+            ("CodeSnapshotRun", JobKind::Run),
+            ("CODE_SNAPSHOT_RUN", JobKind::Run),
+            ("query", JobKind::Query),
+            ("QUERY_RUN", JobKind::Query),
+            ("import-plan-create", JobKind::ImportPlanCreate),
+            ("ImportPlanApply", JobKind::ImportPlanApply),
+            ("TABLE_PLAN_CREATE", JobKind::TablePlanCreate),
+            ("table-plan-apply", JobKind::TablePlanCreateApply),
+            ("TablePlanCreateApply", JobKind::TablePlanCreateApply),
+            ("table-import", JobKind::TableImport),
+            ("TABLE_DATA_IMPORT", JobKind::TableImport),
+            ("Unknown", JobKind::Unspecified),
+        ] {
+            assert_eq!(s.parse::<JobKind>(), Ok(want), "{s:?}");
+        }
+
+        assert!("import".parse::<JobKind>().is_err());
+    }
+
+    #[test]
+    fn parse_round_trips_display() {
+        let states = [
+            JobState::Unspecified,
+            JobState::NotStarted,
+            JobState::Running,
+            JobState::Complete,
+            JobState::Abort,
+            JobState::Fail,
+            JobState::Other,
+        ];
+        for state in states {
+            assert_eq!(state.to_string().parse::<JobState>(), Ok(state));
+        }
+
+        let kinds = [
+            JobKind::Unspecified,
+            JobKind::Run,
+            JobKind::Query,
+            JobKind::ImportPlanCreate,
+            JobKind::ImportPlanApply,
+            JobKind::TablePlanCreate,
+            JobKind::TablePlanCreateApply,
+            JobKind::TableImport,
+        ];
+        for kind in kinds {
+            assert_eq!(kind.to_string().parse::<JobKind>(), Ok(kind));
+        }
+    }
+
+    /// A fake `GetJobs` server, which pages through jobs newest first using
+    /// offsets as pagination tokens.
+    struct FakeJobs {
+        // Newest first.
+        jobs: Vec<commanderpb::JobInfo>,
+    }
+
+    impl FakeJobs {
+        fn create(&mut self, id: &str) {
+            let job = commanderpb::JobInfo {
+                id: id.to_owned(),
+                ..Default::default()
+            };
+
+            self.jobs.insert(0, job);
+        }
+
+        fn get_jobs(
+            &self,
+            token: Option<&str>,
+            limit: usize,
+        ) -> (Vec<commanderpb::JobInfo>, Option<String>) {
+            let off: usize = token.map_or(0, |t| t.parse().unwrap());
+            let end = (off + limit).min(self.jobs.len());
+            let next = (end < self.jobs.len()).then(|| end.to_string());
+            (self.jobs[off..end].to_vec(), next)
+        }
+    }
+
+    #[test]
+    fn dedup_jobs_created_while_paging() {
+        let mut server = FakeJobs { jobs: Vec::new() };
+        for i in (0..10).rev() {
+            server.create(&format!("job-{i}"));
+        }
+
+        let mut dedup = JobPageDedup::default();
+        let mut yielded = Vec::new();
+        let mut token = None;
+        loop {
+            let (mut page, next) = server.get_jobs(token.as_deref(), 3);
+            dedup.filter(&mut page);
+            yielded.extend(page.into_iter().map(|j| j.id));
+
+            // Two new jobs push everything down between pages.
+            server.create(&format!("new-{}", yielded.len()));
+            server.create(&format!("new-{}-b", yielded.len()));
+
+            match next {
+                Some(t) => token = Some(t),
+                None => break,
+            }
+        }
+
+        let unique: HashSet<_> = yielded.iter().collect();
+        assert_eq!(unique.len(), yielded.len(), "duplicates in {yielded:?}");
+
+        let expected: Vec<_> = (0..10).map(|i| format!("job-{i}")).collect();
+        assert_eq!(yielded, expected);
+    }
+
+    #[test]
+    fn oldest_first() {
+        let job = |id: &str, seconds: i64| {
+            Job::from(commanderpb::JobInfo {
+                id: id.to_owned(),
+                created_at: Some(prost_types::Timestamp { seconds, nanos: 0 }),
+                ..Default::default()
+            })
+        };
+
+        let mut jobs = vec![job("c", 20), job("b", 10), job("a", 10)];
+        sort_oldest_first(&mut jobs);
+
+        let ids: Vec<_> = jobs.iter().map(|j| j.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn project_filter() {
+        let job = |id: &str| {
+            Job::from(commanderpb::JobInfo {
+                id: id.to_owned(),
+                ..Default::default()
+            })
+        };
+        let ctx = |job_id: &str, project_id: &str, project_name: &str| commanderpb::JobContext {
+            job_id: job_id.to_owned(),
+            project_id: Some(project_id.to_owned()),
+            project_name: Some(project_name.to_owned()),
+            ..Default::default()
+        };
+
+        let contexts = || {
+            vec![
+                ctx("a", "641D33D8-A6C2-4525-90D5-D54E7F27C26F", "taxi"),
+                ctx("b", "9b2f6c3e-0d7a-4f1e-8c55-2a9e1b7d4c10", "other"),
+            ]
+        };
+
+        let by_id = ProjectFilter::new("641d33d8-a6c2-4525-90d5-d54e7f27c26f").unwrap();
+        let mut jobs = vec![job("a"), job("b"), job("c")];
+        by_id.apply(&mut jobs, contexts());
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].id, "a");
+        assert_eq!(jobs[0].project_name.as_deref(), Some("taxi"));
+
+        let by_name = ProjectFilter::new("other").unwrap();
+        let mut jobs = vec![job("a"), job("b"), job("c")];
+        by_name.apply(&mut jobs, contexts());
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].id, "b");
+    }
+
+    fn task_event(
+        task_id: &str,
+        model: &str,
+        seconds: i64,
+        outcome: Option<commanderpb::task_complete_event::Outcome>,
+    ) -> commanderpb::RunnerEvent {
+        let task_metadata = Some(TaskMetadata {
+            level: commanderpb::task_metadata::TaskLevel::Dag as _,
+            model_name: Some(model.to_owned()),
+            ..Default::default()
+        });
+        let timestamp = Some(prost_types::Timestamp { seconds, nanos: 0 });
+        let event = match outcome {
+            None => RunnerEvent::TaskStart(TaskStartEvent {
+                task_id: task_id.to_owned(),
+                task_metadata,
+                timestamp,
+                ..Default::default()
+            }),
+            outcome => RunnerEvent::TaskCompletion(commanderpb::TaskCompleteEvent {
+                task_id: task_id.to_owned(),
+                task_metadata,
+                timestamp,
+                outcome,
+                ..Default::default()
+            }),
+        };
+
+        commanderpb::RunnerEvent { event: Some(event) }
+    }
+
+    #[test]
+    fn model_statuses_from_task_events() {
+        use commanderpb::task_complete_event::Outcome;
+
+        let success = || Some(Outcome::Success(Default::default()));
+        let failure = Some(Outcome::Failure(commanderpb::TaskFailure {
+            error_message: "boom".to_owned(),
+            is_fatal: true,
+            ..Default::default()
+        }));
+        let skipped = Some(Outcome::Skipped(Default::default()));
+
+        let statuses = model_statuses([
+            task_event("t1", "trips", 10, None),
+            task_event("t1", "trips", 20, success()),
+            task_event("t2", "zones", 15, None),
+            task_event("t3", "zones", 16, None),
+            task_event("t2", "zones", 25, success()),
+            task_event("t3", "zones", 30, failure),
+            task_event("t4", "summary", 30, skipped),
+            task_event("t5", "slow", 31, None),
+        ]);
+
+        let ts = |seconds| Utc.timestamp_opt(seconds, 0).single();
+        assert_eq!(
+            statuses["trips"],
+            ModelStatus {
+                state: ModelState::Success,
+                started_at: ts(10),
+                ended_at: ts(20),
+                error_message: None,
+            }
+        );
+        assert_eq!(
+            statuses["zones"],
+            ModelStatus {
+                state: ModelState::Failed,
+                started_at: ts(15),
+                ended_at: ts(30),
+                error_message: Some("boom".to_owned()),
+            }
+        );
+        assert_eq!(statuses["summary"].state, ModelState::Skipped);
+        assert_eq!(statuses["summary"].started_at, None);
+        assert_eq!(statuses["slow"].state, ModelState::Running);
+        assert_eq!(statuses["slow"].ended_at, None);
+        assert_eq!(statuses.len(), 4);
+
+        assert!(model_statuses([]).is_empty());
+    }
+
+    fn buffered(cap: usize, n: usize) -> (usize, Vec<String>) {
+        let mut buf = HeadTailBuffer::new(cap);
+        for i in 0..n {
+            buf.push(i.to_string());
+        }
+
+        let dropped = buf.dropped();
+        (dropped, buf.into_vec(|n| format!("<{n} dropped>")))
+    }
+
+    #[test]
+    fn head_tail_buffer() {
+        let (dropped, items) = buffered(4, 4);
+        assert_eq!(dropped, 0);
+        assert_eq!(items, ["0", "1", "2", "3"]);
+
+        let (dropped, items) = buffered(4, 10);
+        assert_eq!(dropped, 6);
+        assert_eq!(items, ["0", "1", "<6 dropped>", "8", "9"]);
+
+        // An odd cap gives the head the extra item.
+        let (dropped, items) = buffered(3, 10);
+        assert_eq!(dropped, 7);
+        assert_eq!(items, ["0", "1", "<7 dropped>", "9"]);
+
+        let (dropped, items) = buffered(1, 10);
+        assert_eq!(dropped, 9);
+        assert_eq!(items, ["0", "<9 dropped>"]);
+
+        let (dropped, items) = buffered(0, 2);
+        assert_eq!(dropped, 2);
+        assert_eq!(items, ["<2 dropped>"]);
+    }
+}
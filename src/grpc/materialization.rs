@@ -0,0 +1,97 @@
+//! Types describing the tables that a run writes to.
+//!
+//! The server doesn't report write targets directly. Instead, the tables are
+//! worked out from the run's write tasks (like `s3write`), which are
+//! reported even when they're skipped in a dry run, and name the model they
+//! write.
+
+use serde::Serialize;
+
+use crate::events::TaskMetadata;
+
+/// A table that a run planned to write a model to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlannedMaterialization {
+    /// The name of the table.
+    pub table_name: String,
+    /// The namespace of the table.
+    pub namespace: String,
+    /// The ref the table would be written on.
+    #[serde(rename = "ref")]
+    pub r#ref: String,
+    /// The partitioning expressions for the table. The server doesn't report
+    /// them yet, so this is always empty.
+    pub partitioned_by: Vec<String>,
+}
+
+impl PlannedMaterialization {
+    /// The table written by a task, if it's a write task. Models are written
+    /// to a table of the same name, in the run's namespace and on its ref.
+    pub fn from_task(metadata: &TaskMetadata, namespace: &str, r#ref: &str) -> Option<Self> {
+        if !metadata.description.to_ascii_lowercase().contains("write") {
+            return None;
+        }
+
+        Some(Self {
+            table_name: metadata.model_name.clone()?,
+            namespace: namespace.to_owned(),
+            r#ref: r#ref.to_owned(),
+            partitioned_by: Vec::new(),
+        })
+    }
+
+    /// The fully qualified table name, e.g. `bauplan.titanic`.
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}", self.namespace, self.table_name)
+    }
+}
+
+/// Records the table a task writes to, if it's a write task, given the
+/// namespace and ref of the run. A task may be reported more than once (on
+/// start and on completion), so tables already in the list are ignored.
+pub fn record_materialization(
+    planned: &mut Vec<PlannedMaterialization>,
+    metadata: &TaskMetadata,
+    namespace: &str,
+    r#ref: &str,
+) {
+    let Some(target) = PlannedMaterialization::from_task(metadata, namespace, r#ref) else {
+        return;
+    };
+
+    if !planned.contains(&target) {
+        planned.push(target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(description: &str, model_name: &str) -> TaskMetadata {
+        TaskMetadata {
+            description: description.to_owned(),
+            model_name: Some(model_name.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn record_dedupes() {
+        let mut planned = Vec::new();
+        let mut record = |metadata: &TaskMetadata| {
+            record_materialization(&mut planned, metadata, "staging", "main");
+        };
+
+        record(&metadata("s3write", "trips"));
+        record(&metadata("s3write", "trips"));
+        record(&metadata("python", "zones_raw"));
+        record(&Default::default());
+        record(&metadata("s3write", "zones"));
+
+        let names: Vec<_> = planned.iter().map(|m| m.qualified_name()).collect();
+        assert_eq!(names, ["staging.trips", "staging.zones"]);
+        assert_eq!(planned[0].r#ref, "main");
+        assert!(planned[0].partitioned_by.is_empty());
+    }
+}
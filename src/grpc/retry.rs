@@ -0,0 +1,292 @@
+//! Retries for idempotent calls to the gRPC API, for when the connection
+//! drops mid-session (a laptop going to sleep, or a VPN reconnecting).
+
+use std::{
+    error::Error as StdError,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::future::{BoxFuture, poll_fn};
+use http_body_util::{BodyExt as _, Full};
+use tonic::{body::Body, codegen::Service, transport::Channel};
+use tracing::debug;
+
+type BoxError = Box<dyn StdError + Send + Sync>;
+
+/// The default number of times to retry an idempotent call.
+pub const DEFAULT_GRPC_RETRIES: u32 = 3;
+
+/// The first backoff delay, doubled on each retry.
+const BASE_DELAY: Duration = Duration::from_millis(250);
+
+/// The longest backoff delay.
+const MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// The methods which only read state, and so are safe to send more than
+/// once. Methods which submit jobs must never appear here.
+const IDEMPOTENT_METHODS: &[&str] = &[
+    "/bpln_proto.commander.service.v2.V2CommanderService/GetJobs",
+    "/bpln_proto.commander.service.v2.V2CommanderService/GetLogs",
+    "/bpln_proto.commander.service.v2.V2CommanderService/GetJobContext",
+    "/bpln_proto.commander.service.v2.V2CommanderService/GetBauplanInfo",
+];
+
+/// A [`Channel`] which retries idempotent unary calls, with jittered
+/// exponential backoff, if the connection fails or the server reports
+/// itself unavailable. Other calls are sent once.
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct RetryChannel<S = Channel> {
+    inner: S,
+    max_retries: u32,
+    base_delay: Duration,
+}
+
+impl<S> RetryChannel<S> {
+    /// Wraps `inner`, retrying idempotent calls up to `max_retries` times.
+    pub(crate) fn new(inner: S, max_retries: u32) -> Self {
+        Self {
+            inner,
+            max_retries,
+            base_delay: BASE_DELAY,
+        }
+    }
+}
+
+impl<S> Service<http::Request<Body>> for RetryChannel<S>
+where
+    S: Service<http::Request<Body>, Response = http::Response<Body>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Into<BoxError>,
+{
+    type Response = http::Response<Body>;
+    type Error = BoxError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: http::Request<Body>) -> Self::Future {
+        // The service driven to readiness is the one to call, so keep that
+        // and leave a clone behind.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let max_retries = self.max_retries;
+        if max_retries == 0 || !IDEMPOTENT_METHODS.contains(&req.uri().path()) {
+            let fut = inner.call(req);
+            return Box::pin(async move { fut.await.map_err(Into::<BoxError>::into) });
+        }
+
+        let base_delay = self.base_delay;
+        Box::pin(async move {
+            // Unary request bodies are small, so buffer it to send again.
+            let (parts, body) = req.into_parts();
+            let buffered = body.collect().await?.to_bytes();
+
+            let mut attempt = 0;
+            loop {
+                if attempt > 0 {
+                    poll_fn(|cx| inner.poll_ready(cx))
+                        .await
+                        .map_err(Into::<BoxError>::into)?;
+                }
+
+                let body = Body::new(Full::new(buffered.clone()));
+                let req = http::Request::from_parts(parts.clone(), body);
+                let result = inner.call(req).await.map_err(Into::<BoxError>::into);
+                if attempt >= max_retries || !is_retryable(&result) {
+                    return result;
+                }
+
+                let delay = backoff(base_delay, attempt, rand::random());
+                debug!(
+                    method = parts.uri.path(),
+                    ?delay,
+                    attempt,
+                    "gRPC call failed, retrying"
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+        })
+    }
+}
+
+/// Whether a call failed in a way worth retrying: the connection dropped
+/// or couldn't be made, or the server (or a proxy in front of it) reported
+/// itself unavailable. Timeouts aren't retried, since that would multiply
+/// the time the caller asked to wait.
+fn is_retryable(result: &Result<http::Response<Body>, BoxError>) -> bool {
+    match result {
+        Err(err) => !is_timeout(err.as_ref()),
+        // A trailers-only response carries the status in the headers.
+        Ok(resp) => match resp.headers().get("grpc-status") {
+            Some(status) => status == "14",
+            None => matches!(
+                resp.status(),
+                http::StatusCode::BAD_GATEWAY
+                    | http::StatusCode::SERVICE_UNAVAILABLE
+                    | http::StatusCode::GATEWAY_TIMEOUT
+            ),
+        },
+    }
+}
+
+fn is_timeout(err: &(dyn StdError + 'static)) -> bool {
+    let mut source = Some(err);
+    while let Some(err) = source {
+        if err.is::<tonic::TimeoutExpired>() {
+            return true;
+        }
+
+        source = err.source();
+    }
+
+    false
+}
+
+/// The delay before retry number `attempt` (counting from 0), exponential
+/// in `attempt` and scaled by `jitter` (in [0, 1]) to between half and all
+/// of it.
+fn backoff(base: Duration, attempt: u32, jitter: f64) -> Duration {
+    base.saturating_mul(1u32 << attempt.min(16))
+        .min(MAX_DELAY)
+        .mul_f64(0.5 + jitter / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use futures::{
+        FutureExt as _,
+        future::{self, Ready},
+    };
+
+    use super::*;
+
+    const GET_JOBS: &str = "/bpln_proto.commander.service.v2.V2CommanderService/GetJobs";
+    const CODE_SNAPSHOT_RUN: &str =
+        "/bpln_proto.commander.service.v2.V2CommanderService/CodeSnapshotRun";
+
+    /// A fake server which drops the connection for the first `failures`
+    /// calls, and records the body of every call it receives.
+    #[derive(Clone)]
+    struct FlakyServer {
+        failures: usize,
+        calls: Arc<Mutex<Vec<bytes::Bytes>>>,
+    }
+
+    impl FlakyServer {
+        fn new(failures: usize) -> Self {
+            Self {
+                failures,
+                calls: Arc::default(),
+            }
+        }
+
+        fn calls(&self) -> Vec<bytes::Bytes> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    impl Service<http::Request<Body>> for FlakyServer {
+        type Response = http::Response<Body>;
+        type Error = io::Error;
+        type Future = Ready<Result<Self::Response, Self::Error>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, req: http::Request<Body>) -> Self::Future {
+            // The body is always buffered by the time it gets here.
+            let body = req.into_body().collect().now_or_never().unwrap();
+            let body = body.unwrap().to_bytes();
+
+            let mut calls = self.calls.lock().unwrap();
+            calls.push(body);
+            if calls.len() <= self.failures {
+                return future::ready(Err(io::ErrorKind::ConnectionReset.into()));
+            }
+
+            let resp = http::Response::builder()
+                .header("grpc-status", "0")
+                .body(Body::empty())
+                .unwrap();
+            future::ready(Ok(resp))
+        }
+    }
+
+    async fn send(server: &FlakyServer, path: &str, retries: u32) -> Result<(), BoxError> {
+        let mut channel = RetryChannel::new(server.clone(), retries);
+        channel.base_delay = Duration::ZERO;
+
+        let req = http::Request::builder()
+            .uri(format!("http://localhost{path}"))
+            .body(Body::new(Full::new(bytes::Bytes::from_static(b"req"))))
+            .unwrap();
+
+        poll_fn(|cx| channel.poll_ready(cx)).await?;
+        channel.call(req).await.map(|_| ())
+    }
+
+    #[tokio::test]
+    async fn retries_idempotent_calls() {
+        let server = FlakyServer::new(2);
+        send(&server, GET_JOBS, 3).await.unwrap();
+        assert_eq!(server.calls(), vec![&b"req"[..]; 3]);
+
+        let server = FlakyServer::new(4);
+        assert!(send(&server, GET_JOBS, 3).await.is_err());
+        assert_eq!(server.calls().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn never_retries_job_submission() {
+        let server = FlakyServer::new(1);
+        assert!(send(&server, CODE_SNAPSHOT_RUN, 3).await.is_err());
+        assert_eq!(server.calls().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn zero_disables_retries() {
+        let server = FlakyServer::new(1);
+        assert!(send(&server, GET_JOBS, 0).await.is_err());
+        assert_eq!(server.calls().len(), 1);
+    }
+
+    #[test]
+    fn unavailable_is_retryable() {
+        let resp = |status: u16, grpc_status: Option<&str>| {
+            let mut resp = http::Response::builder().status(status);
+            if let Some(grpc_status) = grpc_status {
+                resp = resp.header("grpc-status", grpc_status);
+            }
+
+            Ok(resp.body(Body::empty()).unwrap())
+        };
+
+        assert!(is_retryable(&resp(200, Some("14"))));
+        assert!(is_retryable(&resp(503, None)));
+        assert!(!is_retryable(&resp(200, Some("0"))));
+        assert!(!is_retryable(&resp(200, Some("5"))));
+        assert!(!is_retryable(&Err(tonic::TimeoutExpired(()).into())));
+
+        let reset = io::Error::from(io::ErrorKind::ConnectionReset);
+        assert!(is_retryable(&Err(reset.into())));
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        assert_eq!(backoff(BASE_DELAY, 0, 1.0), BASE_DELAY);
+        assert_eq!(backoff(BASE_DELAY, 1, 0.0), BASE_DELAY);
+        assert_eq!(backoff(BASE_DELAY, 30, 1.0), MAX_DELAY);
+    }
+}
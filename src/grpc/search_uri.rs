@@ -0,0 +1,167 @@
+//! Validation of the search URI passed to table creation and import jobs.
+//!
+//! The server only reads parquet files, and there's no request field or job
+//! arg for any other format. [`FileFormat::check`] rejects the other formats
+//! up front, instead of letting the planner fail on them.
+
+use std::{fmt, str::FromStr};
+
+/// An invalid search URI.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum SearchUriError {
+    #[error("invalid search URI {0:?}: only s3:// URIs are supported")]
+    NotS3(String),
+    #[error("invalid file format {0:?}: expected one of parquet, csv, jsonl")]
+    InvalidFormat(String),
+    #[error("{0} files can't be read yet: only parquet is supported")]
+    Unsupported(FileFormat),
+    #[error(
+        "invalid search URI {uri:?}: it matches {found} files, but the file format is {format}"
+    )]
+    FormatMismatch {
+        uri: String,
+        format: FileFormat,
+        found: FileFormat,
+    },
+}
+
+/// The format of the files behind a search URI.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FileFormat {
+    /// Parquet files.
+    #[default]
+    Parquet,
+    /// CSV files, with a header row.
+    Csv,
+    /// Newline-delimited JSON, with one object per line.
+    Jsonl,
+}
+
+impl FileFormat {
+    /// The name of the format.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileFormat::Parquet => "parquet",
+            FileFormat::Csv => "csv",
+            FileFormat::Jsonl => "jsonl",
+        }
+    }
+
+    /// Returns the format implied by the extension of a file or pattern, if
+    /// it has a known one. `s3://bucket/*.csv` is CSV, while
+    /// `s3://bucket/data/` or `s3://bucket/*` could be anything.
+    pub fn from_extension(uri: &str) -> Option<Self> {
+        let name = uri.rsplit('/').next().unwrap_or(uri);
+        let (_, ext) = name.rsplit_once('.')?;
+        match ext.to_ascii_lowercase().as_str() {
+            "parquet" => Some(FileFormat::Parquet),
+            "csv" => Some(FileFormat::Csv),
+            "jsonl" | "ndjson" | "json" => Some(FileFormat::Jsonl),
+            _ => None,
+        }
+    }
+
+    /// Checks that `uri` matches files of this format, and that the server
+    /// can read them. A URI without a known extension isn't checked against
+    /// the format.
+    pub fn check(self, uri: &str) -> Result<(), SearchUriError> {
+        if let Some(found) = Self::from_extension(uri)
+            && found != self
+        {
+            return Err(SearchUriError::FormatMismatch {
+                uri: uri.to_owned(),
+                format: self,
+                found,
+            });
+        }
+
+        if self != FileFormat::Parquet {
+            return Err(SearchUriError::Unsupported(self));
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for FileFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for FileFormat {
+    type Err = SearchUriError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "parquet" => Ok(FileFormat::Parquet),
+            "csv" => Ok(FileFormat::Csv),
+            "jsonl" => Ok(FileFormat::Jsonl),
+            _ => Err(SearchUriError::InvalidFormat(s.to_owned())),
+        }
+    }
+}
+
+/// Validates `uri`, returning the value for the `search_string` field of a
+/// request.
+pub fn encode(uri: &str) -> Result<String, SearchUriError> {
+    if !uri
+        .get(..5)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("s3://"))
+    {
+        return Err(SearchUriError::NotS3(uri.to_owned()));
+    }
+
+    Ok(uri.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_valid() {
+        let s = encode("s3://bucket/2024/*.parquet").unwrap();
+        assert_eq!(s, "s3://bucket/2024/*.parquet");
+        assert!(encode("S3://bucket/2024/").is_ok());
+    }
+
+    #[test]
+    fn encode_invalid() {
+        assert!(matches!(
+            encode("gs://bucket/b/*"),
+            Err(SearchUriError::NotS3(uri)) if uri == "gs://bucket/b/*"
+        ));
+    }
+
+    #[test]
+    fn file_format() {
+        FileFormat::Parquet
+            .check("s3://bucket/a/*.parquet")
+            .unwrap();
+        FileFormat::Parquet.check("s3://bucket/b/").unwrap();
+
+        assert_eq!("jsonl".parse::<FileFormat>().unwrap(), FileFormat::Jsonl);
+        assert!(matches!(
+            "avro".parse::<FileFormat>(),
+            Err(SearchUriError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn file_format_invalid() {
+        assert!(matches!(
+            FileFormat::Parquet.check("s3://bucket/*.csv"),
+            Err(SearchUriError::FormatMismatch {
+                format: FileFormat::Parquet,
+                found: FileFormat::Csv,
+                ..
+            })
+        ));
+        assert!(matches!(
+            FileFormat::Csv.check("s3://bucket/a/*.CSV"),
+            Err(SearchUriError::Unsupported(FileFormat::Csv))
+        ));
+    }
+}
@@ -0,0 +1,98 @@
+//! Types describing the user an API key belongs to.
+
+use serde::Serialize;
+
+use crate::grpc::generated as commanderpb;
+
+/// The user and organization an API key belongs to, as reported by
+/// `GetBauplanInfo`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CurrentUser {
+    /// The user's ID. Older servers only report the username.
+    pub id: Option<String>,
+    /// The user's username.
+    pub username: String,
+    /// The user's first and last name, if the server reported them.
+    pub full_name: Option<String>,
+    /// The ID of the user's organization.
+    pub organization_id: Option<String>,
+    /// The name of the user's organization.
+    pub organization_name: Option<String>,
+    /// The prefix of the user's branches, e.g. `alice.`.
+    pub branch_prefix: String,
+}
+
+impl CurrentUser {
+    /// Reads the user from an info response, or returns `None` if the server
+    /// didn't report one.
+    pub fn from_info(resp: commanderpb::GetBauplanInfoResponse) -> Option<Self> {
+        let (id, username, full_name) = match resp.user_info {
+            Some(user) => {
+                let full_name = format!("{} {}", user.first_name, user.last_name);
+                let full_name = Some(full_name.trim().to_owned()).filter(|n| !n.is_empty());
+                (Some(user.id), user.username, full_name)
+            }
+            None if !resp.user.is_empty() => (None, resp.user, None),
+            None => return None,
+        };
+
+        let (organization_id, organization_name) = match resp.organization_info {
+            Some(org) => (Some(org.id), Some(org.name)),
+            None => (None, None),
+        };
+
+        Some(Self {
+            id,
+            branch_prefix: format!("{username}."),
+            username,
+            full_name,
+            organization_id,
+            organization_name,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_info() {
+        let resp = commanderpb::GetBauplanInfoResponse {
+            user_info: Some(commanderpb::UserInfo {
+                id: "u-1".to_owned(),
+                username: "alice".to_owned(),
+                first_name: "Alice".to_owned(),
+                last_name: "Smith".to_owned(),
+            }),
+            organization_info: Some(commanderpb::OrganizationInfo {
+                id: "o-1".to_owned(),
+                name: "Acme".to_owned(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let user = CurrentUser::from_info(resp).unwrap();
+        assert_eq!(user.id.as_deref(), Some("u-1"));
+        assert_eq!(user.full_name.as_deref(), Some("Alice Smith"));
+        assert_eq!(user.organization_name.as_deref(), Some("Acme"));
+        assert_eq!(user.branch_prefix, "alice.");
+    }
+
+    #[test]
+    fn username_only() {
+        let resp = commanderpb::GetBauplanInfoResponse {
+            user: "bob".to_owned(),
+            ..Default::default()
+        };
+
+        let user = CurrentUser::from_info(resp).unwrap();
+        assert_eq!(user.id, None);
+        assert_eq!(user.username, "bob");
+        assert_eq!(user.organization_id, None);
+
+        let resp = commanderpb::GetBauplanInfoResponse::default();
+        assert_eq!(CurrentUser::from_info(resp), None);
+    }
+}
@@ -0,0 +1,74 @@
+//! Storing API keys in the OS keychain, for profiles configured with
+//! `api_key_store: keychain`.
+//!
+//! Keys are stored under the service "bauplan", with the profile name as the
+//! account. Keychain support requires the `keychain` feature; without it,
+//! every operation fails with [`KeychainError::Unsupported`].
+
+/// The keychain service API keys are stored under.
+#[cfg(feature = "keychain")]
+const SERVICE: &str = "bauplan";
+
+/// An error accessing the OS keychain.
+#[derive(Debug, thiserror::Error)]
+pub enum KeychainError {
+    /// This build doesn't include keychain support.
+    #[error("this build of bauplan doesn't support the OS keychain")]
+    Unsupported,
+    /// The keychain couldn't be accessed.
+    #[error("failed to access the OS keychain: {0}")]
+    Keychain(String),
+}
+
+#[cfg(feature = "keychain")]
+fn entry(profile: &str) -> Result<keyring::Entry, KeychainError> {
+    keyring::Entry::new(SERVICE, profile).map_err(|e| KeychainError::Keychain(e.to_string()))
+}
+
+/// Reads the API key stored for `profile`, if there is one.
+#[cfg(feature = "keychain")]
+pub fn get_api_key(profile: &str) -> Result<Option<String>, KeychainError> {
+    match entry(profile)?.get_password() {
+        Ok(api_key) => Ok(Some(api_key)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(KeychainError::Keychain(e.to_string())),
+    }
+}
+
+/// Stores the API key for `profile`, replacing any previous one.
+#[cfg(feature = "keychain")]
+pub fn set_api_key(profile: &str, api_key: &str) -> Result<(), KeychainError> {
+    entry(profile)?
+        .set_password(api_key)
+        .map_err(|e| KeychainError::Keychain(e.to_string()))
+}
+
+/// Deletes the API key stored for `profile`. Returns false if there wasn't
+/// one.
+#[cfg(feature = "keychain")]
+pub fn delete_api_key(profile: &str) -> Result<bool, KeychainError> {
+    match entry(profile)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(KeychainError::Keychain(e.to_string())),
+    }
+}
+
+/// Reads the API key stored for `profile`, if there is one.
+#[cfg(not(feature = "keychain"))]
+pub fn get_api_key(_profile: &str) -> Result<Option<String>, KeychainError> {
+    Err(KeychainError::Unsupported)
+}
+
+/// Stores the API key for `profile`, replacing any previous one.
+#[cfg(not(feature = "keychain"))]
+pub fn set_api_key(_profile: &str, _api_key: &str) -> Result<(), KeychainError> {
+    Err(KeychainError::Unsupported)
+}
+
+/// Deletes the API key stored for `profile`. Returns false if there wasn't
+/// one.
+#[cfg(not(feature = "keychain"))]
+pub fn delete_api_key(_profile: &str) -> Result<bool, KeychainError> {
+    Err(KeychainError::Unsupported)
+}
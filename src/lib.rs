@@ -2,6 +2,19 @@
 //!
 //! This crate provides a Rust SDK for interacting with the Bauplan data platform.
 //!
+//! # Quick start
+//!
+//! [`Client`] is a blocking client that covers common operations:
+//!
+//! ```no_run
+//! # fn main() -> anyhow::Result<()> {
+//! let client = bauplan::Client::new(bauplan::Profile::from_default_env()?)?;
+//! let table = client.get_table("bauplan.taxi_fhvhv", "main")?;
+//! println!("Table: {} ({} records)", table.name, table.records.unwrap_or(0));
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! # HTTP Requests and Responses
 //!
 //! The API types are designed to work with any HTTP client that uses the [`http`]
@@ -36,39 +49,39 @@
 //! # }
 //! ```
 //!
-//! # Example with reqwest
+//! # Async requests
 //!
-//! ```no_run
-//! use bauplan::{ApiRequest, ApiResponse, Profile, table::GetTable};
-//! use http_body_util::BodyExt;
-//! use std::io::Cursor;
+//! With the `async-client` feature, `AsyncClient` sends requests with
+//! [reqwest](https://docs.rs/reqwest), and streams paginated results:
+//!
+#![cfg_attr(feature = "async-client", doc = "```no_run")]
+#![cfg_attr(not(feature = "async-client"), doc = "```ignore")]
+//! use bauplan::{AsyncClient, Profile, table::GetTables};
+//! use futures::TryStreamExt as _;
 //!
 //! # #[tokio::main]
 //! # async fn main() -> anyhow::Result<()> {
-//! let profile = Profile::from_default_env()?;
-//! let client = reqwest::Client::new();
+//! let client = AsyncClient::new(Profile::from_default_env()?)?;
 //!
-//! let req = GetTable {
-//!     name: "bauplan.taxi_fhvhv",
+//! let req = GetTables {
 //!     at_ref: "main",
-//!     namespace: None,
+//!     filter_by_name: None,
+//!     filter_by_namespace: Some("bauplan"),
 //! };
 //!
-//! let http_req = req.into_request(&profile)?;
-//! let reqwest_req: reqwest::Request = http_req.try_into()?;
-//!
-//! let resp = client.execute(reqwest_req).await?;
-//! let http_resp: http::Response<_> = resp.into();
-//! let (parts, body) = http_resp.into_parts();
-//! let bytes = body.collect().await?.to_bytes();
-//!
-//! // You can use the associated type to read the response, or use e.g.
-//! // Table directly
-//! let table = <GetTable as ApiRequest>::Response::from_response_parts(parts, Cursor::new(bytes))?;
-//! println!("Table: {} ({} records)", table.name, table.records.unwrap_or(0));
+//! let mut tables = std::pin::pin!(client.paginate(req, Some(100)));
+//! while let Some(table) = tables.try_next().await? {
+//!     println!("Table: {} ({} records)", table.name, table.records.unwrap_or(0));
+//! }
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! # Testing
+//!
+//! With the `test-util` feature, the `testing` module provides a
+//! `MockCatalog`, which answers requests with canned responses, so code
+//! that uses the API can be tested offline.
 
 #![warn(
     anonymous_parameters,
@@ -87,15 +100,28 @@
 )]
 
 mod api;
+mod client;
 mod config;
 mod refs;
 
+pub mod events;
+pub mod export;
 pub mod flight;
 pub mod grpc;
+pub mod keychain;
 pub mod project;
+pub mod query;
+pub mod sync;
+#[cfg(any(test, feature = "test-util"))]
+pub mod testing;
+#[doc(hidden)]
+pub mod yaml;
 
 pub use api::*;
-pub use config::Profile;
+pub use client::{Client, ClientError};
+#[cfg(feature = "async-client")]
+pub use client::AsyncClient;
+pub use config::{ApiKeySource, Profile};
 pub use refs::*;
 
 #[cfg(feature = "python")]
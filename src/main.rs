@@ -1,11 +1,13 @@
 mod cli;
 
 use clap::Parser;
+use tracing::debug;
 use tracing_indicatif::IndicatifWriter;
 use tracing_subscriber::{EnvFilter, fmt};
 
 fn main() -> anyhow::Result<()> {
-    let args = cli::Args::parse();
+    let (argv, alias) = cli::alias::expand_args(std::env::args_os().collect())?;
+    let args = cli::Args::parse_from(&argv);
 
     // Used by integration tests to validate argument parsing without executing.
     if cfg!(feature = "_check-parse") {
@@ -15,13 +17,29 @@ fn main() -> anyhow::Result<()> {
     // Tracks global progress bar state. This is necessary so that indicatif
     // progress bars and tracing log lines play nicely with each other.
     let mp = indicatif::MultiProgress::new();
+    let plain_progress = cli::init_progress(&mp);
 
-    init_logging(args.global.verbose, mp.clone())?;
+    init_logging(args.global.verbose, !plain_progress, mp.clone())?;
 
-    cli::run(args, mp)
+    if let Some(alias) = alias {
+        let expanded: Vec<_> = argv[1..].iter().map(|a| a.to_string_lossy()).collect();
+        let expanded = shlex::try_join(expanded.iter().map(|a| &**a)).unwrap_or_default();
+        debug!(alias, "expanded alias: bauplan {expanded}");
+    }
+
+    let json_errors = cli::json_errors(&args.global);
+    let res = cli::run(args, mp);
+    if json_errors
+        && let Err(err) = &res
+        && cli::print_json_error(err).is_ok()
+    {
+        std::process::exit(1);
+    }
+
+    res
 }
 
-fn init_logging(verbose: bool, mp: indicatif::MultiProgress) -> anyhow::Result<()> {
+fn init_logging(verbose: bool, ansi: bool, mp: indicatif::MultiProgress) -> anyhow::Result<()> {
     let level = if verbose { "debug" } else { "info" };
     let filter = EnvFilter::builder()
         .with_default_directive(format!("bauplan={level}").parse()?)
@@ -37,6 +55,7 @@ fn init_logging(verbose: bool, mp: indicatif::MultiProgress) -> anyhow::Result<(
     tracing_subscriber::fmt()
         .with_writer(writer)
         .event_format(format)
+        .with_ansi(ansi)
         .with_env_filter(filter)
         .init();
 
@@ -3,25 +3,30 @@
 use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::io::{Read, Write};
 use std::path::{Component, Path, PathBuf};
+use std::sync::LazyLock;
 
 use base64::Engine;
+use regex::Regex;
 use rsa::sha2::Sha256;
 use rsa::{Oaep, RsaPublicKey};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use uuid::Uuid;
 
-use globset::{GlobBuilder, GlobSetBuilder};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use ignore::WalkBuilder;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 
 /// Errors that can occur when working with project files.
 #[derive(Debug, Error)]
 #[allow(missing_docs)]
 pub enum ProjectError {
-    #[error("no bauplan_project.yaml found in {0}")]
-    ProjectFileNotFound(PathBuf),
+    #[error("no bauplan_project.yaml found in {}", display_paths(.0))]
+    ProjectFileNotFound(Vec<PathBuf>),
     #[error("both bauplan_project.yml and .yaml found in {0}; remove one to avoid ambiguity")]
     ProjectFileAmbiguous(PathBuf),
+    #[error("no bauplan_project.yml or bauplan_project.yaml found in code snapshot")]
+    SnapshotProjectFileNotFound,
     #[error("failed to read project file")]
     Io(#[from] std::io::Error),
     #[error("failed to parse project file")]
@@ -42,6 +47,8 @@ pub enum ProjectError {
     Prefix(#[from] std::path::StripPrefixError),
     #[error("invalid value {0:?} of type {1}")]
     InvalidParameterValue(String, ParameterType),
+    #[error("invalid project file name: {0}")]
+    InvalidFileName(String),
 }
 
 /// The type of a parameter.
@@ -279,6 +286,11 @@ pub struct ProjectFile {
     /// The location of the project file on disk.
     #[serde(skip)]
     pub path: PathBuf,
+    /// The project's files, keyed by their path relative to the project
+    /// directory, for a project that isn't on disk. See
+    /// [ProjectFile::from_files].
+    #[serde(skip)]
+    pub files: Option<BTreeMap<String, Vec<u8>>>,
 }
 
 impl ProjectFile {
@@ -288,17 +300,31 @@ impl ProjectFile {
         // Ensure the directory exists before looking for files.
         std::fs::metadata(dir)?;
 
-        let yml_path = dir.join("bauplan_project.yml");
-        let yaml_path = dir.join("bauplan_project.yaml");
+        match project_file_in(dir)? {
+            Some(path) => Self::load(path),
+            None => Err(ProjectError::ProjectFileNotFound(vec![dir.to_path_buf()])),
+        }
+    }
 
-        let path = match (yml_path.exists(), yaml_path.exists()) {
-            (true, false) => yml_path,
-            (false, true) => yaml_path,
-            (true, true) => return Err(ProjectError::ProjectFileAmbiguous(dir.to_path_buf())),
-            (false, false) => return Err(ProjectError::ProjectFileNotFound(dir.to_path_buf())),
-        };
+    /// Find the project directory containing `start`: the nearest ancestor of
+    /// `start`, or `start` itself, with a project file in it. The search stops
+    /// at the root of the git repository containing `start`, if any.
+    pub fn find_dir(start: impl AsRef<Path>) -> Result<PathBuf, ProjectError> {
+        let start = std::path::absolute(start)?;
+        let mut searched = Vec::new();
 
-        Self::load(path)
+        for dir in start.ancestors() {
+            if project_file_in(dir)?.is_some() {
+                return Ok(dir.to_path_buf());
+            }
+
+            searched.push(dir.to_path_buf());
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+
+        Err(ProjectError::ProjectFileNotFound(searched))
     }
 
     /// Load a project file from a specific path.
@@ -311,9 +337,105 @@ impl ProjectFile {
         Ok(project)
     }
 
-    /// Create a zip archive of the project directory, including only relevant
-    /// files (.py, .sql, requirements.txt, and the project file itself).
-    pub fn create_code_snapshot(&self) -> Result<Vec<u8>, ProjectError> {
+    /// Load a project from its files, keyed by their path relative to the
+    /// project directory, instead of from disk. Exactly one of
+    /// `bauplan_project.yml` or `bauplan_project.yaml` must be among them.
+    ///
+    /// The returned project has an empty `path`; code snapshots are built
+    /// from `files`, using the same rules as for a project on disk.
+    pub fn from_files(files: BTreeMap<String, Vec<u8>>) -> Result<Self, ProjectError> {
+        let files = files
+            .into_iter()
+            .map(|(name, contents)| Ok((normalize_file_name(&name)?, contents)))
+            .collect::<Result<BTreeMap<_, _>, ProjectError>>()?;
+
+        let yml = files.get("bauplan_project.yml");
+        let yaml = files.get("bauplan_project.yaml");
+        let content = match (yml, yaml) {
+            (Some(content), None) | (None, Some(content)) => content,
+            (Some(_), Some(_)) => {
+                return Err(ProjectError::ProjectFileAmbiguous(PathBuf::from(
+                    IN_MEMORY_PROJECT,
+                )));
+            }
+            (None, None) => {
+                return Err(ProjectError::ProjectFileNotFound(vec![PathBuf::from(
+                    IN_MEMORY_PROJECT,
+                )]));
+            }
+        };
+
+        let mut project: Self = serde_yaml::from_slice(content)?;
+        project.files = Some(files);
+        Ok(project)
+    }
+
+    /// Load the project file from a code snapshot, as created by
+    /// [ProjectFile::create_code_snapshot]. The returned project has an empty
+    /// `path`, since it doesn't exist on disk.
+    pub fn from_code_snapshot(data: &[u8]) -> Result<Self, ProjectError> {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data))?;
+
+        for name in ["bauplan_project.yml", "bauplan_project.yaml"] {
+            let mut file = match archive.by_name(name) {
+                Ok(file) => file,
+                Err(zip::result::ZipError::FileNotFound) => continue,
+                Err(e) => return Err(e.into()),
+            };
+
+            let mut content = String::new();
+            file.read_to_string(&mut content)?;
+            return Ok(serde_yaml::from_str(&content)?);
+        }
+
+        Err(ProjectError::SnapshotProjectFileNotFound)
+    }
+
+    /// Returns the in-memory files that belong in a code snapshot, keyed by
+    /// name, or `None` if the project is on disk.
+    fn in_memory_files(&self) -> Result<Option<BTreeMap<&str, &[u8]>>, ProjectError> {
+        let Some(files) = &self.files else {
+            return Ok(None);
+        };
+
+        let set = include_set(&self.additional_patterns()?)?;
+        let ignore = match files.get(IGNORE_FILE) {
+            Some(contents) => {
+                let mut builder = GitignoreBuilder::new("");
+                for line in String::from_utf8_lossy(contents).lines() {
+                    builder.add_line(None, line)?;
+                }
+
+                builder.build()?
+            }
+            None => Gitignore::empty(),
+        };
+
+        let files = files
+            .iter()
+            .filter(|(name, _)| set.is_match(name.as_str()))
+            .filter(|(name, _)| {
+                !ignore
+                    .matched_path_or_any_parents(name.as_str(), false)
+                    .is_ignore()
+            })
+            .map(|(name, contents)| (name.as_str(), contents.as_slice()))
+            .collect();
+
+        Ok(Some(files))
+    }
+
+    /// Returns the user-provided patterns from `include_paths`.
+    fn additional_patterns(&self) -> Result<Vec<String>, ProjectError> {
+        self.include_paths
+            .iter()
+            .map(|p| resolve_pattern(p))
+            .collect()
+    }
+
+    /// Returns the project directory, along with the files that belong in a
+    /// code snapshot.
+    fn project_files(&self) -> Result<(&Path, HashSet<PathBuf>), ProjectError> {
         let project_dir = self.path.parent().ok_or_else(|| {
             std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
@@ -321,21 +443,77 @@ impl ProjectFile {
             )
         })?;
 
-        // Additional, user-provided patterns.
-        let additional_patterns = self
-            .include_paths
-            .iter()
-            .map(|p| resolve_pattern(p))
-            .collect::<Result<Vec<_>, _>>()?;
+        let files = resolve_includes(project_dir, &self.additional_patterns()?)?.collect();
+        Ok((project_dir, files))
+    }
+
+    /// Returns the names of the models defined in the project: the stem of
+    /// each SQL file, and each Python function decorated with
+    /// `@bauplan.model`. This is a best-effort scan of the source, rather
+    /// than a full parse.
+    pub fn model_names(&self) -> Result<BTreeSet<String>, ProjectError> {
+        let mut names = BTreeSet::new();
+        if let Some(files) = self.in_memory_files()? {
+            for (name, contents) in files {
+                let path = Path::new(name);
+                match path.extension().and_then(|ext| ext.to_str()) {
+                    Some("sql") => {
+                        if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                            names.insert(stem.to_owned());
+                        }
+                    }
+                    Some("py") => {
+                        names.extend(python_model_names(&String::from_utf8_lossy(contents)));
+                    }
+                    _ => (),
+                }
+            }
+
+            return Ok(names);
+        }
+
+        let (_, files) = self.project_files()?;
+
+        for path in files {
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some("sql") => {
+                    if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                        names.insert(stem.to_owned());
+                    }
+                }
+                Some("py") => {
+                    let source = std::fs::read_to_string(&path)?;
+                    names.extend(python_model_names(&source));
+                }
+                _ => (),
+            }
+        }
 
-        let files: HashSet<PathBuf> =
-            resolve_includes(project_dir, &additional_patterns)?.collect();
+        Ok(names)
+    }
 
+    /// Create a zip archive of the project directory, including only relevant
+    /// files: .py and .sql files at any depth, requirements.txt, and the
+    /// project file itself. Files matched by a `.bauplanignore` file, which
+    /// uses gitignore syntax, are left out.
+    pub fn create_code_snapshot(&self) -> Result<Vec<u8>, ProjectError> {
         let mut buf = Vec::new();
         let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut buf));
         let options = zip::write::SimpleFileOptions::default()
             .compression_method(zip::CompressionMethod::Deflated);
 
+        if let Some(files) = self.in_memory_files()? {
+            for (name, contents) in files {
+                zip.start_file(name, options)?;
+                zip.write_all(contents)?;
+            }
+
+            zip.finish()?;
+            return Ok(buf);
+        }
+
+        let (project_dir, files) = self.project_files()?;
+
         let mut contents = Vec::new();
         for path in files {
             // The zip spec mandates forward slashes in entry names, and serializing
@@ -356,6 +534,71 @@ impl ProjectFile {
     }
 }
 
+/// Finds the functions decorated with `@bauplan.model` in Python source,
+/// using the `name` argument to the decorator if there is one.
+fn python_model_names(source: &str) -> Vec<String> {
+    static DEF_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^\s*(?:async\s+)?def\s+(\w+)").unwrap());
+    static NAME_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r#"\bname\s*=\s*['"]([^'"]+)['"]"#).unwrap());
+
+    let mut names = Vec::new();
+
+    // The text of the model decorator, from `@bauplan.model` up to the next
+    // decorator or the function definition.
+    let mut decorator: Option<String> = None;
+    let mut in_decorator = false;
+    for line in source.lines() {
+        let trimmed = line.trim_start();
+        if let Some(caps) = DEF_RE.captures(line) {
+            if let Some(decorator) = decorator.take() {
+                let name = NAME_RE
+                    .captures(&decorator)
+                    .map_or(&caps[1], |c| c.get(1).unwrap().as_str());
+                names.push(name.to_owned());
+            }
+
+            in_decorator = false;
+        } else if trimmed.starts_with('@') {
+            in_decorator = trimmed.starts_with("@bauplan.model");
+            if in_decorator {
+                decorator = Some(line.to_owned());
+            }
+        } else if in_decorator && let Some(decorator) = &mut decorator {
+            decorator.push('\n');
+            decorator.push_str(line);
+        }
+    }
+
+    names
+}
+
+/// A file listing patterns, in gitignore syntax, for files to leave out of
+/// code snapshots.
+const IGNORE_FILE: &str = ".bauplanignore";
+
+/// Stands in for the project directory in errors about a project loaded with
+/// [ProjectFile::from_files].
+const IN_MEMORY_PROJECT: &str = "the project files";
+
+/// Checks that the name of an in-memory project file is a relative path
+/// inside the project, and converts it to the form used in snapshots.
+fn normalize_file_name(name: &str) -> Result<String, ProjectError> {
+    let normalized = name.replace('\\', "/");
+    let normalized = normalized.trim_start_matches("./");
+
+    let path = Path::new(normalized);
+    let valid = !normalized.is_empty()
+        && !normalized.starts_with('/')
+        && !path.is_absolute()
+        && path.components().all(|c| matches!(c, Component::Normal(_)));
+    if !valid {
+        return Err(ProjectError::InvalidFileName(name.to_owned()));
+    }
+
+    Ok(normalized.to_owned())
+}
+
 /// Given a glob pattern, ensure the pattern is "admissible".
 fn resolve_pattern(p: &str) -> Result<String, ProjectError> {
     // Users should be explicitly including file extensions, not globbing for all files.
@@ -386,20 +629,67 @@ fn resolve_pattern(p: &str) -> Result<String, ProjectError> {
 
 /// Include files in snapshot, based on provided glob patterns.
 /// Ignoring takes precedence over inclusion: any ignored file will not be included
-/// regardless of inclusion patterns.
+/// regardless of inclusion patterns. This covers `.gitignore` and
+/// `.bauplanignore` files, hidden files and directories, and virtual
+/// environments.
+///
+/// Symlinks to directories aren't followed, and a symlink to a file outside
+/// the project directory is an error.
 fn resolve_includes<S: AsRef<str>>(
     base: &Path,
     patterns: &[S],
 ) -> Result<impl Iterator<Item = PathBuf>, ProjectError> {
     let base_canonical = base.canonicalize()?;
+    let set = include_set(patterns)?;
+
+    let mut paths = BTreeSet::new();
 
-    // Build a glob set, matching all the required patterns.
+    // Virtual environments are often left in the project directory, and are
+    // full of Python files that don't belong in the snapshot.
+    let is_venv = |e: &ignore::DirEntry| {
+        e.file_type().is_some_and(|t| t.is_dir()) && e.path().join("pyvenv.cfg").exists()
+    };
+
+    for entry in WalkBuilder::new(&base_canonical)
+        .add_custom_ignore_filename(IGNORE_FILE)
+        .filter_entry(move |e| !is_venv(e))
+        .build()
+    {
+        let entry = entry?.into_path();
+
+        // For globset to work, we need relative paths.
+        let rel_entry = entry.strip_prefix(&base_canonical)?;
+        if !set.is_match(rel_entry) {
+            continue;
+        }
+
+        let canonical = entry.canonicalize()?;
+        if !canonical.is_file() {
+            continue;
+        }
+
+        if !canonical.starts_with(&base_canonical) {
+            return Err(ProjectError::Symlink(entry, canonical));
+        }
+
+        // For zipping to work, we need absolute paths. Symlinks keep their
+        // own name in the snapshot.
+        paths.insert(entry);
+    }
+
+    Ok(paths.into_iter())
+}
+
+/// Builds a glob set matching the files that belong in a code snapshot: the
+/// source files and top-level files every project has, and the given
+/// patterns.
+fn include_set<S: AsRef<str>>(patterns: &[S]) -> Result<GlobSet, ProjectError> {
     let mut glob_builder = GlobSetBuilder::new();
 
-    // Top level patterns, always included.
+    // Always included: sources at any depth, and top-level project files.
     let base_patterns = [
-        "*.py",
-        "*.sql",
+        "**/*.py",
+        "**/*.sql",
         "requirements.txt",
         "bauplan_project.yml",
         "bauplan_project.yaml",
@@ -417,44 +707,293 @@ fn resolve_includes<S: AsRef<str>>(
         glob_builder.add(GlobBuilder::new(p).literal_separator(true).build().unwrap());
     }
 
-    let set = glob_builder.build()?;
+    Ok(glob_builder.build()?)
+}
 
-    let mut paths = BTreeSet::new();
+/// Returns the project file in `dir`, if there is exactly one.
+fn project_file_in(dir: &Path) -> Result<Option<PathBuf>, ProjectError> {
+    let yml_path = dir.join("bauplan_project.yml");
+    let yaml_path = dir.join("bauplan_project.yaml");
 
-    // If no patterns are provided, restrict to project directory alone.
-    let max_depth = if patterns.is_empty() { Some(1) } else { None };
+    match (yml_path.exists(), yaml_path.exists()) {
+        (true, false) => Ok(Some(yml_path)),
+        (false, true) => Ok(Some(yaml_path)),
+        (true, true) => Err(ProjectError::ProjectFileAmbiguous(dir.to_path_buf())),
+        (false, false) => Ok(None),
+    }
+}
 
-    for entry in WalkBuilder::new(&base_canonical)
-        .max_depth(max_depth)
-        .build()
-    {
-        let entry = entry?.into_path();
-        let canonical = entry.canonicalize()?;
+fn display_paths(paths: &[PathBuf]) -> String {
+    let paths: Vec<_> = paths.iter().map(|p| p.display().to_string()).collect();
+    paths.join(", ")
+}
 
-        if !canonical.is_file() {
-            continue;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_snapshot_roundtrip() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        std::fs::write(
+            tmp.path().join("bauplan_project.yml"),
+            "project:\n  id: 6d5f4a3c-2b1a-4c9d-8e7f-0a1b2c3d4e5f\n  name: snap\n",
+        )?;
+        std::fs::write(tmp.path().join("model.py"), "x = 1")?;
+
+        let project = ProjectFile::from_dir(tmp.path())?;
+        let snapshot = project.create_code_snapshot()?;
+
+        let loaded = ProjectFile::from_code_snapshot(&snapshot)?;
+        assert_eq!(loaded.project.id, project.project.id);
+        assert_eq!(loaded.project.name.as_deref(), Some("snap"));
+        Ok(())
+    }
+
+    /// Returns the sorted entry names of a code snapshot.
+    fn snapshot_names(snapshot: &[u8]) -> anyhow::Result<Vec<String>> {
+        let archive = zip::ZipArchive::new(std::io::Cursor::new(snapshot))?;
+        let mut names: Vec<_> = archive.file_names().map(String::from).collect();
+        names.sort_unstable();
+        Ok(names)
+    }
+
+    #[test]
+    fn code_snapshot_nested() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let write = |name: &str| -> std::io::Result<()> {
+            let path = tmp.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, "")
+        };
+
+        std::fs::write(
+            tmp.path().join("bauplan_project.yml"),
+            "project:\n  id: 6d5f4a3c-2b1a-4c9d-8e7f-0a1b2c3d4e5f\n",
+        )?;
+        for name in [
+            "requirements.txt",
+            "models.py",
+            "models/staging/stg_trips.py",
+            "models/marts/trips.sql",
+            "models/requirements.txt",
+            "notebooks/explore.ipynb",
+            "notebooks/helpers.py",
+            "tests/fixtures/sample.sql",
+            "models/staging/scratch_trips.py",
+            ".venv/lib/site.py",
+            "venv/pyvenv.cfg",
+            "venv/lib/site.py",
+        ] {
+            write(name)?;
         }
 
-        let rel_entry = canonical
-            .strip_prefix(&base_canonical)
-            .map_err(|_| ProjectError::Symlink(entry, canonical.clone()))?
-            .to_path_buf();
+        std::fs::write(
+            tmp.path().join(".bauplanignore"),
+            "notebooks/\ntests/\nscratch_*.py\n",
+        )?;
 
-        // For globset to work, we need relative paths.
-        if !set.is_match(&rel_entry) {
-            continue;
+        let project = ProjectFile::from_dir(tmp.path())?;
+        let snapshot = project.create_code_snapshot()?;
+        assert_eq!(
+            snapshot_names(&snapshot)?,
+            [
+                "bauplan_project.yml",
+                "models.py",
+                "models/marts/trips.sql",
+                "models/staging/stg_trips.py",
+                "requirements.txt",
+            ]
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn code_snapshot_symlinks() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let project_dir = tmp.path().join("project");
+        let outside = tmp.path().join("outside");
+        std::fs::create_dir_all(&project_dir)?;
+        std::fs::create_dir_all(&outside)?;
+        std::fs::write(outside.join("secret.py"), "")?;
+        std::fs::write(
+            project_dir.join("bauplan_project.yml"),
+            "project:\n  id: 6d5f4a3c-2b1a-4c9d-8e7f-0a1b2c3d4e5f\n",
+        )?;
+        std::fs::write(project_dir.join("models.py"), "")?;
+
+        // Links inside the project are kept under their own name, and linked
+        // directories aren't followed.
+        std::os::unix::fs::symlink(project_dir.join("models.py"), project_dir.join("alias.py"))?;
+        std::os::unix::fs::symlink(&outside, project_dir.join("linked"))?;
+
+        let project = ProjectFile::from_dir(&project_dir)?;
+        assert_eq!(
+            snapshot_names(&project.create_code_snapshot()?)?,
+            ["alias.py", "bauplan_project.yml", "models.py"]
+        );
+
+        std::os::unix::fs::symlink(outside.join("secret.py"), project_dir.join("secret.py"))?;
+        let err = project.create_code_snapshot().unwrap_err();
+        assert!(matches!(err, ProjectError::Symlink(..)));
+        Ok(())
+    }
+
+    #[test]
+    fn from_files() -> anyhow::Result<()> {
+        let files = BTreeMap::from([
+            (
+                "bauplan_project.yml".to_owned(),
+                b"project:\n  id: 6d5f4a3c-2b1a-4c9d-8e7f-0a1b2c3d4e5f\n  name: mem\n".to_vec(),
+            ),
+            (
+                "./models.py".to_owned(),
+                b"@bauplan.model()\ndef clean(data=bauplan.Model('trips')):\n    return data\n"
+                    .to_vec(),
+            ),
+            ("trips.sql".to_owned(), b"SELECT 1".to_vec()),
+            ("notes.txt".to_owned(), b"not included".to_vec()),
+            ("scratch/draft.py".to_owned(), b"".to_vec()),
+            (".bauplanignore".to_owned(), b"scratch/\n".to_vec()),
+        ]);
+
+        let project = ProjectFile::from_files(files)?;
+        assert_eq!(project.project.name.as_deref(), Some("mem"));
+        assert_eq!(
+            project.model_names()?,
+            BTreeSet::from(["clean", "trips"].map(String::from))
+        );
+
+        let snapshot = project.create_code_snapshot()?;
+        assert_eq!(
+            snapshot_names(&snapshot)?,
+            ["bauplan_project.yml", "models.py", "trips.sql"]
+        );
+
+        let loaded = ProjectFile::from_code_snapshot(&snapshot)?;
+        assert_eq!(loaded.project.id, project.project.id);
+        Ok(())
+    }
+
+    #[test]
+    fn from_files_invalid() {
+        let file = |name: &str| BTreeMap::from([(name.to_owned(), Vec::new())]);
+
+        let err = ProjectFile::from_files(file("models.py")).unwrap_err();
+        assert!(matches!(err, ProjectError::ProjectFileNotFound(_)));
+
+        let mut files = file("bauplan_project.yml");
+        files.append(&mut file("bauplan_project.yaml"));
+        let err = ProjectFile::from_files(files).unwrap_err();
+        assert!(matches!(err, ProjectError::ProjectFileAmbiguous(_)));
+
+        for name in ["../models.py", "/etc/passwd", "models/../../x.py", ""] {
+            let err = ProjectFile::from_files(file(name)).unwrap_err();
+            assert!(matches!(err, ProjectError::InvalidFileName(_)), "{name}");
         }
+    }
 
-        // For zipping to work, we need absolute paths.
-        paths.insert(canonical);
+    #[test]
+    fn find_dir_walks_up() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let project_dir = tmp.path().join("project");
+        let models_dir = project_dir.join("models/staging");
+        std::fs::create_dir(tmp.path().join(".git"))?;
+        std::fs::create_dir_all(&models_dir)?;
+        std::fs::write(project_dir.join("bauplan_project.yml"), "")?;
+
+        assert_eq!(ProjectFile::find_dir(&models_dir)?, project_dir);
+        assert_eq!(ProjectFile::find_dir(&project_dir)?, project_dir);
+        Ok(())
     }
 
-    Ok(paths.into_iter())
-}
+    #[test]
+    fn find_dir_nearest_wins() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let nested = tmp.path().join("nested");
+        std::fs::create_dir(tmp.path().join(".git"))?;
+        std::fs::create_dir_all(nested.join("models"))?;
+        std::fs::write(tmp.path().join("bauplan_project.yml"), "")?;
+        std::fs::write(nested.join("bauplan_project.yaml"), "")?;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        assert_eq!(ProjectFile::find_dir(nested.join("models"))?, nested);
+        Ok(())
+    }
+
+    #[test]
+    fn find_dir_ambiguous() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let models_dir = tmp.path().join("models");
+        std::fs::create_dir(tmp.path().join(".git"))?;
+        std::fs::create_dir(&models_dir)?;
+        std::fs::write(tmp.path().join("bauplan_project.yml"), "")?;
+        std::fs::write(tmp.path().join("bauplan_project.yaml"), "")?;
+
+        let err = ProjectFile::find_dir(&models_dir).unwrap_err();
+        assert!(matches!(err, ProjectError::ProjectFileAmbiguous(dir) if dir == tmp.path()));
+        Ok(())
+    }
+
+    #[test]
+    fn find_dir_stops_at_git_root() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let repo = tmp.path().join("repo");
+        let models_dir = repo.join("models");
+        std::fs::create_dir_all(repo.join(".git"))?;
+        std::fs::create_dir(&models_dir)?;
+        std::fs::write(tmp.path().join("bauplan_project.yml"), "")?;
+
+        let err = ProjectFile::find_dir(&models_dir).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "no bauplan_project.yaml found in {}, {}",
+                models_dir.display(),
+                repo.display(),
+            ),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn model_names() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        std::fs::write(
+            tmp.path().join("bauplan_project.yml"),
+            "project:\n  id: 6d5f4a3c-2b1a-4c9d-8e7f-0a1b2c3d4e5f\n",
+        )?;
+        std::fs::write(tmp.path().join("trips.sql"), "SELECT 1")?;
+        std::fs::write(
+            tmp.path().join("models.py"),
+            r#"
+import bauplan
+
+def helper():
+    pass
+
+@bauplan.model(
+    columns=['col_1'],
+    materialization_strategy='REPLACE',
+)
+@bauplan.python('3.11', pip={'name': 'ignored'})
+def clean_trips(data=bauplan.Model('trips', name='ignored')):
+    return data
+
+@bauplan.model(name="renamed")
+def original(data=bauplan.Model('trips')):
+    return data
+"#,
+        )?;
+
+        let project = ProjectFile::from_dir(tmp.path())?;
+        assert_eq!(
+            project.model_names()?,
+            BTreeSet::from(["clean_trips", "renamed", "trips"].map(String::from))
+        );
+
+        Ok(())
+    }
 
     #[test]
     fn resolve_pattern_rejects_upward_pattern() -> anyhow::Result<()> {
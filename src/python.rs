@@ -1,18 +1,28 @@
 //! Python bindings for the Bauplan client.
 
 use std::{
-    sync::{Arc, OnceLock},
+    ffi::CString,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
     time,
 };
 
-use pyo3::{exceptions::PyValueError, marker::Ungil, prelude::*};
+use pyo3::{
+    exceptions::{PyRuntimeError, PyRuntimeWarning, PyValueError},
+    marker::Ungil,
+    prelude::*,
+};
 use tokio::runtime::Runtime;
+use tracing::{debug, error, warn};
 
 mod branch;
 mod commit;
 mod exceptions;
 mod info;
 pub(crate) mod job;
+mod logging;
 mod namespace;
 mod paginate;
 mod query;
@@ -22,12 +32,41 @@ mod schema;
 mod state;
 mod table;
 mod tag;
+mod write;
 
 use crate::{
-    ApiError, ApiErrorKind, ApiRequest, ApiResponse, Profile, grpc,
+    ApiError, ApiErrorKind, ApiRequest, ApiResponse, CatalogRef, OperationRequest, Profile,
+    RefSpecError, RetryBudget, flight, grpc,
     python::exceptions::{BauplanError, BauplanJobError},
 };
 
+/// The default deadline for each API request, in seconds.
+const DEFAULT_REQUEST_TIMEOUT: f64 = 30.0;
+
+/// The default cap on the number of log events kept on a `RunState`.
+const DEFAULT_MAX_USER_LOGS: usize = 10_000;
+
+/// Incremented in the child process after every fork. The tokio runtime and
+/// anything bound to it (like gRPC channels) are rebuilt when it changes,
+/// since the runtime's threads don't survive a fork.
+static FORK_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+pub(crate) fn fork_generation() -> u64 {
+    FORK_GENERATION.load(Ordering::SeqCst)
+}
+
+/// Registered with `os.register_at_fork` to run in the child after a fork.
+#[pyfunction]
+fn after_fork_in_child() {
+    FORK_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// The error raised when state bound to the parent's runtime, like a log tail
+/// or a paginator, is used in a forked child.
+pub(crate) fn used_after_fork() -> PyErr {
+    BauplanError::new_err("client used after fork; create a new Client")
+}
+
 pub(crate) fn job_err(e: impl std::fmt::Display) -> PyErr {
     BauplanJobError::new_err(e.to_string())
 }
@@ -40,15 +79,52 @@ enum ClientError {
     Transport(#[from] ureq::Error),
     #[error(transparent)]
     Api(#[from] ApiError),
+    #[error(transparent)]
+    Ref(#[from] RefSpecError),
+    #[error(transparent)]
+    Sync(#[from] crate::sync::SyncError),
+    #[error("{operation} timed out after {:.1}s", .elapsed.as_secs_f64())]
+    Timeout {
+        operation: String,
+        elapsed: time::Duration,
+    },
+    #[error("client is closed")]
+    Closed,
 }
 
 impl ClientError {
-    pub(crate) fn kind(&self) -> Option<&ApiErrorKind> {
+    pub(crate) fn api(&self) -> Option<&ApiError> {
         match self {
-            ClientError::Api(ae) => ae.kind(),
+            ClientError::Api(ae) => Some(ae),
             _ => None,
         }
     }
+
+    pub(crate) fn kind(&self) -> Option<&ApiErrorKind> {
+        self.api()?.kind()
+    }
+
+    /// Whether the request might succeed if retried later.
+    pub(crate) fn is_transient(&self) -> bool {
+        match self {
+            ClientError::Validation(_)
+            | ClientError::Ref(_)
+            | ClientError::Sync(_)
+            | ClientError::Closed => false,
+            ClientError::Transport(_) | ClientError::Timeout { .. } => true,
+            ClientError::Api(ae) => ae.is_transient(),
+        }
+    }
+
+    /// Whether the request may have been applied even though it failed.
+    fn response_lost(&self) -> bool {
+        match self {
+            ClientError::Transport(ureq::Error::Timeout(_) | ureq::Error::Io(_)) => true,
+            ClientError::Timeout { .. } => true,
+            ClientError::Api(ae) => ae.response_lost(),
+            _ => false,
+        }
+    }
 }
 
 /// A client for the Bauplan API.
@@ -87,6 +163,23 @@ impl ClientError {
 ///
 /// # specify a profile from ~/.bauplan/config.yml - this supersedes BAUPLAN_PROFILE in the environment
 /// client = bauplan.Client(profile='default')
+///
+/// # with neither, the profile set by `bauplan config use-profile` is used, or else 'default'
+/// ```
+///
+/// #### The active branch
+///
+/// Methods that take a ref or branch use, in order of precedence: the
+/// argument, if given; then `client.active_branch`, which starts out as the
+/// profile's `active_branch`; and finally `"main"`.
+///
+/// ```python
+/// client = bauplan.Client()
+/// client.checkout('my_branch_name')
+/// # >> queries, runs and table operations now default to 'my_branch_name'
+/// client.query('SELECT count(*) FROM titanic')
+/// # an explicit ref always wins
+/// client.query('SELECT count(*) FROM titanic', ref='main')
 /// ```
 ///
 /// #### Handling Exceptions
@@ -99,7 +192,8 @@ impl ClientError {
 ///     - 404: `bauplan.exceptions.ApiMethodError` e.g. the given API method doesn't exist
 ///     - 405: `bauplan.exceptions.ApiRouteError` e.g. POST on a route with only GET defined
 ///     - 409: `bauplan.exceptions.UpdateConflictError` e.g. creating a record with a name that already exists
-///     - 429: `bauplan.exceptions.TooManyRequestsError`
+///     - 429: `bauplan.exceptions.TooManyRequestsError`, once retries within `rate_limit_budget` are exhausted
+///     - `bauplan.exceptions.RequestTimeoutError`, if a request takes longer than `request_timeout`
 ///
 /// Run/Query/Scan/Import operations raise a subclass of `bauplan.exceptions.BauplanError` that represents the error, and also return a `bauplan.state.RunState` object containing details and logs:
 ///     - `bauplan.exceptions.BauplanJobError` e.g. something went wrong in a run/query/import/scan; includes error details
@@ -142,6 +236,41 @@ impl ClientError {
 /// branches = list(client.get_branches())
 /// ```
 ///
+/// #### Async code
+///
+/// Methods that wait for a job to finish (`query`, `run`, `scan`, `import_data`,
+/// `create_table`, and friends) block the calling thread. Called from a coroutine,
+/// they would freeze the event loop until the job is done, so they raise a
+/// `RuntimeError` if an asyncio event loop is running in the current thread.
+/// Run them in a worker thread instead:
+///
+/// ```python
+/// import asyncio
+///
+/// async def main():
+///     client = bauplan.Client()
+///     table = await asyncio.to_thread(client.query, "SELECT 1", ref="main")
+/// ```
+///
+/// In environments where blocking the loop is expected (like notebooks, which
+/// run cells inside an event loop), pass `allow_blocking=True` to get a
+/// warning instead.
+///
+/// #### Threads
+///
+/// A single client can be shared between threads, for example in a
+/// `ThreadPoolExecutor`. Calls from different threads run concurrently,
+/// and the GIL is released while they wait on the network. The active
+/// branch is shared, so set it before handing the client to other threads.
+///
+/// #### Forking
+///
+/// A client can be created before forking, for example at import time in a
+/// preforking server like gunicorn or uWSGI. In the child, its connections are
+/// transparently rebuilt on the next call. Iterators and log tails that were
+/// in progress when the process forked can't be resumed in the child, and
+/// raise a `bauplan.exceptions.BauplanError`.
+///
 /// ## Examples
 ///
 /// ```python
@@ -152,22 +281,67 @@ impl ClientError {
 /// ```
 ///
 /// Parameters:
-///     profile: The Bauplan config profile name to use to determine api_key.
+///     profile: The Bauplan config profile name to use. Defaults to `BAUPLAN_PROFILE`, then the config file's `current_profile`, then `default`. Its `api_key`, endpoints and `default_namespace` can be overridden by environment variables, like `BAUPLAN_API_KEY` and `BAUPLAN_NAMESPACE`.
 ///     api_key: Your unique Bauplan API key; mutually exclusive with `profile`. If not provided, fetch precedence is 1) environment `BAUPLAN_API_KEY` 2) .bauplan/config.yml
-///     client_timeout: The client timeout in seconds for all the requests.
+///     client_timeout: The timeout in seconds for job and query requests.
+///     request_timeout: The timeout in seconds for each catalog request, like `get_table` or `create_branch`. Methods that make catalog requests can override it per call.
 ///     config_file_path: The path to the Bauplan config file to use. If not provided, ~/.bauplan/config.yaml will be used. Note that this disables any environment-based configuration.
-#[pyclass(module = "bauplan")]
+///     allow_blocking: Whether to allow blocking methods to be called while an asyncio event loop is running in the current thread. If `True`, a `RuntimeWarning` is emitted instead of raising an error.
+///     max_user_logs: The maximum number of log events kept on a `bauplan.state.RunState`. Past the cap, the first and last halves are kept, with a marker event in between noting how many were dropped; the full logs remain available from `get_job_logs`. `None` keeps all of them.
+///     rate_limit_budget: The total number of seconds to spend waiting and retrying when an API call is rate limited, before raising `bauplan.exceptions.TooManyRequestsError`. Defaults to the profile's `rate_limit_budget`, or 60 seconds. `0` disables retries.
+///     flight_max_retries: The number of times to retry, with exponential backoff, when connecting to the server that returns query results fails. Errors returned by the server aren't retried. `0` disables retries.
+#[pyclass(module = "bauplan", frozen)]
 pub(crate) struct Client {
     pub(crate) profile: Profile,
-    pub(crate) agent: ureq::Agent,
-    pub(crate) grpc: grpc::Client,
     pub(crate) client_timeout: time::Duration,
-    /// NB: we don't ever call `endpoint.close()`, because there's no good time
-    /// to do that. It's probably fine; in normal use all connections will have
-    /// finished out long before we drop the client. If any are still open when
-    /// we drop, then the server will have to wait for the idle timeout, but
-    /// that's not that tragic.
-    pub(crate) longbow_endpoint: Arc<tokio::sync::OnceCell<bauplan_longbow::iroh::Endpoint>>,
+    pub(crate) request_timeout: time::Duration,
+    pub(crate) allow_blocking: bool,
+    pub(crate) max_user_logs: usize,
+    pub(crate) flight_max_retries: u32,
+    /// The installed version of the bauplan package, sent with every job so
+    /// that the runtime parses the project the way that version would.
+    pub(crate) module_version: String,
+    /// `None` once the client is closed.
+    conn: Mutex<Option<Connection>>,
+    active_branch: Mutex<String>,
+}
+
+type LongbowEndpoint = Arc<tokio::sync::OnceCell<bauplan_longbow::iroh::Endpoint>>;
+
+/// The connections held by a [Client]. The parts bound to the tokio runtime
+/// are rebuilt after a fork.
+struct Connection {
+    fork_generation: u64,
+    agent: ureq::Agent,
+    grpc: grpc::Client,
+    /// NB: this is only closed by `Client.close`. Otherwise, all connections
+    /// will normally have finished out long before we drop the client. If any
+    /// are still open when we drop, then the server will have to wait for the
+    /// idle timeout, but that's not that tragic.
+    longbow_endpoint: LongbowEndpoint,
+}
+
+impl Connection {
+    fn new(
+        profile: &Profile,
+        client_timeout: time::Duration,
+        agent: ureq::Agent,
+    ) -> PyResult<Self> {
+        let fork_generation = fork_generation();
+        let grpc = {
+            let rt = rt();
+            let _guard = rt.enter();
+            grpc::Client::new_lazy(profile, client_timeout)
+                .map_err(|e| BauplanError::new_err(e.to_string()))?
+        };
+
+        Ok(Self {
+            fork_generation,
+            agent,
+            grpc,
+            longbow_endpoint: Arc::new(tokio::sync::OnceCell::new()),
+        })
+    }
 }
 
 #[pymethods]
@@ -178,20 +352,28 @@ impl Client {
             api_key = None,
             client_timeout = None,
             config_file_path = None,
+            allow_blocking = false,
+            max_user_logs = Some(DEFAULT_MAX_USER_LOGS),
+            rate_limit_budget = None,
+            request_timeout = DEFAULT_REQUEST_TIMEOUT,
+            flight_max_retries = flight::DEFAULT_MAX_RETRIES,
         ))]
     #[allow(clippy::too_many_arguments)]
     fn new(
+        py: Python<'_>,
         profile: Option<&str>,
         api_key: Option<String>,
         client_timeout: Option<u64>,
         config_file_path: Option<&str>,
+        allow_blocking: bool,
+        max_user_logs: Option<usize>,
+        rate_limit_budget: Option<u64>,
+        request_timeout: f64,
+        flight_max_retries: u32,
     ) -> PyResult<Self> {
-        let profile = if let Some(p) = config_file_path {
-            Profile::read(p, profile)
-        } else if let Some(name) = profile {
-            Profile::from_env(name)
-        } else {
-            Profile::from_default_env()
+        let profile = match config_file_path {
+            Some(p) => Profile::read(p, profile),
+            None => Profile::from_env_or_file(profile),
         };
 
         let mut profile = profile
@@ -201,6 +383,10 @@ impl Client {
             profile = profile.with_api_key(api_key);
         }
 
+        if let Some(secs) = rate_limit_budget {
+            profile = profile.with_rate_limit_budget(time::Duration::from_secs(secs));
+        }
+
         profile
             .validate()
             .map_err(|e| PyValueError::new_err(e.to_string()))?;
@@ -210,6 +396,7 @@ impl Client {
         let client_timeout = client_timeout
             .map(time::Duration::from_secs)
             .unwrap_or(time::Duration::from_secs(30));
+        let request_timeout = positive_secs("request_timeout", request_timeout)?;
 
         let cfg = ureq::config::Config::builder()
             .http_status_as_error(false)
@@ -218,24 +405,228 @@ impl Client {
                     .root_certs(ureq::tls::RootCerts::PlatformVerifier)
                     .build(),
             )
-            .timeout_global(Some(client_timeout));
+            .timeout_global(Some(request_timeout));
         let agent = ureq::Agent::new_with_config(cfg.build());
 
-        let grpc = {
-            let rt = rt();
-            let _guard = rt.enter();
-            grpc::Client::new_lazy(&profile, client_timeout)
-                .map_err(|e| BauplanError::new_err(e.to_string()))?
-        };
+        let conn = Connection::new(&profile, client_timeout, agent)?;
+        let active_branch = profile
+            .active_branch
+            .as_deref()
+            .unwrap_or("main")
+            .to_owned();
 
         Ok(Self {
             profile,
-            agent,
-            grpc,
             client_timeout,
-            longbow_endpoint: Arc::new(tokio::sync::OnceCell::new()),
+            request_timeout,
+            allow_blocking,
+            max_user_logs: max_user_logs.unwrap_or(usize::MAX),
+            flight_max_retries,
+            module_version: installed_version(py),
+            conn: Mutex::new(Some(conn)),
+            active_branch: Mutex::new(active_branch),
         })
     }
+
+    /// The branch that methods use when their ref or branch argument is
+    /// omitted. Initialized from the profile's `active_branch`, or `"main"`.
+    ///
+    /// Setting it doesn't check that the branch exists; use
+    /// `bauplan.Client.checkout` for that.
+    #[getter]
+    fn active_branch(&self) -> String {
+        self.active_branch.lock().unwrap().clone()
+    }
+
+    #[setter]
+    fn set_active_branch(&self, branch: refs::BranchArg) {
+        *self.active_branch.lock().unwrap() = branch.0;
+    }
+
+    /// The endpoint of the Iceberg REST catalog, for configuring other
+    /// Iceberg clients, like Spark, with the same API key. They add the `/v1`
+    /// prefix themselves.
+    #[getter]
+    fn catalog_endpoint(&self) -> String {
+        self.profile.catalog_endpoint().to_string()
+    }
+
+    /// The endpoint of the gRPC API, which runs jobs and streams their
+    /// events.
+    #[getter]
+    fn grpc_endpoint(&self) -> String {
+        self.profile.grpc_endpoint().to_string()
+    }
+
+    /// Closes the client's connections. Afterwards, methods that make
+    /// requests raise `bauplan.exceptions.BauplanError`. Closing a closed
+    /// client does nothing.
+    ///
+    /// Other clients aren't affected. A client can also be used as a context
+    /// manager, which closes it on exit:
+    ///
+    /// ```python
+    /// with bauplan.Client() as client:
+    ///     client.query('SELECT 1', ref='main')
+    /// ```
+    fn close(&self, py: Python<'_>) {
+        let Some(conn) = self.conn.lock().unwrap().take() else {
+            return;
+        };
+
+        // The endpoint belongs to the parent's runtime after a fork.
+        if conn.fork_generation == fork_generation()
+            && let Some(ep) = conn.longbow_endpoint.get()
+        {
+            detach(py, ep.close());
+        }
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python<'_>,
+        _exc_type: &Bound<'_, PyAny>,
+        _exc_value: &Bound<'_, PyAny>,
+        _traceback: &Bound<'_, PyAny>,
+    ) {
+        self.close(py);
+    }
+}
+
+/// Converts a number of seconds from Python, which must be positive.
+fn positive_secs(name: &str, secs: f64) -> PyResult<time::Duration> {
+    time::Duration::try_from_secs_f64(secs)
+        .ok()
+        .filter(|d| !d.is_zero())
+        .ok_or_else(|| PyValueError::new_err(format!("{name} must be a positive number")))
+}
+
+/// Sleeps for `delay`, waking up regularly to handle signals like
+/// KeyboardInterrupt. Returns false if the deadline passes first.
+fn sleep_until(
+    py: Python<'_>,
+    delay: time::Duration,
+    deadline: Option<time::Instant>,
+) -> PyResult<bool> {
+    const TICK: time::Duration = time::Duration::from_millis(200);
+
+    let wake = time::Instant::now() + delay;
+    let until = deadline.map_or(wake, |d| d.min(wake));
+    loop {
+        let now = time::Instant::now();
+        if now >= until {
+            break;
+        }
+
+        py.detach(|| std::thread::sleep((until - now).min(TICK)));
+        py.check_signals()?;
+    }
+
+    Ok(deadline.is_none_or(|d| wake < d))
+}
+
+/// The version of the installed bauplan package, which may differ from the
+/// crate version in format (e.g. "1.0.0a1" for "1.0.0-alpha.1"). Falls back
+/// to the crate version if the package metadata can't be read.
+fn installed_version(py: Python<'_>) -> String {
+    py.import("importlib.metadata")
+        .and_then(|m| m.call_method1("version", ("bauplan",)))
+        .and_then(|v| v.extract())
+        .unwrap_or_else(|_| env!("CARGO_PKG_VERSION").to_owned())
+}
+
+impl Client {
+    /// Returns `r`, or the active branch if it's `None`.
+    pub(crate) fn ref_or_active(&self, r: Option<refs::RefArg>) -> refs::RefArg {
+        r.unwrap_or_else(|| refs::RefArg(self.active_branch()))
+    }
+
+    /// Returns the per-call `request_timeout`, or the client's if it's
+    /// `None`.
+    pub(crate) fn request_timeout(&self, secs: Option<f64>) -> PyResult<time::Duration> {
+        match secs {
+            Some(secs) => positive_secs("request_timeout", secs),
+            None => Ok(self.request_timeout),
+        }
+    }
+
+    /// Returns `branch`, or the active branch if it's `None`.
+    pub(crate) fn branch_or_active(&self, branch: Option<impl Into<String>>) -> String {
+        branch.map_or_else(|| self.active_branch(), Into::into)
+    }
+
+    /// Returns `namespace`, or the profile's `default_namespace` if it's
+    /// `None`.
+    pub(crate) fn namespace_or_default(
+        &self,
+        namespace: Option<impl Into<String>>,
+    ) -> Option<String> {
+        namespace
+            .map(Into::into)
+            .or_else(|| self.profile.default_namespace.clone())
+    }
+
+    /// Returns the HTTP agent for catalog requests.
+    pub(crate) fn agent(&self) -> Result<ureq::Agent, ClientError> {
+        let conn = self.conn.lock().unwrap();
+        let conn = conn.as_ref().ok_or(ClientError::Closed)?;
+        Ok(conn.agent.clone())
+    }
+
+    /// Returns the gRPC client, reconnecting first if the process forked
+    /// since it was created.
+    pub(crate) fn grpc(&self) -> PyResult<grpc::Client> {
+        self.with_connection(|conn| conn.grpc.clone())
+    }
+
+    /// Returns the endpoint used to fetch query results over longbow,
+    /// rebuilding it first if the process forked since it was created.
+    pub(crate) fn longbow_endpoint(&self) -> PyResult<LongbowEndpoint> {
+        self.with_connection(|conn| conn.longbow_endpoint.clone())
+    }
+
+    fn with_connection<T>(&self, f: impl FnOnce(&Connection) -> T) -> PyResult<T> {
+        let mut conn = self.conn.lock().unwrap();
+        let conn = conn.as_mut().ok_or(ClientError::Closed)?;
+        if conn.fork_generation != fork_generation() {
+            debug!("process forked, reconnecting");
+            let agent = conn.agent.clone();
+            *conn = Connection::new(&self.profile, self.client_timeout, agent).map_err(|err| {
+                error!(%err, "failed to reconnect after fork");
+                used_after_fork()
+            })?;
+        }
+
+        Ok(f(conn))
+    }
+
+    /// Checks that `method`, which blocks until a job finishes, isn't being
+    /// called from a running asyncio event loop.
+    pub(crate) fn check_blocking(&self, py: Python<'_>, method: &str) -> PyResult<()> {
+        let asyncio = py.import("asyncio")?;
+        if asyncio.call_method0("get_running_loop").is_err() {
+            return Ok(());
+        }
+
+        let msg = format!(
+            "Client.{method}() blocks until the job finishes, which would stall the running \
+            asyncio event loop. Run it in a worker thread instead, e.g. with \
+            `await asyncio.to_thread(client.{method}, ...)` or `loop.run_in_executor(...)`, \
+            or pass `allow_blocking=True` to `bauplan.Client()` to allow it."
+        );
+
+        if !self.allow_blocking {
+            return Err(PyRuntimeError::new_err(msg));
+        }
+
+        let category = py.get_type::<PyRuntimeWarning>();
+        let msg = CString::new(msg).expect("message has no nul bytes");
+        PyErr::warn(py, category.as_any(), &msg, 1)
+    }
 }
 
 #[allow(clippy::result_large_err)]
@@ -248,13 +639,111 @@ fn roundtrip<T: ApiRequest>(
 where
     T::Response: Send,
 {
-    let req = req.into_request(profile)?;
+    roundtrip_timeout(py, req, profile, agent, None)
+}
+
+/// Like [roundtrip], with a deadline for this request only, instead of the
+/// client's `request_timeout`.
+#[allow(clippy::result_large_err)]
+fn roundtrip_timeout<T: ApiRequest>(
+    py: Python<'_>,
+    req: T,
+    profile: &Profile,
+    agent: &ureq::Agent,
+    timeout: Option<time::Duration>,
+) -> Result<T::Response, ClientError>
+where
+    T::Response: Send,
+{
+    req.validate()?;
+    let req = with_timeout(agent, req.into_request(profile)?, timeout);
     py.detach(|| {
-        let resp = agent.run(req)?.map(ureq::Body::into_reader);
+        let resp = send(req, profile, agent)?;
         Ok(<T::Response as ApiResponse>::from_response(resp)?)
     })
 }
 
+/// Like [roundtrip], for use from threads that don't hold the GIL. The
+/// optional timeout applies to this request only.
+#[allow(clippy::result_large_err)]
+fn roundtrip_detached<T: ApiRequest>(
+    req: T,
+    profile: &Profile,
+    agent: &ureq::Agent,
+    timeout: Option<time::Duration>,
+) -> Result<T::Response, ClientError> {
+    req.validate()?;
+    let req = with_timeout(agent, req.into_request(profile)?, timeout);
+    let resp = send(req, profile, agent)?;
+    Ok(<T::Response as ApiResponse>::from_response(resp)?)
+}
+
+/// Like [roundtrip_timeout], for merges and reverts, which are retried if
+/// the response is lost. See [`crate::send_operation`].
+fn roundtrip_operation<T: OperationRequest + Ungil>(
+    py: Python<'_>,
+    req: T,
+    profile: &Profile,
+    agent: &ureq::Agent,
+    timeout: Option<time::Duration>,
+) -> Result<CatalogRef, ClientError> {
+    py.detach(|| {
+        let res = crate::send_operation(
+            req,
+            |req| roundtrip_detached(req, profile, agent, timeout),
+            |req| roundtrip_detached(req, profile, agent, timeout),
+            ClientError::response_lost,
+            std::thread::sleep,
+        )?;
+
+        Ok(res.into_ref())
+    })
+}
+
+/// Overrides the agent's timeout for a single request.
+fn with_timeout(
+    agent: &ureq::Agent,
+    req: http::Request<String>,
+    timeout: Option<time::Duration>,
+) -> http::Request<String> {
+    if timeout.is_some() {
+        agent.configure_request(req).timeout_global(timeout).build()
+    } else {
+        req
+    }
+}
+
+/// Sends a request, retrying rate limits within the profile's budget.
+#[allow(clippy::result_large_err)]
+fn send(
+    req: http::Request<String>,
+    profile: &Profile,
+    agent: &ureq::Agent,
+) -> Result<http::Response<ureq::BodyReader<'static>>, ClientError> {
+    let operation = format!("{} {}", req.method(), req.uri().path());
+    let start = time::Instant::now();
+    let budget = RetryBudget::new(profile.rate_limit_budget);
+    let resp = crate::send_with_backoff(
+        &budget,
+        req,
+        |req| Ok::<_, ClientError>(agent.run(req)?.map(ureq::Body::into_reader)),
+        std::thread::sleep,
+    )
+    .map_err(|e| match e {
+        ClientError::Transport(ureq::Error::Timeout(_)) => ClientError::Timeout {
+            operation,
+            elapsed: start.elapsed(),
+        },
+        e => e,
+    })?;
+
+    if let Some(summary) = budget.summary() {
+        warn!("{summary}");
+    }
+
+    Ok(resp)
+}
+
 fn optional_on_off<'a>(name: &'static str, v: Option<&'a str>) -> PyResult<Option<&'a str>> {
     match v {
         None | Some("on") | Some("off") => Ok(v),
@@ -290,6 +779,22 @@ mod _internal {
     #[pymodule_export]
     use super::info::PyUserInfo as UserInfo;
 
+    // Query
+    #[pymodule_export]
+    use super::query::QueryFileResult;
+    #[pymodule_export]
+    use super::query::QueryResultsEndpoint;
+    #[pymodule_export]
+    use super::query::fetch_query_results;
+
+    // Write
+    #[pymodule_export]
+    use super::write::WriteTableResult;
+
+    // Logging
+    #[pymodule_export]
+    use super::logging::enable_logging;
+
     // Register submodules in sys.modules so that
     // `from bauplan._internal.schema import X` works.
     #[pymodule_init]
@@ -302,6 +807,18 @@ mod _internal {
 
         m.add("__version__", env!("CARGO_PKG_VERSION"))?;
 
+        // Rebuild the tokio runtime and connections in forked children.
+        // Windows doesn't fork, and has no `register_at_fork`.
+        let os = m.py().import("os")?;
+        if os.hasattr("register_at_fork")? {
+            let kwargs = pyo3::types::PyDict::new(m.py());
+            kwargs.set_item(
+                "after_in_child",
+                wrap_pyfunction!(super::after_fork_in_child, m)?,
+            )?;
+            os.call_method("register_at_fork", (), Some(&kwargs))?;
+        }
+
         let sys = m.py().import("sys")?;
         let modules = sys.getattr("modules")?;
         let name = m.name()?;
@@ -317,22 +834,27 @@ mod _internal {
 
 /// Releases the GIL and does some async work.
 pub(crate) fn detach<T: Ungil>(py: Python<'_>, f: impl Future<Output = T> + Send) -> T {
-    py.detach(|| rt().block_on(f))
+    let res = py.detach(|| rt().block_on(f));
+    logging::flush(py);
+    res
 }
 
+/// Returns the tokio runtime, creating a new one in a forked child: the
+/// parent's runtime threads don't exist there
+/// (<https://github.com/tokio-rs/tokio/issues/4301>). The old runtime is
+/// leaked, since shutting it down would wait on those threads.
 pub(crate) fn rt() -> &'static Runtime {
-    static TOKIO_RT: OnceLock<Runtime> = OnceLock::new();
-    static PID: OnceLock<u32> = OnceLock::new();
-    let pid = std::process::id();
-    let runtime_pid = *PID.get_or_init(|| pid);
-    if pid != runtime_pid {
-        panic!(
-            "Forked process detected - current PID is {pid} but the tokio runtime was created by {runtime_pid}. The tokio \
-            runtime does not support forked processes https://github.com/tokio-rs/tokio/issues/4301. If you are \
-            seeing this message while using Python multithreading make sure to use the `spawn` or `forkserver` \
-            mode.",
-        );
-    }
+    static TOKIO_RT: Mutex<Option<(u64, &'static Runtime)>> = Mutex::new(None);
 
-    TOKIO_RT.get_or_init(|| Runtime::new().expect("Failed to create a tokio runtime."))
+    let generation = fork_generation();
+    let mut current = TOKIO_RT.lock().unwrap();
+    match *current {
+        Some((g, rt)) if g == generation => rt,
+        _ => {
+            let rt = Runtime::new().expect("Failed to create a tokio runtime.");
+            let rt: &'static Runtime = Box::leak(Box::new(rt));
+            *current = Some((generation, rt));
+            rt
+        }
+    }
 }
@@ -1,21 +1,25 @@
 //! Branch operations.
 
-use pyo3::prelude::*;
+use pyo3::{exceptions::PyValueError, prelude::*};
 use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Instant;
+use tracing::{info, warn};
 
 use crate::{
-    ApiErrorKind, ApiRequest, CatalogRef,
+    ApiError, ApiErrorKind, ApiRequest, CatalogRef, Profile,
     branch::{
         Branch, CreateBranch, DeleteBranch, GetBranch, GetBranches, MergeBranch,
-        MergeCommitOptions, RenameBranch,
+        MergeCommitOptions, RenameBranch, WatchSchedule,
     },
     python::{
+        exceptions::BauplanError,
         paginate::PyPaginator,
         refs::{BranchArg, RefArg},
     },
 };
 
-use super::Client;
+use super::{Client, ClientError};
 
 #[pymethods]
 impl Client {
@@ -35,6 +39,7 @@ impl Client {
     ///     name: Filter the branches by name.
     ///     user: Filter the branches by user.
     ///     limit: Optional, max number of branches to get.
+    ///     request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
     /// Returns:
     ///     An iterator over `bauplan.schema.Branch` objects.
     #[pyo3(signature = (
@@ -42,6 +47,7 @@ impl Client {
         name: "str | None" = None,
         user: "str | None" = None,
         limit: "int | None" = None,
+        request_timeout: "float | None" = None,
     ) -> "typing.Iterator[Branch]")]
     fn get_branches(
         &self,
@@ -49,9 +55,11 @@ impl Client {
         name: Option<String>,
         user: Option<String>,
         limit: Option<usize>,
+        request_timeout: Option<f64>,
     ) -> PyResult<PyPaginator> {
+        let timeout = self.request_timeout(request_timeout)?;
         let profile = self.profile.clone();
-        let agent = self.agent.clone();
+        let agent = self.agent()?;
         PyPaginator::new(py, limit, move |py, token, limit| {
             let req = GetBranches {
                 filter_by_name: name.as_deref(),
@@ -59,7 +67,13 @@ impl Client {
             }
             .paginate(token, limit);
 
-            Ok(super::roundtrip(py, req, &profile, &agent)?)
+            Ok(super::roundtrip_timeout(
+                py,
+                req,
+                &profile,
+                &agent,
+                Some(timeout),
+            )?)
         })
     }
 
@@ -76,6 +90,7 @@ impl Client {
     ///
     /// Parameters:
     ///     branch: The name of the branch to retrieve.
+    ///     request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
     /// Returns:
     ///     A `bauplan.schema.Branch` object.
     ///
@@ -86,10 +101,23 @@ impl Client {
     ///     `bauplan.exceptions.ForbiddenError`: if the user does not have access to the branch.
     ///     `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
     ///     `ValueError`: if one or more parameters are invalid.
-    #[pyo3(signature = (branch: "str | Branch") -> "Branch")]
-    fn get_branch(&self, py: Python<'_>, branch: BranchArg) -> PyResult<Branch> {
-        let req = GetBranch { name: &branch.0 };
-        let b = super::roundtrip(py, req, &self.profile, &self.agent)?;
+    #[pyo3(signature = (
+        branch: "str | Branch",
+        *,
+        request_timeout: "float | None" = None,
+    ) -> "Branch")]
+    fn get_branch(
+        &self,
+        py: Python<'_>,
+        branch: BranchArg,
+        request_timeout: Option<f64>,
+    ) -> PyResult<Branch> {
+        let timeout = self.request_timeout(request_timeout)?;
+        let req = GetBranch {
+            name: &branch.0,
+            if_none_match: None,
+        };
+        let b = super::roundtrip_timeout(py, req, &self.profile, &self.agent()?, Some(timeout))?;
         Ok(b)
     }
 
@@ -116,9 +144,12 @@ impl Client {
     ///     `ValueError`: if one or more parameters are invalid.
     #[pyo3(signature = (branch: "str | Branch") -> "bool")]
     fn has_branch(&self, py: Python<'_>, branch: BranchArg) -> PyResult<bool> {
-        let req = GetBranch { name: &branch.0 };
+        let req = GetBranch {
+            name: &branch.0,
+            if_none_match: None,
+        };
 
-        match super::roundtrip(py, req, &self.profile, &self.agent) {
+        match super::roundtrip(py, req, &self.profile, &self.agent()?) {
             Ok(_) => Ok(true),
             Err(e)
                 if matches!(
@@ -133,6 +164,72 @@ impl Client {
         }
     }
 
+    /// Watch a branch, yielding its new head hash each time it moves.
+    ///
+    /// The branch is polled with conditional requests, so a poll that finds
+    /// the head unchanged is cheap. Polls that fail with a transient error
+    /// (a network failure, a rate limit or a server error) are retried with
+    /// backoff; other errors are raised from the iterator.
+    ///
+    /// ```python
+    /// import bauplan
+    /// client = bauplan.Client()
+    ///
+    /// for head in client.watch_ref('main', poll_interval=30):
+    ///     print(f"main moved to {head}")
+    /// ```
+    ///
+    /// Parameters:
+    ///     ref: The branch to watch.
+    ///     poll_interval: Seconds between polls. Each delay is jittered by up to 20%.
+    ///     timeout: Optional, seconds after which the iterator stops. By default, it never stops.
+    /// Returns:
+    ///     An iterator over the new head hashes of the branch.
+    ///
+    /// Raises:
+    ///     `bauplan.exceptions.BranchNotFoundError`: if the branch does not exist, or is deleted while being watched.
+    ///     `bauplan.exceptions.NotABranchRefError`: if the object is not a branch.
+    ///     `bauplan.exceptions.ForbiddenError`: if the user does not have access to the branch.
+    ///     `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+    ///     `ValueError`: if one or more parameters are invalid.
+    #[pyo3(signature = (
+        r#ref: "str | Branch",
+        *,
+        poll_interval: "float" = 15.0,
+        timeout: "float | None" = None,
+    ) -> "typing.Iterator[str]")]
+    fn watch_ref(
+        &self,
+        py: Python<'_>,
+        r#ref: BranchArg,
+        poll_interval: f64,
+        timeout: Option<f64>,
+    ) -> PyResult<RefWatcher> {
+        let interval = super::positive_secs("poll_interval", poll_interval)?;
+        let deadline = timeout
+            .map(|t| super::positive_secs("timeout", t))
+            .transpose()?
+            .map(|t| Instant::now() + t);
+
+        let name = r#ref.0;
+        let req = GetBranch {
+            name: &name,
+            if_none_match: None,
+        };
+
+        let head = super::roundtrip(py, req, &self.profile, &self.agent()?)?.hash;
+        Ok(RefWatcher {
+            inner: Mutex::new(WatchState {
+                name,
+                head,
+                schedule: WatchSchedule::new(interval),
+                deadline,
+                profile: self.profile.clone(),
+                agent: self.agent()?,
+            }),
+        })
+    }
+
     /// Create a new branch at a given ref.
     /// The branch name should follow the convention of `username.branch_name`,
     /// otherwise non-admin users won't be able to complete the operation.
@@ -156,8 +253,9 @@ impl Client {
     ///
     /// Parameters:
     ///     branch: The name of the new branch.
-    ///     from_ref: The name of the base branch; either a branch like "main" or ref like "main@[sha]".
+    ///     from_ref: The name of the base branch; either a branch like "main" or ref like "main@[sha]". Defaults to the active branch.
     ///     if_not_exists: If set to `True`, the branch will not be created if it already exists.
+    ///     request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
     /// Returns:
     ///     The created `bauplan.schema.Branch` object.
     ///
@@ -170,34 +268,29 @@ impl Client {
     ///     `ValueError`: if one or more parameters are invalid.
     #[pyo3(signature = (
         branch: "str | Branch",
-        from_ref: "str | Ref",
+        from_ref: "str | Ref | None" = None,
         *,
         if_not_exists: "bool" = false,
+        request_timeout: "float | None" = None,
     ) -> "Branch")]
     fn create_branch(
         &self, py: Python<'_>,
         branch: BranchArg,
-        from_ref: RefArg,
+        from_ref: Option<RefArg>,
         if_not_exists: bool,
+        request_timeout: Option<f64>,
     ) -> PyResult<Branch> {
+        let timeout = self.request_timeout(request_timeout)?;
         let req = CreateBranch {
             name: &branch.0,
-            from_ref: &from_ref.0,
+            from_ref: &self.ref_or_active(from_ref).resolve(py, self)?,
         };
 
-        match super::roundtrip(py, req, &self.profile, &self.agent) {
+        match super::roundtrip_timeout(py, req, &self.profile, &self.agent()?, Some(timeout)) {
             Ok(b) => Ok(b),
             Err(e) => {
-                if let Some(ApiErrorKind::BranchExists {
-                    catalog_ref: CatalogRef::Branch { name, hash },
-                    ..
-                }) = e.kind()
-                    && if_not_exists
-                {
-                    Ok(Branch {
-                        name: name.clone(),
-                        hash: hash.clone(),
-                    })
+                if if_not_exists && let Some(b) = e.api().and_then(CreateBranch::existing) {
+                    Ok(b)
                 } else {
                     Err(e.into())
                 }
@@ -205,6 +298,140 @@ impl Client {
         }
     }
 
+    /// Create a branch if it doesn't exist yet, and return it either way.
+    ///
+    /// Only a `bauplan.exceptions.BranchExistsError` counts as the branch
+    /// already existing; anything else is raised, as with `create_branch`.
+    /// Whether the branch was created is logged to the `bauplan` logger.
+    ///
+    /// Upon failure, raises `bauplan.exceptions.BauplanError`
+    ///
+    /// ```python
+    /// import bauplan
+    /// client = bauplan.Client()
+    ///
+    /// branch = client.ensure_branch('my_branch_name', from_ref='main')
+    /// ```
+    ///
+    /// Parameters:
+    ///     branch: The name of the branch.
+    ///     from_ref: The ref to create the branch from, if it doesn't exist. Defaults to the active branch.
+    ///     request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
+    /// Returns:
+    ///     The created or existing `bauplan.schema.Branch` object. An existing branch is returned at its current head, which may not be `from_ref`.
+    ///
+    /// Raises:
+    ///     `bauplan.exceptions.CreateBranchForbiddenError`: if the user does not have access to create the branch.
+    ///     `bauplan.exceptions.RefNotFoundError`: if the source ref does not exist.
+    ///     `bauplan.exceptions.InvalidRefError`: if the ref format is invalid.
+    ///     `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+    ///     `ValueError`: if one or more parameters are invalid.
+    #[pyo3(signature = (
+        branch: "str | Branch",
+        from_ref: "str | Ref | None" = None,
+        *,
+        request_timeout: "float | None" = None,
+    ) -> "Branch")]
+    fn ensure_branch(
+        &self,
+        py: Python<'_>,
+        branch: BranchArg,
+        from_ref: Option<RefArg>,
+        request_timeout: Option<f64>,
+    ) -> PyResult<Branch> {
+        let timeout = self.request_timeout(request_timeout)?;
+        let req = CreateBranch {
+            name: &branch.0,
+            from_ref: &self.ref_or_active(from_ref).resolve(py, self)?,
+        };
+
+        match super::roundtrip_timeout(py, req, &self.profile, &self.agent()?, Some(timeout)) {
+            Ok(b) => {
+                info!(branch = b.name, hash = b.hash, "created branch");
+                Ok(b)
+            }
+            Err(e) => match e.api().and_then(CreateBranch::existing) {
+                Some(b) => {
+                    info!(branch = b.name, hash = b.hash, "branch already exists");
+                    Ok(b)
+                }
+                None => Err(e.into()),
+            },
+        }
+    }
+
+    /// Switch the active branch, which methods use when their ref or branch
+    /// argument is omitted.
+    ///
+    /// The branch must exist, unless `create` is set. By default only this
+    /// client is affected; with `persist`, the branch is also saved as the
+    /// profile's `active_branch` in the config file, like `bauplan checkout`.
+    ///
+    /// Upon failure, raises `bauplan.exceptions.BauplanError`
+    ///
+    /// ```python
+    /// import bauplan
+    /// client = bauplan.Client()
+    ///
+    /// client.checkout('my_branch_name', create=True, from_ref='main')
+    /// table = client.get_table('my_table_name')  # read from my_branch_name
+    /// ```
+    ///
+    /// Parameters:
+    ///     branch: The name of the branch to switch to.
+    ///     create: If set to `True`, create the branch first if it doesn't exist.
+    ///     from_ref: The ref to create the branch from. Defaults to the active branch. Only valid with `create`.
+    ///     persist: If set to `True`, save the branch as the profile's active branch.
+    /// Returns:
+    ///     The `bauplan.schema.Branch` object switched to.
+    ///
+    /// Raises:
+    ///     `bauplan.exceptions.BranchNotFoundError`: if the branch does not exist.
+    ///     `bauplan.exceptions.NotABranchRefError`: if the object is not a branch.
+    ///     `bauplan.exceptions.RefNotFoundError`: if the source ref does not exist.
+    ///     `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+    ///     `ValueError`: if one or more parameters are invalid.
+    #[pyo3(signature = (
+        branch: "str | Branch",
+        *,
+        create: "bool" = false,
+        from_ref: "str | Ref | None" = None,
+        persist: "bool" = false,
+    ) -> "Branch")]
+    fn checkout(
+        &self,
+        py: Python<'_>,
+        branch: BranchArg,
+        create: bool,
+        from_ref: Option<RefArg>,
+        persist: bool,
+    ) -> PyResult<Branch> {
+        if from_ref.is_some() && !create {
+            return Err(PyValueError::new_err("from_ref requires create=True"));
+        }
+
+        let branch = if create {
+            self.create_branch(py, branch, from_ref, true)?
+        } else {
+            self.get_branch(py, branch)?
+        };
+
+        if persist {
+            let profile = &self.profile;
+            py.detach(|| {
+                crate::yaml::edit_locked(&profile.config_path, |doc| {
+                    let mut m = crate::yaml::mapping_at_path(doc, &["profiles", &profile.name])?;
+                    crate::yaml::upsert_str(&mut m, "active_branch", &branch.name);
+                    Ok(())
+                })
+            })
+            .map_err(|e| BauplanError::new_err(e.to_string()))?;
+        }
+
+        *self.active_branch.lock().unwrap() = branch.name.clone();
+        Ok(branch)
+    }
+
     /// Rename an existing branch.
     /// The branch name should follow the convention of "username.branch_name",
     /// otherwise non-admin users won't be able to complete the operation.
@@ -245,7 +472,7 @@ impl Client {
             new_name: &new_branch.0,
         };
 
-        let b = super::roundtrip(py, req, &self.profile, &self.agent)?;
+        let b = super::roundtrip(py, req, &self.profile, &self.agent()?)?;
         Ok(b)
     }
 
@@ -265,10 +492,11 @@ impl Client {
     ///
     /// Parameters:
     ///     source_ref: The name of the merge source; either a branch like "main" or ref like "main@[sha]".
-    ///     into_branch: The name of the merge target.
+    ///     into_branch: The name of the merge target. Defaults to the active branch.
     ///     commit_message: Optional, the commit message.
     ///     commit_body: Optional, the commit body.
     ///     commit_properties: Optional, a list of properties to attach to the merge.
+    ///     request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
     /// Returns:
     ///     The `bauplan.schema.Branch` where the merge was made.
     ///
@@ -284,37 +512,45 @@ impl Client {
     ///     `ValueError`: if one or more parameters are invalid.
     #[pyo3(signature = (
         source_ref: "str | Ref",
-        into_branch: "str | Branch",
+        into_branch: "str | Branch | None" = None,
         *,
         commit_message: "str | None" = None,
         commit_body: "str | None" = None,
         commit_properties: "dict[str, str] | None" = None,
+        request_timeout: "float | None" = None,
     ) -> "Branch")]
+    #[allow(clippy::too_many_arguments)]
     fn merge_branch(
         &self, py: Python<'_>,
         source_ref: RefArg,
-        into_branch: BranchArg,
+        into_branch: Option<BranchArg>,
         commit_message: Option<&str>,
         commit_body: Option<&str>,
         commit_properties: Option<BTreeMap<String, String>>,
+        request_timeout: Option<f64>,
     ) -> PyResult<CatalogRef> {
+        let timeout = self.request_timeout(request_timeout)?;
         let commit_properties = commit_properties.unwrap_or_default();
         let properties = commit_properties
             .iter()
             .map(|(k, v)| (k.as_str(), v.as_str()))
             .collect();
 
+        let operation_id = crate::new_operation_id();
         let req = MergeBranch {
-            source_ref: &source_ref.0,
-            into_branch: &into_branch.0,
+            source_ref: &source_ref.resolve(py, self)?,
+            into_branch: &self.branch_or_active(into_branch.map(|b| b.0)),
             commit: MergeCommitOptions {
                 commit_message,
                 commit_body,
                 commit_properties: properties,
             },
+            operation_id: Some(&operation_id),
         };
 
-        Ok(super::roundtrip(py, req, &self.profile, &self.agent)?)
+        let resp =
+            super::roundtrip_operation(py, req, &self.profile, &self.agent()?, Some(timeout))?;
+        Ok(resp)
     }
 
     /// Delete a branch.
@@ -332,6 +568,7 @@ impl Client {
     /// Parameters:
     ///     branch: The name of the branch to delete.
     ///     if_exists: If set to `True`, the branch will not raise an error if it does not exist.
+    ///     request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
     /// Returns:
     ///     A boolean for if the branch was deleted.
     ///
@@ -348,12 +585,21 @@ impl Client {
         branch: "str | Branch",
         *,
         if_exists: "bool" = false,
+        request_timeout: "float | None" = None,
     ) -> "bool")]
-    fn delete_branch(&self, py: Python<'_>, branch: BranchArg, if_exists: bool) -> PyResult<bool> {
+    fn delete_branch(
+        &self,
+        py: Python<'_>,
+        branch: BranchArg,
+        if_exists: bool,
+        request_timeout: Option<f64>,
+    ) -> PyResult<bool> {
+        let timeout = self.request_timeout(request_timeout)?;
         let req = DeleteBranch { name: &branch.0 };
 
-        if let Err(e) = super::roundtrip(py, req, &self.profile, &self.agent) {
-            if if_exists && matches!(e.kind(), Some(ApiErrorKind::BranchNotFound { .. })) {
+        let res = super::roundtrip_timeout(py, req, &self.profile, &self.agent()?, Some(timeout));
+        if let Err(e) = res {
+            if if_exists && e.api().is_some_and(DeleteBranch::missing) {
                 return Ok(false);
             } else {
                 return Err(e.into());
@@ -363,3 +609,60 @@ impl Client {
         Ok(true)
     }
 }
+
+/// The iterator returned by `Client.watch_ref`.
+#[pyclass]
+pub(crate) struct RefWatcher {
+    inner: Mutex<WatchState>,
+}
+
+struct WatchState {
+    name: String,
+    head: String,
+    schedule: WatchSchedule,
+    deadline: Option<Instant>,
+    profile: Profile,
+    agent: ureq::Agent,
+}
+
+#[pymethods]
+impl RefWatcher {
+    fn __iter__(this: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        this
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<String>> {
+        let state = &mut *self.inner.lock().unwrap();
+
+        let mut delay = state.schedule.succeeded();
+        loop {
+            if !super::sleep_until(py, delay, state.deadline)? {
+                return Ok(None);
+            }
+
+            let req = GetBranch {
+                name: &state.name,
+                if_none_match: Some(&state.head),
+            };
+
+            match super::roundtrip(py, req, &state.profile, &state.agent) {
+                // The server may not support conditional requests.
+                Ok(branch) if branch.hash != state.head => {
+                    state.head = branch.hash.clone();
+                    return Ok(Some(branch.hash));
+                }
+                Ok(_) | Err(ClientError::Api(ApiError::NotModified)) => {
+                    delay = state.schedule.succeeded();
+                }
+                Err(e) if !e.is_transient() => return Err(e.into()),
+                Err(e) => {
+                    delay = state.schedule.failed();
+                    warn!(
+                        branch = state.name.as_str(),
+                        "failed to poll branch, retrying in {delay:?}: {e}"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -42,7 +42,7 @@ impl Client {
     /// ```
     ///
     /// Parameters:
-    ///     ref: The ref or branch to get the commits from.
+    ///     ref: The ref or branch to get the commits from. Defaults to the active branch.
     ///     filter_by_message: Optional, filter the commits by message (can be a string or a regex like '^abc.*$')
     ///     filter_by_author_username: Optional, filter the commits by author username (can be a string or a regex like '^abc.*$')
     ///     filter_by_author_name: Optional, filter the commits by author name (can be a string or a regex like '^abc.*$')
@@ -63,7 +63,7 @@ impl Client {
     ///     `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
     ///     `ValueError`: if one or more parameters are invalid.
     #[pyo3(signature = (
-        r#ref: "str | Ref",
+        r#ref: "str | Ref | None" = None,
         *,
         filter_by_message: "str | None" = None,
         filter_by_author_username: "str | None" = None,
@@ -81,7 +81,7 @@ impl Client {
     fn get_commits(
         &self,
         py: Python<'_>,
-        r#ref: RefArg,
+        r#ref: Option<RefArg>,
         filter_by_message: Option<String>,
         filter_by_author_username: Option<String>,
         filter_by_author_name: Option<String>,
@@ -95,8 +95,8 @@ impl Client {
         limit: Option<usize>,
     ) -> PyResult<PyPaginator> {
         let profile = self.profile.clone();
-        let agent = self.agent.clone();
-        let r#ref = r#ref.0;
+        let agent = self.agent()?;
+        let r#ref = self.ref_or_active(r#ref).resolve(py, self)?;
         let filter_by_authored_date = filter_by_authored_date.map(|a| a.0);
         let filter_by_authored_date_start_at = filter_by_authored_date_start_at.map(|a| a.0);
         let filter_by_authored_date_end_at = filter_by_authored_date_end_at.map(|a| a.0);
@@ -4,6 +4,7 @@ use pyo3::prelude::*;
 
 use crate::{
     api::{ApiError, ApiErrorKind},
+    branch::MergeConflictTable,
     python::ClientError,
 };
 
@@ -13,6 +14,8 @@ pub mod exceptions {
 
     #[pymodule_export]
     use crate::api::ApiErrorKind;
+    #[pymodule_export]
+    use crate::branch::MergeConflictTable;
 
     // Re-export exception types into the module.
     #[pymodule_export]
@@ -140,6 +143,12 @@ pub mod exceptions {
     #[pymodule_export]
     use super::NoResultsFoundError;
     #[pymodule_export]
+    use super::RequestTimeoutError;
+    #[pymodule_export]
+    use super::RowLimitWarning;
+    #[pymodule_export]
+    use super::SchemaMismatchError;
+    #[pymodule_export]
     use super::TableCreatePlanApplyStatusError;
     #[pymodule_export]
     use super::TableCreatePlanError;
@@ -216,6 +225,7 @@ impl From<ClientError> for PyErr {
     fn from(err: ClientError) -> Self {
         match err {
             ClientError::Api(api_error) => api_error.into_py_err(),
+            ClientError::Timeout { .. } => RequestTimeoutError::new_err(err.to_string()),
             _ => BauplanError::new_err(err.to_string()),
         }
     }
@@ -223,26 +233,32 @@ impl From<ClientError> for PyErr {
 
 impl ApiError {
     pub(crate) fn into_py_err(self) -> PyErr {
-        let (code, kind, message) = match self {
-            ApiError::ErrorResponse {
-                status,
-                kind,
+        if let ApiError::RateLimited {
+            error,
+            limit,
+            retries,
+            waited,
+        } = self
+        {
+            let (code, kind, message) = error.into_parts();
+            let type_str = kind.as_ref().map(|k| k.to_string()).unwrap_or_default();
+            let message = format!("{message} ({})", crate::api::retry_summary(retries, waited));
+
+            return PyErr::new::<TooManyRequestsError, _>((
+                code,
+                type_str,
                 message,
-            } => (
-                status.as_u16(),
-                Some(kind),
-                message.unwrap_or(status.to_string()),
-            ),
-            ApiError::Other {
-                status, message, ..
-            } => (status.as_u16(), None, message.unwrap_or(status.to_string())),
-            ApiError::InvalidResponse(status) => (
-                status.as_u16(),
-                None,
-                format!("Invalid response ({status})"),
-            ),
-        };
+                kind,
+                retries,
+                waited.as_secs_f64(),
+                limit.retry_after.map(|d| d.as_secs_f64()),
+                limit.limit,
+                limit.remaining,
+                limit.reset.map(|d| d.as_secs_f64()),
+            ));
+        }
 
+        let (code, kind, message) = self.into_parts();
         let type_str = kind.as_ref().map(|k| k.to_string()).unwrap_or_default();
         let args = (code, type_str, message, kind);
 
@@ -307,7 +323,10 @@ impl ApiError {
                 ApiErrorKind::BranchHeadChanged { .. } => {
                     PyErr::new::<BranchHeadChangedError, _>(args)
                 }
-                ApiErrorKind::MergeConflict { .. } => PyErr::new::<MergeConflictError, _>(args),
+                ApiErrorKind::MergeConflict { conflicts, .. } => {
+                    let conflicts = conflicts.clone();
+                    merge_conflict_err(args, conflicts)
+                }
                 ApiErrorKind::NamespaceExists { .. } => PyErr::new::<NamespaceExistsError, _>(args),
                 ApiErrorKind::NamespaceIsNotEmpty { .. } => {
                     PyErr::new::<NamespaceIsNotEmptyError, _>(args)
@@ -341,6 +360,67 @@ impl ApiError {
             }
         }
     }
+
+    /// Like [`ApiError::into_py_err`], for requests that change a table's
+    /// metadata, like registering it with the iceberg catalog or changing
+    /// its schema. Their errors don't always carry a kind the API
+    /// recognizes; rejected requests are raised as `InvalidDataError`, and
+    /// conflicts, like a concurrent schema change, as `UpdateConflictError`.
+    pub(crate) fn table_update_into_py_err(self) -> PyErr {
+        let status = self.status().as_u16();
+        if self.kind().is_some()
+            || matches!(self, ApiError::RateLimited { .. })
+            || !matches!(status, 400 | 409)
+        {
+            return self.into_py_err();
+        }
+
+        let (code, kind, message) = self.into_parts();
+        let args = (code, String::new(), message, kind);
+        if code == 400 {
+            PyErr::new::<InvalidDataError, _>(args)
+        } else {
+            PyErr::new::<UpdateConflictError, _>(args)
+        }
+    }
+
+    /// The status code, kind and message to raise the error with.
+    fn into_parts(self) -> (u16, Option<ApiErrorKind>, String) {
+        match self {
+            ApiError::ErrorResponse {
+                status,
+                kind,
+                message,
+            } => (
+                status.as_u16(),
+                Some(kind),
+                message.unwrap_or(status.to_string()),
+            ),
+            ApiError::Other {
+                status, message, ..
+            } => (status.as_u16(), None, message.unwrap_or(status.to_string())),
+            ApiError::InvalidResponse(status) => (
+                status.as_u16(),
+                None,
+                format!("Invalid response ({status})"),
+            ),
+            ApiError::NotModified => (304, None, "Not modified".to_owned()),
+            ApiError::RateLimited { error, .. } => error.into_parts(),
+        }
+    }
+}
+
+/// Builds a `MergeConflictError`, with the conflicting tables set as its
+/// `conflicts` attribute.
+fn merge_conflict_err(
+    args: (u16, String, String, Option<ApiErrorKind>),
+    conflicts: Vec<MergeConflictTable>,
+) -> PyErr {
+    let err = PyErr::new::<MergeConflictError, _>(args);
+    Python::attach(|py| {
+        let res = err.value(py).setattr("conflicts", conflicts);
+        res.map_or_else(|e| e, |()| err)
+    })
 }
 
 // 400 Bad Request
@@ -608,12 +688,70 @@ pyo3::create_exception!(
 );
 
 // 429 Too Many Requests
-pyo3::create_exception!(
-    bauplan.exceptions,
-    TooManyRequestsError,
-    BauplanHTTPError,
-    "Raised on an HTTP 429 response from the API."
-);
+/// Raised on an HTTP 429 response from the API, once the client has spent its
+/// `rate_limit_budget` retrying.
+#[pyclass(extends=BauplanHTTPError, module="bauplan.exceptions", skip_from_py_object)]
+pub(crate) struct TooManyRequestsError {
+    /// The number of times requests were retried.
+    #[pyo3(get)]
+    retries: u32,
+    /// The total number of seconds spent waiting to retry.
+    #[pyo3(get)]
+    waited: f64,
+    /// The number of seconds the API asked the client to wait, if it said.
+    #[pyo3(get)]
+    retry_after: Option<f64>,
+    /// The number of requests allowed per window, if the API said.
+    #[pyo3(get)]
+    limit: Option<u64>,
+    /// The number of requests left in the window, if the API said.
+    #[pyo3(get)]
+    remaining: Option<u64>,
+    /// The number of seconds until the window resets, if the API said.
+    #[pyo3(get)]
+    reset: Option<f64>,
+}
+
+#[pymethods]
+impl TooManyRequestsError {
+    #[new]
+    #[pyo3(signature = (
+        code,
+        r#type,
+        message,
+        kind=None,
+        retries=0,
+        waited=0.0,
+        retry_after=None,
+        limit=None,
+        remaining=None,
+        reset=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        code: u16,
+        r#type: String,
+        message: String,
+        kind: Option<ApiErrorKind>,
+        retries: u32,
+        waited: f64,
+        retry_after: Option<f64>,
+        limit: Option<u64>,
+        remaining: Option<u64>,
+        reset: Option<f64>,
+    ) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(BauplanHTTPError::new(code, r#type, message, kind)).add_subclass(
+            Self {
+                retries,
+                waited,
+                retry_after,
+                limit,
+                remaining,
+                reset,
+            },
+        )
+    }
+}
 
 // 5xx Server Errors
 pyo3::create_exception!(
@@ -660,6 +798,24 @@ pyo3::create_exception!(
     BauplanError,
     "Raised when a query returns no results."
 );
+pyo3::create_exception!(
+    bauplan.exceptions,
+    RequestTimeoutError,
+    BauplanError,
+    "Raised when a catalog request doesn't complete within the client's `request_timeout`."
+);
+pyo3::create_exception!(
+    bauplan.exceptions,
+    RowLimitWarning,
+    pyo3::exceptions::PyUserWarning,
+    "Emitted when results are truncated to a default row limit."
+);
+pyo3::create_exception!(
+    bauplan.exceptions,
+    SchemaMismatchError,
+    BauplanError,
+    "Raised when query results don't match the expected schema."
+);
 pyo3::create_exception!(
     bauplan.exceptions,
     InvalidPlanError,
@@ -45,6 +45,10 @@ pub(crate) struct PyUserInfo {
     first_name: String,
     #[pyo3(get)]
     last_name: String,
+    #[pyo3(get)]
+    organization_id: Option<String>,
+    #[pyo3(get)]
+    organization_name: Option<String>,
 }
 
 #[pymethods]
@@ -68,6 +72,12 @@ impl PyUserInfo {
         format!("{} {}", self.first_name, self.last_name)
     }
 
+    /// The prefix of the user's branches, e.g. `alice.`.
+    #[getter]
+    fn branch_prefix(&self) -> String {
+        format!("{}.", self.username)
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "UserInfo(username={:?}, name={:?})",
@@ -122,6 +132,8 @@ impl From<GetBauplanInfoResponse> for PyInfoState {
             username: u.username,
             first_name: u.first_name,
             last_name: u.last_name,
+            organization_id: organization.as_ref().map(|o| o.id.clone()),
+            organization_name: organization.as_ref().map(|o| o.name.clone()),
         });
 
         let runners: Vec<PyRunnerNodeInfo> = resp
@@ -170,9 +182,31 @@ impl Client {
                 .unwrap_or(self.client_timeout),
         );
 
-        let info = detach(py, self.grpc.clone().get_bauplan_info(request))
+        let info = detach(py, self.grpc()?.get_bauplan_info(request))
             .map_err(|e| BauplanError::new_err(e.to_string()))?;
 
         Ok(info.into_inner().into())
     }
+
+    /// Fetch the user and organization that the API key belongs to.
+    ///
+    /// ```python
+    /// import bauplan
+    /// client = bauplan.Client()
+    ///
+    /// user = client.whoami()
+    /// print(f"{user.username} ({user.organization_name})")
+    /// ```
+    ///
+    /// Parameters:
+    ///     client_timeout: timeout in seconds.
+    ///
+    /// Returns:
+    ///     A `bauplan.UserInfo` object.
+    #[pyo3(signature = (*, client_timeout: "int | None" = None) -> "UserInfo")]
+    fn whoami(&self, py: Python<'_>, client_timeout: Option<u64>) -> PyResult<PyUserInfo> {
+        self.info(py, client_timeout)?
+            .user
+            .ok_or_else(|| BauplanError::new_err("the server didn't report a user"))
+    }
 }
@@ -1,19 +1,40 @@
 //! Jobs operations.
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
 
 use chrono::{DateTime, Utc};
-use pyo3::{Borrowed, exceptions::PyValueError, prelude::*};
+use futures::{StreamExt as _, stream::BoxStream};
+use pyo3::{
+    Borrowed,
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyList, PyTuple},
+};
 use serde::Serialize;
 use tonic::Request;
+use tracing::warn;
 
 use crate::{
     PaginatedResponse,
+    branch::WatchSchedule,
+    events::{self, JobEvent, LogMessage},
     grpc::{
-        generated as commanderpb,
-        job::{Job, JobKind, JobState},
+        self, generated as commanderpb,
+        job::{
+            Job, JobKind, JobPageDedup, JobState, ModelStatus, ProjectFilter, sort_oldest_first,
+        },
+    },
+    python::{
+        detach, exceptions::BauplanError, fork_generation, paginate::PyPaginator, rt,
+        run::job_status_strings, used_after_fork,
     },
-    python::{detach, exceptions::BauplanError, paginate::PyPaginator},
 };
 
 use super::Client;
@@ -78,21 +99,7 @@ impl<'a, 'py> FromPyObject<'a, 'py> for JobStateListArg {
     type Error = PyErr;
 
     fn extract(ob: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
-        if let Ok(state) = ob.extract::<JobState>() {
-            Ok(Self(vec![state]))
-        } else if let Ok(s) = ob.extract::<&str>() {
-            let state: JobState = s.parse().map_err(|e: String| PyValueError::new_err(e))?;
-            Ok(Self(vec![state]))
-        } else if let Ok(states) = ob.extract::<Vec<JobState>>() {
-            Ok(Self(states))
-        } else if let Ok(strings) = ob.extract::<Vec<String>>() {
-            let states: Result<Vec<_>, String> = strings.iter().map(|s| s.parse()).collect();
-            Ok(Self(states.map_err(PyValueError::new_err)?))
-        } else {
-            Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                "expected str, JobState, list[str], or list[JobState]",
-            ))
-        }
+        extract_enum_list(&ob, "expected str, JobState, or a list of either").map(Self)
     }
 }
 
@@ -114,21 +121,36 @@ impl<'a, 'py> FromPyObject<'a, 'py> for JobKindListArg {
     type Error = PyErr;
 
     fn extract(ob: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
-        if let Ok(kind) = ob.extract::<JobKind>() {
-            Ok(Self(vec![kind]))
-        } else if let Ok(s) = ob.extract::<&str>() {
-            let kind: JobKind = s.parse().map_err(|e: String| PyValueError::new_err(e))?;
-            Ok(Self(vec![kind]))
-        } else if let Ok(kinds) = ob.extract::<Vec<JobKind>>() {
-            Ok(Self(kinds))
-        } else if let Ok(strings) = ob.extract::<Vec<String>>() {
-            let kinds: Result<Vec<_>, String> = strings.iter().map(|s| s.parse()).collect();
-            Ok(Self(kinds.map_err(PyValueError::new_err)?))
-        } else {
-            Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                "expected str, JobKind, list[str], or list[JobKind]",
-            ))
-        }
+        extract_enum_list(&ob, "expected str, JobKind, or a list of either").map(Self)
+    }
+}
+
+/// Extracts an enum member or a string, or a list or tuple mixing the two.
+/// Strings are parsed with the enum's `FromStr`, which accepts the same
+/// names as the CLI.
+fn extract_enum_list<T>(ob: &Bound<'_, PyAny>, expected: &str) -> PyResult<Vec<T>>
+where
+    T: for<'a, 'py> FromPyObject<'a, 'py> + FromStr<Err = String>,
+{
+    if ob.is_instance_of::<PyList>() || ob.is_instance_of::<PyTuple>() {
+        ob.try_iter()?
+            .map(|v| extract_enum(&v?, expected))
+            .collect()
+    } else {
+        Ok(vec![extract_enum(ob, expected)?])
+    }
+}
+
+fn extract_enum<T>(ob: &Bound<'_, PyAny>, expected: &str) -> PyResult<T>
+where
+    T: for<'a, 'py> FromPyObject<'a, 'py> + FromStr<Err = String>,
+{
+    if let Ok(v) = ob.extract::<T>() {
+        Ok(v)
+    } else if let Ok(s) = ob.extract::<&str>() {
+        s.parse().map_err(PyValueError::new_err)
+    } else {
+        Err(PyTypeError::new_err(expected.to_owned()))
     }
 }
 
@@ -142,33 +164,20 @@ pub(crate) enum JobLogStream {
     Stderr,
 }
 
-impl TryFrom<commanderpb::runtime_log_event::OutputStream> for JobLogStream {
+impl TryFrom<events::OutputStream> for JobLogStream {
     type Error = PyErr;
 
-    fn try_from(value: commanderpb::runtime_log_event::OutputStream) -> Result<Self, Self::Error> {
+    fn try_from(value: events::OutputStream) -> Result<Self, Self::Error> {
         match value {
-            commanderpb::runtime_log_event::OutputStream::Stdout => Ok(Self::Stdout),
-            commanderpb::runtime_log_event::OutputStream::Stderr => Ok(Self::Stderr),
-            commanderpb::runtime_log_event::OutputStream::Unspecified => {
+            events::OutputStream::Stdout => Ok(Self::Stdout),
+            events::OutputStream::Stderr => Ok(Self::Stderr),
+            events::OutputStream::Unspecified => {
                 Err(PyValueError::new_err("invalid OutputStream: UNSPECIFIED"))
             }
         }
     }
 }
 
-impl TryFrom<i32> for JobLogStream {
-    type Error = PyErr;
-
-    fn try_from(value: i32) -> Result<Self, Self::Error> {
-        match commanderpb::runtime_log_event::OutputStream::try_from(value) {
-            Ok(v) => v.try_into(),
-            Err(_) => Err(PyValueError::new_err(format!(
-                "invalid OutputStream: {value}"
-            ))),
-        }
-    }
-}
-
 /// The severity level of a log event.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[pyclass(
@@ -190,34 +199,23 @@ pub(crate) enum JobLogLevel {
     Trace,
 }
 
-impl TryFrom<commanderpb::runtime_log_event::LogLevel> for JobLogLevel {
+impl TryFrom<events::LogLevel> for JobLogLevel {
     type Error = PyErr;
 
-    fn try_from(value: commanderpb::runtime_log_event::LogLevel) -> Result<Self, PyErr> {
+    fn try_from(value: events::LogLevel) -> Result<Self, PyErr> {
         match value {
-            commanderpb::runtime_log_event::LogLevel::Error => Ok(Self::Error),
-            commanderpb::runtime_log_event::LogLevel::Warning => Ok(Self::Warn),
-            commanderpb::runtime_log_event::LogLevel::Debug => Ok(Self::Debug),
-            commanderpb::runtime_log_event::LogLevel::Info => Ok(Self::Info),
-            commanderpb::runtime_log_event::LogLevel::Trace => Ok(Self::Trace),
-            commanderpb::runtime_log_event::LogLevel::Unspecified => {
+            events::LogLevel::Error => Ok(Self::Error),
+            events::LogLevel::Warning => Ok(Self::Warn),
+            events::LogLevel::Debug => Ok(Self::Debug),
+            events::LogLevel::Info => Ok(Self::Info),
+            events::LogLevel::Trace => Ok(Self::Trace),
+            events::LogLevel::Unspecified => {
                 Err(PyValueError::new_err("invalid LogLevel: UNSPECIFIED"))
             }
         }
     }
 }
 
-impl TryFrom<i32> for JobLogLevel {
-    type Error = PyErr;
-
-    fn try_from(value: i32) -> Result<Self, PyErr> {
-        match commanderpb::runtime_log_event::LogLevel::try_from(value) {
-            Ok(v) => Self::try_from(v),
-            Err(_) => Err(PyValueError::new_err(format!("invalid LogLevel: {value}"))),
-        }
-    }
-}
-
 /// A single log message from a job execution. When you output logs within a Python model, they are persisted as `JobLogEvent`s.
 #[derive(Debug, Clone, Serialize)]
 #[pyclass(
@@ -245,22 +243,283 @@ impl JobLogEvent {
     }
 }
 
+impl JobLogEvent {
+    /// The event standing in for the logs dropped from the middle of a run's
+    /// logs.
+    pub(crate) fn truncation_marker(dropped: usize) -> Self {
+        JobLogEvent {
+            stream: JobLogStream::Stdout,
+            level: JobLogLevel::Warn,
+            message: format!(
+                "... {dropped} log messages omitted; use Client.get_job_logs to get all of them ..."
+            ),
+        }
+    }
+
+    /// Converts a log message of any type, including system logs.
+    fn from_any_log(log: LogMessage) -> PyResult<Self> {
+        Ok(JobLogEvent {
+            stream: log.stream.try_into()?,
+            level: log.level.try_into()?,
+            message: log.message,
+        })
+    }
+}
+
+impl TryFrom<LogMessage> for JobLogEvent {
+    type Error = PyErr;
+
+    fn try_from(log: LogMessage) -> Result<Self, Self::Error> {
+        if !log.user {
+            return Err(PyValueError::new_err("not a user log"));
+        }
+
+        Self::from_any_log(log)
+    }
+}
+
 impl TryFrom<commanderpb::RuntimeLogEvent> for JobLogEvent {
     type Error = PyErr;
 
     fn try_from(log: commanderpb::RuntimeLogEvent) -> Result<Self, Self::Error> {
-        use commanderpb::runtime_log_event::*;
+        LogMessage::from(log).try_into()
+    }
+}
 
-        if LogType::try_from(log.r#type) != Ok(LogType::User) {
-            return Err(PyValueError::new_err("not a user log"));
+/// A change in a job's progress, yielded by `Client.stream_job_logs`
+/// alongside its log events.
+#[derive(Debug, Clone, Serialize)]
+#[pyclass(
+    name = "JobLifecycleEvent",
+    module = "bauplan.schema",
+    skip_from_py_object,
+    get_all
+)]
+pub(crate) struct JobLifecycleEvent {
+    /// The kind of event: "task_start", "task_complete", or "job_complete".
+    pub kind: String,
+    /// The name of the task, for task events.
+    pub task: Option<String>,
+    /// The final status of the job (e.g. "SUCCESS" or "FAILED"), for
+    /// "job_complete" events.
+    pub status: Option<String>,
+}
+
+#[pymethods]
+impl JobLifecycleEvent {
+    fn __repr__(&self) -> String {
+        match (&self.task, &self.status) {
+            (Some(task), _) => format!("JobLifecycleEvent(kind={:?}, task={task:?})", self.kind),
+            (None, Some(status)) => {
+                format!("JobLifecycleEvent(kind={:?}, status={status:?})", self.kind)
+            }
+            (None, None) => format!("JobLifecycleEvent(kind={:?})", self.kind),
         }
+    }
+}
 
-        Ok(JobLogEvent {
-            stream: log.output_stream.try_into()?,
-            level: log.level.try_into()?,
-            message: log.msg,
-        })
+/// An item yielded by `Client.stream_job_logs`.
+#[derive(IntoPyObject)]
+pub(crate) enum StreamedJobEvent {
+    Log(JobLogEvent),
+    Lifecycle(JobLifecycleEvent),
+}
+
+/// The iterator returned by `Client.stream_job_logs`.
+#[pyclass]
+pub(crate) struct JobLogTail {
+    inner: Mutex<TailState>,
+}
+
+struct TailState {
+    job_id: String,
+    grpc: grpc::Client,
+    longbow_endpoint: Arc<tokio::sync::OnceCell<bauplan_longbow::iroh::Endpoint>>,
+    include_system: bool,
+    since_ns: Option<i64>,
+    stream: Option<BoxStream<'static, Result<JobEvent, tonic::Status>>>,
+    /// The number of events received since the stream was (re)opened.
+    received: usize,
+    /// After a reconnect, the number of replayed events to skip.
+    skip: usize,
+    schedule: WatchSchedule,
+    reconnects: u32,
+    done: bool,
+    /// The stream is bound to the tokio runtime of the process that opened
+    /// it, so it can't be used in a forked child.
+    fork_generation: u64,
+}
+
+impl TailState {
+    /// How many times in a row we reconnect before giving up.
+    const MAX_RECONNECTS: u32 = 5;
+
+    fn connect(&mut self) {
+        let req = commanderpb::SubscribeLogsRequest {
+            job_id: self.job_id.clone(),
+        };
+
+        let stream = self.grpc.monitor_job(req, self.longbow_endpoint.clone());
+        self.stream = Some(stream.boxed());
+    }
+
+    /// Drops the current stream and waits before the next attempt. The
+    /// SubscribeLogs stream replays a job's events from the beginning, so
+    /// the events we've already yielded are skipped once it's reopened.
+    fn reconnect(&mut self, py: Python<'_>, reason: impl std::fmt::Display) -> PyResult<()> {
+        self.stream = None;
+        if self.reconnects >= Self::MAX_RECONNECTS {
+            self.done = true;
+            return Err(BauplanError::new_err(format!(
+                "lost the log stream for job {}: {reason}",
+                self.job_id
+            )));
+        }
+
+        self.reconnects += 1;
+        self.skip += self.received;
+        self.received = 0;
+
+        let delay = self.schedule.failed();
+        warn!(
+            job_id = self.job_id.as_str(),
+            "log stream interrupted, reconnecting in {delay:?}: {reason}"
+        );
+
+        let wake = Instant::now() + delay;
+        while let Some(left) = wake.checked_duration_since(Instant::now()) {
+            py.detach(|| thread::sleep(left.min(TAIL_TICK)));
+            py.check_signals()?;
+        }
+
+        Ok(())
+    }
+
+    /// Converts an event from the stream, or returns `None` if it's not
+    /// one we yield.
+    fn convert(&mut self, event: JobEvent) -> PyResult<Option<StreamedJobEvent>> {
+        let lifecycle = |kind: &str, task: String| {
+            StreamedJobEvent::Lifecycle(JobLifecycleEvent {
+                kind: kind.to_owned(),
+                task: Some(task).filter(|t| !t.is_empty()),
+                status: None,
+            })
+        };
+
+        let ev = match event {
+            JobEvent::UserLog(log) => {
+                // Synthesized output lines from attached tasks have no
+                // timestamp; always include them.
+                if let Some(since) = self.since_ns
+                    && log.timestamp_ns != 0
+                    && log.timestamp_ns < since
+                {
+                    return Ok(None);
+                }
+
+                if log.user {
+                    StreamedJobEvent::Log(log.try_into()?)
+                } else if self.include_system {
+                    StreamedJobEvent::Log(JobLogEvent::from_any_log(log)?)
+                } else {
+                    return Ok(None);
+                }
+            }
+            JobEvent::TaskStarted(task) => lifecycle("task_start", task.name),
+            JobEvent::TaskCompleted(task, _) => lifecycle("task_complete", task.name),
+            JobEvent::Completion(completion) => {
+                self.done = true;
+                let (status, _) = job_status_strings(completion.result.map(|_| ()));
+                StreamedJobEvent::Lifecycle(JobLifecycleEvent {
+                    kind: "job_complete".to_owned(),
+                    task: None,
+                    status: Some(status),
+                })
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(ev))
+    }
+}
+
+/// How often to wake up while waiting, to handle signals like
+/// KeyboardInterrupt.
+const TAIL_TICK: Duration = Duration::from_millis(200);
+
+#[pymethods]
+impl JobLogTail {
+    fn __iter__(this: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        this
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<StreamedJobEvent>> {
+        let state = &mut *self.inner.lock().unwrap();
+        if state.fork_generation != fork_generation() {
+            state.done = true;
+            return Err(used_after_fork());
+        }
+
+        loop {
+            if state.done {
+                return Ok(None);
+            }
+
+            let Some(stream) = state.stream.as_mut() else {
+                state.connect();
+                continue;
+            };
+
+            let next = py.detach(|| rt().block_on(tokio::time::timeout(TAIL_TICK, stream.next())));
+            py.check_signals()?;
+
+            let event = match next {
+                // Nothing yet.
+                Err(_) => continue,
+                Ok(Some(Ok(event))) => event,
+                Ok(Some(Err(e))) if is_transient(&e) => {
+                    state.reconnect(py, e.message())?;
+                    continue;
+                }
+                Ok(Some(Err(e))) => {
+                    state.done = true;
+                    state.stream = None;
+                    return Err(BauplanError::new_err(e.to_string()));
+                }
+                Ok(None) => {
+                    state.reconnect(py, "stream ended before the job completed")?;
+                    continue;
+                }
+            };
+
+            state.received += 1;
+            if state.skip > 0 {
+                state.skip -= 1;
+                continue;
+            }
+
+            state.reconnects = 0;
+            state.schedule.succeeded();
+            if let Some(ev) = state.convert(event)? {
+                return Ok(Some(ev));
+            }
+        }
     }
+
+    /// Stops streaming. This closes the connection, but doesn't cancel the
+    /// job.
+    fn close(&self) {
+        let state = &mut *self.inner.lock().unwrap();
+        state.stream = None;
+        state.done = true;
+    }
+}
+
+fn is_transient(status: &tonic::Status) -> bool {
+    matches!(
+        status.code(),
+        tonic::Code::Unavailable | tonic::Code::Unknown | tonic::Code::Internal
+    )
 }
 
 /// A node in the job DAG (a model).
@@ -271,6 +530,14 @@ pub(crate) struct DAGNode {
     id: String,
     /// The model name.
     name: String,
+    /// The state of the model's task: "not_started", "running", "success", "failed", "skipped" or "cancelled". `None` if the job's events weren't fetched.
+    state: Option<String>,
+    /// When the model's task started.
+    started_at: Option<DateTime<Utc>>,
+    /// When the model's task finished.
+    ended_at: Option<DateTime<Utc>>,
+    /// Why the model failed or was cancelled, if it was.
+    error_message: Option<String>,
 }
 
 impl From<commanderpb::ModelNode> for DAGNode {
@@ -278,10 +545,34 @@ impl From<commanderpb::ModelNode> for DAGNode {
         DAGNode {
             id: value.model_id,
             name: value.model_name,
+            state: None,
+            started_at: None,
+            ended_at: None,
+            error_message: None,
         }
     }
 }
 
+impl DAGNode {
+    fn set_status(&mut self, status: ModelStatus) {
+        self.state = Some(status.state.as_str().to_owned());
+        self.started_at = status.started_at;
+        self.ended_at = status.ended_at;
+        self.error_message = status.error_message;
+    }
+}
+
+#[pymethods]
+impl DAGNode {
+    fn __repr__(&self) -> String {
+        format!(
+            "DAGNode(name={:?}, state={})",
+            self.name,
+            self.state.as_deref().unwrap_or("None")
+        )
+    }
+}
+
 /// A dependency between two `DAGNode` instances, representing dataflow.
 #[derive(Debug, Clone, Serialize)]
 #[pyclass(module = "bauplan.schema", skip_from_py_object, get_all)]
@@ -301,6 +592,68 @@ impl From<commanderpb::ModelEdge> for DAGEdge {
     }
 }
 
+/// The models of a job and the dataflow between them, with the state of
+/// each model. Jobs that predate DAG tracking have no nodes or edges.
+#[derive(Debug, Clone, Serialize)]
+#[pyclass(module = "bauplan.schema", skip_from_py_object, get_all)]
+pub(crate) struct JobDAG {
+    /// The ID of the job.
+    job_id: String,
+    /// The models in the job.
+    nodes: Vec<DAGNode>,
+    /// The dependencies between models.
+    edges: Vec<DAGEdge>,
+}
+
+#[pymethods]
+impl JobDAG {
+    fn __repr__(&self) -> String {
+        format!(
+            "JobDAG(job_id={:?}, nodes={}, edges={})",
+            self.job_id,
+            self.nodes.len(),
+            self.edges.len()
+        )
+    }
+
+    /// Renders the DAG in Graphviz's DOT language. Nodes are labelled with
+    /// the model name and state.
+    ///
+    /// Returns:
+    ///     The DAG as a DOT `digraph`.
+    fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph {\n");
+        for node in &self.nodes {
+            let label = match &node.state {
+                Some(state) => format!("{}\\n{state}", dot_escape(&node.name)),
+                None => dot_escape(&node.name),
+            };
+            writeln!(dot, "  \"{}\" [label=\"{label}\"];", dot_escape(&node.id)).unwrap();
+        }
+
+        for edge in &self.edges {
+            // Edges from table scans have no source model.
+            if let Some(source) = &edge.source_model {
+                writeln!(
+                    dot,
+                    "  \"{}\" -> \"{}\";",
+                    dot_escape(source),
+                    dot_escape(&edge.destination_model)
+                )
+                .unwrap();
+            }
+        }
+
+        dot.push('}');
+        dot
+    }
+}
+
+/// Escapes a string for a quoted DOT ID.
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
 /// The working context of a job, including its ref, DAG, code snapshot, and logs.
 #[derive(Debug, Clone)]
 #[pyclass(module = "bauplan.schema", skip_from_py_object, get_all)]
@@ -326,6 +679,14 @@ impl TryFrom<commanderpb::JobContext> for JobContext {
 
         let tx_ref = ctx.transaction_branch.map(|b| b.name);
 
+        // Without events (they're only fetched with the logs), the state of
+        // each model is unknown.
+        let statuses = if ctx.job_events.is_empty() {
+            None
+        } else {
+            Some(grpc::job::model_statuses(ctx.job_events.iter().cloned()))
+        };
+
         let logs: Vec<JobLogEvent> = ctx
             .job_events
             .into_iter()
@@ -338,7 +699,12 @@ impl TryFrom<commanderpb::JobContext> for JobContext {
             })
             .collect();
 
-        let dag_nodes: Vec<DAGNode> = ctx.models.into_iter().map(|m| m.into()).collect();
+        let mut dag_nodes: Vec<DAGNode> = ctx.models.into_iter().map(|m| m.into()).collect();
+        if let Some(mut statuses) = statuses {
+            for node in &mut dag_nodes {
+                node.set_status(statuses.remove(&node.name).unwrap_or_default());
+            }
+        }
         let dag_edges: Vec<DAGEdge> = ctx.model_deps.into_iter().map(|e| e.into()).collect();
 
         // Decompress code snapshot if present.
@@ -409,7 +775,7 @@ impl Client {
         });
         req.set_timeout(self.client_timeout);
 
-        let response = detach(py, self.grpc.clone().get_jobs(req))
+        let response = detach(py, self.grpc()?.get_jobs(req))
             .map_err(|e| BauplanError::new_err(e.to_string()))?;
 
         let jobs = response.into_inner().jobs;
@@ -436,10 +802,21 @@ impl Client {
     ///     filter_by_ids: Optional[Union[str, List[str]]]: Optional, filter by job IDs.
     ///     filter_by_users: Optional[Union[str, List[str]]]: Optional, filter by job users.
     ///     filter_by_kinds: Optional[Union[str, JobKind, List[Union[str, JobKind]]]]: Optional, filter by job kinds.
+    ///         Strings may be enum member names or the CLI's names, in any case, like "RUN", "run" or "table-import".
     ///     filter_by_statuses: Optional[Union[str, JobState, List[Union[str, JobState]]]]: Optional, filter by job statuses.
+    ///         Strings may be enum member names or the CLI's names, in any case, like "NOT_STARTED" or "not-started".
     ///     filter_by_created_after: Optional[datetime]: Optional, filter jobs created after this datetime.
     ///     filter_by_created_before: Optional[datetime]: Optional, filter jobs created before this datetime.
+    ///     filter_by_project: Optional[str]: Optional, filter by project ID or name, or "." for the project in the
+    ///         current directory. This is done client-side, with an extra request per page of jobs; if `limit` is
+    ///         set, only the `limit` most recent jobs are checked, so fewer may be returned.
     ///     limit: Optional[int]: Optional, max number of jobs to return.
+    ///     order: Literal["asc", "desc"]: Whether to return the oldest or the newest jobs first. Defaults to "desc".
+    ///         The server lists jobs newest first, so with "asc" every matching job is fetched before the first
+    ///         one is returned; use `filter_by_created_after` to keep that small.
+    ///
+    /// Jobs are sorted by creation time. Each job is returned at most once,
+    /// but jobs created while iterating may be missed.
     ///
     /// Returns:
     ///     An iterator over `bauplan.schema.Job` objects.
@@ -448,11 +825,13 @@ impl Client {
         filter_by_current_user=true,
         filter_by_ids=None,
         filter_by_users=None,
-        filter_by_kinds=None,
-        filter_by_statuses=None,
+        filter_by_kinds: "str | JobKind | list[str | JobKind] | None" = None,
+        filter_by_statuses: "str | JobState | list[str | JobState] | None" = None,
         filter_by_created_after=None,
         filter_by_created_before=None,
+        filter_by_project=None,
         limit=None,
+        order: "Literal['asc', 'desc']" = "desc",
     ) -> "typing.Iterator[Job]")]
     #[allow(clippy::too_many_arguments)]
     fn get_jobs(
@@ -465,8 +844,20 @@ impl Client {
         filter_by_statuses: Option<JobStateListArg>,
         filter_by_created_after: Option<DateTime<Utc>>,
         filter_by_created_before: Option<DateTime<Utc>>,
+        filter_by_project: Option<&str>,
         limit: Option<usize>,
+        order: &str,
     ) -> PyResult<PyPaginator> {
+        let oldest_first = match order {
+            "desc" => false,
+            "asc" => true,
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "order must be 'asc' or 'desc', got '{other}'"
+                )));
+            }
+        };
+
         let filter_created_after = filter_by_created_after.map(|dt| prost_types::Timestamp {
             seconds: dt.timestamp(),
             nanos: dt.timestamp_subsec_nanos() as i32,
@@ -491,37 +882,94 @@ impl Client {
         let filter_kinds: Vec<i32> = filter_by_kinds.unwrap_or_default().into();
         let filter_statuses: Vec<i32> = filter_by_statuses.unwrap_or_default().into();
 
+        let project_filter = filter_by_project
+            .map(ProjectFilter::new)
+            .transpose()
+            .map_err(|e| BauplanError::new_err(e.to_string()))?;
+
         let client_timeout = self.client_timeout;
-        let mut grpc = self.grpc.clone();
+        let mut grpc = self.grpc()?;
+        let generation = fork_generation();
+        let mut dedup = JobPageDedup::default();
+        let mut scanned = 0;
+
+        // The oldest jobs can only be found by listing all of them, so the
+        // project filter doesn't stop early in that case.
+        let scan_limit = if oldest_first { None } else { limit };
 
         PyPaginator::new(py, limit, move |py, token, page_limit| {
-            let mut req = Request::new(commanderpb::GetJobsRequest {
-                job_ids: job_ids.clone(),
-                all_users,
-                filter_users: filter_users.clone(),
-                filter_kinds: filter_kinds.clone(),
-                filter_statuses: filter_statuses.clone(),
-                filter_created_after,
-                filter_created_before,
-                max_records: page_limit.unwrap_or_default() as _,
-                pagination_token: token.unwrap_or("").to_string(),
-                ..Default::default()
-            });
-            req.set_timeout(client_timeout);
+            if generation != fork_generation() {
+                return Err(used_after_fork());
+            }
 
-            let page = detach(py, grpc.get_jobs(req))
-                .map_err(|e| BauplanError::new_err(e.to_string()))?
-                .into_inner();
+            // The server lists jobs newest first, so to return the oldest
+            // first, fetch every page here and sort them.
+            let mut token = token.map(str::to_owned);
+            let page_limit = if oldest_first { None } else { page_limit };
+            let mut oldest = Vec::new();
+            loop {
+                let mut req = Request::new(commanderpb::GetJobsRequest {
+                    job_ids: job_ids.clone(),
+                    all_users,
+                    filter_users: filter_users.clone(),
+                    filter_kinds: filter_kinds.clone(),
+                    filter_statuses: filter_statuses.clone(),
+                    filter_created_after,
+                    filter_created_before,
+                    max_records: page_limit.unwrap_or_default() as _,
+                    pagination_token: token.as_deref().unwrap_or("").to_string(),
+                    ..Default::default()
+                });
+                req.set_timeout(client_timeout);
+
+                let mut page = detach(py, grpc.get_jobs(req))
+                    .map_err(|e| BauplanError::new_err(e.to_string()))?
+                    .into_inner();
+                dedup.filter(&mut page.jobs);
+                scanned += page.jobs.len();
+
+                let mut pagination_token = if page.pagination_token.is_empty() {
+                    None
+                } else {
+                    Some(page.pagination_token)
+                };
+
+                let mut jobs: Vec<Job> = page.jobs.into_iter().map(Job::from).collect();
+                if let Some(filter) = &project_filter {
+                    if !jobs.is_empty() {
+                        let mut req = Request::new(ProjectFilter::context_request(&jobs));
+                        req.set_timeout(client_timeout);
+
+                        let contexts = detach(py, grpc.get_job_context(req))
+                            .map_err(|e| BauplanError::new_err(e.to_string()))?
+                            .into_inner();
+                        filter.apply(&mut jobs, contexts.job_contexts);
+                    }
+
+                    // Stop once `limit` jobs have been checked, matching or not.
+                    if scan_limit.is_some_and(|l| scanned >= l) {
+                        pagination_token = None;
+                    }
+                }
 
-            let pagination_token = if page.pagination_token.is_empty() {
-                None
-            } else {
-                Some(page.pagination_token)
-            };
+                if !oldest_first {
+                    return Ok(PaginatedResponse {
+                        page: jobs,
+                        pagination_token,
+                    });
+                }
+
+                oldest.extend(jobs);
+                match pagination_token {
+                    Some(t) => token = Some(t),
+                    None => break,
+                }
+            }
 
+            sort_oldest_first(&mut oldest);
             Ok(PaginatedResponse {
-                page: page.jobs.into_iter().map(Job::from).collect(),
-                pagination_token,
+                page: oldest,
+                pagination_token: None,
             })
         })
     }
@@ -549,7 +997,7 @@ impl Client {
         });
         req.set_timeout(self.client_timeout);
 
-        let response = detach(py, self.grpc.clone().get_logs(req))
+        let response = detach(py, self.grpc()?.get_logs(req))
             .map_err(|e| BauplanError::new_err(e.to_string()))?;
 
         let events: Vec<JobLogEvent> = response
@@ -568,6 +1016,61 @@ impl Client {
         Ok(events)
     }
 
+    /// EXPERIMENTAL: Stream the logs for a job as they're emitted, until it
+    /// completes.
+    ///
+    /// Alongside `bauplan.schema.JobLogEvent` objects, the iterator yields a
+    /// `bauplan.schema.JobLifecycleEvent` as each task starts and completes,
+    /// and a final one when the job completes. If the connection drops, it's
+    /// reopened automatically. Closing the iterator stops streaming, but
+    /// doesn't cancel the job.
+    ///
+    /// ```python
+    /// #! my_job: bauplan.schema.Job = ...  # type: ignore[assignment]
+    /// import bauplan
+    /// client = bauplan.Client()
+    ///
+    /// for event in client.stream_job_logs(my_job.id):
+    ///     if isinstance(event, bauplan.schema.JobLogEvent):
+    ///         print(f"[{event.level}] {event.message}")
+    /// ```
+    ///
+    /// Parameters:
+    ///     job: Union[str, Job]: A job ID or a Job instance.
+    ///     include_system: Whether to include system logs, in addition to logs from user code.
+    ///     since: Optional, skip log events emitted before this datetime.
+    /// Returns:
+    ///     An iterator over `bauplan.schema.JobLogEvent` and `bauplan.schema.JobLifecycleEvent` objects.
+    #[pyo3(signature = (
+        job,
+        *,
+        include_system: "bool" = false,
+        since: "datetime | None" = None,
+    ) -> "typing.Iterator[JobLogEvent | JobLifecycleEvent]")]
+    fn stream_job_logs(
+        &self,
+        job: JobArg,
+        include_system: bool,
+        since: Option<DateTime<Utc>>,
+    ) -> PyResult<JobLogTail> {
+        Ok(JobLogTail {
+            inner: Mutex::new(TailState {
+                job_id: job.0,
+                grpc: self.grpc()?,
+                longbow_endpoint: self.longbow_endpoint()?,
+                include_system,
+                since_ns: since.and_then(|dt| dt.timestamp_nanos_opt()),
+                stream: None,
+                received: 0,
+                skip: 0,
+                schedule: WatchSchedule::new(Duration::from_secs(1)),
+                reconnects: 0,
+                done: false,
+                fork_generation: fork_generation(),
+            }),
+        })
+    }
+
     /// EXPERIMENTAL: Get context for a job by ID.
     ///
     /// ```python
@@ -604,7 +1107,7 @@ impl Client {
         });
         req.set_timeout(self.client_timeout);
 
-        let response = detach(py, self.grpc.clone().get_job_context(req))
+        let response = detach(py, self.grpc()?.get_job_context(req))
             .map_err(|e| BauplanError::new_err(e.to_string()))?;
 
         let inner = response.into_inner();
@@ -660,7 +1163,7 @@ impl Client {
         });
         req.set_timeout(self.client_timeout);
 
-        let resp = detach(py, self.grpc.clone().get_job_context(req))
+        let resp = detach(py, self.grpc()?.get_job_context(req))
             .map_err(|e| BauplanError::new_err(e.to_string()))?
             .into_inner();
 
@@ -680,6 +1183,37 @@ impl Client {
         Ok(ctxs)
     }
 
+    /// EXPERIMENTAL: Get the DAG of a job, with the state of each model.
+    ///
+    /// The state and timing of each model come from the job's task events.
+    /// Jobs that predate DAG tracking return an empty DAG.
+    ///
+    /// ```python
+    /// #! my_job: bauplan.schema.Job = ...  # type: ignore[assignment]
+    /// import bauplan
+    /// client = bauplan.Client()
+    ///
+    /// dag = client.get_job_dag(my_job)
+    /// for node in dag.nodes:
+    ///     print(f"{node.name}: {node.state}")
+    /// print(dag.to_dot())
+    /// ```
+    ///
+    /// Parameters:
+    ///     job: Union[str, Job]: A job ID or a Job instance.
+    /// Returns:
+    ///     A `bauplan.schema.JobDAG` object.
+    #[pyo3(signature = (job) -> "JobDAG")]
+    fn get_job_dag(&self, py: Python<'_>, job: JobArg) -> PyResult<JobDAG> {
+        // The task events are only included with the logs.
+        let ctx = self.get_job_context(py, job, true, false)?;
+        Ok(JobDAG {
+            job_id: ctx.id,
+            nodes: ctx.dag_nodes,
+            edges: ctx.dag_edges,
+        })
+    }
+
     /// EXPERIMENTAL: Cancel a job by ID.
     ///
     /// ```python
@@ -706,8 +1240,7 @@ impl Client {
                 ..Default::default()
             }),
         };
-        detach(py, self.grpc.clone().cancel(req))
-            .map_err(|e| BauplanError::new_err(e.to_string()))?;
+        detach(py, self.grpc()?.cancel(req)).map_err(|e| BauplanError::new_err(e.to_string()))?;
 
         Ok(())
     }
@@ -0,0 +1,332 @@
+//! Forwards `tracing` events to Python's `logging` module.
+//!
+//! Events are emitted from arbitrary threads, including tokio's, which must
+//! never wait for the GIL: the thread holding it may be waiting on them. So
+//! events are only queued where they're emitted. A dedicated thread takes
+//! the GIL to hand them to the "bauplan" logger, and blocking client calls
+//! drain the queue before returning, so that a call's records are logged
+//! before it returns.
+
+use std::{
+    collections::VecDeque,
+    fmt::{self, Write as _},
+    mem,
+    sync::{Condvar, Mutex, OnceLock},
+    thread,
+};
+
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    prelude::*,
+    types::PyDict,
+};
+use tracing::{
+    Event, Level, Metadata, Subscriber,
+    field::{Field, Visit},
+    level_filters::LevelFilter,
+};
+use tracing_subscriber::{
+    Layer, Registry,
+    layer::{Context, SubscriberExt as _},
+    reload,
+};
+
+use super::fork_generation;
+
+/// The most events kept waiting to be logged. Beyond that, events are
+/// dropped (and counted) rather than blocking the thread emitting them.
+const QUEUE_CAPACITY: usize = 10_000;
+
+/// The attributes of a `logging.LogRecord`, which can't be set with `extra`.
+const RESERVED_ATTRS: &[&str] = &[
+    "args",
+    "asctime",
+    "created",
+    "exc_info",
+    "exc_text",
+    "filename",
+    "funcName",
+    "levelname",
+    "levelno",
+    "lineno",
+    "message",
+    "module",
+    "msecs",
+    "msg",
+    "name",
+    "pathname",
+    "process",
+    "processName",
+    "relativeCreated",
+    "stack_info",
+    "taskName",
+    "thread",
+    "threadName",
+];
+
+static BRIDGE: OnceLock<Bridge> = OnceLock::new();
+
+struct Bridge {
+    queue: Mutex<Queue>,
+    ready: Condvar,
+    filter: reload::Handle<LevelFilter, Registry>,
+}
+
+#[derive(Default)]
+struct Queue {
+    records: VecDeque<Record>,
+    dropped: u64,
+    /// The fork generation the drain thread was started in, if any.
+    generation: Option<u64>,
+}
+
+struct Record {
+    level: Level,
+    message: String,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl Bridge {
+    fn push(&'static self, record: Record) {
+        let mut queue = self.queue.lock().unwrap();
+
+        // The drain thread doesn't survive a fork, so each process needs
+        // its own.
+        let generation = fork_generation();
+        if queue.generation != Some(generation) {
+            queue.generation = Some(generation);
+            let _ = thread::Builder::new()
+                .name("bauplan-logging".to_owned())
+                .spawn(move || self.drain_forever());
+        }
+
+        if queue.records.len() >= QUEUE_CAPACITY {
+            queue.dropped += 1;
+            return;
+        }
+
+        queue.records.push_back(record);
+        self.ready.notify_one();
+    }
+
+    fn take(&self, queue: &mut Queue) -> (VecDeque<Record>, u64) {
+        (mem::take(&mut queue.records), mem::take(&mut queue.dropped))
+    }
+
+    fn drain_forever(&self) {
+        loop {
+            let (records, dropped) = {
+                let mut queue = self.queue.lock().unwrap();
+                while queue.records.is_empty() && queue.dropped == 0 {
+                    queue = self.ready.wait(queue).unwrap();
+                }
+
+                self.take(&mut queue)
+            };
+
+            // The queue must be unlocked here, or a thread holding the GIL
+            // could block on it in `flush`.
+            Python::attach(|py| emit(py, records, dropped));
+        }
+    }
+}
+
+/// Queues events for the drain thread.
+struct PyLogLayer;
+
+impl<S: Subscriber> Layer<S> for PyLogLayer {
+    fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        metadata.target().starts_with("bauplan")
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let Some(bridge) = BRIDGE.get() else {
+            return;
+        };
+
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        bridge.push(Record {
+            level: *event.metadata().level(),
+            message: visitor.message,
+            fields: visitor.fields,
+        });
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl FieldVisitor {
+    fn record(&mut self, field: &Field, value: String) {
+        if field.name() == "message" {
+            self.message = value;
+        } else {
+            self.fields.push((field.name(), value));
+        }
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record(field, value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record(field, format!("{value:?}"));
+    }
+}
+
+/// Logs queued records. Called after blocking calls, so that their records
+/// are logged before they return.
+pub(crate) fn flush(py: Python<'_>) {
+    let Some(bridge) = BRIDGE.get() else {
+        return;
+    };
+
+    let (records, dropped) = bridge.take(&mut bridge.queue.lock().unwrap());
+    emit(py, records, dropped);
+}
+
+fn emit(py: Python<'_>, records: VecDeque<Record>, dropped: u64) {
+    if records.is_empty() && dropped == 0 {
+        return;
+    }
+
+    if let Err(err) = try_emit(py, records, dropped) {
+        err.write_unraisable(py, None);
+    }
+}
+
+fn try_emit(py: Python<'_>, records: VecDeque<Record>, dropped: u64) -> PyResult<()> {
+    let logger = py
+        .import("logging")?
+        .call_method1("getLogger", ("bauplan",))?;
+
+    if dropped > 0 {
+        let msg = format!("dropped {dropped} log records; they were emitted too quickly");
+        logger.call_method1("warning", (msg,))?;
+    }
+
+    for Record {
+        level,
+        mut message,
+        fields,
+    } in records
+    {
+        // Fields are added to the message, as well as passed as extra
+        // attributes for formatters and filters.
+        let extra = PyDict::new(py);
+        for (name, value) in fields {
+            let _ = write!(message, " {name}={value}");
+            if !RESERVED_ATTRS.contains(&name) {
+                extra.set_item(name, value)?;
+            }
+        }
+
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("extra", extra)?;
+        logger.call_method("log", (levelno(level), message), Some(&kwargs))?;
+    }
+
+    Ok(())
+}
+
+/// The `logging` level for a `tracing` level. Python has no TRACE level, so
+/// trace events are logged at 5, below DEBUG.
+fn levelno(level: Level) -> u32 {
+    match level {
+        Level::TRACE => 5,
+        Level::DEBUG => 10,
+        Level::INFO => 20,
+        Level::WARN => 30,
+        _ => 40,
+    }
+}
+
+#[derive(FromPyObject)]
+pub(crate) enum LevelArg {
+    Number(u32),
+    Name(String),
+}
+
+impl LevelArg {
+    fn to_filter(&self) -> PyResult<LevelFilter> {
+        let filter = match self {
+            LevelArg::Number(0..=5) => LevelFilter::TRACE,
+            LevelArg::Number(6..=10) => LevelFilter::DEBUG,
+            LevelArg::Number(11..=20) => LevelFilter::INFO,
+            LevelArg::Number(21..=30) => LevelFilter::WARN,
+            LevelArg::Number(_) => LevelFilter::ERROR,
+            LevelArg::Name(name) => match name.to_ascii_uppercase().as_str() {
+                "TRACE" => LevelFilter::TRACE,
+                "DEBUG" => LevelFilter::DEBUG,
+                "INFO" => LevelFilter::INFO,
+                "WARN" | "WARNING" => LevelFilter::WARN,
+                "ERROR" | "CRITICAL" => LevelFilter::ERROR,
+                "OFF" => LevelFilter::OFF,
+                _ => {
+                    return Err(PyValueError::new_err(format!("invalid log level {name:?}")));
+                }
+            },
+        };
+
+        Ok(filter)
+    }
+}
+
+/// Forward the client's internal log events, such as job progress, to the
+/// standard `logging` module, under the "bauplan" logger. Fields like
+/// `job_id` are appended to the message, and set as attributes on the
+/// records for formatters and filters to use.
+///
+/// This is off by default. Once enabled, every event at or above `level` is
+/// formatted and queued, even if the logger then discards it, and a
+/// background thread takes the GIL to log them. Keep `level` at "INFO" or
+/// above unless debugging. Call this again to change the level, or pass
+/// "OFF" to stop forwarding.
+///
+/// ```python
+/// import logging
+/// import bauplan
+///
+/// logging.basicConfig(format="%(levelname)s %(name)s: %(message)s")
+/// bauplan.enable_logging("INFO")
+/// ```
+///
+/// Parameters:
+///     level: The lowest level to forward, as a name like "DEBUG" or a `logging` level like `logging.DEBUG`.
+///
+/// Raises:
+///     `ValueError`: if the level is invalid.
+///     `RuntimeError`: if another `tracing` subscriber was already installed.
+#[pyfunction]
+#[pyo3(signature = (level: "str | int" = LevelArg::Name("INFO".to_owned())) -> "None")]
+pub(crate) fn enable_logging(level: LevelArg) -> PyResult<()> {
+    let filter = level.to_filter()?;
+
+    // Calls are serialized by the GIL, so there's no race between checking
+    // and setting the bridge.
+    if let Some(bridge) = BRIDGE.get() {
+        return bridge
+            .filter
+            .reload(filter)
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()));
+    }
+
+    let (filter, handle) = reload::Layer::new(filter);
+    let subscriber = Registry::default().with(filter).with(PyLogLayer);
+    tracing::subscriber::set_global_default(subscriber)
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+
+    let _ = BRIDGE.set(Bridge {
+        queue: Mutex::default(),
+        ready: Condvar::new(),
+        filter: handle,
+    });
+
+    Ok(())
+}
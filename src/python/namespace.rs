@@ -2,6 +2,7 @@
 
 use pyo3::{exceptions::PyTypeError, prelude::*};
 use std::collections::BTreeMap;
+use tracing::info;
 
 use crate::{
     ApiErrorKind, ApiRequest, CatalogRef,
@@ -46,7 +47,7 @@ impl Client {
     /// ```
     ///
     /// Parameters:
-    ///     ref: The ref, branch name or tag name to retrieve the namespaces from.
+    ///     ref: The ref, branch name or tag name to retrieve the namespaces from. Defaults to the active branch.
     ///     filter_by_name: Optional, filter the namespaces by name.
     ///     limit: Optional, max number of namespaces to get.
     ///
@@ -59,7 +60,7 @@ impl Client {
     /// Yields:
     ///     `bauplan.schema.Namespace` objects.
     #[pyo3(signature = (
-        r#ref: "str | Ref",
+        r#ref: "str | Ref | None" = None,
         *,
         filter_by_name: "str | None" = None,
         limit: "int | None" = None,
@@ -67,13 +68,13 @@ impl Client {
     fn get_namespaces(
         &self,
         py: Python<'_>,
-        r#ref: RefArg,
+        r#ref: Option<RefArg>,
         filter_by_name: Option<String>,
         limit: Option<usize>,
     ) -> PyResult<PyPaginator> {
-        let r#ref = r#ref.0;
+        let r#ref = self.ref_or_active(r#ref).resolve(py, self)?;
         let profile = self.profile.clone();
-        let agent = self.agent.clone();
+        let agent = self.agent()?;
         PyPaginator::new(py, limit, move |py, token, limit| {
             let req = GetNamespaces {
                 at_ref: &r#ref,
@@ -101,7 +102,7 @@ impl Client {
     ///
     /// Parameters:
     ///     namespace: The name of the namespace to get.
-    ///     ref: The ref, branch name or tag name to check the namespace on.
+    ///     ref: The ref, branch name or tag name to check the namespace on. Defaults to the active branch.
     /// Returns:
     ///     A `bauplan.schema.Namespace` object.
     ///
@@ -113,15 +114,15 @@ impl Client {
     ///     `ValueError`: if one or more parameters are invalid.
     #[pyo3(signature = (
         namespace: "str | Namespace",
-        r#ref: "str | Ref",
+        r#ref: "str | Ref | None" = None,
     ) -> "Namespace")]
-    fn get_namespace(&self, py: Python<'_>, namespace: NamespaceArg, r#ref: RefArg) -> PyResult<Namespace> {
+    fn get_namespace(&self, py: Python<'_>, namespace: NamespaceArg, r#ref: Option<RefArg>) -> PyResult<Namespace> {
         let req = GetNamespace {
             name: &namespace.0,
-            at_ref: &r#ref.0,
+            at_ref: &self.ref_or_active(r#ref).resolve(py, self)?,
         };
 
-        Ok(super::roundtrip(py, req, &self.profile, &self.agent)?)
+        Ok(super::roundtrip(py, req, &self.profile, &self.agent()?)?)
     }
 
     /// Create a new namespace at a given branch.
@@ -141,7 +142,7 @@ impl Client {
     ///
     /// Parameters:
     ///     namespace: The name of the namespace.
-    ///     branch: The name of the branch to create the namespace on.
+    ///     branch: The name of the branch to create the namespace on. Defaults to the active branch.
     ///     commit_body: Optional, the commit body to attach to the operation.
     ///     commit_properties: Optional, a list of properties to attach to the commit.
     ///     if_not_exists: If set to `True`, the namespace will not be created if it already exists.
@@ -161,7 +162,7 @@ impl Client {
     ///     `ValueError`: if one or more parameters are invalid.
     #[pyo3(signature = (
         namespace: "str | Namespace",
-        branch: "str | Branch",
+        branch: "str | Branch | None" = None,
         *,
         commit_body: "str | None" = None,
         commit_properties: "dict[str, str] | None" = None,
@@ -170,13 +171,13 @@ impl Client {
     fn create_namespace(
         &self, py: Python<'_>,
         namespace: NamespaceArg,
-        branch: BranchArg,
+        branch: Option<BranchArg>,
         commit_body: Option<&str>,
         commit_properties: Option<BTreeMap<String, String>>,
         if_not_exists: bool,
     ) -> PyResult<Namespace> {
         let namespace = &namespace.0;
-        let branch = &branch.0;
+        let branch = &self.branch_or_active(branch.map(|b| b.0));
         let commit_properties = commit_properties.unwrap_or_default();
         let properties = commit_properties
             .iter()
@@ -192,15 +193,11 @@ impl Client {
             },
         };
 
-        match super::roundtrip(py, req, &self.profile, &self.agent) {
+        match super::roundtrip(py, req, &self.profile, &self.agent()?) {
             Ok(ns) => Ok(ns),
             Err(e) => {
-                if if_not_exists
-                    && let Some(ApiErrorKind::NamespaceExists { namespace_name, .. }) = e.kind()
-                {
-                    Ok(Namespace {
-                        name: namespace_name.clone(),
-                    })
+                if if_not_exists && let Some(ns) = e.api().and_then(CreateNamespace::existing) {
+                    Ok(ns)
                 } else {
                     Err(e.into())
                 }
@@ -208,6 +205,84 @@ impl Client {
         }
     }
 
+    /// Create a namespace if it doesn't exist yet, and return it either way.
+    ///
+    /// Only a `bauplan.exceptions.NamespaceExistsError` counts as the
+    /// namespace already existing; anything else is raised, as with
+    /// `create_namespace`. Whether the namespace was created is logged to the
+    /// `bauplan` logger.
+    ///
+    /// Upon failure, raises `bauplan.exceptions.BauplanError`
+    ///
+    /// ```python
+    /// import bauplan
+    /// client = bauplan.Client()
+    ///
+    /// namespace = client.ensure_namespace('my_namespace_name', 'my_branch_name')
+    /// ```
+    ///
+    /// Parameters:
+    ///     namespace: The name of the namespace.
+    ///     branch: The name of the branch to create the namespace on. Defaults to the active branch.
+    ///     commit_body: Optional, the commit body to attach to the operation, if the namespace is created.
+    ///     commit_properties: Optional, a list of properties to attach to the commit, if the namespace is created.
+    /// Returns:
+    ///     The created or existing `bauplan.schema.Namespace` object.
+    ///
+    /// Raises:
+    ///     `bauplan.exceptions.CreateNamespaceForbiddenError`: if the user does not have access to create the namespace.
+    ///     `bauplan.exceptions.BranchNotFoundError`: if the branch does not exist.
+    ///     `bauplan.exceptions.NotABranchRefError`: if the object is not a branch.
+    ///     `bauplan.exceptions.NotAWriteBranchRefError`: if the destination branch is not a writable ref.
+    ///     `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+    ///     `ValueError`: if one or more parameters are invalid.
+    #[pyo3(signature = (
+        namespace: "str | Namespace",
+        branch: "str | Branch | None" = None,
+        *,
+        commit_body: "str | None" = None,
+        commit_properties: "dict[str, str] | None" = None,
+    ) -> "Namespace")]
+    fn ensure_namespace(
+        &self,
+        py: Python<'_>,
+        namespace: NamespaceArg,
+        branch: Option<BranchArg>,
+        commit_body: Option<&str>,
+        commit_properties: Option<BTreeMap<String, String>>,
+    ) -> PyResult<Namespace> {
+        let namespace = &namespace.0;
+        let branch = &self.branch_or_active(branch.map(|b| b.0));
+        let commit_properties = commit_properties.unwrap_or_default();
+        let properties = commit_properties
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+
+        let req = CreateNamespace {
+            name: namespace,
+            branch,
+            commit: CommitOptions {
+                body: commit_body,
+                properties,
+            },
+        };
+
+        match super::roundtrip(py, req, &self.profile, &self.agent()?) {
+            Ok(ns) => {
+                info!(namespace = ns.name, branch, "created namespace");
+                Ok(ns)
+            }
+            Err(e) => match e.api().and_then(CreateNamespace::existing) {
+                Some(ns) => {
+                    info!(namespace = ns.name, branch, "namespace already exists");
+                    Ok(ns)
+                }
+                None => Err(e.into()),
+            },
+        }
+    }
+
     /// Delete a namespace.
     ///
     /// Upon failure, raises `bauplan.exceptions.BauplanError`
@@ -224,7 +299,7 @@ impl Client {
     ///
     /// Parameters:
     ///     namespace: The name of the namespace to delete.
-    ///     branch: The name of the branch to delete the namespace from.
+    ///     branch: The name of the branch to delete the namespace from. Defaults to the active branch.
     ///     commit_body: Optional, the commit body to attach to the operation.
     ///     commit_properties: Optional, a list of properties to attach to the commit.
     ///     if_exists: If set to `True`, the namespace will not raise an error if it does not exist.
@@ -243,7 +318,7 @@ impl Client {
     ///     `ValueError`: if one or more parameters are invalid.
     #[pyo3(signature = (
         namespace: "str | Namespace",
-        branch: "str | Branch",
+        branch: "str | Branch | None" = None,
         *,
         if_exists: "bool" = false,
         commit_body: "str | None" = None,
@@ -252,13 +327,13 @@ impl Client {
     fn delete_namespace(
         &self, py: Python<'_>,
         namespace: NamespaceArg,
-        branch: BranchArg,
+        branch: Option<BranchArg>,
         if_exists: bool,
         commit_body: Option<&str>,
         commit_properties: Option<BTreeMap<String, String>>,
     ) -> PyResult<CatalogRef> {
         let namespace = &namespace.0;
-        let branch = &branch.0;
+        let branch = &self.branch_or_active(branch.map(|b| b.0));
         let commit_properties = commit_properties.unwrap_or_default();
         let properties = commit_properties
             .iter()
@@ -274,12 +349,10 @@ impl Client {
             },
         };
 
-        match super::roundtrip(py, req, &self.profile, &self.agent) {
+        match super::roundtrip(py, req, &self.profile, &self.agent()?) {
             Ok(r) => Ok(r),
             Err(e) => {
-                if if_exists
-                    && let Some(ApiErrorKind::NamespaceNotFound { catalog_ref, .. }) = e.kind()
-                {
+                if if_exists && let Some(catalog_ref) = e.api().and_then(DeleteNamespace::missing) {
                     Ok(catalog_ref.clone())
                 } else {
                     Err(e.into())
@@ -304,7 +377,7 @@ impl Client {
     ///
     /// Parameters:
     ///     namespace: The name of the namespace to check.
-    ///     ref: The ref, branch name or tag name to check the namespace on.
+    ///     ref: The ref, branch name or tag name to check the namespace on. Defaults to the active branch.
     ///
     /// Returns:
     ///     A boolean for if the namespace exists.
@@ -316,15 +389,15 @@ impl Client {
     ///     `ValueError`: if one or more parameters are invalid.
     #[pyo3(signature = (
         namespace: "str | Namespace",
-        r#ref: "str | Ref",
+        r#ref: "str | Ref | None" = None,
     ) -> "bool")]
-    fn has_namespace(&self, py: Python<'_>, namespace: NamespaceArg, r#ref: RefArg) -> PyResult<bool> {
+    fn has_namespace(&self, py: Python<'_>, namespace: NamespaceArg, r#ref: Option<RefArg>) -> PyResult<bool> {
         let req = GetNamespace {
             name: &namespace.0,
-            at_ref: &r#ref.0,
+            at_ref: &self.ref_or_active(r#ref).resolve(py, self)?,
         };
 
-        match super::roundtrip(py, req, &self.profile, &self.agent) {
+        match super::roundtrip(py, req, &self.profile, &self.agent()?) {
             Ok(_) => Ok(true),
             Err(e) => {
                 if matches!(e.kind(), Some(ApiErrorKind::NamespaceNotFound { .. })) {
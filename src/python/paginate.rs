@@ -91,21 +91,23 @@ impl PyPaginator {
             return Ok(Some(item));
         }
 
-        let token = match state.pagination_token.take() {
-            Some(t) => t,
-            None => return Ok(None),
-        };
-
-        let remaining = state.limit.map(|l| l - state.off);
-        let (batch, token) = (state.fetch)(py, Some(&token), remaining)?;
-        state.batch = batch;
-        state.pagination_token = token;
-
-        if let Some(item) = state.batch.pop_front() {
-            state.off += 1;
-            Ok(Some(item))
-        } else {
-            Ok(None)
+        // A page can come back empty (for example, if the fetch function
+        // filtered it) without being the last one.
+        loop {
+            let token = match state.pagination_token.take() {
+                Some(t) => t,
+                None => return Ok(None),
+            };
+
+            let remaining = state.limit.map(|l| l - state.off);
+            let (batch, token) = (state.fetch)(py, Some(&token), remaining)?;
+            state.batch = batch;
+            state.pagination_token = token;
+
+            if let Some(item) = state.batch.pop_front() {
+                state.off += 1;
+                return Ok(Some(item));
+            }
         }
     }
 }
@@ -1,236 +1,300 @@
 //! Query operations.
 
+mod contract;
+mod endpoint;
 mod iter;
+mod params;
+mod reader;
+mod scan;
 
-use std::{collections::HashMap, fs::File, path::PathBuf, sync::Arc};
+use std::{collections::HashMap, ffi::CString, fs::File, io, path::PathBuf, sync::Arc};
 
 use arrow::{
-    array::{RecordBatch, RecordBatchWriter},
+    array::{RecordBatch, RecordBatchReader as _, RecordBatchWriter},
     datatypes::Schema,
 };
-use commanderpb::runner_event::Event as RunnerEvent;
-use futures::{Stream, TryStreamExt, future::Either};
-use polyglot_sql::{Expression, Parser, builder, expressions::TableRef};
-use pyo3::{IntoPyObjectExt, exceptions::PyValueError, prelude::*};
-use tracing::{debug, error, info};
-
-use bauplan_longbow::{BauplanPreset, iroh};
+use futures::{Stream, TryStreamExt, future::Either, stream};
+use pyo3::{
+    IntoPyObjectExt,
+    exceptions::{PyFileExistsError, PyValueError},
+    prelude::*,
+};
+use tracing::debug;
 
 use crate::{
-    flight,
-    grpc::{self, generated as commanderpb},
+    export,
+    grpc::job::Priority,
     python::{
         detach,
-        exceptions::{BauplanError, BauplanQueryError},
+        exceptions::{BauplanError, BauplanQueryError, RowLimitWarning},
         namespace::NamespaceArg,
         optional_on_off,
         refs::RefArg,
     },
+    query::{QueryError, QueryRun},
 };
 
+pub(crate) use endpoint::{QueryResultsEndpoint, fetch_query_results};
 pub(crate) use iter::BatchStreamRowIterator;
+use params::QueryParams;
+use reader::BatchStreamReader;
+use scan::{ScanFilter, scan_sql};
 
 use super::Client;
 
+/// The number of rows `scan` returns when no `limit` is given.
+const DEFAULT_SCAN_MAX_ROWS: u64 = 1_000_000;
+
+/// The number of rows `query_to_generator` yields when no `max_rows` is given.
+const DEFAULT_GENERATOR_MAX_ROWS: u64 = 100_000;
+
 fn query_err(e: impl std::fmt::Display) -> PyErr {
     BauplanQueryError::new_err(e.to_string())
 }
 
+impl From<QueryError> for PyErr {
+    fn from(err: QueryError) -> Self {
+        match err {
+            QueryError::Params(_) => PyValueError::new_err(err.to_string()),
+            QueryError::NoResults | QueryError::InvalidEndpoint(_) => {
+                BauplanError::new_err(err.to_string())
+            }
+            _ => query_err(err),
+        }
+    }
+}
+
+/// Resolves the row cap for methods that apply a safety limit unless the
+/// caller explicitly opts out. Returns the number of rows to fetch and, if the
+/// default applies, the limit to enforce with [truncate_with_warning]. One
+/// extra row is fetched in that case, so that truncation can be detected.
+fn safety_limit(
+    requested: Option<u64>,
+    unsafe_full_scan: bool,
+    default: u64,
+) -> (Option<u64>, Option<u64>) {
+    match requested {
+        Some(n) => (Some(n), None),
+        None if unsafe_full_scan => (None, None),
+        None => (Some(default + 1), Some(default)),
+    }
+}
+
+/// The file written by one of the `query_to_*_file` methods. Converting it
+/// with `str()` or `os.fspath()` gives the path of the file.
+#[pyclass(module = "bauplan", frozen, skip_from_py_object)]
+#[derive(Debug, Clone)]
+pub(crate) struct QueryFileResult {
+    /// The path of the file, with any placeholders expanded.
+    #[pyo3(get)]
+    path: PathBuf,
+    /// The number of rows written.
+    #[pyo3(get)]
+    rows_written: u64,
+    /// The size of the file, in bytes.
+    #[pyo3(get)]
+    bytes_written: u64,
+    /// The ID of the query job.
+    #[pyo3(get)]
+    job_id: String,
+}
+
+#[pymethods]
+impl QueryFileResult {
+    fn __repr__(&self) -> String {
+        format!(
+            "QueryFileResult(path={:?}, rows_written={}, bytes_written={}, job_id={:?})",
+            self.path, self.rows_written, self.bytes_written, self.job_id,
+        )
+    }
+
+    fn __str__(&self) -> &std::ffi::OsStr {
+        self.path.as_os_str()
+    }
+
+    fn __fspath__(&self) -> &std::ffi::OsStr {
+        self.path.as_os_str()
+    }
+}
+
+fn output_exists_err(path: &std::path::Path) -> PyErr {
+    PyFileExistsError::new_err(format!(
+        "{} already exists; pass overwrite=True to replace it",
+        path.display()
+    ))
+}
+
+/// Caps a stream of batches at `limit` rows, emitting a `RowLimitWarning` if
+/// any rows were dropped. If warnings are turned into errors, the stream
+/// fails instead.
+fn truncate_with_warning(
+    batches: impl Stream<Item = PyResult<RecordBatch>>,
+    limit: u64,
+) -> impl Stream<Item = PyResult<RecordBatch>> {
+    stream::try_unfold(Some((Box::pin(batches), limit)), move |state| async move {
+        let Some((mut batches, remaining)) = state else {
+            return Ok(None);
+        };
+
+        let Some(batch) = batches.try_next().await? else {
+            return Ok(None);
+        };
+
+        let rows = batch.num_rows() as u64;
+        if rows <= remaining {
+            return Ok(Some((batch, Some((batches, remaining - rows)))));
+        }
+
+        let message = format!(
+            "results truncated to the default limit of {limit} rows; pass a limit \
+                explicitly, or unsafe_full_scan=True to fetch all rows"
+        );
+        Python::attach(|py| {
+            let category = py.get_type::<RowLimitWarning>();
+            PyErr::warn(py, &category, &CString::new(message)?, 1)
+        })?;
+
+        Ok(Some((batch.slice(0, remaining as usize), None)))
+    })
+}
+
 impl Client {
     /// Submits a query and runs it to completion, canceling on timeout.
+    /// Returns the job ID along with the results.
     #[allow(clippy::too_many_arguments)]
     async fn run_query(
         &self,
         query: &str,
+        params: QueryParams,
         r#ref: Option<RefArg>,
         max_rows: Option<u64>,
         cache: Option<&str>,
         namespace: Option<&str>,
         args: HashMap<String, String>,
-        priority: Option<u32>,
+        priority: Option<Priority>,
+        job_name: Option<String>,
         client_timeout: Option<u64>,
-    ) -> PyResult<(Schema, impl Stream<Item = PyResult<RecordBatch>> + use<>)> {
-        let timeout = self.job_timeout(client_timeout);
-        let common = self.job_request_common(priority, args)?;
-        let cache = optional_on_off("cache", cache)?;
-
-        let req = commanderpb::QueryRunRequest {
-            job_request_common: Some(common),
-            r#ref: r#ref.map(|r| r.0),
-            sql_query: query.to_owned(),
-            cache: cache.unwrap_or_default().to_owned(),
-            namespace: namespace.map(str::to_owned),
-        };
-
-        let resp = self
-            .grpc
-            .clone()
-            .query_run(req)
-            .await
-            .map_err(query_err)?
-            .into_inner();
-
-        let Some(commanderpb::JobResponseCommon { job_id, .. }) = resp.job_response_common else {
-            return Err(query_err("response missing job ID"));
-        };
-
-        info!(job_id, "successfully planned query");
-
-        let mut req = tonic::Request::new(commanderpb::SubscribeLogsRequest {
-            job_id: job_id.clone(),
-        });
-        req.set_timeout(timeout);
-
-        let mut client = self.grpc.clone();
-        let stream = client.monitor_job(req, self.longbow_endpoint.clone());
-        futures::pin_mut!(stream);
-
-        let mut flight_event = None;
-        loop {
-            let event = match stream.try_next().await {
-                Ok(Some(ev)) => ev,
-                Ok(None) => break,
-                Err(e)
-                    if e.code() == tonic::Code::Cancelled
-                        || e.code() == tonic::Code::DeadlineExceeded =>
-                {
-                    error!(job_id, "query timed out, cancelling execution");
-                    self.cancel_query(&job_id).await?;
-                    return Err(query_err("query execution timed out"));
-                }
-                Err(e) => return Err(query_err(e)),
-            };
-
-            match event {
-                RunnerEvent::FlightServerStart(ev) => flight_event = Some(ev),
-                RunnerEvent::JobCompletion(completion) => {
-                    grpc::interpret_outcome(completion.outcome).map_err(query_err)?;
-                    break;
-                }
-                _ => (),
-            }
+    ) -> PyResult<(
+        String,
+        Schema,
+        impl Stream<Item = PyResult<RecordBatch>> + use<>,
+    )> {
+        let at_ref = self.ref_or_active(r#ref).resolve_blocking(self)?;
+        if let Some((_, id)) = crate::split_snapshot_ref(&at_ref) {
+            return Err(PyValueError::new_err(format!(
+                "can't read snapshot {id}: queries can only read a table at a ref, not as of a snapshot"
+            )));
         }
 
-        if let Some(artifact) = resp.result_artifact {
-            let public_key = iroh::PublicKey::try_from(artifact.server_public_key.as_slice())
-                .map_err(|_| query_err("invalid longbow public key"))?;
-            let preset = BauplanPreset::default();
-            let addr = iroh::EndpointAddr::new(public_key);
-            let addr = preset.add_relay_urls(addr);
-
-            let endpoint = self
-                .longbow_endpoint
-                .get_or_try_init(|| async {
-                    iroh::Endpoint::bind(BauplanPreset::default())
-                        .await
-                        .map_err(|_| query_err("failed to bind longbow endpoint"))
-                })
-                .await?;
+        let mut run = params
+            .bind(QueryRun::new(query))
+            .at_ref(at_ref)
+            .args(args)
+            .module_version(&self.module_version)
+            .timeout(self.job_timeout(client_timeout))
+            .flight_max_retries(self.flight_max_retries);
 
-            let (schema, batches) = tokio::time::timeout(timeout, async {
-                bauplan_longbow::fetch_query_results(
-                    endpoint,
-                    addr,
-                    &artifact.artifact_id,
-                    &artifact.auth_token,
-                    max_rows,
-                )
-                .await
-                .map_err(query_err)
-            })
-            .await
-            .map_err(|_| query_err("timed out fetching query results"))??;
-
-            let schema: Schema = schema.as_ref().clone();
-            let batches = flight::limit_rows(batches.map_err(query_err), max_rows);
-            return Ok((schema, Either::Left(batches)));
+        if let Some(cache) = optional_on_off("cache", cache)? {
+            run = run.cache(cache == "on");
+        }
+        if let Some(namespace) = namespace.or(self.profile.default_namespace.as_deref()) {
+            run = run.namespace(namespace);
+        }
+        if let Some(max_rows) = max_rows {
+            run = run.max_rows(max_rows);
+        }
+        if let Some(priority) = priority {
+            run = run.priority(priority);
+        }
+        if let Some(job_name) = job_name {
+            run = run.job_name(job_name);
         }
 
-        let Some(commanderpb::FlightServerStartEvent {
-            endpoint,
-            magic_token,
-            ..
-        }) = flight_event
-        else {
-            return Err(BauplanError::new_err(
-                "query completed, but no results available",
-            ));
-        };
-
-        let endpoint = if endpoint.starts_with("http://") || endpoint.starts_with("https://") {
-            endpoint
-        } else {
-            format!("https://{endpoint}")
-        };
-
-        let endpoint = endpoint
-            .parse()
-            .map_err(|_| BauplanError::new_err(format!("invalid flight endpoint: {endpoint}")))?;
-
-        let (schema, batches) =
-            flight::fetch_flight_results(endpoint, magic_token, timeout, max_rows, None)
-                .await
-                .map_err(|_| query_err("failed to fetch query results"))?;
+        let (job_id, schema, batches) = run
+            .run_with(&self.profile, self.grpc()?, self.longbow_endpoint()?)
+            .await?;
 
-        let batches = flight::limit_rows(batches.map_err(query_err), max_rows);
-        Ok((schema, Either::Right(batches)))
+        Ok((job_id, schema, batches.map_err(PyErr::from)))
     }
 
+    /// Runs a query and writes the results to `path`, which may contain
+    /// placeholders as described in [export].
     #[allow(clippy::too_many_arguments)]
     async fn query_to_file<T: RecordBatchWriter>(
         &self,
+        path: PathBuf,
+        overwrite: bool,
+        mkdir_parents: bool,
         query: &str,
+        params: QueryParams,
         r#ref: Option<RefArg>,
         max_rows: Option<u64>,
         cache: Option<&str>,
         namespace: Option<&str>,
         args: HashMap<String, String>,
-        priority: Option<u32>,
+        priority: Option<Priority>,
         client_timeout: Option<u64>,
-        open: impl FnOnce(Arc<Schema>) -> arrow::error::Result<T>,
-    ) -> PyResult<()> {
-        let (schema, batches) = self
+        open: impl FnOnce(File, Arc<Schema>) -> arrow::error::Result<T>,
+    ) -> PyResult<QueryFileResult> {
+        let template = path.to_str().filter(|s| export::is_output_path_template(s));
+
+        // Without placeholders, we can check for a collision before running
+        // the query.
+        if template.is_none() && !overwrite && path.exists() {
+            return Err(output_exists_err(&path));
+        }
+
+        let (job_id, schema, batches) = self
             .run_query(
                 query,
+                params,
                 r#ref,
                 max_rows,
                 cache,
                 namespace,
                 args,
                 priority,
+                None,
                 client_timeout,
             )
             .await?;
 
+        let path = match template {
+            Some(t) => export::expand_output_path(t, &job_id, &chrono::Local::now())
+                .map_err(|e| PyValueError::new_err(e.to_string()))?,
+            None => path,
+        };
+
+        let file = export::create_output_file(&path, overwrite, mkdir_parents).map_err(|e| {
+            if e.kind() == io::ErrorKind::AlreadyExists {
+                output_exists_err(&path)
+            } else {
+                e.into()
+            }
+        })?;
+
         futures::pin_mut!(batches);
-        let mut writer = open(Arc::new(schema)).map_err(query_err)?;
+        let mut writer = open(file, Arc::new(schema)).map_err(query_err)?;
 
+        let mut rows_written = 0;
         loop {
             let Some(batch) = batches.try_next().await? else {
                 break;
             };
 
+            rows_written += batch.num_rows() as u64;
             writer.write(&batch).map_err(query_err)?;
         }
 
         writer.close().map_err(query_err)?;
-        Ok(())
-    }
+        let bytes_written = std::fs::metadata(&path)?.len();
 
-    async fn cancel_query(&self, job_id: &str) -> PyResult<()> {
-        let req = commanderpb::CancelJobRequest {
-            job_id: Some(commanderpb::JobId {
-                id: job_id.to_owned(),
-                ..Default::default()
-            }),
-        };
-
-        if let Err(err) = self.grpc.clone().cancel(req).await {
-            error!(?err, "failed to cancel timed out query");
-            return Err(query_err(err));
-        }
-
-        Ok(())
+        Ok(QueryFileResult {
+            path,
+            rows_written,
+            bytes_written,
+            job_id,
+        })
     }
 }
 
@@ -256,22 +320,39 @@ impl Client {
     ///
     /// # efficiently cast the table to a pandas DataFrame
     /// df = my_table.to_pandas()
+    ///
+    /// # bind values to :name placeholders, instead of formatting them into the query
+    /// survivors = client.query(
+    ///     query='SELECT Name FROM bauplan.titanic WHERE Age > :age AND Sex IN :sexes',
+    ///     params={'age': 30, 'sexes': ['male', 'female']},
+    /// )
+
+    /// # or to ? placeholders, in order
+    /// survivors = client.query(
+    ///     query='SELECT Name FROM bauplan.titanic WHERE Age > ? AND Sex = ?',
+    ///     params=[30, 'female'],
+    /// )
     /// ```
     ///
     /// Parameters:
     ///     query: The Bauplan query to execute.
-    ///     ref: The ref, branch name or tag name to query from.
+    ///     params: Values for the placeholders in the query: a dict for `:name` placeholders, or a list for `?` placeholders, in order. Values may be `None`, `bool`, `int`, `float`, `str`, `bytes`, `datetime` or `date`, or a non-empty list of those for use with `IN`. They're escaped and substituted into the query before it's sent. Raises `ValueError` if a value can't be represented, or doesn't match a placeholder.
+    ///     ref: The ref, branch name or tag name to query from. Defaults to the active branch. Queries can't read a table as of a snapshot, so a ref like `main@4527150312935411733` raises `ValueError`.
     ///     max_rows: The maximum number of rows to return; default: `None` (no limit).
     ///     cache: Whether to enable or disable caching for the query.
     ///     namespace: The Namespace to run the query in. If not set, the query will be run in the default namespace for your account.
     ///     args: Additional arguments to pass to the query (default: None).
     ///     priority: Optional job priority (1-10, where 10 is highest priority).
     ///     client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+    ///     job_name: Optional human-friendly name for the job, sent as the `bauplan.job-name` job arg.
+    ///     expected_schema: If set, raise a `SchemaMismatchError` before fetching any rows if the results don't have this schema.
+    ///     ignore_nullability: Whether to ignore differences in nullability when checking `expected_schema` (default: `True`).
     /// Returns:
     ///     The query results as a `pyarrow.Table`.
     #[pyo3(signature = (
         query: "str",
         *,
+        params: "dict[str, typing.Any] | list[typing.Any] | None" = None,
         r#ref: "str | Ref | None" = None,
         max_rows: "int | None" = None,
         cache: "Literal['on', 'off'] | None" = None,
@@ -279,35 +360,50 @@ impl Client {
         args: "dict[str, str] | None" = None,
         priority: "int | None" = None,
         client_timeout: "int | None" = None,
+        job_name: "str | None" = None,
+        expected_schema: "pyarrow.Schema | None" = None,
+        ignore_nullability: "bool" = true,
     ) -> "pyarrow.Table")]
     #[allow(clippy::too_many_arguments)]
     fn query(
         &self,
         py: Python<'_>,
         query: &str,
+        params: Option<QueryParams>,
         r#ref: Option<RefArg>,
         max_rows: Option<u64>,
         cache: Option<&str>,
         namespace: Option<NamespaceArg>,
         args: Option<HashMap<String, String>>,
-        priority: Option<u32>,
+        priority: Option<Priority>,
         client_timeout: Option<u64>,
+        job_name: Option<String>,
+        expected_schema: Option<pyo3_arrow::PySchema>,
+        ignore_nullability: bool,
     ) -> Result<Py<PyAny>, PyErr> {
+        self.check_blocking(py, "query")?;
+
         let namespace = namespace.map(|a| a.0);
         let table = detach(py, async {
-            let (schema, stream) = self
+            let (_, schema, stream) = self
                 .run_query(
                     query,
+                    params.unwrap_or_default(),
                     r#ref,
                     max_rows,
                     cache,
                     namespace.as_deref(),
                     args.unwrap_or_default(),
                     priority,
+                    job_name,
                     client_timeout,
                 )
                 .await?;
 
+            if let Some(expected) = expected_schema {
+                contract::check_schema(&expected.into_inner(), &schema, ignore_nullability)?;
+            }
+
             let batches: Vec<RecordBatch> = stream.try_collect().await?;
             pyo3_arrow::PyTable::try_new(batches, Arc::new(schema))
         })?;
@@ -334,19 +430,22 @@ impl Client {
     ///
     /// Parameters:
     ///     query: The Bauplan query to execute.
-    ///     ref: The ref, branch name or tag name to query from.
-    ///     max_rows: The maximum number of rows to return; default: `None` (no limit).
+    ///     params: Values for the placeholders in the query, as in `query`.
+    ///     ref: The ref, branch name or tag name to query from. Defaults to the active branch.
+    ///     max_rows: The maximum number of rows to return; default: `None` (100,000 rows, unless `unsafe_full_scan` is set). A `RowLimitWarning` is emitted if the default limit truncates the results.
     ///     cache: Whether to enable or disable caching for the query.
     ///     namespace: The Namespace to run the query in. If not set, the query will be run in the default namespace for your account.
     ///     args: Additional arguments to pass to the query (default: `None`).
     ///     priority: Optional job priority (1-10, where 10 is highest priority).
     ///     client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+    ///     unsafe_full_scan: If `True` and `max_rows` is `None`, return all rows instead of applying the default limit.
     ///
     /// Yields:
     ///     A dictionary representing a row of query results.
     #[pyo3(signature = (
         query: "str",
         *,
+        params: "dict[str, typing.Any] | list[typing.Any] | None" = None,
         r#ref: "str | Ref | None" = None,
         max_rows: "int | None" = None,
         cache: "Literal['on', 'off'] | None" = None,
@@ -354,67 +453,194 @@ impl Client {
         args: "dict[str, str] | None" = None,
         priority: "int | None" = None,
         client_timeout: "int | None" = None,
+        unsafe_full_scan: "bool" = false,
     ) -> "typing.Iterator[dict[str, typing.Any]]")]
     #[allow(clippy::too_many_arguments)]
     fn query_to_generator(
         &self,
         py: Python<'_>,
         query: &str,
+        params: Option<QueryParams>,
         r#ref: Option<RefArg>,
         max_rows: Option<u64>,
         cache: Option<&str>,
         namespace: Option<NamespaceArg>,
         args: Option<HashMap<String, String>>,
-        priority: Option<u32>,
+        priority: Option<Priority>,
         client_timeout: Option<u64>,
+        unsafe_full_scan: bool,
     ) -> PyResult<Py<PyAny>> {
+        self.check_blocking(py, "query_to_generator")?;
+
         let namespace = namespace.map(|a| a.0);
-        let (_schema, batches) = detach(
+        let (max_rows, default_limit) =
+            safety_limit(max_rows, unsafe_full_scan, DEFAULT_GENERATOR_MAX_ROWS);
+        let (_, _schema, batches) = detach(
             py,
             self.run_query(
                 query,
+                params.unwrap_or_default(),
                 r#ref,
                 max_rows,
                 cache,
                 namespace.as_deref(),
                 args.unwrap_or_default(),
                 priority,
+                None,
                 client_timeout,
             ),
         )?;
 
+        let batches = match default_limit {
+            Some(n) => Either::Left(truncate_with_warning(batches, n)),
+            None => Either::Right(batches),
+        };
+
         BatchStreamRowIterator::new(Box::pin(batches)).into_py_any(py)
     }
 
+    /// Execute a SQL query and return the results as a `pyarrow.RecordBatchReader`,
+    /// which fetches each batch as it's read rather than collecting them all
+    /// first. The reader can be passed directly to DuckDB or polars, or to
+    /// anything else that supports the Arrow C stream interface.
+    ///
+    /// If the reader is closed or garbage collected before all the batches
+    /// have been read, the query job is cancelled.
+    ///
+    /// ```python
+    /// import bauplan
+    /// import duckdb
+    ///
+    /// client = bauplan.Client()
+    ///
+    /// reader = client.query_to_reader(
+    ///     query='SELECT Name, Age FROM bauplan.titanic',
+    ///     ref='my_ref_or_branch_name',
+    /// )
+    ///
+    /// duckdb.sql('SELECT avg(Age) FROM reader').show()
+    /// ```
+    ///
+    /// Parameters:
+    ///     query: The Bauplan query to execute.
+    ///     params: Values for the placeholders in the query, as in `query`.
+    ///     ref: The ref, branch name or tag name to query from. Defaults to the active branch.
+    ///     max_rows: The maximum number of rows to return; default: `None` (no limit).
+    ///     cache: Whether to enable or disable caching for the query.
+    ///     namespace: The Namespace to run the query in. If not set, the query will be run in the default namespace for your account.
+    ///     args: Additional arguments to pass to the query (default: None).
+    ///     priority: Optional job priority (1-10, where 10 is highest priority).
+    ///     client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+    ///     job_name: Optional human-friendly name for the job, sent as the `bauplan.job-name` job arg.
+    ///     expected_schema: If set, raise a `SchemaMismatchError` before fetching any rows if the results don't have this schema.
+    ///     ignore_nullability: Whether to ignore differences in nullability when checking `expected_schema` (default: `True`).
+    /// Returns:
+    ///     The query results as a `pyarrow.RecordBatchReader`. Errors while
+    ///     fetching results are raised from `read_next_batch()`.
+    #[pyo3(signature = (
+        query: "str",
+        *,
+        params: "dict[str, typing.Any] | list[typing.Any] | None" = None,
+        r#ref: "str | Ref | None" = None,
+        max_rows: "int | None" = None,
+        cache: "Literal['on', 'off'] | None" = None,
+        namespace: "str | Namespace | None" = None,
+        args: "dict[str, str] | None" = None,
+        priority: "int | None" = None,
+        client_timeout: "int | None" = None,
+        job_name: "str | None" = None,
+        expected_schema: "pyarrow.Schema | None" = None,
+        ignore_nullability: "bool" = true,
+    ) -> "pyarrow.RecordBatchReader")]
+    #[allow(clippy::too_many_arguments)]
+    fn query_to_reader(
+        &self,
+        py: Python<'_>,
+        query: &str,
+        params: Option<QueryParams>,
+        r#ref: Option<RefArg>,
+        max_rows: Option<u64>,
+        cache: Option<&str>,
+        namespace: Option<NamespaceArg>,
+        args: Option<HashMap<String, String>>,
+        priority: Option<Priority>,
+        client_timeout: Option<u64>,
+        job_name: Option<String>,
+        expected_schema: Option<pyo3_arrow::PySchema>,
+        ignore_nullability: bool,
+    ) -> PyResult<Py<PyAny>> {
+        self.check_blocking(py, "query_to_reader")?;
+
+        let namespace = namespace.map(|a| a.0);
+        let (job_id, schema, batches) = detach(
+            py,
+            self.run_query(
+                query,
+                params.unwrap_or_default(),
+                r#ref,
+                max_rows,
+                cache,
+                namespace.as_deref(),
+                args.unwrap_or_default(),
+                priority,
+                job_name,
+                client_timeout,
+            ),
+        )?;
+
+        let reader =
+            BatchStreamReader::new(Arc::new(schema), Box::pin(batches), job_id, self.grpc()?);
+        if let Some(expected) = expected_schema {
+            contract::check_schema(&expected.into_inner(), &reader.schema(), ignore_nullability)?;
+        }
+
+        let reader = pyo3_arrow::PyRecordBatchReader::new(Box::new(reader));
+        Ok(reader.into_pyarrow(py)?.unbind())
+    }
+
     /// Export the results of a SQL query to a file in Parquet format.
     ///
+    /// The path may contain `{job_id}`, `{date}` and `{time}` placeholders,
+    /// as well as `strftime` specifiers like `%Y`, which are expanded once the
+    /// query has been submitted.
+    ///
     /// ```python
     /// import bauplan
     /// client = bauplan.Client()
     ///
-    /// # query the table and iterate through the results one row at a time
-    /// client.query_to_parquet_file(
-    ///     path='/tmp/out.parquet',
+    /// # query the table and write the results to a parquet file
+    /// result = client.query_to_parquet_file(
+    ///     path='/tmp/out/{job_id}_{date}.parquet',
     ///     query='SELECT Name, Age FROM bauplan.titanic LIMIT 100',
     ///     ref='my_ref_or_branch_name',
     /// )
+    ///
+    /// print(result.path, result.rows_written)
     /// ```
     ///
     /// Parameters:
     ///     path: The name or path of the file parquet to write the results to.
     ///     query: The Bauplan query to execute.
-    ///     ref: The ref, branch name or tag name to query from.
+    ///     params: Values for the placeholders in the query, as in `query`.
+    ///     ref: The ref, branch name or tag name to query from. Defaults to the active branch.
     ///     max_rows: The maximum number of rows to return; default: `None` (no limit).
     ///     cache: Whether to enable or disable caching for the query.
     ///     namespace: The Namespace to run the query in. If not set, the query will be run in the default namespace for your account.
     ///     args: Additional arguments to pass to the query (default: None).
     ///     client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+    ///     overwrite: Whether to replace the file if it already exists; default: `False`.
+    ///     mkdir_parents: Whether to create any missing parent directories; default: `True`.
     /// Returns:
-    ///     The path of the file written.
+    ///     A `QueryFileResult` describing the file written. `str(result)` is the path of the file.
+    ///
+    /// Raises:
+    ///     `FileExistsError`: if the file exists and `overwrite` is not set.
+    ///     `ValueError`: if the path contains an unknown placeholder.
     #[pyo3(signature = (
         path: "str | pathlib.Path",
         query: "str",
         *,
+        params: "dict[str, typing.Any] | list[typing.Any] | None" = None,
         r#ref: "str | Ref | None" = None,
         max_rows: "int | None" = None,
         cache: "Literal['on', 'off'] | None" = None,
@@ -422,28 +648,39 @@ impl Client {
         args: "dict[str, str] | None" = None,
         priority: "int | None" = None,
         client_timeout: "int | None" = None,
-    ) -> "pathlib.Path")]
+        overwrite: "bool" = false,
+        mkdir_parents: "bool" = true,
+    ) -> "QueryFileResult")]
     #[allow(clippy::too_many_arguments)]
     fn query_to_parquet_file(
         &self,
         py: Python<'_>,
         path: PathBuf,
         query: &str,
+        params: Option<QueryParams>,
         r#ref: Option<RefArg>,
         max_rows: Option<u64>,
         cache: Option<&str>,
         namespace: Option<NamespaceArg>,
         args: Option<HashMap<String, String>>,
-        priority: Option<u32>,
+        priority: Option<Priority>,
         client_timeout: Option<u64>,
-    ) -> PyResult<PathBuf> {
+        overwrite: bool,
+        mkdir_parents: bool,
+    ) -> PyResult<QueryFileResult> {
+        self.check_blocking(py, "query_to_parquet_file")?;
+
         use parquet::arrow::ArrowWriter;
 
         let namespace = namespace.map(|a| a.0);
         detach(
             py,
             self.query_to_file(
+                path,
+                overwrite,
+                mkdir_parents,
                 query,
+                params.unwrap_or_default(),
                 r#ref,
                 max_rows,
                 cache,
@@ -451,45 +688,51 @@ impl Client {
                 args.unwrap_or_default(),
                 priority,
                 client_timeout,
-                |schema| {
-                    let file = File::create(&path)?;
-                    Ok(ArrowWriter::try_new(file, schema, None)?)
-                },
+                |file, schema| Ok(ArrowWriter::try_new(file, schema, None)?),
             ),
-        )?;
-
-        Ok(path)
+        )
     }
 
     /// Export the results of a SQL query to a file in CSV format.
     ///
+    /// The path may contain placeholders, as in `query_to_parquet_file`.
+    ///
     /// ```python
     /// import bauplan
     /// client = bauplan.Client()
     ///
-    /// # query the table and iterate through the results one row at a time
+    /// # query the table and write the results to a csv file
     /// client.query_to_csv_file(
     ///     path='/tmp/out.csv',
     ///     query='SELECT Name, Age FROM bauplan.titanic LIMIT 100',
     ///     ref='my_ref_or_branch_name',
+    ///     overwrite=True,
     /// )
     /// ```
     ///
     /// Parameters:
     ///     path: The name or path of the file csv to write the results to.
     ///     query: The Bauplan query to execute.
-    ///     ref: The ref, branch name or tag name to query from.
+    ///     params: Values for the placeholders in the query, as in `query`.
+    ///     ref: The ref, branch name or tag name to query from. Defaults to the active branch.
     ///     max_rows: The maximum number of rows to return; default: `None` (no limit).
     ///     cache: Whether to enable or disable caching for the query.
     ///     namespace: The Namespace to run the query in. If not set, the query will be run in the default namespace for your account.
     ///     args: Additional arguments to pass to the query (default: None).
     ///     client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+    ///     overwrite: Whether to replace the file if it already exists; default: `False`.
+    ///     mkdir_parents: Whether to create any missing parent directories; default: `True`.
     /// Returns:
-    ///     The path of the file written.
+    ///     A `QueryFileResult` describing the file written. `str(result)` is the path of the file.
+    ///
+    /// Raises:
+    ///     `FileExistsError`: if the file exists and `overwrite` is not set.
+    ///     `ValueError`: if the path contains an unknown placeholder.
     #[pyo3(signature = (
         path: "str | pathlib.Path",
         query: "str",
         *,
+        params: "dict[str, typing.Any] | list[typing.Any] | None" = None,
         r#ref: "str | Ref | None" = None,
         max_rows: "int | None" = None,
         cache: "Literal['on', 'off'] | None" = None,
@@ -497,28 +740,39 @@ impl Client {
         args: "dict[str, str] | None" = None,
         priority: "int | None" = None,
         client_timeout: "int | None" = None,
-    ) -> "pathlib.Path")]
+        overwrite: "bool" = false,
+        mkdir_parents: "bool" = true,
+    ) -> "QueryFileResult")]
     #[allow(clippy::too_many_arguments)]
     fn query_to_csv_file(
         &self,
         py: Python<'_>,
         path: PathBuf,
         query: &str,
+        params: Option<QueryParams>,
         r#ref: Option<RefArg>,
         max_rows: Option<u64>,
         cache: Option<&str>,
         namespace: Option<NamespaceArg>,
         args: Option<HashMap<String, String>>,
-        priority: Option<u32>,
+        priority: Option<Priority>,
         client_timeout: Option<u64>,
-    ) -> PyResult<PathBuf> {
+        overwrite: bool,
+        mkdir_parents: bool,
+    ) -> PyResult<QueryFileResult> {
+        self.check_blocking(py, "query_to_csv_file")?;
+
         use arrow_csv::WriterBuilder;
 
         let namespace = namespace.map(|a| a.0);
         detach(
             py,
             self.query_to_file(
+                path,
+                overwrite,
+                mkdir_parents,
                 query,
+                params.unwrap_or_default(),
                 r#ref,
                 max_rows,
                 cache,
@@ -526,23 +780,20 @@ impl Client {
                 args.unwrap_or_default(),
                 priority,
                 client_timeout,
-                |_| {
-                    let file = File::create(&path)?;
-                    Ok(WriterBuilder::new().with_header(true).build(file))
-                },
+                |file, _| Ok(WriterBuilder::new().with_header(true).build(file)),
             ),
-        )?;
-
-        Ok(path)
+        )
     }
 
     /// Export the results of a SQL query to a file in JSON format.
     ///
+    /// The path may contain placeholders, as in `query_to_parquet_file`.
+    ///
     /// ```python
     /// import bauplan
     /// client = bauplan.Client()
     ///
-    /// # query the table and iterate through the results one row at a time
+    /// # query the table and write the results to a json file
     /// client.query_to_json_file(
     ///     path='/tmp/out.json',
     ///     query='SELECT Name, Age FROM bauplan.titanic LIMIT 100',
@@ -553,19 +804,27 @@ impl Client {
     /// Parameters:
     ///     path: The name or path of the file json to write the results to.
     ///     query: The Bauplan query to execute.
+    ///     params: Values for the placeholders in the query, as in `query`.
     ///     file_format: The format to write the results in; default: `json`. Allowed values are 'json' and 'jsonl'.
-    ///     ref: The ref, branch name or tag name to query from.
+    ///     ref: The ref, branch name or tag name to query from. Defaults to the active branch.
     ///     max_rows: The maximum number of rows to return; default: `None` (no limit).
     ///     cache: Whether to enable or disable caching for the query.
     ///     namespace: The Namespace to run the query in. If not set, the query will be run in the default namespace for your account.
     ///     args: Additional arguments to pass to the query (default: None).
     ///     client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+    ///     overwrite: Whether to replace the file if it already exists; default: `False`.
+    ///     mkdir_parents: Whether to create any missing parent directories; default: `True`.
     /// Returns:
-    ///     The path of the file written.
+    ///     A `QueryFileResult` describing the file written. `str(result)` is the path of the file.
+    ///
+    /// Raises:
+    ///     `FileExistsError`: if the file exists and `overwrite` is not set.
+    ///     `ValueError`: if the path contains an unknown placeholder.
     #[pyo3(signature = (
         path: "str | pathlib.Path",
         query: "str",
         *,
+        params: "dict[str, typing.Any] | list[typing.Any] | None" = None,
         file_format: "Literal['json', 'jsonl']" = "json",
         r#ref: "str | Ref | None" = None,
         max_rows: "int | None" = None,
@@ -574,22 +833,29 @@ impl Client {
         args: "dict[str, str] | None" = None,
         priority: "int | None" = None,
         client_timeout: "int | None" = None,
-    ) -> "pathlib.Path")]
+        overwrite: "bool" = false,
+        mkdir_parents: "bool" = true,
+    ) -> "QueryFileResult")]
     #[allow(clippy::too_many_arguments)]
     fn query_to_json_file(
         &self,
         py: Python<'_>,
         path: PathBuf,
         query: &str,
+        params: Option<QueryParams>,
         file_format: &str,
         r#ref: Option<RefArg>,
         max_rows: Option<u64>,
         cache: Option<&str>,
         namespace: Option<NamespaceArg>,
         args: Option<HashMap<String, String>>,
-        priority: Option<u32>,
+        priority: Option<Priority>,
         client_timeout: Option<u64>,
-    ) -> PyResult<PathBuf> {
+        overwrite: bool,
+        mkdir_parents: bool,
+    ) -> PyResult<QueryFileResult> {
+        self.check_blocking(py, "query_to_json_file")?;
+
         use arrow::json::{ArrayWriter, LineDelimitedWriter};
 
         let namespace = namespace.map(|a| a.0);
@@ -607,7 +873,11 @@ impl Client {
             detach(
                 py,
                 self.query_to_file(
+                    path,
+                    overwrite,
+                    mkdir_parents,
                     query,
+                    params.unwrap_or_default(),
                     r#ref,
                     max_rows,
                     cache,
@@ -615,14 +885,18 @@ impl Client {
                     args.unwrap_or_default(),
                     priority,
                     client_timeout,
-                    |_| Ok(LineDelimitedWriter::new(File::create(&path)?)),
+                    |file, _| Ok(LineDelimitedWriter::new(file)),
                 ),
-            )?;
+            )
         } else {
             detach(
                 py,
                 self.query_to_file(
+                    path,
+                    overwrite,
+                    mkdir_parents,
                     query,
+                    params.unwrap_or_default(),
                     r#ref,
                     max_rows,
                     cache,
@@ -630,19 +904,20 @@ impl Client {
                     args.unwrap_or_default(),
                     priority,
                     client_timeout,
-                    |_| Ok(ArrayWriter::new(File::create(&path)?)),
+                    |file, _| Ok(ArrayWriter::new(file)),
                 ),
-            )?;
+            )
         }
-
-        Ok(path)
     }
 
     /// Execute a table scan (with optional filters) and return the results as an arrow Table.
     ///
-    /// Note that this function uses SQLGlot to compose a safe SQL query,
-    /// and then internally defer to the `query` function for the actual
-    /// scan.
+    /// Column and table names are always quoted. `filters` is either a SQL
+    /// expression, which is checked to be a single expression before any job
+    /// is submitted, or a list of `(column, op, value)` tuples like pyarrow's
+    /// dataset filters: a flat list is ANDed together, and a list of lists is
+    /// an OR of ANDs. The supported ops are `=`, `==`, `!=`, `<>`, `<`, `<=`,
+    /// `>`, `>=`, `in` and `not in`. The query is then run with `query`.
     /// ```python
     /// import bauplan
     /// client = bauplan.Client()
@@ -656,19 +931,30 @@ impl Client {
     ///     columns=['Name'],
     ///     filters='Age < 30',
     /// )
+    ///
+    /// # or as (column, op, value) tuples
+    /// my_table = client.scan(
+    ///     table='titanic',
+    ///     namespace='bauplan',
+    ///     columns=['Name'],
+    ///     filters=[('Age', '<', 30), ('Sex', '=', 'female')],
+    /// )
     /// ```
     ///
     /// Parameters:
     ///     table: The table to scan.
-    ///     ref: The ref, branch name or tag name to scan from.
+    ///     ref: The ref, branch name or tag name to scan from. Defaults to the active branch.
     ///     columns: The columns to return (default: `None`).
-    ///     filters: The filters to apply (default: `None`).
-    ///     limit: The maximum number of rows to return (default: `None`).
+    ///     filters: A SQL expression or a list of `(column, op, value)` tuples to filter rows by (default: `None`). Raises `ValueError` if it's invalid.
+    ///     limit: The maximum number of rows to return (default: `None`, which returns at most 1,000,000 rows unless `unsafe_full_scan` is set). A `RowLimitWarning` is emitted if the default limit truncates the results.
     ///     cache: Whether to enable or disable caching for the query.
     ///     namespace: The Namespace to run the scan in. If not set, the scan will be run in the default namespace for your account.
     ///     args: dict of arbitrary args to pass to the backend.
     ///     priority: Optional job priority (1-10, where 10 is highest priority).
     ///     client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+    ///     unsafe_full_scan: If `True` and `limit` is `None`, return the whole table instead of applying the default limit.
+    ///     expected_schema: If set, raise a `SchemaMismatchError` before fetching any rows if the results don't have this schema.
+    ///     ignore_nullability: Whether to ignore differences in nullability when checking `expected_schema` (default: `True`).
     /// Returns:
     ///     The scan results as a `pyarrow.Table`.
     #[pyo3(signature = (
@@ -676,13 +962,16 @@ impl Client {
         *,
         r#ref: "str | Ref | None" = None,
         columns: "list[str] | None" = None,
-        filters: "str | None" = None,
+        filters: "str | list[tuple[str, str, typing.Any]] | list[list[tuple[str, str, typing.Any]]] | None" = None,
         limit: "int | None" = None,
         cache: "Literal['on', 'off'] | None" = None,
         namespace: "str | Namespace | None" = None,
         args: "dict[str, str] | None" = None,
         priority: "int | None" = None,
         client_timeout: "int | None" = None,
+        unsafe_full_scan: "bool" = false,
+        expected_schema: "pyarrow.Schema | None" = None,
+        ignore_nullability: "bool" = true,
     ) -> "pyarrow.Table")]
     #[allow(clippy::too_many_arguments)]
     fn scan(
@@ -691,57 +980,64 @@ impl Client {
         table: &str,
         r#ref: Option<RefArg>,
         columns: Option<Vec<String>>,
-        filters: Option<&str>,
+        filters: Option<ScanFilter>,
         limit: Option<usize>,
         cache: Option<&str>,
         namespace: Option<NamespaceArg>,
         args: Option<HashMap<String, String>>,
-        priority: Option<u32>,
+        priority: Option<Priority>,
         client_timeout: Option<u64>,
+        unsafe_full_scan: bool,
+        expected_schema: Option<pyo3_arrow::PySchema>,
+        ignore_nullability: bool,
     ) -> PyResult<Py<PyAny>> {
-        let namespace = namespace.map(|a| a.0);
-        let table_expr = match namespace.as_deref() {
-            Some(ns) => TableRef::new_with_schema(table, ns),
-            None => TableRef::new(table),
-        };
-
-        let mut query = match columns.as_deref() {
-            Some([]) => return Err(PyValueError::new_err("Empty column list")),
-            Some(cols) => builder::select(cols.iter().map(String::as_str)),
-            _ => builder::select([builder::star()]),
-        };
+        self.check_blocking(py, "scan")?;
 
-        query = query.from_expr(builder::Expr(Expression::Table(Box::new(table_expr))));
-
-        if let Some(f) = filters {
-            let Some(expr) = parse_expr(f) else {
-                return Err(PyValueError::new_err("Invalid SQL filter"));
-            };
-
-            query = query.where_(builder::Expr(expr))
-        }
-
-        if let Some(n) = limit {
-            query = query.limit(n);
-        }
+        let namespace = namespace.map(|a| a.0);
+        let at_ref = self.ref_or_active(r#ref).resolve(py, self)?;
 
-        let sql = query.to_sql();
+        let sql = scan_sql(
+            table,
+            namespace.as_deref(),
+            columns.as_deref(),
+            filters.as_ref(),
+            limit,
+        )?;
         debug!(sql, "built SQL query");
 
+        // An explicit limit is part of the query itself. Otherwise, the default
+        // is enforced through max_rows, so the server stops sending data.
+        let (max_rows, default_limit) = if limit.is_some() {
+            (None, None)
+        } else {
+            safety_limit(None, unsafe_full_scan, DEFAULT_SCAN_MAX_ROWS)
+        };
+
         let table = detach(py, async {
-            let (schema, stream) = self
+            let (_, schema, stream) = self
                 .run_query(
                     &sql,
-                    r#ref,
-                    None,
+                    QueryParams::default(),
+                    Some(RefArg(at_ref)),
+                    max_rows,
                     cache,
                     namespace.as_deref(),
                     args.unwrap_or_default(),
                     priority,
+                    None,
                     client_timeout,
                 )
                 .await?;
 
+            if let Some(expected) = expected_schema {
+                contract::check_schema(&expected.into_inner(), &schema, ignore_nullability)?;
+            }
+
+            let stream = match default_limit {
+                Some(n) => Either::Left(truncate_with_warning(stream, n)),
+                None => Either::Right(stream),
+            };
+
             let batches: Vec<RecordBatch> = stream.try_collect().await?;
             pyo3_arrow::PyTable::try_new(batches, Arc::new(schema))
         })?;
@@ -749,19 +1045,3 @@ impl Client {
         Ok(table.into_pyarrow(py)?.unbind())
     }
 }
-
-// Adapted from polyglot_sql::builder::parse_expr (which panics).
-fn parse_expr(sql: &str) -> Option<Expression> {
-    let wrapped = format!("SELECT {}", sql);
-    let ast = Parser::parse_sql(&wrapped).ok()?;
-
-    if ast.len() != 1 {
-        return None;
-    }
-
-    if let Expression::Select(s) = ast.into_iter().next()? {
-        Some(s.expressions.into_iter().next()?)
-    } else {
-        None
-    }
-}
@@ -0,0 +1,116 @@
+//! Checks query results against a schema the caller expects.
+
+use std::fmt;
+
+use arrow::datatypes::{DataType, Schema};
+use pyo3::prelude::*;
+
+use crate::python::exceptions::SchemaMismatchError;
+
+/// A difference between an expected schema and the actual one.
+#[derive(Debug)]
+enum FieldMismatch {
+    Missing {
+        name: String,
+        data_type: DataType,
+    },
+    Extra {
+        name: String,
+        data_type: DataType,
+    },
+    Type {
+        name: String,
+        expected: DataType,
+        actual: DataType,
+    },
+    Nullability {
+        name: String,
+        expected: bool,
+    },
+}
+
+impl fmt::Display for FieldMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nullable = |n: bool| if n { "nullable" } else { "non-nullable" };
+
+        match self {
+            FieldMismatch::Missing { name, data_type } => {
+                write!(f, "missing column {name:?} ({data_type})")
+            }
+            FieldMismatch::Extra { name, data_type } => {
+                write!(f, "unexpected column {name:?} ({data_type})")
+            }
+            FieldMismatch::Type {
+                name,
+                expected,
+                actual,
+            } => write!(f, "column {name:?} has type {actual}, expected {expected}"),
+            FieldMismatch::Nullability { name, expected } => write!(
+                f,
+                "column {name:?} is {}, expected {}",
+                nullable(!expected),
+                nullable(*expected)
+            ),
+        }
+    }
+}
+
+/// Compares the fields of two schemas by name. Field order doesn't matter.
+fn compare(expected: &Schema, actual: &Schema, ignore_nullability: bool) -> Vec<FieldMismatch> {
+    let mut diffs = Vec::new();
+
+    for field in expected.fields() {
+        let name = field.name().clone();
+        let Ok(other) = actual.field_with_name(&name) else {
+            diffs.push(FieldMismatch::Missing {
+                name,
+                data_type: field.data_type().clone(),
+            });
+            continue;
+        };
+
+        if field.data_type() != other.data_type() {
+            diffs.push(FieldMismatch::Type {
+                name,
+                expected: field.data_type().clone(),
+                actual: other.data_type().clone(),
+            });
+        } else if !ignore_nullability && field.is_nullable() != other.is_nullable() {
+            diffs.push(FieldMismatch::Nullability {
+                name,
+                expected: field.is_nullable(),
+            });
+        }
+    }
+
+    for field in actual.fields() {
+        if expected.field_with_name(field.name()).is_err() {
+            diffs.push(FieldMismatch::Extra {
+                name: field.name().clone(),
+                data_type: field.data_type().clone(),
+            });
+        }
+    }
+
+    diffs
+}
+
+/// Raises a `SchemaMismatchError` listing the differences, if `actual` doesn't
+/// match `expected`.
+pub(super) fn check_schema(
+    expected: &Schema,
+    actual: &Schema,
+    ignore_nullability: bool,
+) -> PyResult<()> {
+    let diffs = compare(expected, actual, ignore_nullability);
+    if diffs.is_empty() {
+        return Ok(());
+    }
+
+    let mut msg = "query results don't match the expected schema:".to_owned();
+    for diff in diffs {
+        msg.push_str(&format!("\n  - {diff}"));
+    }
+
+    Err(SchemaMismatchError::new_err(msg))
+}
@@ -0,0 +1,226 @@
+//! Handing off the results of a finished query to another process.
+
+use std::{sync::Arc, time};
+
+use arrow::array::RecordBatch;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt as _;
+use pyo3::{exceptions::PyValueError, prelude::*};
+use tonic::Request;
+
+use crate::{
+    events::FlightServer,
+    flight::{self, ResultsEndpoint},
+    grpc::{
+        generated as commanderpb,
+        job::{Job, JobKind, JobState},
+    },
+    python::{detach, exceptions::BauplanError, job::JobArg},
+    query::QueryError,
+};
+
+use super::{Client, query_err};
+
+/// Where to fetch the results of a finished query over Arrow Flight, from
+/// `Client.get_query_endpoint`. Pass it to `bauplan.fetch_query_results`,
+/// which doesn't need an API key, to fetch the results in another process.
+///
+/// The token grants access to the results until they expire, to anyone who
+/// has it. Treat it like an API key: it's redacted from the `repr`, but
+/// `token` returns it as-is.
+#[pyclass(module = "bauplan", frozen, skip_from_py_object)]
+#[derive(Debug, Clone)]
+pub(crate) struct QueryResultsEndpoint(ResultsEndpoint);
+
+#[pymethods]
+impl QueryResultsEndpoint {
+    #[new]
+    #[pyo3(signature = (endpoint: "str", token: "str", expires_at: "datetime | None" = None))]
+    fn new(endpoint: &str, token: String, expires_at: Option<DateTime<Utc>>) -> PyResult<Self> {
+        let endpoint = ResultsEndpoint::new(endpoint, token, expires_at)
+            .map_err(|e| PyValueError::new_err(format!("invalid endpoint: {e}")))?;
+        Ok(Self(endpoint))
+    }
+
+    fn __repr__(&self) -> String {
+        let expires_at = match self.0.expires_at {
+            Some(t) => format!("{:?}", t.to_rfc3339()),
+            None => "None".to_owned(),
+        };
+
+        format!(
+            "QueryResultsEndpoint(endpoint={:?}, token=\"<redacted>\", expires_at={expires_at})",
+            self.0.endpoint.to_string(),
+        )
+    }
+
+    /// The address of the flight server.
+    #[getter]
+    fn endpoint(&self) -> String {
+        self.0.endpoint.to_string()
+    }
+
+    /// The token to authenticate with.
+    #[getter]
+    fn token(&self) -> &str {
+        &self.0.auth_token
+    }
+
+    /// When the results stop being available, if known.
+    #[getter]
+    fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.0.expires_at
+    }
+
+    /// Whether the results have expired.
+    #[getter]
+    fn expired(&self) -> bool {
+        self.0.is_expired(Utc::now())
+    }
+}
+
+/// Fetch the results of a query from an endpoint returned by
+/// `Client.get_query_endpoint`. This doesn't need an API key, so it can be
+/// called from a process that only has the endpoint.
+///
+/// ```python
+/// #! endpoint: bauplan.QueryResultsEndpoint = ...  # type: ignore[assignment]
+/// import bauplan
+///
+/// table = bauplan.fetch_query_results(endpoint)
+/// ```
+///
+/// Parameters:
+///     endpoint: The endpoint to fetch the results from.
+///     max_rows: The maximum number of rows to return; default: `None` (no limit).
+///     timeout: seconds to wait for the results. Defaults to 30 seconds.
+///
+/// Returns:
+///     The results, as a `pyarrow.Table`.
+///
+/// Raises:
+///     `bauplan.exceptions.BauplanError`: if the results have expired.
+///     `bauplan.exceptions.BauplanQueryError`: if the results can't be fetched.
+#[pyfunction]
+#[pyo3(signature = (
+    endpoint: "QueryResultsEndpoint",
+    *,
+    max_rows: "int | None" = None,
+    timeout: "int" = 30,
+) -> "pyarrow.Table")]
+pub(crate) fn fetch_query_results(
+    py: Python<'_>,
+    endpoint: pyo3::PyRef<'_, QueryResultsEndpoint>,
+    max_rows: Option<u64>,
+    timeout: u64,
+) -> PyResult<Py<PyAny>> {
+    if endpoint.0.is_expired(Utc::now()) {
+        return Err(BauplanError::new_err("the query results have expired"));
+    }
+
+    let endpoint = endpoint.0.clone();
+    let table = detach(py, async {
+        let (schema, batches) = endpoint
+            .fetch(
+                time::Duration::from_secs(timeout),
+                max_rows,
+                flight::DEFAULT_MAX_RETRIES,
+            )
+            .await
+            .map_err(|e| PyErr::from(QueryError::from(e)))?;
+
+        let batches: Vec<RecordBatch> = batches.try_collect().await.map_err(query_err)?;
+        pyo3_arrow::PyTable::try_new(batches, Arc::new(schema))
+    })?;
+
+    Ok(table.into_pyarrow(py)?.unbind())
+}
+
+#[pymethods]
+impl Client {
+    /// Get where to fetch the results of a completed query, so that they
+    /// can be fetched by another process with `bauplan.fetch_query_results`.
+    ///
+    /// The returned endpoint includes a token that grants access to the
+    /// results until they expire, without an API key. Only hand it to
+    /// processes you'd trust with the results, and don't log it. Results
+    /// can only be fetched once; the server shuts down after serving them.
+    ///
+    /// ```python
+    /// #! my_job: bauplan.schema.Job = ...  # type: ignore[assignment]
+    /// import bauplan
+    /// client = bauplan.Client()
+    ///
+    /// endpoint = client.get_query_endpoint(my_job)
+    /// print(endpoint.endpoint, endpoint.expires_at)
+    /// ```
+    ///
+    /// Parameters:
+    ///     job: A job ID or Job instance, for a completed query.
+    ///
+    /// Returns:
+    ///     A `bauplan.QueryResultsEndpoint`.
+    ///
+    /// Raises:
+    ///     `ValueError`: if the job isn't a query.
+    ///     `bauplan.exceptions.BauplanError`: if the query hasn't completed, or its results weren't served over Arrow Flight.
+    #[pyo3(signature = (job: "str | Job", /) -> "QueryResultsEndpoint")]
+    fn get_query_endpoint(&self, py: Python<'_>, job: JobArg) -> PyResult<QueryResultsEndpoint> {
+        let job_id = job.0;
+
+        let mut jobs_req = Request::new(commanderpb::GetJobsRequest {
+            job_ids: vec![job_id.clone()],
+            all_users: true,
+            ..Default::default()
+        });
+        jobs_req.set_timeout(self.client_timeout);
+
+        let mut ctx_req = Request::new(commanderpb::GetJobContextRequest {
+            job_ids: vec![job_id.clone()],
+            include_logs: true,
+            ..Default::default()
+        });
+        ctx_req.set_timeout(self.client_timeout);
+
+        let mut grpc = self.grpc()?;
+        let (jobs, contexts) = detach(py, async {
+            let jobs = grpc.get_jobs(jobs_req).await?.into_inner().jobs;
+            let contexts = grpc.get_job_context(ctx_req).await?.into_inner();
+            Ok::<_, tonic::Status>((jobs, contexts.job_contexts))
+        })
+        .map_err(|e| BauplanError::new_err(e.to_string()))?;
+
+        let Some(job) = jobs.into_iter().next().map(Job::from) else {
+            return Err(BauplanError::new_err(format!("job not found: {job_id}")));
+        };
+
+        if job.kind != JobKind::Query {
+            return Err(PyValueError::new_err(format!(
+                "job {job_id} is a {}, not a query",
+                job.kind
+            )));
+        } else if job.status != JobState::Complete {
+            return Err(BauplanError::new_err(format!(
+                "query {job_id} hasn't completed (status: {})",
+                job.status
+            )));
+        }
+
+        let server = contexts
+            .into_iter()
+            .flat_map(|ctx| ctx.job_events)
+            .find_map(|ev| match ev.event? {
+                commanderpb::runner_event::Event::FlightServerStart(ev) => Some(ev),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                BauplanError::new_err(format!(
+                    "the results of query {job_id} weren't served over Arrow Flight"
+                ))
+            })?;
+
+        let endpoint = ResultsEndpoint::from_server(FlightServer::from(server), job.finished_at)
+            .map_err(|e| BauplanError::new_err(format!("invalid results location: {e}")))?;
+        Ok(QueryResultsEndpoint(endpoint))
+    }
+}
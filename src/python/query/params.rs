@@ -0,0 +1,131 @@
+//! Converting Python values to query parameters.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Utc};
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyBytes, PyDict, PyInt, PyList, PyTuple},
+};
+
+use crate::query::{QueryRun, SqlValue};
+
+/// Values for a query's placeholders: a dict for `:name` placeholders, or a
+/// list or tuple for `?` placeholders.
+#[derive(Default)]
+pub(crate) enum QueryParams {
+    #[default]
+    None,
+    Named(HashMap<String, SqlValue>),
+    Positional(Vec<SqlValue>),
+}
+
+impl QueryParams {
+    pub(crate) fn bind(self, run: QueryRun) -> QueryRun {
+        match self {
+            QueryParams::None => run,
+            QueryParams::Named(params) => run.params(params),
+            QueryParams::Positional(values) => run.positional_params(values),
+        }
+    }
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for QueryParams {
+    type Error = PyErr;
+
+    fn extract(ob: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        // Errors name the parameter, since the value alone may not make it
+        // obvious which one is wrong.
+        let with_name = |placeholder: &str, err: PyErr| {
+            let py = ob.py();
+            let msg = format!("invalid value for {placeholder}: {}", err.value(py));
+            if err.is_instance_of::<PyTypeError>(py) {
+                PyTypeError::new_err(msg)
+            } else {
+                PyValueError::new_err(msg)
+            }
+        };
+
+        if let Ok(dict) = ob.cast::<PyDict>() {
+            let mut params = HashMap::with_capacity(dict.len());
+            for (k, v) in dict.iter() {
+                let name: String = k.extract()?;
+                let value = v
+                    .extract::<SqlParam>()
+                    .map_err(|e| with_name(&format!("parameter :{name}"), e))?;
+                params.insert(name, value.0);
+            }
+
+            Ok(QueryParams::Named(params))
+        } else if ob.is_instance_of::<PyList>() || ob.is_instance_of::<PyTuple>() {
+            let values = ob
+                .try_iter()?
+                .enumerate()
+                .map(|(i, v)| {
+                    let value = v?
+                        .extract::<SqlParam>()
+                        .map_err(|e| with_name(&format!("? placeholder {}", i + 1), e))?;
+                    Ok(value.0)
+                })
+                .collect::<PyResult<_>>()?;
+
+            Ok(QueryParams::Positional(values))
+        } else {
+            Err(PyTypeError::new_err(format!(
+                "params must be a dict or a list, not {}",
+                ob.get_type().name()?
+            )))
+        }
+    }
+}
+
+/// A value for a placeholder, from `None`, a `bool`, `int`, `float`,
+/// `str`, `bytes`, `datetime`, `date`, or a list or tuple of those.
+pub(crate) struct SqlParam(pub(crate) SqlValue);
+
+impl<'a, 'py> FromPyObject<'a, 'py> for SqlParam {
+    type Error = PyErr;
+
+    fn extract(ob: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        // bool is a subclass of int, and datetime of date, so the order
+        // matters.
+        let value = if ob.is_none() {
+            SqlValue::Null
+        } else if let Ok(v) = ob.extract::<bool>() {
+            SqlValue::Bool(v)
+        } else if let Ok(v) = ob.extract::<i64>() {
+            SqlValue::Int(v)
+        } else if ob.is_instance_of::<PyInt>() {
+            // Rather than losing precision as a float.
+            return Err(PyValueError::new_err(
+                "integers must fit in a signed 64-bit integer",
+            ));
+        } else if let Ok(v) = ob.extract::<f64>() {
+            SqlValue::Float(v)
+        } else if let Ok(v) = ob.extract::<String>() {
+            SqlValue::Str(v)
+        } else if let Ok(v) = ob.cast::<PyBytes>() {
+            SqlValue::Bytes(v.as_bytes().to_vec())
+        } else if let Ok(v) = ob.extract::<DateTime<FixedOffset>>() {
+            SqlValue::Timestamp(v.with_timezone(&Utc))
+        } else if let Ok(v) = ob.extract::<NaiveDateTime>() {
+            SqlValue::NaiveTimestamp(v)
+        } else if let Ok(v) = ob.extract::<NaiveDate>() {
+            SqlValue::Date(v)
+        } else if ob.is_instance_of::<PyList>() || ob.is_instance_of::<PyTuple>() {
+            let values = ob
+                .try_iter()?
+                .map(|v| Ok(v?.extract::<SqlParam>()?.0))
+                .collect::<PyResult<_>>()?;
+            SqlValue::List(values)
+        } else {
+            return Err(PyTypeError::new_err(format!(
+                "unsupported query parameter type: {}",
+                ob.get_type().name()?
+            )));
+        };
+
+        Ok(SqlParam(value))
+    }
+}
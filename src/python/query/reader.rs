@@ -0,0 +1,99 @@
+use std::pin::Pin;
+
+use arrow::{
+    array::{RecordBatch, RecordBatchReader},
+    datatypes::SchemaRef,
+    error::ArrowError,
+};
+use futures::{Stream, TryStreamExt};
+use pyo3::prelude::*;
+use tracing::{debug, warn};
+
+use crate::{
+    grpc::{self, generated as commanderpb},
+    python::{detach, rt},
+};
+
+type BatchStream = Pin<Box<dyn Stream<Item = Result<RecordBatch, PyErr>> + Send>>;
+
+/// A [RecordBatchReader] over the results of a query, fetching each batch
+/// as it's read. If it's dropped before the results are exhausted, the
+/// query job is cancelled.
+pub(crate) struct BatchStreamReader {
+    schema: SchemaRef,
+    stream: Option<BatchStream>,
+    job_id: String,
+    grpc: grpc::Client,
+}
+
+impl BatchStreamReader {
+    pub(crate) fn new(
+        schema: SchemaRef,
+        stream: BatchStream,
+        job_id: String,
+        grpc: grpc::Client,
+    ) -> Self {
+        Self {
+            schema,
+            stream: Some(stream),
+            job_id,
+            grpc,
+        }
+    }
+}
+
+impl Iterator for BatchStreamReader {
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let stream = self.stream.as_mut()?;
+
+        // pyarrow may or may not hold the GIL while reading, so take it
+        // (to flush logs) and release it while the batch is fetched.
+        let res = Python::attach(|py| detach(py, stream.try_next()));
+        match res {
+            Ok(Some(batch)) => Some(Ok(batch)),
+            Ok(None) => {
+                self.stream = None;
+                None
+            }
+            Err(e) => {
+                self.stream = None;
+                Some(Err(ArrowError::ExternalError(Box::new(e))))
+            }
+        }
+    }
+}
+
+impl RecordBatchReader for BatchStreamReader {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl Drop for BatchStreamReader {
+    fn drop(&mut self) {
+        if self.stream.take().is_none() {
+            return;
+        }
+
+        debug!(
+            job_id = self.job_id,
+            "query reader dropped early, cancelling job"
+        );
+        let mut grpc = self.grpc.clone();
+        let req = commanderpb::CancelJobRequest {
+            job_id: Some(commanderpb::JobId {
+                id: std::mem::take(&mut self.job_id),
+                ..Default::default()
+            }),
+        };
+
+        // This may run with the GIL held, so don't wait for it.
+        rt().spawn(async move {
+            if let Err(err) = grpc.cancel(req).await {
+                warn!(?err, "failed to cancel query job");
+            }
+        });
+    }
+}
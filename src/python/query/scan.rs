@@ -0,0 +1,313 @@
+//! Composing the SQL for `Client.scan`.
+//!
+//! Identifiers are always quoted. A filter string is checked to be a single
+//! expression before it's spliced into the query, and the whole statement is
+//! parsed again afterwards; structured filters are rendered with the same
+//! literals as query parameters.
+
+use std::{collections::HashMap, fmt::Write as _};
+
+use polyglot_sql::{Expression, Parser};
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyList, PyString, PyTuple},
+};
+
+use super::params::SqlParam;
+use crate::query::{SqlValue, bind_params};
+
+/// The `filters` argument of `scan`: either a SQL expression, or a list of
+/// `(column, op, value)` tuples in disjunctive normal form, like pyarrow's
+/// dataset filters. A flat list is ANDed together; a list of lists is an OR
+/// of ANDs.
+pub(crate) enum ScanFilter {
+    Sql(String),
+    Clauses(Vec<Vec<Clause>>),
+}
+
+pub(crate) struct Clause {
+    column: String,
+    op: String,
+    value: SqlValue,
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for ScanFilter {
+    type Error = PyErr;
+
+    fn extract(ob: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        if let Ok(s) = ob.cast::<PyString>() {
+            return Ok(ScanFilter::Sql(s.to_str()?.to_owned()));
+        }
+
+        let Ok(list) = ob.cast::<PyList>() else {
+            return Err(PyTypeError::new_err(
+                "filters must be a string or a list of (column, op, value) tuples",
+            ));
+        };
+
+        if list.is_empty() {
+            return Err(PyValueError::new_err("Empty filter list"));
+        }
+
+        // A list of lists is an OR of ANDs.
+        if list.get_item(0)?.is_instance_of::<PyList>() {
+            let groups = list
+                .iter()
+                .map(|group| {
+                    let group = group.cast_into::<PyList>().map_err(|_| {
+                        PyTypeError::new_err("filters can't mix tuples and lists of tuples")
+                    })?;
+                    if group.is_empty() {
+                        return Err(PyValueError::new_err("Empty filter list"));
+                    }
+                    group.iter().map(|c| extract_clause(&c)).collect()
+                })
+                .collect::<PyResult<_>>()?;
+            return Ok(ScanFilter::Clauses(groups));
+        }
+
+        let group = list
+            .iter()
+            .map(|c| extract_clause(&c))
+            .collect::<PyResult<_>>()?;
+        Ok(ScanFilter::Clauses(vec![group]))
+    }
+}
+
+fn extract_clause(ob: &Bound<'_, PyAny>) -> PyResult<Clause> {
+    let Ok(tuple) = ob.cast::<PyTuple>() else {
+        return Err(PyTypeError::new_err(
+            "filters must be (column, op, value) tuples",
+        ));
+    };
+
+    let (column, op, value): (String, String, SqlParam) = tuple.extract()?;
+    Ok(Clause {
+        column,
+        op: op.to_ascii_lowercase(),
+        value: value.0,
+    })
+}
+
+/// Composes `SELECT <columns> FROM <table> [WHERE <filter>] [LIMIT <n>]`.
+///
+/// If `namespace` isn't set, a dotted table name like `ns.table` is split
+/// on the first dot.
+pub(crate) fn scan_sql(
+    table: &str,
+    namespace: Option<&str>,
+    columns: Option<&[String]>,
+    filter: Option<&ScanFilter>,
+    limit: Option<usize>,
+) -> PyResult<String> {
+    let mut sql = String::from("SELECT ");
+    match columns {
+        Some([]) => return Err(PyValueError::new_err("Empty column list")),
+        Some(cols) => {
+            for (i, col) in cols.iter().enumerate() {
+                if i > 0 {
+                    sql.push_str(", ");
+                }
+                sql.push_str(&quote_ident(col)?);
+            }
+        }
+        None => sql.push('*'),
+    }
+
+    sql.push_str(" FROM ");
+    let (namespace, table) = match namespace {
+        Some(ns) => (Some(ns), table),
+        None => match table.split_once('.') {
+            Some((ns, table)) => (Some(ns), table),
+            None => (None, table),
+        },
+    };
+    if let Some(ns) = namespace {
+        sql.push_str(&quote_ident(ns)?);
+        sql.push('.');
+    }
+    sql.push_str(&quote_ident(table)?);
+
+    match filter {
+        Some(ScanFilter::Sql(expr)) => {
+            check_filter(expr).map_err(|reason| {
+                PyValueError::new_err(format!("Invalid SQL filter {expr:?}: {reason}"))
+            })?;
+            write!(sql, " WHERE ({expr})").unwrap();
+        }
+        Some(ScanFilter::Clauses(groups)) => {
+            sql.push_str(" WHERE ");
+            sql.push_str(&render_clauses(groups)?);
+        }
+        None => (),
+    }
+
+    if let Some(n) = limit {
+        write!(sql, " LIMIT {n}").unwrap();
+    }
+
+    // The filter has already been checked on its own, so this is a
+    // backstop: whatever was spliced in, the result is one SELECT.
+    match Parser::parse_sql(&sql) {
+        Ok(ast) if ast.len() == 1 && matches!(ast[0], Expression::Select(_)) => Ok(sql),
+        _ => Err(PyValueError::new_err(
+            "Invalid scan: the composed query isn't a single SELECT",
+        )),
+    }
+}
+
+/// Quotes an identifier, doubling any quotes inside it.
+fn quote_ident(name: &str) -> PyResult<String> {
+    if name.is_empty() {
+        return Err(PyValueError::new_err("Identifiers can't be empty"));
+    }
+
+    if name.contains('\0') {
+        return Err(PyValueError::new_err(
+            "Identifiers can't contain NUL characters",
+        ));
+    }
+
+    Ok(format!("\"{}\"", name.replace('"', "\"\"")))
+}
+
+/// Checks that a filter string is a single expression that can be wrapped
+/// in parentheses without changing the rest of the query: no statement
+/// separators or comments, balanced parentheses, and closed quotes.
+fn check_filter(expr: &str) -> Result<(), &'static str> {
+    if expr.trim().is_empty() {
+        return Err("the filter is empty");
+    }
+
+    let mut chars = expr.chars().peekable();
+    let mut quote = None;
+    let mut depth = 0usize;
+    while let Some(c) = chars.next() {
+        if c.is_control() && !matches!(c, '\t' | '\n' | '\r') {
+            return Err("control characters aren't allowed");
+        }
+
+        if let Some(q) = quote {
+            if c == q {
+                // A doubled quote is an escaped one.
+                if chars.peek() == Some(&q) {
+                    chars.next();
+                } else {
+                    quote = None;
+                }
+            }
+            continue;
+        }
+
+        match (c, chars.peek().copied()) {
+            ('\'' | '"', _) => quote = Some(c),
+            (';', _) => return Err("multiple statements aren't allowed"),
+            ('-', Some('-')) | ('/', Some('*')) => return Err("comments aren't allowed"),
+            ('$', _) => return Err("parameters and dollar-quoted strings aren't allowed"),
+            ('(', _) => depth += 1,
+            (')', _) => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or("the parentheses are unbalanced")?;
+            }
+            _ => (),
+        }
+    }
+
+    if quote.is_some() {
+        return Err("a quoted string or identifier isn't closed");
+    }
+
+    if depth != 0 {
+        return Err("the parentheses are unbalanced");
+    }
+
+    if !is_single_expr(expr) {
+        return Err("expected a single expression");
+    }
+
+    Ok(())
+}
+
+// Adapted from polyglot_sql::builder::parse_expr (which panics).
+fn is_single_expr(sql: &str) -> bool {
+    let wrapped = format!("SELECT {}", sql);
+    let Ok(ast) = Parser::parse_sql(&wrapped) else {
+        return false;
+    };
+
+    match ast.as_slice() {
+        [Expression::Select(s)] => s.expressions.len() == 1,
+        _ => false,
+    }
+}
+
+/// Renders structured filters as `(a AND b) OR (c AND d)`, binding each
+/// value as a parameter so it gets the same literal syntax as in `query`.
+fn render_clauses(groups: &[Vec<Clause>]) -> PyResult<String> {
+    let mut sql = String::new();
+    let mut params = HashMap::new();
+    for (i, group) in groups.iter().enumerate() {
+        if i > 0 {
+            sql.push_str(" OR ");
+        }
+
+        sql.push('(');
+        for (j, clause) in group.iter().enumerate() {
+            if j > 0 {
+                sql.push_str(" AND ");
+            }
+
+            let column = quote_ident(&clause.column)?;
+            let name = format!("p{}", params.len());
+            let op = match (clause.op.as_str(), &clause.value) {
+                ("=" | "==", SqlValue::Null) => {
+                    write!(sql, "{column} IS NULL").unwrap();
+                    continue;
+                }
+                ("!=" | "<>", SqlValue::Null) => {
+                    write!(sql, "{column} IS NOT NULL").unwrap();
+                    continue;
+                }
+                (_, SqlValue::Null) => {
+                    return Err(PyValueError::new_err(format!(
+                        "Invalid filter on {:?}: None can only be compared with = or !=",
+                        clause.column
+                    )));
+                }
+                ("in", SqlValue::List(_)) => "IN",
+                ("not in", SqlValue::List(_)) => "NOT IN",
+                ("in" | "not in", _) => {
+                    return Err(PyValueError::new_err(format!(
+                        "Invalid filter on {:?}: {:?} expects a list of values",
+                        clause.column, clause.op
+                    )));
+                }
+                (_, SqlValue::List(_)) => {
+                    return Err(PyValueError::new_err(format!(
+                        "Invalid filter on {:?}: {:?} expects a single value",
+                        clause.column, clause.op
+                    )));
+                }
+                ("=" | "==", _) => "=",
+                ("!=" | "<>", _) => "!=",
+                ("<", _) => "<",
+                ("<=", _) => "<=",
+                (">", _) => ">",
+                (">=", _) => ">=",
+                (op, _) => {
+                    return Err(PyValueError::new_err(format!(
+                        "Invalid filter operator {op:?}: expected one of =, ==, !=, <>, <, <=, >, >=, in, not in"
+                    )));
+                }
+            };
+
+            write!(sql, "{column} {op} :{name}").unwrap();
+            params.insert(name, clause.value.clone());
+        }
+        sql.push(')');
+    }
+
+    bind_params(&sql, &params).map_err(|e| PyValueError::new_err(e.to_string()))
+}
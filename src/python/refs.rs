@@ -1,15 +1,19 @@
 //! Ref types for the Python SDK.
 
+use std::hash::{DefaultHasher, Hash as _, Hasher as _};
+
 use pyo3::Borrowed;
-use pyo3::exceptions::PyTypeError;
+use pyo3::exceptions::{PyTypeError, PyValueError};
 use pyo3::prelude::*;
 
-use crate::CatalogRef;
 use crate::branch::Branch;
 use crate::tag::Tag;
+use crate::{ApiRequest as _, ApiResponse, CatalogRef, RefSpec};
+
+use super::{Client, ClientError};
 
 /// The type of a ref.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[pyclass(
     name = "RefType",
     module = "bauplan.schema",
@@ -36,7 +40,10 @@ impl std::fmt::Display for PyRefType {
 }
 
 /// A reference to a branch, tag, or commit, as returned by API operations.
-#[derive(Debug, Clone)]
+///
+/// Refs can also be constructed directly, to pass to methods that take a ref
+/// without looking it up first.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[pyclass(
     name = "Ref",
     module = "bauplan.schema",
@@ -48,14 +55,14 @@ impl std::fmt::Display for PyRefType {
 pub struct PyRef {
     /// The name of the branch or tag.
     pub name: String,
-    /// The hash of the branch or tag.
-    pub hash: String,
+    /// The hash of the branch or tag, if known.
+    pub hash: Option<String>,
     /// The type of the ref, either 'BRANCH', 'TAG', or 'DETACHED'.
     pub r#type: PyRefType,
 }
 
 impl PyRef {
-    fn branch(name: String, hash: String) -> (PyBranch, Self) {
+    fn branch(name: String, hash: Option<String>) -> (PyBranch, Self) {
         (
             PyBranch,
             PyRef {
@@ -66,7 +73,7 @@ impl PyRef {
         )
     }
 
-    fn tag(name: String, hash: String) -> (PyTag, Self) {
+    fn tag(name: String, hash: Option<String>) -> (PyTag, Self) {
         (
             PyTag,
             PyRef {
@@ -82,30 +89,72 @@ impl PyRef {
             PyDetachedRef,
             PyRef {
                 name: "".to_string(),
-                hash,
+                hash: Some(hash),
                 r#type: PyRefType::Detached,
             },
         )
     }
+
+    /// Checks the fields of a ref constructed from Python.
+    fn validate(self) -> PyResult<Self> {
+        if self.r#type == PyRefType::Detached {
+            if self.hash.is_none() {
+                return Err(PyValueError::new_err("a detached ref requires a hash"));
+            }
+        } else if self.name.is_empty() {
+            return Err(PyValueError::new_err("name must not be empty"));
+        }
+
+        if let Some(hash) = &self.hash
+            && hash.is_empty()
+        {
+            return Err(PyValueError::new_err("hash must not be empty"));
+        }
+
+        Ok(self)
+    }
 }
 
 // todo: does this work for subclasses?
 impl std::fmt::Display for PyRef {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}@{}", self.name, self.hash)
+        match &self.hash {
+            Some(hash) => write!(f, "{}@{hash}", self.name),
+            None => f.write_str(&self.name),
+        }
     }
 }
 
 #[pymethods]
 impl PyRef {
+    #[new]
+    #[pyo3(signature = (name, hash = None, *, r#type))]
+    fn new(name: String, hash: Option<String>, r#type: PyRefType) -> PyResult<Self> {
+        PyRef { name, hash, r#type }.validate()
+    }
+
     fn __repr__(&self) -> String {
+        let hash = match &self.hash {
+            Some(hash) => format!("{hash:?}"),
+            None => "None".to_owned(),
+        };
+
         format!(
-            "{}(name={:?}, hash={:?})",
+            "{}(name={:?}, hash={hash})",
             self.r#type.class_name(),
             self.name,
-            self.hash,
         )
     }
+
+    fn __eq__(&self, other: pyo3::PyRef<'_, PyRef>) -> bool {
+        *self == *other
+    }
+
+    fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
 }
 
 impl PyRefType {
@@ -123,16 +172,45 @@ impl PyRefType {
 #[pyclass(name = "Branch", module = "bauplan.schema", extends = PyRef, from_py_object)]
 pub struct PyBranch;
 
+#[pymethods]
+impl PyBranch {
+    #[new]
+    #[pyo3(signature = (name, hash = None))]
+    fn new(name: String, hash: Option<String>) -> PyResult<(Self, PyRef)> {
+        let (branch, r) = PyRef::branch(name, hash);
+        Ok((branch, r.validate()?))
+    }
+}
+
 /// A tag reference returned by the API.
 #[derive(Debug, Clone, Copy)]
 #[pyclass(name = "Tag", module = "bauplan.schema", extends = PyRef, from_py_object)]
 pub struct PyTag;
 
+#[pymethods]
+impl PyTag {
+    #[new]
+    #[pyo3(signature = (name, hash = None))]
+    fn new(name: String, hash: Option<String>) -> PyResult<(Self, PyRef)> {
+        let (tag, r) = PyRef::tag(name, hash);
+        Ok((tag, r.validate()?))
+    }
+}
+
 /// A ref not attached to a branch or tag, pointing directly to a commit hash.
 #[derive(Debug, Clone, Copy)]
 #[pyclass(name = "DetachedRef", module = "bauplan.schema", extends = PyRef, from_py_object)]
 pub(crate) struct PyDetachedRef;
 
+#[pymethods]
+impl PyDetachedRef {
+    #[new]
+    fn new(hash: String) -> PyResult<(Self, PyRef)> {
+        let (detached, r) = PyRef::detached(hash);
+        Ok((detached, r.validate()?))
+    }
+}
+
 /// Accepts a ref hash, a tag/branch name, or any ref object (Ref, Branch,
 /// Tag, DetachedRef), from which a ref string that the API understands is
 /// extracted.
@@ -143,6 +221,7 @@ pub(crate) struct PyDetachedRef;
 ///  - For `Branch(name='foo', hash='abcd...')`, the result
 ///    is `foo@abcd...`.
 ///  - For `Tag(name='bar', hash=None)`, the result is `bar`.
+///  - For `DetachedRef(hash='abcd...')`, the result is `@abcd...`.
 pub(crate) struct RefArg(pub String);
 
 impl<'a, 'py> FromPyObject<'a, 'py> for RefArg {
@@ -150,6 +229,7 @@ impl<'a, 'py> FromPyObject<'a, 'py> for RefArg {
 
     fn extract(ob: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
         if let Ok(s) = ob.extract::<String>() {
+            RefSpec::parse(&s).map_err(|e| PyValueError::new_err(e.to_string()))?;
             Ok(RefArg(s))
         } else if let Ok(r) = ob.extract::<pyo3::PyRef<'_, PyRef>>() {
             Ok(RefArg(r.to_string()))
@@ -159,7 +239,32 @@ impl<'a, 'py> FromPyObject<'a, 'py> for RefArg {
     }
 }
 
-/// Accepts either a branch name or a Branch object (from which the name is extracted).
+impl RefArg {
+    /// Resolves the `HEAD` and `<branch>@{<time>}` shorthands, releasing the
+    /// GIL if commits need to be fetched.
+    pub(crate) fn resolve(self, py: Python<'_>, client: &Client) -> PyResult<String> {
+        if let Ok(RefSpec::Plain(_)) = RefSpec::parse(&self.0) {
+            return Ok(self.0);
+        }
+
+        Ok(py.detach(|| self.resolve_blocking(client))?)
+    }
+
+    /// Like [`RefArg::resolve`], but blocks the calling thread. This is for
+    /// use in contexts where the GIL is already released.
+    pub(super) fn resolve_blocking(self, client: &Client) -> Result<String, ClientError> {
+        let active_branch = client.active_branch();
+        crate::resolve_ref::<_, ClientError>(&self.0, Some(&active_branch), |req| {
+            req.validate()?;
+            let req = req.into_request(&client.profile)?;
+            let resp = super::send(req, &client.profile, &client.agent()?)?;
+            Ok(ApiResponse::from_response(resp)?)
+        })
+    }
+}
+
+/// Accepts either a branch name or a Branch object, or any Ref of type
+/// BRANCH (from which the name is extracted).
 ///
 /// This is used by methods like `rename_branch`, which operate on the branch
 /// name and not a specific hash.
@@ -171,15 +276,18 @@ impl<'a, 'py> FromPyObject<'a, 'py> for BranchArg {
     fn extract(ob: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
         if let Ok(s) = ob.extract::<String>() {
             Ok(BranchArg(s))
-        } else if let Ok(branch) = ob.extract::<pyo3::PyRef<'_, PyBranch>>() {
-            Ok(BranchArg(branch.as_super().name.clone()))
+        } else if let Ok(r) = ob.extract::<pyo3::PyRef<'_, PyRef>>()
+            && r.r#type == PyRefType::Branch
+        {
+            Ok(BranchArg(r.name.clone()))
         } else {
             Err(PyTypeError::new_err("expected str or Branch"))
         }
     }
 }
 
-/// Accepts a tag name or Tag object (from which the name is extracted).
+/// Accepts a tag name or Tag object, or any Ref of type TAG (from which the
+/// name is extracted).
 ///
 /// This is used by methods like `delete_tag`, which operate on the tag name and
 /// not a specific tag.
@@ -191,8 +299,10 @@ impl<'a, 'py> FromPyObject<'a, 'py> for TagArg {
     fn extract(ob: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
         if let Ok(s) = ob.extract::<String>() {
             Ok(TagArg(s))
-        } else if let Ok(tag) = ob.extract::<pyo3::PyRef<'_, PyTag>>() {
-            Ok(TagArg(tag.as_super().name.clone()))
+        } else if let Ok(r) = ob.extract::<pyo3::PyRef<'_, PyRef>>()
+            && r.r#type == PyRefType::Tag
+        {
+            Ok(TagArg(r.name.clone()))
         } else {
             Err(PyTypeError::new_err("expected str or Tag"))
         }
@@ -206,10 +316,10 @@ impl<'py> IntoPyObject<'py> for CatalogRef {
 
     fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
         match self {
-            CatalogRef::Branch { name, hash } => Ok(Py::new(py, PyRef::branch(name, hash))?
+            CatalogRef::Branch { name, hash } => Ok(Py::new(py, PyRef::branch(name, Some(hash)))?
                 .into_bound(py)
                 .into_super()),
-            CatalogRef::Tag { name, hash } => Ok(Py::new(py, PyRef::tag(name, hash))?
+            CatalogRef::Tag { name, hash } => Ok(Py::new(py, PyRef::tag(name, Some(hash)))?
                 .into_bound(py)
                 .into_super()),
             CatalogRef::Detached { hash } => Ok(Py::new(py, PyRef::detached(hash))?
@@ -224,18 +334,23 @@ impl<'a, 'py> FromPyObject<'a, 'py> for CatalogRef {
 
     fn extract(ob: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
         let r = ob.extract::<pyo3::PyRef<'_, PyRef>>()?;
+        let Some(hash) = r.hash.clone() else {
+            return Err(PyValueError::new_err(format!(
+                "ref {:?} has no hash",
+                r.name
+            )));
+        };
+
         Ok(match r.r#type {
             PyRefType::Branch => CatalogRef::Branch {
                 name: r.name.clone(),
-                hash: r.hash.clone(),
+                hash,
             },
             PyRefType::Tag => CatalogRef::Tag {
                 name: r.name.clone(),
-                hash: r.hash.clone(),
-            },
-            PyRefType::Detached => CatalogRef::Detached {
-                hash: r.hash.clone(),
+                hash,
             },
+            PyRefType::Detached => CatalogRef::Detached { hash },
         })
     }
 }
@@ -246,7 +361,7 @@ impl<'py> IntoPyObject<'py> for Branch {
     type Error = PyErr;
 
     fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
-        Ok(Py::new(py, PyRef::branch(self.name, self.hash))?.into_bound(py))
+        Ok(Py::new(py, PyRef::branch(self.name, Some(self.hash)))?.into_bound(py))
     }
 }
 
@@ -256,6 +371,6 @@ impl<'py> IntoPyObject<'py> for Tag {
     type Error = PyErr;
 
     fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
-        Ok(Py::new(py, PyRef::tag(self.name, self.hash))?.into_bound(py))
+        Ok(Py::new(py, PyRef::tag(self.name, Some(self.hash)))?.into_bound(py))
     }
 }
@@ -4,29 +4,37 @@
 
 pub(crate) mod state;
 
-use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::ControlFlow;
+use std::path::PathBuf;
 use std::time;
 
 use anyhow::bail;
-use chrono::{TimeZone, Utc};
-use commanderpb::runner_event::Event as RunnerEvent;
+use chrono::Utc;
 use futures::TryStreamExt;
-use tracing::{error, info, trace};
+use tracing::{debug, error, info, trace};
 
 use super::Client;
 use super::refs::RefArg;
-use crate::grpc::{self, generated as commanderpb};
+use crate::events::{self, EmptyOutputCheck, JobEvent, capture::EventRecorder};
+use crate::grpc::{
+    self, explain, generated as commanderpb,
+    job::{HeadTailBuffer, JOB_NAME_ARG, Job, JobState, Priority},
+    materialization::{self, record_materialization},
+};
+use crate::namespace::{GetNamespaces, NAMESPACE_CHECK_LIMIT, NamespaceCheck, check_namespace};
 use crate::project::{ParameterType, ParameterValue, ProjectFile};
-use crate::python::job::JobLogEvent;
+use crate::python::exceptions::NamespaceNotFoundError;
+use crate::python::job::{JobArg, JobLogEvent};
 use crate::python::namespace::NamespaceArg;
 use crate::python::{job_err, optional_on_off, rt};
+use crate::{ApiErrorKind, ApiRequest as _};
 use gethostname::gethostname;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use rsa::RsaPublicKey;
 
-use self::state::{RunExecutionContext, RunState};
+use self::state::{RunExecutionContext, RunPlan, RunState};
 
 pub(crate) fn job_status_strings(result: Result<(), grpc::JobError>) -> (String, Option<String>) {
     match result {
@@ -35,14 +43,43 @@ pub(crate) fn job_status_strings(result: Result<(), grpc::JobError>) -> (String,
     }
 }
 
+/// Priorities are validated as they're passed in, so that an out of range
+/// one raises `ValueError` before anything is sent.
+impl<'a, 'py> FromPyObject<'a, 'py> for Priority {
+    type Error = PyErr;
+
+    fn extract(ob: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        let n: i64 = ob.extract()?;
+        Priority::try_from(n).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+}
+
 impl Client {
     pub(crate) async fn monitor_job(
         &self,
         job_id: &str,
         timeout: time::Duration,
-        mut on_event: impl FnMut(RunnerEvent),
+        events: Option<&EventRecorder>,
+        mut on_event: impl FnMut(JobEvent),
+    ) -> PyResult<Result<(), grpc::JobError>> {
+        self.monitor_job_until(job_id, timeout, events, |event| {
+            on_event(event);
+            ControlFlow::Continue(())
+        })
+        .await
+    }
+
+    /// Like `monitor_job`, but cancels the job if `on_event` breaks, with
+    /// the reason it gives. The raw events are also recorded to `events`, if
+    /// given.
+    pub(crate) async fn monitor_job_until(
+        &self,
+        job_id: &str,
+        timeout: time::Duration,
+        events: Option<&EventRecorder>,
+        mut on_event: impl FnMut(JobEvent) -> ControlFlow<&'static str>,
     ) -> PyResult<Result<(), grpc::JobError>> {
-        let mut grpc = self.grpc.clone();
+        let mut grpc = self.grpc()?;
         info!(job_id, "running job");
 
         let mut req = tonic::Request::new(grpc::generated::SubscribeLogsRequest {
@@ -51,7 +88,8 @@ impl Client {
         req.set_timeout(timeout);
 
         let mut stream_client = grpc.clone();
-        let stream = stream_client.monitor_job(req, self.longbow_endpoint.clone());
+        let stream =
+            stream_client.monitor_job_recorded(req, self.longbow_endpoint()?, events.cloned());
         futures::pin_mut!(stream);
 
         loop {
@@ -85,11 +123,24 @@ impl Client {
 
             trace!(job_id, ?event, "received runner event");
 
-            if let RunnerEvent::JobCompletion(ev) = event {
-                return Ok(grpc::interpret_outcome(ev.outcome).map(|_| ()));
+            if let JobEvent::Completion(completion) = event {
+                return Ok(completion.result.map(|_| ()));
             }
 
-            on_event(event);
+            if let ControlFlow::Break(reason) = on_event(event) {
+                error!(job_id, "{reason}, cancelling job");
+                let cancel_req = commanderpb::CancelJobRequest {
+                    job_id: Some(commanderpb::JobId {
+                        id: job_id.to_owned(),
+                        ..Default::default()
+                    }),
+                };
+
+                if let Err(e) = grpc.cancel(cancel_req).await {
+                    return Err(job_err(format!("failed to cancel job: {e}")));
+                }
+                return Ok(Err(grpc::JobError::Cancelled));
+            }
         }
     }
 
@@ -97,38 +148,49 @@ impl Client {
         &self,
         timeout: time::Duration,
         state: &mut RunState,
+        mut empty_check: Option<EmptyOutputCheck>,
+        events: Option<&EventRecorder>,
     ) -> PyResult<()> {
         let job_id = state.job_id.clone().unwrap_or_default();
+        let mut observer = RunObserver::new(self.max_user_logs);
+
+        // If a model is empty, the job is cancelled before its transaction
+        // can be merged. Without a transaction, the output is already written.
+        let cancel_on_empty = state.ctx.transaction != "off" && !state.ctx.dry_run;
 
         let status = self
-            .monitor_job(&job_id, timeout, |event| match event {
-                RunnerEvent::TaskStart(ev) => {
-                    if let Some(ts) = ev.timestamp
-                        && let Some(dt) = Utc.timestamp_opt(ts.seconds, ts.nanos as u32).single()
-                    {
-                        state.tasks_started.insert(ev.task_id, dt);
-                    }
-                }
-                RunnerEvent::TaskCompletion(ev) => {
-                    if let Some(ts) = ev.timestamp
-                        && let Some(dt) = Utc.timestamp_opt(ts.seconds, ts.nanos as u32).single()
-                    {
-                        state.tasks_stopped.insert(ev.task_id, dt);
-                    }
-                }
-                RunnerEvent::RuntimeUserLog(ev)
-                    if ev.r#type() == commanderpb::runtime_log_event::LogType::User =>
+            .monitor_job_until(&job_id, timeout, events, |event| {
+                if let Some(check) = &mut empty_check
+                    && let Some(model) = check.observe(&event)
                 {
-                    if let Ok(log) = JobLogEvent::try_from(ev) {
-                        state.user_logs.push(log);
+                    info!(model, "model output is empty");
+                    if cancel_on_empty {
+                        return ControlFlow::Break("model output is empty");
                     }
                 }
-                _ => (),
+
+                observer.observe(state, event);
+                ControlFlow::Continue(())
             })
             .await?;
 
         state.ended_at_ns = Some(Utc::now().timestamp_nanos_opt().unwrap());
-        let (job_status, error) = job_status_strings(status);
+        observer.finish(state);
+
+        // Empty models fail the run if the job succeeded, or if it was
+        // cancelled because of them.
+        let mut empty_reason = None;
+        if let Some(check) = &empty_check {
+            state.empty_models = check.empty_models().to_vec();
+            if matches!(status, Ok(()) | Err(grpc::JobError::Cancelled)) {
+                empty_reason = check.failure_reason();
+            }
+        }
+
+        let (job_status, error) = match empty_reason {
+            Some(reason) => ("FAILED".to_owned(), Some(reason)),
+            None => job_status_strings(status),
+        };
         state.job_status = Some(job_status);
         state.error = error;
 
@@ -147,30 +209,79 @@ impl Client {
 
     pub(crate) fn job_request_common(
         &self,
-        priority: Option<u32>,
+        priority: Option<Priority>,
         args: HashMap<String, String>,
+        job_name: Option<String>,
     ) -> PyResult<commanderpb::JobRequestCommon> {
-        if let Some(p) = priority
-            && !(1..=10).contains(&p)
-        {
-            return Err(PyValueError::new_err("priority must be between 1 and 10"));
-        }
-
         let hostname = gethostname().to_string_lossy().into_owned();
 
         let mut merged_args = self.profile.args.clone();
         merged_args.extend(args);
+        if let Some(name) = job_name {
+            merged_args.insert(JOB_NAME_ARG.to_owned(), name);
+        }
 
+        debug!(module_version = %self.module_version, "built job request");
         Ok(commanderpb::JobRequestCommon {
-            module_version: env!("CARGO_PKG_VERSION").to_owned(),
+            module_version: self.module_version.clone(),
             hostname,
             args: merged_args,
             debug: 0,
-            priority: priority.map(|p| p as _),
+            priority: priority.map(|p| p.get() as _),
         })
     }
 }
 
+/// Collects what a run reports as it goes into its `RunState`: task
+/// timings, user logs and the tables it plans to write.
+struct RunObserver {
+    planned: Vec<materialization::PlannedMaterialization>,
+    user_logs: HeadTailBuffer<JobLogEvent>,
+}
+
+impl RunObserver {
+    fn new(max_user_logs: usize) -> Self {
+        Self {
+            planned: Vec::new(),
+            user_logs: HeadTailBuffer::new(max_user_logs),
+        }
+    }
+
+    fn observe(&mut self, state: &mut RunState, event: JobEvent) {
+        match event {
+            JobEvent::TaskStarted(task) => {
+                self.record_materialization(state, &task);
+                state.task_started(&task);
+            }
+            JobEvent::TaskCompleted(task, outcome) => {
+                self.record_materialization(state, &task);
+                state.task_completed(&task, outcome);
+            }
+            JobEvent::UserLog(log) if log.user => {
+                if let Ok(log) = JobLogEvent::try_from(log) {
+                    self.user_logs.push(log);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn record_materialization(&mut self, state: &RunState, task: &events::Task) {
+        record_materialization(
+            &mut self.planned,
+            &task.metadata,
+            &state.ctx.namespace,
+            &state.ctx.r#ref,
+        );
+    }
+
+    fn finish(self, state: &mut RunState) {
+        state.planned_materializations = self.planned.into_iter().map(Into::into).collect();
+        state.user_logs_truncated = self.user_logs.dropped() > 0;
+        state.user_logs = self.user_logs.into_vec(JobLogEvent::truncation_marker);
+    }
+}
+
 #[derive(FromPyObject)]
 enum RawParameterValue {
     Bool(bool),
@@ -190,6 +301,40 @@ impl From<RawParameterValue> for ParameterValue {
     }
 }
 
+/// The models to check for empty output: a list of names, or `True` for all
+/// of them.
+#[derive(FromPyObject)]
+enum EmptyModelsArg {
+    All(bool),
+    Models(Vec<String>),
+}
+
+impl EmptyModelsArg {
+    fn into_check(self) -> Option<EmptyOutputCheck> {
+        match self {
+            EmptyModelsArg::All(true) => Some(EmptyOutputCheck::new(Vec::new())),
+            EmptyModelsArg::All(false) => None,
+            EmptyModelsArg::Models(models) => Some(EmptyOutputCheck::new(models)),
+        }
+    }
+}
+
+/// The contents of a file in an in-memory project: text, or raw bytes.
+#[derive(FromPyObject)]
+enum ProjectFileContents {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl From<ProjectFileContents> for Vec<u8> {
+    fn from(value: ProjectFileContents) -> Self {
+        match value {
+            ProjectFileContents::Text(s) => s.into_bytes(),
+            ProjectFileContents::Bytes(b) => b,
+        }
+    }
+}
+
 impl RawParameterValue {
     fn type_str(&self) -> &'static str {
         match self {
@@ -221,9 +366,24 @@ impl Client {
     ///     raise Exception(f"{run_state.job_id} failed: {run_state.job_status} - {run_state.error}")
     /// ```
     ///
+    /// A project can also be passed in memory, as a dict of file names to
+    /// their contents, instead of a directory:
+    ///
+    /// ```python
+    /// #! client = bauplan.Client()
+    /// run_state = client.run(
+    ///     project={
+    ///         "bauplan_project.yml": open("bauplan_project.yml").read(),
+    ///         "trips_clean.sql": "SELECT * FROM taxi_fhvhv",
+    ///     },
+    ///     ref="username.dev_branch",
+    /// )
+    /// ```
+    ///
     /// Parameters:
-    ///     project_dir: The directory of the project (where the `bauplan_project.yml` or `bauplan_project.yaml` file is located).
-    ///     ref: The ref, branch name or tag name from which to run the project.
+    ///     project_dir: The directory of the project (where the `bauplan_project.yml` or `bauplan_project.yaml` file is located). Exactly one of `project_dir` and `project` must be set.
+    ///     project: The files of the project, keyed by their path relative to the project directory, as text or bytes. It must include a `bauplan_project.yml` or `bauplan_project.yaml` file. Exactly one of `project_dir` and `project` must be set.
+    ///     ref: The ref, branch name or tag name from which to run the project. Defaults to the active branch.
     ///     namespace: The Namespace to run the job in. If not set, the job will be run in the default namespace.
     ///     parameters: Parameters for templating into SQL or Python models.
     ///     cache: Whether to enable or disable caching for the run. Defaults to 'on'.
@@ -235,11 +395,16 @@ impl Client {
     ///     priority: Optional job priority (1-10, where 10 is highest priority).
     ///     client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
     ///     detach: Whether to detach the run and return immediately instead of blocking on log streaming.
+    ///     job_name: Optional human-friendly name for the job, sent as the `bauplan.job-name` job arg.
+    ///     preflight: Whether to check that `namespace` exists on the ref before submitting the run. Defaults to `True`.
+    ///     fail_on_empty_models: Model names, or `True` for all models, to fail the run if they write no rows. This turns on previews, which are used to count the rows. With transactions on, the job is cancelled as soon as an empty model is found, so that nothing is merged; without them, or if the job finishes first, the output is still written to the ref. The empty models are listed in `RunState.empty_models`.
+    ///     events_out: Optional path to record the raw events of the job to, for debugging. The file can be replayed with `bauplan job replay-events`. Can't be used with `detach`.
     /// Returns:
     ///     `bauplan.state.RunState`: The state of the run.
     #[pyo3(signature = (
-        project_dir: "str",
+        project_dir: "str | None" = None,
         *,
+        project: "dict[str, str | bytes] | None" = None,
         r#ref: "str | Ref | None" = None,
         namespace: "str | Namespace | None" = None,
         parameters: "dict[str, str | int | float | bool | None] | None" = None,
@@ -252,12 +417,17 @@ impl Client {
         priority: "int | None" = None,
         client_timeout: "int | None" = None,
         detach: "bool" = false,
+        job_name: "str | None" = None,
+        preflight: "bool" = true,
+        fail_on_empty_models: "bool | list[str] | None" = None,
+        events_out: "str | pathlib.Path | None" = None,
     ) -> "RunState")]
     #[allow(clippy::too_many_arguments)]
     fn run(
         &self,
         py: Python<'_>,
-        project_dir: PathBuf,
+        project_dir: Option<PathBuf>,
+        project: Option<BTreeMap<String, ProjectFileContents>>,
         r#ref: Option<RefArg>,
         namespace: Option<NamespaceArg>,
         parameters: Option<HashMap<String, Option<RawParameterValue>>>,
@@ -267,12 +437,39 @@ impl Client {
         strict: Option<&str>,
         preview: Option<&str>,
         args: Option<HashMap<String, String>>,
-        priority: Option<u32>,
+        priority: Option<Priority>,
         client_timeout: Option<u64>,
         detach: bool,
+        job_name: Option<String>,
+        preflight: bool,
+        fail_on_empty_models: Option<EmptyModelsArg>,
+        events_out: Option<PathBuf>,
     ) -> PyResult<RunState> {
+        if !detach {
+            self.check_blocking(py, "run")?;
+        }
+
+        let (project, project_dir) = match (project_dir, project) {
+            (Some(dir), None) => {
+                let project = ProjectFile::from_dir(&dir).map_err(job_err)?;
+                (project, dir.display().to_string())
+            }
+            (None, Some(files)) => {
+                let files = files.into_iter().map(|(k, v)| (k, v.into())).collect();
+                let project = ProjectFile::from_files(files).map_err(job_err)?;
+                (project, String::new())
+            }
+            _ => {
+                return Err(PyValueError::new_err(
+                    "exactly one of project_dir and project must be set",
+                ));
+            }
+        };
+
+        let events = create_event_recorder(events_out, detach)?;
+
         let timeout = self.job_timeout(client_timeout);
-        let common = self.job_request_common(priority, args.unwrap_or_default())?;
+        let common = self.job_request_common(priority, args.unwrap_or_default(), job_name)?;
         let cache = optional_on_off("cache", cache)?;
         let transaction = optional_on_off("transaction", transaction)?;
         let strict = optional_on_off("strict", strict)?;
@@ -283,24 +480,159 @@ impl Client {
             None => commanderpb::JobRequestOptionalBool::Unspecified,
         };
 
-        let project_dir = Path::new(&project_dir);
-        let project = ProjectFile::from_dir(project_dir).map_err(job_err)?;
         let zip_file = project.create_code_snapshot().map_err(job_err)?;
 
         let parameters = super::detach(
             py,
-            resolve_job_parameters(
-                &mut self.grpc.clone(),
-                &project,
-                parameters.unwrap_or_default(),
-            ),
+            resolve_job_parameters(&mut self.grpc()?, &project, parameters.unwrap_or_default()),
         )?;
 
+        let r#ref = self.ref_or_active(r#ref).resolve(py, self)?;
+        let namespace = self.namespace_or_default(namespace.map(|a| a.0));
+        if let Some(namespace) = &namespace
+            && preflight
+        {
+            py.detach(|| preflight_namespace(self, &r#ref, namespace))?;
+        }
+
+        // The empty model check counts the rows in the previews.
+        let empty_check = fail_on_empty_models.and_then(EmptyModelsArg::into_check);
+        let preview = match preview {
+            None | Some("off") if empty_check.is_some() => "head",
+            p => p.unwrap_or_default(),
+        };
+
         let req = commanderpb::CodeSnapshotRunRequest {
             job_request_common: Some(common),
             zip_file,
-            r#ref: r#ref.map(|a| a.0),
-            namespace: namespace.map(|a| a.0),
+            r#ref: Some(r#ref),
+            namespace,
+            dry_run: dry_run.into(),
+            transaction: transaction.unwrap_or_default().to_owned(),
+            strict: strict.unwrap_or_default().to_owned(),
+            cache: cache.unwrap_or_default().to_owned(),
+            preview: preview.to_owned(),
+            project_id: project.project.id.as_hyphenated().to_string(),
+            project_name: project.project.name.clone().unwrap_or_default(),
+            parameters,
+            ..Default::default()
+        };
+
+        self.submit_run(py, req, project_dir, timeout, detach, empty_check, events)
+    }
+
+    /// Rerun the code from an earlier run, without a local checkout of the
+    /// project. The snapshot is fetched from the server, and parameters are
+    /// resolved against the project file it contains.
+    ///
+    /// The snapshot is uploaded again as part of the run. A rerun that only
+    /// references existing code has to name the job that ran it rather than
+    /// the snapshot, and can't take new parameters.
+    ///
+    /// ```python
+    /// #! client = bauplan.Client()
+    /// state = client.run(project_dir='./etl_pipelines/daily_sales')
+    ///
+    /// # Later, run the exact same code against another branch.
+    /// rerun_state = client.run_from_snapshot(
+    ///     state.ctx.snapshot_id,
+    ///     ref='username.dev_branch',
+    /// )
+    /// ```
+    ///
+    /// Parameters:
+    ///     snapshot: The `snapshot_id` or `snapshot_uri` of a previous run, as found on `bauplan.state.RunExecutionContext`.
+    ///     ref: The ref, branch name or tag name from which to run the project. Defaults to the active branch.
+    ///     namespace: The Namespace to run the job in. If not set, the job will be run in the default namespace.
+    ///     parameters: Parameters for templating into SQL or Python models.
+    ///     cache: Whether to enable or disable caching for the run. Defaults to 'on'.
+    ///     transaction: Whether to enable or disable transaction mode for the run. Defaults to 'on'.
+    ///     dry_run: Whether to enable or disable dry-run mode for the run; models are not materialized.
+    ///     strict: Whether to enable or disable strict schema validation.
+    ///     preview: Whether to enable or disable preview mode for the run.
+    ///     args: Additional arguments (optional).
+    ///     priority: Optional job priority (1-10, where 10 is highest priority).
+    ///     client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+    ///     detach: Whether to detach the run and return immediately instead of blocking on log streaming.
+    /// Returns:
+    ///     `bauplan.state.RunState`: The state of the run.
+    #[pyo3(signature = (
+        snapshot: "str",
+        *,
+        r#ref: "str | Ref | None" = None,
+        namespace: "str | Namespace | None" = None,
+        parameters: "dict[str, str | int | float | bool | None] | None" = None,
+        cache: "Literal['on', 'off'] | None" = None,
+        transaction: "Literal['on', 'off'] | None" = None,
+        dry_run: "bool | None" = None,
+        strict: "Literal['on', 'off'] | None" = None,
+        preview: "str | None" = None,
+        args: "dict[str, str] | None" = None,
+        priority: "int | None" = None,
+        client_timeout: "int | None" = None,
+        detach: "bool" = false,
+    ) -> "RunState")]
+    #[allow(clippy::too_many_arguments)]
+    fn run_from_snapshot(
+        &self,
+        py: Python<'_>,
+        snapshot: &str,
+        r#ref: Option<RefArg>,
+        namespace: Option<NamespaceArg>,
+        parameters: Option<HashMap<String, Option<RawParameterValue>>>,
+        cache: Option<&str>,
+        transaction: Option<&str>,
+        dry_run: Option<bool>,
+        strict: Option<&str>,
+        preview: Option<&str>,
+        args: Option<HashMap<String, String>>,
+        priority: Option<Priority>,
+        client_timeout: Option<u64>,
+        detach: bool,
+    ) -> PyResult<RunState> {
+        if !detach {
+            self.check_blocking(py, "run_from_snapshot")?;
+        }
+
+        let timeout = self.job_timeout(client_timeout);
+        let common = self.job_request_common(priority, args.unwrap_or_default(), None)?;
+        let cache = optional_on_off("cache", cache)?;
+        let transaction = optional_on_off("transaction", transaction)?;
+        let strict = optional_on_off("strict", strict)?;
+
+        let dry_run = match dry_run {
+            Some(true) => commanderpb::JobRequestOptionalBool::True,
+            Some(false) => commanderpb::JobRequestOptionalBool::False,
+            None => commanderpb::JobRequestOptionalBool::Unspecified,
+        };
+
+        let snapshot_id = snapshot_id_from_arg(snapshot);
+        let mut req = tonic::Request::new(commanderpb::GetSnapshotInfoRequest {
+            snapshot_sha: snapshot_id.to_owned(),
+        });
+        req.set_timeout(self.client_timeout);
+
+        let info = super::detach(py, self.grpc()?.get_snapshot_info(req))
+            .map_err(|e| job_err(format!("failed to fetch snapshot {snapshot_id:?}: {e}")))?
+            .into_inner();
+        let Some(info) = info.snapshot_info.filter(|i| !i.snapshot_zip.is_empty()) else {
+            return Err(job_err(format!("snapshot not found: {snapshot_id:?}")));
+        };
+
+        let project = ProjectFile::from_code_snapshot(&info.snapshot_zip).map_err(job_err)?;
+
+        let parameters = super::detach(
+            py,
+            resolve_job_parameters(&mut self.grpc()?, &project, parameters.unwrap_or_default()),
+        )?;
+
+        // CodeSnapshotReRun takes a job id and no parameters, so the
+        // snapshot is sent back as a new code snapshot.
+        let req = commanderpb::CodeSnapshotRunRequest {
+            job_request_common: Some(common),
+            zip_file: info.snapshot_zip,
+            r#ref: Some(self.ref_or_active(r#ref).resolve(py, self)?),
+            namespace: self.namespace_or_default(namespace.map(|a| a.0)),
             dry_run: dry_run.into(),
             transaction: transaction.unwrap_or_default().to_owned(),
             strict: strict.unwrap_or_default().to_owned(),
@@ -312,8 +644,243 @@ impl Client {
             ..Default::default()
         };
 
-        let mut client = self.grpc.clone();
-        let state = super::detach(py, async {
+        self.submit_run(py, req, String::new(), timeout, detach, None, None)
+    }
+
+    /// Plan a Bauplan project without running it, and return the plan: the
+    /// models that would run and their dependencies. This is the equivalent
+    /// of running through the CLI the `bauplan run --explain` command.
+    ///
+    /// The project is submitted as a dry run. Once it has been planned, the
+    /// job is cancelled and the plan is read from its context.
+    ///
+    /// ```python
+    /// #! client = bauplan.Client()
+    /// plan = client.explain_run(project_dir='./etl_pipelines/daily_sales')
+    ///
+    /// for model in plan.models:
+    ///     print(model.model_name, model.depends_on)
+    /// ```
+    ///
+    /// Parameters:
+    ///     project_dir: The directory of the project (where the `bauplan_project.yml` or `bauplan_project.yaml` file is located).
+    ///     ref: The ref, branch name or tag name to plan the project against. Defaults to the active branch.
+    ///     namespace: The Namespace to plan the job in. If not set, the default namespace is used.
+    ///     parameters: Parameters for templating into SQL or Python models.
+    ///     cache: Whether to enable or disable caching for the run. Defaults to 'on'.
+    ///     args: Additional arguments (optional).
+    ///     priority: Optional job priority (1-10, where 10 is highest priority).
+    ///     client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+    /// Returns:
+    ///     `bauplan.state.RunPlan`: The plan for the run.
+    #[pyo3(signature = (
+        project_dir: "str",
+        *,
+        r#ref: "str | Ref | None" = None,
+        namespace: "str | Namespace | None" = None,
+        parameters: "dict[str, str | int | float | bool | None] | None" = None,
+        cache: "Literal['on', 'off'] | None" = None,
+        args: "dict[str, str] | None" = None,
+        priority: "int | None" = None,
+        client_timeout: "int | None" = None,
+    ) -> "RunPlan")]
+    #[allow(clippy::too_many_arguments)]
+    fn explain_run(
+        &self,
+        py: Python<'_>,
+        project_dir: PathBuf,
+        r#ref: Option<RefArg>,
+        namespace: Option<NamespaceArg>,
+        parameters: Option<HashMap<String, Option<RawParameterValue>>>,
+        cache: Option<&str>,
+        args: Option<HashMap<String, String>>,
+        priority: Option<Priority>,
+        client_timeout: Option<u64>,
+    ) -> PyResult<RunPlan> {
+        self.check_blocking(py, "explain_run")?;
+
+        let timeout = self.job_timeout(client_timeout);
+        let common = self.job_request_common(priority, args.unwrap_or_default(), None)?;
+        let cache = optional_on_off("cache", cache)?;
+
+        let project = ProjectFile::from_dir(&project_dir).map_err(job_err)?;
+        let zip_file = project.create_code_snapshot().map_err(job_err)?;
+
+        let parameters = super::detach(
+            py,
+            resolve_job_parameters(&mut self.grpc()?, &project, parameters.unwrap_or_default()),
+        )?;
+
+        let req = commanderpb::CodeSnapshotRunRequest {
+            job_request_common: Some(common),
+            zip_file,
+            r#ref: Some(self.ref_or_active(r#ref).resolve(py, self)?),
+            namespace: self.namespace_or_default(namespace.map(|a| a.0)),
+            dry_run: commanderpb::JobRequestOptionalBool::True.into(),
+            cache: cache.unwrap_or_default().to_owned(),
+            project_id: project.project.id.as_hyphenated().to_string(),
+            project_name: project.project.name.clone().unwrap_or_default(),
+            parameters,
+            ..Default::default()
+        };
+
+        let mut client = self.grpc()?;
+        let endpoint = self.longbow_endpoint()?;
+        super::detach(py, async move {
+            let resp = client
+                .code_snapshot_run(req)
+                .await
+                .map_err(job_err)?
+                .into_inner();
+
+            let Some(commanderpb::JobResponseCommon { job_id, .. }) = resp.job_response_common
+            else {
+                return Err(job_err("response missing job ID"));
+            };
+
+            let mut req = tonic::Request::new(commanderpb::SubscribeLogsRequest {
+                job_id: job_id.clone(),
+            });
+            req.set_timeout(timeout);
+
+            let mut stream_client = client.clone();
+            let res = explain::wait_for_planning(stream_client.monitor_job(req, endpoint)).await;
+
+            // This fails if the job already finished, which is fine.
+            let cancel_req = commanderpb::CancelJobRequest {
+                job_id: Some(commanderpb::JobId {
+                    id: job_id.clone(),
+                    ..Default::default()
+                }),
+            };
+
+            if let Err(e) = client.cancel(cancel_req).await {
+                debug!(job_id, error = %e, "failed to cancel job after planning");
+            }
+
+            res.map_err(job_err)?;
+
+            let req = explain::RunPlan::context_request(&job_id);
+            let contexts = client.get_job_context(req).await.map_err(job_err)?;
+            let plan = explain::RunPlan::from_contexts(job_id, contexts.into_inner().job_contexts)
+                .map_err(job_err)?;
+            Ok(plan.into())
+        })
+    }
+
+    /// Wait for a job to finish, and return its state as a `RunState`, as if
+    /// it had been run without `detach`. If the job has already finished, its
+    /// state is rebuilt from its stored record and logs.
+    ///
+    /// The parameters a run was submitted with aren't stored with the job, so
+    /// the returned state's `ctx` is empty; to keep them, use `attach` on the
+    /// `RunState` returned by `run(..., detach=True)` instead.
+    ///
+    /// ```python
+    /// #! client = bauplan.Client()
+    /// state = client.run(project_dir='./my_pipeline', detach=True)
+    ///
+    /// # Later, possibly from another process.
+    /// result = client.wait_for_job(state.job_id)
+    /// print(result.job_status, result.duration)
+    /// ```
+    ///
+    /// Parameters:
+    ///     job: A job ID or a Job instance.
+    ///     client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+    /// Returns:
+    ///     `bauplan.state.RunState`: The state of the job.
+    #[pyo3(signature = (
+        job: "str | Job",
+        *,
+        client_timeout: "int | None" = None,
+    ) -> "RunState")]
+    fn wait_for_job(
+        &self,
+        py: Python<'_>,
+        job: JobArg,
+        client_timeout: Option<u64>,
+    ) -> PyResult<RunState> {
+        self.check_blocking(py, "wait_for_job")?;
+
+        let mut state = RunState {
+            job_id: Some(job.0),
+            ctx: RunExecutionContext::default(),
+            user_logs: Vec::new(),
+            user_logs_truncated: false,
+            tasks: Vec::new(),
+            job_status: None,
+            started_at_ns: 0,
+            ended_at_ns: None,
+            error: None,
+            planned_materializations: Vec::new(),
+            empty_models: Vec::new(),
+        };
+
+        self.wait_into(py, &mut state, client_timeout)?;
+        Ok(state)
+    }
+
+    /// Wait for a detached run to finish, and fill in its `RunState` in
+    /// place, as if it had been run without `detach`. The state is also
+    /// returned.
+    ///
+    /// ```python
+    /// #! client = bauplan.Client()
+    /// state = client.run(project_dir='./my_pipeline', detach=True)
+    ///
+    /// # Do something else, then wait for the run.
+    /// client.attach(state)
+    /// print(state.job_status, len(state.user_logs))
+    /// ```
+    ///
+    /// Parameters:
+    ///     run_state: The `RunState` returned by `run(..., detach=True)`.
+    ///     client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+    /// Returns:
+    ///     `bauplan.state.RunState`: The same `run_state`, filled in.
+    #[pyo3(signature = (
+        run_state: "RunState",
+        *,
+        client_timeout: "int | None" = None,
+    ) -> "RunState")]
+    fn attach(
+        &self,
+        py: Python<'_>,
+        run_state: Bound<'_, RunState>,
+        client_timeout: Option<u64>,
+    ) -> PyResult<Py<RunState>> {
+        self.check_blocking(py, "attach")?;
+
+        if run_state.borrow().job_id.is_none() {
+            return Err(PyValueError::new_err("the run state has no job ID"));
+        }
+
+        // Don't hold the borrow while waiting, so the state can still be
+        // read from other threads.
+        let mut state = run_state.borrow().clone();
+        self.wait_into(py, &mut state, client_timeout)?;
+        *run_state.borrow_mut() = state;
+
+        Ok(run_state.unbind())
+    }
+}
+
+impl Client {
+    /// Submits a code snapshot run, and (unless detached) monitors it to
+    /// completion, failing it if `empty_check` finds an empty model.
+    fn submit_run(
+        &self,
+        py: Python<'_>,
+        req: commanderpb::CodeSnapshotRunRequest,
+        project_dir: String,
+        timeout: time::Duration,
+        detach: bool,
+        empty_check: Option<EmptyOutputCheck>,
+        events: Option<EventRecorder>,
+    ) -> PyResult<RunState> {
+        let mut client = self.grpc()?;
+        super::detach(py, async {
             let resp = client
                 .code_snapshot_run(req)
                 .await
@@ -328,7 +895,7 @@ impl Client {
             let ctx = RunExecutionContext {
                 snapshot_id: resp.snapshot_id,
                 snapshot_uri: resp.snapshot_uri,
-                project_dir: project_dir.display().to_string(),
+                project_dir,
                 r#ref: resp.r#ref,
                 namespace: resp.namespace,
                 dry_run: resp.dry_run,
@@ -344,12 +911,14 @@ impl Client {
                 job_id: Some(job_id),
                 ctx,
                 user_logs: Vec::new(),
-                tasks_started: HashMap::new(),
-                tasks_stopped: HashMap::new(),
+                user_logs_truncated: false,
+                tasks: Vec::new(),
                 job_status: None,
                 started_at_ns: Utc::now().timestamp_nanos_opt().unwrap_or(0),
                 ended_at_ns: None,
                 error: None,
+                planned_materializations: Vec::new(),
+                empty_models: Vec::new(),
             };
 
             if detach {
@@ -358,14 +927,159 @@ impl Client {
 
             // Run the job until we get a completion. A job error is not an
             // Err here.
-            match self.monitor_run(timeout, &mut state).await {
+            match self
+                .monitor_run(timeout, &mut state, empty_check, events.as_ref())
+                .await
+            {
                 Ok(()) => Ok(state),
                 Err(e) => Err(e),
             }
-        })?;
+        })
+    }
+}
 
-        Ok(state)
+impl Client {
+    /// Waits for the job of `state` to finish, and fills in the rest of
+    /// `state`. Jobs that already finished would leave the log stream
+    /// hanging, so their state is rebuilt from their record and stored logs.
+    fn wait_into(
+        &self,
+        py: Python<'_>,
+        state: &mut RunState,
+        client_timeout: Option<u64>,
+    ) -> PyResult<()> {
+        let job_id = state.job_id.clone().unwrap_or_default();
+        let timeout = self.job_timeout(client_timeout);
+        let mut client = self.grpc()?;
+
+        super::detach(py, async {
+            let mut req = tonic::Request::new(commanderpb::GetJobsRequest {
+                job_ids: vec![job_id.clone()],
+                all_users: true,
+                ..Default::default()
+            });
+            req.set_timeout(self.client_timeout);
+
+            let resp = client.get_jobs(req).await.map_err(job_err)?.into_inner();
+            let Some(job) = resp.jobs.into_iter().next().map(Job::from) else {
+                return Err(job_err(format!("job not found: {job_id}")));
+            };
+
+            if state.started_at_ns == 0
+                && let Some(ts) = job.started_at.or(job.created_at)
+            {
+                state.started_at_ns = ts.timestamp_nanos_opt().unwrap_or(0);
+            }
+
+            if matches!(job.status, JobState::NotStarted | JobState::Running) {
+                return self.monitor_run(timeout, state, None, None).await;
+            }
+
+            let mut req = tonic::Request::new(commanderpb::GetLogsRequest {
+                job_id: job_id.clone(),
+                ..Default::default()
+            });
+            req.set_timeout(self.client_timeout);
+
+            let stored = client.get_logs(req).await.map_err(job_err)?.into_inner();
+            let mut converter = events::Converter::new();
+            let mut observer = RunObserver::new(self.max_user_logs);
+            let mut status = None;
+            for event in stored
+                .events
+                .into_iter()
+                .filter_map(|ev| ev.event)
+                .flat_map(|ev| converter.convert(ev))
+            {
+                match event {
+                    JobEvent::Completion(completion) => {
+                        status = Some(completion.result.map(|_| ()));
+                    }
+                    event => observer.observe(state, event),
+                }
+            }
+
+            observer.finish(state);
+            let status = status.unwrap_or_else(|| match job.status {
+                JobState::Complete => Ok(()),
+                JobState::Abort => Err(grpc::JobError::Cancelled),
+                _ => Err(grpc::JobError::Failed(
+                    Default::default(),
+                    job.error_message.clone().unwrap_or_default(),
+                )),
+            });
+
+            let (job_status, error) = job_status_strings(status);
+            state.job_status = Some(job_status);
+            state.error = error;
+            state.ended_at_ns = job
+                .finished_at
+                .and_then(|ts| ts.timestamp_nanos_opt())
+                .or_else(|| Utc::now().timestamp_nanos_opt());
+
+            Ok(())
+        })
+    }
+}
+
+/// Creates the file for `events_out`, if it was given.
+pub(crate) fn create_event_recorder(
+    events_out: Option<PathBuf>,
+    detach: bool,
+) -> PyResult<Option<EventRecorder>> {
+    match events_out {
+        Some(_) if detach => Err(PyValueError::new_err(
+            "events_out can't be used with detach",
+        )),
+        Some(path) => Ok(Some(EventRecorder::create(path)?)),
+        None => Ok(None),
+    }
+}
+
+/// Accepts either a bare snapshot ID or a snapshot URI, whose last path
+/// segment is the ID (with an optional `.zip` extension).
+/// Raises `NamespaceNotFoundError` if `namespace` doesn't exist on `at_ref`,
+/// suggesting similar names, so that a typo fails before the run is
+/// submitted. The check is skipped if the namespaces can't be listed.
+fn preflight_namespace(client: &Client, at_ref: &str, namespace: &str) -> PyResult<()> {
+    const CHECK_TIMEOUT: time::Duration = time::Duration::from_secs(5);
+
+    let req = GetNamespaces {
+        at_ref,
+        filter_by_name: None,
     }
+    .paginate(None, Some(NAMESPACE_CHECK_LIMIT));
+
+    let res =
+        super::roundtrip_detached(req, &client.profile, &client.agent()?, Some(CHECK_TIMEOUT));
+    let listing = match res {
+        Ok(listing) => listing,
+        Err(e) => {
+            debug!(at_ref, namespace, error = %e, "skipping namespace check");
+            return Ok(());
+        }
+    };
+
+    let NamespaceCheck::Missing { suggestions } = check_namespace(namespace, listing) else {
+        return Ok(());
+    };
+
+    let mut message = format!("namespace {namespace:?} doesn't exist on {at_ref:?}");
+    if !suggestions.is_empty() {
+        message.push_str(&format!("; did you mean: {}?", suggestions.join(", ")));
+    }
+
+    Err(PyErr::new::<NamespaceNotFoundError, _>((
+        404u16,
+        "NAMESPACE_NOT_FOUND".to_owned(),
+        message,
+        None::<ApiErrorKind>,
+    )))
+}
+
+fn snapshot_id_from_arg(snapshot: &str) -> &str {
+    let last = snapshot.rsplit('/').next().unwrap_or(snapshot);
+    last.strip_suffix(".zip").unwrap_or(last)
 }
 
 async fn resolve_job_parameters(
@@ -2,17 +2,24 @@ use std::collections::HashMap;
 use std::fmt;
 
 use chrono::{DateTime, Utc};
-use pyo3::prelude::*;
+use pyo3::{
+    exceptions::{PyAssertionError, PyDeprecationWarning},
+    prelude::*,
+    types::PyDict,
+};
 
+use crate::events::{Task, TaskOutcome};
+use crate::grpc::{explain, materialization};
 use crate::python::job::JobLogEvent;
 
 /// The execution context for a run, capturing the parameters that were
 /// used to launch it.
-#[derive(Clone)]
+#[derive(Clone, Default, PartialEq)]
 #[pyclass(
     name = "RunExecutionContext",
     module = "bauplan.state",
     skip_from_py_object,
+    eq,
     get_all
 )]
 pub(crate) struct RunExecutionContext {
@@ -20,7 +27,8 @@ pub(crate) struct RunExecutionContext {
     pub snapshot_id: String,
     /// URI locating the project snapshot that the server executed.
     pub snapshot_uri: String,
-    /// Local project directory that was packaged and submitted.
+    /// Local project directory that was packaged and submitted, or empty
+    /// for a project passed in memory.
     pub project_dir: String,
     /// Ref (branch or tag) the run was executed against.
     pub r#ref: String,
@@ -52,6 +60,31 @@ impl RunExecutionContext {
             self.r#ref, self.namespace,
         )
     }
+
+    /// Alias of `ref`, for consistency with the table job contexts. Runs can
+    /// also be executed against a tag, so `ref` is the canonical name.
+    #[getter]
+    fn branch_name(&self) -> &str {
+        &self.r#ref
+    }
+
+    /// The context as a dict, keyed by attribute name.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("snapshot_id", &self.snapshot_id)?;
+        dict.set_item("snapshot_uri", &self.snapshot_uri)?;
+        dict.set_item("project_dir", &self.project_dir)?;
+        dict.set_item("ref", &self.r#ref)?;
+        dict.set_item("namespace", &self.namespace)?;
+        dict.set_item("dry_run", self.dry_run)?;
+        dict.set_item("transaction", &self.transaction)?;
+        dict.set_item("strict", &self.strict)?;
+        dict.set_item("cache", &self.cache)?;
+        dict.set_item("preview", &self.preview)?;
+        dict.set_item("debug", self.debug)?;
+        dict.set_item("detach", self.detach)?;
+        Ok(dict)
+    }
 }
 
 impl fmt::Debug for RunExecutionContext {
@@ -68,6 +101,208 @@ impl fmt::Debug for RunExecutionContext {
     }
 }
 
+/// A table that a run planned to write a model to. For dry runs, this is a
+/// table that would have been written.
+#[derive(Debug, Clone)]
+#[pyclass(
+    name = "PlannedMaterialization",
+    module = "bauplan.state",
+    skip_from_py_object,
+    get_all
+)]
+pub(crate) struct PlannedMaterialization {
+    /// Name of the table.
+    pub table_name: String,
+    /// Namespace of the table.
+    pub namespace: String,
+    /// Ref the table would be written on.
+    pub r#ref: String,
+    /// Partitioning expressions for the table. The server doesn't report
+    /// them yet, so this is always empty.
+    pub partitioned_by: Vec<String>,
+}
+
+impl From<materialization::PlannedMaterialization> for PlannedMaterialization {
+    fn from(m: materialization::PlannedMaterialization) -> Self {
+        Self {
+            table_name: m.table_name,
+            namespace: m.namespace,
+            r#ref: m.r#ref,
+            partitioned_by: m.partitioned_by,
+        }
+    }
+}
+
+#[pymethods]
+impl PlannedMaterialization {
+    fn __repr__(&self) -> String {
+        format!(
+            "PlannedMaterialization(table={}.{}, ref={:?})",
+            self.namespace, self.table_name, self.r#ref,
+        )
+    }
+}
+
+/// A model in a run's plan, as returned by `Client.explain_run`.
+#[derive(Debug, Clone)]
+#[pyclass(
+    name = "PlannedModel",
+    module = "bauplan.state",
+    skip_from_py_object,
+    get_all
+)]
+pub(crate) struct PlannedModel {
+    /// ID of the model, as referenced by `depends_on`.
+    pub model_id: String,
+    /// Name of the model.
+    pub model_name: String,
+    /// IDs of the models this model reads from.
+    pub depends_on: Vec<String>,
+}
+
+impl From<explain::PlannedModel> for PlannedModel {
+    fn from(m: explain::PlannedModel) -> Self {
+        Self {
+            model_id: m.model_id,
+            model_name: m.model_name,
+            depends_on: m.depends_on,
+        }
+    }
+}
+
+#[pymethods]
+impl PlannedModel {
+    fn __repr__(&self) -> String {
+        format!(
+            "PlannedModel(name={:?}, depends_on={:?})",
+            self.model_name, self.depends_on,
+        )
+    }
+}
+
+/// The plan for a run, as returned by `Client.explain_run`: the models it
+/// would run.
+#[derive(Debug, Clone)]
+#[pyclass(
+    name = "RunPlan",
+    module = "bauplan.state",
+    skip_from_py_object,
+    get_all
+)]
+pub(crate) struct RunPlan {
+    /// ID of the job that produced the plan.
+    pub job_id: String,
+    /// The planned models.
+    pub models: Vec<PlannedModel>,
+}
+
+impl From<explain::RunPlan> for RunPlan {
+    fn from(plan: explain::RunPlan) -> Self {
+        Self {
+            job_id: plan.job_id,
+            models: plan.models.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[pymethods]
+impl RunPlan {
+    fn __repr__(&self) -> String {
+        format!(
+            "RunPlan(job_id={:?}, models={})",
+            self.job_id,
+            self.models.len()
+        )
+    }
+}
+
+/// A task of a run, built up from the events reported as it starts and
+/// completes.
+#[derive(Debug, Clone)]
+#[pyclass(
+    name = "TaskState",
+    module = "bauplan.state",
+    skip_from_py_object,
+    get_all
+)]
+pub(crate) struct TaskState {
+    /// ID of the task.
+    pub task_id: String,
+    /// Name of the task.
+    pub name: String,
+    /// What kind of task it is, e.g. `"Python model"`.
+    pub description: String,
+    /// Name of the model the task runs, if any.
+    pub model_name: Option<String>,
+    /// Name of the function the task runs, if any.
+    pub function_name: Option<String>,
+    /// File the function is defined in.
+    pub file_name: Option<String>,
+    /// Line the function is defined on.
+    pub line_number: Option<u32>,
+    /// When the task started, if it did.
+    pub started_at: Option<DateTime<Utc>>,
+    /// When the task ended, if it has.
+    pub ended_at: Option<DateTime<Utc>>,
+    /// How the task ended (`"SUCCESS"`, `"FAILED"`, `"SKIPPED"`,
+    /// `"CANCELLED"` or `"TIMEOUT"`), or `None` if it hasn't.
+    pub outcome: Option<String>,
+    /// Whether the task succeeded with output served from the cache. Always
+    /// `False` for servers that don't report it.
+    pub cached: bool,
+    /// Why the task failed, was cancelled or timed out.
+    pub error: Option<String>,
+}
+
+impl TaskState {
+    fn new(task: &Task) -> Self {
+        let metadata = &task.metadata;
+        Self {
+            task_id: task.id.clone(),
+            name: task.name.clone(),
+            description: metadata.description.clone(),
+            model_name: metadata.model_name.clone(),
+            function_name: metadata.function_name.clone(),
+            file_name: metadata.file_name.clone(),
+            line_number: metadata.line_number,
+            started_at: None,
+            ended_at: None,
+            outcome: None,
+            cached: false,
+            error: None,
+        }
+    }
+
+    /// Records that the task ended with `outcome`.
+    fn complete(&mut self, ended_at: Option<DateTime<Utc>>, outcome: TaskOutcome) {
+        let (status, error) = match outcome {
+            TaskOutcome::Success { cached, .. } => {
+                self.cached = cached;
+                ("SUCCESS", None)
+            }
+            TaskOutcome::Failure { message, .. } => ("FAILED", Some(message)),
+            TaskOutcome::Skipped => ("SKIPPED", None),
+            TaskOutcome::Cancelled { reason } => ("CANCELLED", Some(reason)),
+            TaskOutcome::Timeout { message } => ("TIMEOUT", Some(message)),
+        };
+
+        self.ended_at = ended_at;
+        self.outcome = Some(status.to_owned());
+        self.error = error.filter(|e| !e.is_empty());
+    }
+}
+
+#[pymethods]
+impl TaskState {
+    fn __repr__(&self) -> String {
+        format!(
+            "TaskState(name={:?}, outcome={:?})",
+            self.model_name.as_deref().unwrap_or(&self.name),
+            self.outcome,
+        )
+    }
+}
+
 /// The state of a completed (or failed) run, including logs, timing, and
 /// per-task lifecycle events.
 #[derive(Debug, Clone)]
@@ -77,12 +312,14 @@ pub(crate) struct RunState {
     pub job_id: Option<String>,
     /// The execution context for the run.
     pub ctx: RunExecutionContext,
-    /// User log messages emitted during the run.
+    /// User log messages emitted during the run. Past the client's
+    /// `max_user_logs`, only the first and last messages are kept, with a
+    /// marker in between; use `Client.get_job_logs` to get all of them.
     pub user_logs: Vec<JobLogEvent>,
-    /// Per-task start times, keyed by task ID.
-    pub tasks_started: HashMap<String, DateTime<Utc>>,
-    /// Per-task stop times, keyed by task ID.
-    pub tasks_stopped: HashMap<String, DateTime<Utc>>,
+    /// Whether messages were dropped from `user_logs`.
+    pub user_logs_truncated: bool,
+    /// The tasks of the run, in the order they started.
+    pub tasks: Vec<TaskState>,
     /// The final status string (e.g. "SUCCESS", "FAILED").
     pub job_status: Option<String>,
     /// Epoch nanoseconds when the run started.
@@ -91,6 +328,12 @@ pub(crate) struct RunState {
     pub ended_at_ns: Option<i64>,
     /// Error message, if the run failed.
     pub error: Option<String>,
+    /// Tables that the run planned to write models to. For dry runs, these
+    /// are the tables that would have been written.
+    pub planned_materializations: Vec<PlannedMaterialization>,
+    /// Models checked with `fail_on_empty_models` that wrote no rows. If
+    /// any did, the run is failed, with an error like `"empty output: trips"`.
+    pub empty_models: Vec<String>,
 }
 
 #[pymethods]
@@ -102,6 +345,32 @@ impl RunState {
         )
     }
 
+    /// Per-task start times, keyed by task ID.
+    ///
+    /// Deprecated: use `tasks` instead.
+    #[getter]
+    fn tasks_started(&self, py: Python<'_>) -> PyResult<HashMap<String, DateTime<Utc>>> {
+        warn_deprecated(py, c"RunState.tasks_started is deprecated; use tasks")?;
+        Ok(self
+            .tasks
+            .iter()
+            .filter_map(|t| Some((t.task_id.clone(), t.started_at?)))
+            .collect())
+    }
+
+    /// Per-task stop times, keyed by task ID.
+    ///
+    /// Deprecated: use `tasks` instead.
+    #[getter]
+    fn tasks_stopped(&self, py: Python<'_>) -> PyResult<HashMap<String, DateTime<Utc>>> {
+        warn_deprecated(py, c"RunState.tasks_stopped is deprecated; use tasks")?;
+        Ok(self
+            .tasks
+            .iter()
+            .filter_map(|t| Some((t.task_id.clone(), t.ended_at?)))
+            .collect())
+    }
+
     /// Duration in seconds, or None if the run hasn't ended.
     #[getter]
     fn duration(&self) -> Option<f64> {
@@ -114,14 +383,76 @@ impl RunState {
     fn duration_ns(&self) -> Option<i64> {
         self.ended_at_ns.map(|end| end - self.started_at_ns)
     }
+
+    /// Raises `AssertionError` if the run planned to write any table outside
+    /// of the given namespace. Combined with `dry_run=True`, this can be used
+    /// as a preflight check.
+    ///
+    /// ```python
+    /// #! client = bauplan.Client()
+    /// state = client.run("./my_pipeline", ref="main", dry_run=True)
+    /// state.assert_writes_within("staging")
+    /// ```
+    ///
+    /// Parameters:
+    ///     namespace: The only namespace the run may write to.
+    ///
+    /// Raises:
+    ///     `AssertionError`: if any planned materialization is in another namespace.
+    fn assert_writes_within(&self, namespace: &str) -> PyResult<()> {
+        let outside: Vec<_> = self
+            .planned_materializations
+            .iter()
+            .filter(|m| m.namespace != namespace)
+            .map(|m| format!("{}.{}", m.namespace, m.table_name))
+            .collect();
+
+        if outside.is_empty() {
+            Ok(())
+        } else {
+            Err(PyAssertionError::new_err(format!(
+                "run would write outside of namespace {namespace:?}: {}",
+                outside.join(", ")
+            )))
+        }
+    }
+}
+
+impl RunState {
+    /// Records that a task started.
+    pub(crate) fn task_started(&mut self, task: &Task) {
+        self.task_mut(task).started_at = task.timestamp;
+    }
+
+    /// Records that a task ended. Tasks which are skipped are reported as
+    /// completed without having started.
+    pub(crate) fn task_completed(&mut self, task: &Task, outcome: TaskOutcome) {
+        self.task_mut(task).complete(task.timestamp, outcome);
+    }
+
+    fn task_mut(&mut self, task: &Task) -> &mut TaskState {
+        match self.tasks.iter().position(|t| t.task_id == task.id) {
+            Some(i) => &mut self.tasks[i],
+            None => {
+                self.tasks.push(TaskState::new(task));
+                self.tasks.last_mut().unwrap()
+            }
+        }
+    }
+}
+
+fn warn_deprecated(py: Python<'_>, message: &std::ffi::CStr) -> PyResult<()> {
+    let category = py.get_type::<PyDeprecationWarning>();
+    PyErr::warn(py, &category, message, 1)
 }
 
 /// The parameters that were passed to a `Client.plan_table_creation` call.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 #[pyclass(
     name = "TableCreatePlanContext",
     module = "bauplan.state",
     skip_from_py_object,
+    eq,
     get_all
 )]
 pub(crate) struct TableCreatePlanContext {
@@ -141,6 +472,35 @@ pub(crate) struct TableCreatePlanContext {
     pub search_string: String,
 }
 
+#[pymethods]
+impl TableCreatePlanContext {
+    fn __repr__(&self) -> String {
+        format!(
+            "TableCreatePlanContext(branch_name={:?}, namespace={:?}, table_name={:?})",
+            self.branch_name, self.namespace, self.table_name,
+        )
+    }
+
+    /// Alias of `branch_name`, which is the canonical name, for consistency
+    /// with `bauplan.state.RunExecutionContext.ref`.
+    #[getter]
+    fn r#ref(&self) -> &str {
+        &self.branch_name
+    }
+
+    /// The context as a dict, keyed by attribute name.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("branch_name", &self.branch_name)?;
+        dict.set_item("table_name", &self.table_name)?;
+        dict.set_item("table_replace", self.table_replace)?;
+        dict.set_item("table_partitioned_by", &self.table_partitioned_by)?;
+        dict.set_item("namespace", &self.namespace)?;
+        dict.set_item("search_string", &self.search_string)?;
+        Ok(dict)
+    }
+}
+
 /// The result of a `Client.plan_table_creation` call.
 ///
 /// The `plan` field contains the schema plan as a YAML string. You can modify
@@ -248,11 +608,12 @@ impl fmt::Debug for TableCreatePlanApplyState {
 }
 
 /// The parameters that were passed to a data import job.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 #[pyclass(
     name = "TableDataImportContext",
     module = "bauplan.state",
     skip_from_py_object,
+    eq,
     get_all
 )]
 pub(crate) struct TableDataImportContext {
@@ -279,6 +640,38 @@ pub(crate) struct TableDataImportContext {
     pub preview: String,
 }
 
+#[pymethods]
+impl TableDataImportContext {
+    fn __repr__(&self) -> String {
+        format!(
+            "TableDataImportContext(branch_name={:?}, namespace={:?}, table_name={:?})",
+            self.branch_name, self.namespace, self.table_name,
+        )
+    }
+
+    /// Alias of `branch_name`, which is the canonical name, for consistency
+    /// with `bauplan.state.RunExecutionContext.ref`.
+    #[getter]
+    fn r#ref(&self) -> &str {
+        &self.branch_name
+    }
+
+    /// The context as a dict, keyed by attribute name.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("branch_name", &self.branch_name)?;
+        dict.set_item("table_name", &self.table_name)?;
+        dict.set_item("namespace", &self.namespace)?;
+        dict.set_item("search_string", &self.search_string)?;
+        dict.set_item("import_duplicate_files", self.import_duplicate_files)?;
+        dict.set_item("best_effort", self.best_effort)?;
+        dict.set_item("continue_on_error", self.continue_on_error)?;
+        dict.set_item("transformation_query", &self.transformation_query)?;
+        dict.set_item("preview", &self.preview)?;
+        Ok(dict)
+    }
+}
+
 /// The state of a completed data import job.
 #[derive(Clone)]
 #[pyclass(
@@ -321,11 +714,12 @@ impl fmt::Debug for TableDataImportState {
 ///
 /// External tables are read-only Iceberg tables registered against data that
 /// already lives in object storage; no data is copied.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 #[pyclass(
     name = "ExternalTableCreateContext",
     module = "bauplan.state",
     skip_from_py_object,
+    eq,
     get_all
 )]
 pub(crate) struct ExternalTableCreateContext {
@@ -337,6 +731,32 @@ pub(crate) struct ExternalTableCreateContext {
     pub namespace: String,
 }
 
+#[pymethods]
+impl ExternalTableCreateContext {
+    fn __repr__(&self) -> String {
+        format!(
+            "ExternalTableCreateContext(branch_name={:?}, namespace={:?}, table_name={:?})",
+            self.branch_name, self.namespace, self.table_name,
+        )
+    }
+
+    /// Alias of `branch_name`, which is the canonical name, for consistency
+    /// with `bauplan.state.RunExecutionContext.ref`.
+    #[getter]
+    fn r#ref(&self) -> &str {
+        &self.branch_name
+    }
+
+    /// The context as a dict, keyed by attribute name.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("branch_name", &self.branch_name)?;
+        dict.set_item("table_name", &self.table_name)?;
+        dict.set_item("namespace", &self.namespace)?;
+        Ok(dict)
+    }
+}
+
 /// The state of a completed external table creation job.
 #[derive(Clone)]
 #[pyclass(
@@ -24,13 +24,27 @@ pub mod schema {
     #[pymodule_export]
     use crate::namespace::Namespace;
     #[pymodule_export]
+    use crate::python::table::PyTableSync as TableSync;
+    #[pymodule_export]
+    use crate::table::ColumnDiff;
+    #[pymodule_export]
+    use crate::table::DiffKind;
+    #[pymodule_export]
+    use crate::table::PartitionDiff;
+    #[pymodule_export]
     use crate::table::PartitionField;
     #[pymodule_export]
+    use crate::table::RefSummary;
+    #[pymodule_export]
     use crate::table::Table;
     #[pymodule_export]
+    use crate::table::TableDiff;
+    #[pymodule_export]
     use crate::table::TableField;
     #[pymodule_export]
     use crate::table::TableKind;
+    #[pymodule_export]
+    use crate::table::TableSnapshot;
 
     // Jobs
     #[pymodule_export]
@@ -46,6 +60,10 @@ pub mod schema {
     #[pymodule_export]
     use crate::python::job::JobContext;
     #[pymodule_export]
+    use crate::python::job::JobDAG;
+    #[pymodule_export]
+    use crate::python::job::JobLifecycleEvent;
+    #[pymodule_export]
     use crate::python::job::JobLogEvent;
     #[pymodule_export]
     use crate::python::job::JobLogLevel;
@@ -7,8 +7,14 @@ pub mod state {
     #[pymodule_export]
     use crate::python::run::state::ExternalTableCreateState;
     #[pymodule_export]
+    use crate::python::run::state::PlannedMaterialization;
+    #[pymodule_export]
+    use crate::python::run::state::PlannedModel;
+    #[pymodule_export]
     use crate::python::run::state::RunExecutionContext;
     #[pymodule_export]
+    use crate::python::run::state::RunPlan;
+    #[pymodule_export]
     use crate::python::run::state::RunState;
     #[pymodule_export]
     use crate::python::run::state::TableCreatePlanApplyState;
@@ -20,4 +26,6 @@ pub mod state {
     use crate::python::run::state::TableDataImportContext;
     #[pymodule_export]
     use crate::python::run::state::TableDataImportState;
+    #[pymodule_export]
+    use crate::python::run::state::TaskState;
 }
@@ -1,33 +1,56 @@
 //! Table operations.
 
-use std::collections::BTreeMap;
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
-use commanderpb::runner_event::Event as RunnerEvent;
-use pyo3::{exceptions::PyTypeError, prelude::*};
+use pyo3::{
+    exceptions::{PyTimeoutError, PyTypeError, PyValueError},
+    prelude::*,
+};
+use tracing::info;
 
 use crate::{
-    ApiErrorKind, ApiRequest, CatalogRef,
+    ApiError, ApiErrorKind, ApiRequest, CatalogRef, TableReadiness, TableWait,
     api::table::Table,
+    branch::{GetBranch, WatchSchedule},
     commit::CommitOptions,
-    grpc::generated as commanderpb,
-    iceberg::RegisterTable,
+    events::{JobEvent, capture::EventRecorder},
+    grpc::{generated as commanderpb, job::Priority, search_uri},
+    iceberg::{GetCatalogConfig, RegisterTable},
+    namespace::GetNamespaces,
     python::{
         job_err,
         namespace::NamespaceArg,
         paginate::PyPaginator,
         refs::{BranchArg, RefArg},
     },
-    table::{DeleteTable, GetTable, GetTables, RevertTable},
+    sync::TableSync,
+    table::{
+        DeleteTable, GetTable, GetTableSnapshots, GetTables, NAMESPACE_HINT_LIMIT, NewColumn,
+        RefSummary, RevertTable, TableDiff, UpdateTableSchema, qualified_name_hint,
+        summarize_namespaces,
+    },
 };
 
-use super::Client;
-use super::exceptions::{TableCreatePlanApplyStatusError, TableCreatePlanStatusError};
-use super::run::job_status_strings;
+use super::exceptions::{
+    BauplanError, TableCreatePlanApplyStatusError, TableCreatePlanStatusError,
+};
+use super::run::{create_event_recorder, job_status_strings};
+use super::{Client, ClientError};
 use crate::python::run::state::{
     ExternalTableCreateContext, ExternalTableCreateState, TableCreatePlanApplyState,
     TableCreatePlanContext, TableCreatePlanState, TableDataImportContext, TableDataImportState,
 };
 
+/// If the error is because the table doesn't exist, returns the ref the API
+/// looked for it at, or `None` if the error didn't include one.
+pub(crate) fn table_not_found(e: &ClientError) -> Option<Option<&CatalogRef>> {
+    DeleteTable::missing(e.api()?)
+}
+
 /// Accepts a table name or Table object (from which the name is extracted).
 pub(crate) struct TableArg(pub String);
 
@@ -45,12 +68,86 @@ impl<'a, 'py> FromPyObject<'a, 'py> for TableArg {
     }
 }
 
+/// An error while waiting for a table: from a request, or raised by the
+/// predicate or a signal handler.
+#[derive(Debug, thiserror::Error)]
+enum WaitError {
+    #[error(transparent)]
+    Client(ClientError),
+    #[error(transparent)]
+    Python(PyErr),
+}
+
+/// Looks for tables named `table_name` in any namespace, for a hint after a
+/// lookup of the bare name fails. The lookup is kept short, and any errors
+/// are ignored.
+fn suggest_qualified_names(client: &Client, table_name: &str, at_ref: &str) -> Option<String> {
+    const HINT_TIMEOUT: Duration = Duration::from_secs(3);
+
+    let req = GetTables {
+        at_ref,
+        filter_by_name: Some(table_name),
+        filter_by_namespace: None,
+    };
+
+    let tables = crate::paginate(req, Some(NAMESPACE_HINT_LIMIT), |r| {
+        super::roundtrip_detached(r, &client.profile, &client.agent()?, Some(HINT_TIMEOUT))
+    })
+    .ok()?;
+
+    qualified_name_hint(table_name, tables.map_while(Result::ok))
+}
+
+/// The tables added, removed or changed on a ref since the last sync,
+/// returned by `bauplan.Client.sync_tables`.
+#[pyclass(name = "TableSync", module = "bauplan.schema", skip_from_py_object)]
+pub(crate) struct PyTableSync(TableSync);
+
+#[pymethods]
+impl PyTableSync {
+    /// Tables that weren't on the ref at the last sync.
+    #[getter]
+    fn added(&self) -> Vec<Table> {
+        self.0.delta.added.clone()
+    }
+
+    /// The fully qualified names of tables that are no longer on the ref.
+    #[getter]
+    fn removed(&self) -> Vec<String> {
+        self.0.delta.removed.clone()
+    }
+
+    /// Tables with a new snapshot, schema or update time.
+    #[getter]
+    fn changed(&self) -> Vec<Table> {
+        self.0.delta.changed.clone()
+    }
+
+    /// Records the tables as synced, by updating the state file. Call this
+    /// once the changes have been processed.
+    fn commit(&self, py: Python<'_>) -> PyResult<()> {
+        py.detach(|| self.0.commit())
+            .map_err(|e| BauplanError::new_err(e.to_string()))
+    }
+
+    fn __repr__(&self) -> String {
+        let delta = &self.0.delta;
+        format!(
+            "TableSync(added={}, removed={}, changed={})",
+            delta.added.len(),
+            delta.removed.len(),
+            delta.changed.len(),
+        )
+    }
+}
+
 #[pymethods]
 impl Client {
     /// Create a table from an S3 location.
     ///
     /// This operation will attempt to create a table based on schemas of N
-    /// parquet files found by a given search uri. This is a two step operation using
+    /// parquet, CSV or JSONL files found by a given search uri. This is a two step
+    /// operation using
     /// `plan_table_creation` and `apply_table_creation_plan`.
     ///
     /// ```python
@@ -82,14 +179,16 @@ impl Client {
     ///
     /// Parameters:
     ///     table: The table which will be created.
-    ///     search_uri: The location of the files to scan for schema.
-    ///     branch: The branch name in which to create the table.
+    ///     search_uri: The location of the files to scan for schema, as an `s3://` URI.
+    ///     branch: The branch name in which to create the table. Defaults to the active branch.
     ///     namespace: Optional argument specifying the namespace. If not specified, it will be inferred based on table location or the default.
+    ///     file_format: The format of the files: `'parquet'`, `'csv'` or `'jsonl'`. Only parquet can be read for now. Search URIs with a known extension must match it.
     ///     partitioned_by: Optional argument specifying the table partitioning.
     ///     replace: Replace the table if it already exists.
     ///     args: dict of arbitrary args to pass to the backend.
     ///     priority: Optional job priority (1-10, where 10 is highest priority).
     ///     client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+    ///     events_out: Optional path to record the raw events of the planning and apply jobs to, for debugging. The file can be replayed with `bauplan job replay-events`.
     /// Returns:
     ///     A `bauplan.schema.Table` object.
     ///
@@ -102,38 +201,47 @@ impl Client {
         *,
         branch: "str | Branch | None" = None,
         namespace: "str | Namespace | None" = None,
+        file_format: "Literal['parquet', 'csv', 'jsonl']" = "parquet",
         partitioned_by: "str | None" = None,
         replace: "bool | None" = None,
         args: "dict[str, str] | None" = None,
         priority: "int | None" = None,
         client_timeout: "int | None" = None,
+        events_out: "str | pathlib.Path | None" = None,
     ) -> "Table")]
     #[allow(clippy::too_many_arguments)]
-    fn create_table(
+    pub(crate) fn create_table(
         &self,
         py: Python<'_>,
         table: &str,
         search_uri: &str,
         branch: Option<&str>,
         namespace: Option<&str>,
+        file_format: &str,
         partitioned_by: Option<&str>,
         replace: Option<bool>,
         args: Option<std::collections::HashMap<String, String>>,
-        priority: Option<i64>,
+        priority: Option<Priority>,
         client_timeout: Option<i64>,
+        events_out: Option<PathBuf>,
     ) -> PyResult<Table> {
+        self.check_blocking(py, "create_table")?;
+        let events = create_event_recorder(events_out, false)?;
+
         // Create the plan.
-        let plan_state = self.plan_table_creation(
+        let plan_state = self.plan_table_creation_recorded(
             py,
             table,
             search_uri,
             branch,
             namespace,
+            file_format,
             partitioned_by,
             replace,
             args.clone(),
             priority,
             client_timeout,
+            events.as_ref(),
         )?;
 
         if plan_state.error.is_some() {
@@ -161,15 +269,14 @@ impl Client {
 
         // Apply the plan.
         let timeout = self.job_timeout(client_timeout.map(|v| v as u64));
-        let common =
-            self.job_request_common(priority.map(|p| p as u32), args.unwrap_or_default())?;
+        let common = self.job_request_common(priority, args.unwrap_or_default(), None)?;
 
         let req = commanderpb::TableCreatePlanApplyRequest {
             job_request_common: Some(common),
             plan_yaml: plan_yaml.clone(),
         };
 
-        let mut client = self.grpc.clone();
+        let mut client = self.grpc()?;
         super::detach(py, async {
             let resp = client
                 .table_create_plan_apply(req)
@@ -182,7 +289,9 @@ impl Client {
                 return Err(job_err("response missing job ID"));
             };
 
-            let res = self.monitor_job(&job_id, timeout, |_| {}).await?;
+            let res = self
+                .monitor_job(&job_id, timeout, events.as_ref(), |_| {})
+                .await?;
             let (job_status, error) = job_status_strings(res);
 
             if let Some(err_msg) = error.clone() {
@@ -205,13 +314,14 @@ impl Client {
             namespace: Some(&plan_state.ctx.namespace),
         };
 
-        Ok(super::roundtrip(py, req, &self.profile, &self.agent)?)
+        Ok(super::roundtrip(py, req, &self.profile, &self.agent()?)?)
     }
 
     /// Create a table import plan from an S3 location.
     ///
     /// This operation will attempt to create a table based on schemas of N
-    /// parquet files found by a given search uri. A YAML file containing the
+    /// parquet, CSV or JSONL files found by a given search uri. A YAML file
+    /// containing the
     /// schema and plan is returned and if there are no conflicts, it is
     /// automatically applied.
     ///
@@ -259,9 +369,10 @@ impl Client {
     ///
     /// Parameters:
     ///     table: The table which will be created.
-    ///     search_uri: The location of the files to scan for schema.
-    ///     branch: The branch name in which to create the table.
+    ///     search_uri: The location of the files to scan for schema, as an `s3://` URI.
+    ///     branch: The branch name in which to create the table. Defaults to the active branch.
     ///     namespace: Optional argument specifying the namespace. If not specified, it will be inferred based on table location or the default.
+    ///     file_format: The format of the files: `'parquet'`, `'csv'` or `'jsonl'`. Only parquet can be read for now. Search URIs with a known extension must match it.
     ///     partitioned_by: Optional argument specifying the table partitioning.
     ///     replace: Replace the table if it already exists.
     ///     args: dict of arbitrary args to pass to the backend.
@@ -279,6 +390,7 @@ impl Client {
         *,
         branch: "str | Branch | None" = None,
         namespace: "str | Namespace | None" = None,
+        file_format: "Literal['parquet', 'csv', 'jsonl']" = "parquet",
         partitioned_by: "str | None" = None,
         replace: "bool | None" = None,
         args: "dict[str, str] | None" = None,
@@ -293,87 +405,28 @@ impl Client {
         search_uri: &str,
         branch: Option<&str>,
         namespace: Option<&str>,
+        file_format: &str,
         partitioned_by: Option<&str>,
         replace: Option<bool>,
         args: Option<std::collections::HashMap<String, String>>,
-        priority: Option<i64>,
+        priority: Option<Priority>,
         client_timeout: Option<i64>,
     ) -> PyResult<TableCreatePlanState> {
-        let timeout = self.job_timeout(client_timeout.map(|v| v as u64));
-        let common =
-            self.job_request_common(priority.map(|p| p as u32), args.unwrap_or_default())?;
-
-        let req = commanderpb::TableCreatePlanRequest {
-            job_request_common: Some(common),
-            branch_name: branch.map(str::to_owned),
-            table_name: table.to_owned(),
-            namespace: namespace.map(str::to_owned),
-            search_string: search_uri.to_owned(),
-            table_replace: replace.unwrap_or(false),
-            table_partitioned_by: partitioned_by.map(str::to_owned),
-        };
-
-        let mut client = self.grpc.clone();
-        super::detach(py, async {
-            let resp = client
-                .table_create_plan(req)
-                .await
-                .map_err(job_err)?
-                .into_inner();
-
-            let Some(commanderpb::JobResponseCommon { job_id, .. }) = resp.job_response_common
-            else {
-                return Err(job_err("response missing job ID"));
-            };
-
-            let ctx = TableCreatePlanContext {
-                branch_name: resp.branch_name,
-                table_name: resp.table_name,
-                table_replace: resp.table_replace,
-                table_partitioned_by: resp.table_partitioned_by,
-                namespace: resp.namespace,
-                search_string: resp.search_string,
-            };
-
-            let mut state = TableCreatePlanState {
-                job_id: Some(job_id.clone()),
-                ctx,
-                job_status: None,
-                error: None,
-                plan: None,
-                can_auto_apply: false,
-                files_to_be_imported: Vec::new(),
-            };
-
-            let res = self
-                .monitor_job(&job_id, timeout, |event| {
-                    if let RunnerEvent::TableCreatePlanDoneEvent(ev) = event {
-                        if !ev.error_message.is_empty() {
-                            state.error = Some(ev.error_message);
-                        }
-
-                        state.plan = Some(ev.plan_as_yaml);
-                        state.can_auto_apply = ev.can_auto_apply;
-                        state.files_to_be_imported = ev.files_to_be_imported;
-                    }
-                })
-                .await?;
-
-            let (job_status, error) = job_status_strings(res);
-            state.job_status = Some(job_status);
-            if let Some(e) = error
-                && state.error.is_none()
-            {
-                state.error = Some(e);
-            }
-
-            // There's a conflict in the plan, and it can't be autoapplied.
-            if state.error.is_none() && !state.can_auto_apply && state.plan.is_some() {
-                state.error = Some("table plan created but has conflicts".to_owned());
-            }
-
-            Ok(state)
-        })
+        self.check_blocking(py, "plan_table_creation")?;
+        self.plan_table_creation_recorded(
+            py,
+            table,
+            search_uri,
+            branch,
+            namespace,
+            file_format,
+            partitioned_by,
+            replace,
+            args,
+            priority,
+            client_timeout,
+            None,
+        )
     }
 
     /// Apply a plan for creating a table. It is done automatically during the
@@ -434,9 +487,11 @@ impl Client {
         py: Python<'_>,
         plan: Py<PyAny>,
         args: Option<std::collections::HashMap<String, String>>,
-        priority: Option<i64>,
+        priority: Option<Priority>,
         client_timeout: Option<i64>,
     ) -> PyResult<TableCreatePlanApplyState> {
+        self.check_blocking(py, "apply_table_creation_plan")?;
+
         // Accept either a TableCreatePlanState or a string YAML.
         let plan_yaml = if let Ok(state) = plan.extract::<TableCreatePlanState>(py) {
             state
@@ -449,15 +504,14 @@ impl Client {
         };
 
         let timeout = self.job_timeout(client_timeout.map(|v| v as u64));
-        let common =
-            self.job_request_common(priority.map(|p| p as u32), args.unwrap_or_default())?;
+        let common = self.job_request_common(priority, args.unwrap_or_default(), None)?;
 
         let req = commanderpb::TableCreatePlanApplyRequest {
             job_request_common: Some(common),
             plan_yaml,
         };
 
-        let mut client = self.grpc.clone();
+        let mut client = self.grpc()?;
         super::detach(py, async {
             let resp = client
                 .table_create_plan_apply(req)
@@ -471,7 +525,7 @@ impl Client {
                 .map(|c| c.job_id.clone())
                 .ok_or_else(|| job_err("response missing job ID"))?;
 
-            let res = self.monitor_job(&job_id, timeout, |_| {}).await?;
+            let res = self.monitor_job(&job_id, timeout, None, |_| {}).await?;
             let (job_status, error) = job_status_strings(res);
 
             if let Some(msg) = error.clone() {
@@ -508,11 +562,15 @@ impl Client {
     ///     print(f"Import succeeded: {state.job_status}")
     /// ```
     ///
+    /// With `continue_on_error`, the job succeeds even if some files fail.
+    /// The server doesn't report which files failed.
+    ///
     /// Parameters:
     ///     table: Previously created table into which data will be imported.
-    ///     search_uri: URI to scan for files to import.
-    ///     branch: Branch in which to import the table.
+    ///     search_uri: The `s3://` URI to scan for files to import.
+    ///     branch: Branch in which to import the table. Defaults to the active branch.
     ///     namespace: Namespace of the table. If not specified, namespace will be inferred from table name or default settings.
+    ///     file_format: The format of the files: `'parquet'`, `'csv'` or `'jsonl'`. Only parquet can be read for now. Files and search URIs with a known extension must match it.
     ///     continue_on_error: Do not fail the import even if 1 data import fails.
     ///     import_duplicate_files: Ignore prevention of importing s3 files that were already imported.
     ///     best_effort: Don't fail if schema of table does not match.
@@ -521,6 +579,8 @@ impl Client {
     ///     priority: Optional job priority (1-10, where 10 is highest priority).
     ///     client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
     ///     detach: Whether to detach the job and return immediately without waiting for the job to finish.
+    ///     job_name: Optional human-friendly name for the job, sent as the `bauplan.job-name` job arg.
+    ///     events_out: Optional path to record the raw events of the job to, for debugging. The file can be replayed with `bauplan job replay-events`. Can't be used with `detach`.
     /// Returns:
     ///     A `bauplan.state.TableDataImportState` object.
     #[pyo3(signature = (
@@ -529,6 +589,7 @@ impl Client {
         *,
         branch: "str | Branch | None" = None,
         namespace: "str | Namespace | None" = None,
+        file_format: "Literal['parquet', 'csv', 'jsonl']" = "parquet",
         continue_on_error: "bool" = false,
         import_duplicate_files: "bool" = false,
         best_effort: "bool" = false,
@@ -537,34 +598,51 @@ impl Client {
         priority: "int | None" = None,
         client_timeout: "int | None" = None,
         detach: "bool" = false,
+        job_name: "str | None" = None,
+        events_out: "str | pathlib.Path | None" = None,
     ) -> "TableDataImportState")]
     #[allow(clippy::too_many_arguments)]
-    fn import_data(
+    pub(crate) fn import_data(
         &self,
         py: Python<'_>,
         table: &str,
         search_uri: &str,
         branch: Option<&str>,
         namespace: Option<&str>,
+        file_format: &str,
         continue_on_error: bool,
         import_duplicate_files: bool,
         best_effort: bool,
         preview: Option<&str>,
         args: Option<std::collections::HashMap<String, String>>,
-        priority: Option<i64>,
+        priority: Option<Priority>,
         client_timeout: Option<i64>,
         detach: bool,
+        job_name: Option<String>,
+        events_out: Option<PathBuf>,
     ) -> PyResult<TableDataImportState> {
+        if !detach {
+            self.check_blocking(py, "import_data")?;
+        }
+
+        let events = create_event_recorder(events_out, detach)?;
+
         let timeout = self.job_timeout(client_timeout.map(|v| v as u64));
-        let common =
-            self.job_request_common(priority.map(|p| p as u32), args.unwrap_or_default())?;
+        let common = self.job_request_common(priority, args.unwrap_or_default(), job_name)?;
+        let search_string = search_uri::encode(search_uri)
+            .and_then(|s| {
+                let format: search_uri::FileFormat = file_format.parse()?;
+                format.check(search_uri)?;
+                Ok(s)
+            })
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
 
         let req = commanderpb::TableDataImportRequest {
             job_request_common: Some(common),
-            branch_name: branch.map(str::to_owned),
+            branch_name: Some(self.branch_or_active(branch)),
             table_name: table.to_owned(),
-            namespace: namespace.map(str::to_owned),
-            search_string: search_uri.to_owned(),
+            namespace: self.namespace_or_default(namespace),
+            search_string,
             import_duplicate_files,
             best_effort,
             continue_on_error,
@@ -572,7 +650,7 @@ impl Client {
             preview: preview.unwrap_or_default().to_owned(),
         };
 
-        let mut client = self.grpc.clone();
+        let mut client = self.grpc()?;
         super::detach(py, async {
             let resp = client
                 .table_data_import(req)
@@ -607,7 +685,10 @@ impl Client {
                 });
             }
 
-            let res = self.monitor_job(&job_id, timeout, |_| {}).await?;
+            let res = self
+                .monitor_job(&job_id, timeout, events.as_ref(), |_| {})
+                .await?;
+
             let (job_status, error) = job_status_strings(res);
 
             Ok(TableDataImportState {
@@ -641,7 +722,7 @@ impl Client {
     /// Parameters:
     ///     table: The name of the external table to create.
     ///     search_patterns: List of search_patterns for files to create the external table from. Must resolve to parquet files
-    ///     branch: Branch in which to create the table.
+    ///     branch: Branch in which to create the table. Defaults to the active branch.
     ///     namespace: Namespace of the table. If not specified, namespace will be inferred from table name or default settings.
     ///     overwrite: Whether to delete and recreate the table if it already exists.
     ///     args: dict of arbitrary args to pass to the backend.
@@ -673,19 +754,22 @@ impl Client {
         namespace: Option<&str>,
         overwrite: bool,
         args: Option<std::collections::HashMap<String, String>>,
-        priority: Option<i64>,
+        priority: Option<Priority>,
         client_timeout: Option<i64>,
         detach: bool,
     ) -> PyResult<ExternalTableCreateState> {
+        if !detach {
+            self.check_blocking(py, "create_external_table_from_parquet")?;
+        }
+
         let timeout = self.job_timeout(client_timeout.map(|v| v as u64));
-        let common =
-            self.job_request_common(priority.map(|p| p as u32), args.unwrap_or_default())?;
+        let common = self.job_request_common(priority, args.unwrap_or_default(), None)?;
 
         let req = commanderpb::ExternalTableCreateRequest {
             job_request_common: Some(common),
-            branch_name: branch.map(str::to_owned),
+            branch_name: Some(self.branch_or_active(branch)),
             table_name: table.to_owned(),
-            namespace: namespace.map(str::to_owned),
+            namespace: self.namespace_or_default(namespace),
             input_source: Some(
                 commanderpb::external_table_create_request::InputSource::InputFiles(
                     commanderpb::SearchUris {
@@ -696,7 +780,7 @@ impl Client {
             overwrite,
         };
 
-        let mut client = self.grpc.clone();
+        let mut client = self.grpc()?;
         super::detach(py, async {
             let resp = client
                 .external_table_create(req)
@@ -725,7 +809,7 @@ impl Client {
                 });
             }
 
-            let res = self.monitor_job(&job_id, timeout, |_| {}).await?;
+            let res = self.monitor_job(&job_id, timeout, None, |_| {}).await?;
             let (job_status, error) = job_status_strings(res);
 
             Ok(ExternalTableCreateState {
@@ -750,10 +834,11 @@ impl Client {
     /// ```
     ///
     /// Parameters:
-    ///     ref: The ref or branch to get the tables from.
+    ///     ref: The ref or branch to get the tables from. Defaults to the active branch.
     ///     filter_by_name: Optional, the table name to filter by.
     ///     filter_by_namespace: Optional, the namespace to get filtered tables from.
     ///     limit: Optional, max number of tables to get.
+    ///     request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
     /// Returns:
     ///     An iterator over `bauplan.schema.Table` objects.
     ///
@@ -765,24 +850,27 @@ impl Client {
     ///     `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
     ///     `ValueError`: if one or more parameters are invalid.
     #[pyo3(signature = (
-        r#ref: "str | Ref",
+        r#ref: "str | Ref | None" = None,
         *,
         filter_by_name: "str | None" = None,
         filter_by_namespace: "str | Namespace | None" = None,
         limit: "int | None" = None,
+        request_timeout: "float | None" = None,
     ) -> "typing.Iterator[Table]")]
     fn get_tables(
         &self,
         py: Python<'_>,
-        r#ref: RefArg,
+        r#ref: Option<RefArg>,
         filter_by_name: Option<String>,
         filter_by_namespace: Option<NamespaceArg>,
         limit: Option<usize>,
+        request_timeout: Option<f64>,
     ) -> PyResult<PyPaginator> {
-        let r#ref = r#ref.0;
+        let timeout = self.request_timeout(request_timeout)?;
+        let r#ref = self.ref_or_active(r#ref).resolve(py, self)?;
         let filter_by_namespace = filter_by_namespace.map(|a| a.0);
         let profile = self.profile.clone();
-        let agent = self.agent.clone();
+        let agent = self.agent()?;
         PyPaginator::new(py, limit, move |py, token, limit| {
             let req = GetTables {
                 at_ref: &r#ref,
@@ -791,10 +879,150 @@ impl Client {
             }
             .paginate(token, limit);
 
-            Ok(super::roundtrip(py, req, &profile, &agent)?)
+            Ok(super::roundtrip_timeout(
+                py,
+                req,
+                &profile,
+                &agent,
+                Some(timeout),
+            )?)
         })
     }
 
+    /// Get aggregate statistics for the tables in a ref: the number of
+    /// tables, their total records and size, and when any of them was last
+    /// updated.
+    ///
+    /// This lists every table in the ref, a few namespaces at a time, so it
+    /// can be slow for refs with many tables.
+    ///
+    /// ```python
+    /// import bauplan
+    /// client = bauplan.Client()
+    ///
+    /// summary = client.get_ref_summary('main')
+    /// print(f"{summary.tables} tables, {summary.total_size} bytes")
+    /// ```
+    ///
+    /// Parameters:
+    ///     ref: The ref or branch to summarize. Defaults to the active branch.
+    /// Returns:
+    ///     A `bauplan.schema.RefSummary` object.
+    ///
+    /// Raises:
+    ///     `bauplan.exceptions.RefNotFoundError`: if the ref does not exist.
+    ///     `bauplan.exceptions.InvalidRefError`: if the ref format is invalid.
+    ///     `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+    #[pyo3(signature = (r#ref: "str | Ref | None" = None, /) -> "RefSummary")]
+    fn get_ref_summary(&self, py: Python<'_>, r#ref: Option<RefArg>) -> PyResult<RefSummary> {
+        let r#ref = self.ref_or_active(r#ref).resolve(py, self)?;
+        let profile = &self.profile;
+        let agent = &self.agent()?;
+
+        let summary = py.detach(|| {
+            let req = GetNamespaces {
+                at_ref: &r#ref,
+                filter_by_name: None,
+            };
+
+            let namespaces = crate::paginate(req, None, |r| {
+                super::roundtrip_detached(r, profile, agent, None)
+            })?
+            .map(|ns| ns.map(|ns| ns.name))
+            .collect::<Result<Vec<_>, _>>()?;
+
+            summarize_namespaces(&r#ref, &namespaces, |ns| {
+                let req = GetTables {
+                    at_ref: &r#ref,
+                    filter_by_name: None,
+                    filter_by_namespace: Some(ns),
+                };
+
+                let mut summary = RefSummary::new(r#ref.as_str());
+                for table in crate::paginate(req, None, |r| {
+                    super::roundtrip_detached(r, profile, agent, None)
+                })? {
+                    // The namespace filter may also match by regex.
+                    let table = table?;
+                    if table.namespace == ns {
+                        summary.add(&table);
+                    }
+                }
+
+                Ok::<_, super::ClientError>(summary)
+            })
+        })?;
+
+        Ok(summary)
+    }
+
+    /// Get the tables added, removed or changed on a ref since the last sync,
+    /// for mirroring the catalog into another system.
+    ///
+    /// The tables as of each sync are recorded in `state_file`, a small JSON
+    /// file; if it doesn't exist yet, every table is reported as added. The
+    /// file is only updated when `commit()` is called on the result, so if
+    /// processing the changes fails, the next sync reports them again.
+    ///
+    /// ```python
+    /// import bauplan
+    /// client = bauplan.Client()
+    ///
+    /// sync = client.sync_tables('main', state_file='catalog_sync.json')
+    /// for table in sync.added + sync.changed:
+    ///     ...  # upsert into the external catalog
+    /// for name in sync.removed:
+    ///     ...  # remove from the external catalog
+    /// sync.commit()
+    /// ```
+    ///
+    /// Parameters:
+    ///     ref: The ref or branch to sync. Defaults to the active branch. The state file records the ref as given, and can't be reused for a different one.
+    ///     state_file: The path of the file recording the tables as of the last sync.
+    /// Returns:
+    ///     A `bauplan.schema.TableSync` object.
+    ///
+    /// Raises:
+    ///     `bauplan.exceptions.RefNotFoundError`: if the ref does not exist.
+    ///     `bauplan.exceptions.InvalidRefError`: if the ref format is invalid.
+    ///     `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+    ///     `bauplan.exceptions.BauplanError`: if the state file is invalid or belongs to a different ref.
+    #[pyo3(signature = (
+        r#ref: "str | Ref | None" = None,
+        *,
+        state_file: "str | pathlib.Path",
+    ) -> "TableSync")]
+    fn sync_tables(
+        &self,
+        py: Python<'_>,
+        r#ref: Option<RefArg>,
+        state_file: PathBuf,
+    ) -> PyResult<PyTableSync> {
+        // The state is recorded against the ref as given, so that a branch
+        // can be synced as it moves.
+        let sync_ref = self.ref_or_active(r#ref);
+        let name = sync_ref.0.clone();
+        let at_ref = sync_ref.resolve(py, self)?;
+        let profile = &self.profile;
+        let agent = &self.agent()?;
+
+        let sync = py.detach(|| {
+            let req = GetTables {
+                at_ref: &at_ref,
+                filter_by_name: None,
+                filter_by_namespace: None,
+            };
+
+            let tables = crate::paginate(req, None, |r| {
+                super::roundtrip_detached(r, profile, agent, None)
+            })?;
+
+            TableSync::new::<ClientError>(&state_file, &name, tables)
+        })?;
+
+        Ok(PyTableSync(sync))
+    }
+
     /// Get the table data and metadata for a table in the target branch.
     ///
     /// Upon failure, raises `bauplan.exceptions.BauplanError`
@@ -819,9 +1047,11 @@ impl Client {
     /// ```
     ///
     /// Parameters:
-    ///     ref: The ref, branch name or tag name to get the table from.
+    ///     ref: The ref, branch name or tag name to get the table from. Defaults to the active branch.
     ///     table: The table to retrieve.
     ///     namespace: The namespace of the table to retrieve.
+    ///     suggest_namespaces: If `True` and a table given without a namespace isn't found, look for tables with that name in other namespaces, and suggest them in the `TableNotFoundError` message.
+    ///     request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
     /// Returns:
     ///     a `bauplan.schema.Table` object
     ///
@@ -835,25 +1065,118 @@ impl Client {
     ///     `ValueError`: if one or more parameters are invalid.
     #[pyo3(signature = (
         table: "str | Table",
-        r#ref: "str | Ref",
+        r#ref: "str | Ref | None" = None,
         *,
         namespace: "str | Namespace | None" = None,
+        suggest_namespaces: "bool" = false,
+        request_timeout: "float | None" = None,
     ) -> "Table")]
     fn get_table(
         &self,
         py: Python<'_>,
         table: TableArg,
-        r#ref: RefArg,
+        r#ref: Option<RefArg>,
         namespace: Option<NamespaceArg>,
+        suggest_namespaces: bool,
+        request_timeout: Option<f64>,
     ) -> PyResult<Table> {
+        let timeout = self.request_timeout(request_timeout)?;
         let namespace = namespace.map(|a| a.0);
+        let at_ref = self.ref_or_active(r#ref).resolve(py, self)?;
         let req = GetTable {
             name: &table.0,
-            at_ref: &r#ref.0,
+            at_ref: &at_ref,
             namespace: namespace.as_deref(),
         };
 
-        Ok(super::roundtrip(py, req, &self.profile, &self.agent)?)
+        match super::roundtrip_timeout(py, req, &self.profile, &self.agent()?, Some(timeout)) {
+            Err(ClientError::Api(mut e))
+                if suggest_namespaces
+                    && namespace.is_none()
+                    && !table.0.contains('.')
+                    && matches!(e.kind(), Some(ApiErrorKind::TableNotFound { .. })) =>
+            {
+                if let Some(hint) = py.detach(|| suggest_qualified_names(self, &table.0, &at_ref))
+                    && let ApiError::ErrorResponse { message, .. } = &mut e
+                {
+                    *message = Some(match message.take() {
+                        Some(message) => format!("{message}; {hint}"),
+                        None => hint,
+                    });
+                }
+
+                Err(e.into_py_err())
+            }
+            res => Ok(res?),
+        }
+    }
+
+    /// Get the snapshot history of a table, newest first. Each snapshot is
+    /// the state of the table after one write. Queries can't read a table as
+    /// of a snapshot, so these are for inspecting the table's history.
+    ///
+    /// ```python
+    /// import bauplan
+    /// client = bauplan.Client()
+    ///
+    /// for snapshot in client.get_table_snapshots('titanic', 'main', namespace='bauplan'):
+    ///     print(snapshot.snapshot_id, snapshot.timestamp, snapshot.operation)
+    /// ```
+    ///
+    /// Parameters:
+    ///     table: The table to get the snapshots of.
+    ///     ref: The ref, branch name or tag name to read the table at. Defaults to the active branch.
+    ///     namespace: The namespace of the table.
+    ///     limit: Optional, max number of snapshots to get.
+    ///     request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
+    /// Returns:
+    ///     An iterator over `bauplan.schema.TableSnapshot` objects.
+    ///
+    /// Raises:
+    ///     `bauplan.exceptions.RefNotFoundError`: if the ref does not exist.
+    ///     `bauplan.exceptions.InvalidRefError`: if the ref format is invalid.
+    ///     `bauplan.exceptions.NamespaceNotFoundError`: if the namespace does not exist.
+    ///     `bauplan.exceptions.TableNotFoundError`: if the table does not exist.
+    ///     `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+    ///     `ValueError`: if one or more parameters are invalid.
+    #[pyo3(signature = (
+        table: "str | Table",
+        r#ref: "str | Ref | None" = None,
+        *,
+        namespace: "str | Namespace | None" = None,
+        limit: "int | None" = None,
+        request_timeout: "float | None" = None,
+    ) -> "typing.Iterator[TableSnapshot]")]
+    fn get_table_snapshots(
+        &self,
+        py: Python<'_>,
+        table: TableArg,
+        r#ref: Option<RefArg>,
+        namespace: Option<NamespaceArg>,
+        limit: Option<usize>,
+        request_timeout: Option<f64>,
+    ) -> PyResult<PyPaginator> {
+        let timeout = self.request_timeout(request_timeout)?;
+        let at_ref = self.ref_or_active(r#ref).resolve(py, self)?;
+        let namespace = namespace.map(|a| a.0);
+        let profile = self.profile.clone();
+        let agent = self.agent()?;
+        PyPaginator::new(py, limit, move |py, token, limit| {
+            let req = GetTableSnapshots {
+                name: &table.0,
+                at_ref: &at_ref,
+                namespace: namespace.as_deref(),
+            }
+            .paginate(token, limit);
+
+            Ok(super::roundtrip_timeout(
+                py,
+                req,
+                &profile,
+                &agent,
+                Some(timeout),
+            )?)
+        })
     }
 
     /// Check if a table exists.
@@ -872,7 +1195,7 @@ impl Client {
     /// ```
     ///
     /// Parameters:
-    ///     ref: The ref, branch name or tag name to get the table from.
+    ///     ref: The ref, branch name or tag name to get the table from. Defaults to the active branch.
     ///     table: The table to retrieve.
     ///     namespace: The namespace of the table to check.
     /// Returns:
@@ -886,7 +1209,7 @@ impl Client {
     ///     `ValueError`: if one or more parameters are invalid.
     #[pyo3(signature = (
         table: "str | Table",
-        r#ref: "str | Ref",
+        r#ref: "str | Ref | None" = None,
         *,
         namespace: "str | Namespace | None" = None,
     ) -> "bool")]
@@ -894,17 +1217,17 @@ impl Client {
         &self,
         py: Python<'_>,
         table: TableArg,
-        r#ref: RefArg,
+        r#ref: Option<RefArg>,
         namespace: Option<NamespaceArg>,
     ) -> PyResult<bool> {
         let namespace = namespace.map(|a| a.0);
         let req = GetTable {
             name: &table.0,
-            at_ref: &r#ref.0,
+            at_ref: &self.ref_or_active(r#ref).resolve(py, self)?,
             namespace: namespace.as_deref(),
         };
 
-        if let Err(e) = super::roundtrip(py, req, &self.profile, &self.agent) {
+        if let Err(e) = super::roundtrip(py, req, &self.profile, &self.agent()?) {
             if matches!(e.kind(), Some(ApiErrorKind::TableNotFound { .. })) {
                 return Ok(false);
             } else {
@@ -915,93 +1238,505 @@ impl Client {
         Ok(true)
     }
 
-    /// Drop a table.
-    ///
-    /// Upon failure, raises `bauplan.exceptions.BauplanError`
+    /// Compare a table between two refs: its record count, current snapshot,
+    /// columns and partition fields.
     ///
     /// ```python
     /// import bauplan
     /// client = bauplan.Client()
     ///
-    /// assert client.delete_table(
-    ///     table='my_table_name',
-    ///     branch='my_branch_name',
-    ///     namespace='my_namespace',
+    /// # what changed in titanic on my branch, compared to main?
+    /// diff = client.diff_table(
+    ///     table='titanic',
+    ///     from_ref='main',
+    ///     to_ref='my_branch_name',
+    ///     namespace='bauplan',
     /// )
+    ///
+    /// if not diff.is_empty():
+    ///     print(diff.records_delta)
+    ///     for c in diff.columns:
+    ///         print(c.name, c.kind, c.from_type, c.to_type)
     /// ```
     ///
     /// Parameters:
-    ///     table: The table to delete.
-    ///     branch: The branch on which the table is stored.
-    ///     namespace: The namespace of the table to delete.
-    ///     commit_body: Optional, the commit body message to attach to the commit.
-    ///     commit_properties: Optional, a list of properties to attach to the commit.
-    ///     if_exists: If set to `True`, the table will not raise an error if it does not exist.
+    ///     table: The table to compare.
+    ///     from_ref: The ref, branch name or tag name to compare from.
+    ///     to_ref: The ref, branch name or tag name to compare to. Defaults to the active branch.
+    ///     namespace: The namespace of the table.
+    ///     request_timeout: Optional, seconds to wait for each request. Defaults to the client's `request_timeout`.
     /// Returns:
-    ///     A `bauplan.schema.Branch` object pointing to the new head.
+    ///     a `bauplan.schema.TableDiff` object
     ///
     /// Raises:
-    ///     `bauplan.exceptions.DeleteTableForbiddenError`: if the user does not have access to delete the table.
-    ///     `bauplan.exceptions.BranchNotFoundError`: if the branch does not exist.
-    ///     `bauplan.exceptions.BranchHeadChangedError`: if the branch head hash has changed.
-    ///     `bauplan.exceptions.NotAWriteBranchRefError`: if the destination branch is not a writable ref.
-    ///     `bauplan.exceptions.MergeConflictError`: if the delete operation results in a conflict.
-    ///     `bauplan.exceptions.TableNotFoundError`: if the table does not exist.
+    ///     `bauplan.exceptions.RefNotFoundError`: if either ref does not exist.
+    ///     `bauplan.exceptions.InvalidRefError`: if either ref format is invalid.
     ///     `bauplan.exceptions.NamespaceNotFoundError`: if the namespace does not exist.
-    ///     `bauplan.exceptions.NamespaceUnresolvedError`: if conflicting namespaces names are specified.
-    ///     `bauplan.exceptions.InvalidRefError`: if the ref format is invalid.
+    ///     `bauplan.exceptions.TableNotFoundError`: if the table does not exist on either ref.
     ///     `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
     ///     `ValueError`: if one or more parameters are invalid.
     #[pyo3(signature = (
         table: "str | Table",
-        branch: "str | Branch",
+        from_ref: "str | Ref",
+        to_ref: "str | Ref | None" = None,
         *,
         namespace: "str | Namespace | None" = None,
-        if_exists: "bool" = false,
-        commit_body: "str | None" = None,
-        commit_properties: "dict[str, str] | None" = None,
-    ) -> "Branch")]
-    #[allow(clippy::too_many_arguments)]
-    fn delete_table(
+        request_timeout: "float | None" = None,
+    ) -> "TableDiff")]
+    fn diff_table(
         &self,
         py: Python<'_>,
         table: TableArg,
-        branch: BranchArg,
+        from_ref: RefArg,
+        to_ref: Option<RefArg>,
         namespace: Option<NamespaceArg>,
-        if_exists: bool,
-        commit_body: Option<&str>,
-        commit_properties: Option<BTreeMap<String, String>>,
-    ) -> PyResult<CatalogRef> {
+        request_timeout: Option<f64>,
+    ) -> PyResult<TableDiff> {
+        let timeout = self.request_timeout(request_timeout)?;
         let namespace = namespace.map(|a| a.0);
-        let commit_properties = commit_properties.unwrap_or_default();
-        let properties = commit_properties
-            .iter()
-            .map(|(k, v)| (k.as_str(), v.as_str()))
-            .collect();
+        let from_ref = from_ref.resolve(py, self)?;
+        let to_ref = self.ref_or_active(to_ref).resolve(py, self)?;
+
+        let get = |at_ref: &str| {
+            let req = GetTable {
+                name: &table.0,
+                at_ref,
+                namespace: namespace.as_deref(),
+            };
 
-        let req = DeleteTable {
-            name: &table.0,
-            branch: &branch.0,
-            namespace: namespace.as_deref(),
-            commit: CommitOptions {
-                body: commit_body,
-                properties,
-            },
+            super::roundtrip_timeout(py, req, &self.profile, &self.agent()?, Some(timeout))
+                .map_err(PyErr::from)
         };
 
-        match super::roundtrip(py, req, &self.profile, &self.agent) {
-            Ok(r) => Ok(r),
-            Err(e) => {
-                if if_exists && let Some(ApiErrorKind::TableNotFound { catalog_ref, .. }) = e.kind()
-                {
-                    Ok(catalog_ref.clone())
-                } else {
-                    Err(e.into())
-                }
+        let from = get(&from_ref)?;
+        let to = get(&to_ref)?;
+        Ok(TableDiff::new(&from_ref, &from, &to_ref, &to))
+    }
+
+    /// Wait for a table to exist, and optionally to meet some conditions.
+    ///
+    /// If `ref` is a branch, it's polled with conditional requests, and the
+    /// table is only fetched again when the branch moves, so polling is
+    /// cheap. Polls that fail with a transient error (a network failure, a
+    /// rate limit or a server error) are retried with backoff.
+    ///
+    /// ```python
+    /// import bauplan
+    /// client = bauplan.Client()
+    ///
+    /// # wait up to ten minutes for an upstream import to land some rows
+    /// table = client.wait_for_table(
+    ///     table='titanic',
+    ///     ref='my_branch_name',
+    ///     timeout=600,
+    ///     min_records=1,
+    /// )
+    /// ```
+    ///
+    /// Parameters:
+    ///     table: The table to wait for.
+    ///     ref: The ref, branch name or tag name to poll the table on. Defaults to the active branch.
+    ///     timeout: Seconds to wait before giving up.
+    ///     namespace: The namespace of the table.
+    ///     min_records: Optional, the fewest records the table must have.
+    ///     after_snapshot_id: Optional, a snapshot ID that the table must have moved on from, like its `current_snapshot_id` before an import.
+    ///     predicate: Optional, a callable that takes the `Table` and returns `True` once it's ready. It's called each time the table is fetched, once the other conditions are met.
+    ///     poll_interval: Seconds between polls. Each delay is jittered by up to 20%.
+    /// Returns:
+    ///     The `bauplan.schema.Table`, once it's ready.
+    ///
+    /// Raises:
+    ///     `TimeoutError`: if the table isn't ready before the timeout. Its `table` attribute holds the table as last seen, or `None` if it didn't exist.
+    ///     `bauplan.exceptions.RefNotFoundError`: if the ref does not exist.
+    ///     `bauplan.exceptions.InvalidRefError`: if the ref format is invalid.
+    ///     `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+    ///     `ValueError`: if one or more parameters are invalid.
+    #[pyo3(signature = (
+        table: "str | Table",
+        r#ref: "str | Ref | None" = None,
+        timeout: "float" = 600.0,
+        *,
+        namespace: "str | Namespace | None" = None,
+        min_records: "int | None" = None,
+        after_snapshot_id: "int | None" = None,
+        predicate: "typing.Callable[[Table], bool] | None" = None,
+        poll_interval: "float" = 5.0,
+    ) -> "Table")]
+    #[allow(clippy::too_many_arguments)]
+    fn wait_for_table(
+        &self,
+        py: Python<'_>,
+        table: TableArg,
+        r#ref: Option<RefArg>,
+        timeout: f64,
+        namespace: Option<NamespaceArg>,
+        min_records: Option<u64>,
+        after_snapshot_id: Option<i64>,
+        predicate: Option<Bound<'_, PyAny>>,
+        poll_interval: f64,
+    ) -> PyResult<Table> {
+        let interval = super::positive_secs("poll_interval", poll_interval)?;
+        let timeout = super::positive_secs("timeout", timeout)?;
+        if let Some(predicate) = &predicate
+            && !predicate.is_callable()
+        {
+            return Err(PyTypeError::new_err("predicate must be callable"));
+        }
+
+        let start = Instant::now();
+        let deadline = start + timeout;
+        let namespace = namespace.map(|a| a.0);
+        let at_ref = self.ref_or_active(r#ref).resolve(py, self)?;
+        let req = GetTable {
+            name: &table.0,
+            at_ref: &at_ref,
+            namespace: namespace.as_deref(),
+        };
+
+        let readiness = TableReadiness {
+            min_records,
+            after_snapshot_id,
+        };
+
+        let agent = self.agent()?;
+        let res = crate::wait_for_table(
+            req,
+            WatchSchedule::new(interval),
+            |req| super::roundtrip(py, req, &self.profile, &agent).map_err(WaitError::Client),
+            |req| super::roundtrip(py, req, &self.profile, &agent).map_err(WaitError::Client),
+            |e| match e {
+                WaitError::Client(ClientError::Api(e)) => Some(e),
+                _ => None,
+            },
+            |t| match &predicate {
+                _ if !readiness.is_ready(t) => Ok(false),
+                Some(predicate) => predicate
+                    .call1((t.clone(),))
+                    .and_then(|r| r.is_truthy())
+                    .map_err(WaitError::Python),
+                None => Ok(true),
+            },
+            |delay| super::sleep_until(py, delay, Some(deadline)).map_err(WaitError::Python),
+        );
+
+        match res {
+            Ok(TableWait::Ready(t)) => Ok(t),
+            Ok(TableWait::TimedOut(last)) => {
+                let seen = match &last {
+                    Some(t) => format!(
+                        "last seen with {} records at snapshot {}",
+                        t.records.unwrap_or(0),
+                        t.current_snapshot_id
+                            .map_or_else(|| "none".to_owned(), |id| id.to_string()),
+                    ),
+                    None => "it doesn't exist".to_owned(),
+                };
+
+                let err = PyTimeoutError::new_err(format!(
+                    "table {:?} on {at_ref:?} wasn't ready after {:.1}s; {seen}",
+                    table.0,
+                    start.elapsed().as_secs_f64(),
+                ));
+                err.value(py).setattr("table", last)?;
+                Err(err)
             }
+            Err(WaitError::Client(e)) => Err(e.into()),
+            Err(WaitError::Python(e)) => Err(e),
         }
     }
 
+    /// Add, drop or rename columns of a table, in a single commit.
+    ///
+    /// The changes are checked against the table's current schema before
+    /// they're sent. If the schema changes in the meantime, the update fails
+    /// instead of being applied to the new schema.
+    ///
+    /// ```python
+    /// import bauplan
+    /// client = bauplan.Client()
+    ///
+    /// table = client.alter_table(
+    ///     table='my_table_name',
+    ///     branch='my_branch_name',
+    ///     add_columns=[('notes', 'string', False)],
+    ///     drop_columns=['old_column'],
+    ///     rename_columns={'name': 'full_name'},
+    /// )
+    /// ```
+    ///
+    /// Parameters:
+    ///     table: The table to change.
+    ///     branch: The branch on which the table is stored. Defaults to the active branch.
+    ///     namespace: The namespace of the table.
+    ///     add_columns: Optional, columns to add, as `(name, type, required)` tuples. Types are Iceberg types, like `'string'` or `'decimal(10,2)'`.
+    ///     drop_columns: Optional, names of columns to drop. Partition columns can't be dropped.
+    ///     rename_columns: Optional, a dict from old column names to new ones.
+    ///     commit_body: Optional, the commit body message to attach to the commit.
+    ///     commit_properties: Optional, a list of properties to attach to the commit.
+    ///     request_timeout: Optional, seconds to wait for each request. Defaults to the client's `request_timeout`.
+    /// Returns:
+    ///     The updated `bauplan.schema.Table`.
+    ///
+    /// Raises:
+    ///     `bauplan.exceptions.TableNotFoundError`: if the table does not exist.
+    ///     `bauplan.exceptions.BranchNotFoundError`: if the branch does not exist.
+    ///     `bauplan.exceptions.NotAWriteBranchRefError`: if the branch is not a writable ref.
+    ///     `bauplan.exceptions.BranchHeadChangedError`: if the branch head hash has changed.
+    ///     `bauplan.exceptions.UpdateConflictError`: if the table's schema changed concurrently.
+    ///     `bauplan.exceptions.InvalidDataError`: if the catalog rejects the changes.
+    ///     `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+    ///     `ValueError`: if the changes don't apply to the table's schema, or one or more parameters are invalid.
+    #[pyo3(signature = (
+        table: "str | Table",
+        branch: "str | Branch | None" = None,
+        *,
+        namespace: "str | Namespace | None" = None,
+        add_columns: "list[tuple[str, str, bool]] | None" = None,
+        drop_columns: "list[str] | None" = None,
+        rename_columns: "dict[str, str] | None" = None,
+        commit_body: "str | None" = None,
+        commit_properties: "dict[str, str] | None" = None,
+        request_timeout: "float | None" = None,
+    ) -> "Table")]
+    #[allow(clippy::too_many_arguments)]
+    fn alter_table(
+        &self,
+        py: Python<'_>,
+        table: TableArg,
+        branch: Option<BranchArg>,
+        namespace: Option<NamespaceArg>,
+        add_columns: Option<Vec<(String, String, bool)>>,
+        drop_columns: Option<Vec<String>>,
+        rename_columns: Option<BTreeMap<String, String>>,
+        commit_body: Option<&str>,
+        commit_properties: Option<BTreeMap<String, String>>,
+        request_timeout: Option<f64>,
+    ) -> PyResult<Table> {
+        let timeout = self.request_timeout(request_timeout)?;
+        let namespace = namespace.map(|a| a.0);
+        let branch = self.branch_or_active(branch.map(|b| b.0));
+        let commit_properties = commit_properties.unwrap_or_default();
+        let add_columns = add_columns.unwrap_or_default();
+        let drop_columns = drop_columns.unwrap_or_default();
+        let rename_columns = rename_columns.unwrap_or_default();
+
+        let req = GetTable {
+            name: &table.0,
+            at_ref: &branch,
+            namespace: namespace.as_deref(),
+        };
+        let current =
+            super::roundtrip_timeout(py, req, &self.profile, &self.agent()?, Some(timeout))?;
+
+        let req = UpdateTableSchema {
+            name: &table.0,
+            branch: &branch,
+            namespace: namespace.as_deref(),
+            add_columns: add_columns
+                .iter()
+                .map(|(name, r#type, required)| NewColumn {
+                    name,
+                    r#type,
+                    required: *required,
+                })
+                .collect(),
+            drop_columns: drop_columns.iter().map(String::as_str).collect(),
+            rename_columns: rename_columns
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect(),
+            expected_schema_id: current.current_schema_id,
+            commit: CommitOptions {
+                body: commit_body,
+                properties: commit_properties
+                    .iter()
+                    .map(|(k, v)| (k.as_str(), v.as_str()))
+                    .collect(),
+            },
+        };
+
+        req.validate(&current)
+            .map_err(|e| PyValueError::new_err(format!("can't alter {}: {e}", current.fqn())))?;
+
+        super::roundtrip_timeout(py, req, &self.profile, &self.agent()?, Some(timeout)).map_err(
+            |e| match e {
+                ClientError::Api(e) => e.table_update_into_py_err(),
+                e => e.into(),
+            },
+        )
+    }
+
+    /// Drop a table.
+    ///
+    /// Upon failure, raises `bauplan.exceptions.BauplanError`
+    ///
+    /// ```python
+    /// import bauplan
+    /// client = bauplan.Client()
+    ///
+    /// assert client.delete_table(
+    ///     table='my_table_name',
+    ///     branch='my_branch_name',
+    ///     namespace='my_namespace',
+    /// )
+    /// ```
+    ///
+    /// Parameters:
+    ///     table: The table to delete.
+    ///     branch: The branch on which the table is stored. Defaults to the active branch.
+    ///     namespace: The namespace of the table to delete.
+    ///     commit_body: Optional, the commit body message to attach to the commit.
+    ///     commit_properties: Optional, a list of properties to attach to the commit.
+    ///     if_exists: If set to `True`, the table will not raise an error if it does not exist.
+    ///     request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
+    /// Returns:
+    ///     A `bauplan.schema.Branch` object pointing to the new head.
+    ///
+    /// Raises:
+    ///     `bauplan.exceptions.DeleteTableForbiddenError`: if the user does not have access to delete the table.
+    ///     `bauplan.exceptions.BranchNotFoundError`: if the branch does not exist.
+    ///     `bauplan.exceptions.BranchHeadChangedError`: if the branch head hash has changed.
+    ///     `bauplan.exceptions.NotAWriteBranchRefError`: if the destination branch is not a writable ref.
+    ///     `bauplan.exceptions.MergeConflictError`: if the delete operation results in a conflict.
+    ///     `bauplan.exceptions.TableNotFoundError`: if the table does not exist.
+    ///     `bauplan.exceptions.NamespaceNotFoundError`: if the namespace does not exist.
+    ///     `bauplan.exceptions.NamespaceUnresolvedError`: if conflicting namespaces names are specified.
+    ///     `bauplan.exceptions.InvalidRefError`: if the ref format is invalid.
+    ///     `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+    ///     `ValueError`: if one or more parameters are invalid.
+    #[pyo3(signature = (
+        table: "str | Table",
+        branch: "str | Branch | None" = None,
+        *,
+        namespace: "str | Namespace | None" = None,
+        if_exists: "bool" = false,
+        commit_body: "str | None" = None,
+        commit_properties: "dict[str, str] | None" = None,
+        request_timeout: "float | None" = None,
+    ) -> "Branch")]
+    #[allow(clippy::too_many_arguments)]
+    fn delete_table(
+        &self,
+        py: Python<'_>,
+        table: TableArg,
+        branch: Option<BranchArg>,
+        namespace: Option<NamespaceArg>,
+        if_exists: bool,
+        commit_body: Option<&str>,
+        commit_properties: Option<BTreeMap<String, String>>,
+        request_timeout: Option<f64>,
+    ) -> PyResult<CatalogRef> {
+        let timeout = self.request_timeout(request_timeout)?;
+        let namespace = namespace.map(|a| a.0);
+        let commit_properties = commit_properties.unwrap_or_default();
+        let properties = commit_properties
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+
+        let branch = self.branch_or_active(branch.map(|b| b.0));
+        let req = DeleteTable {
+            name: &table.0,
+            branch: &branch,
+            namespace: namespace.as_deref(),
+            commit: CommitOptions {
+                body: commit_body,
+                properties,
+            },
+        };
+
+        let (head, _) = self.delete_table_if_exists(py, req, if_exists, timeout)?;
+        Ok(head)
+    }
+
+    /// Delete a table if it exists, and do nothing otherwise.
+    ///
+    /// Only a `bauplan.exceptions.TableNotFoundError` counts as the table
+    /// already being absent; anything else is raised, as with `delete_table`.
+    /// Whether the table was deleted is logged to the `bauplan` logger.
+    ///
+    /// Upon failure, raises `bauplan.exceptions.BauplanError`
+    ///
+    /// ```python
+    /// import bauplan
+    /// client = bauplan.Client()
+    ///
+    /// client.ensure_table_absent(
+    ///     table='my_table_name',
+    ///     branch='my_branch_name',
+    ///     namespace='my_namespace',
+    /// )
+    /// ```
+    ///
+    /// Parameters:
+    ///     table: The table to delete.
+    ///     branch: The branch on which the table is stored. Defaults to the active branch.
+    ///     namespace: The namespace of the table to delete.
+    ///     commit_body: Optional, the commit body message to attach to the commit, if the table is deleted.
+    ///     commit_properties: Optional, a list of properties to attach to the commit, if the table is deleted.
+    ///     request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
+    /// Returns:
+    ///     A `bauplan.schema.Branch` object pointing to the head of the branch, after the delete if there was one.
+    ///
+    /// Raises:
+    ///     `bauplan.exceptions.DeleteTableForbiddenError`: if the user does not have access to delete the table.
+    ///     `bauplan.exceptions.BranchNotFoundError`: if the branch does not exist.
+    ///     `bauplan.exceptions.BranchHeadChangedError`: if the branch head hash has changed.
+    ///     `bauplan.exceptions.NotAWriteBranchRefError`: if the destination branch is not a writable ref.
+    ///     `bauplan.exceptions.NamespaceNotFoundError`: if the namespace does not exist.
+    ///     `bauplan.exceptions.NamespaceUnresolvedError`: if conflicting namespaces names are specified.
+    ///     `bauplan.exceptions.InvalidRefError`: if the ref format is invalid.
+    ///     `bauplan.exceptions.UnauthorizedError`: if the user's credentials are invalid.
+    ///     `ValueError`: if one or more parameters are invalid.
+    #[pyo3(signature = (
+        table: "str | Table",
+        branch: "str | Branch | None" = None,
+        *,
+        namespace: "str | Namespace | None" = None,
+        commit_body: "str | None" = None,
+        commit_properties: "dict[str, str] | None" = None,
+        request_timeout: "float | None" = None,
+    ) -> "Branch")]
+    #[allow(clippy::too_many_arguments)]
+    fn ensure_table_absent(
+        &self,
+        py: Python<'_>,
+        table: TableArg,
+        branch: Option<BranchArg>,
+        namespace: Option<NamespaceArg>,
+        commit_body: Option<&str>,
+        commit_properties: Option<BTreeMap<String, String>>,
+        request_timeout: Option<f64>,
+    ) -> PyResult<CatalogRef> {
+        let timeout = self.request_timeout(request_timeout)?;
+        let namespace = namespace.map(|a| a.0);
+        let commit_properties = commit_properties.unwrap_or_default();
+        let properties = commit_properties
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+
+        let branch = self.branch_or_active(branch.map(|b| b.0));
+        let req = DeleteTable {
+            name: &table.0,
+            branch: &branch,
+            namespace: namespace.as_deref(),
+            commit: CommitOptions {
+                body: commit_body,
+                properties,
+            },
+        };
+
+        let (head, deleted) = self.delete_table_if_exists(py, req, true, timeout)?;
+        if deleted {
+            info!(table = table.0, branch, "deleted table");
+        } else {
+            info!(table = table.0, branch, "table already absent");
+        }
+
+        Ok(head)
+    }
+
     /// Create an external table from an Iceberg metadata.json file.
     ///
     /// This operation creates an external table by pointing to an existing Iceberg table's
@@ -1024,8 +1759,8 @@ impl Client {
     /// Parameters:
     ///     table: The name of the table to create.
     ///     metadata_json_uri: The S3 URI pointing to the Iceberg table's metadata.json file.
-    ///     namespace: The namespace for the table (required).
-    ///     branch: The branch name in which to create the table. Defaults to the active branch, or 'main'.
+    ///     namespace: The namespace for the table. Defaults to the namespace in the table name (e.g. `ns.table`), or the catalog's default namespace.
+    ///     branch: The branch name in which to create the table. Defaults to the active branch.
     ///     overwrite: Whether to overwrite an existing table with the same name (default: False).
     ///
     /// Returns:
@@ -1043,40 +1778,72 @@ impl Client {
         table: "str | Table",
         metadata_json_uri: "str",
         *,
-        namespace: "str | Namespace",
+        namespace: "str | Namespace | None" = None,
         branch: "str | Branch | None" = None,
         overwrite: "bool" = false,
     ) -> "Table")]
     fn create_external_table_from_metadata(
         &self,
         py: Python<'_>,
-        table: &str,
+        table: TableArg,
         metadata_json_uri: &str,
-        namespace: &str,
-        branch: Option<&str>,
+        namespace: Option<NamespaceArg>,
+        branch: Option<BranchArg>,
         overwrite: bool,
     ) -> PyResult<Table> {
-        let branch = branch
-            .or(self.profile.active_branch.as_deref())
-            .unwrap_or("-");
+        if metadata_json_uri.is_empty() {
+            return Err(PyValueError::new_err("metadata_json_uri must not be empty"));
+        }
+
+        let url = url::Url::parse(metadata_json_uri)
+            .map_err(|e| PyValueError::new_err(format!("invalid metadata_json_uri: {e}")))?;
+        if url.scheme() != "s3" {
+            return Err(PyValueError::new_err(
+                "metadata_json_uri must use the s3:// scheme",
+            ));
+        }
+
+        let branch = self.branch_or_active(branch.map(|b| b.0));
+
+        // The namespace forms part of the iceberg endpoint, so it has to be
+        // resolved here rather than by the server.
+        let (namespace, name) = match (namespace, table.0.rsplit_once('.')) {
+            (Some(namespace), _) => (namespace.0, table.0.as_str()),
+            (None, Some((namespace, name))) => (namespace.to_owned(), name),
+            (None, None) => {
+                let req = GetCatalogConfig { branch: &branch };
+                let config = super::roundtrip(py, req, &self.profile, &self.agent()?)?;
+                let namespace = config.default_namespace().ok_or_else(|| {
+                    PyValueError::new_err(format!(
+                        "no namespace given for {:?}; pass namespace or use namespace.table",
+                        table.0
+                    ))
+                })?;
+
+                (namespace.to_owned(), table.0.as_str())
+            }
+        };
 
         let req = RegisterTable {
-            name: table,
+            name,
             metadata_location: metadata_json_uri,
             overwrite,
-            branch,
-            namespace,
+            branch: &branch,
+            namespace: &namespace,
         };
 
-        super::roundtrip(py, req, &self.profile, &self.agent)?;
+        super::roundtrip(py, req, &self.profile, &self.agent()?).map_err(|e| match e {
+            ClientError::Api(e) => e.table_update_into_py_err(),
+            e => e.into(),
+        })?;
 
         let req = GetTable {
-            name: table,
-            at_ref: branch,
-            namespace: Some(namespace),
+            name,
+            at_ref: &branch,
+            namespace: Some(&namespace),
         };
 
-        Ok(super::roundtrip(py, req, &self.profile, &self.agent)?)
+        Ok(super::roundtrip(py, req, &self.profile, &self.agent()?)?)
     }
 
     /// Revert a table to a previous state.
@@ -1099,10 +1866,11 @@ impl Client {
     ///     table: The table to revert.
     ///     namespace: The namespace of the table to revert.
     ///     source_ref: The name of the source ref; either a branch like "main" or ref like "main@[sha]".
-    ///     into_branch: The name of the target branch where the table will be reverted.
+    ///     into_branch: The name of the target branch where the table will be reverted. Defaults to the active branch.
     ///     replace: Optional, whether to replace the table if it already exists.
     ///     commit_body: Optional, the commit body message to attach to the operation.
     ///     commit_properties: Optional, a list of properties to attach to the operation.
+    ///     request_timeout: Optional, seconds to wait for the request. Defaults to the client's `request_timeout`.
     /// Returns:
     ///     The `bauplan.schema.Branch` where the revert was made.
     ///
@@ -1127,10 +1895,11 @@ impl Client {
         *,
         namespace: "str | Namespace | None" = None,
         source_ref: "str | Ref",
-        into_branch: "str | Branch",
+        into_branch: "str | Branch | None" = None,
         replace: "bool | None" = None,
         commit_body: "str | None" = None,
         commit_properties: "dict[str, str] | None" = None,
+        request_timeout: "float | None" = None,
     ) -> "Branch")]
     #[allow(clippy::too_many_arguments)]
     fn revert_table(
@@ -1139,11 +1908,13 @@ impl Client {
         table: TableArg,
         namespace: Option<NamespaceArg>,
         source_ref: RefArg,
-        into_branch: BranchArg,
+        into_branch: Option<BranchArg>,
         replace: Option<bool>,
         commit_body: Option<&str>,
         commit_properties: Option<BTreeMap<String, String>>,
+        request_timeout: Option<f64>,
     ) -> PyResult<CatalogRef> {
+        let timeout = self.request_timeout(request_timeout)?;
         let namespace = namespace.map(|a| a.0);
         let commit_properties = commit_properties.unwrap_or_default();
         let properties = commit_properties
@@ -1151,19 +1922,169 @@ impl Client {
             .map(|(k, v)| (k.as_str(), v.as_str()))
             .collect();
 
+        let operation_id = crate::new_operation_id();
         let req = RevertTable {
             name: &table.0,
-            source_ref: &source_ref.0,
-            into_branch: &into_branch.0,
+            source_ref: &source_ref.resolve(py, self)?,
+            into_branch: &self.branch_or_active(into_branch.map(|b| b.0)),
             namespace: namespace.as_deref(),
             replace: replace.unwrap_or_default(),
             commit: CommitOptions {
                 body: commit_body,
                 properties,
             },
+            operation_id: Some(&operation_id),
         };
 
-        let resp = super::roundtrip(py, req, &self.profile, &self.agent)?;
+        let resp =
+            super::roundtrip_operation(py, req, &self.profile, &self.agent()?, Some(timeout))?;
         Ok(resp)
     }
 }
+
+impl Client {
+    /// Deletes a table, returning the head of the branch afterwards and
+    /// whether the table was there. With `if_exists`, a missing table isn't
+    /// an error.
+    fn delete_table_if_exists(
+        &self,
+        py: Python<'_>,
+        req: DeleteTable<'_>,
+        if_exists: bool,
+        timeout: Duration,
+    ) -> PyResult<(CatalogRef, bool)> {
+        let branch = req.branch;
+        let err =
+            match super::roundtrip_timeout(py, req, &self.profile, &self.agent()?, Some(timeout)) {
+                Ok(r) => return Ok((r, true)),
+                Err(e) => e,
+            };
+
+        match table_not_found(&err) {
+            Some(Some(catalog_ref)) if if_exists => Ok((catalog_ref.clone(), false)),
+            // The API didn't say which ref it looked at, so fetch the head of
+            // the branch instead.
+            Some(None) if if_exists => {
+                let req = GetBranch {
+                    name: branch,
+                    if_none_match: None,
+                };
+
+                let head = super::roundtrip_timeout(
+                    py,
+                    req,
+                    &self.profile,
+                    &self.agent()?,
+                    Some(timeout),
+                )?;
+                let head = CatalogRef::Branch {
+                    name: head.name,
+                    hash: head.hash,
+                };
+                Ok((head, false))
+            }
+            _ => Err(err.into()),
+        }
+    }
+
+    /// Like `plan_table_creation`, recording the raw events of the planning
+    /// job to `events`, if given.
+    #[allow(clippy::too_many_arguments)]
+    fn plan_table_creation_recorded(
+        &self,
+        py: Python<'_>,
+        table: &str,
+        search_uri: &str,
+        branch: Option<&str>,
+        namespace: Option<&str>,
+        file_format: &str,
+        partitioned_by: Option<&str>,
+        replace: Option<bool>,
+        args: Option<std::collections::HashMap<String, String>>,
+        priority: Option<Priority>,
+        client_timeout: Option<i64>,
+        events: Option<&EventRecorder>,
+    ) -> PyResult<TableCreatePlanState> {
+        let timeout = self.job_timeout(client_timeout.map(|v| v as u64));
+        let common = self.job_request_common(priority, args.unwrap_or_default(), None)?;
+        let search_string = search_uri::encode(search_uri)
+            .and_then(|s| {
+                let format: search_uri::FileFormat = file_format.parse()?;
+                format.check(search_uri)?;
+                Ok(s)
+            })
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+        let req = commanderpb::TableCreatePlanRequest {
+            job_request_common: Some(common),
+            branch_name: Some(self.branch_or_active(branch)),
+            table_name: table.to_owned(),
+            namespace: self.namespace_or_default(namespace),
+            search_string,
+            table_replace: replace.unwrap_or(false),
+            table_partitioned_by: partitioned_by.map(str::to_owned),
+        };
+
+        let mut client = self.grpc()?;
+        super::detach(py, async {
+            let resp = client
+                .table_create_plan(req)
+                .await
+                .map_err(job_err)?
+                .into_inner();
+
+            let Some(commanderpb::JobResponseCommon { job_id, .. }) = resp.job_response_common
+            else {
+                return Err(job_err("response missing job ID"));
+            };
+
+            let ctx = TableCreatePlanContext {
+                branch_name: resp.branch_name,
+                table_name: resp.table_name,
+                table_replace: resp.table_replace,
+                table_partitioned_by: resp.table_partitioned_by,
+                namespace: resp.namespace,
+                search_string: resp.search_string,
+            };
+
+            let mut state = TableCreatePlanState {
+                job_id: Some(job_id.clone()),
+                ctx,
+                job_status: None,
+                error: None,
+                plan: None,
+                can_auto_apply: false,
+                files_to_be_imported: Vec::new(),
+            };
+
+            let res = self
+                .monitor_job(&job_id, timeout, events, |event| {
+                    if let JobEvent::TableCreatePlanned(plan) = event {
+                        if plan.error.is_some() {
+                            state.error = plan.error;
+                        }
+
+                        state.plan = Some(plan.plan_yaml);
+                        state.can_auto_apply = plan.can_auto_apply;
+                        state.files_to_be_imported = plan.files_to_be_imported;
+                    }
+                })
+                .await?;
+
+            let (job_status, error) = job_status_strings(res);
+            state.job_status = Some(job_status);
+            if let Some(e) = error
+                && state.error.is_none()
+            {
+                state.error = Some(e);
+            }
+
+            // There's a conflict in the plan, and it can't be autoapplied.
+            if state.error.is_none() && !state.can_auto_apply && state.plan.is_some() {
+                state.error = Some("table plan created but has conflicts".to_owned());
+            }
+
+            Ok(state)
+        })
+    }
+}
@@ -3,7 +3,7 @@
 use pyo3::prelude::*;
 
 use crate::{
-    ApiErrorKind, ApiRequest, CatalogRef,
+    ApiRequest,
     python::{
         paginate::PyPaginator,
         refs::{RefArg, TagArg},
@@ -49,7 +49,7 @@ impl Client {
         limit: Option<usize>,
     ) -> PyResult<PyPaginator> {
         let profile = self.profile.clone();
-        let agent = self.agent.clone();
+        let agent = self.agent()?;
         PyPaginator::new(py, limit, move |py, token, limit| {
             let req = GetTags {
                 filter_by_name: filter_by_name.as_deref(),
@@ -85,7 +85,7 @@ impl Client {
     #[pyo3(signature = (tag: "str | Tag") -> "Tag")]
     fn get_tag(&self, py: Python<'_>, tag: TagArg) -> PyResult<Tag> {
         let req = GetTag { name: &tag.0 };
-        let t = super::roundtrip(py, req, &self.profile, &self.agent)?;
+        let t = super::roundtrip(py, req, &self.profile, &self.agent()?)?;
         Ok(t)
     }
 
@@ -114,7 +114,7 @@ impl Client {
     fn has_tag(&self, py: Python<'_>, tag: TagArg) -> PyResult<bool> {
         let req = GetTag { name: &tag.0 };
 
-        match super::roundtrip(py, req, &self.profile, &self.agent) {
+        match super::roundtrip(py, req, &self.profile, &self.agent()?) {
             Ok(_) => Ok(true),
             Err(e)
                 if matches!(
@@ -144,7 +144,7 @@ impl Client {
     ///
     /// Parameters:
     ///     tag: The name of the new tag.
-    ///     from_ref: The name of the base branch; either a branch like "main" or ref like "main@[sha]".
+    ///     from_ref: The name of the base branch; either a branch like "main" or ref like "main@[sha]". Defaults to the active branch.
     ///     if_not_exists: If set to `True`, the tag will not be created if it already exists.
     /// Returns:
     ///     The created `bauplan.schema.Tag` object.
@@ -158,30 +158,22 @@ impl Client {
     ///     `ValueError`: if one or more parameters are invalid.
     #[pyo3(signature = (
         tag: "str | Tag",
-        from_ref: "str | Ref",
+        from_ref: "str | Ref | None" = None,
         *,
         if_not_exists: "bool" = false,
     ) -> "Tag")]
-    fn create_tag(&self, py: Python<'_>, tag: TagArg, from_ref: RefArg, if_not_exists: bool) -> PyResult<Tag> {
+    fn create_tag(&self, py: Python<'_>, tag: TagArg, from_ref: Option<RefArg>, if_not_exists: bool) -> PyResult<Tag> {
         let req = CreateTag {
             name: &tag.0,
-            from_ref: &from_ref.0,
+            from_ref: &self.ref_or_active(from_ref).resolve(py, self)?,
         };
 
-        match super::roundtrip(py, req, &self.profile, &self.agent) {
+        match super::roundtrip(py, req, &self.profile, &self.agent()?) {
             Ok(t) => Ok(t),
 
             Err(e) => {
-                if if_not_exists
-                    && let Some(ApiErrorKind::TagExists {
-                        catalog_ref: CatalogRef::Tag { name, hash },
-                        ..
-                    }) = e.kind()
-                {
-                    Ok(Tag {
-                        name: name.clone(),
-                        hash: hash.clone(),
-                    })
+                if if_not_exists && let Some(t) = e.api().and_then(CreateTag::existing) {
+                    Ok(t)
                 } else {
                     Err(e.into())
                 }
@@ -227,7 +219,7 @@ impl Client {
             new_name: &new_tag.0,
         };
 
-        let t = super::roundtrip(py, req, &self.profile, &self.agent)?;
+        let t = super::roundtrip(py, req, &self.profile, &self.agent()?)?;
         Ok(t)
     }
 
@@ -264,11 +256,9 @@ impl Client {
     fn delete_tag(&self, py: Python<'_>, tag: TagArg, if_exists: bool) -> PyResult<bool> {
         let req = DeleteTag { name: &tag.0 };
 
-        match super::roundtrip(py, req, &self.profile, &self.agent) {
+        match super::roundtrip(py, req, &self.profile, &self.agent()?) {
             Ok(_) => Ok(true),
-            Err(e) if matches!(e.kind(), Some(ApiErrorKind::TagNotFound { .. })) && if_exists => {
-                Ok(false)
-            }
+            Err(e) if if_exists && e.api().is_some_and(DeleteTag::missing) => Ok(false),
             Err(e) => Err(e.into()),
         }
     }
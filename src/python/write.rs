@@ -0,0 +1,411 @@
+//! Writing local Arrow data to tables.
+
+use std::{fmt, fs::File, path::Path};
+
+use arrow::{
+    array::RecordBatchReader,
+    datatypes::{DataType, Schema},
+};
+use parquet::arrow::ArrowWriter;
+use pyo3::{exceptions::PyValueError, prelude::*};
+use pyo3_arrow::PyRecordBatchReader;
+
+use crate::{
+    ApiErrorKind, CatalogRef,
+    api::table::{Table, TableField},
+    branch::GetBranch,
+    grpc::job::Priority,
+    python::{job_err, namespace::NamespaceArg, refs::BranchArg},
+    table::GetTable,
+};
+
+use super::Client;
+use super::exceptions::InvalidDataError;
+use super::table::table_not_found;
+
+/// What `write_table` does with the rows already in the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WriteMode {
+    Append,
+    Overwrite,
+}
+
+/// The result of `Client.write_table`.
+#[pyclass(module = "bauplan", frozen, skip_from_py_object)]
+#[derive(Debug, Clone)]
+pub(crate) struct WriteTableResult {
+    /// The branch, at the commit with the new rows.
+    #[pyo3(get)]
+    r#ref: CatalogRef,
+    /// The table, as of that commit.
+    #[pyo3(get)]
+    table: Table,
+    /// The number of rows written.
+    #[pyo3(get)]
+    rows_written: u64,
+    /// The URI of the Parquet file the rows were staged in.
+    #[pyo3(get)]
+    staged_uri: String,
+    /// The ID of the import job.
+    #[pyo3(get)]
+    job_id: String,
+}
+
+#[pymethods]
+impl WriteTableResult {
+    fn __repr__(&self) -> String {
+        format!(
+            "WriteTableResult(ref={}, rows_written={}, staged_uri={:?}, job_id={:?})",
+            self.r#ref, self.rows_written, self.staged_uri, self.job_id,
+        )
+    }
+}
+
+/// A difference between the columns of a table and the data written to it.
+#[derive(Debug)]
+enum ColumnMismatch {
+    Missing {
+        name: String,
+        r#type: String,
+    },
+    Extra {
+        name: String,
+        data_type: DataType,
+    },
+    Type {
+        name: String,
+        r#type: String,
+        data_type: DataType,
+    },
+}
+
+impl fmt::Display for ColumnMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnMismatch::Missing { name, r#type } => {
+                write!(f, "missing required column {name:?} ({type})")
+            }
+            ColumnMismatch::Extra { name, data_type } => {
+                write!(f, "column {name:?} ({data_type}) isn't in the table")
+            }
+            ColumnMismatch::Type {
+                name,
+                r#type,
+                data_type,
+            } => write!(
+                f,
+                "column {name:?} has type {data_type}, but the table's column is {type}"
+            ),
+        }
+    }
+}
+
+/// Whether a column of the given Iceberg type can hold Arrow values of
+/// `data_type`, or `None` for types that aren't checked, like nested ones.
+fn accepts(r#type: &str, data_type: &DataType) -> Option<bool> {
+    use DataType::*;
+
+    let data_type = match data_type {
+        Dictionary(_, values) => values.as_ref(),
+        t => t,
+    };
+
+    let ok = match r#type {
+        "boolean" => matches!(data_type, Boolean),
+        "int" => matches!(data_type, Int8 | Int16 | Int32 | UInt8 | UInt16),
+        "long" => data_type.is_integer() && *data_type != UInt64,
+        "float" => matches!(data_type, Float16 | Float32),
+        "double" => data_type.is_floating(),
+        "date" => matches!(data_type, Date32 | Date64),
+        "time" => matches!(data_type, Time32(_) | Time64(_)),
+        "timestamp" | "timestamp_ns" => matches!(data_type, Timestamp(_, None)),
+        "timestamptz" | "timestamptz_ns" => matches!(data_type, Timestamp(_, Some(_))),
+        "string" => matches!(data_type, Utf8 | LargeUtf8 | Utf8View),
+        "uuid" => matches!(data_type, FixedSizeBinary(16)),
+        "binary" => matches!(
+            data_type,
+            Binary | LargeBinary | BinaryView | FixedSizeBinary(_)
+        ),
+        t if t.starts_with("decimal") => matches!(data_type, Decimal128(..) | Decimal256(..)),
+        t if t.starts_with("fixed") => matches!(data_type, FixedSizeBinary(_)),
+        _ => return None,
+    };
+
+    Some(ok)
+}
+
+/// Compares the columns of a table with the data being appended to it, by
+/// name. Optional columns may be left out of the data.
+fn compare(fields: &[TableField], schema: &Schema) -> Vec<ColumnMismatch> {
+    let mut diffs = Vec::new();
+
+    for field in fields {
+        match schema.field_with_name(&field.name) {
+            Ok(column) if accepts(&field.r#type, column.data_type()) == Some(false) => {
+                diffs.push(ColumnMismatch::Type {
+                    name: field.name.clone(),
+                    r#type: field.r#type.clone(),
+                    data_type: column.data_type().clone(),
+                });
+            }
+            Ok(_) => (),
+            Err(_) if field.required => diffs.push(ColumnMismatch::Missing {
+                name: field.name.clone(),
+                r#type: field.r#type.clone(),
+            }),
+            Err(_) => (),
+        }
+    }
+
+    for column in schema.fields() {
+        if !fields.iter().any(|f| f.name == *column.name()) {
+            diffs.push(ColumnMismatch::Extra {
+                name: column.name().clone(),
+                data_type: column.data_type().clone(),
+            });
+        }
+    }
+
+    diffs
+}
+
+/// Raises an `InvalidDataError` listing the differences, if the data can't
+/// be appended to the table.
+fn check_schema(table: &Table, schema: &Schema) -> PyResult<()> {
+    let diffs = compare(&table.fields, schema);
+    if diffs.is_empty() {
+        return Ok(());
+    }
+
+    let mut msg = format!(
+        "data doesn't match the schema of {}.{}:",
+        table.namespace, table.name
+    );
+    for diff in diffs {
+        msg.push_str(&format!("\n  - {diff}"));
+    }
+
+    Err(PyErr::new::<InvalidDataError, _>((
+        400u16,
+        String::new(),
+        msg,
+        None::<ApiErrorKind>,
+    )))
+}
+
+/// Writes the batches from `reader` to a Parquet file, returning the number
+/// of rows written.
+fn write_parquet(
+    path: &Path,
+    reader: Box<dyn RecordBatchReader + Send>,
+) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    let mut writer = ArrowWriter::try_new(File::create(path)?, reader.schema(), None)?;
+    let mut rows = 0;
+    for batch in reader {
+        let batch = batch?;
+        rows += batch.num_rows() as u64;
+        writer.write(&batch)?;
+    }
+
+    writer.close()?;
+    Ok(rows)
+}
+
+#[pymethods]
+impl Client {
+    /// Write Arrow data to a table, creating the table if it doesn't exist.
+    ///
+    /// `data` can be anything that implements the Arrow PyCapsule stream
+    /// interface, like a `pyarrow.Table`, a `pyarrow.RecordBatchReader` or a
+    /// `polars.DataFrame`. The rows are staged as a Parquet file under
+    /// `staging_uri`, using the AWS credentials from the environment, and
+    /// then imported into the table. The staged file isn't deleted
+    /// afterwards, so consider a prefix with an expiration policy.
+    ///
+    /// ```python
+    /// import bauplan
+    /// import pyarrow as pa
+    ///
+    /// client = bauplan.Client()
+    ///
+    /// result = client.write_table(
+    ///     'my_table_name',
+    ///     pa.table({'id': [1, 2, 3], 'name': ['a', 'b', 'c']}),
+    ///     branch='my_branch_name',
+    ///     staging_uri='s3://my-bucket/staging/',
+    /// )
+    /// print(f"Wrote {result.rows_written} rows at {result.ref}")
+    /// ```
+    ///
+    /// Parameters:
+    ///     table: The table to write to.
+    ///     data: The rows to write.
+    ///     branch: The branch to write to. Defaults to the active branch.
+    ///     namespace: The namespace of the table.
+    ///     mode: `'append'` to add the rows to the table, or `'overwrite'` to replace the table with them.
+    ///     staging_uri: An `s3://` prefix to stage the rows under, which Bauplan can read from.
+    ///     args: dict of arbitrary args to pass to the backend.
+    ///     priority: Optional job priority (1-10, where 10 is highest priority).
+    ///     client_timeout: seconds to timeout; this also cancels the remote job execution. Defaults to 1800 seconds.
+    /// Returns:
+    ///     A `bauplan.WriteTableResult` with the new head of the branch and the number of rows written.
+    ///
+    /// Raises:
+    ///     `bauplan.exceptions.InvalidDataError`: if the data doesn't match the schema of the table it's appended to.
+    ///     `bauplan.exceptions.TableCreatePlanStatusError`: if the table can't be created from the data.
+    ///     `bauplan.exceptions.BauplanJobError`: if the import fails.
+    ///     `ValueError`: if `mode` or `staging_uri` is invalid.
+    #[pyo3(signature = (
+        table: "str",
+        data: "pyarrow.Table | pyarrow.RecordBatchReader | typing.Any",
+        branch: "str | Branch | None" = None,
+        *,
+        namespace: "str | Namespace | None" = None,
+        mode: "Literal['append', 'overwrite']" = "append",
+        staging_uri: "str",
+        args: "dict[str, str] | None" = None,
+        priority: "int | None" = None,
+        client_timeout: "int | None" = None,
+    ) -> "WriteTableResult")]
+    #[allow(clippy::too_many_arguments)]
+    fn write_table(
+        &self,
+        py: Python<'_>,
+        table: &str,
+        data: PyRecordBatchReader,
+        branch: Option<BranchArg>,
+        namespace: Option<NamespaceArg>,
+        mode: &str,
+        staging_uri: &str,
+        args: Option<std::collections::HashMap<String, String>>,
+        priority: Option<Priority>,
+        client_timeout: Option<i64>,
+    ) -> PyResult<WriteTableResult> {
+        self.check_blocking(py, "write_table")?;
+
+        let mode = match mode {
+            "append" => WriteMode::Append,
+            "overwrite" => WriteMode::Overwrite,
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "mode must be 'append' or 'overwrite', got '{other}'"
+                )));
+            }
+        };
+
+        if !staging_uri.starts_with("s3://") {
+            return Err(PyValueError::new_err(format!(
+                "staging_uri must be an s3:// URI, got '{staging_uri}'"
+            )));
+        }
+
+        let branch = self.branch_or_active(branch.map(|b| b.0));
+        let namespace = namespace.map(|a| a.0);
+        let reader = data.into_reader()?;
+
+        // Check the data against the table before staging it, since a
+        // mismatch would otherwise only show up as a failed import job.
+        let exists = if mode == WriteMode::Append {
+            let req = GetTable {
+                name: table,
+                at_ref: &branch,
+                namespace: namespace.as_deref(),
+            };
+
+            match super::roundtrip(py, req, &self.profile, &self.agent()?) {
+                Ok(existing) => {
+                    check_schema(&existing, &reader.schema())?;
+                    true
+                }
+                Err(e) if table_not_found(&e).is_some() => false,
+                Err(e) => return Err(e.into()),
+            }
+        } else {
+            false
+        };
+
+        let name = format!("{table}-{}.parquet", uuid::Uuid::new_v4());
+        let staged_uri = format!("{}/{name}", staging_uri.trim_end_matches('/'));
+        let local = std::env::temp_dir().join(format!("bauplan-{name}"));
+
+        let staged = py
+            .detach(|| write_parquet(&local, reader))
+            .map_err(|e| PyValueError::new_err(format!("failed to write data: {e}")))
+            .and_then(|rows| {
+                py.import("pyarrow.fs")?
+                    .call_method1("copy_files", (local.to_string_lossy(), &staged_uri))?;
+                Ok(rows)
+            });
+
+        let _ = std::fs::remove_file(&local);
+        let rows_written = staged?;
+
+        if !exists {
+            self.create_table(
+                py,
+                table,
+                &staged_uri,
+                Some(&branch),
+                namespace.as_deref(),
+                None,
+                Some(mode == WriteMode::Overwrite),
+                args.clone(),
+                priority,
+                client_timeout,
+                None,
+            )?;
+        }
+
+        let state = self.import_data(
+            py,
+            table,
+            Some(&staged_uri),
+            None,
+            Some(&branch),
+            namespace.as_deref(),
+            false,
+            false,
+            false,
+            None,
+            args,
+            priority,
+            client_timeout,
+            false,
+            None,
+            None,
+        )?;
+
+        if let Some(error) = state.error {
+            return Err(job_err(format!(
+                "failed to import {staged_uri} into {table}: {error}"
+            )));
+        }
+
+        let req = GetBranch {
+            name: &branch,
+            if_none_match: None,
+        };
+        let head = super::roundtrip(py, req, &self.profile, &self.agent()?)?;
+
+        let r#ref = CatalogRef::Branch {
+            name: head.name,
+            hash: head.hash,
+        };
+
+        let at_ref = r#ref.to_string();
+        let req = GetTable {
+            name: table,
+            at_ref: &at_ref,
+            namespace: namespace.as_deref(),
+        };
+        let table = super::roundtrip(py, req, &self.profile, &self.agent()?)?;
+
+        Ok(WriteTableResult {
+            r#ref,
+            table,
+            rows_written,
+            staged_uri,
+            job_id: state.job_id.unwrap_or_default(),
+        })
+    }
+}
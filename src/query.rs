@@ -0,0 +1,441 @@
+//! Running SQL queries and fetching their results.
+//!
+//! ```no_run
+//! use bauplan::{Profile, query::QueryRun};
+//! use futures::TryStreamExt as _;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> anyhow::Result<()> {
+//! let profile = Profile::from_default_env()?;
+//!
+//! let (job_id, schema, batches) = QueryRun::new("SELECT * FROM bauplan.titanic")
+//!     .at_ref("main")
+//!     .max_rows(100)
+//!     .run(&profile)
+//!     .await?;
+//!
+//! println!("job {job_id} returned {} columns", schema.fields().len());
+//!
+//! futures::pin_mut!(batches);
+//! while let Some(batch) = batches.try_next().await? {
+//!     println!("{} rows", batch.num_rows());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod params;
+
+use std::{collections::HashMap, sync::Arc, time};
+
+use arrow::{array::RecordBatch, datatypes::Schema};
+use arrow_flight::error::FlightError;
+use bauplan_longbow::{BauplanPreset, iroh};
+use futures::{Stream, TryStreamExt as _, future::Either};
+use tracing::{error, info};
+
+use crate::{
+    Profile,
+    events::JobEvent,
+    flight,
+    grpc::{
+        self, CancelJobError, JobError, generated as commanderpb,
+        job::{JOB_NAME_ARG, Priority},
+    },
+};
+
+pub use params::{ParamError, SqlValue, bind_params, bind_positional_params};
+
+/// How long a query may run when no timeout is set.
+pub const DEFAULT_QUERY_TIMEOUT: time::Duration = time::Duration::from_secs(1800);
+
+/// An error running a query or fetching its results.
+#[derive(Debug, thiserror::Error)]
+pub enum QueryError {
+    /// The parameters couldn't be bound into the query.
+    #[error(transparent)]
+    Params(#[from] ParamError),
+    /// The connection to the API couldn't be set up.
+    #[error(transparent)]
+    Connect(#[from] tonic::transport::Error),
+    /// The API returned an error.
+    #[error(transparent)]
+    Status(#[from] tonic::Status),
+    /// The API accepted the query, but didn't return a job ID.
+    #[error("response missing job ID")]
+    MissingJobId,
+    /// The query job didn't succeed.
+    #[error(transparent)]
+    Job(#[from] JobError),
+    /// The query didn't finish in time, and was cancelled.
+    #[error("query execution timed out")]
+    Timeout,
+    /// The query didn't finish in time, and cancelling it failed.
+    #[error(transparent)]
+    Cancel(#[from] CancelJobError),
+    /// The results couldn't be fetched over longbow.
+    #[error(transparent)]
+    Longbow(#[from] bauplan_longbow::Error),
+    /// The results weren't available in time.
+    #[error("timed out fetching query results")]
+    FetchTimeout,
+    /// The query completed without saying where to fetch the results from.
+    #[error("query completed, but no results available")]
+    NoResults,
+    /// The results are stored somewhere the client can't make sense of.
+    #[error("invalid results location: {0}")]
+    InvalidEndpoint(String),
+    /// The results couldn't be fetched over Arrow Flight.
+    #[error("failed to fetch query results: {0}")]
+    Flight(#[from] FlightError),
+}
+
+/// A SQL query to run against a ref.
+///
+/// Only the query text is required; set anything else with the builder
+/// methods, then call [`QueryRun::run`].
+#[derive(Debug, Clone)]
+pub struct QueryRun {
+    sql: String,
+    params: HashMap<String, SqlValue>,
+    positional_params: Vec<SqlValue>,
+    at_ref: Option<String>,
+    namespace: Option<String>,
+    cache: Option<bool>,
+    max_rows: Option<u64>,
+    priority: Option<Priority>,
+    args: HashMap<String, String>,
+    job_name: Option<String>,
+    module_version: String,
+    timeout: time::Duration,
+    flight_max_retries: u32,
+}
+
+impl QueryRun {
+    /// Creates a query with the given SQL. Column and table names are
+    /// case-sensitive.
+    pub fn new(sql: impl Into<String>) -> Self {
+        Self {
+            sql: sql.into(),
+            params: HashMap::new(),
+            positional_params: Vec::new(),
+            at_ref: None,
+            namespace: None,
+            cache: None,
+            max_rows: None,
+            priority: None,
+            args: HashMap::new(),
+            job_name: None,
+            module_version: env!("CARGO_PKG_VERSION").to_owned(),
+            timeout: DEFAULT_QUERY_TIMEOUT,
+            flight_max_retries: flight::DEFAULT_MAX_RETRIES,
+        }
+    }
+
+    /// Binds values to `:name` placeholders in the query, replacing any
+    /// already bound with the same names. See [`bind_params`] for how they're
+    /// substituted.
+    pub fn params<K, V>(mut self, params: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<SqlValue>,
+    {
+        self.params
+            .extend(params.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+
+    /// Binds values, in order, to `?` placeholders in the query, replacing
+    /// any already bound. See [`bind_positional_params`] for how they're
+    /// substituted.
+    pub fn positional_params<V>(mut self, values: impl IntoIterator<Item = V>) -> Self
+    where
+        V: Into<SqlValue>,
+    {
+        self.positional_params = values.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the ref to query. Without one, the server's default (usually
+    /// `main`) is used.
+    pub fn at_ref(mut self, at_ref: impl Into<String>) -> Self {
+        self.at_ref = Some(at_ref.into());
+        self
+    }
+
+    /// Sets the namespace unqualified table names are resolved in.
+    pub fn namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Enables or disables the query cache. Without this, the server's
+    /// default applies.
+    pub fn cache(mut self, cache: bool) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Limits the number of rows returned.
+    pub fn max_rows(mut self, max_rows: u64) -> Self {
+        self.max_rows = Some(max_rows);
+        self
+    }
+
+    /// Sets the job priority.
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Adds extra arguments for the job, on top of those in the profile.
+    pub fn args(mut self, args: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.args.extend(args);
+        self
+    }
+
+    /// Sets a human-friendly name for the job.
+    pub fn job_name(mut self, job_name: impl Into<String>) -> Self {
+        self.job_name = Some(job_name.into());
+        self
+    }
+
+    /// Sets the version of the bauplan module reported to the runtime.
+    /// Defaults to the version of this crate.
+    pub fn module_version(mut self, module_version: impl Into<String>) -> Self {
+        self.module_version = module_version.into();
+        self
+    }
+
+    /// Sets how long to wait for the query to finish, and then for the
+    /// results, before giving up. Defaults to [`DEFAULT_QUERY_TIMEOUT`].
+    pub fn timeout(mut self, timeout: time::Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets how many times to retry connecting to the flight server that
+    /// serves the results. Defaults to [`flight::DEFAULT_MAX_RETRIES`].
+    pub fn flight_max_retries(mut self, max_retries: u32) -> Self {
+        self.flight_max_retries = max_retries;
+        self
+    }
+
+    /// Runs the query to completion, and returns the job ID, the schema of
+    /// the results and a stream of record batches.
+    ///
+    /// If the query doesn't finish in time, it's cancelled.
+    pub async fn run(
+        self,
+        profile: &Profile,
+    ) -> Result<
+        (
+            String,
+            Schema,
+            impl Stream<Item = Result<RecordBatch, QueryError>> + use<>,
+        ),
+        QueryError,
+    > {
+        let client = grpc::Client::new_lazy(profile, self.timeout)?;
+        let longbow = Arc::new(tokio::sync::OnceCell::new());
+        self.run_with(profile, client, longbow).await
+    }
+
+    /// Like [`QueryRun::run`], but reuses an existing gRPC client and
+    /// longbow endpoint, which is bound on first use.
+    pub async fn run_with(
+        self,
+        profile: &Profile,
+        mut client: grpc::Client,
+        longbow: Arc<tokio::sync::OnceCell<iroh::Endpoint>>,
+    ) -> Result<
+        (
+            String,
+            Schema,
+            impl Stream<Item = Result<RecordBatch, QueryError>> + use<>,
+        ),
+        QueryError,
+    > {
+        let sql_query = bind_params(&self.sql, &self.params)?;
+        let sql_query = bind_positional_params(&sql_query, &self.positional_params)?;
+
+        let mut args = profile.args.clone();
+        args.extend(self.args);
+        if let Some(name) = self.job_name {
+            args.insert(JOB_NAME_ARG.to_owned(), name);
+        }
+
+        let common = commanderpb::JobRequestCommon {
+            module_version: self.module_version,
+            hostname: gethostname::gethostname().to_string_lossy().into_owned(),
+            args,
+            debug: 0,
+            priority: self.priority.map(|p| p.get() as _),
+        };
+
+        let cache = match self.cache {
+            Some(true) => "on",
+            Some(false) => "off",
+            None => "",
+        };
+
+        let req = commanderpb::QueryRunRequest {
+            job_request_common: Some(common),
+            r#ref: self.at_ref,
+            sql_query,
+            cache: cache.to_owned(),
+            namespace: self.namespace,
+        };
+
+        let resp = client.query_run(req).await?.into_inner();
+        let Some(commanderpb::JobResponseCommon { job_id, .. }) = resp.job_response_common else {
+            return Err(QueryError::MissingJobId);
+        };
+
+        info!(job_id, "successfully planned query");
+
+        let mut req = tonic::Request::new(commanderpb::SubscribeLogsRequest {
+            job_id: job_id.clone(),
+        });
+        req.set_timeout(self.timeout);
+
+        let stream = client.monitor_job(req, longbow.clone());
+        futures::pin_mut!(stream);
+
+        let mut flight_server = None;
+        loop {
+            let event = match stream.try_next().await {
+                Ok(Some(ev)) => ev,
+                Ok(None) => break,
+                Err(e)
+                    if e.code() == tonic::Code::Cancelled
+                        || e.code() == tonic::Code::DeadlineExceeded =>
+                {
+                    error!(job_id, "query timed out, cancelling execution");
+                    cancel(&mut client, &job_id).await?;
+                    return Err(QueryError::Timeout);
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            match event {
+                JobEvent::FlightReady(server) => flight_server = Some(server),
+                JobEvent::Completion(completion) => {
+                    completion.result?;
+                    break;
+                }
+                _ => (),
+            }
+        }
+
+        if let Some(artifact) = resp.result_artifact {
+            let public_key = iroh::PublicKey::try_from(artifact.server_public_key.as_slice())
+                .map_err(|_| bauplan_longbow::Error::InvalidKey)?;
+            let preset = BauplanPreset::default();
+            let addr = iroh::EndpointAddr::new(public_key);
+            let addr = preset.add_relay_urls(addr);
+
+            let endpoint = longbow
+                .get_or_try_init(|| async {
+                    iroh::Endpoint::bind(BauplanPreset::default())
+                        .await
+                        .map_err(bauplan_longbow::Error::from)
+                })
+                .await?;
+
+            let (schema, batches) = tokio::time::timeout(
+                self.timeout,
+                bauplan_longbow::fetch_query_results(
+                    endpoint,
+                    addr,
+                    &artifact.artifact_id,
+                    &artifact.auth_token,
+                    self.max_rows,
+                ),
+            )
+            .await
+            .map_err(|_| QueryError::FetchTimeout)??;
+
+            let schema: Schema = schema.as_ref().clone();
+            let batches = flight::limit_rows(batches.map_err(QueryError::from), self.max_rows);
+            return Ok((job_id, schema, Either::Left(batches)));
+        }
+
+        let Some(server) = flight_server else {
+            return Err(QueryError::NoResults);
+        };
+
+        let endpoint = flight::ResultsEndpoint::from_server(server, None)
+            .map_err(QueryError::InvalidEndpoint)?;
+        let (schema, batches) = endpoint
+            .fetch(self.timeout, self.max_rows, self.flight_max_retries)
+            .await?;
+
+        let batches = flight::limit_rows(batches.map_err(QueryError::from), self.max_rows);
+        Ok((job_id, schema, Either::Right(batches)))
+    }
+}
+
+/// Cancels a query that timed out.
+async fn cancel(client: &mut grpc::Client, job_id: &str) -> Result<(), CancelJobError> {
+    let req = commanderpb::CancelJobRequest {
+        job_id: Some(commanderpb::JobId {
+            id: job_id.to_owned(),
+            ..Default::default()
+        }),
+    };
+
+    client.cancel(req).await.inspect_err(|err| {
+        error!(?err, "failed to cancel timed out query");
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn unbound_param() -> anyhow::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let path = tmp.path().join("config.yaml");
+        std::fs::write(
+            &path,
+            "profiles:\n  default:\n    api_key: bpln_dummy_key\n",
+        )?;
+
+        let profile = Profile::read(&path, None)?;
+        let res = QueryRun::new("SELECT :a, :b")
+            .params([("a", 1)])
+            .run(&profile)
+            .await;
+        assert!(matches!(
+            res,
+            Err(QueryError::Params(ParamError::Missing(name))) if name == "b"
+        ));
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "_integration-tests"))]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn run_query() -> anyhow::Result<()> {
+        let profile = Profile::from_default_env()?;
+        let (job_id, schema, batches) =
+            QueryRun::new("SELECT PassengerId, Name FROM bauplan.titanic")
+                .at_ref("main")
+                .max_rows(10)
+                .run(&profile)
+                .await?;
+
+        assert!(!job_id.is_empty());
+        assert_eq!(schema.fields().len(), 2);
+
+        let batches: Vec<RecordBatch> = batches.try_collect().await?;
+        let rows: usize = batches.iter().map(RecordBatch::num_rows).sum();
+        assert_eq!(rows, 10);
+        Ok(())
+    }
+}
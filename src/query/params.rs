@@ -0,0 +1,741 @@
+//! Binding typed parameters into SQL.
+//!
+//! The query API takes SQL text only, so parameters are rendered as DuckDB
+//! literals on the client, and substituted for `:name` or `?` placeholders.
+//! Placeholders inside string literals, quoted identifiers and comments are
+//! left alone, as are `::` casts.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Write as _,
+};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+/// A value to bind to a `:name` or `?` placeholder in a query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlValue {
+    /// `NULL`.
+    Null,
+    /// A `BOOLEAN`.
+    Bool(bool),
+    /// A `BIGINT`.
+    Int(i64),
+    /// A `DOUBLE`, including NaN and infinities.
+    Float(f64),
+    /// A `VARCHAR`. It mustn't contain NUL characters.
+    Str(String),
+    /// A `BLOB`.
+    Bytes(Vec<u8>),
+    /// A `DATE`.
+    Date(NaiveDate),
+    /// A `TIMESTAMPTZ`.
+    Timestamp(DateTime<Utc>),
+    /// A `TIMESTAMP`, without a time zone.
+    NaiveTimestamp(NaiveDateTime),
+    /// A parenthesized list of values, for use with `IN`. It must have at
+    /// least one element, and can't contain other lists.
+    List(Vec<SqlValue>),
+}
+
+/// An error binding parameters into a query.
+#[derive(Debug, thiserror::Error)]
+pub enum ParamError {
+    /// A parameter name isn't a valid placeholder name.
+    #[error("invalid parameter name {0:?}: names must start with a letter or underscore")]
+    InvalidName(String),
+    /// The query has a placeholder with no value.
+    #[error("no value for parameter :{0}")]
+    Missing(String),
+    /// A value was given for a placeholder that isn't in the query.
+    #[error("parameter :{0} isn't used in the query")]
+    Unused(String),
+    /// A value can't be rendered as a literal.
+    #[error("invalid value for parameter :{name}: {reason}")]
+    InvalidValue {
+        /// The name of the parameter.
+        name: String,
+        /// Why the value is invalid.
+        reason: &'static str,
+    },
+    /// The query has more `?` placeholders than values. The inner value is
+    /// the position of the first placeholder without one, counting from 1.
+    #[error("no value for ? placeholder {0}")]
+    MissingPositional(usize),
+    /// More values were given than the query has `?` placeholders.
+    #[error("{values} values given for {placeholders} ? placeholders")]
+    UnusedPositional {
+        /// The number of values given.
+        values: usize,
+        /// The number of placeholders in the query.
+        placeholders: usize,
+    },
+    /// A value for a `?` placeholder can't be rendered as a literal.
+    #[error("invalid value for ? placeholder {position}: {reason}")]
+    InvalidPositionalValue {
+        /// The position of the placeholder, counting from 1.
+        position: usize,
+        /// Why the value is invalid.
+        reason: &'static str,
+    },
+}
+
+/// A placeholder found in a query.
+#[derive(Debug, Clone, Copy)]
+enum Placeholder<'a> {
+    /// A `:name` placeholder.
+    Named(&'a str),
+    /// A `?` placeholder, by position, counting from 1.
+    Positional(usize),
+}
+
+impl Placeholder<'_> {
+    fn invalid_value(self, reason: &'static str) -> ParamError {
+        match self {
+            Placeholder::Named(name) => ParamError::InvalidValue {
+                name: name.to_owned(),
+                reason,
+            },
+            Placeholder::Positional(position) => {
+                ParamError::InvalidPositionalValue { position, reason }
+            }
+        }
+    }
+}
+
+impl SqlValue {
+    /// Appends the value to `out` as a DuckDB literal.
+    fn render(&self, out: &mut String, in_list: bool) -> Result<(), &'static str> {
+        match self {
+            SqlValue::Null => out.push_str("NULL"),
+            SqlValue::Bool(true) => out.push_str("TRUE"),
+            SqlValue::Bool(false) => out.push_str("FALSE"),
+            // Parenthesized, so that `x -:n` doesn't become a comment.
+            SqlValue::Int(v) if *v < 0 => write!(out, "({v})").unwrap(),
+            SqlValue::Int(v) => write!(out, "{v}").unwrap(),
+            // Unquoted, 1.5 would be a DECIMAL, and NaN and the infinities
+            // have no literal syntax at all.
+            SqlValue::Float(v) => write!(out, "'{v:?}'::DOUBLE").unwrap(),
+            SqlValue::Str(s) => {
+                if s.contains('\0') {
+                    return Err("strings can't contain NUL characters");
+                }
+
+                out.push('\'');
+                out.push_str(&s.replace('\'', "''"));
+                out.push('\'');
+            }
+            SqlValue::Bytes(b) => {
+                out.push('\'');
+                for byte in b {
+                    write!(out, "\\x{byte:02X}").unwrap();
+                }
+                out.push_str("'::BLOB");
+            }
+            SqlValue::Date(d) => write!(out, "DATE '{}'", d.format("%Y-%m-%d")).unwrap(),
+            SqlValue::Timestamp(t) => write!(
+                out,
+                "TIMESTAMPTZ '{}'",
+                t.format("%Y-%m-%d %H:%M:%S%.6f+00")
+            )
+            .unwrap(),
+            SqlValue::NaiveTimestamp(t) => {
+                write!(out, "TIMESTAMP '{}'", t.format("%Y-%m-%d %H:%M:%S%.6f")).unwrap()
+            }
+            SqlValue::List(_) if in_list => return Err("lists can't be nested"),
+            SqlValue::List(values) if values.is_empty() => {
+                return Err("lists must have at least one element");
+            }
+            SqlValue::List(values) => {
+                out.push('(');
+                for (i, v) in values.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    v.render(out, true)?;
+                }
+                out.push(')');
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl From<bool> for SqlValue {
+    fn from(v: bool) -> Self {
+        SqlValue::Bool(v)
+    }
+}
+
+impl From<i64> for SqlValue {
+    fn from(v: i64) -> Self {
+        SqlValue::Int(v)
+    }
+}
+
+impl From<i32> for SqlValue {
+    fn from(v: i32) -> Self {
+        SqlValue::Int(v.into())
+    }
+}
+
+impl From<f64> for SqlValue {
+    fn from(v: f64) -> Self {
+        SqlValue::Float(v)
+    }
+}
+
+impl From<&str> for SqlValue {
+    fn from(v: &str) -> Self {
+        SqlValue::Str(v.to_owned())
+    }
+}
+
+impl From<String> for SqlValue {
+    fn from(v: String) -> Self {
+        SqlValue::Str(v)
+    }
+}
+
+impl From<Vec<u8>> for SqlValue {
+    fn from(v: Vec<u8>) -> Self {
+        SqlValue::Bytes(v)
+    }
+}
+
+impl From<NaiveDate> for SqlValue {
+    fn from(v: NaiveDate) -> Self {
+        SqlValue::Date(v)
+    }
+}
+
+impl From<DateTime<Utc>> for SqlValue {
+    fn from(v: DateTime<Utc>) -> Self {
+        SqlValue::Timestamp(v)
+    }
+}
+
+impl From<NaiveDateTime> for SqlValue {
+    fn from(v: NaiveDateTime) -> Self {
+        SqlValue::NaiveTimestamp(v)
+    }
+}
+
+impl<T: Into<SqlValue>> From<Option<T>> for SqlValue {
+    fn from(v: Option<T>) -> Self {
+        v.map_or(SqlValue::Null, Into::into)
+    }
+}
+
+/// Substitutes the values in `params` for the `:name` placeholders in
+/// `sql`. Every placeholder must have a value, and every value must be
+/// used. If `params` is empty, the query is returned as-is.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use bauplan::query::{SqlValue, bind_params};
+///
+/// let params = HashMap::from([
+///     ("name".to_owned(), SqlValue::from("O'Brien")),
+///     ("ids".to_owned(), SqlValue::List(vec![1.into(), 2.into()])),
+/// ]);
+///
+/// let sql = bind_params("SELECT * FROM t WHERE name = :name AND id IN :ids", &params)?;
+/// assert_eq!(sql, "SELECT * FROM t WHERE name = 'O''Brien' AND id IN (1, 2)");
+/// # Ok::<_, bauplan::query::ParamError>(())
+/// ```
+pub fn bind_params(sql: &str, params: &HashMap<String, SqlValue>) -> Result<String, ParamError> {
+    if params.is_empty() {
+        return Ok(sql.to_owned());
+    }
+
+    let mut names: BTreeSet<&str> = BTreeSet::new();
+    for name in params.keys() {
+        if !is_valid_name(name) {
+            return Err(ParamError::InvalidName(name.clone()));
+        }
+        names.insert(name.as_str());
+    }
+
+    let out = substitute(sql, false, |placeholder| {
+        let Placeholder::Named(name) = placeholder else {
+            unreachable!("positional placeholder in a named query");
+        };
+
+        let (name, value) = params
+            .get_key_value(name)
+            .ok_or_else(|| ParamError::Missing(name.to_owned()))?;
+        names.remove(name.as_str());
+        Ok(value)
+    })?;
+
+    if let Some(name) = names.first() {
+        return Err(ParamError::Unused((*name).to_owned()));
+    }
+
+    Ok(out)
+}
+
+/// Substitutes `values`, in order, for the `?` placeholders in `sql`. There
+/// must be exactly as many values as placeholders. If `values` is empty, the
+/// query is returned as-is.
+///
+/// ```
+/// use bauplan::query::{SqlValue, bind_positional_params};
+///
+/// let values = [SqlValue::from("O'Brien"), SqlValue::from(30)];
+///
+/// let sql = bind_positional_params("SELECT * FROM t WHERE name = ? AND age > ?", &values)?;
+/// assert_eq!(sql, "SELECT * FROM t WHERE name = 'O''Brien' AND age > 30");
+/// # Ok::<_, bauplan::query::ParamError>(())
+/// ```
+pub fn bind_positional_params(sql: &str, values: &[SqlValue]) -> Result<String, ParamError> {
+    if values.is_empty() {
+        return Ok(sql.to_owned());
+    }
+
+    let mut placeholders = 0;
+    let out = substitute(sql, true, |placeholder| {
+        let Placeholder::Positional(position) = placeholder else {
+            unreachable!("named placeholder in a positional query");
+        };
+
+        placeholders = position;
+        values
+            .get(position - 1)
+            .ok_or(ParamError::MissingPositional(position))
+    })?;
+
+    if placeholders < values.len() {
+        return Err(ParamError::UnusedPositional {
+            values: values.len(),
+            placeholders,
+        });
+    }
+
+    Ok(out)
+}
+
+/// Replaces the placeholders in `sql` with the literals for the values
+/// `value_for` returns: `?` placeholders if `positional` is set, and `:name`
+/// placeholders otherwise.
+fn substitute<'v>(
+    sql: &str,
+    positional: bool,
+    mut value_for: impl FnMut(Placeholder<'_>) -> Result<&'v SqlValue, ParamError>,
+) -> Result<String, ParamError> {
+    let bytes = sql.as_bytes();
+    let mut out = String::with_capacity(sql.len());
+    let mut copied = 0;
+    let mut position = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let next = bytes.get(i + 1).copied();
+        let after_ident = i > 0 && is_ident_byte(bytes[i - 1]);
+
+        let (placeholder, end) = match bytes[i] {
+            b'\'' => {
+                // An E'...' string, where backslashes escape quotes.
+                let escapes = i > 0
+                    && matches!(bytes[i - 1], b'E' | b'e')
+                    && !(i > 1 && is_ident_byte(bytes[i - 2]));
+                i = skip_string(bytes, i, escapes);
+                continue;
+            }
+            b'"' => {
+                i = skip_string(bytes, i, false);
+                continue;
+            }
+            b'-' if next == Some(b'-') => {
+                i = match sql[i..].find('\n') {
+                    Some(n) => i + n + 1,
+                    None => bytes.len(),
+                };
+                continue;
+            }
+            b'/' if next == Some(b'*') => {
+                i = skip_block_comment(bytes, i);
+                continue;
+            }
+            b'$' if !after_ident => {
+                i = skip_dollar_quoted(sql, i);
+                continue;
+            }
+            b':' if next == Some(b':') => {
+                i += 2;
+                continue;
+            }
+            b':' if !positional && !after_ident && next.is_some_and(is_name_start) => {
+                let end = bytes[i + 1..]
+                    .iter()
+                    .position(|b| !is_name_byte(*b))
+                    .map_or(bytes.len(), |n| i + 1 + n);
+                (Placeholder::Named(&sql[i + 1..end]), end)
+            }
+            b'?' if positional => {
+                position += 1;
+                (Placeholder::Positional(position), i + 1)
+            }
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+
+        let value = value_for(placeholder)?;
+        out.push_str(&sql[copied..i]);
+        value
+            .render(&mut out, false)
+            .map_err(|reason| placeholder.invalid_value(reason))?;
+
+        // Adjacent string literals would be joined into one.
+        if bytes.get(end) == Some(&b'\'') {
+            out.push(' ');
+        }
+
+        copied = end;
+        i = end;
+    }
+
+    out.push_str(&sql[copied..]);
+    Ok(out)
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut bytes = name.bytes();
+    bytes.next().is_some_and(is_name_start) && bytes.all(is_name_byte)
+}
+
+fn is_name_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_'
+}
+
+fn is_name_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// Whether a byte can be part of an unquoted identifier. Anything outside
+/// ASCII can be, in DuckDB.
+fn is_ident_byte(b: u8) -> bool {
+    is_name_byte(b) || !b.is_ascii()
+}
+
+/// Returns the index just past the string or quoted identifier starting at
+/// `start`, or the end of the query if it isn't closed.
+fn skip_string(bytes: &[u8], start: usize, escapes: bool) -> usize {
+    let quote = bytes[start];
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if escapes => i += 2,
+            b if b == quote && bytes.get(i + 1) == Some(&quote) => i += 2,
+            b if b == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+
+    bytes.len()
+}
+
+/// Returns the index just past the block comment starting at `start`.
+/// Block comments nest.
+fn skip_block_comment(bytes: &[u8], start: usize) -> usize {
+    let mut depth = 0;
+    let mut i = start;
+    while i < bytes.len() {
+        match (bytes[i], bytes.get(i + 1)) {
+            (b'/', Some(b'*')) => {
+                depth += 1;
+                i += 2;
+            }
+            (b'*', Some(b'/')) => {
+                depth -= 1;
+                i += 2;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    bytes.len()
+}
+
+/// Returns the index just past the `$tag$...$tag$` string starting at
+/// `start`, or just past the `$` if it doesn't start one (for example, if
+/// it's a positional parameter like `$1`).
+fn skip_dollar_quoted(sql: &str, start: usize) -> usize {
+    let bytes = sql.as_bytes();
+    let tag_len = bytes[start + 1..]
+        .iter()
+        .position(|b| !is_name_byte(*b))
+        .unwrap_or(bytes.len() - start - 1);
+    let tag_end = start + 1 + tag_len;
+
+    if bytes.get(tag_end) != Some(&b'$') || bytes.get(start + 1).is_some_and(u8::is_ascii_digit) {
+        return start + 1;
+    }
+
+    let tag = &sql[start..=tag_end];
+    match sql[tag_end + 1..].find(tag) {
+        Some(n) => tag_end + 1 + n + tag.len(),
+        None => bytes.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bind(sql: &str, params: &[(&str, SqlValue)]) -> Result<String, ParamError> {
+        let params = params
+            .iter()
+            .map(|(k, v)| ((*k).to_owned(), v.clone()))
+            .collect();
+        bind_params(sql, &params)
+    }
+
+    fn render(value: SqlValue) -> String {
+        bind("SELECT :v", &[("v", value)]).unwrap()["SELECT ".len()..].to_owned()
+    }
+
+    #[test]
+    fn renders_literals() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        let ts = date.and_hms_micro_opt(13, 5, 9, 1234).unwrap();
+
+        assert_eq!(render(SqlValue::Null), "NULL");
+        assert_eq!(render(true.into()), "TRUE");
+        assert_eq!(render(false.into()), "FALSE");
+        assert_eq!(render(42.into()), "42");
+        assert_eq!(render((-42).into()), "(-42)");
+        assert_eq!(render(i64::MIN.into()), "(-9223372036854775808)");
+        assert_eq!(render(1.5.into()), "'1.5'::DOUBLE");
+        assert_eq!(render((-1e-7).into()), "'-1e-7'::DOUBLE");
+        assert_eq!(render(f64::NAN.into()), "'NaN'::DOUBLE");
+        assert_eq!(render(f64::INFINITY.into()), "'inf'::DOUBLE");
+        assert_eq!(render(f64::NEG_INFINITY.into()), "'-inf'::DOUBLE");
+        assert_eq!(render("hello".into()), "'hello'");
+        assert_eq!(render("".into()), "''");
+        assert_eq!(render("naïve ☃".into()), "'naïve ☃'");
+        assert_eq!(
+            render(vec![0x00_u8, 0xab, 0x27].into()),
+            r"'\x00\xAB\x27'::BLOB"
+        );
+        assert_eq!(render(Vec::<u8>::new().into()), "''::BLOB");
+        assert_eq!(render(date.into()), "DATE '2024-02-29'");
+        assert_eq!(render(ts.into()), "TIMESTAMP '2024-02-29 13:05:09.001234'");
+        assert_eq!(
+            render(ts.and_utc().into()),
+            "TIMESTAMPTZ '2024-02-29 13:05:09.001234+00'"
+        );
+        assert_eq!(render(None::<i64>.into()), "NULL");
+        assert_eq!(render(Some("x").into()), "'x'");
+        assert_eq!(
+            render(SqlValue::List(vec![
+                1.into(),
+                "a".into(),
+                SqlValue::Null,
+                (-2).into()
+            ])),
+            "(1, 'a', NULL, (-2))"
+        );
+    }
+
+    #[test]
+    fn escapes_strings() {
+        let cases = [
+            ("O'Brien", "'O''Brien'"),
+            ("'", "''''"),
+            ("''", "''''''"),
+            ("x' OR '1'='1", "'x'' OR ''1''=''1'"),
+            ("'; DROP TABLE t; --", "'''; DROP TABLE t; --'"),
+            // Backslashes aren't escapes in standard strings.
+            (r"\'; DROP TABLE t; --", r"'\''; DROP TABLE t; --'"),
+            (r"\", r"'\'"),
+            ("a\nb", "'a\nb'"),
+            ("*/ DROP TABLE t /*", "'*/ DROP TABLE t /*'"),
+            ("$$ DROP TABLE t $$", "'$$ DROP TABLE t $$'"),
+            (":other", "':other'"),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(render(value.into()), expected, "{value:?}");
+        }
+    }
+
+    #[test]
+    fn substituted_values_are_not_rebound() {
+        let sql = bind("SELECT :a, :b", &[("a", ":b".into()), ("b", "x".into())]).unwrap();
+        assert_eq!(sql, "SELECT ':b', 'x'");
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let err = bind("SELECT :s", &[("s", "a\0b".into())]).unwrap_err();
+        assert!(matches!(err, ParamError::InvalidValue { name, .. } if name == "s"));
+
+        let err = bind("SELECT 1 IN :l", &[("l", SqlValue::List(vec![]))]).unwrap_err();
+        assert!(matches!(err, ParamError::InvalidValue { .. }));
+
+        let nested = SqlValue::List(vec![SqlValue::List(vec![1.into()])]);
+        let err = bind("SELECT 1 IN :l", &[("l", nested)]).unwrap_err();
+        assert!(matches!(err, ParamError::InvalidValue { .. }));
+
+        let err = bind(
+            "SELECT 1 IN :l",
+            &[("l", SqlValue::List(vec!["a\0".into()]))],
+        )
+        .unwrap_err();
+        assert!(matches!(err, ParamError::InvalidValue { .. }));
+    }
+
+    #[test]
+    fn binds_placeholders() {
+        let params = [
+            ("id", SqlValue::from(7)),
+            ("name", "x".into()),
+            ("_n2", SqlValue::Null),
+        ];
+
+        let cases = [
+            ("SELECT :id", "SELECT 7"),
+            ("SELECT :id, :id", "SELECT 7, 7"),
+            ("WHERE id=:id AND name=:name", "WHERE id=7 AND name='x'"),
+            ("SELECT (:id)", "SELECT (7)"),
+            ("SELECT :_n2 IS NULL", "SELECT NULL IS NULL"),
+            ("SELECT :id::VARCHAR", "SELECT 7::VARCHAR"),
+            ("SELECT a - :id", "SELECT a - 7"),
+            ("SELECT :name'x'", "SELECT 'x' 'x'"),
+            ("SELECT :id\n-- :id\n", "SELECT 7\n-- :id\n"),
+        ];
+
+        for (sql, expected) in cases {
+            let sql = format!("{sql} /* :name :_n2 */");
+            let expected = format!("{expected} /* :name :_n2 */");
+            // Make sure every parameter is used.
+            let sql = format!("{sql} :name :_n2 :id");
+            let expected = format!("{expected} 'x' NULL 7");
+            assert_eq!(bind(&sql, &params).unwrap(), expected, "{sql:?}");
+        }
+    }
+
+    #[test]
+    fn skips_quoted_text_and_comments() {
+        let params = [("id", SqlValue::from(7))];
+        let cases = [
+            "SELECT ':id', :id",
+            "SELECT 'it''s :id', :id",
+            "SELECT E'it\\'s :id', :id",
+            "SELECT e'\\\\', :id",
+            "SELECT \":id\", :id",
+            "SELECT \"a\"\":id\", :id",
+            "SELECT $$ :id $$, :id",
+            "SELECT $tag$ :id $$ :id $tag$, :id",
+            "SELECT $1, :id",
+            "SELECT 1 -- :id\n, :id",
+            "SELECT /* :id */ :id",
+            "SELECT /* /* :id */ :id */ :id",
+            "SELECT x::id, :id",
+            "SELECT {'a': 1}, :id",
+            "SELECT f(a := 1), :id",
+            "SELECT arr[1:2], :id",
+        ];
+
+        for sql in cases {
+            let bound = bind(sql, &params).unwrap();
+            assert!(bound.ends_with(", 7") || bound.ends_with(" 7"), "{sql:?}");
+            assert_eq!(bound.matches('7').count(), 1, "{sql:?} => {bound:?}");
+        }
+    }
+
+    #[test]
+    fn ignores_colons_after_identifiers() {
+        // E:x would become E'...', which is an escape string.
+        let bound = bind("SELECT E:x, :x", &[("x", "\\'".into())]).unwrap();
+        assert_eq!(bound, r"SELECT E:x, '\'''");
+
+        let bound = bind("SELECT a:x, :x", &[("x", 1.into())]).unwrap();
+        assert_eq!(bound, "SELECT a:x, 1");
+    }
+
+    #[test]
+    fn unterminated_quotes_hide_placeholders() {
+        let err = bind("SELECT 'abc :x", &[("x", 1.into())]).unwrap_err();
+        assert!(matches!(err, ParamError::Unused(name) if name == "x"));
+
+        let err = bind("SELECT /* :x", &[("x", 1.into())]).unwrap_err();
+        assert!(matches!(err, ParamError::Unused(name) if name == "x"));
+    }
+
+    #[test]
+    fn checks_names() {
+        // Without parameters, the query is left alone.
+        assert_eq!(bind("SELECT :a", &[]).unwrap(), "SELECT :a");
+
+        let err = bind("SELECT :a, :b", &[("a", 1.into())]).unwrap_err();
+        assert!(matches!(err, ParamError::Missing(name) if name == "b"));
+
+        let err = bind("SELECT :a", &[("a", 1.into()), ("b", 2.into())]).unwrap_err();
+        assert!(matches!(err, ParamError::Unused(name) if name == "b"));
+
+        for name in ["", "1a", "a-b", "a b", "a'--", "é"] {
+            let err = bind("SELECT 1", &[(name, 1.into())]).unwrap_err();
+            assert!(matches!(err, ParamError::InvalidName(_)), "{name:?}");
+        }
+    }
+
+    #[test]
+    fn binds_positional_placeholders() {
+        let values = [
+            SqlValue::from("it's\n'; DROP TABLE t; -- ☃"),
+            SqlValue::from(-1),
+            SqlValue::Null,
+        ];
+
+        let bound = bind_positional_params(
+            "SELECT ?, '?', \"?\" -- ?\n, x - ? /* ? */, ?::VARCHAR, :a",
+            &values,
+        )
+        .unwrap();
+        assert_eq!(
+            bound,
+            "SELECT 'it''s\n''; DROP TABLE t; -- ☃', '?', \"?\" -- ?\n, x - (-1) /* ? */, \
+             NULL::VARCHAR, :a"
+        );
+
+        // Without values, the query is left alone.
+        assert_eq!(bind_positional_params("SELECT ?", &[]).unwrap(), "SELECT ?");
+
+        // Named placeholders are left for bind_params.
+        let bound = bind_positional_params("SELECT ? IN :ids", &[1.into()]).unwrap();
+        assert_eq!(bound, "SELECT 1 IN :ids");
+    }
+
+    #[test]
+    fn checks_positional_counts() {
+        let err = bind_positional_params("SELECT ?, ?", &[1.into()]).unwrap_err();
+        assert!(matches!(err, ParamError::MissingPositional(2)));
+
+        let err = bind_positional_params("SELECT ?", &[1.into(), 2.into()]).unwrap_err();
+        assert!(matches!(
+            err,
+            ParamError::UnusedPositional {
+                values: 2,
+                placeholders: 1
+            }
+        ));
+
+        let err = bind_positional_params("SELECT ?, ?", &[1.into(), "\0".into()]).unwrap_err();
+        assert!(matches!(
+            err,
+            ParamError::InvalidPositionalValue { position: 2, .. }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "invalid value for ? placeholder 2: strings can't contain NUL characters"
+        );
+    }
+}
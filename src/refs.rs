@@ -1,7 +1,13 @@
 use std::{fmt::Display, str::FromStr, sync::LazyLock};
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    ApiError, PaginatedRequest, PaginatedResponse,
+    commit::{Commit, GetCommits},
+};
+
 /// A ref returned by the API.
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
@@ -62,6 +68,188 @@ fn match_ref(s: &str) -> Option<CatalogRef> {
     Some(CatalogRef::Detached { hash })
 }
 
+/// A ref as written by a user, which may use one of the git-like shorthands
+/// resolved by [`resolve_ref`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefSpec<'a> {
+    /// A ref the API understands as-is.
+    Plain(&'a str),
+    /// `HEAD`, meaning the active branch.
+    Head,
+    /// `<branch>@{<time>}`, meaning the head of the branch at some point in
+    /// time. The branch is `None` for `@{<time>}` or `HEAD@{<time>}`, which
+    /// refer to the active branch.
+    AsOf {
+        /// The branch name.
+        branch: Option<&'a str>,
+        /// The point in time.
+        at: RefTime,
+    },
+}
+
+/// The point in time in a `<branch>@{<time>}` ref.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefTime {
+    /// An absolute time, like `main@{2024-06-01}`.
+    At(DateTime<Utc>),
+    /// A time relative to now, like `main@{2 days ago}` or `main@{yesterday}`.
+    Ago(chrono::Duration),
+}
+
+impl RefTime {
+    fn resolve(self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            RefTime::At(t) => t,
+            RefTime::Ago(d) => now - d,
+        }
+    }
+}
+
+/// An error parsing or resolving a ref shorthand.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum RefSpecError {
+    /// The ref used the `@{...}` syntax, but the time couldn't be parsed.
+    #[error("invalid ref {input:?}: {reason}")]
+    Invalid {
+        /// The ref as written.
+        input: String,
+        /// Why it couldn't be parsed.
+        reason: String,
+    },
+    /// The branch has no commits from before the requested time.
+    #[error("the history of branch {branch:?} doesn't extend back to {at}")]
+    HistoryTooShort {
+        /// The branch name.
+        branch: String,
+        /// The requested time.
+        at: DateTime<Utc>,
+    },
+}
+
+// Matches "main@{yesterday}", "@{2024-06-01}", etc.
+static AS_OF_REGEX: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"\A([^@{}]*)@\{([^{}]*)\}\z").unwrap());
+
+// Matches "2 days" in "2 days ago", so that it can be passed to humantime as
+// "2days".
+static SPACED_DURATION_REGEX: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"(\d+)\s+([a-zA-Z]+)").unwrap());
+
+impl<'a> RefSpec<'a> {
+    /// Parses a ref, recognizing the `HEAD` and `<branch>@{<time>}`
+    /// shorthands. Anything else is passed through as [`RefSpec::Plain`].
+    pub fn parse(s: &'a str) -> Result<Self, RefSpecError> {
+        if s == "HEAD" {
+            return Ok(RefSpec::Head);
+        }
+
+        let Some(caps) = AS_OF_REGEX.captures(s) else {
+            return Ok(RefSpec::Plain(s));
+        };
+
+        let branch = caps.get(1).map(|m| m.as_str());
+        let branch = branch.filter(|b| !b.is_empty() && *b != "HEAD");
+        let invalid = |reason: &str| RefSpecError::Invalid {
+            input: s.to_owned(),
+            reason: reason.to_owned(),
+        };
+
+        let time = caps[2].trim();
+        if time.is_empty() {
+            return Err(invalid("expected a date or duration between the braces"));
+        }
+
+        let at = parse_ref_time(time).ok_or_else(|| {
+            invalid("expected a date like 2024-06-01, or a duration like \"2 days ago\"")
+        })?;
+
+        Ok(RefSpec::AsOf { branch, at })
+    }
+}
+
+fn parse_ref_time(s: &str) -> Option<RefTime> {
+    if s.eq_ignore_ascii_case("yesterday") {
+        return Some(RefTime::Ago(chrono::Duration::days(1)));
+    }
+
+    let duration = s.strip_suffix("ago").unwrap_or(s).trim();
+    let duration = SPACED_DURATION_REGEX.replace_all(duration, "$1$2");
+    if let Ok(d) = humantime::parse_duration(&duration) {
+        return chrono::Duration::from_std(d).ok().map(RefTime::Ago);
+    }
+
+    dateparser::parse_with_timezone(s, &Utc)
+        .ok()
+        .map(RefTime::At)
+}
+
+/// Resolves the `HEAD` and `<branch>@{<time>}` shorthands (see [`RefSpec`])
+/// to a ref that the API understands, fetching commits with `fetch_batch` if
+/// necessary. Other refs are returned unchanged, without any requests.
+///
+/// `HEAD` refers to `active_branch`, or `main` if there is none. A time is
+/// resolved to `<branch>@<hash>`, where the hash is the last commit on the
+/// branch made at or before that time.
+pub fn resolve_ref<F, E>(r: &str, active_branch: Option<&str>, fetch_batch: F) -> Result<String, E>
+where
+    F: Fn(PaginatedRequest<'_, GetCommits<'_>>) -> Result<PaginatedResponse<Commit>, E>,
+    E: From<ApiError> + From<RefSpecError>,
+{
+    let active_branch = active_branch.unwrap_or("main");
+    let (branch, at) = match RefSpec::parse(r)? {
+        RefSpec::Plain(r) => return Ok(r.to_owned()),
+        RefSpec::Head => return Ok(active_branch.to_owned()),
+        RefSpec::AsOf { branch, at } => (branch.unwrap_or(active_branch), at),
+    };
+
+    let at = at.resolve(Utc::now());
+    let end_at = at.to_rfc3339();
+    let req = GetCommits {
+        at_ref: branch,
+        filter_by_message: None,
+        filter_by_author_username: None,
+        filter_by_author_name: None,
+        filter_by_author_email: None,
+        filter_by_authored_date: None,
+        filter_by_authored_date_start_at: None,
+        filter_by_authored_date_end_at: Some(&end_at),
+        filter_by_parent_hash: None,
+        filter_by_properties: None,
+        filter: None,
+    };
+
+    // Commits are listed newest first. The server filters on the authored
+    // date, so check the commit date as well.
+    for commit in crate::paginate(req, None, |r| fetch_batch(r))? {
+        let commit = commit?;
+        if commit.committed_date <= at {
+            return Ok(format!("{branch}@{}", commit.hash()));
+        }
+    }
+
+    Err(RefSpecError::HistoryTooShort {
+        branch: branch.to_owned(),
+        at,
+    }
+    .into())
+}
+
+/// Splits a `<branch>@<snapshot id>` ref, naming an Iceberg snapshot of a
+/// table, into the branch and the snapshot ID. Queries can't read a table as
+/// of a snapshot, so this is used to refuse such refs with a clear error.
+///
+/// Snapshot IDs are decimal, so a suffix made only of digits is taken as one;
+/// a commit hash that happens to be all digits has to be written out in full.
+/// Returns `None` for any other ref, including a detached `@<hash>`.
+pub fn split_snapshot_ref(r: &str) -> Option<(&str, i64)> {
+    let (branch, id) = r.split_once('@')?;
+    if branch.is_empty() || id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    Some((branch, id.parse().ok()?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,4 +285,137 @@ mod tests {
             assert_eq!(&result, expected, "parsing {input:?}");
         }
     }
+
+    #[test]
+    fn parse_ref_spec() {
+        let day = chrono::Duration::days(1);
+        let cases: &[(&str, RefSpec<'_>)] = &[
+            ("main", RefSpec::Plain("main")),
+            ("main@abc123", RefSpec::Plain("main@abc123")),
+            ("HEAD", RefSpec::Head),
+            (
+                "main@{yesterday}",
+                RefSpec::AsOf {
+                    branch: Some("main"),
+                    at: RefTime::Ago(day),
+                },
+            ),
+            (
+                "HEAD@{2 days ago}",
+                RefSpec::AsOf {
+                    branch: None,
+                    at: RefTime::Ago(day * 2),
+                },
+            ),
+            (
+                "@{1h}",
+                RefSpec::AsOf {
+                    branch: None,
+                    at: RefTime::Ago(chrono::Duration::hours(1)),
+                },
+            ),
+            (
+                "user.feature@{2024-06-01T12:00:00Z}",
+                RefSpec::AsOf {
+                    branch: Some("user.feature"),
+                    at: RefTime::At("2024-06-01T12:00:00Z".parse().unwrap()),
+                },
+            ),
+        ];
+
+        for (input, expected) in cases {
+            let result = RefSpec::parse(input).unwrap();
+            assert_eq!(&result, expected, "parsing {input:?}");
+        }
+    }
+
+    #[test]
+    fn parse_ref_spec_errors() {
+        for input in [
+            "main@{}",
+            "main@{ }",
+            "main@{not a time}",
+            "main@{3 fortnights ago}",
+        ] {
+            let err = RefSpec::parse(input).unwrap_err();
+            assert!(
+                matches!(&err, RefSpecError::Invalid { input: i, .. } if i == input),
+                "parsing {input:?}: {err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn snapshot_refs() {
+        assert_eq!(
+            split_snapshot_ref("main@4527150312935411733"),
+            Some(("main", 4527150312935411733))
+        );
+        assert_eq!(
+            split_snapshot_ref("user.feature@12"),
+            Some(("user.feature", 12))
+        );
+
+        for input in [
+            "main",
+            "main@abc123",
+            "@123",
+            "main@",
+            "main@12~1",
+            "main@{yesterday}",
+            // Too long to be a snapshot ID, so it's a hash.
+            "main@12345678901234567890123",
+        ] {
+            assert_eq!(split_snapshot_ref(input), None, "{input:?}");
+        }
+    }
+}
+
+#[cfg(all(test, feature = "_integration-tests"))]
+mod test {
+    use std::{thread, time::Duration};
+
+    use super::*;
+    use crate::{
+        api::testutil::{TestBranch, roundtrip},
+        branch::GetBranch,
+        namespace::CreateNamespace,
+    };
+
+    fn create_namespace(branch: &str, name: &str) -> Result<String, ApiError> {
+        let req = CreateNamespace {
+            name,
+            branch,
+            commit: Default::default(),
+        };
+        roundtrip(req)?;
+
+        let req = GetBranch {
+            name: branch,
+            if_none_match: None,
+        };
+        Ok(roundtrip(req)?.hash)
+    }
+
+    #[test]
+    fn resolve_ref_as_of() -> anyhow::Result<()> {
+        let branch = TestBranch::new("test_resolve_ref")?;
+        let first = create_namespace(&branch.name, "test_ns_1")?;
+        thread::sleep(Duration::from_secs(5));
+        let second = create_namespace(&branch.name, "test_ns_2")?;
+        assert_ne!(first, second);
+
+        let r = format!("{}@{{3s ago}}", branch.name);
+        let resolved = resolve_ref::<_, anyhow::Error>(&r, None, |r| Ok(roundtrip(r)?))?;
+        assert_eq!(resolved, format!("{}@{first}", branch.name));
+
+        let r = format!("{}@{{100 years ago}}", branch.name);
+        let err = resolve_ref::<_, anyhow::Error>(&r, None, |r| Ok(roundtrip(r)?)).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(RefSpecError::HistoryTooShort { .. })
+        ));
+
+        Ok(())
+    }
 }
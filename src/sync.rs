@@ -0,0 +1,285 @@
+//! Incremental syncs of the tables on a ref, for mirroring the catalog into
+//! another system.
+//!
+//! A sync lists the tables on a ref and compares them against a state file
+//! recorded by the previous sync, yielding only the tables that were added,
+//! removed or changed since. The state file is only updated once the caller
+//! has processed the delta and calls [`TableSync::commit`], so an interrupted
+//! sync is simply retried from the same point next time.
+
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::table::Table;
+
+/// The version of the state file format written by this crate.
+pub const SYNC_STATE_VERSION: u32 = 1;
+
+/// An error reading or writing a sync state file.
+#[derive(Debug, thiserror::Error)]
+pub enum SyncError {
+    /// The state file couldn't be read or written.
+    #[error("failed to access state file {0:?}: {1}")]
+    Io(PathBuf, #[source] io::Error),
+    /// The state file isn't valid.
+    #[error("invalid state file {0:?}: {1}")]
+    Invalid(PathBuf, #[source] serde_json::Error),
+    /// The state file was written by a newer version of the format.
+    #[error("state file {0:?} has unsupported version {1}")]
+    UnsupportedVersion(PathBuf, u32),
+    /// The state file records the tables on a different ref.
+    #[error("state file {path:?} is for ref {expected:?}, not {actual:?}")]
+    RefMismatch {
+        /// The state file.
+        path: PathBuf,
+        /// The ref recorded in the state file.
+        expected: String,
+        /// The ref being synced.
+        actual: String,
+    },
+}
+
+/// What's recorded about a table between syncs; enough to tell whether it
+/// changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableState {
+    /// The current Iceberg snapshot ID.
+    pub snapshot_id: Option<i64>,
+    /// The current Iceberg schema ID.
+    pub schema_id: Option<i32>,
+    /// When the table was last updated, in milliseconds since the epoch.
+    pub last_updated_ms: i64,
+}
+
+impl From<&Table> for TableState {
+    fn from(table: &Table) -> Self {
+        Self {
+            snapshot_id: table.current_snapshot_id,
+            schema_id: table.current_schema_id,
+            last_updated_ms: table.last_updated_at.timestamp_millis(),
+        }
+    }
+}
+
+/// The tables on a ref as of a sync, keyed by fully qualified name. This is
+/// what's stored in the state file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncState {
+    /// The version of the format.
+    pub version: u32,
+    /// The ref the tables were listed on.
+    #[serde(rename = "ref")]
+    pub at_ref: String,
+    /// The tables, keyed by fully qualified name.
+    pub tables: BTreeMap<String, TableState>,
+}
+
+impl SyncState {
+    /// Creates an empty state for `at_ref`, as if no sync had happened yet.
+    pub fn new(at_ref: &str) -> Self {
+        Self {
+            version: SYNC_STATE_VERSION,
+            at_ref: at_ref.to_owned(),
+            tables: BTreeMap::new(),
+        }
+    }
+
+    /// Reads the state for `at_ref` from `path`, or returns an empty state
+    /// if the file doesn't exist yet.
+    pub fn load(path: &Path, at_ref: &str) -> Result<Self, SyncError> {
+        let contents = match fs::read(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::new(at_ref)),
+            Err(e) => return Err(SyncError::Io(path.to_owned(), e)),
+        };
+
+        let state: Self = serde_json::from_slice(&contents)
+            .map_err(|e| SyncError::Invalid(path.to_owned(), e))?;
+        if state.version != SYNC_STATE_VERSION {
+            return Err(SyncError::UnsupportedVersion(
+                path.to_owned(),
+                state.version,
+            ));
+        } else if state.at_ref != at_ref {
+            return Err(SyncError::RefMismatch {
+                path: path.to_owned(),
+                expected: state.at_ref,
+                actual: at_ref.to_owned(),
+            });
+        }
+
+        Ok(state)
+    }
+
+    /// Writes the state to `path`, atomically replacing any previous state.
+    pub fn save(&self, path: &Path) -> Result<(), SyncError> {
+        let mut contents = serde_json::to_vec(self).expect("state serializes");
+        contents.push(b'\n');
+        crate::yaml::write_atomic(path, &contents).map_err(|e| SyncError::Io(path.to_owned(), e))
+    }
+}
+
+/// The tables that differ from the previous sync.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TableDelta {
+    /// Tables that weren't on the ref at the previous sync.
+    pub added: Vec<Table>,
+    /// The fully qualified names of tables that are no longer on the ref.
+    pub removed: Vec<String>,
+    /// Tables with a new snapshot, schema or update time.
+    pub changed: Vec<Table>,
+}
+
+impl TableDelta {
+    /// Returns true if nothing changed since the previous sync.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// A sync in progress: the delta since the previous sync, and the state to
+/// record once it's been processed.
+#[derive(Debug)]
+pub struct TableSync {
+    /// The tables that differ from the previous sync.
+    pub delta: TableDelta,
+    next: SyncState,
+    state_file: PathBuf,
+}
+
+impl TableSync {
+    /// Compares the tables listed on `at_ref` against the state recorded in
+    /// `state_file`. The tables are consumed as they're listed, so only the
+    /// compact state and the tables in the delta are kept in memory.
+    pub fn new<E: From<SyncError>>(
+        state_file: &Path,
+        at_ref: &str,
+        tables: impl IntoIterator<Item = Result<Table, E>>,
+    ) -> Result<Self, E> {
+        let mut prev = SyncState::load(state_file, at_ref)?;
+        let mut next = SyncState::new(at_ref);
+        let mut delta = TableDelta::default();
+
+        for table in tables {
+            let table = table?;
+            let fqn = table.fqn();
+            let state = TableState::from(&table);
+
+            match prev.tables.remove(&fqn) {
+                None => delta.added.push(table),
+                Some(prev) if prev != state => delta.changed.push(table),
+                Some(_) => (),
+            }
+
+            next.tables.insert(fqn, state);
+        }
+
+        delta.removed = prev.tables.into_keys().collect();
+        Ok(Self {
+            delta,
+            next,
+            state_file: state_file.to_owned(),
+        })
+    }
+
+    /// Records the new state, so that the next sync starts from here. Call
+    /// this only once the delta has been processed.
+    pub fn commit(&self) -> Result<(), SyncError> {
+        self.next.save(&self.state_file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone as _, Utc};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::table::TableKind;
+
+    fn table(fqn: &str, snapshot_id: i64) -> Result<Table, SyncError> {
+        let (namespace, name) = fqn.split_once('.').unwrap();
+        Ok(Table {
+            id: Uuid::new_v4(),
+            name: name.to_owned(),
+            namespace: namespace.to_owned(),
+            kind: TableKind::Table,
+            records: None,
+            size: None,
+            last_updated_at: Utc.timestamp_millis_opt(1_700_000_000_000).unwrap(),
+            fields: Vec::new(),
+            snapshots: None,
+            partitions: Vec::new(),
+            metadata_location: String::new(),
+            current_snapshot_id: Some(snapshot_id),
+            current_schema_id: Some(0),
+            properties: BTreeMap::new(),
+        })
+    }
+
+    fn names(tables: &[Table]) -> Vec<String> {
+        tables.iter().map(Table::fqn).collect()
+    }
+
+    #[test]
+    fn sync() -> Result<(), SyncError> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+
+        // The first sync sees everything as added.
+        let sync = TableSync::new(&path, "main", [table("a.t1", 1), table("a.t2", 1)])?;
+        assert_eq!(names(&sync.delta.added), ["a.t1", "a.t2"]);
+        assert!(sync.delta.removed.is_empty() && sync.delta.changed.is_empty());
+        sync.commit()?;
+
+        let tables = || [table("a.t2", 2), table("b.t3", 1)];
+        let sync = TableSync::new(&path, "main", tables())?;
+        assert_eq!(names(&sync.delta.added), ["b.t3"]);
+        assert_eq!(sync.delta.removed, ["a.t1"]);
+        assert_eq!(names(&sync.delta.changed), ["a.t2"]);
+
+        // Without a commit, the next sync starts from the same state.
+        let sync = TableSync::new(&path, "main", tables())?;
+        assert_eq!(names(&sync.delta.changed), ["a.t2"]);
+        sync.commit()?;
+
+        let sync = TableSync::new(&path, "main", tables())?;
+        assert!(sync.delta.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn listing_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+
+        let err = io::Error::other("connection reset");
+        let tables = [table("a.t1", 1), Err(SyncError::Io(path.clone(), err))];
+        assert!(TableSync::new(&path, "main", tables).is_err());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn state_file_mismatch() -> Result<(), SyncError> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        TableSync::new(&path, "main", [table("a.t1", 1)])?.commit()?;
+
+        let res = TableSync::new(&path, "dev", [table("a.t1", 1)]);
+        assert!(matches!(res, Err(SyncError::RefMismatch { .. })));
+
+        fs::write(&path, r#"{"version": 99, "ref": "main", "tables": {}}"#).unwrap();
+        let res = TableSync::new(&path, "main", [table("a.t1", 1)]);
+        assert!(matches!(res, Err(SyncError::UnsupportedVersion(_, 99))));
+
+        fs::write(&path, "{").unwrap();
+        let res = TableSync::new(&path, "main", [table("a.t1", 1)]);
+        assert!(matches!(res, Err(SyncError::Invalid(..))));
+        Ok(())
+    }
+}
@@ -0,0 +1,419 @@
+//! Test doubles for code that talks to the Bauplan API, enabled with the
+//! `test-util` feature.
+//!
+//! [`MockCatalog`] answers API requests with canned JSON responses instead
+//! of sending them, so code that reads tables or branches can be tested
+//! without credentials or a network connection:
+//!
+//! ```
+//! use bauplan::{
+//!     table::{GetTable, GetTables},
+//!     testing::{MockCatalog, fixtures},
+//! };
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let catalog = MockCatalog::new()
+//!     .with_data(
+//!         http::Method::GET,
+//!         "/catalog/v0/refs/main/tables/bauplan.titanic",
+//!         fixtures::table("bauplan", "titanic"),
+//!     )
+//!     .with_pages(
+//!         http::Method::GET,
+//!         "/catalog/v0/refs/main/tables",
+//!         [
+//!             vec![fixtures::table("bauplan", "titanic")],
+//!             vec![fixtures::table("bauplan", "taxi_fhvhv")],
+//!         ],
+//!     );
+//!
+//! let table = catalog.roundtrip(GetTable {
+//!     name: "bauplan.titanic",
+//!     at_ref: "main",
+//!     namespace: None,
+//! })?;
+//! assert_eq!(table.name, "titanic");
+//!
+//! let req = GetTables {
+//!     at_ref: "main",
+//!     filter_by_name: None,
+//!     filter_by_namespace: None,
+//! };
+//!
+//! let tables = bauplan::paginate(req, None, |r| catalog.roundtrip(r))?;
+//! assert_eq!(tables.count(), 2);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{collections::HashMap, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ApiError, ApiRequest, ApiResponse, Profile};
+
+/// A canned response.
+#[derive(Debug, Clone)]
+struct Canned {
+    status: http::StatusCode,
+    body: String,
+}
+
+/// A response in a fixture file: either a bare response body, returned with
+/// `200 OK`, or a body with a status.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FixtureResponse {
+    WithStatus {
+        status: u16,
+        body: serde_json::Value,
+    },
+    Body(serde_json::Value),
+}
+
+/// A stand-in for the Bauplan API, which answers requests with canned
+/// responses, keyed by method and path.
+///
+/// Paths are matched as they're sent, after percent-encoding, and without
+/// the query string, except for the `pagination_token`: a page after the
+/// first is keyed by a path like
+/// `/catalog/v0/refs/main/tables?pagination_token=page-2`. Other query
+/// parameters, like filters and limits, are ignored.
+///
+/// A request with no canned response panics, listing the ones there are.
+#[derive(Debug, Clone)]
+pub struct MockCatalog {
+    profile: Profile,
+    responses: HashMap<String, Canned>,
+}
+
+impl Default for MockCatalog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockCatalog {
+    /// Creates a catalog with no responses, using [`Profile::for_tests`].
+    pub fn new() -> Self {
+        Self {
+            profile: Profile::for_tests(),
+            responses: HashMap::new(),
+        }
+    }
+
+    /// Creates a catalog with the responses in a JSON fixture file. See
+    /// [`MockCatalog::from_json`] for the format.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::from_json(&std::fs::read_to_string(path)?)?)
+    }
+
+    /// Creates a catalog with the responses in a JSON object, keyed by
+    /// method and path. Each value is either a response body, returned with
+    /// `200 OK`, or an object with a `status` and a `body`:
+    ///
+    /// ```json
+    /// {
+    ///   "GET /catalog/v0/branches/main": {
+    ///     "data": { "name": "main", "hash": "abc123" },
+    ///     "metadata": {}
+    ///   },
+    ///   "GET /catalog/v0/branches/nope": {
+    ///     "status": 404,
+    ///     "body": {
+    ///       "error": { "type": "BRANCH_NOT_FOUND", "context": { "branch_name": "nope" } }
+    ///     }
+    ///   }
+    /// }
+    /// ```
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let fixtures: HashMap<String, FixtureResponse> = serde_json::from_str(json)?;
+
+        let mut catalog = Self::new();
+        for (key, resp) in fixtures {
+            let (status, body) = match resp {
+                FixtureResponse::WithStatus { status, body } => (status, body),
+                FixtureResponse::Body(body) => (200, body),
+            };
+
+            let status = http::StatusCode::from_u16(status)
+                .map_err(<serde_json::Error as serde::de::Error>::custom)?;
+            catalog.responses.insert(
+                key,
+                Canned {
+                    status,
+                    body: body.to_string(),
+                },
+            );
+        }
+
+        Ok(catalog)
+    }
+
+    /// The profile requests are built with.
+    pub fn profile(&self) -> &Profile {
+        &self.profile
+    }
+
+    /// Adds a response with any status and body.
+    pub fn with_response(
+        mut self,
+        method: http::Method,
+        path: &str,
+        status: http::StatusCode,
+        body: serde_json::Value,
+    ) -> Self {
+        let canned = Canned {
+            status,
+            body: body.to_string(),
+        };
+
+        self.responses.insert(format!("{method} {path}"), canned);
+        self
+    }
+
+    /// Adds a successful response, with `data` as its payload.
+    pub fn with_data(self, method: http::Method, path: &str, data: impl Serialize) -> Self {
+        let body = serde_json::json!({ "data": data, "metadata": {} });
+        self.with_response(method, path, http::StatusCode::OK, body)
+    }
+
+    /// Adds a paginated response, one page per item of `pages`. The pages
+    /// after the first have the pagination tokens `page-2`, `page-3` and so
+    /// on.
+    pub fn with_pages<T: Serialize>(
+        mut self,
+        method: http::Method,
+        path: &str,
+        pages: impl IntoIterator<Item = Vec<T>>,
+    ) -> Self {
+        let pages: Vec<_> = pages.into_iter().collect();
+        let count = pages.len();
+        for (i, page) in pages.into_iter().enumerate() {
+            let number = i + 1;
+            let key = match number {
+                1 => path.to_owned(),
+                n => format!("{path}?pagination_token=page-{n}"),
+            };
+
+            let next = (number < count).then(|| format!("page-{}", number + 1));
+            let body = serde_json::json!({
+                "data": page,
+                "metadata": { "pagination_token": next },
+            });
+
+            self = self.with_response(method.clone(), &key, http::StatusCode::OK, body);
+        }
+
+        self
+    }
+
+    /// Adds an error response, with an API error type like
+    /// `TABLE_NOT_FOUND` and its context.
+    pub fn with_error(
+        self,
+        method: http::Method,
+        path: &str,
+        status: http::StatusCode,
+        error_type: &str,
+        context: serde_json::Value,
+    ) -> Self {
+        let body = serde_json::json!({
+            "error": { "type": error_type, "message": null, "context": context },
+        });
+
+        self.with_response(method, path, status, body)
+    }
+
+    /// Answers a request with the matching canned response, and parses it
+    /// as the API response would be. This has the same shape as
+    /// [`Client::roundtrip`](crate::Client::roundtrip), so it can be
+    /// passed to [`paginate`](crate::paginate).
+    pub fn roundtrip<T: ApiRequest>(&self, req: T) -> Result<T::Response, ApiError> {
+        req.validate()?;
+        let req = req
+            .into_request(&self.profile)
+            .expect("the request should be valid");
+
+        let key = request_key(&req);
+        let Some(canned) = self.responses.get(&key) else {
+            let mut known: Vec<_> = self.responses.keys().map(String::as_str).collect();
+            known.sort_unstable();
+            panic!("no canned response for {key}; have: {known:?}");
+        };
+
+        let resp = http::Response::builder()
+            .status(canned.status)
+            .body(canned.body.as_bytes())
+            .expect("the response should be valid");
+
+        T::Response::from_response(resp)
+    }
+}
+
+/// The key a request's response is stored under.
+fn request_key(req: &http::Request<String>) -> String {
+    let uri = req.uri();
+    let token = uri.query().and_then(|qs| {
+        url::form_urlencoded::parse(qs.as_bytes())
+            .find(|(k, _)| k == "pagination_token")
+            .map(|(_, v)| v.into_owned())
+    });
+
+    match token {
+        Some(token) => format!("{} {}?pagination_token={token}", req.method(), uri.path()),
+        None => format!("{} {}", req.method(), uri.path()),
+    }
+}
+
+/// Example API objects, to use as canned responses.
+pub mod fixtures {
+    use std::collections::BTreeMap;
+
+    use chrono::{TimeZone as _, Utc};
+    use uuid::Uuid;
+
+    use crate::{
+        branch::Branch,
+        table::{PartitionField, Table, TableField, TableKind},
+    };
+
+    /// A table with a few columns, partitioned by day.
+    pub fn table(namespace: &str, name: &str) -> Table {
+        let field = |id, name: &str, r#type: &str, required| TableField {
+            id,
+            name: name.to_owned(),
+            required,
+            r#type: r#type.to_owned(),
+        };
+
+        Table {
+            id: Uuid::nil(),
+            name: name.to_owned(),
+            namespace: namespace.to_owned(),
+            kind: TableKind::Table,
+            records: Some(1000),
+            size: Some(64 * 1024),
+            last_updated_at: Utc.timestamp_millis_opt(1_700_000_000_000).unwrap(),
+            fields: vec![
+                field(1, "id", "long", true),
+                field(2, "name", "string", false),
+                field(3, "created_at", "timestamptz", false),
+            ],
+            snapshots: Some(1),
+            partitions: vec![PartitionField {
+                name: "created_at_day".to_owned(),
+                transform: "day".to_owned(),
+            }],
+            metadata_location: format!(
+                "s3://bauplan-test/iceberg/{namespace}/{name}/metadata/00000.metadata.json"
+            ),
+            current_snapshot_id: Some(1),
+            current_schema_id: Some(0),
+            properties: BTreeMap::new(),
+        }
+    }
+
+    /// A branch, with a fixed hash.
+    pub fn branch(name: &str) -> Branch {
+        Branch {
+            name: name.to_owned(),
+            hash: "0123456789abcdef".to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ApiErrorKind,
+        branch::{GetBranch, GetBranches},
+        table::GetTable,
+    };
+
+    #[test]
+    fn canned_table() -> Result<(), ApiError> {
+        let catalog = MockCatalog::new().with_data(
+            http::Method::GET,
+            "/catalog/v0/refs/main/tables/bauplan.titanic",
+            fixtures::table("bauplan", "titanic"),
+        );
+
+        let table = catalog.roundtrip(GetTable {
+            name: "bauplan.titanic",
+            at_ref: "main",
+            namespace: None,
+        })?;
+
+        assert_eq!(table.fqn(), "bauplan.titanic");
+        assert_eq!(table.fields.len(), 3);
+        assert_eq!(table.partitions[0].transform, "day");
+        Ok(())
+    }
+
+    #[test]
+    fn paginated() -> Result<(), ApiError> {
+        let catalog = MockCatalog::new().with_pages(
+            http::Method::GET,
+            "/catalog/v0/branches",
+            [
+                vec![fixtures::branch("main"), fixtures::branch("dev")],
+                vec![fixtures::branch("u.feature")],
+            ],
+        );
+
+        let req = GetBranches {
+            filter_by_name: None,
+            filter_by_user: None,
+        };
+
+        let names = crate::paginate(req, None, |r| catalog.roundtrip(r))?
+            .map(|b| b.map(|b| b.name))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(names, ["main", "dev", "u.feature"]);
+        Ok(())
+    }
+
+    #[test]
+    fn from_json() -> Result<(), serde_json::Error> {
+        let catalog = MockCatalog::from_json(
+            r#"{
+                "GET /catalog/v0/branches/main": {
+                    "data": { "name": "main", "hash": "abc123" },
+                    "metadata": {}
+                },
+                "GET /catalog/v0/branches/nope": {
+                    "status": 404,
+                    "body": {
+                        "error": { "type": "BRANCH_NOT_FOUND", "context": { "branch_name": "nope" } }
+                    }
+                }
+            }"#,
+        )?;
+
+        let get = |name: &str| {
+            catalog.roundtrip(GetBranch {
+                name,
+                if_none_match: None,
+            })
+        };
+
+        assert_eq!(get("main").unwrap().hash, "abc123");
+        let err = get("nope").unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            Some(ApiErrorKind::BranchNotFound { branch_name }) if branch_name == "nope"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "no canned response for GET /catalog/v0/branches/main")]
+    fn missing_response() {
+        let _ = MockCatalog::new().roundtrip(GetBranch {
+            name: "main",
+            if_none_match: None,
+        });
+    }
+}
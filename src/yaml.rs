@@ -0,0 +1,315 @@
+//! Nondestructive edits to YAML files, like the config file. Intended for
+//! internal use.
+
+use std::{
+    fs::{self, File, TryLockError},
+    io::{self, Write as _},
+    path::{Path, PathBuf},
+    thread, time,
+};
+
+use anyhow::bail;
+use nondestructive::yaml;
+
+/// How long to wait for another process to finish editing a file before
+/// giving up.
+const LOCK_TIMEOUT: time::Duration = time::Duration::from_secs(10);
+
+/// Load a YAML file, apply edits via a closure, and write it back. The
+/// closure receives a mutable reference to the parsed document.
+///
+/// Edits made in this way will be nondestructive, ie they will not affect
+/// whitespace or comments. The new contents are swapped in atomically, so a
+/// concurrent reader sees either the old file or the new one.
+pub fn edit(
+    path: &Path,
+    f: impl FnOnce(&mut yaml::Document) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut doc = match fs::read_to_string(path) {
+        Ok(content) => yaml::from_slice(&content)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => yaml::Document::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    f(&mut doc)?;
+
+    let mut res = doc.to_string();
+    if !res.ends_with('\n') {
+        res.push('\n');
+    }
+
+    if let Some(parent) = path.parent()
+        && !parent.exists()
+    {
+        fs::create_dir_all(parent)?;
+    }
+
+    write_atomic(path, res.as_bytes())?;
+    Ok(())
+}
+
+/// Like [`edit`], but holds an advisory lock across the read-modify-write,
+/// so that concurrent edits from multiple processes don't clobber each
+/// other. Used for the config file, which several commands rewrite.
+pub fn edit_locked(
+    path: &Path,
+    f: impl FnOnce(&mut yaml::Document) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.exists()
+    {
+        fs::create_dir_all(parent)?;
+    }
+
+    let _lock = lock(path)?;
+    edit(path, f)
+}
+
+/// Takes an exclusive advisory lock for editing `path`, which is released
+/// when the returned file is dropped.
+///
+/// The lock is taken on a sidecar file rather than `path` itself, because
+/// `path` is replaced by a rename on every write.
+fn lock(path: &Path) -> anyhow::Result<File> {
+    let lock_path = sibling(path, "lock");
+    let file = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)?;
+
+    let start = time::Instant::now();
+    loop {
+        match file.try_lock() {
+            Ok(()) => return Ok(file),
+            Err(TryLockError::WouldBlock) if start.elapsed() < LOCK_TIMEOUT => {
+                thread::sleep(time::Duration::from_millis(50));
+            }
+            Err(TryLockError::WouldBlock) => bail!(
+                "timed out waiting to edit {}: another bauplan process is holding {}",
+                path.display(),
+                lock_path.display(),
+            ),
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+    }
+}
+
+/// Writes `contents` to a temporary file next to `path`, syncs it, and then
+/// renames it into place, so that readers never observe a partial write.
+/// The temporary file is only readable by the owner until the permissions
+/// of the existing file, if any, are copied over.
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let tmp_path = sibling(path, &format!("{}.tmp", std::process::id()));
+    let permissions = fs::metadata(path).ok().map(|m| m.permissions());
+
+    let res = (|| {
+        let mut options = File::options();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        // Left behind by an earlier process with the same pid.
+        let _ = fs::remove_file(&tmp_path);
+        let mut tmp = options.open(&tmp_path)?;
+        if let Some(permissions) = permissions {
+            tmp.set_permissions(permissions)?;
+        }
+
+        tmp.write_all(contents)?;
+        tmp.sync_all()?;
+        fs::rename(&tmp_path, path)
+    })();
+
+    if res.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+
+    res
+}
+
+/// Returns `path` with `.{suffix}` appended to the file name.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Navigate into a nested YAML mapping by key path, creating any missing
+/// intermediate mappings along the way. Returns a `MappingMut` pointing at
+/// the innermost mapping.
+///
+/// For example, `&["parameters", "location_id"]` returns a `MappingMut` for
+/// the `location_id` entry, and `&[]` returns the top-level mapping.
+pub fn mapping_at_path<'a>(
+    doc: &'a mut yaml::Document,
+    path: &[&str],
+) -> anyhow::Result<yaml::MappingMut<'a>> {
+    let mut current = match doc.as_ref().as_any() {
+        yaml::Any::Null | yaml::Any::Mapping(_) => doc.as_mut().make_mapping(),
+        _ => bail!("invalid file: not a dictionary"),
+    };
+
+    for &key in path {
+        if current.as_ref().get(key).is_none() {
+            let _ = current.insert(key, yaml::Separator::Auto).make_mapping();
+        }
+
+        let Some(next) = current.get_into_mut(key).and_then(|v| v.into_mapping_mut()) else {
+            bail!("key {key:?} exists, but is not a dictionary");
+        };
+
+        current = next
+    }
+
+    Ok(current)
+}
+
+/// Sets `key` to `value`, in place if `key` already exists.
+pub fn upsert_str(m: &mut yaml::MappingMut<'_>, key: &str, value: &str) {
+    if let Some(mut v) = m.get_mut(key) {
+        v.set_string(value);
+    } else {
+        m.insert_str(key, value);
+    }
+}
+
+/// Sets `key` to `value`, in place if `key` already exists.
+pub fn upsert_bool(m: &mut yaml::MappingMut<'_>, key: &str, value: bool) {
+    if let Some(mut v) = m.get_mut(key) {
+        v.set_bool(value);
+    } else {
+        m.insert_bool(key, value);
+    }
+}
+
+/// Sets `key` to `value`, in place if `key` already exists.
+pub fn upsert_i64(m: &mut yaml::MappingMut<'_>, key: &str, value: i64) {
+    if let Some(mut v) = m.get_mut(key) {
+        v.set_i64(value);
+    } else {
+        m.insert_i64(key, value);
+    }
+}
+
+/// Sets `key` to `value`, in place if `key` already exists.
+pub fn upsert_f64(m: &mut yaml::MappingMut<'_>, key: &str, value: f64) {
+    if let Some(mut v) = m.get_mut(key) {
+        v.set_f64(value);
+    } else {
+        m.insert_f64(key, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mapping_at_path_start_empty() -> anyhow::Result<()> {
+        let mut doc = yaml::from_slice("null")?;
+        let mut m = mapping_at_path(&mut doc, &["a", "b", "c"])?;
+        m.insert_str("key", "value");
+
+        let output = doc.to_string();
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&output)?;
+        assert_eq!(parsed["a"]["b"]["c"]["key"].as_str(), Some("value"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn mapping_at_path_create() -> anyhow::Result<()> {
+        let mut doc = yaml::from_slice("root: 1\n")?;
+        let mut m = mapping_at_path(&mut doc, &["a", "b", "c"])?;
+        m.insert_str("key", "value");
+
+        let output = doc.to_string();
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&output)?;
+        assert_eq!(parsed["a"]["b"]["c"]["key"].as_str(), Some("value"));
+        assert_eq!(parsed["root"].as_i64(), Some(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn mapping_at_path_existing() -> anyhow::Result<()> {
+        let input = "\
+top:
+  nested:
+    existing: 42
+";
+        let mut doc = yaml::from_slice(input)?;
+        let mut m = mapping_at_path(&mut doc, &["top", "nested"])?;
+        m.insert_str("added", "hello");
+
+        let output = doc.to_string();
+        assert!(output.contains("existing: 42"));
+        assert!(output.contains("added: hello"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn edit_locked_concurrent() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("config.yaml");
+        std::fs::write(&path, "profiles:\n  default:\n    api_key: secret\n")?;
+
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    for j in 0..10 {
+                        edit_locked(&path, |doc| {
+                            let mut m = mapping_at_path(doc, &["profiles", "default"])?;
+                            upsert_str(&mut m, "active_branch", &format!("branch_{i}_{j}"));
+                            Ok(())
+                        })
+                        .unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        for w in writers {
+            w.join().unwrap();
+        }
+
+        let output = std::fs::read_to_string(&path)?;
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&output)?;
+        let profile = &parsed["profiles"]["default"];
+        assert_eq!(profile["api_key"].as_str(), Some("secret"));
+        assert!(
+            profile["active_branch"]
+                .as_str()
+                .is_some_and(|b| b.starts_with("branch_"))
+        );
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_atomic_permissions() -> anyhow::Result<()> {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        let dir = tempfile::tempdir()?;
+        let mode =
+            |p: &Path| -> io::Result<u32> { Ok(fs::metadata(p)?.permissions().mode() & 0o777) };
+
+        let new = dir.path().join("new.yaml");
+        write_atomic(&new, b"a: 1\n")?;
+        assert_eq!(mode(&new)?, 0o600);
+
+        let existing = dir.path().join("existing.yaml");
+        fs::write(&existing, "a: 1\n")?;
+        fs::set_permissions(&existing, fs::Permissions::from_mode(0o640))?;
+        write_atomic(&existing, b"a: 2\n")?;
+        assert_eq!(mode(&existing)?, 0o640);
+        assert_eq!(fs::read_to_string(&existing)?, "a: 2\n");
+
+        Ok(())
+    }
+}
@@ -7,12 +7,15 @@ use similar::{ChangeTag, TextDiff};
 use std::fmt::Write as _;
 
 mod cli {
+    mod alias;
     mod auth;
     mod branch;
     mod config;
     mod import;
     mod init;
     mod job;
+    mod json_output;
+    mod namespace;
     mod parameter;
     mod query;
     mod run;
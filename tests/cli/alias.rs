@@ -0,0 +1,112 @@
+use anyhow::Result;
+use predicates::str::contains;
+
+fn bauplan_at(home: &tempfile::TempDir) -> assert_cmd::Command {
+    let mut cmd = crate::bauplan();
+    cmd.env("HOME", home.path())
+        .env("USERPROFILE", home.path())
+        .env_remove("BAUPLAN_PROFILE")
+        .env_remove("BAUPLAN_API_KEY")
+        .env_remove("BAUPLAN_API_ENDPOINT");
+    cmd
+}
+
+fn write_config(home: &tempfile::TempDir, aliases: &str) -> Result<()> {
+    let dir = home.path().join(".bauplan");
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(
+        dir.join("config.yaml"),
+        format!("profiles:\n  default:\n    api_key: bpln_test_key\n    aliases:\n{aliases}"),
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn alias_set_and_expand() -> Result<()> {
+    let home = tempfile::tempdir()?;
+
+    bauplan_at(&home)
+        .args(["alias", "set", "v", "version"])
+        .assert()
+        .success();
+
+    bauplan_at(&home)
+        .args(["alias", "ls"])
+        .assert()
+        .success()
+        .stdout(contains("version"));
+
+    bauplan_at(&home)
+        .args(["-v", "v"])
+        .assert()
+        .success()
+        .stdout(contains("bauplan "))
+        .stderr(contains("expanded alias: bauplan -v version"));
+
+    bauplan_at(&home)
+        .args(["alias", "rm", "v"])
+        .assert()
+        .success();
+
+    bauplan_at(&home)
+        .arg("v")
+        .assert()
+        .failure()
+        .stderr(contains("unrecognized subcommand"));
+
+    Ok(())
+}
+
+#[test]
+fn alias_unknown_passthrough() -> Result<()> {
+    let home = tempfile::tempdir()?;
+    write_config(&home, "      v: version\n")?;
+
+    bauplan_at(&home)
+        .arg("nope")
+        .assert()
+        .failure()
+        .stderr(contains("unrecognized subcommand 'nope'"));
+
+    Ok(())
+}
+
+#[test]
+fn alias_recursion() -> Result<()> {
+    let home = tempfile::tempdir()?;
+    write_config(&home, "      a: b\n      b: a\n")?;
+
+    bauplan_at(&home)
+        .arg("a")
+        .assert()
+        .failure()
+        .stderr(contains("alias \"a\" is recursive: a -> b -> a"));
+
+    bauplan_at(&home)
+        .args(["alias", "set", "c", "c --verbose"])
+        .assert()
+        .failure()
+        .stderr(contains("is recursive"));
+
+    Ok(())
+}
+
+#[test]
+fn alias_rejects_profile_flag() -> Result<()> {
+    let home = tempfile::tempdir()?;
+
+    bauplan_at(&home)
+        .args(["alias", "set", "prod-run", "run -P prod"])
+        .assert()
+        .failure()
+        .stderr(contains("can't set -P"));
+
+    bauplan_at(&home)
+        .args(["alias", "set", "run", "version"])
+        .assert()
+        .failure()
+        .stderr(contains("built-in command"));
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::cli::bauplan;
+use crate::cli::{bauplan, username};
 use predicates::prelude::PredicateBooleanExt as _;
 use predicates::str::contains;
 
@@ -12,3 +12,149 @@ fn invalid_api_key() {
         .stderr(contains("UNAUTHORIZED"))
         .stderr(contains("Failed to parse").not());
 }
+
+#[test]
+fn header_cannot_override_authorization() {
+    bauplan()
+        .args(["-H", "Authorization: Bearer other", "branch", "ls"])
+        .assert()
+        .failure()
+        .stderr(contains("Invalid header \"Authorization\""));
+}
+
+#[test]
+fn whoami() {
+    bauplan()
+        .args(["whoami"])
+        .assert()
+        .success()
+        .stdout(contains(username()))
+        .stdout(contains("Branch prefix"));
+
+    bauplan()
+        .args(["-O", "json", "whoami"])
+        .assert()
+        .success()
+        .stdout(contains(format!(r#""branch_prefix":"{}.""#, username())));
+}
+
+/// Runs the CLI with a config file containing `profile` as the default
+/// profile, and no credentials in the environment.
+fn with_config(home: &tempfile::TempDir, profile: &str) -> assert_cmd::Command {
+    let dir = home.path().join(".bauplan");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("config.yaml"),
+        format!("profiles:\n  default:\n{profile}"),
+    )
+    .unwrap();
+
+    let mut cmd = bauplan();
+    cmd.env("HOME", home.path())
+        .env("USERPROFILE", home.path())
+        .env_remove("BAUPLAN_PROFILE")
+        .env_remove("BAUPLAN_API_KEY")
+        .env_remove("BAUPLAN_API_ENDPOINT");
+    cmd
+}
+
+fn read_config(home: &tempfile::TempDir) -> serde_yaml::Value {
+    let config = std::fs::read_to_string(home.path().join(".bauplan/config.yaml")).unwrap();
+    serde_yaml::from_str(&config).unwrap()
+}
+
+#[test]
+fn auth_status_precedence() {
+    let home = tempfile::tempdir().unwrap();
+    let profile = "    api_key: bpln_stored_key\n";
+
+    with_config(&home, profile)
+        .args(["-O", "json", "auth", "status"])
+        .assert()
+        .success()
+        .stdout(contains(r#""source":"config_file""#))
+        .stdout(contains(r#""shadowed":null"#))
+        .stdout(contains("bpln_stored_key").not());
+
+    with_config(&home, profile)
+        .env("BAUPLAN_API_KEY", "bpln_env_key")
+        .args(["-O", "json", "auth", "status"])
+        .assert()
+        .success()
+        .stdout(contains(r#""source":"env""#))
+        .stdout(contains(r#""shadowed":"config_file""#))
+        .stdout(contains("bpln_env_key").not());
+
+    with_config(&home, profile)
+        .env("BAUPLAN_API_KEY", "bpln_env_key")
+        .args(["auth", "status"])
+        .assert()
+        .success()
+        .stdout(contains("BAUPLAN_API_KEY environment variable"))
+        .stdout(contains("Overridden"));
+}
+
+#[test]
+fn auth_status_not_logged_in() {
+    let home = tempfile::tempdir().unwrap();
+    with_config(&home, "    active_branch: main\n")
+        .args(["auth", "status"])
+        .assert()
+        .failure()
+        .stderr(contains("bauplan auth login"));
+}
+
+#[test]
+fn auth_login_invalid_key_keeps_credentials() {
+    let home = tempfile::tempdir().unwrap();
+    let profile = concat!(
+        "    api_key: bpln_old_key\n",
+        "    api_endpoint: http://127.0.0.1:1\n",
+        "    active_branch: a.b\n",
+    );
+
+    with_config(&home, profile)
+        .args(["--client-timeout", "2", "auth", "login"])
+        .write_stdin("bpln_new_key\n")
+        .assert()
+        .failure()
+        .stderr(contains("no credentials were changed"))
+        .stderr(contains("bpln_new_key").not());
+
+    let config = read_config(&home);
+    let stored = &config["profiles"]["default"];
+    assert_eq!(stored["api_key"].as_str(), Some("bpln_old_key"));
+    assert_eq!(stored["active_branch"].as_str(), Some("a.b"));
+}
+
+#[test]
+fn auth_login_empty_key() {
+    let home = tempfile::tempdir().unwrap();
+    with_config(&home, "    api_key: bpln_old_key\n")
+        .args(["auth", "login"])
+        .write_stdin("\n")
+        .assert()
+        .failure()
+        .stderr(contains("no API key provided"));
+}
+
+#[test]
+fn auth_logout() {
+    let home = tempfile::tempdir().unwrap();
+    with_config(&home, "    api_key: bpln_old_key\n    active_branch: a.b\n")
+        .args(["auth", "logout"])
+        .assert()
+        .success()
+        .stderr(contains("Removed stored credentials"));
+
+    let config = read_config(&home);
+    let stored = &config["profiles"]["default"];
+    assert!(stored["api_key"].is_null());
+    assert_eq!(stored["active_branch"].as_str(), Some("a.b"));
+
+    with_config(&home, "    active_branch: a.b\n")
+        .args(["auth", "logout"])
+        .assert()
+        .success()
+        .stderr(contains("No stored credentials"));
+}
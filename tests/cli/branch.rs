@@ -25,6 +25,21 @@ fn get_main() {
     bauplan().args(["branch", "get", "main"]).assert().success();
 }
 
+#[test]
+fn summary() {
+    bauplan()
+        .args(["branch", "summary", "main"])
+        .assert()
+        .success()
+        .stdout(contains("TABLES").and(contains("LAST UPDATED")));
+
+    bauplan()
+        .args(["-O", "json", "branch", "summary", "main"])
+        .assert()
+        .success()
+        .stdout(contains(r#""ref":"main""#));
+}
+
 #[test]
 fn create_and_delete() {
     let branch = test_branch("cli_create_delete");
@@ -69,6 +84,30 @@ fn create_if_not_exists() {
         .failure();
 }
 
+#[test]
+fn checkout_validates_branch() {
+    let branch = test_branch("cli_checkout_create");
+
+    // Neither of these gets as far as changing the active branch.
+    bauplan()
+        .args(["checkout", &format!("{}_typo", branch.name)])
+        .assert()
+        .failure()
+        .stderr(contains("doesn't exist").and(contains("checkout -b")));
+
+    bauplan()
+        .args(["checkout", "--create", &branch.name, "--from-ref", "main"])
+        .assert()
+        .failure()
+        .stderr(contains("already exists").and(contains("--if-not-exists")));
+
+    // --if-not-exists requires -b.
+    bauplan()
+        .args(["checkout", "--if-not-exists", &branch.name])
+        .assert()
+        .failure();
+}
+
 #[test]
 fn delete_if_exists() {
     let branch = format!("{}.cli_delete_idempotent", username());
@@ -116,3 +155,67 @@ fn rename() {
         .stdout(contains(&branch.name))
         .stdout(contains("cli_rename_old").not());
 }
+
+#[test]
+fn log() {
+    let out = bauplan()
+        .args(["-O", "json", "branch", "log", "main", "-n", "3"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+
+    let commits: Vec<serde_json::Value> = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(commits.len(), 3);
+    for commit in &commits {
+        assert!(commit["ref"]["hash"].is_string());
+        assert!(commit["parent_hashes"].is_array());
+    }
+}
+
+#[test]
+fn log_filter_by_message() {
+    let branch = test_branch("cli_branch_log");
+    let marker = format!("log-marker-{}", std::process::id());
+
+    bauplan()
+        .args([
+            "namespace",
+            "create",
+            "--branch",
+            &branch.name,
+            "--commit-body",
+            &marker,
+            "logged_ns",
+        ])
+        .assert()
+        .success();
+
+    bauplan()
+        .args([
+            "branch",
+            "log",
+            &branch.name,
+            "--message",
+            &marker,
+            "--format",
+            "medium",
+        ])
+        .assert()
+        .success()
+        .stdout(contains(&marker).and(contains("commit ")));
+
+    // A filter that matches nothing prints an empty list.
+    bauplan()
+        .args([
+            "-O",
+            "json",
+            "branch",
+            "log",
+            &branch.name,
+            "--message",
+            "no commit has this message",
+        ])
+        .assert()
+        .success()
+        .stdout("[]\n");
+}
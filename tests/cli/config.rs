@@ -22,13 +22,121 @@ fn config_set_writes_supported_profile_settings() -> Result<()> {
     Ok(())
 }
 
-fn config_set(home: &tempfile::TempDir, name: &str, value: &str) {
-    crate::bauplan()
-        .env("HOME", home.path())
+#[test]
+fn concurrent_config_set_keeps_profile_intact() -> Result<()> {
+    let home = tempfile::tempdir()?;
+    let api_key = "bpln_test_key";
+    config_set(&home, "api_key", api_key);
+
+    let branches: Vec<String> = (0..8).map(|i| format!("branch_{i}")).collect();
+    std::thread::scope(|s| {
+        for branch in &branches {
+            let home = &home;
+            s.spawn(move || config_set(home, "active_branch", branch));
+        }
+    });
+
+    let config = std::fs::read_to_string(home.path().join(".bauplan/config.yaml"))?;
+    let parsed: serde_yaml::Value = serde_yaml::from_str(&config)?;
+    let profile = &parsed["profiles"]["default"];
+
+    assert_eq!(profile["api_key"].as_str(), Some(api_key));
+    let active_branch = profile["active_branch"].as_str().unwrap_or_default();
+    assert!(
+        branches.iter().any(|b| b == active_branch),
+        "unexpected active branch {active_branch:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn use_profile_and_precedence() -> Result<()> {
+    let home = tempfile::tempdir()?;
+    let dir = home.path().join(".bauplan");
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(
+        dir.join("config.yaml"),
+        "profiles:
+  default:
+    api_key: bpln_default_key
+  staging:
+    api_key: bpln_staging_key
+    default_namespace: raw
+",
+    )?;
+
+    config_cmd(&home)
+        .args(["config", "use-profile", "staging"])
+        .assert()
+        .success();
+
+    let config = std::fs::read_to_string(dir.join("config.yaml"))?;
+    let parsed: serde_yaml::Value = serde_yaml::from_str(&config)?;
+    assert_eq!(parsed["current_profile"].as_str(), Some("staging"));
+    assert_eq!(
+        parsed["profiles"]["default"]["api_key"].as_str(),
+        Some("bpln_default_key")
+    );
+
+    // The config file's current profile is used by default.
+    let profile = current_profile(config_cmd(&home))?;
+    assert_eq!(profile["name"], "staging");
+    assert_eq!(profile["default_namespace"], "raw");
+
+    // BAUPLAN_PROFILE wins over the config file, and BAUPLAN_NAMESPACE over
+    // the profile's settings.
+    let mut cmd = config_cmd(&home);
+    cmd.env("BAUPLAN_PROFILE", "default")
+        .env("BAUPLAN_NAMESPACE", "env_ns");
+    let profile = current_profile(cmd)?;
+    assert_eq!(profile["name"], "default");
+    assert_eq!(profile["default_namespace"], "env_ns");
+
+    // --profile wins over both.
+    let mut cmd = config_cmd(&home);
+    cmd.env("BAUPLAN_PROFILE", "default")
+        .args(["--profile", "staging"]);
+    let profile = current_profile(cmd)?;
+    assert_eq!(profile["name"], "staging");
+    assert_eq!(profile["api_key"], "bpln_staging_key");
+
+    config_cmd(&home)
+        .args(["config", "use-profile", "nope"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Profile 'nope' not found"));
+
+    Ok(())
+}
+
+fn current_profile(mut cmd: assert_cmd::Command) -> Result<serde_json::Value> {
+    let output = cmd
+        .args(["-O", "json", "config", "get"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    Ok(serde_json::from_slice(&output)?)
+}
+
+/// A command isolated from the user's config and environment.
+fn config_cmd(home: &tempfile::TempDir) -> assert_cmd::Command {
+    let mut cmd = crate::bauplan();
+    cmd.env("HOME", home.path())
         .env("USERPROFILE", home.path())
         .env_remove("BAUPLAN_PROFILE")
         .env_remove("BAUPLAN_API_KEY")
         .env_remove("BAUPLAN_API_ENDPOINT")
+        .env_remove("BAUPLAN_GRPC_ENDPOINT")
+        .env_remove("BAUPLAN_CATALOG_ENDPOINT")
+        .env_remove("BAUPLAN_NAMESPACE");
+    cmd
+}
+
+fn config_set(home: &tempfile::TempDir, name: &str, value: &str) {
+    config_cmd(home)
         .args(["config", "set", name, value])
         .assert()
         .success();
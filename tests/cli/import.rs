@@ -75,7 +75,8 @@ fn import_manually() {
             "/tmp/planabcd.yaml",
         ])
         .assert()
-        .success();
+        .success()
+        .stderr(contains("Matched "));
 
     bauplan()
         .args(["table", "create-plan-apply", "--plan", "/tmp/planabcd.yaml"])
@@ -134,3 +135,50 @@ fn import_manually() {
         .success()
         .stdout(contains("400"));
 }
+
+#[test]
+fn format_must_match_search_uri() {
+    bauplan()
+        .args([
+            "table",
+            "create",
+            TABLE_NAME,
+            "--search-uri",
+            "s3://bpln-e2e-test-tables/test_tables/two_columns_csv/*.csv",
+            "--branch",
+            "main",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains(
+            "matches csv files, but the file format is parquet",
+        ));
+
+    bauplan()
+        .args([
+            "table",
+            "import",
+            TABLE_NAME,
+            "--file-format",
+            "avro",
+            "--search-uri",
+            "s3://bpln-e2e-test-tables/test_tables/two_columns_csv/*.csv",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("invalid file format"));
+
+    bauplan()
+        .args([
+            "table",
+            "import",
+            TABLE_NAME,
+            "--file-format",
+            "csv",
+            "--search-uri",
+            "s3://bpln-e2e-test-tables/test_tables/two_columns_csv/*.csv",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("only parquet is supported"));
+}
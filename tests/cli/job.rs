@@ -5,6 +5,16 @@ fn ls() {
     bauplan().args(["job", "ls"]).assert().success();
 }
 
+#[test]
+fn ls_formats() {
+    for format in ["table", "wide", "json", "yaml"] {
+        bauplan()
+            .args(["job", "ls", "--format", format])
+            .assert()
+            .success();
+    }
+}
+
 #[test]
 fn filter_by_kind_pascal_case() {
     bauplan()
@@ -20,3 +30,98 @@ fn filter_by_status_screaming_case() {
         .assert()
         .success();
 }
+
+#[test]
+fn submit_with_name() {
+    let name = format!(
+        "cli-job-name-{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    );
+
+    bauplan()
+        .args(["query", "--name", &name, "SELECT 1"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn filter_by_project() {
+    bauplan()
+        .args([
+            "run",
+            "--dry-run",
+            "--no-cache",
+            "-p",
+            "tests/fixtures/simple_taxi_dag",
+        ])
+        .assert()
+        .success();
+
+    for project in ["641d33d8-a6c2-4525-90d5-d54e7f27c26f", "bauplan_prophet"] {
+        bauplan()
+            .args([
+                "job",
+                "ls",
+                "--kind",
+                "run",
+                "-n",
+                "20",
+                "--project",
+                project,
+            ])
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("PROJECT"))
+            .stdout(predicates::str::contains("bauplan_prophet"));
+    }
+
+    // "." resolves to the project in the current directory.
+    bauplan()
+        .current_dir("tests/fixtures/simple_taxi_dag")
+        .args(["job", "ls", "--kind", "run", "-n", "20", "--project", "."])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("bauplan_prophet"));
+}
+
+#[test]
+fn logs_follow() {
+    let output = bauplan()
+        .args([
+            "run",
+            "--dry-run",
+            "--no-cache",
+            "--detach",
+            "-p",
+            "tests/fixtures/simple_taxi_dag",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let job_id = stderr
+        .split_whitespace()
+        .skip_while(|w| *w != "Job")
+        .nth(1)
+        .expect("no job id printed");
+
+    // Follows the job until it completes.
+    bauplan()
+        .args(["job", "logs", job_id, "--follow"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Normalizing model"));
+
+    // Once the job has finished, the stored logs are printed instead.
+    bauplan()
+        .args(["job", "logs", job_id, "-f", "--output", "json"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Normalizing model"))
+        .stdout(predicates::str::starts_with("{"));
+}
@@ -0,0 +1,195 @@
+//! Every command prints a single JSON object to stdout with `-O json`.
+
+use anyhow::Result;
+use predicates::prelude::PredicateBooleanExt as _;
+use predicates::str::{contains, ends_with, starts_with};
+
+use crate::cli::{bauplan, test_branch};
+
+/// A JSON object on a single line.
+fn json_object() -> impl predicates::Predicate<str> {
+    starts_with("{").and(ends_with("}\n"))
+}
+
+fn bauplan_at(home: &tempfile::TempDir) -> assert_cmd::Command {
+    let mut cmd = bauplan();
+    cmd.env("HOME", home.path())
+        .env("USERPROFILE", home.path())
+        .env_remove("BAUPLAN_PROFILE")
+        .env_remove("BAUPLAN_API_KEY")
+        .env_remove("BAUPLAN_API_ENDPOINT");
+    cmd
+}
+
+#[test]
+fn local_commands() -> Result<()> {
+    let home = tempfile::tempdir()?;
+    let dir = home.path().join(".bauplan");
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(
+        dir.join("config.yaml"),
+        "profiles:\n  default:\n    api_key: bpln_test_key\n",
+    )?;
+
+    bauplan_at(&home)
+        .args(["-O", "json", "version"])
+        .assert()
+        .success()
+        .stdout(json_object().and(contains(r#""version":"#)));
+
+    bauplan_at(&home)
+        .args(["-O", "json", "config", "set", "default_namespace", "ns"])
+        .assert()
+        .success()
+        .stdout(json_object().and(contains(r#""setting":"default_namespace""#)));
+
+    bauplan_at(&home)
+        .args(["-O", "json", "config", "set", "api_key", "bpln_new_key"])
+        .assert()
+        .success()
+        .stdout(json_object().and(contains("bpln_new_key").not()));
+
+    bauplan_at(&home)
+        .args(["-O", "json", "config", "use-profile", "default"])
+        .assert()
+        .success()
+        .stdout(json_object().and(contains(r#""current_profile":"default""#)));
+
+    bauplan_at(&home)
+        .args(["-O", "json", "alias", "set", "v", "version"])
+        .assert()
+        .success()
+        .stdout(json_object().and(contains(r#""command":"version""#)));
+
+    bauplan_at(&home)
+        .args(["-O", "json", "alias", "rm", "v"])
+        .assert()
+        .success()
+        .stdout(json_object().and(contains(r#""command":null"#)));
+
+    bauplan_at(&home)
+        .args(["-O", "json", "auth", "logout"])
+        .assert()
+        .success()
+        .stdout(json_object().and(contains(r#""removed":"config_file""#)));
+
+    bauplan_at(&home)
+        .args(["-O", "json", "init", "--name", "json_project"])
+        .arg(home.path().join("project"))
+        .assert()
+        .success()
+        .stdout(json_object().and(contains(r#""name":"json_project""#)));
+
+    Ok(())
+}
+
+#[test]
+fn ref_commands() {
+    let branch = test_branch("cli_json_refs");
+    let child = format!("{}_child", branch.name);
+    let renamed = format!("{}_renamed", branch.name);
+    let tag = format!("{}_tag", branch.name);
+
+    bauplan()
+        .args(["-O", "json", "branch", "create", &child])
+        .args(["--from-ref", &branch.name])
+        .assert()
+        .success()
+        .stdout(json_object().and(contains(r#""changed":true"#)));
+
+    bauplan()
+        .args(["-O", "json", "branch", "create", &child, "--if-not-exists"])
+        .assert()
+        .success()
+        .stdout(json_object().and(contains(r#""changed":false"#)));
+
+    bauplan()
+        .args(["-O", "json", "branch", "rename", &child, &renamed])
+        .assert()
+        .success()
+        .stdout(json_object().and(contains(&renamed)));
+
+    bauplan()
+        .args(["-O", "json", "branch", "rm", &renamed])
+        .assert()
+        .success()
+        .stdout(json_object().and(contains(r#""changed":true"#)));
+
+    bauplan()
+        .args(["-O", "json", "branch", "rm", &renamed, "--if-exists"])
+        .assert()
+        .success()
+        .stdout(json_object().and(contains(r#""ref":null"#)));
+
+    bauplan()
+        .args(["-O", "json", "tag", "create", &tag])
+        .args(["--from-ref", &branch.name])
+        .assert()
+        .success()
+        .stdout(json_object());
+
+    bauplan()
+        .args(["-O", "json", "tag", "rm", &tag])
+        .assert()
+        .success()
+        .stdout(json_object());
+
+    bauplan()
+        .args(["-O", "json", "namespace", "create", "cli_json_ns"])
+        .args(["--branch", &branch.name])
+        .assert()
+        .success()
+        .stdout(json_object().and(contains(r#""name":"cli_json_ns""#)));
+
+    bauplan()
+        .args(["-O", "json", "namespace", "rm", "cli_json_ns"])
+        .args(["--branch", &branch.name])
+        .assert()
+        .success()
+        .stdout(json_object().and(contains(r#""type":"BRANCH""#)));
+
+    bauplan()
+        .args(["-O", "json", "table", "rm", "no_such_table", "--if-exists"])
+        .args(["--branch", &branch.name, "--namespace", "bauplan"])
+        .assert()
+        .success()
+        .stdout(json_object().and(contains(r#""changed":false"#)));
+}
+
+#[test]
+fn job_commands() {
+    let branch = test_branch("cli_json_jobs");
+    let search_uri = "s3://bpln-e2e-test-tables/test_tables/two_columns_two_dates/*";
+
+    bauplan()
+        .args(["namespace", "create", "cli_json_jobs"])
+        .args(["--branch", &branch.name])
+        .assert()
+        .success();
+
+    bauplan()
+        .args(["-O", "json", "table", "create", "cli_json_table"])
+        .args(["--search-uri", search_uri])
+        .args(["--namespace", "cli_json_jobs", "--branch", &branch.name])
+        .assert()
+        .success()
+        .stdout(json_object().and(contains(r#""status":"Complete""#)));
+
+    bauplan()
+        .args(["-O", "json", "table", "import", "cli_json_table"])
+        .args(["--search-uri", search_uri])
+        .args(["--namespace", "cli_json_jobs", "--branch", &branch.name])
+        .args(["--import-duplicate-files"])
+        .assert()
+        .success()
+        .stdout(json_object().and(contains(r#""job_id":"#)));
+
+    bauplan()
+        .args(["-O", "json", "table", "import", "cli_json_table"])
+        .args(["--search-uri", search_uri])
+        .args(["--namespace", "cli_json_jobs", "--branch", &branch.name])
+        .args(["--import-duplicate-files", "--detach"])
+        .assert()
+        .success()
+        .stdout(json_object().and(contains(r#""status":"Running""#)));
+}
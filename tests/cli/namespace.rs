@@ -0,0 +1,89 @@
+use crate::cli::{bauplan, test_branch};
+use predicates::prelude::PredicateBooleanExt as _;
+use predicates::str::contains;
+
+#[test]
+fn list_namespaces() {
+    bauplan()
+        .args(["namespace", "ls"])
+        .assert()
+        .success()
+        .stdout(contains("bauplan"));
+}
+
+#[test]
+fn create_list_delete_namespace() {
+    let branch = test_branch("cli_namespace");
+
+    bauplan()
+        .args(["namespace", "create", "--branch", &branch.name, "cli_ns"])
+        .assert()
+        .success()
+        .stderr(contains("Created namespace \"cli_ns\""));
+
+    bauplan()
+        .args(["namespace", "ls", "--ref", &branch.name])
+        .assert()
+        .success()
+        .stdout(contains("cli_ns").and(contains("bauplan")));
+
+    // Filtering by name leaves out the other namespaces.
+    bauplan()
+        .args(["namespace", "ls", "--ref", &branch.name, "cli_ns"])
+        .assert()
+        .success()
+        .stdout(contains("cli_ns").and(contains("bauplan").not()));
+
+    bauplan()
+        .args(["namespace", "create", "--branch", &branch.name, "cli_ns"])
+        .assert()
+        .failure();
+
+    bauplan()
+        .args([
+            "namespace",
+            "create",
+            "--branch",
+            &branch.name,
+            "--if-not-exists",
+            "cli_ns",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("already exists"));
+
+    bauplan()
+        .args(["namespace", "rm", "--branch", &branch.name, "cli_ns"])
+        .assert()
+        .success()
+        .stderr(contains("Deleted namespace \"cli_ns\""));
+
+    bauplan()
+        .args(["namespace", "ls", "--ref", &branch.name])
+        .assert()
+        .success()
+        .stdout(contains("cli_ns").not());
+}
+
+#[test]
+fn delete_missing_namespace() {
+    let branch = test_branch("cli_namespace_missing");
+
+    bauplan()
+        .args(["namespace", "rm", "--branch", &branch.name, "no_such_ns"])
+        .assert()
+        .failure();
+
+    bauplan()
+        .args([
+            "namespace",
+            "rm",
+            "--branch",
+            &branch.name,
+            "--if-exists",
+            "no_such_ns",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("does not exist"));
+}
@@ -15,6 +15,49 @@ fn ambiguous_column_name() {
         .code(1);
 }
 
+#[test]
+fn text_input_encodings() {
+    bauplan()
+        .args([
+            "query",
+            "--no-cache",
+            "-f",
+            "tests/fixtures/text_input/crlf.sql",
+        ])
+        .assert()
+        .success();
+
+    bauplan()
+        .args(["query", "-f", "tests/fixtures/text_input/utf16.sql"])
+        .assert()
+        .failure()
+        .stderr(contains("input is UTF-16 encoded; re-save it as UTF-8"));
+
+    bauplan()
+        .args(["query", "-f", "tests/fixtures/text_input/invalid_utf8.sql"])
+        .assert()
+        .failure()
+        .stderr(contains("invalid UTF-8 at byte 12"));
+
+    bauplan()
+        .env("BAUPLAN_MAX_INPUT_SIZE", "4")
+        .args(["query", "-f", "tests/fixtures/text_input/crlf.sql"])
+        .assert()
+        .failure()
+        .stderr(contains("input is larger than 4 bytes"));
+
+    bauplan()
+        .args([
+            "query",
+            "--arg",
+            "foo=@tests/fixtures/text_input/utf16.sql",
+            "SELECT 1",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("input is UTF-16 encoded"));
+}
+
 #[test]
 fn expected_zero_results() {
     bauplan()
@@ -156,3 +199,157 @@ fn run_twice() {
             "6             207"
         ]));
 }
+
+#[test]
+fn output_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("out.csv");
+    let p = path.to_str().unwrap();
+
+    bauplan()
+        .args([
+            "query",
+            "--output-file",
+            p,
+            "SELECT PassengerId, Name FROM bauplan.titanic LIMIT 5",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("Wrote 5 rows to"));
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(contents.lines().count(), 6);
+
+    // Without --overwrite, an existing file is an error.
+    bauplan()
+        .args([
+            "query",
+            "--output-file",
+            p,
+            "SELECT PassengerId, Name FROM bauplan.titanic LIMIT 5",
+        ])
+        .assert()
+        .code(1)
+        .stderr(contains("already exists"));
+
+    bauplan()
+        .args([
+            "query",
+            "--output-file",
+            p,
+            "--overwrite",
+            "SELECT PassengerId, Name FROM bauplan.titanic LIMIT 3",
+        ])
+        .assert()
+        .success();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(contents.lines().count(), 4);
+}
+
+#[test]
+fn output_file_parquet() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("out.parquet");
+
+    bauplan()
+        .args([
+            "query",
+            "--output-file",
+            path.to_str().unwrap(),
+            "SELECT PassengerId, Name FROM bauplan.titanic LIMIT 5",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("Wrote 5 rows to"));
+
+    let file = std::fs::File::open(&path).unwrap();
+    let reader =
+        parquet::arrow::arrow_reader::ParquetRecordBatchReader::try_new(file, 1024).unwrap();
+    let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 5);
+    assert_eq!(batches[0].schema().field(1).name(), "Name");
+}
+
+#[test]
+fn row_count_footer() {
+    bauplan()
+        .args(["query", "SELECT PassengerId FROM bauplan.titanic LIMIT 3"])
+        .assert()
+        .success()
+        .stderr(contains("(3 rows)"));
+
+    bauplan()
+        .args([
+            "query",
+            "--max-rows",
+            "2",
+            "SELECT PassengerId FROM bauplan.titanic LIMIT 3",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("(2 rows; there may be more"));
+}
+
+#[test]
+fn output_file_placeholders() {
+    let tmp = tempfile::tempdir().unwrap();
+    let template = tmp.path().join("nested/{job_id}.jsonl");
+
+    let out = bauplan()
+        .args([
+            "-O",
+            "json",
+            "query",
+            "--output-file",
+            template.to_str().unwrap(),
+            "SELECT PassengerId FROM bauplan.titanic LIMIT 2",
+        ])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+
+    let written: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let job_id = written["job_id"].as_str().unwrap();
+    assert_eq!(written["rows_written"], 2);
+
+    let path = tmp.path().join(format!("nested/{job_id}.jsonl"));
+    assert_eq!(written["path"], path.to_str().unwrap());
+    assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+}
+
+#[test]
+fn param_sql() {
+    bauplan()
+        .args([
+            "query",
+            "--param-sql",
+            "name=x' OR '1'='1",
+            "--param-sql",
+            "n=1",
+            "SELECT :n AS n, COUNT(*) AS matches FROM bauplan.titanic WHERE Name = :name",
+        ])
+        .assert()
+        .success()
+        .stdout(lines(&["n  matches", "1  0"]));
+
+    bauplan()
+        .args(["query", "--param-sql", "a=1", "SELECT :a, :b"])
+        .assert()
+        .failure()
+        .stderr(contains("no value for parameter :b"));
+
+    bauplan()
+        .args([
+            "query",
+            "--param-sql",
+            "a=1",
+            "--param-sql",
+            "b=2",
+            "SELECT :a",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("parameter :b isn't used in the query"));
+}
@@ -21,6 +21,193 @@ fn dry_run() {
         .stderr(contains("num_rows= 430488"));
 }
 
+#[test]
+fn dry_run_would_materialize() {
+    bauplan()
+        .args([
+            "run",
+            "--ref",
+            "main",
+            "--dry-run",
+            "--no-cache",
+            "-p",
+            "tests/fixtures/simple_taxi_dag",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("WOULD MATERIALIZE"))
+        .stderr(contains("normalize_data"));
+}
+
+#[test]
+fn dry_run_task_counts() {
+    bauplan()
+        .args([
+            "run",
+            "--ref",
+            "main",
+            "--dry-run",
+            "--no-cache",
+            "-p",
+            "tests/fixtures/simple_taxi_dag",
+        ])
+        .assert()
+        .success()
+        .stderr(contains(" models: "))
+        .stderr(contains(", 0 cached, 0 failed"));
+}
+
+#[test]
+fn dry_run_repro_command() {
+    bauplan()
+        .args([
+            "run",
+            "--ref",
+            "main",
+            "--dry-run",
+            "--no-cache",
+            "-p",
+            "tests/fixtures/simple_taxi_dag",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("=> Reproduce"))
+        .stderr(contains("--ref 'main@").or(contains("--ref main@")))
+        .stderr(contains("--no-cache --dry-run"))
+        .stderr(contains("https://app.bauplanlabs.com/jobs/"));
+
+    bauplan()
+        .args([
+            "-O",
+            "json",
+            "run",
+            "--dry-run",
+            "-p",
+            "tests/fixtures/simple_taxi_dag",
+        ])
+        .assert()
+        .success()
+        .stdout(contains(r#""repro_command":"bauplan run --project-dir "#))
+        .stdout(contains(r#""job_url":"https://app.bauplanlabs.com/jobs/"#));
+}
+
+#[test]
+fn events_out_replay() {
+    let tmp = tempfile::tempdir().unwrap();
+    let events = tmp.path().join("events.jsonl");
+    let events = events.to_str().unwrap();
+
+    bauplan()
+        .args([
+            "run",
+            "--ref",
+            "main",
+            "--dry-run",
+            "--no-cache",
+            "-p",
+            "tests/fixtures/simple_taxi_dag",
+            "--events-out",
+            events,
+        ])
+        .assert()
+        .success();
+
+    bauplan()
+        .args(["job", "replay-events", events])
+        .assert()
+        .success()
+        .stderr(contains("Normalizing model"))
+        .stderr(contains(" models: "))
+        .stderr(contains(", 0 cached, 0 failed"))
+        .stderr(contains("failed:").not());
+}
+
+#[test]
+fn log_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    let log = tmp.path().join("run.jsonl");
+
+    let output = bauplan()
+        .args([
+            "run",
+            "--ref",
+            "main",
+            "--dry-run",
+            "--no-cache",
+            "-p",
+            "tests/fixtures/simple_taxi_dag",
+            "--log-file",
+        ])
+        .arg(&log)
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+
+    // The console output is unchanged.
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("num_rows= 430488"));
+
+    let log = std::fs::read_to_string(&log).unwrap();
+    let records: Vec<serde_json::Value> = log
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert!(records.iter().any(|r| r["type"] == "task_started"));
+    assert!(records.iter().any(|r| r["type"] == "log"
+        && r["message"].as_str().unwrap().contains("num_rows= 430488")
+        && r["file_name"].is_string()));
+
+    let summary = records.last().unwrap();
+    assert_eq!(summary["type"], "summary");
+    assert_eq!(summary["outcome"], "SUCCESS");
+    assert!(summary["job_id"].is_string());
+}
+
+#[test]
+fn log_file_stdout() {
+    bauplan()
+        .args([
+            "-O",
+            "json",
+            "run",
+            "--ref",
+            "main",
+            "--dry-run",
+            "-p",
+            "tests/fixtures/simple_taxi_dag",
+            "--log-file",
+            "-",
+        ])
+        .assert()
+        .success()
+        .stdout(starts_with(r#"{"type":"#))
+        .stdout(contains(r#"{"type":"summary","#))
+        .stderr(contains("num_rows=").not());
+
+    bauplan()
+        .args([
+            "run",
+            "-p",
+            "tests/fixtures/simple_taxi_dag",
+            "--log-file",
+            "-",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("requires -O json"));
+}
+
+#[test]
+fn replay_missing_events() {
+    bauplan()
+        .args(["job", "replay-events", "does-not-exist.jsonl"])
+        .assert()
+        .failure()
+        .stderr(contains("failed to read does-not-exist.jsonl"));
+}
+
 #[test]
 fn run_json_output() {
     bauplan()
@@ -35,7 +222,43 @@ fn run_json_output() {
         ])
         .assert()
         .success()
-        .stdout(starts_with("{"));
+        .stdout(starts_with("{"))
+        .stdout(contains(r#""cached":false"#));
+}
+
+#[test]
+fn explain() {
+    bauplan()
+        .args([
+            "run",
+            "--ref",
+            "main",
+            "--explain",
+            "-p",
+            "tests/fixtures/simple_taxi_dag",
+        ])
+        .assert()
+        .success()
+        .stdout(starts_with("MODEL"))
+        .stdout(contains("normalize_data"))
+        .stderr(contains("num_rows=").not());
+}
+
+#[test]
+fn explain_json_output() {
+    bauplan()
+        .args([
+            "-O",
+            "json",
+            "run",
+            "--explain",
+            "-p",
+            "tests/fixtures/simple_taxi_dag",
+        ])
+        .assert()
+        .success()
+        .stdout(starts_with("{"))
+        .stdout(contains(r#""model_name":"normalize_data""#));
 }
 
 #[test]
@@ -268,6 +491,38 @@ fn parameters_project() {
         .stderr(contains("yayparams.num_columns=3"));
 }
 
+#[test]
+fn parameters_project_from_file_and_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("golden_ratio");
+    std::fs::write(&path, "4.2\n").unwrap();
+
+    bauplan()
+        .args([
+            "run",
+            "--no-cache",
+            "--dry-run",
+            "-p",
+            "tests/fixtures/parameters",
+            "--param",
+            &format!("golden_ratio=@{}", path.display()),
+            "--param",
+            "end_datetime=-",
+        ])
+        .write_stdin("2023-01-02T00:00:00+00:00\n")
+        .assert()
+        .success()
+        .stderr(contains("golden_ratio=4.2"))
+        .stderr(contains("end_datetime=2023-01-02T00:00:00+00:00"));
+
+    bauplan()
+        .args(["run", "--dry-run", "-p", "tests/fixtures/parameters"])
+        .args(["--param", "golden_ratio=@/no/such/file"])
+        .assert()
+        .failure()
+        .stderr(contains(r#"parameter "golden_ratio""#));
+}
+
 #[test]
 fn parameters_project_default_values() {
     bauplan()
@@ -424,3 +679,214 @@ fn without_transaction() {
         .success()
         .stderr(contains("num_rows= 430488"));
 }
+
+#[test]
+fn require_clean_diff() {
+    let branch = test_branch("e2e_require_clean_diff");
+
+    // A fresh branch hasn't diverged from main.
+    bauplan()
+        .args([
+            "run",
+            "--ref",
+            &branch.name,
+            "--dry-run",
+            "--require-clean-diff",
+            "-p",
+            "tests/fixtures/simple_taxi_dag",
+        ])
+        .assert()
+        .success();
+
+    // Materializing the project's own models doesn't count.
+    bauplan()
+        .args([
+            "run",
+            "--ref",
+            &branch.name,
+            "--no-cache",
+            "-p",
+            "tests/fixtures/materialize_partitioned_by_year",
+        ])
+        .assert()
+        .success();
+
+    bauplan()
+        .args([
+            "run",
+            "--ref",
+            &branch.name,
+            "--dry-run",
+            "--require-clean-diff",
+            "-p",
+            "tests/fixtures/materialize_partitioned_by_year",
+        ])
+        .assert()
+        .success();
+
+    // But from another project, it does.
+    bauplan()
+        .args([
+            "run",
+            "--ref",
+            &branch.name,
+            "--dry-run",
+            "--require-clean-diff",
+            "--against",
+            "main",
+            "-p",
+            "tests/fixtures/simple_taxi_dag",
+        ])
+        .assert()
+        .code(1)
+        .stderr(contains("materialized_table_model"))
+        .stderr(contains("has diverged from \"main\""));
+}
+
+#[test]
+fn plain_progress() {
+    let output = bauplan()
+        .env_remove("NO_COLOR")
+        .env("BAUPLAN_NO_PROGRESS", "1")
+        .args([
+            "run",
+            "--dry-run",
+            "--no-cache",
+            "-p",
+            "tests/fixtures/simple_taxi_dag",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("] Executing job..."))
+        .stderr(contains("Executing job... done"))
+        .get_output()
+        .clone();
+
+    assert!(
+        !output.stderr.contains(&0x1b),
+        "escape sequences in output:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn namespace_preflight_exists() {
+    bauplan()
+        .args([
+            "run",
+            "--ref",
+            "main",
+            "--namespace",
+            "bauplan",
+            "--dry-run",
+            "--no-cache",
+            "-p",
+            "tests/fixtures/simple_taxi_dag",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("doesn't exist on").not());
+}
+
+#[test]
+fn namespace_preflight_missing() {
+    // Without a terminal to confirm on, a missing namespace fails the run.
+    bauplan()
+        .env("BAUPLAN_NO_PROGRESS", "1")
+        .args([
+            "run",
+            "--ref",
+            "main",
+            "--namespace",
+            "bauplna",
+            "--dry-run",
+            "-p",
+            "tests/fixtures/simple_taxi_dag",
+        ])
+        .assert()
+        .code(1)
+        .stderr(contains("namespace \"bauplna\" doesn't exist on \"main\""))
+        .stderr(contains("did you mean: bauplan?"))
+        .stderr(contains("Planning job").not());
+}
+
+#[test]
+fn namespace_preflight_skipped() {
+    bauplan()
+        .env("BAUPLAN_NO_PROGRESS", "1")
+        .args([
+            "run",
+            "--ref",
+            "main",
+            "--namespace",
+            "bauplna",
+            "--no-preflight",
+            "--dry-run",
+            "-p",
+            "tests/fixtures/simple_taxi_dag",
+        ])
+        .assert()
+        .stderr(contains("doesn't exist on").not())
+        .stderr(contains("Planning job"));
+}
+
+#[test]
+fn fail_on_empty_model() {
+    bauplan()
+        .args([
+            "-O",
+            "json",
+            "run",
+            "--ref",
+            "main",
+            "--dry-run",
+            "--no-cache",
+            "-p",
+            "tests/fixtures/empty_model",
+            "--fail-on-empty-model",
+        ])
+        .assert()
+        .failure()
+        .stdout(contains(r#""outcome":"FAILED""#))
+        .stdout(contains(r#""reason":"empty output: empty_trips""#));
+}
+
+#[test]
+fn fail_on_empty_model_other_model() {
+    bauplan()
+        .args([
+            "run",
+            "--ref",
+            "main",
+            "--dry-run",
+            "--no-cache",
+            "-p",
+            "tests/fixtures/empty_model",
+            "--fail-on-empty-model",
+            "some_other_model",
+        ])
+        .assert()
+        .success();
+}
+
+#[test]
+fn job_timeout_cancels_job() {
+    bauplan()
+        .args([
+            "-O",
+            "json",
+            "run",
+            "--ref",
+            "main",
+            "--dry-run",
+            "--no-cache",
+            "-p",
+            "tests/fixtures/long_running_dag",
+            "--job-timeout",
+            "1s",
+        ])
+        .assert()
+        .failure()
+        .stdout(contains(r#""outcome":"TIMEOUT""#))
+        .stderr(contains("job exceeded --job-timeout of 1s"));
+}
@@ -3,39 +3,90 @@ use predicates::prelude::PredicateBooleanExt as _;
 use predicates::str::contains;
 
 #[test]
-fn namespace() {
+fn register_table_metadata() {
+    let branch = test_branch("externalclimetadata");
+
+    bauplan()
+        .args([
+            "table",
+            "create-external",
+            "external_table_metadata",
+            "--branch",
+            &branch.name,
+            "--metadata-json-uri",
+            "s3://bauplan-openlake-db87a23/iceberg/tpch_1/customer_e53c682c-36c4-4e3d-9ded-1214d0ee157f/metadata/00000-b6f502e1-5140-499e-bf83-22f943067e36.metadata.json",
+            "--namespace",
+            "bauplan",
+        ])
+        .assert()
+        .success();
+
     bauplan()
-        .args(["namespace", "ls"])
+        .args([
+            "query",
+            "--ref",
+            &branch.name,
+            "SELECT COUNT(*) FROM external_table_metadata",
+        ])
         .assert()
         .success()
-        .stdout(contains("bauplan"));
+        .stdout(contains("150000"));
 }
 
 #[test]
-fn register_table_metadata() {
-    let branch = test_branch("externalclimetadata");
+fn register_table_metadata_namespace_in_name() {
+    let branch = test_branch("externalclimetadatans");
 
     bauplan()
         .args([
             "table",
             "create-external",
-            "external_table_metadata",
+            "bauplan.external_table_metadata_ns",
             "--branch",
             &branch.name,
             "--metadata-json-uri",
             "s3://bauplan-openlake-db87a23/iceberg/tpch_1/customer_e53c682c-36c4-4e3d-9ded-1214d0ee157f/metadata/00000-b6f502e1-5140-499e-bf83-22f943067e36.metadata.json",
+        ])
+        .assert()
+        .success();
+
+    bauplan()
+        .args([
+            "table",
+            "get",
+            "external_table_metadata_ns",
+            "--ref",
+            &branch.name,
             "--namespace",
             "bauplan",
         ])
         .assert()
         .success();
+}
+
+#[test]
+fn register_table_metadata_default_namespace() {
+    let branch = test_branch("externalclimetadatadefault");
+
+    bauplan()
+        .args([
+            "table",
+            "create-external",
+            "external_table_metadata_default",
+            "--branch",
+            &branch.name,
+            "--metadata-json-uri",
+            "s3://bauplan-openlake-db87a23/iceberg/tpch_1/customer_e53c682c-36c4-4e3d-9ded-1214d0ee157f/metadata/00000-b6f502e1-5140-499e-bf83-22f943067e36.metadata.json",
+        ])
+        .assert()
+        .success();
 
     bauplan()
         .args([
             "query",
             "--ref",
             &branch.name,
-            "SELECT COUNT(*) FROM external_table_metadata",
+            "SELECT COUNT(*) FROM external_table_metadata_default",
         ])
         .assert()
         .success()
@@ -81,6 +132,106 @@ fn get_json_output() {
         .stdout(contains(r#""namespace":"bauplan","#));
 }
 
+#[test]
+fn get_format() {
+    bauplan()
+        .args(["table", "get", "bauplan.taxi_fhvhv", "--format", "yaml"])
+        .assert()
+        .success()
+        .stdout(contains("name: taxi_fhvhv\n"))
+        .stdout(contains("namespace: bauplan\n"));
+
+    bauplan()
+        .args(["table", "get", "bauplan.taxi_fhvhv", "--format", "wide"])
+        .assert()
+        .success()
+        .stdout(contains("Name:"))
+        .stdout(contains("bauplan.taxi_fhvhv"))
+        .stdout(contains("REQUIRED"));
+
+    // --format takes precedence over -O.
+    bauplan()
+        .args([
+            "-O",
+            "json",
+            "table",
+            "get",
+            "bauplan.taxi_fhvhv",
+            "--format",
+            "table",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("NAME"))
+        .stdout(contains(r#""name""#).not());
+}
+
+#[test]
+fn get_schema_only() {
+    bauplan()
+        .args(["table", "get", "bauplan.taxi_fhvhv"])
+        .assert()
+        .success()
+        .stdout(contains("Snapshots:"))
+        .stdout(contains("Partitions:"))
+        .stdout(contains("REQUIRED"));
+
+    bauplan()
+        .args(["table", "get", "bauplan.taxi_fhvhv", "--schema-only"])
+        .assert()
+        .success()
+        .stdout(contains("Snapshots:").not())
+        .stdout(contains("REQUIRED"));
+}
+
+#[test]
+fn snapshots() {
+    bauplan()
+        .args(["table", "snapshots", "bauplan.titanic", "--ref", "main"])
+        .assert()
+        .success()
+        .stdout(contains("SNAPSHOT ID"))
+        .stdout(contains(" ago"));
+
+    bauplan()
+        .args(["-O", "json", "table", "snapshots", "bauplan.titanic"])
+        .args(["--ref", "main", "--limit", "1"])
+        .assert()
+        .success()
+        .stdout(contains(r#""snapshot_id":"#));
+}
+
+#[test]
+fn diff() {
+    bauplan()
+        .args(["table", "diff", "bauplan.titanic"])
+        .args(["--from", "main", "--to", "main"])
+        .assert()
+        .success()
+        .stdout("no changes\n");
+
+    let branch = test_branch("cli_table_diff");
+    bauplan()
+        .args(["table", "alter", "bauplan.titanic"])
+        .args(["--branch", &branch.name, "--add", "notes:string"])
+        .assert()
+        .success();
+
+    bauplan()
+        .args(["table", "diff", "bauplan.titanic", "--from", "main"])
+        .args(["--to", &branch.name])
+        .assert()
+        .code(3)
+        .stdout(contains("+ notes"));
+
+    bauplan()
+        .args(["-O", "json", "table", "diff", "bauplan.titanic"])
+        .args(["--from", "main", "--to", &branch.name])
+        .assert()
+        .code(3)
+        .stdout(contains(r#""name":"notes","kind":"added""#));
+}
+
 #[test]
 fn main_taxi_fhvhv() {
     bauplan()
@@ -131,6 +282,76 @@ fn delete_table() {
         .stdout(contains("delete_me").not());
 }
 
+#[test]
+fn write_to_tag_or_commit() {
+    let branch = test_branch("cli_write_ref_check");
+    let tag = format!("{}_tag", branch.name);
+
+    bauplan()
+        .args(["tag", "create", &tag, "--from-ref", &branch.name])
+        .assert()
+        .success();
+
+    bauplan()
+        .args(["table", "rm", "bauplan.no_such_table", "--branch", &tag])
+        .assert()
+        .failure()
+        .stderr(contains("is a tag, not a branch"));
+
+    bauplan()
+        .args(["table", "import", "no_such_table", "--branch", &tag])
+        .args(["--search-uri", "s3://bucket/*.parquet"])
+        .assert()
+        .failure()
+        .stderr(contains("is a tag, not a branch"));
+
+    bauplan()
+        .args(["table", "rm", "bauplan.no_such_table"])
+        .args(["--branch", "main@abc123"])
+        .assert()
+        .failure()
+        .stderr(contains("is a commit, not a branch"));
+
+    bauplan().args(["tag", "rm", &tag]).assert().success();
+}
+
+#[test]
+fn alter_table() {
+    let branch = test_branch("cli_alter_table");
+
+    bauplan()
+        .args([
+            "table",
+            "alter",
+            "bauplan.titanic",
+            "--branch",
+            &branch.name,
+            "--add",
+            "notes:string",
+            "--rename",
+            "Name=PassengerName",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("Altered table"))
+        .stdout(contains("notes").and(contains("PassengerName")));
+
+    // Checked before anything is sent.
+    bauplan()
+        .args([
+            "table",
+            "alter",
+            "bauplan.titanic",
+            "--branch",
+            &branch.name,
+            "--drop",
+            "no_such_column",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("table has no column \"no_such_column\""));
+}
+
 #[test]
 fn delete_table_if_exists() {
     let branch = test_branch("cli_delete_table_exists");
@@ -161,3 +382,322 @@ fn delete_table_if_exists() {
         .assert()
         .failure();
 }
+
+#[test]
+fn get_and_delete_table_with_namespace() {
+    let branch = test_branch("cli_table_namespace");
+    let metadata_uri = "s3://bauplan-openlake-db87a23/iceberg/tpch_1/customer_e53c682c-36c4-4e3d-9ded-1214d0ee157f/metadata/00000-b6f502e1-5140-499e-bf83-22f943067e36.metadata.json";
+
+    bauplan()
+        .args(["namespace", "create", "--branch", &branch.name, "other_ns"])
+        .assert()
+        .success();
+
+    // Register the same table name in two namespaces.
+    for namespace in ["bauplan", "other_ns"] {
+        bauplan()
+            .args([
+                "table",
+                "create-external",
+                "twin_table",
+                "--branch",
+                &branch.name,
+                "--metadata-json-uri",
+                metadata_uri,
+                "--namespace",
+                namespace,
+            ])
+            .assert()
+            .success();
+    }
+
+    // A bare name is ambiguous, and the error lists both namespaces.
+    bauplan()
+        .args(["table", "get", "twin_table", "--ref", &branch.name])
+        .assert()
+        .failure()
+        .stderr(contains("bauplan").and(contains("other_ns")));
+
+    bauplan()
+        .args([
+            "-O",
+            "json",
+            "table",
+            "get",
+            "twin_table",
+            "--ref",
+            &branch.name,
+            "--namespace",
+            "other_ns",
+        ])
+        .assert()
+        .success()
+        .stdout(contains(r#""namespace":"other_ns","#));
+
+    bauplan()
+        .args(["table", "rm", "twin_table", "--branch", &branch.name])
+        .assert()
+        .failure()
+        .stderr(contains("--namespace"));
+
+    bauplan()
+        .args([
+            "table",
+            "rm",
+            "twin_table",
+            "--branch",
+            &branch.name,
+            "--namespace",
+            "other_ns",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("Deleted table"));
+
+    // Only the table in the other namespace is gone.
+    bauplan()
+        .args(["table", "ls", "--ref", &branch.name, "--name", "twin_table"])
+        .assert()
+        .success()
+        .stdout(contains("bauplan").and(contains("other_ns").not()));
+}
+
+#[test]
+fn get_table_suggests_namespaces() {
+    let branch = test_branch("cli_table_suggest");
+    let metadata_uri = "s3://bauplan-openlake-db87a23/iceberg/tpch_1/customer_e53c682c-36c4-4e3d-9ded-1214d0ee157f/metadata/00000-b6f502e1-5140-499e-bf83-22f943067e36.metadata.json";
+
+    for namespace in ["ns_one", "ns_two"] {
+        bauplan()
+            .args(["namespace", "create", "--branch", &branch.name, namespace])
+            .assert()
+            .success();
+    }
+
+    // Neither table is in the default namespace.
+    for (namespace, table) in [
+        ("ns_one", "lone_table"),
+        ("ns_one", "pair_table"),
+        ("ns_two", "pair_table"),
+    ] {
+        bauplan()
+            .args([
+                "table",
+                "create-external",
+                table,
+                "--branch",
+                &branch.name,
+                "--metadata-json-uri",
+                metadata_uri,
+                "--namespace",
+                namespace,
+            ])
+            .assert()
+            .success();
+    }
+
+    bauplan()
+        .args(["table", "get", "lone_table", "--ref", &branch.name])
+        .assert()
+        .failure()
+        .stderr(contains("did you mean: ns_one.lone_table?"));
+
+    bauplan()
+        .args(["table", "get", "pair_table", "--ref", &branch.name])
+        .assert()
+        .failure()
+        .stderr(contains(
+            "did you mean: ns_one.pair_table, ns_two.pair_table?",
+        ));
+
+    bauplan()
+        .args(["table", "get", "no_such_table", "--ref", &branch.name])
+        .assert()
+        .failure()
+        .stderr(contains("did you mean").not());
+
+    // The extra lookup is skipped for JSON output.
+    bauplan()
+        .args([
+            "-O",
+            "json",
+            "table",
+            "get",
+            "lone_table",
+            "--ref",
+            &branch.name,
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("did you mean").not());
+}
+
+#[test]
+fn sync_state() -> anyhow::Result<()> {
+    let branch = test_branch("tablesyncstate");
+    let dir = tempfile::tempdir()?;
+    let state_file = dir.path().join("state.json");
+    let state_file = state_file.to_str().unwrap();
+
+    // Without a commit, the first sync is repeated.
+    for _ in 0..2 {
+        bauplan()
+            .args(["table", "sync-state", "--state-file", state_file])
+            .args(["--ref", &branch.name, "--no-commit"])
+            .assert()
+            .success()
+            .stdout(contains("+ bauplan.taxi_fhvhv"));
+    }
+
+    bauplan()
+        .args(["table", "sync-state", "--state-file", state_file])
+        .args(["--ref", &branch.name])
+        .assert()
+        .success();
+
+    bauplan()
+        .args(["-O", "json", "table", "sync-state"])
+        .args(["--state-file", state_file, "--ref", &branch.name])
+        .assert()
+        .success()
+        .stdout(contains(r#"{"added":[],"removed":[],"changed":[]}"#));
+
+    // The state file can't be reused for another ref.
+    bauplan()
+        .args(["table", "sync-state", "--state-file", state_file])
+        .args(["--ref", "main"])
+        .assert()
+        .failure()
+        .stderr(contains("is for ref"));
+
+    Ok(())
+}
+
+#[test]
+fn plan_verify() {
+    bauplan()
+        .args(["table", "plan", "verify", "tests/fixtures/plans/plan.yaml"])
+        .assert()
+        .success()
+        .stdout(contains(
+            "4efb5a6fb9811f5d53785a8d0bb31ecc7a1e4c19ae4182678926f17f8a7dffe3",
+        ))
+        .stdout(contains("verified"));
+}
+
+#[test]
+fn plan_verify_tampered() {
+    bauplan()
+        .args([
+            "table",
+            "plan",
+            "verify",
+            "tests/fixtures/plans/tampered.yaml",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains(
+            "expected sha256 4efb5a6fb9811f5d53785a8d0bb31ecc7a1e4c19ae4182678926f17f8a7dffe3",
+        ))
+        .stderr(contains(
+            "got b1e6983a0f71291a5cc1fc2b1ba47703b19b1b3cad2d88542157a65fa6d49f36",
+        ));
+
+    // The plan is checked before anything is sent.
+    bauplan()
+        .args([
+            "table",
+            "create-plan-apply",
+            "--plan",
+            "tests/fixtures/plans/tampered.yaml",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("doesn't match its checksum"));
+}
+
+#[test]
+fn plan_verify_unsigned() {
+    bauplan()
+        .args([
+            "table",
+            "plan",
+            "verify",
+            "tests/fixtures/plans/unsigned.yaml",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("missing"))
+        .stderr(contains("no checksum file found"));
+}
+
+#[test]
+fn plan_verify_update() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let plan = dir.path().join("plan.yaml");
+    std::fs::copy("tests/fixtures/plans/tampered.yaml", &plan)?;
+    std::fs::copy(
+        "tests/fixtures/plans/tampered.yaml.sha256",
+        dir.path().join("plan.yaml.sha256"),
+    )?;
+
+    bauplan()
+        .args(["table", "plan", "verify"])
+        .arg(&plan)
+        .assert()
+        .failure();
+
+    bauplan()
+        .args(["table", "plan", "verify", "--update"])
+        .arg(&plan)
+        .assert()
+        .success();
+
+    bauplan()
+        .args(["table", "plan", "verify"])
+        .arg(&plan)
+        .assert()
+        .success()
+        .stdout(contains("verified"));
+    Ok(())
+}
+
+#[test]
+fn wait_for_table() {
+    bauplan()
+        .args([
+            "table",
+            "wait",
+            "bauplan.titanic",
+            "--ref",
+            "main",
+            "--timeout",
+            "30s",
+            "--min-records",
+            "1",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("Table bauplan.titanic is ready"));
+}
+
+#[test]
+fn wait_for_table_timeout() {
+    let branch = test_branch("cli_table_wait");
+
+    bauplan()
+        .args([
+            "table",
+            "wait",
+            "no_such_table",
+            "--ref",
+            &branch.name,
+            "--timeout",
+            "2s",
+            "--poll-interval",
+            "1",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("wasn't ready after 2s; it doesn't exist"));
+}